@@ -1,3 +1,8 @@
 """Python to Rust migration tool."""
 
 __version__ = "0.1.0"
+
+from .migrator import Migrator
+from .report import FileReport, MigrationReport
+
+__all__ = ["Migrator", "MigrationReport", "FileReport"]
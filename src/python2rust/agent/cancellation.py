@@ -0,0 +1,74 @@
+import asyncio
+from typing import Any, Awaitable, List, Optional
+
+
+class MigrationCancelled(Exception):
+    """Raised when a migration is aborted through its cancellation token."""
+
+
+class CancellationToken:
+    """Cooperative cancellation shared between a caller and a running migration.
+
+    cancel() may be called from any thread; awaitables run through guard() are
+    cancelled as soon as the token fires, dropping in-flight LLM requests and
+    killing running build processes.
+    """
+
+    def __init__(self):
+        self._cancelled = False
+        self._event: Optional[asyncio.Event] = None
+        self._loop: Optional[asyncio.AbstractEventLoop] = None
+        self._children: List["CancellationToken"] = []
+
+    @property
+    def cancelled(self) -> bool:
+        return self._cancelled
+
+    def cancel(self) -> None:
+        self._cancelled = True
+        if self._loop is not None and self._event is not None:
+            self._loop.call_soon_threadsafe(self._event.set)
+        for child in self._children:
+            child.cancel()
+
+    def child(self) -> "CancellationToken":
+        """A token cancelled with this one, which can also be cancelled on its own."""
+        child = CancellationToken()
+        self._children.append(child)
+        if self._cancelled:
+            child.cancel()
+        return child
+
+    def raise_if_cancelled(self) -> None:
+        if self._cancelled:
+            raise MigrationCancelled("Migration was cancelled")
+
+    def _get_event(self) -> asyncio.Event:
+        loop = asyncio.get_running_loop()
+        if self._event is None or self._loop is not loop:
+            self._loop = loop
+            self._event = asyncio.Event()
+            if self._cancelled:
+                self._event.set()
+        return self._event
+
+    async def guard(self, awaitable: Awaitable[Any]) -> Any:
+        """Await something, cancelling it if the token fires first."""
+        self.raise_if_cancelled()
+
+        task = asyncio.ensure_future(awaitable)
+        waiter = asyncio.ensure_future(self._get_event().wait())
+        try:
+            await asyncio.wait({task, waiter}, return_when=asyncio.FIRST_COMPLETED)
+        finally:
+            waiter.cancel()
+
+        if task.done():
+            return task.result()
+
+        task.cancel()
+        try:
+            await task
+        except asyncio.CancelledError:
+            pass
+        raise MigrationCancelled("Migration was cancelled")
@@ -2,13 +2,31 @@ from typing import Optional, Tuple, Dict, Any, TYPE_CHECKING, List
 from pathlib import Path
 from ..initializers import LLMInitializer, ChainInitializer
 from .state import MigrationState
-from ..workflows import MigrationWorkflow, BuildWorkflow, TestWorkflow
-from ..config.settings import Settings
+from .cancellation import CancellationToken, MigrationCancelled
+from ..workflows import (
+    MigrationWorkflow, BuildWorkflow, TestWorkflow, TranslationPipeline, ConsensusGenerator,
+    ChunkedGenerator
+)
+from ..workflows.consensus import Generator
+from ..analyzers.token_estimate import generation_token_budget
+from ..config.settings import Settings, LLMChoice
 from ..utils.logging import setup_logger
 from langchain.schema.runnable import RunnableSequence
 from ..utils.trackers import create_tracker
+from ..utils.response_cache import ResponseCache, write_reproducibility_manifest
+from ..utils.rate_limit import LimitedModel, RequestLimiter
+from ..utils.stream_log import StreamLogHandler
+from ..utils.redaction import RedactingModel, SecretRedactor
+from ..utils.rustfmt import Rustfmt
+from ..providers import provider_for
 import json
-from ..builders import RustBuilder, ServerTester
+from ..builders import RustBuilder, Sandbox, ServerTester
+from ..checkers import (
+    CargoCheck, CargoTest, Checker, Clippy, DependencyAuditCheck, Miri, PolicyCheck, RustfmtCheck,
+    ShellChecker
+)
+from ..plugins import Plugin, PluginManager, load_plugins
+from ..events import EventBus, EventCallbackHandler
 
 logger = setup_logger()
 
@@ -20,7 +38,13 @@ class MigrationAgent:
         output_dir: Optional[Path] = None,
         settings: Optional[Settings] = None,
         workflows: Optional[List[str]] = None,
-        test_script_path: Optional[Path] = None
+        test_script_path: Optional[Path] = None,
+        plugins: Optional[List[Plugin]] = None,
+        events: Optional[EventBus] = None,
+        cancel_token: Optional[CancellationToken] = None,
+        request_limiter: Optional[RequestLimiter] = None,
+        redactor: Optional[SecretRedactor] = None,
+        checkers: Optional[List[Checker]] = None
     ):
         self.tokens = tokens
         self.settings = settings or Settings()
@@ -28,19 +52,65 @@ class MigrationAgent:
             self.settings.output_dir = output_dir
 
         self.enabled_workflows = workflows or ["migration", "build", "test"]
+        self.events = events or EventBus()
+        self.cancel_token = cancel_token or CancellationToken()
 
         self.state = MigrationState()
-        self.llm_initializer = LLMInitializer(self.settings)
+        self.plugins = PluginManager(
+            load_plugins(self.settings.plugins_dir) + list(plugins or []))
+        self.response_cache = None
+        if self.settings.deterministic or self.settings.response_cache:
+            # Deterministic runs answer a repeated prompt the same way; others retry it
+            self.response_cache = ResponseCache(
+                self.settings.response_cache_dir or self.settings.debug_dir / "responses",
+                replay=self.settings.replay,
+                reuse=self.settings.response_cache,
+                serve_repeats=self.settings.deterministic)
+        self.llm_initializer = LLMInitializer(self.settings, cache=self.response_cache)
+        # Shared with the other agents of a run so that concurrency and rates hold for the run
+        self.request_limiter = request_limiter or RequestLimiter.from_settings(self.settings)
+        self.redactor = None
+        if self.settings.redact_secrets:
+            self.redactor = redactor or SecretRedactor(extra_patterns=self.settings.secret_patterns)
         self.chain_initializer = ChainInitializer(
             settings=self.settings,
             callbacks=create_tracker(debug_dir=self.settings.debug_dir)
         )
 
-        self.rust_builder = RustBuilder(output_dir=self.settings.output_dir)
+        # Generated code is built and run in a container when the sandbox is enabled
+        sandbox = Sandbox(self.settings.sandbox) if self.settings.sandbox.enabled else None
+        self.rust_builder = RustBuilder(
+            output_dir=self.settings.output_dir,
+            log_dir=self.settings.debug_dir / "logs",
+            sandbox=sandbox,
+            limits=self.settings.limits,
+            env=self.settings.build_env)
         self.server_tester = ServerTester(
             host=self.settings.server_host,
             port=self.settings.server_port,
-            test_script_path=test_script_path)
+            test_script_path=test_script_path,
+            sandbox=sandbox,
+            limits=self.settings.limits.run)
+
+        # Normalizes each revision of the code, so that they differ only where the code does
+        self.formatter = Rustfmt() if self.settings.rustfmt else None
+        # Run by the build workflow in order; clippy is skipped when no lint is denied
+        rustfmt = [RustfmtCheck(self.formatter)] if self.formatter is not None else []
+        clippy = ([Clippy(self.settings.clippy_deny, self.settings.clippy_allow)]
+                  if self.settings.clippy_deny else [])
+        policies = [PolicyCheck(self.settings.policies)] if self.settings.policies.enabled else []
+        audit = self.settings.audit
+        # Before the tests, so that dependency changes are tested
+        audits = ([DependencyAuditCheck(audit.fail, audit.licenses, audit.ignore)]
+                  if audit.enabled else [])
+        tests = [CargoTest()] if self.settings.cargo_test else []
+        miri = ([Miri(self.settings.miri_flags, self.settings.miri_toolchain)]
+                if self.settings.miri else [])
+        self.checkers: List[Checker] = [*rustfmt, CargoCheck(), *clippy, *policies,
+                                        *audits, *tests, *miri, *(
+            ShellChecker(name, check.command, check.exit_codes, check.timeout)
+            for name, check in self.settings.shell_checkers.items()
+        ), *(checkers or [])]
 
         self.llms = None
         self.chains = None
@@ -48,6 +118,8 @@ class MigrationAgent:
         self.build_workflow = None
         self.test_workflow = None
         self.migration_chain = None
+        self.last_result: Optional[Dict[str, Any]] = None
+        self.last_error: Optional[str] = None
         self._is_setup = False
 
     async def load_existing_code(self) -> Dict[str, Any]:
@@ -91,6 +163,11 @@ class MigrationAgent:
                 self.migration_workflow.setup(),
                 self._maybe_proceed_to_build,
             ])
+        elif "verify" in self.enabled_workflows:
+            steps.extend([
+                self.migration_workflow.setup_verification(),
+                self._maybe_proceed_to_build,
+            ])
 
         if "build" in self.enabled_workflows:
             steps.extend([
@@ -123,7 +200,9 @@ class MigrationAgent:
                 "rust_code": build_result.rust_code,
                 "toml_content": build_result.toml_content,
                 "error": build_result.error,
-                "build_info": build_result.build_info
+                "build_info": build_result.build_info,
+                "warnings": build_result.warnings,
+                "metrics": build_result.metrics
             },
             "output_dir": self.settings.output_dir
         }
@@ -147,31 +226,54 @@ class MigrationAgent:
         build_info = result.get("build", {})
         test_info = result.get("test", {})
 
-        # If we're only testing, we consider build successful if we have the code
+        if "build" not in self.enabled_workflows and "rust_code" in result:
+            build_info = {"rust_code": result["rust_code"], "toml_content": result["toml_content"]}
+        verification = result.get("code") or self.state.last_verification_result or {}
+
+        # Without the build workflow, we consider build successful if we have the code
         build_success = build_info.get("success", False)
-        if "test" in self.enabled_workflows and len(self.enabled_workflows) == 1:
+        if "build" not in self.enabled_workflows:
             build_success = bool(build_info.get("rust_code"))
 
+        success = build_success and not test_info.get("error")
+        if "verify" in self.enabled_workflows:
+            success = success and verification.get("matches", False)
+
         self.state.update_best_result(
-            verification_result=result.get("code", {}),
+            verification_result=verification,
             rust_code=build_info.get("rust_code"),
             toml_content=build_info.get("toml_content")
         )
 
         return {
-            "success": build_success and not test_info.get("error"),
+            "success": success,
             "rust_code": build_info.get("rust_code"),
             "toml_content": build_info.get("toml_content"),
             "output_dir": self.settings.output_dir,
+            "verification": verification,
+            "warnings": build_info.get("warnings", []),
             "metrics": {
                 "build_duration": build_info.get("build_info", {}).get("duration"),
                 "test_success": test_info.get("success", False),
-                "verification_score": self.state.best_verification_score
+                "verification_score": self.state.best_verification_score,
+                # Code revisions after the first generation (fixes of all stages)
+                "iterations": max(len(self.state.code_history) - 1, 0),
+                "verification_matches": verification.get("matches"),
+                # e.g. the memory usage of the compared runs
+                **build_info.get("metrics", {})
             }
         }
 
-    async def migrate(self, python_code: str = None) -> Tuple[bool, Optional[str], Optional[str]]:
-        """Run the migration process or test existing code."""
+    async def migrate(
+        self,
+        python_code: str = None,
+        existing_code: Optional[Tuple[str, str]] = None
+    ) -> Tuple[bool, Optional[str], Optional[str]]:
+        """Run the migration process or test existing code.
+
+        existing_code is a (rust_code, toml_content) pair to verify instead of
+        generating new code, used when resuming a run without the migration workflow.
+        """
         if not self._is_setup:
             await self.setup()
 
@@ -183,6 +285,19 @@ class MigrationAgent:
                 logger.info("Running in test-only mode")
                 initial_context = await self.load_existing_code()
 
+            # Verify previously generated code
+            elif existing_code is not None and "migration" not in self.enabled_workflows:
+                logger.info("Verifying previously generated code")
+                rust_code, toml_content = existing_code
+                self.state.record_code("previous run", rust_code)
+                initial_context = {
+                    "python_code": python_code,
+                    "rust_code": rust_code,
+                    "toml_content": toml_content,
+                    "analysis": None,
+                    "output_dir": self.settings.output_dir
+                }
+
             # Full or partial migration mode
             elif python_code is not None:
                 initial_context = {
@@ -195,13 +310,29 @@ class MigrationAgent:
 
             logger.info(
                 f"Starting workflow with context keys: {list(initial_context.keys())}")
-            result = await self.migration_chain.ainvoke(initial_context)
+            result = await self.cancel_token.guard(
+                self.migration_chain.ainvoke(initial_context))
+            self.last_result = result
             return self._extract_result(result)
 
+        except MigrationCancelled:
+            logger.warning("Migration cancelled, saving partial state")
+            self.last_error = "Cancelled"
+            self._persist_partial_state()
+            self.rust_builder.cleanup()
+            raise
+
         except Exception as e:
             logger.exception(f"Process failed: {e}")
+            self.last_error = str(e)
             return self._handle_failure()
 
+        finally:
+            if self.response_cache is not None and self.settings.deterministic:
+                write_reproducibility_manifest(
+                    self.settings, self.response_cache,
+                    self.settings.debug_dir / "reproducibility.json")
+
     def _extract_result(self, result: Dict[str, Any]) -> Tuple[bool, Optional[str], Optional[str]]:
         """Extract the final result tuple from the pipeline result."""
         if result and result.get("success"):
@@ -212,6 +343,16 @@ class MigrationAgent:
             )
         return False, None, None
 
+    def _persist_partial_state(self) -> None:
+        """Save what the pipeline produced so far so the run can be resumed."""
+        try:
+            self.settings.debug_dir.mkdir(parents=True, exist_ok=True)
+            state_file = self.settings.debug_dir / "partial_state.json"
+            state_file.write_text(json.dumps(self.state.to_dict(), indent=2, default=str))
+            logger.info(f"Partial state saved to {state_file}")
+        except Exception as e:
+            logger.error(f"Failed to save partial state: {e}")
+
     def _handle_failure(self) -> Tuple[bool, Optional[str], Optional[str]]:
         logger.error("Migration failed")
         return False, None, None
@@ -221,20 +362,109 @@ class MigrationAgent:
         if self._is_setup:
             return
 
-        self.llms = await self.llm_initializer.initialize(
+        llms = await self.llm_initializer.initialize(
             tokens=self.tokens,
-            callbacks=create_tracker(debug_dir=self.settings.debug_dir)
+            callbacks=create_tracker(debug_dir=self.settings.debug_dir) + [
+                EventCallbackHandler(self.events),
+                StreamLogHandler(self.settings.debug_dir / "streams")
+            ]
         )
+        self.llms = {
+            choice: LimitedModel(llm, self.request_limiter, provider_for(
+                self.settings, self.llm_initializer.serving.get(choice, choice)).name)
+            for choice, llm in llms.items()
+        }
+        if self.redactor is not None:
+            self.llms = {choice: RedactingModel(llm, self.redactor)
+                         for choice, llm in self.llms.items()}
 
         self.chains = self.chain_initializer.initialize(self.llms)
-        self.migration_workflow = MigrationWorkflow(self.chains, self.state)
+        pipeline = None
+        if self.settings.translation_passes:
+            pipeline = TranslationPipeline(
+                self.chains["passes"],
+                self.settings.translation_passes,
+                artifacts_dir=self.settings.debug_dir / "passes",
+                max_attempts=self.settings.max_pass_attempts,
+                events=self.events
+            )
+        chunker = self._chunker(
+            self.chains,
+            self.settings.llm_steps.generation,
+            self.settings.debug_dir,
+            pipeline.run if pipeline is not None else self.chains["generation"].generate
+        )
+        consensus = None
+        if self.chains["candidates"]:
+            candidates_dir = self.settings.debug_dir / "candidates"
+            consensus = ConsensusGenerator(
+                {
+                    name: self._candidate_generator(chains, LLMChoice(name), candidates_dir / name)
+                    for name, chains in self.chains["candidates"].items()
+                },
+                artifacts_dir=candidates_dir,
+                events=self.events
+            )
+        self.migration_workflow = MigrationWorkflow(
+            self.chains, self.state, self.plugins, self.events, pipeline, consensus, chunker,
+            self.formatter)
         self.build_workflow = BuildWorkflow(
-            self.chains, self.rust_builder, self.state)
+            self.chains, self.rust_builder, self.state, self.events, self.checkers)
         self.test_workflow = TestWorkflow(
-            self.chains, self.server_tester, self.state)
+            self.chains, self.server_tester, self.state, self.events, self.formatter)
+        for workflow in (self.migration_workflow, self.build_workflow, self.test_workflow):
+            if not self.settings.apply_fixes:
+                workflow.max_fix_attempts = 0
+            else:
+                workflow.max_fix_attempts += self.settings.extra_fix_attempts
         self.migration_chain = self._setup_migration_chain()
         self._is_setup = True
 
+    def _candidate_generator(
+        self,
+        chains: Dict[str, Any],
+        choice: LLMChoice,
+        artifacts_dir: Path
+    ) -> Generator:
+        """Generate a consensus candidate the way the generation model would, passes
+        and chunks included."""
+        generate = self._translation(chains, artifacts_dir)
+        chunker = self._chunker(chains, choice, artifacts_dir, generate)
+        return chunker.run if chunker is not None else generate
+
+    def _chunker(
+        self,
+        chains: Dict[str, Any],
+        choice: LLMChoice,
+        artifacts_dir: Path,
+        generate: Generator
+    ) -> Optional[ChunkedGenerator]:
+        """Chunked generation of modules too large for a model, or None when it has no limits."""
+        config = self.settings.llm_configs[choice]
+        max_tokens = self.settings.chunk_tokens or generation_token_budget(
+            config.max_tokens, config.context_length)
+        if max_tokens is None:
+            return None
+        return ChunkedGenerator(
+            generate,
+            max_tokens,
+            artifacts_dir=artifacts_dir / "chunks",
+            events=self.events,
+            chunk_generator=lambda directory: self._translation(chains, directory)
+        )
+
+    def _translation(self, chains: Dict[str, Any], artifacts_dir: Path) -> Generator:
+        """Translate with the generation chain, or with the translation passes when enabled."""
+        if not self.settings.translation_passes:
+            return chains["generation"].generate
+        return TranslationPipeline(
+            chains["passes"],
+            self.settings.translation_passes,
+            artifacts_dir=artifacts_dir / "passes",
+            max_attempts=self.settings.max_pass_attempts,
+            events=self.events
+        ).run
+
     async def __aenter__(self):
         await self.setup()
         return self
@@ -1,4 +1,4 @@
-from dataclasses import dataclass, field
+from dataclasses import dataclass, field, asdict
 from typing import Set, Dict, Any, List, Optional
 
 @dataclass
@@ -22,6 +22,7 @@ class MigrationState:
     latest_generation: Optional[Dict[str, Any]] = None
     last_verification_result: Optional[Dict[str, Any]] = None
     current_differences: Optional[Dict[str, Any]] = None
+    code_history: List[Dict[str, Any]] = field(default_factory=list)
     
     def update_metrics(self, step: str, duration: float, tokens: int):
         """Update metrics for a step."""
@@ -38,6 +39,14 @@ class MigrationState:
             
         self.token_usage[step] = self.token_usage.get(step, 0) + tokens
 
+    def record_code(self, origin: str, rust_code: Optional[str]):
+        """Remember which prompt produced a version of the code, for blame."""
+        if not rust_code:
+            return
+        if self.code_history and self.code_history[-1]["rust_code"] == rust_code:
+            return
+        self.code_history.append({"origin": origin, "rust_code": rust_code})
+
     def update_best_result(self, verification_result: Dict[str, Any], 
                           rust_code: str, toml_content: str):
         """Update best result if current is better."""
@@ -53,3 +62,10 @@ class MigrationState:
                 "toml_content": toml_content,
                 "verification": verification_result
             }
+
+    def to_dict(self) -> Dict[str, Any]:
+        """Serializable snapshot of the state, used to persist partial progress."""
+        data = asdict(self)
+        data["successful_fixes"] = sorted(self.successful_fixes)
+        data["failed_fixes"] = sorted(self.failed_fixes)
+        return data
@@ -0,0 +1,74 @@
+# analyzers/__init__.py
+from .async_runtime import RUNTIMES, AsyncUse, Runtime, async_uses, runtime_mappings
+from .class_hierarchy import ClassMapping, class_mappings
+from .context_managers import ContextManager, context_managers
+from .database import (
+    LAYERS, DatabaseLayer, DatabaseUse, SqlStatement, SqlTable, database_mappings, database_use,
+    migration_files, write_migrations
+)
+from .dead_code import DeadDefinition, find_dead_code, referencing_files, remove_definitions
+from .decorators import DecoratorUse, decorator_uses
+from .docstrings import DocumentedSymbol, doc_comment, documented_symbols, rustdoc
+from .exceptions import (
+    ERRORS_CRATE, ExceptionClass, ExceptionHierarchy, ExceptionSite,
+    exception_sites, write_error_crate, write_error_module
+)
+from .formatting import FormatConversion, format_conversions
+from .generators import PythonGenerator, python_generators
+from .definition_index import (
+    Definition, DefinitionIndex, HashingEmbeddings, python_definitions, rust_definitions
+)
+from .import_graph import ImportGraph, module_name
+from .module_state import ModuleState, module_state, state_strategy
+from .naming import Renaming, camel_case, escape_keyword, renamings, rust_identifier, snake_case
+from .native import NativeImport, native_imports
+from .licenses import (
+    CrateLicense, CrateLicenses, LicenseViolation, license_allowed, license_violations
+)
+from .notebook import NotebookCell, notebook_cells, notebook_source
+from .requirements import (
+    CrateDependency, Requirement, imported_dependencies, project_requirements, resolve_requirements
+)
+from .project_inspector import ProjectInspection, inspect_project
+from .python2 import Python2Construct, python2_constructs
+from .python_chunks import PythonChunk, outline, split_module
+from .python_structure import (
+    FunctionSignature, ModuleStructure, analyze_structure, structure_summary
+)
+from .python_symbols import PythonSymbol, collect_symbols
+from .risk import RiskFinding, extra_fix_attempts, risk_warnings, scan_risks
+from .rust_items import RustItem, scan_items
+from .rust_quality import scan_rust_quality
+from .rust_signatures import extract_signatures, summarize_rust
+from .source_map import SourceMap, build_source_map, write_source_map
+from .templates import (
+    ENGINES, PythonTemplate, TemplateEngine, engine_mappings, engine_source,
+    python_templates, write_templates
+)
+from .web_framework import FRAMEWORKS, Framework, framework_mappings
+
+__all__ = [
+    'ImportGraph', 'module_name', 'extract_signatures', 'summarize_rust',
+    'Definition', 'DefinitionIndex', 'HashingEmbeddings', 'python_definitions', 'rust_definitions',
+    'FunctionSignature', 'ModuleStructure', 'analyze_structure', 'structure_summary',
+    'PythonSymbol', 'collect_symbols', 'PythonChunk', 'split_module', 'outline',
+    'RustItem', 'scan_items', 'SourceMap', 'build_source_map', 'write_source_map',
+    'ProjectInspection', 'inspect_project', 'RiskFinding', 'extra_fix_attempts',
+    'risk_warnings', 'scan_risks', 'scan_rust_quality', 'Python2Construct', 'python2_constructs',
+    'DeadDefinition', 'find_dead_code', 'referencing_files', 'remove_definitions',
+    'NotebookCell', 'notebook_cells', 'notebook_source', 'NativeImport', 'native_imports',
+    'DocumentedSymbol', 'doc_comment', 'documented_symbols', 'rustdoc',
+    'ModuleState', 'module_state', 'state_strategy', 'ClassMapping', 'class_mappings',
+    'DecoratorUse', 'decorator_uses', 'PythonGenerator', 'python_generators', 'ContextManager',
+    'context_managers', 'ExceptionClass', 'ExceptionHierarchy', 'ExceptionSite', 'exception_sites',
+    'ERRORS_CRATE', 'write_error_crate', 'write_error_module',
+    'FormatConversion', 'format_conversions', 'CrateDependency', 'Requirement',
+    'imported_dependencies', 'project_requirements', 'resolve_requirements', 'CrateLicense',
+    'CrateLicenses', 'LicenseViolation', 'license_allowed', 'license_violations', 'Renaming',
+    'camel_case', 'escape_keyword', 'renamings', 'rust_identifier', 'snake_case', 'RUNTIMES',
+    'AsyncUse', 'Runtime', 'async_uses', 'runtime_mappings', 'FRAMEWORKS', 'Framework',
+    'framework_mappings', 'ENGINES', 'PythonTemplate', 'TemplateEngine', 'engine_mappings',
+    'engine_source', 'python_templates', 'write_templates', 'LAYERS', 'DatabaseLayer',
+    'DatabaseUse', 'SqlStatement', 'SqlTable', 'database_mappings', 'database_use',
+    'migration_files', 'write_migrations'
+]
@@ -0,0 +1,163 @@
+import ast
+from dataclasses import dataclass, replace
+from typing import Dict, List, Optional, Set, Tuple
+
+from ..config.crate_mappings import CrateMapping
+
+
+@dataclass
+class Runtime:
+    """How generated code targeting a runtime runs, waits for and spawns its work."""
+    name: str
+    crate: Optional[str]  # None for synchronous code
+    version: str = ""
+    features: Tuple[str, ...] = ()
+    main: str = ""
+    test: str = ""
+    sleep: str = ""
+    spawn: str = ""
+    gather: str = ""
+
+    def dependency(self) -> Optional[str]:
+        """The [dependencies] line of the runtime's crate."""
+        if self.crate is None:
+            return None
+        return CrateMapping("", self.crate, "async runtime", version=self.version,
+                            features=list(self.features)).dependency()
+
+    @property
+    def identifier(self) -> Optional[str]:
+        return self.crate.replace("-", "_") if self.crate else None
+
+
+RUNTIMES = {
+    "tokio": Runtime(
+        "tokio", "tokio", "1", ("full",), main="`#[tokio::main] async fn main()`",
+        test="`#[tokio::test] async fn`",
+        sleep="`tokio::time::sleep(Duration::from_secs_f64(seconds)).await`",
+        spawn="`tokio::spawn(future)`, awaiting its JoinHandle",
+        gather="`tokio::join!`, or a `JoinSet`"),
+    "async-std": Runtime(
+        "async-std", "async-std", "1", ("attributes",), main="`#[async_std::main] async fn main()`",
+        test="`#[async_std::test] async fn`",
+        sleep="`async_std::task::sleep(Duration::from_secs_f64(seconds)).await`",
+        spawn="`async_std::task::spawn(future)`, awaiting its JoinHandle",
+        gather="the JoinHandles of `async_std::task::spawn` awaited in order"),
+    "sync": Runtime(
+        "sync", None, main="a plain `fn main()`", test="`#[test] fn`",
+        sleep="`std::thread::sleep(Duration::from_secs_f64(seconds))`",
+        spawn="`std::thread::spawn(closure)`, joining its JoinHandle",
+        gather="`std::thread::scope` threads joined in order, or plain sequential calls"),
+}
+# Calls of the Python libraries -> the idiom of Runtime replacing them
+IDIOMS = {
+    "asyncio.run": "main", "asyncio.sleep": "sleep", "time.sleep": "sleep",
+    "asyncio.create_task": "spawn", "asyncio.ensure_future": "spawn", "threading.Thread": "spawn",
+    "asyncio.gather": "gather", "asyncio.wait": "gather",
+}
+# Calls blocking a thread, taking the runtime's idiom in async functions only
+BLOCKING = ("time.sleep", "threading.Thread")
+
+
+@dataclass
+class AsyncUse:
+    """An async construct of a module, or a call sleeping, spawning or gathering work."""
+    line: int
+    kind: str  # async def, await, async with, async for, or an idiom of IDIOMS
+    construct: str  # the name of the function, or the call as written
+    blocking: bool = False  # a blocking call outside async functions, keeping its std idiom
+
+    def describe(self, runtime: Runtime) -> str:
+        if self.blocking:
+            runtime = RUNTIMES["sync"]
+        if self.kind in IDIOMS.values():
+            return f"line {self.line}: {self.construct} -> {getattr(runtime, self.kind)}"
+        described = f"line {self.line}: {self.kind} {self.construct}".rstrip()
+        return described + (" -> synchronous code" if runtime.crate is None else "")
+
+
+def _imported_names(tree: ast.Module) -> Dict[str, str]:
+    """Local names of the module -> the dotted names they were imported as."""
+    names = {}
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            names.update({alias.asname or alias.name: alias.name for alias in node.names})
+        elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+            names.update({alias.asname or alias.name: f"{node.module}.{alias.name}"
+                          for alias in node.names})
+    return names
+
+
+def _dotted(node: ast.AST) -> Optional[str]:
+    if isinstance(node, ast.Name):
+        return node.id
+    if isinstance(node, ast.Attribute):
+        value = _dotted(node.value)
+        return f"{value}.{node.attr}" if value else None
+    return None
+
+
+def _async_calls(node: ast.AST, in_async: bool = False,
+                 found: Optional[Set[int]] = None) -> Set[int]:
+    """The ids of the calls whose nearest enclosing function is an async one."""
+    found = set() if found is None else found
+    for child in ast.iter_child_nodes(node):
+        if isinstance(child, ast.Call) and in_async:
+            found.add(id(child))
+        inner = isinstance(child, ast.AsyncFunctionDef) or (
+            in_async and not isinstance(child, (ast.FunctionDef, ast.Lambda)))
+        _async_calls(child, inner, found)
+    return found
+
+
+def async_uses(python_code: str) -> List[AsyncUse]:
+    """The async functions, awaits, async with and for blocks of a module, and its calls of
+    asyncio whose Rust idiom depends on the runtime, by line; time.sleep and threading.Thread
+    take the runtime's idiom in async functions, and std's blocking ones elsewhere."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    imported = _imported_names(tree)
+    in_async = _async_calls(tree)
+    uses = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.AsyncFunctionDef):
+            uses.append(AsyncUse(node.lineno, "async def", node.name))
+        elif isinstance(node, ast.Await):
+            uses.append(AsyncUse(node.lineno, "await", ""))
+        elif isinstance(node, ast.AsyncWith):
+            uses.append(AsyncUse(node.lineno, "async with", ""))
+        elif isinstance(node, ast.AsyncFor):
+            uses.append(AsyncUse(node.lineno, "async for", ""))
+        elif isinstance(node, ast.Call):
+            written = _dotted(node.func)
+            if written is None:
+                continue
+            head, _, rest = written.partition(".")
+            resolved = imported.get(head, head) + (f".{rest}" if rest else "")
+            if resolved in IDIOMS:
+                blocking = resolved in BLOCKING and id(node) not in in_async
+                uses.append(AsyncUse(node.lineno, IDIOMS[resolved], f"{written}()", blocking))
+    return sorted(uses, key=lambda use: (use.line, use.kind))
+
+
+def runtime_mappings(mappings: List[CrateMapping], runtime: str) -> List[CrateMapping]:
+    """The crate mappings with the tokio ones, and the runtime-tokio features of the others, moved
+    to the runtime; without a runtime the libraries mapped to tokio are left unmapped."""
+    target = RUNTIMES[runtime]
+    if runtime == "tokio":
+        return mappings
+    moved = []
+    for mapping in mappings:
+        if mapping.crate == "tokio":
+            if target.crate is not None:
+                moved.append(replace(mapping, crate=target.crate, version=target.version,
+                                     features=list(target.features)))
+            continue
+        if target.crate is not None and "runtime-tokio" in mapping.features:
+            mapping = replace(mapping,
+                              features=[f"runtime-{target.crate}" if feature == "runtime-tokio"
+                                                 else feature for feature in mapping.features])
+        moved.append(mapping)
+    return moved
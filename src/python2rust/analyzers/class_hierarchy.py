@@ -0,0 +1,235 @@
+import ast
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Set, Tuple
+
+ENUM_BASES = {"Enum", "IntEnum", "StrEnum", "Flag", "IntFlag"}
+ABSTRACT_BASES = {"ABC", "ABCMeta", "Protocol"}
+EXCEPTION_BASES = {"Exception", "BaseException"}
+# What a class can be planned as; a variant is decided by its base being an enum
+KINDS = ("struct", "trait", "enum")
+
+
+@dataclass
+class ClassMapping:
+    """The Rust item planned for a class of the module, and why."""
+    name: str
+    line: int
+    kind: str  # struct, trait, enum, or variant of the enum its base becomes
+    reason: str
+    bases: List[str] = field(default_factory=list)
+    depth: int = 0  # of inheritance, counting bases outside the module as one level
+    parent: Optional[str] = None  # enum of a variant
+    # traits of its bases, or Protocols it satisfies
+    implements: List[str] = field(default_factory=list)
+    required_methods: List[str] = field(default_factory=list)  # of a trait, without a body
+    default_methods: List[str] = field(default_factory=list)  # of a trait, keeping their body
+    # a trait's __init__ sets, held by its implementors
+    fields: List[str] = field(default_factory=list)
+    overridden: List[str] = field(default_factory=list)  # methods redefining those of a base
+    isinstance_lines: List[int] = field(default_factory=list)  # checks against the class
+
+    def describe(self) -> str:
+        if self.kind == "variant":
+            target = f"variant {self.parent}::{self.name}"
+        else:
+            target = f"{self.kind} {self.name}"
+        if self.implements:
+            target += f" implementing {', '.join(self.implements)}"
+        details = [self.reason]
+        if self.required_methods:
+            details.append(f"requires {', '.join(self.required_methods)}")
+        if self.default_methods:
+            details.append(f"default {', '.join(self.default_methods)}")
+        if self.fields:
+            details.append(f"its implementors hold {', '.join(self.fields)}")
+        if self.depth > 1:
+            details.append(f"inheritance depth {self.depth}")
+        if self.overridden:
+            details.append(f"overrides {', '.join(self.overridden)}")
+        if self.isinstance_lines:
+            details.append("isinstance checks at lines "
+                           f"{', '.join(map(str, self.isinstance_lines))}")
+        return f"{self.name} (line {self.line}) -> {target}: {'; '.join(details)}"
+
+
+def _base_names(node: ast.ClassDef) -> List[str]:
+    names = []
+    for base in node.bases:
+        if isinstance(base, ast.Subscript):
+            base = base.value
+        if isinstance(base, ast.Attribute):
+            names.append(base.attr)
+        elif isinstance(base, ast.Name):
+            names.append(base.id)
+    return names
+
+
+def _methods(node: ast.ClassDef) -> Dict[str, ast.AST]:
+    return {statement.name: statement for statement in node.body
+            if isinstance(statement, (ast.FunctionDef, ast.AsyncFunctionDef))}
+
+
+def _is_abstract_method(method: ast.AST) -> bool:
+    return any((decorator.attr if isinstance(decorator, ast.Attribute)
+                else getattr(decorator, "id", ""))
+               == "abstractmethod" for decorator in method.decorator_list)
+
+
+def _is_abstract(node: ast.ClassDef, bases: List[str]) -> bool:
+    if ABSTRACT_BASES & set(bases) or any(keyword.arg == "metaclass" for keyword in node.keywords):
+        return True
+    return any(_is_abstract_method(method) for method in _methods(node).values())
+
+
+def _is_stub(method: ast.AST) -> bool:
+    """Whether a method only has a docstring, ..., pass or raise NotImplementedError."""
+    for statement in method.body:
+        if isinstance(statement, ast.Pass) or (
+                isinstance(statement, ast.Expr) and isinstance(statement.value, ast.Constant)):
+            continue
+        if isinstance(statement, ast.Raise) and statement.exc is not None:
+            raised = statement.exc.func if isinstance(statement.exc, ast.Call) else statement.exc
+            if isinstance(raised, ast.Name) and raised.id == "NotImplementedError":
+                continue
+        return False
+    return True
+
+
+def _trait_methods(node: ast.ClassDef) -> Tuple[List[str], List[str]]:
+    """The methods of an interface its implementors must write, abstract or stubs, and those
+    with a body, which become default methods."""
+    required, defaults = [], []
+    for name, method in _methods(node).items():
+        if name == "__init__":
+            continue
+        (required if _is_abstract_method(method) or _is_stub(method) else defaults).append(name)
+    return required, defaults
+
+
+def _init_fields(node: ast.ClassDef) -> List[str]:
+    """The attributes __init__ sets on self."""
+    init = _methods(node).get("__init__")
+    fields: List[str] = []
+    for target in ast.walk(init) if init is not None else []:
+        if (isinstance(target, ast.Attribute) and isinstance(target.ctx, ast.Store)
+                and isinstance(target.value, ast.Name) and target.value.id == "self"
+                and target.attr not in fields):
+            fields.append(target.attr)
+    return fields
+
+
+def _isinstance_checks(tree: ast.AST) -> Dict[str, List[int]]:
+    """The lines of the isinstance and issubclass checks against each class name."""
+    checks: Dict[str, List[int]] = {}
+    for node in ast.walk(tree):
+        if (isinstance(node, ast.Call) and isinstance(node.func, ast.Name)
+                and node.func.id in ("isinstance", "issubclass") and len(node.args) == 2):
+            classes = node.args[1].elts if isinstance(node.args[1], ast.Tuple) else [node.args[1]]
+            for target in classes:
+                if isinstance(target, ast.Name):
+                    checks.setdefault(target.id, []).append(node.lineno)
+    return checks
+
+
+def class_mappings(python_code: str,
+                   overrides: Optional[Dict[str, str]] = None) -> List[ClassMapping]:
+    """Plan a Rust item for each top-level class from its hierarchy.
+
+    Enum classes become enums and concrete classes structs. A base class of the module becomes a
+    trait its subclasses implement when it is abstract or they extend it, and an enum with a variant
+    per subclass when the code tells them apart with isinstance and they have no subclasses of their
+    own. Protocols and abstract classes are traits, whose abstract and stub methods are required and
+    the others default methods, implemented by their subclasses and, for Protocols, by the classes
+    defining their required methods. Exception bases with such subclasses become error enums, and
+    the other exceptions structs implementing std::error::Error. overrides, from an edited plan, set
+    the kind (struct, trait or enum) of the classes it names, the kinds of their subclasses
+    following.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    overrides = overrides or {}
+    classes = {node.name: node for node in tree.body if isinstance(node, ast.ClassDef)}
+    bases = {name: _base_names(node) for name, node in classes.items()}
+    subclasses: Dict[str, List[str]] = {name: [] for name in classes}
+    for name, names in bases.items():
+        for base in names:
+            if base in classes:
+                subclasses[base].append(name)
+    checks = _isinstance_checks(tree)
+
+    depths: Dict[str, int] = {}
+
+    def depth(name: str, seen: Set[str]) -> int:
+        if name not in depths:
+            local = [base for base in bases[name] if base in classes and base not in seen]
+            inherited = [depth(base, seen | {name}) + 1 for base in local]
+            depths[name] = max(inherited or [1 if bases[name] and bases[name] != ["object"] else 0])
+        return depths[name]
+
+    def is_exception(name: str, seen: Set[str]) -> bool:
+        return any(base in EXCEPTION_BASES or (base.endswith("Error") and base not in classes)
+                   or (base in classes and base not in seen and is_exception(base, seen | {name}))
+                   for base in bases[name])
+
+    kinds: Dict[str, str] = {}
+    reasons: Dict[str, str] = {}
+    for name, node in classes.items():
+        children = subclasses[name]
+        closed = bool(children) and all(not subclasses[child] for child in children)
+        checked = name in checks or any(child in checks for child in children)
+        exception = is_exception(name, set())
+        if name in overrides:
+            kinds[name], reasons[name] = overrides[name], "as the approved plan says"
+        elif ENUM_BASES & set(bases[name]):
+            kinds[name], reasons[name] = "enum", "an Enum class"
+        elif exception and closed:
+            kinds[name], reasons[name] = "enum", (f"exception base of {', '.join(children)}, as an "
+                                                  "error enum")
+        elif exception and not children:
+            kinds[name], reasons[name] = "struct", "an exception, implementing std::error::Error"
+        elif children and closed and checked and not _is_abstract(node, bases[name]):
+            kinds[name], reasons[name] = "enum", (
+                f"its subclasses {', '.join(children)} are told apart with isinstance")
+        elif children:
+            why = "abstract" if _is_abstract(node, bases[name]) else "extended"
+            kinds[name], reasons[name] = "trait", f"{why} base of {', '.join(children)}"
+        elif "Protocol" in bases[name]:
+            kinds[name], reasons[name] = "trait", ("a Protocol, implemented by the "
+                                                   "classes with its methods")
+        elif _is_abstract(node, bases[name]):
+            kinds[name], reasons[name] = "trait", "an abstract interface"
+        else:
+            kinds[name], reasons[name] = "struct", "a concrete class"
+
+    mappings = []
+    for name, node in classes.items():
+        local_bases = [base for base in bases[name] if base in classes]
+        enum_bases = [base for base in local_bases if kinds[base] == "enum"]
+        inherited = {method for base in local_bases for method in _methods(classes[base])}
+        overridden = sorted(set(_methods(node)) & inherited - {"__init__"})
+        mapping = ClassMapping(
+            name, node.lineno, kinds[name], reasons[name], bases[name], depth(name, set()),
+            implements=[base for base in local_bases if kinds[base] == "trait"],
+            overridden=overridden, isinstance_lines=checks.get(name, []))
+        if enum_bases and name not in overrides and kinds[name] == "struct":
+            mapping.kind, mapping.parent, mapping.reason = "variant", enum_bases[0], (
+                f"a case of {enum_bases[0]}, holding its fields")
+        if mapping.kind == "trait":
+            mapping.required_methods, mapping.default_methods = _trait_methods(node)
+            mapping.fields = _init_fields(node)
+        mappings.append(mapping)
+
+    # Protocols are satisfied by the classes defining their methods, without inheriting them
+    by_name = {mapping.name: mapping for mapping in mappings}
+    for name, node in classes.items():
+        if "Protocol" not in bases[name] or kinds[name] != "trait":
+            continue
+        required = set(by_name[name].required_methods)
+        for other, other_node in classes.items():
+            mapping = by_name[other]
+            if (other != name and required and required <= set(_methods(other_node))
+                    and name not in mapping.implements and mapping.kind == "struct"):
+                mapping.implements.append(name)
+    return mappings
@@ -0,0 +1,176 @@
+import ast
+import io
+import re
+import tokenize
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple
+
+from .python_symbols import collect_symbols
+from .rust_items import RustItem
+from .source_map import matching_symbol
+
+# Comments meant for tools rather than readers
+DIRECTIVE = re.compile(r"^#\s*(type:|noqa|pragma|pylint:|mypy:|pyright:|fmt:|isort:|-\*-|!)",
+                       re.IGNORECASE)
+CHAR_LITERAL = re.compile(r"'(\\.|[^\\'\n])'")
+WORD = re.compile(r"[A-Za-z0-9]{3,}")
+# Share of the words of a Python comment a Rust comment repeats, roughly stemmed, to count as kept
+KEPT_WORDS = 0.6
+
+
+@dataclass
+class PythonComment:
+    """A comment of the Python code with the statement it documents."""
+    line: int
+    text: str  # without the #
+    anchor: str  # first line of the statement it is on or above
+    scope: Optional[str] = None  # qualname of the function, method or class it is in
+
+    def describe(self) -> str:
+        where = f" in {self.scope}" if self.scope else ""
+        return f"line {self.line}{where}: `# {self.text}` at `{self.anchor}`"
+
+
+def _statements(tree: ast.AST) -> List[ast.stmt]:
+    return sorted((node for node in ast.walk(tree) if isinstance(node, ast.stmt)),
+                  key=lambda node: (node.lineno, -node.end_lineno))
+
+
+def _scopes(python_code: str) -> List[Tuple[int, int, str]]:
+    """Line spans of the functions, classes and methods, innermost last."""
+    return [(symbol.start_line, symbol.end_line, symbol.qualname)
+            for symbol in collect_symbols(python_code)
+            if symbol.kind != "main"]
+
+
+def python_comments(python_code: str) -> List[PythonComment]:
+    """The comments of the code, each with the statement on its line or, alone on its line,
+    the next one; tool directives like `# type:` and `# noqa` are left out."""
+    try:
+        tree = ast.parse(python_code)
+        tokens = list(tokenize.generate_tokens(io.StringIO(python_code).readline))
+    except (SyntaxError, tokenize.TokenError, IndentationError):
+        return []
+    lines = python_code.splitlines()
+    statements = _statements(tree)
+    scopes = _scopes(python_code)
+    # Where the comment of each line starts, cut from the statements quoted
+    columns = {token.start[0]: token.start[1] for token in tokens if token.type == tokenize.COMMENT}
+    comments = []
+    for token in tokens:
+        if token.type != tokenize.COMMENT or DIRECTIVE.match(token.string):
+            continue
+        line = token.start[0]
+        text = token.string.lstrip("#").strip()
+        if not text:
+            continue
+        alone = not lines[line - 1][:token.start[1]].strip()
+        if alone:
+            # The statement it is above
+            following = [node for node in statements if node.lineno > line]
+            node = following[0] if following else None
+        else:
+            # The innermost statement of its line
+            containing = [node for node in statements if node.lineno <= line <= node.end_lineno]
+            node = containing[-1] if containing else None
+        anchor = ""
+        if node is not None:
+            anchor = lines[node.lineno - 1][:columns.get(node.lineno)].strip()
+        if len(anchor) > 80:
+            anchor = anchor[:77] + "..."
+        inside = [qualname for start, end, qualname in scopes if start <= line <= end]
+        comments.append(PythonComment(line, text, anchor, inside[-1] if inside else None))
+    return comments
+
+
+def rust_comments(rust_code: str) -> List[Tuple[int, str]]:
+    """The line and text of the comments of Rust code, skipping string and char literals but
+    not raw strings."""
+    comments: List[Tuple[int, str]] = []
+    index, line, length = 0, 1, len(rust_code)
+    while index < length:
+        char = rust_code[index]
+        if rust_code.startswith("//", index):
+            end = rust_code.find("\n", index)
+            end = length if end == -1 else end
+            comments.append((line, rust_code[index:end].lstrip("/!").strip()))
+            index = end
+            continue
+        if rust_code.startswith("/*", index):
+            end = rust_code.find("*/", index + 2)
+            end = length if end == -1 else end + 2
+            text = rust_code[index + 2:end - 2].strip("*! \n")
+            comments.append((line, " ".join(text.split())))
+            line += rust_code.count("\n", index, end)
+            index = end
+            continue
+        if char == '"':
+            end = index + 1
+            while end < length and rust_code[end] != '"':
+                end += 2 if rust_code[end] == "\\" else 1
+            line += rust_code.count("\n", index, end)
+            index = end + 1
+            continue
+        literal = CHAR_LITERAL.match(rust_code, index) if char == "'" else None
+        if literal:
+            index = literal.end()
+            continue
+        if char == "\n":
+            line += 1
+        index += 1
+    return comments
+
+
+def _stem(word: str) -> str:
+    word = word.lower()
+    for suffix in ("ing", "ed", "es", "s"):
+        if word.endswith(suffix) and len(word) - len(suffix) >= 3:
+            return word[:-len(suffix)]
+    return word
+
+
+def _words(text: str) -> List[str]:
+    return [_stem(word) for word in WORD.findall(text)]
+
+
+def _kept(comment: PythonComment, rust_texts: List[str]) -> bool:
+    words = set(_words(comment.text))
+    for text in rust_texts:
+        if not words:
+            if comment.text.lower() in text.lower():
+                return True
+            continue
+        if len(words & set(_words(text))) >= KEPT_WORDS * len(words):
+            return True
+    return False
+
+
+def missing_comments(
+    comments: List[PythonComment],
+    python_code: str,
+    rust_code: str,
+    functions: List[Dict]
+) -> List[PythonComment]:
+    """The comments the Rust code does not repeat in or just above the functions translating
+    their scope, or anywhere for the comments outside functions and those of untranslated ones.
+
+    functions are those of the policy analyzer's report, with their names and line spans.
+    """
+    symbols = collect_symbols(python_code)
+    spans: Dict[str, List[Tuple[int, int]]] = {}
+    for function in functions:
+        parent, _, name = function["name"].rpartition("::")
+        item = RustItem(name, "method" if parent else "fn", function["start_line"],
+                        function["end_line"], parent or None)
+        symbol = matching_symbol(item, symbols)
+        if symbol is not None:
+            spans.setdefault(symbol.qualname, []).append((item.start_line, item.end_line))
+    found = rust_comments(rust_code)
+    missing = []
+    for comment in comments:
+        ranges = spans.get(comment.scope or "")
+        texts = [text for line, text in found
+                 if ranges is None or any(start - 3 <= line <= end for start, end in ranges)]
+        if not _kept(comment, texts):
+            missing.append(comment)
+    return missing
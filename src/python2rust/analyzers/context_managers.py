@@ -0,0 +1,122 @@
+import ast
+import re
+from dataclasses import dataclass, field
+from typing import List
+
+CONTEXTMANAGER_DECORATORS = {"contextmanager", "asynccontextmanager"}
+
+
+@dataclass
+class ContextManager:
+    """A context manager the module defines, or a with block of the module."""
+    line: int
+    kind: str  # class (with __enter__ and __exit__), function (@contextmanager) or with (a block)
+    name: str  # of the class or function, or of the function holding a with block
+    managers: List[str] = field(default_factory=list)  # what a with block enters
+    # return, break, continue or raise leaving a block
+    exits_early: List[str] = field(default_factory=list)
+    suppresses: bool = False  # an __exit__ returning True, swallowing the exception
+    is_async: bool = False
+
+    @property
+    def guard(self) -> str:
+        """The Rust type whose Drop cleans up after a context manager the module defines."""
+        last = self.name.split(".")[-1]
+        if self.kind == "class":
+            return last
+        return "".join(part.capitalize() for part in last.split("_") if part) + "Guard"
+
+    @property
+    def test_name(self) -> str:
+        """The Rust test checking the cleanup of a context manager runs when its block fails."""
+        name = re.sub(r"(?<=[a-z0-9])(?=[A-Z])", "_", self.name)
+        name = re.sub(r"\W+", "_", name).strip("_").lower()
+        return f"{name}_cleans_up_on_error"
+
+    def describe(self) -> str:
+        if self.kind == "with":
+            entered = ", ".join(self.managers)
+            early = f", left early by {', '.join(self.exits_early)}" if self.exits_early else ""
+            block = "async with" if self.is_async else "with"
+            return f"line {self.line}: {block} {entered} in {self.name}{early}"
+        what = ("class with __enter__ and __exit__" if self.kind == "class"
+                else "@contextmanager function")
+        swallow = ", its __exit__ can swallow the exception" if self.suppresses else ""
+        return f"line {self.line}: {self.name} ({what}{swallow}) -> Drop of {self.guard}"
+
+
+def _own_nodes(node: ast.AST) -> List[ast.AST]:
+    """The nodes of a block outside the functions, lambdas and classes it defines."""
+    nodes, pending = [], list(ast.iter_child_nodes(node))
+    while pending:
+        child = pending.pop()
+        nodes.append(child)
+        if not isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef, ast.Lambda, ast.ClassDef)):
+            pending.extend(ast.iter_child_nodes(child))
+    return nodes
+
+
+def _early_exits(block: ast.AST) -> List[str]:
+    """The statements leaving a with block before its end, as "return (line 4)"."""
+    loops = [node for node in _own_nodes(block)
+             if isinstance(node, (ast.For, ast.AsyncFor, ast.While))]
+    in_loop = {id(inner) for loop in loops for inner in ast.walk(loop)}
+    exits = []
+    for node in _own_nodes(block):
+        if isinstance(node, (ast.Return, ast.Raise)) or (
+                isinstance(node, (ast.Break, ast.Continue)) and id(node) not in in_loop):
+            exits.append((node.lineno, f"{type(node).__name__.lower()} (line {node.lineno})"))
+    return [statement for _, statement in sorted(exits)]
+
+
+def _suppresses(exit_method: ast.AST) -> bool:
+    return any(isinstance(node, ast.Return) and isinstance(node.value, ast.Constant)
+               and node.value.value is True for node in _own_nodes(exit_method))
+
+
+def _decorator_name(decorator: ast.AST) -> str:
+    if isinstance(decorator, ast.Call):
+        decorator = decorator.func
+    return decorator.attr if isinstance(decorator, ast.Attribute) else getattr(decorator, "id", "")
+
+
+def context_managers(python_code: str) -> List[ContextManager]:
+    """The context managers a module defines, classes with __enter__ and __exit__ (or their
+    async versions) and @contextmanager functions, and its with blocks, in order."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    found = []
+
+    def visit(node: ast.AST, prefix: str, scope: str) -> None:
+        for child in ast.iter_child_nodes(node):
+            if isinstance(child, ast.ClassDef):
+                methods = {statement.name: statement for statement in child.body
+                           if isinstance(statement, (ast.FunctionDef, ast.AsyncFunctionDef))}
+                for enter, exit_name in (("__enter__", "__exit__"), ("__aenter__", "__aexit__")):
+                    if enter in methods and exit_name in methods:
+                        found.append(ContextManager(
+                            child.lineno, "class", f"{prefix}{child.name}",
+                            suppresses=_suppresses(methods[exit_name]),
+                            is_async=enter == "__aenter__"))
+                        break
+                visit(child, f"{prefix}{child.name}.", scope)
+                continue
+            if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                qualname = f"{prefix}{child.name}"
+                if any(_decorator_name(decorator) in CONTEXTMANAGER_DECORATORS
+                       for decorator in child.decorator_list):
+                    found.append(ContextManager(child.lineno, "function", qualname,
+                                                is_async=isinstance(child, ast.AsyncFunctionDef)))
+                visit(child, f"{qualname}.", qualname)
+                continue
+            if isinstance(child, (ast.With, ast.AsyncWith)):
+                found.append(ContextManager(
+                    child.lineno, "with", scope,
+                    managers=[ast.unparse(item.context_expr) for item in child.items],
+                    exits_early=_early_exits(child), is_async=isinstance(child, ast.AsyncWith)))
+            visit(child, prefix, scope)
+
+    visit(tree, "", "<module>")
+    return sorted(found, key=lambda manager: manager.line)
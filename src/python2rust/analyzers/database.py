@@ -0,0 +1,215 @@
+import ast
+import re
+from dataclasses import dataclass, field, replace
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from ..config.crate_mappings import CrateMapping
+
+# The Python database drivers -> the backend they talk to, None for SQLAlchemy's any
+DRIVERS = {
+    "sqlite3": "sqlite", "psycopg2": "postgres", "psycopg": "postgres", "asyncpg": "postgres",
+    "pymysql": "mysql", "MySQLdb": "mysql", "mysql.connector": "mysql", "aiosqlite": "sqlite",
+    "sqlalchemy": None,
+}
+DATABASE_URL = re.compile(r"^(sqlite|postgres(?:ql)?|mysql)(?:\+\w+)?://")
+STATEMENT = re.compile(r"^\s*(SELECT|INSERT|UPDATE|DELETE|CREATE\s+TABLE|WITH)\b", re.IGNORECASE)
+CREATE_TABLE = re.compile(r"CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?[\"`]?"
+                          r"(\w+)[\"`]?\s*\(.*?\)\s*(?:;|$)",
+                          re.IGNORECASE | re.DOTALL)
+
+
+@dataclass
+class DatabaseLayer:
+    """A Rust data-access stack, and how generated code pools connections, queries and migrates."""
+    name: str
+    version: str
+    features: Tuple[str, ...]
+    backends: Dict[str, Tuple[str, ...]]  # the features of each backend
+    # the features of each async runtime, none when synchronous
+    runtimes: Dict[str, Tuple[str, ...]]
+    connections: Dict[str, str]  # the connection type of each backend, for pool
+    extra: Tuple[str, ...]  # [dependencies] lines of its other crates
+    pool: str  # with {connection}
+    queries: str
+    migrations: str
+    files: Tuple[str, ...]  # the files of a migration under migrations/, with {version} and {name}
+    unchecked: str = ""  # the queries without a database at build time, when they differ
+
+    @property
+    def checks_queries(self) -> bool:
+        """Whether its queries are checked against a database when the code builds."""
+        return bool(self.unchecked)
+
+    @property
+    def identifier(self) -> str:
+        return self.name.replace("-", "_")
+
+    def mapping(self, backend: Optional[str], runtime: str) -> CrateMapping:
+        """The crate mapping of the layer with the features of the backend, if known, and of the
+        runtime; async layers run on tokio when the code is synchronous."""
+        runtime_features = self.runtimes.get(runtime, self.runtimes.get("tokio", ()))
+        features = [*self.features, *self.backends.get(backend or "", ()), *runtime_features]
+        return CrateMapping("", self.name, "database", version=self.version, features=features)
+
+    def dependencies(self, backend: str, runtime: str) -> List[str]:
+        """The [dependencies] lines of the layer and its other crates."""
+        return [self.mapping(backend, runtime).dependency(), *self.extra]
+
+
+LAYERS = {
+    "sqlx": DatabaseLayer(
+        "sqlx", "0.7", ("macros", "migrate"),
+        backends={"sqlite": ("sqlite",), "postgres": ("postgres",), "mysql": ("mysql",)},
+        runtimes={"tokio": ("runtime-tokio",), "async-std": ("runtime-async-std",)},
+        connections={"sqlite": "SqlitePool", "postgres": "PgPool", "mysql": "MySqlPool"}, extra=(),
+        pool="a `sqlx::{connection}` connected with `{connection}::connect(&database_url).await?`",
+        queries="the `sqlx::query!`, `sqlx::query_as!` and `sqlx::query_scalar!` macros, checked "
+                "against the database at compile time, and the backend's bind parameters",
+        migrations="run at startup with `sqlx::migrate!(\"./migrations\").run(&pool).await?`",
+        files=("{version}_{name}.sql",),
+        unchecked="the `sqlx::query` and `sqlx::query_as` functions, with "
+                  "`#[derive(sqlx::FromRow)]` structs for the rows and the backend's bind "
+                  "parameters; no database is available at build time for the query! macros "
+                  "to check them against"),
+    "diesel": DatabaseLayer(
+        "diesel", "2", ("r2d2",),
+        backends={"sqlite": ("sqlite",), "postgres": ("postgres",), "mysql": ("mysql",)},
+        runtimes={}, connections={"sqlite": "SqliteConnection", "postgres": "PgConnection",
+                                  "mysql": "MysqlConnection"},
+        extra=('diesel_migrations = "2"',),
+        pool="an `r2d2::Pool<ConnectionManager<{connection}>>` built with "
+             "`Pool::builder().build(ConnectionManager::new(database_url))?`",
+        queries="diesel's query builder over the `table!` schema of src/schema.rs, with "
+                "`#[derive(Queryable, Selectable, Insertable)]` structs for the rows",
+        migrations="embedded with `diesel_migrations::embed_migrations!(\"migrations\")` and run "
+                   "at startup with `connection.run_pending_migrations(MIGRATIONS)`",
+        files=("{version}_{name}/up.sql", "{version}_{name}/down.sql")),
+    "sea-orm": DatabaseLayer(
+        "sea-orm", "0.12", ("macros",),
+        backends={"sqlite": ("sqlx-sqlite",), "postgres": ("sqlx-postgres",),
+                  "mysql": ("sqlx-mysql",)},
+        runtimes={"tokio": ("runtime-tokio-rustls",), "async-std": ("runtime-async-std-rustls",)},
+        connections={}, extra=('sea-orm-migration = "0.12"',),
+        pool="a pooled `sea_orm::DatabaseConnection` connected with "
+             "`Database::connect(ConnectOptions::new(database_url)).await?`",
+        queries="an entity with `#[derive(DeriveEntityModel)]` for each table, `Entity::find()` "
+                "and `ActiveModel` inserts and updates, and "
+                "`Statement::from_sql_and_values` for the others",
+        migrations="each a `MigrationTrait` running its file with "
+                   "`manager.get_connection().execute_unprepared(include_str!(...))`, listed by a "
+                   "`MigratorTrait` run at startup with `Migrator::up(&db, None).await?`",
+        files=("{version}_{name}.sql",)),
+}
+
+
+@dataclass
+class SqlStatement:
+    """A SQL statement of a module, a string literal it executes."""
+    line: int
+    kind: str  # select, insert, update, delete, create table or with
+    sql: str
+
+    def describe(self) -> str:
+        return f"line {self.line}: {' '.join(self.sql.split())}"
+
+
+@dataclass
+class SqlTable:
+    """A table a module creates, and the statement creating it."""
+    name: str
+    sql: str
+
+
+@dataclass
+class DatabaseUse:
+    """How a module uses a SQL database: its driver, backend, tables and queries."""
+    driver: str
+    backend: str  # sqlite, postgres or mysql
+    tables: List[SqlTable] = field(default_factory=list)
+    statements: List[SqlStatement] = field(default_factory=list)
+
+    @property
+    def queries(self) -> List[SqlStatement]:
+        return [statement for statement in self.statements if statement.kind != "create table"]
+
+    def describe(self) -> str:
+        tables = ", ".join(table.name for table in self.tables) or "none"
+        return (f"{self.driver} on {self.backend}, creating tables: {tables}, with "
+                f"{len(self.queries)} queries")
+
+
+def _imported_driver(tree: ast.Module) -> Optional[str]:
+    for node in ast.walk(tree):
+        names = []
+        if isinstance(node, ast.Import):
+            names = [alias.name for alias in node.names]
+        elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+            names = [node.module]
+        for name in names:
+            driver = next((driver for driver in DRIVERS
+                           if name == driver or name.startswith(f"{driver}.")), None)
+            if driver is not None:
+                return driver
+    return None
+
+
+def database_use(python_code: str) -> Optional[DatabaseUse]:
+    """The SQL database use of a module: the driver it imports, the backend the driver or its
+    database URLs tell, postgres when neither does, the tables its CREATE TABLE statements create
+    and its queries. None when it imports no SQL driver."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return None
+    driver = _imported_driver(tree)
+    if driver is None:
+        return None
+    backend = DRIVERS[driver]
+    statements: List[SqlStatement] = []
+    tables: Dict[str, SqlTable] = {}
+    for node in ast.walk(tree):
+        if not isinstance(node, ast.Constant) or not isinstance(node.value, str):
+            continue
+        url = DATABASE_URL.match(node.value)
+        if url and backend is None:
+            backend = "postgres" if url.group(1).startswith("postgres") else url.group(1)
+        for table in CREATE_TABLE.finditer(node.value):
+            tables.setdefault(table.group(1), SqlTable(table.group(1), table.group(0).strip()))
+        statement = STATEMENT.match(node.value)
+        if statement:
+            kind = " ".join(statement.group(1).lower().split())
+            statements.append(SqlStatement(node.lineno, kind, node.value.strip()))
+    return DatabaseUse(driver, backend or "postgres", list(tables.values()),
+                       sorted(statements, key=lambda statement: statement.line))
+
+
+def migration_files(tables: List[SqlTable], layer: str) -> List[Tuple[str, SqlTable]]:
+    """The files of the migrations creating the tables, relative to the crate, in
+    the layer's layout."""
+    return [(f"migrations/{pattern.format(version=f'{version:04d}', name=f'create_{table.name}')}",
+             table)
+            for version, table in enumerate(tables, start=1) for pattern in LAYERS[layer].files]
+
+
+def write_migrations(output_dir: Path, tables: List[SqlTable], layer: str) -> List[Path]:
+    """A migration creating each table, written into the migrations/ directory of the crate in the
+    layer's layout; diesel ones with a down.sql dropping it."""
+    written = []
+    for name, table in migration_files(tables, layer):
+        path = Path(output_dir) / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        sql = (f"DROP TABLE {table.name};" if path.name == "down.sql"
+               else table.sql.rstrip(";") + ";")
+        path.write_text(sql + "\n")
+        written.append(path)
+    return written
+
+
+def database_mappings(mappings: List[CrateMapping], layer: str, runtime: str) -> List[CrateMapping]:
+    """The crate mappings with the SQL drivers mapped to the layer, with the features of their
+    backend and of the runtime."""
+    target = LAYERS[layer]
+    return [replace(mapping, crate=target.name, version=target.version,
+                    features=target.mapping(DRIVERS[mapping.python], runtime).features)
+            if mapping.python in DRIVERS else mapping for mapping in mappings]
@@ -0,0 +1,145 @@
+import ast
+import re
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional, Set, Tuple
+
+from ..utils.logging import setup_logger
+from .import_graph import ImportGraph
+from .python_tests import SKIPPED_DIRS, TEST_DIRS, is_test_file
+
+logger = setup_logger()
+
+IDENTIFIER = re.compile(r"[A-Za-z_]\w*")
+# Strings naming code, such as getattr(obj, "name"), __all__ entries or "package.module:function"
+NAME_STRING = re.compile(r"^[A-Za-z_][\w.:]*$")
+
+DefinitionNode = (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)
+
+
+@dataclass
+class DeadDefinition:
+    """A top-level function or class nothing reachable refers to."""
+    name: str
+    kind: str  # function or class
+    start_line: int
+    end_line: int
+
+
+def _references(nodes: Iterable[ast.AST]) -> Set[str]:
+    """Every name the nodes use, as variables, attributes, imports or name-like strings."""
+    names = set()
+    for root in nodes:
+        for node in ast.walk(root):
+            if isinstance(node, ast.Name):
+                names.add(node.id)
+            elif isinstance(node, ast.Attribute):
+                names.add(node.attr)
+            elif isinstance(node, ast.alias):
+                names.update(node.name.split("."))
+            elif isinstance(node, ast.Constant) and isinstance(node.value, str) \
+                    and NAME_STRING.match(node.value):
+                names.update(IDENTIFIER.findall(node.value))
+    return names
+
+
+def referencing_files(roots: Iterable[Path], sources: Iterable[Path]) -> List[Path]:
+    """The test files and package __init__.py files under the source roots, or under a tests/
+    directory next to them, which are not migrated but use what is."""
+    sources = set(sources)
+    files = []
+    for root in sorted(set(roots)):
+        for base in [root,
+                     *(root.parent / name for name in TEST_DIRS if (root.parent / name).is_dir())]:
+            for path in sorted(base.rglob("*.py")):
+                parts = path.relative_to(base).parts[:-1]
+                if any(part in SKIPPED_DIRS or part.startswith(".") for part in parts):
+                    continue
+                if (is_test_file(path) or path.name == "__init__.py") and path not in sources \
+                        and path not in files:
+                    files.append(path)
+    return files
+
+
+def find_dead_code(
+    graph: ImportGraph,
+    entry_points: Optional[List[Path]] = None,
+    references: Iterable[Path] = (),
+    used: Iterable[str] = ()
+) -> Dict[Path, List[DeadDefinition]]:
+    """The top-level functions and classes of each file that no reachable code uses.
+
+    Reachable are the module-level code of the files imported from the entry points and the
+    test files, decorated definitions (which frameworks call once registered), dunder names,
+    and whatever they use, transitively. Names are matched across files whatever module they
+    come from, so anything named like something used is kept. Without entry points the files
+    are taken for a library: all of them are imported, and their public definitions used.
+    Every name written in the references files is reachable too, and so are the used names.
+    """
+    resolved = {Path(entry_point).resolve() for entry_point in entry_points or []}
+    application = [file for file in graph.files if file.resolve() in resolved]
+    if resolved and not application:
+        logger.warning("None of the entry points is migrated; looking for dead "
+                       "code as in a library")
+    tests = [file for file in graph.files if is_test_file(file)]
+    imported = graph.imported_from(application + tests) if application else set(graph.files)
+    definitions: Dict[str, List[Tuple[Path, ast.stmt]]] = {}
+    reachable: Set[str] = set(used)
+    for file in references:
+        try:
+            reachable.update(IDENTIFIER.findall(file.read_text()))
+        except OSError:
+            continue
+    for file in graph.files:
+        try:
+            code = file.read_text()
+        except OSError:
+            continue
+        try:
+            tree = ast.parse(code, filename=str(file))
+        except (SyntaxError, ValueError) as e:
+            # Keep whatever its words could name
+            logger.warning(f"Not looking for dead code in {file}: {e}")
+            reachable.update(IDENTIFIER.findall(code))
+            continue
+        if is_test_file(file):
+            reachable.update(_references([tree]))
+            continue
+        for node in tree.body:
+            if not isinstance(node, DefinitionNode):
+                if file in imported:
+                    reachable.update(_references([node]))
+                continue
+            definitions.setdefault(node.name, []).append((file, node))
+            called = node.decorator_list or node.name.startswith("__")
+            if called and file in imported or not node.name.startswith("_") and not application:
+                reachable.add(node.name)
+
+    visited: Set[int] = set()
+    pending = list(reachable)
+    while pending:
+        for _, node in definitions.get(pending.pop(), []):
+            if id(node) not in visited:
+                visited.add(id(node))
+                names = _references([node]) - reachable
+                reachable.update(names)
+                pending.extend(names)
+
+    dead: Dict[Path, List[DeadDefinition]] = {}
+    for name, nodes in definitions.items():
+        for file, node in nodes:
+            if id(node) not in visited:
+                kind = "class" if isinstance(node, ast.ClassDef) else "function"
+                dead.setdefault(file,
+                                []).append(DeadDefinition(name, kind, node.lineno, node.end_lineno))
+    for unused in dead.values():
+        unused.sort(key=lambda definition: definition.start_line)
+    return dead
+
+
+def remove_definitions(python_code: str, definitions: List[DeadDefinition]) -> str:
+    """The code without the lines of the definitions."""
+    removed = {line for definition in definitions
+               for line in range(definition.start_line, definition.end_line + 1)}
+    lines = python_code.splitlines(keepends=True)
+    return "".join(line for number, line in enumerate(lines, start=1) if number not in removed)
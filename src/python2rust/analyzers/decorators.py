@@ -0,0 +1,123 @@
+import ast
+from dataclasses import dataclass
+from fnmatch import fnmatchcase
+from typing import Dict, Iterable, List, Optional, Tuple
+
+from ..config.decorators import load_decorator_strategies
+
+# How decorators defined by the project itself are translated
+CUSTOM_STRATEGY = (
+    "a decorator of the project: translate what its wrapper adds around the decorated function "
+    "(a wrapping function, or a macro_rules! macro when many functions use it)"
+)
+
+
+@dataclass
+class DecoratorUse:
+    """A decorator applied to a function or class, and how its translation handles it."""
+    line: int
+    name: str  # resolved through the module's imports, e.g. functools.wraps for @wraps
+    target: str  # decorated function or class, Class.method for methods
+    strategy: Optional[str] = None  # None when neither the catalog nor the project maps it
+    # catalog, config (python2rust.toml), project (defined in it) or unknown
+    origin: str = "unknown"
+
+    @property
+    def known(self) -> bool:
+        return self.strategy is not None
+
+    def describe(self) -> str:
+        return f"line {self.line}: @{self.name} on {self.target}"
+
+
+def _imported_names(tree: ast.Module) -> Tuple[Dict[str, str], List[str]]:
+    """The full dotted name of each imported name, and the names imported from the project
+    through relative imports."""
+    names: Dict[str, str] = {}
+    relative = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            for alias in node.names:
+                if alias.asname:
+                    names[alias.asname] = alias.name
+                else:
+                    root = alias.name.split(".")[0]
+                    names[root] = root
+        elif isinstance(node, ast.ImportFrom):
+            for alias in node.names:
+                local = alias.asname or alias.name
+                if node.level:
+                    relative.append(local)
+                names[local] = f"{node.module}.{alias.name}" if node.module else alias.name
+    return names, relative
+
+
+def _dotted(node: ast.AST) -> Optional[List[str]]:
+    if isinstance(node, ast.Call):
+        node = node.func
+    parts = []
+    while isinstance(node, ast.Attribute):
+        parts.insert(0, node.attr)
+        node = node.value
+    if not isinstance(node, ast.Name):
+        return None
+    return [node.id, *parts]
+
+
+def _match(name: str, rules: Iterable[Tuple[str, str]]) -> Optional[str]:
+    """The strategy of the rule naming the decorator, or else of the first pattern matching it."""
+    rules = list(rules)
+    for pattern, strategy in rules:
+        if pattern == name:
+            return strategy
+    for pattern, strategy in rules:
+        if "*" in pattern and fnmatchcase(name, pattern):
+            return strategy
+    return None
+
+
+def decorator_uses(python_code: str,
+                   configured: Optional[Dict[str, str]] = None) -> List[DecoratorUse]:
+    """The decorators of a module's functions, methods and classes, in order, with their strategy.
+
+    The decorators of the [decorators] table of python2rust.toml (configured) come first, then
+    those of the built-in catalog; names can be patterns, *.route matching the route decorator
+    of any app. Decorators the module defines or imports from the project are translated with
+    it, and the others, without a strategy, need one configured.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    imported, relative = _imported_names(tree)
+    defined = {node.name for node in tree.body
+               if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef))}
+    catalog = [(decorator.name, decorator.strategy) for decorator in load_decorator_strategies()]
+
+    uses = []
+
+    def visit(body: List[ast.stmt], prefix: str) -> None:
+        for node in body:
+            if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)):
+                continue
+            target = f"{prefix}{node.name}"
+            for decorator in node.decorator_list:
+                parts = _dotted(decorator)
+                if parts is None:
+                    continue
+                name = ".".join([imported.get(parts[0], parts[0]), *parts[1:]])
+                use = DecoratorUse(decorator.lineno, name, target)
+                strategy = _match(name, (configured or {}).items())
+                if strategy is not None:
+                    use.strategy, use.origin = strategy, "config"
+                elif parts[0] in relative or (len(parts) == 1 and parts[0] in defined):
+                    use.strategy, use.origin = CUSTOM_STRATEGY, "project"
+                else:
+                    strategy = _match(name, catalog)
+                    if strategy is not None:
+                        use.strategy, use.origin = strategy, "catalog"
+                uses.append(use)
+            visit(node.body, f"{target}.")
+
+    visit(tree.body, "")
+    return sorted(uses, key=lambda use: use.line)
@@ -0,0 +1,171 @@
+import hashlib
+import json
+import keyword
+import math
+import re
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from .python_symbols import collect_symbols
+from .rust_items import scan_items
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+EMBEDDING_DIMENSIONS = 512
+# Longer definitions are cut, their signature and first lines are what prompts need
+MAX_DEFINITION_LINES = 40
+RUST_KINDS = ("fn", "struct", "enum", "trait", "type", "const", "static")
+IDENTIFIER_PATTERN = re.compile(r"[A-Za-z_][A-Za-z0-9_]*")
+WORD_PATTERN = re.compile(r"[A-Z]+(?![a-z])|[A-Z]?[a-z]+|[0-9]+")
+# Words every definition shares, which would make unrelated code look similar
+STOP_WORDS = {word.lower() for word in keyword.kwlist} | {
+    "self", "cls", "fn", "pub", "impl", "struct", "enum", "trait", "let", "mut", "use", "crate",
+    "mod", "match", "ref", "where", "str", "int", "float", "bool", "string", "vec", "option",
+    "result", "ok", "err", "some", "none", "i32", "i64", "u32", "u64", "f32", "f64", "usize",
+    "print", "println", "len", "new", "init", "to", "from", "get", "set"
+}
+
+
+@dataclass
+class Definition:
+    """A type or function of one module, in Python or in its generated Rust."""
+    module: str
+    name: str
+    kind: str
+    language: str  # python, rust
+    code: str
+
+
+def _excerpt(lines: List[str], start_line: int, end_line: int) -> str:
+    selected = lines[start_line - 1:end_line]
+    if len(selected) > MAX_DEFINITION_LINES:
+        selected = selected[:MAX_DEFINITION_LINES] + ["    ..."]
+    return "\n".join(selected)
+
+
+def python_definitions(module: str, python_code: str) -> List[Definition]:
+    """Top-level functions and classes of a Python module."""
+    lines = python_code.splitlines()
+    return [
+        Definition(module, symbol.name, symbol.kind, "python",
+                   _excerpt(lines, symbol.start_line, symbol.end_line))
+        for symbol in collect_symbols(python_code)
+        if symbol.kind in ("function", "class")
+    ]
+
+
+def rust_definitions(module: str, rust_code: str) -> List[Definition]:
+    """Top-level types, functions and constants of generated Rust code, except main."""
+    lines = rust_code.splitlines()
+    return [
+        Definition(module, item.name, item.kind, "rust",
+                   _excerpt(lines, item.start_line, item.end_line))
+        for item in scan_items(rust_code)
+        if item.kind in RUST_KINDS and not (item.kind == "fn" and item.name == "main")
+    ]
+
+
+class HashingEmbeddings:
+    """Local embeddings: the words of identifiers hashed into a fixed-size vector.
+
+    Implements the embed_documents/embed_query interface of LangChain embeddings
+    without calling a model, so that references by name (`Circle`, `parse_config`)
+    find the definitions they point to.
+    """
+
+    def __init__(self, dimensions: int = EMBEDDING_DIMENSIONS):
+        self.dimensions = dimensions
+
+    def _terms(self, text: str) -> Iterable[str]:
+        for identifier in IDENTIFIER_PATTERN.findall(text):
+            terms = {identifier.lower(),
+                     *(word.lower() for word in WORD_PATTERN.findall(identifier))}
+            yield from (term for term in terms if term not in STOP_WORDS)
+
+    def embed_query(self, text: str) -> List[float]:
+        vector = [0.0] * self.dimensions
+        for term in self._terms(text):
+            digest = hashlib.sha256(term.encode()).digest()
+            vector[int.from_bytes(digest[:4], "big") % self.dimensions] += 1.0
+        return vector
+
+    def embed_documents(self, texts: List[str]) -> List[List[float]]:
+        return [self.embed_query(text) for text in texts]
+
+
+def _cosine(a: List[float], b: List[float]) -> float:
+    dot = sum(x * y for x, y in zip(a, b))
+    norm = math.sqrt(sum(x * x for x in a)) * math.sqrt(sum(y * y for y in b))
+    return dot / norm if norm else 0.0
+
+
+class DefinitionIndex:
+    """Embedding index over the definitions of a project's Python modules and generated Rust.
+
+    Vectors are cached by text hash, in cache_file when given, so that each
+    definition is embedded once across runs.
+    """
+
+    def __init__(self, embeddings: Optional[Any] = None, cache_file: Optional[Path] = None):
+        self.embeddings = embeddings or HashingEmbeddings()
+        self.cache_file = Path(cache_file) if cache_file else None
+        self._definitions: Dict[Tuple[str, str], List[Definition]] = {}
+        self._vectors: Dict[str, List[float]] = {}
+        if self.cache_file is not None and self.cache_file.exists():
+            try:
+                self._vectors = json.loads(self.cache_file.read_text())
+            except (OSError, json.JSONDecodeError) as e:
+                logger.warning(f"Ignoring unreadable embedding cache {self.cache_file}: {e}")
+
+    @staticmethod
+    def _hash(text: str) -> str:
+        return hashlib.sha256(text.encode()).hexdigest()
+
+    def _embed(self, texts: List[str]) -> None:
+        missing = list(dict.fromkeys(t for t in texts if self._hash(t) not in self._vectors))
+        if not missing:
+            return
+        for text, vector in zip(missing, self.embeddings.embed_documents(missing)):
+            self._vectors[self._hash(text)] = list(vector)
+        if self.cache_file is not None:
+            self.cache_file.parent.mkdir(parents=True, exist_ok=True)
+            self.cache_file.write_text(json.dumps(self._vectors))
+
+    def update(self, module: str, language: str, definitions: List[Definition]) -> None:
+        """Replace the definitions of a module in one language."""
+        self._embed([definition.code for definition in definitions])
+        self._definitions[(module, language)] = list(definitions)
+
+    def __len__(self) -> int:
+        return sum(len(definitions) for definitions in self._definitions.values())
+
+    def search(self, text: str, limit: int,
+               exclude_modules: Iterable[str] = ()) -> List[Definition]:
+        """The definitions most similar to a text, the Rust version of each when it exists."""
+        excluded = set(exclude_modules)
+        candidates = [
+            definition
+            for (module, _), definitions in self._definitions.items()
+            if module not in excluded
+            for definition in definitions
+        ]
+        if not candidates or limit <= 0:
+            return []
+
+        query = self.embeddings.embed_query(text)
+        scores = [_cosine(query, self._vectors[self._hash(d.code)]) for d in candidates]
+        rust = {(d.module, d.name): d for d in candidates if d.language == "rust"}
+        results: List[Definition] = []
+        seen = set()
+        for index in sorted(range(len(candidates)), key=lambda i: (-scores[i], i)):
+            definition = candidates[index]
+            key = (definition.module, definition.name)
+            if scores[index] <= 0 or key in seen:
+                continue
+            seen.add(key)
+            results.append(rust.get(key, definition))
+            if len(results) == limit:
+                break
+        return results
@@ -0,0 +1,183 @@
+import ast
+import inspect
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple
+
+from .python_symbols import PythonSymbol, collect_symbols
+
+# Google and NumPy section titles, and the rustdoc section each becomes
+SECTIONS = {
+    "args": "Arguments", "arguments": "Arguments", "parameters": "Arguments", "params": "Arguments",
+    "keyword args": "Arguments", "keyword arguments": "Arguments", "other parameters": "Arguments",
+    "returns": "Returns", "return": "Returns", "yields": "Returns", "yield": "Returns",
+    "raises": "Errors", "raise": "Errors", "except": "Errors", "exceptions": "Errors",
+    "examples": "Examples", "example": "Examples",
+    "note": "Notes", "notes": "Notes", "warning": "Warning", "warnings": "Warning",
+    "attributes": "Fields", "see also": "See also", "references": "References",
+}
+# Sections listing names, each with its description
+LISTS = ("Arguments", "Errors", "Fields")
+GOOGLE_HEADER = re.compile(r"^(?P<title>[A-Za-z][A-Za-z ]*):\s*$")
+NUMPY_UNDERLINE = re.compile(r"^-{3,}\s*$")
+REST_FIELD = re.compile(r"^:(?P<field>\w+)(?:\s+(?P<argument>[^:]+?))?:\s*(?P<text>.*)$")
+GOOGLE_ENTRY = re.compile(r"^\*{0,2}(?P<name>[\w.]+)\s*(\([^)]*\))?\s*:\s*(?P<text>.*)$")
+NUMPY_ENTRY = re.compile(r"^\*{0,2}(?P<name>[\w.]+)\s*(:\s*.*)?$")
+TYPED_TEXT = re.compile(r"^[\w.]+(\[[^\]]*\])?:\s+(?P<text>.+)$")
+REST_PARAMETERS = {"param", "parameter", "arg", "argument", "key", "keyword"}
+REST_IGNORED = {"type", "rtype", "vartype", "ytype"}
+
+
+@dataclass
+class DocumentedSymbol:
+    """A module, function, class or method with its docstring as rustdoc markdown."""
+    qualname: str
+    kind: str  # module, function, class, method
+    rustdoc: str
+    symbol: Optional[PythonSymbol] = None
+
+
+def _indent(line: str) -> int:
+    return len(line) - len(line.lstrip())
+
+
+def _joined(lines: List[str]) -> str:
+    return " ".join(line.strip() for line in lines if line.strip())
+
+
+def _entries(lines: List[str], numpy: bool) -> List[Tuple[str, str]]:
+    """The names and descriptions of a Google or NumPy list section, whose entries start at
+    its least indented lines."""
+    body = [line for line in lines if line.strip()]
+    if not body:
+        return []
+    margin = min(_indent(line) for line in body)
+    entries: List[Tuple[str, List[str]]] = []
+    for line in body:
+        if _indent(line) == margin:
+            match = (NUMPY_ENTRY if numpy else GOOGLE_ENTRY).match(line.strip())
+            if match:
+                text = "" if numpy else match.group("text")
+                entries.append((match.group("name"), [text]))
+                continue
+        if entries:
+            entries[-1][1].append(line)
+    return [(name, _joined(text)) for name, text in entries]
+
+
+def _returns(lines: List[str], numpy: bool) -> List[str]:
+    """A return section without the types, which the Rust signature gives."""
+    body = [line for line in lines if line.strip()]
+    if numpy:
+        margin = min((_indent(line) for line in body), default=0)
+        body = [line for line in body if _indent(line) > margin] or body
+        return [_joined(body)]
+    text = _joined(body)
+    match = TYPED_TEXT.match(text)
+    return [match.group("text") if match else text]
+
+
+def _section(title: str, lines: List[str], numpy: bool) -> List[str]:
+    if title in LISTS:
+        return [f"* `{name}` - {text}".rstrip(" -") for name, text in _entries(lines, numpy)]
+    if title == "Returns":
+        return _returns(lines, numpy)
+    if title == "Examples":
+        # Python examples are not Rust doctests
+        return ["```text", *inspect.cleandoc("\n".join(lines)).splitlines(), "```"]
+    return inspect.cleandoc("\n".join(lines)).splitlines()
+
+
+def _rest_fields(lines: List[str]) -> Tuple[List[str], List[Tuple[str, str, str]]]:
+    """The description of a reST docstring and its :param:, :returns: and :raises: fields."""
+    description: List[str] = []
+    fields: List[Tuple[str, str, List[str]]] = []
+    for line in lines:
+        match = REST_FIELD.match(line.strip()) if _indent(line) == 0 else None
+        if match:
+            argument = (match.group("argument") or "").split()
+            fields.append((match.group("field"), argument[-1] if argument else "",
+                           [match.group("text")]))
+        elif fields and line.strip():
+            fields[-1][2].append(line)
+        elif not fields:
+            description.append(line)
+    return description, [(field, argument, _joined(text)) for field, argument, text in fields]
+
+
+def rustdoc(docstring: str) -> str:
+    """A Python docstring as rustdoc markdown, with its Google, NumPy or reST parameter, return,
+    exception and example sections as Arguments, Returns, Errors and Examples sections."""
+    lines = inspect.cleandoc(docstring).splitlines()
+    description, fields = _rest_fields(lines)
+    sections: List[Tuple[str, List[str]]] = []
+    current: Optional[Tuple[str, List[str], bool]] = None
+    text: List[str] = []
+    index = 0
+    while index < len(description):
+        line = description[index]
+        google = GOOGLE_HEADER.match(line) if _indent(line) == 0 else None
+        numpy = (_indent(line) == 0 and index + 1 < len(description)
+                 and NUMPY_UNDERLINE.match(description[index + 1]) is not None)
+        title = (google.group("title") if google else line.strip() if numpy else "").lower()
+        if title in SECTIONS:
+            if current is not None:
+                sections.append((current[0], _section(*current)))
+            current = (SECTIONS[title], [], bool(numpy))
+            index += 2 if numpy else 1
+            continue
+        if current is not None:
+            current[1].append(line)
+        else:
+            text.append(line)
+        index += 1
+    if current is not None:
+        sections.append((current[0], _section(*current)))
+
+    for field, argument, field_text in fields:
+        if field in REST_PARAMETERS:
+            sections.append(("Arguments", [f"* `{argument}` - {field_text}"]))
+        elif field in ("returns", "return", "yields", "yield"):
+            sections.append(("Returns", [field_text]))
+        elif field in ("raises", "raise", "except", "exception"):
+            sections.append(("Errors", [f"* `{argument}` - {field_text}"]))
+        elif field not in REST_IGNORED:
+            sections.append(("Notes", [f"{field}: {field_text}"]))
+
+    # Sections of a title given twice, or in fields and sections, are merged
+    merged: Dict[str, List[str]] = {}
+    for title, body in sections:
+        if title in LISTS:
+            body = [line for line in body if line.strip() and not line.startswith("* `self`")]
+        if any(line.strip() for line in body):
+            merged.setdefault(title, []).extend(body)
+
+    parts = ["\n".join(text).strip()]
+    parts.extend(f"# {title}\n\n" + "\n".join(body).strip() for title, body in merged.items())
+    return "\n\n".join(part for part in parts if part)
+
+
+def doc_comment(markdown: str, prefix: str = "///") -> str:
+    """Markdown as Rust doc comment lines."""
+    return "\n".join(f"{prefix} {line}".rstrip() for line in markdown.splitlines())
+
+
+def documented_symbols(python_code: str) -> List[DocumentedSymbol]:
+    """The module, functions, classes and methods of a module with a docstring, as rustdoc."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    documented = []
+    docstring = ast.get_docstring(tree)
+    if docstring:
+        documented.append(DocumentedSymbol("<module>", "module", rustdoc(docstring)))
+    nodes = {node.lineno: node for node in ast.walk(tree)
+             if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef))}
+    for symbol in collect_symbols(python_code):
+        node = nodes.get(symbol.start_line)
+        docstring = ast.get_docstring(node) if node is not None else None
+        if docstring:
+            documented.append(DocumentedSymbol(symbol.qualname, symbol.kind,
+                                               rustdoc(docstring), symbol))
+    return documented
@@ -0,0 +1,84 @@
+import ast
+import doctest
+import re
+from dataclasses import dataclass, field
+from typing import List
+
+from .docstrings import documented_symbols
+
+# The exception a traceback ends with, e.g. "ValueError: negative amount"
+EXCEPTION = re.compile(r"^(?P<name>[\w.]+)(:|$)", re.MULTILINE)
+
+
+@dataclass
+class Doctest:
+    """An example of a docstring with an expected result, and the examples setting it up."""
+    qualname: str  # of the documented definition, "<module>" for the module docstring
+    line: int  # of the example in the module
+    source: str
+    want: str
+    kind: str  # value, output or error
+    # earlier examples of the docstring without a result
+    setup: List[str] = field(default_factory=list)
+
+    @property
+    def exception(self) -> str:
+        """The exception an error example raises."""
+        names = [match.group("name") for match in EXCEPTION.finditer(self.want)]
+        return names[-1] if names else "Exception"
+
+
+def _kind(source: str, want: str) -> str:
+    if want.startswith("Traceback (most recent call last)"):
+        return "error"
+    try:
+        ast.parse(source.strip(), mode="eval")
+        ast.literal_eval(want.strip())
+    except (SyntaxError, ValueError, TypeError, MemoryError, RecursionError):
+        return "output"
+    return "value"
+
+
+def doctest_name(qualname: str, index: int) -> str:
+    """The Rust test translating the index-th (from 1) doctest of a definition."""
+    name = "module" if qualname == "<module>" else re.sub(r"(?<=[a-z0-9])(?=[A-Z])", "_", qualname)
+    name = re.sub(r"\W+", "_", name).strip("_").lower()
+    return f"doctest_{name}_{index}"
+
+
+def python_doctests(python_code: str) -> List[Doctest]:
+    """The doctests of the module's docstrings, in order; an example printing nothing sets up
+    the ones after it in the same docstring."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    docstrings = {}
+    for node in ast.walk(tree):
+        if (isinstance(node, (ast.Module, ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef))
+                and node.body):
+            first = node.body[0]
+            if (isinstance(first, ast.Expr) and isinstance(first.value, ast.Constant)
+                    and isinstance(first.value.value, str)):
+                docstrings[0 if isinstance(node, ast.Module) else node.lineno] = first
+    parser = doctest.DocTestParser()
+    doctests = []
+    for symbol in documented_symbols(python_code):
+        node = docstrings.get(symbol.symbol.start_line if symbol.symbol else 0)
+        if node is None:
+            continue
+        setup: List[str] = []
+        try:
+            examples = parser.get_examples(node.value.value)
+        except ValueError:
+            continue
+        for example in examples:
+            source = example.source.rstrip("\n")
+            if not example.want and example.exc_msg is None:
+                setup.append(source)
+                continue
+            want = example.want.rstrip("\n")
+            # Docstring lines count from the line the string starts on
+            doctests.append(Doctest(symbol.qualname, node.lineno + example.lineno, source, want,
+                                    _kind(source, want), list(setup)))
+    return doctests
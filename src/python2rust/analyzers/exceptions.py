@@ -0,0 +1,258 @@
+import ast
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+# Built-in exceptions the project raises or catches, and the variant of the error enum they become
+BUILTIN_VARIANTS = {
+    "ValueError": "Value", "TypeError": "Type", "KeyError": "Key", "IndexError": "Index",
+    "LookupError": "Lookup", "RuntimeError": "Runtime", "NotImplementedError": "NotImplemented",
+    "AssertionError": "Assertion", "ZeroDivisionError": "ZeroDivision", "OverflowError": "Overflow",
+    "TimeoutError": "Timeout", "UnicodeError": "Utf8", "UnicodeDecodeError": "Utf8",
+    "OSError": "Io", "IOError": "Io", "FileNotFoundError": "Io", "PermissionError": "Io",
+    "ConnectionError": "Io", "EOFError": "Io",
+}
+CATCH_ALL = ["Exception", "BaseException"]
+# The crate of the error module, in shared/ under the target directory, out of the way of the crates
+# of the files
+ERRORS_CRATE = "errors"
+ERRORS_DIR = Path("shared") / ERRORS_CRATE
+# Rust field types of annotated exception arguments, the others being Strings
+FIELD_TYPES = {"int": "i64", "float": "f64", "bool": "bool", "str": "String"}
+
+
+@dataclass
+class ExceptionClass:
+    """An exception class of the project, with the fields and message of its Rust error."""
+    name: str
+    module: str
+    line: int
+    bases: List[str] = field(default_factory=list)
+    fields: List[Tuple[str, str]] = field(default_factory=list)  # __init__ arguments and Rust types
+    message: Optional[str] = None  # thiserror format of the message __init__ passes to its base
+    parent: Optional[str] = None  # the project exception it derives from, if any
+    children: List[str] = field(default_factory=list)
+    raised: int = 0  # raise sites across the project
+
+    def describe(self) -> str:
+        item = (f"enum {self.name} with a variant per subclass" if self.children
+                else f"struct {self.name}")
+        parent = f", converted into {self.parent}" if self.parent else ", a variant of Error"
+        raised = f"raised at {self.raised} site{'' if self.raised == 1 else 's'}"
+        return f"{self.module}.{self.name} (line {self.line}) -> {item}{parent}, {raised}"
+
+
+@dataclass
+class ExceptionSite:
+    """A raise statement or an except clause of a module."""
+    line: int
+    kind: str  # raise or except
+    exceptions: List[str]  # raised or caught
+    message: Optional[str] = None  # of a raise, as written
+
+
+def _name(node: Optional[ast.AST]) -> Optional[str]:
+    if isinstance(node, ast.Call):
+        node = node.func
+    if isinstance(node, ast.Attribute):
+        return node.attr
+    return node.id if isinstance(node, ast.Name) else None
+
+
+def _is_exception_name(name: str) -> bool:
+    return name in CATCH_ALL or name in BUILTIN_VARIANTS or name.endswith(("Error", "Exception"))
+
+
+def exception_sites(python_code: str) -> List[ExceptionSite]:
+    """The raise statements and except clauses of a module, in order."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    sites = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Raise) and node.exc is not None and _name(node.exc):
+            message = None
+            if isinstance(node.exc, ast.Call) and node.exc.args:
+                message = ast.unparse(node.exc.args[0])
+            sites.append(ExceptionSite(node.lineno, "raise", [_name(node.exc)], message))
+        elif isinstance(node, ast.ExceptHandler):
+            caught = node.type.elts if isinstance(node.type, ast.Tuple) else [node.type]
+            names = [name
+                     for name in (_name(target) for target in caught if target is not None) if name]
+            sites.append(ExceptionSite(node.lineno, "except", names or ["BaseException"]))
+    return sorted(sites, key=lambda site: site.line)
+
+
+def _message_format(init: ast.FunctionDef, arguments: List[str]) -> Optional[str]:
+    """The message __init__ passes to its base, as a thiserror format of its arguments."""
+    for node in ast.walk(init):
+        if not (isinstance(node, ast.Call) and isinstance(node.func, ast.Attribute)
+                and node.func.attr == "__init__" and node.args):
+            continue
+        message = node.args[0]
+        if isinstance(message, ast.Constant) and isinstance(message.value, str):
+            return message.value.replace("{", "{{").replace("}", "}}")
+        if isinstance(message, ast.Name) and message.id in arguments:
+            return f"{{{message.id}}}"
+        if isinstance(message, ast.JoinedStr):
+            parts = []
+            for value in message.values:
+                if isinstance(value, ast.Constant):
+                    parts.append(str(value.value).replace("{", "{{").replace("}", "}}"))
+                elif (isinstance(value, ast.FormattedValue) and isinstance(value.value, ast.Name)
+                      and value.value.id in arguments and value.conversion == -1
+                      and not value.format_spec):
+                    parts.append(f"{{{value.value.id}}}")
+                else:
+                    return None
+            return "".join(parts)
+    return None
+
+
+def _fields(node: ast.ClassDef) -> Tuple[List[Tuple[str, str]], Optional[str]]:
+    init = next((statement for statement in node.body
+                 if isinstance(statement, ast.FunctionDef) and statement.name == "__init__"), None)
+    if init is None:
+        return [], None
+    arguments = [argument for argument in init.args.args[1:]]
+    fields = [(argument.arg, FIELD_TYPES.get(_name(argument.annotation) or "", "String"))
+              for argument in arguments]
+    return fields, _message_format(init, [argument.arg for argument in arguments])
+
+
+class ExceptionHierarchy:
+    """The exception classes of a project and the error module translating them: a thiserror
+    enum per exception with subclasses, a struct per other exception, and an Error enum with a
+    variant per root exception and per built-in exception the project raises or catches."""
+
+    def __init__(self, sources: Dict[str, str]):
+        self.classes: Dict[str, ExceptionClass] = {}
+        self.builtins: List[str] = []
+        sites: List[ExceptionSite] = []
+        for module, python_code in sources.items():
+            try:
+                tree = ast.parse(python_code)
+            except SyntaxError:
+                continue
+            for node in ast.walk(tree):
+                if isinstance(node, ast.ClassDef) and node.name not in self.classes:
+                    bases = [name for name in map(_name, node.bases) if name]
+                    fields, message = _fields(node)
+                    self.classes[node.name] = ExceptionClass(
+                        node.name, module, node.lineno, bases, fields, message)
+            sites.extend(exception_sites(python_code))
+
+        # Only classes deriving from an exception, directly or through the
+        # project's classes, are kept
+        def is_exception(name: str, seen: Tuple[str, ...] = ()) -> bool:
+            return any(_is_exception_name(base) or (base in self.classes and base not in seen
+                                                    and is_exception(base, (*seen, name)))
+                       for base in self.classes[name].bases)
+
+        self.classes = {name: exception for name, exception in self.classes.items()
+                        if is_exception(name)}
+        for exception in self.classes.values():
+            exception.parent = next((base for base in exception.bases if base in self.classes),
+                                    None)
+            if exception.parent:
+                self.classes[exception.parent].children.append(exception.name)
+        for site in sites:
+            for name in site.exceptions:
+                if name in self.classes and site.kind == "raise":
+                    self.classes[name].raised += 1
+                variant = BUILTIN_VARIANTS.get(name)
+                if name not in self.classes and variant and variant not in self.builtins:
+                    self.builtins.append(variant)
+        self._raised_directly = {name for site in sites if site.kind == "raise"
+                                 for name in site.exceptions}
+
+    def ancestors(self, name: str) -> List[str]:
+        """The project exceptions above one, from its root down to its parent."""
+        chain, parent = [], self.classes[name].parent
+        while parent is not None and parent not in chain:
+            chain.insert(0, parent)
+            parent = self.classes[parent].parent
+        return chain
+
+    def pattern(self, name: str) -> str:
+        """The pattern matching an exception in an error::Error, as an except clause catches it."""
+        if name in BUILTIN_VARIANTS:
+            return f"error::Error::{BUILTIN_VARIANTS[name]}(_)"
+        if name not in self.classes:
+            return "_"
+        chain = ["Error", *self.ancestors(name), name]
+        pattern = "_"
+        for enum, variant in reversed(list(zip(chain[:-1], chain[1:]))):
+            pattern = f"error::{enum}::{variant}({pattern})"
+        return pattern
+
+    def rust_module(self) -> str:
+        """The error module of the project, the lib.rs of the errors crate its crates depend on."""
+        items = []
+        for exception in self.classes.values():
+            derive = "#[derive(Debug, thiserror::Error)]"
+            if exception.children:
+                variants = [f"    #[error(transparent)]\n    {child}(#[from] {child}),"
+                            for child in exception.children]
+                if exception.name in self._raised_directly:
+                    variants.append('    #[error("{0}")]\n    Message(String),')
+                items.append(f"{derive}\npub enum {exception.name} {{\n"
+                             + "\n".join(variants) + "\n}")
+            elif exception.fields and exception.message is not None:
+                fields = "\n".join(f"    pub {name}: {rust_type},"
+                                   for name, rust_type in exception.fields)
+                items.append(f'{derive}\n#[error("{exception.message}")]\n'
+                             f'pub struct {exception.name} {{\n'
+                             f"{fields}\n}}")
+            else:
+                items.append(f'{derive}\n#[error("{{0}}")]\n'
+                             f'pub struct {exception.name}(pub String);')
+
+        roots = [exception.name for exception in self.classes.values() if exception.parent is None]
+        variants = [f"    #[error(transparent)]\n    {root}(#[from] {root})," for root in roots]
+        for variant in self.builtins:
+            if variant == "Io":
+                variants.append("    #[error(transparent)]\n    Io(#[from] std::io::Error),")
+            else:
+                variants.append(f'    #[error("{{0}}")]\n    {variant}(String),')
+        items.append("#[derive(Debug, thiserror::Error)]\npub enum Error {\n"
+                     + "\n".join(variants) + "\n}")
+
+        # thiserror converts a child into its parent; the conversions skipping
+        # levels are written out
+        for exception in self.classes.values():
+            chain = ["Error", *self.ancestors(exception.name)]
+            for enum, next_level in zip(chain[:-1], chain[1:]):
+                items.append(f"impl From<{exception.name}> for {enum} {{\n"
+                             f"    fn from(error: {exception.name}) -> Self {{\n"
+                             f"        {enum}::{next_level}(error.into())\n    }}\n}}")
+        items.append("pub type Result<T> = std::result::Result<T, Error>;")
+        return ("//! Errors of the project, mirroring its exception classes; "
+                "generated by python2rust.\n\n"
+                + "\n\n".join(items) + "\n")
+
+    def __bool__(self) -> bool:
+        return bool(self.classes or self.builtins)
+
+
+
+def write_error_crate(target_dir: Path, hierarchy: ExceptionHierarchy) -> Path:
+    """Write the errors crate of the project into <target_dir>/shared/errors, its lib.rs being the
+    error module, and return its directory."""
+    directory = Path(target_dir) / ERRORS_DIR
+    (directory / "src").mkdir(parents=True, exist_ok=True)
+    (directory / "Cargo.toml").write_text(f'[package]\nname = "{ERRORS_CRATE}"\nversion = "0.1.0"\n'
+                                          'edition = "2021"\n\n[dependencies]\nthiserror = "1"\n')
+    (directory / "src" / "lib.rs").write_text(hierarchy.rust_module())
+    return directory
+
+
+def write_error_module(output_dir: Path) -> Path:
+    """Write the src/error.rs of a crate, which its `mod error;` declares: the errors crate
+    re-exported, so that every crate of the project has the same error types."""
+    path = Path(output_dir) / "src" / "error.rs"
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text("//! The project's error module, shared by its crates.\n\n"
+                    f"pub use {ERRORS_CRATE}::*;\n")
+    return path
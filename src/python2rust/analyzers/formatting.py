@@ -0,0 +1,240 @@
+import ast
+import re
+import string
+from dataclasses import dataclass, field
+from typing import Any, List, Optional, Tuple
+
+# [[fill]align][sign][z][#][0][width][grouping][.precision][type] of the format spec mini-language
+FORMAT_SPEC = re.compile(
+    r"^(?:(?P<fill>.)?(?P<align>[<>=^]))?(?P<sign>[-+ ])?(?P<z>z)?(?P<alt>#)?(?P<zero>0)?"
+    r"(?P<width>\d+)?(?P<grouping>[,_])?(?:\.(?P<precision>\d+))?(?P<type>[bcdeEfFgGnosxX%])?$",
+    re.DOTALL)
+# %[(key)][flags][width][.precision][length]type of printf-style formatting
+PERCENT = re.compile(
+    r"%(?:\((?P<key>[^)]*)\))?(?P<flags>[-#0 +]*)(?P<width>\*|\d+)?(?:\.(?P<precision>\*|"
+    r"\d+))?[hlL]?(?P<type>[diouxXeEfFgGcrsa%])")
+
+# Representative values, as Python values and Rust literals, of each kind of formatted value
+FLOATS = [(3.14159, "3.14159_f64"), (-2.5, "-2.5_f64"), (1234567.891, "1234567.891_f64")]
+INTEGERS = [(7, "7_i64"), (-42, "-42_i64"), (1234567, "1234567_i64")]
+UNSIGNED = [(7, "7_u64"), (255, "255_u64")]
+CODE_POINTS = [(65, "65_u32"), (233, "233_u32")]
+STRINGS = [("abc", '"abc"'), ("it's", '"it\'s"')]
+# What Rust's formatting cannot reproduce, so that a helper has to
+HELPERS = {
+    "repr": "Python's repr, quoting strings with single quotes where Rust's {:?} uses double ones",
+    "ascii": "Python's ascii(), a repr escaping non-ASCII characters",
+    "exponent": "Python's exponent notation, with a sign and at least two digits (1.5e+00)",
+    "general": "Python's general format g, switching to exponent notation and "
+               "dropping trailing zeros",
+    "percent": "a percentage: the value times 100 in fixed notation, followed by %",
+    "grouping": "thousands separators",
+    "space": "a space before positive numbers",
+    "sign_aware": "padding between the sign and the digits",
+    "upper_prefix": "the upper-case 0X prefix of Python's #X",
+    "alternate": "the alternate form of a float, keeping its decimal point",
+    "negative_zero": "the z option, making negative zero positive",
+    "dynamic": "a spec computed at run time, unless a named `width$` or `prec$` "
+               "argument of format! does",
+}
+
+
+@dataclass
+class FormatConversion:
+    """A formatting directive of the module and the Rust formatting reproducing it."""
+    line: int
+    python: str  # as written, e.g. {total:>8.3f} or %05d
+    style: str  # f-string, format or percent
+    rust: Optional[str] = None  # the Rust placeholder, None when a helper has to produce the text
+    helpers: List[str] = field(default_factory=list)  # what Rust's formatting cannot reproduce
+    note: Optional[str] = None  # about the value formatted, e.g. converting a code point to a char
+    cases: List[Tuple[str, str]] = field(default_factory=list)  # Rust literal, Python's output
+
+    def describe(self) -> str:
+        if self.rust is None:
+            target = "a helper reproducing " + "; ".join(HELPERS[helper] for helper in self.helpers)
+        else:
+            target = self.rust + (f" ({self.note})" if self.note else "")
+        return f"line {self.line}: {self.python} -> {target}"
+
+
+def _samples(kind: Optional[str], conversion: Optional[str], numeric: bool = False,
+             precision: bool = False) -> List[Tuple[Any, str]]:
+    if kind is None and precision and conversion is None:
+        return FLOATS + STRINGS  # significant digits of a float, truncation of a string
+    if conversion in ("r", "a") or kind in ("s", "r", "a") or (kind is None and not numeric):
+        return STRINGS
+    if kind is None or kind in "dn":
+        return INTEGERS
+    if kind in "eEfFgG%":
+        return FLOATS
+    return CODE_POINTS if kind == "c" else UNSIGNED
+
+
+def convert_format_spec(spec: str,
+                        conversion: Optional[str] = None) -> Tuple[Optional[str], List[
+                            str], Optional[str]]:
+    """The Rust placeholder of a replacement field of str.format or an f-string, with what a
+    helper has to reproduce when Rust's formatting cannot, and a note about the value."""
+    helpers = {"r": ["repr"], "a": ["ascii"]}.get(conversion or "", [])
+    match = FORMAT_SPEC.match(spec)
+    if match is None:
+        return None, helpers + ["dynamic"], None
+    fill, align, sign, kind = match["fill"], match["align"], match["sign"], match["type"]
+    rust = ""
+    if align == "=":
+        if fill != "0":
+            helpers.append("sign_aware")
+    elif align:
+        rust += (fill or "") + align
+    elif kind == "c" and match["width"]:
+        rust += ">"  # Python right-aligns the character of a code point, Rust left-aligns chars
+    if sign == "+":
+        rust += "+"
+    elif sign == " ":
+        helpers.append("space")
+    if match["z"]:
+        helpers.append("negative_zero")
+    if match["alt"]:
+        if kind == "X":
+            helpers.append("upper_prefix")
+        elif kind in ("x", "o", "b"):
+            rust += "#"
+        else:
+            helpers.append("alternate")
+    if match["zero"] or (align == "=" and fill == "0"):
+        rust += "0"
+    rust += match["width"] or ""
+    if match["grouping"]:
+        helpers.append("grouping")
+    precision = match["precision"]
+    if kind in ("f", "F"):
+        precision = precision or "6"
+    if precision:
+        rust += f".{precision}"
+    if precision and kind is None and not conversion:
+        # Without a type, Python gives a float that many significant digits, which Rust's
+        # {:.N} takes as decimals; only strings, truncated, come out the same
+        helpers.append("general")
+    helpers.extend({"e": ["exponent"], "E": ["exponent"], "g": ["general"], "G": ["general"],
+                    "n": ["general"], "%": ["percent"]}.get(kind or "", []))
+    if kind in ("x", "X", "o", "b"):
+        rust += kind
+    note = "of char::from_u32(value)" if kind == "c" else None
+    if precision and kind is None and not conversion:
+        note = f"a string is truncated to {precision} characters, as {{:.{precision}}} does"
+    if kind in ("x", "X", "o", "b"):
+        note = "of the absolute value after a '-' when negative, Rust printing the two's complement"
+    if helpers:
+        return None, helpers, note
+    return "{" + (":" + rust if rust else "") + "}", [], note
+
+
+def convert_percent(directive: str) -> Tuple[Optional[str], List[str], Optional[str]]:
+    """The Rust placeholder of a printf-style directive, as convert_format_spec."""
+    match = PERCENT.fullmatch(directive)
+    if match is None:
+        return None, ["dynamic"], None
+    flags, kind = match["flags"], match["type"]
+    if "*" in (match["width"] or "") + (match["precision"] or ""):
+        return None, ["dynamic"], None
+    spec = ""
+    if "-" in flags:
+        spec += "<"
+    elif kind in ("s", "r", "a") and match["width"]:
+        spec += ">"  # % right-aligns strings, where Rust and format() left-align them
+    spec += "+" if "+" in flags else " " if " " in flags else ""
+    spec += "#" if "#" in flags else ""
+    spec += "0" if "0" in flags and "-" not in flags else ""
+    spec += match["width"] or ""
+    if match["precision"] is not None:
+        spec += f".{match['precision']}"
+    conversion = kind if kind in ("r", "a") else None
+    python_kind = {"i": "d", "u": "d", "r": "s", "a": "s"}.get(kind, kind)
+    return convert_format_spec(spec + python_kind, conversion)
+
+
+def _cases(render: Any, kind: Optional[str], conversion: Optional[str],
+           numeric: bool = False, precision: bool = False) -> List[Tuple[str, str]]:
+    cases = []
+    for value, literal in _samples(kind, conversion, numeric, precision):
+        try:
+            cases.append((literal, render(value)))
+        except (ValueError, TypeError, OverflowError):
+            continue
+    return cases
+
+
+def _field_conversion(line: int, python: str, style: str, spec: str,
+                      conversion: Optional[str]) -> FormatConversion:
+    rust, helpers, note = convert_format_spec(spec, conversion)
+    match = FORMAT_SPEC.match(spec)
+    kind = match["type"] if match else None
+    converters = {"r": repr, "a": ascii}
+
+    def render(value: Any) -> str:
+        return format(converters.get(conversion or "", lambda same: same)(value), spec)
+
+    # Without a type, signs, zero padding and grouping only apply to numbers
+    numeric = bool(match and (match["sign"] or match["zero"] or match["grouping"]
+                              or match["align"] == "="))
+    precision = bool(match and match["precision"])
+    cases = _cases(render, kind, conversion, numeric, precision) if match else []
+    return FormatConversion(line, python, style, rust, helpers, note, cases)
+
+
+def format_conversions(python_code: str) -> List[FormatConversion]:
+    """The formatting directives of a module with a format spec or a conversion (`{x:>8.3f}`,
+    `{name!r}`, `"%05d" % n`), each converted once, with Python's output for representative
+    values the generated tests compare the Rust formatting against."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    found = {}
+    for node in ast.walk(tree):
+        if isinstance(node, ast.JoinedStr):
+            for value in node.values:
+                if not isinstance(value, ast.FormattedValue):
+                    continue
+                conversion = chr(value.conversion) if value.conversion != -1 else None
+                if value.format_spec is not None and not all(
+                        isinstance(part, ast.Constant) for part in value.format_spec.values):
+                    spec = None
+                else:
+                    parts = value.format_spec.values if value.format_spec else []
+                    spec = "".join(part.value for part in parts)
+                if spec == "" and not conversion:
+                    continue
+                python = "{" + ast.unparse(value.value) + (f"!{conversion}"
+                                                           if conversion else "") + \
+                         (f":{spec}" if spec else ":{...}" if spec is None else "") + "}"
+                found.setdefault(python, _field_conversion(
+                    node.lineno, python, "f-string", "{}" if spec is None else spec, conversion))
+        elif (isinstance(node, ast.Call) and isinstance(node.func, ast.Attribute)
+              and node.func.attr == "format" and isinstance(node.func.value, ast.Constant)
+              and isinstance(node.func.value.value, str)):
+            try:
+                fields = list(string.Formatter().parse(node.func.value.value))
+            except ValueError:
+                continue
+            for _, name, spec, conversion in fields:
+                if name is None or not (spec or conversion):
+                    continue
+                python = "{" + name + (f"!{conversion}" if conversion else "") + \
+                         (f":{spec}" if spec else "") + "}"
+                found.setdefault(python,
+                                 _field_conversion(node.lineno, python, "format", spec, conversion))
+        elif (isinstance(node, ast.BinOp) and isinstance(node.op, ast.Mod)
+              and isinstance(node.left, ast.Constant) and isinstance(node.left.value, str)):
+            for match in PERCENT.finditer(node.left.value):
+                directive = match.group(0)
+                if directive in ("%s", "%d", "%i", "%%") or directive in found:
+                    continue
+                rust, helpers, note = convert_percent(directive)
+                plain = re.sub(r"\([^)]*\)", "", directive)
+                found[directive] = FormatConversion(
+                    node.lineno, directive, "percent", rust, helpers, note,
+                    _cases(lambda value: plain % value, match["type"], None)
+                    if "*" not in plain else [])
+    return sorted(found.values(), key=lambda conversion: conversion.line)
@@ -0,0 +1,156 @@
+import ast
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple
+
+RUST_INTEGERS = {
+    "i8": (-2 ** 7, 2 ** 7 - 1), "i16": (-2 ** 15, 2 ** 15 - 1), "i32": (-2 ** 31, 2 ** 31 - 1),
+    "i64": (-2 ** 63, 2 ** 63 - 1), "isize": (-2 ** 63, 2 ** 63 - 1),
+    "u8": (0, 2 ** 8 - 1), "u16": (0, 2 ** 16 - 1), "u32": (0, 2 ** 32 - 1),
+    "u64": (0, 2 ** 64 - 1), "usize": (0, 2 ** 64 - 1)
+}
+RUST_FLOATS = ("f32", "f64")
+
+FN_PATTERN = re.compile(r"^(?:pub(?:\([^)]*\))?\s+)?fn\s+(\w+)\s*\(")
+
+
+@dataclass
+class FuzzType:
+    """A parameter type inputs can be generated for: int, float, bool, str or list."""
+    kind: str
+    rust: str
+    low: int = 0
+    high: int = 0
+    item: Optional["FuzzType"] = None
+
+
+@dataclass
+class FuzzTarget:
+    """A Python function and its Rust translation, called with the same generated inputs."""
+    name: str
+    params: List[FuzzType]
+    returns: str = ""
+
+    @property
+    def single_precision(self) -> bool:
+        """Whether f32 values are involved, so results are only close to Python's f64 ones."""
+        return any("f32" in rust for rust in [self.returns, *(param.rust for param in self.params)])
+
+
+def _split(text: str) -> List[str]:
+    """Comma-separated parts of text, outside brackets."""
+    parts, depth, current = [], 0, ""
+    for char in text:
+        if char in "<([":
+            depth += 1
+        elif char in ">)]":
+            depth -= 1
+        if char == "," and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += char
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def parse_fuzz_type(rust: str) -> Optional[FuzzType]:
+    """The input type of a Rust parameter type, None when inputs cannot be written for it."""
+    rust = rust.strip()
+    inner = rust[1:].strip() if rust.startswith("&") and not rust.startswith("&mut") else rust
+    if inner in RUST_INTEGERS:
+        return FuzzType("int", rust, *RUST_INTEGERS[inner])
+    if inner in RUST_FLOATS:
+        return FuzzType("float", rust)
+    if inner == "bool":
+        return FuzzType("bool", rust)
+    if inner in ("str", "String"):
+        return FuzzType("str", rust)
+    match = re.fullmatch(r"Vec<(.+)>|\[(.+)\]", inner)
+    if match:
+        item = parse_fuzz_type(match.group(1) or match.group(2))
+        if item is not None and item.rust == item.rust.lstrip("&"):
+            return FuzzType("list", rust, item=item)
+    return None
+
+
+def printable(rust: str) -> bool:
+    """Whether the harness can print values of a Rust return type."""
+    rust = rust.strip()
+    if not rust or rust == "()":
+        return True
+    if "'" in rust or rust.startswith("&mut"):
+        return False
+    if rust.startswith("&"):
+        return printable(rust[1:])
+    if rust in RUST_INTEGERS or rust in RUST_FLOATS or rust in ("bool", "char", "str", "String"):
+        return True
+    if rust.startswith("(") and rust.endswith(")"):
+        return all(printable(part) for part in _split(rust[1:-1]))
+    match = re.fullmatch(r"(\w+(?:::\w+)*)<(.+)>|\[(.+)\]", rust)
+    if not match:
+        return False
+    if match.group(3):
+        return printable(match.group(3))
+    wrapper = match.group(1).split("::")[-1]
+    arguments = _split(match.group(2))
+    if wrapper == "Vec" or wrapper in ("Option", "Box"):
+        return len(arguments) == 1 and printable(arguments[0])
+    if wrapper == "Result":
+        # The error is printed with Debug
+        return printable(arguments[0])
+    if wrapper in ("HashMap", "BTreeMap"):
+        return len(arguments) == 2 and all(printable(argument) for argument in arguments)
+    return False
+
+
+def rust_functions(rust_code: str) -> Dict[str, Tuple[List[str], str]]:
+    """Top-level functions of Rust code: name -> (parameter types, return type)."""
+    functions = {}
+    lines = rust_code.splitlines()
+    depth = 0
+    for index, line in enumerate(lines):
+        match = FN_PATTERN.match(line) if depth == 0 else None
+        if match:
+            header = " ".join(part.strip() for part in lines[index:index + 10])
+            header = header[:header.find("{")] if "{" in header else header
+            signature = re.match(r".*?fn\s+\w+\s*\((.*)\)\s*(?:->\s*(.+?))?\s*(?:where\b.*)?$",
+                                 header)
+            if signature:
+                params = _split(signature.group(1))
+                types = [param.split(":", 1)[1].strip() if ":" in param else "" for param in params]
+                functions[match.group(1)] = (types, (signature.group(2) or "").strip())
+        depth += line.count("{") - line.count("}")
+    return functions
+
+
+def fuzz_targets(python_code: str, rust_code: str) -> List[FuzzTarget]:
+    """Top-level Python functions whose Rust translation takes parameters inputs
+    can be generated for.
+
+    Functions are paired by name and must take as many parameters in both languages;
+    generic functions and those returning borrowed or unprintable values are left out.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    functions = rust_functions(rust_code)
+    targets = []
+    for node in tree.body:
+        if (not isinstance(node, ast.FunctionDef) or node.name.startswith("_")
+                or node.name == "main"):
+            continue
+        arguments = node.args
+        if arguments.vararg or arguments.kwarg or arguments.kwonlyargs or arguments.posonlyargs:
+            continue
+        if node.name not in functions:
+            continue
+        types, returns = functions[node.name]
+        if not types or len(types) != len(arguments.args) or not printable(returns):
+            continue
+        params = [parse_fuzz_type(rust) for rust in types]
+        if all(param is not None for param in params):
+            targets.append(FuzzTarget(node.name, params, returns))
+    return targets
@@ -0,0 +1,107 @@
+import ast
+import re
+from dataclasses import dataclass, field
+from typing import List, Optional
+
+# Calls consuming a generator expression right away, which an iterator chain feeds directly
+CONSUMERS = {
+    "sum", "any", "all", "min", "max", "list", "tuple", "set", "frozenset",
+    "dict", "sorted", "join", "extend", "update", "Counter", "deque",
+}
+
+
+@dataclass
+class PythonGenerator:
+    """A generator function, or a generator expression, whose elements are produced lazily."""
+    qualname: str  # of the generator function, or of the function holding the expression
+    line: int
+    kind: str  # function or expression
+    infinite: bool = False  # a function yielding from a loop that never ends, as `while True`
+    delegates: List[str] = field(default_factory=list)  # what it yields from
+    sends: bool = False  # uses the value of yield, sent by its callers
+    consumer: Optional[str] = None  # of an expression consumed right away, e.g. sum
+    source: str = ""  # of an expression
+
+    @property
+    def test_name(self) -> str:
+        """The Rust test comparing the elements of a generator function with Python's."""
+        name = re.sub(r"(?<=[a-z0-9])(?=[A-Z])", "_", self.qualname)
+        name = re.sub(r"\W+", "_", name).strip("_").lower()
+        return f"generator_{name}_matches_python"
+
+    def describe(self) -> str:
+        if self.kind == "expression":
+            use = f"consumed by {self.consumer}" if self.consumer else "kept lazy"
+            return (f"line {self.line}: generator expression `{self.source}` in "
+                    f"{self.qualname} ({use})")
+        details = [detail for detail, found in [
+            ("infinite", self.infinite),
+            (f"yields from {', '.join(self.delegates)}", self.delegates),
+            ("receives sent values", self.sends)] if found]
+        described = f" ({', '.join(details)})" if details else ""
+        return f"line {self.line}: generator {self.qualname}{described}"
+
+
+def _own_nodes(function: ast.AST) -> List[ast.AST]:
+    """The nodes of a function outside the functions, lambdas and classes it defines."""
+    nodes, pending = [], list(ast.iter_child_nodes(function))
+    while pending:
+        node = pending.pop()
+        nodes.append(node)
+        if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.Lambda, ast.ClassDef)):
+            pending.extend(ast.iter_child_nodes(node))
+    return nodes
+
+
+def _is_infinite(function: ast.AST) -> bool:
+    for node in _own_nodes(function):
+        if (isinstance(node, ast.While) and isinstance(node.test, ast.Constant) and node.test.value
+                and not any(isinstance(inner, (ast.Break, ast.Return))
+                            for inner in ast.walk(node))):
+            return any(isinstance(inner, (ast.Yield, ast.YieldFrom)) for inner in ast.walk(node))
+    return False
+
+
+def _consumer(parent: Optional[ast.AST]) -> Optional[str]:
+    if not isinstance(parent, ast.Call):
+        return None
+    function = parent.func
+    name = function.attr if isinstance(function, ast.Attribute) else getattr(function, "id", None)
+    return name if name in CONSUMERS else None
+
+
+def python_generators(python_code: str) -> List[PythonGenerator]:
+    """The generator functions and generator expressions of a module, in order."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    parents = {child: node for node in ast.walk(tree) for child in ast.iter_child_nodes(node)}
+    found = []
+
+    def visit(node: ast.AST, prefix: str, scope: str) -> None:
+        for child in ast.iter_child_nodes(node):
+            if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)):
+                qualname = f"{prefix}{child.name}"
+                if not isinstance(child, ast.ClassDef):
+                    yields = sorted((inner for inner in _own_nodes(child)
+                                     if isinstance(inner, (ast.Yield, ast.YieldFrom))),
+                                    key=lambda inner: (inner.lineno, inner.col_offset))
+                    if yields:
+                        found.append(PythonGenerator(
+                            qualname, child.lineno, "function", _is_infinite(child),
+                            delegates=[ast.unparse(inner.value) for inner in yields
+                                       if isinstance(inner, ast.YieldFrom)],
+                            sends=any(isinstance(inner, ast.Yield)
+                                      and not isinstance(parents.get(inner), ast.Expr)
+                                      for inner in yields)))
+                visit(child, f"{qualname}.", qualname)
+                continue
+            if isinstance(child, ast.GeneratorExp):
+                found.append(PythonGenerator(
+                    scope, child.lineno, "expression", consumer=_consumer(parents.get(child)),
+                    source=ast.unparse(child)))
+            visit(child, prefix, scope)
+
+    visit(tree, "", "<module>")
+    return sorted(found, key=lambda generator: generator.line)
@@ -0,0 +1,98 @@
+import ast
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from .fuzz_targets import FuzzTarget, FuzzType, fuzz_targets
+
+# Iterations of the innermost loop the default inputs of a hot function aim for
+DEFAULT_WORK = 10 ** 4
+LOOPS = (ast.For, ast.AsyncFor, ast.While)
+COMPREHENSIONS = (ast.ListComp, ast.SetComp, ast.DictComp, ast.GeneratorExp)
+
+
+@dataclass
+class HotFunction:
+    """A performance-critical function, with the arguments of its measured call."""
+    target: FuzzTarget
+    args: List[Any] = field(default_factory=list)
+    reason: str = ""
+
+    @property
+    def name(self) -> str:
+        return self.target.name
+
+
+def loop_depth(node: ast.AST) -> int:
+    """Deepest nesting of loops and comprehensions in a function, nested functions left out."""
+    deepest = 0
+    for child in ast.iter_child_nodes(node):
+        if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef, ast.Lambda, ast.ClassDef)):
+            continue
+        depth = loop_depth(child)
+        if isinstance(child, LOOPS):
+            depth += 1
+        elif isinstance(child, COMPREHENSIONS):
+            # Each for clause of a comprehension loops inside the previous one
+            depth += len(child.generators)
+        deepest = max(deepest, depth)
+    return deepest
+
+
+def _recursive(function: ast.FunctionDef) -> bool:
+    return any(
+        isinstance(node, ast.Call) and isinstance(node.func, ast.Name)
+        and node.func.id == function.name
+        for node in ast.walk(function)
+    )
+
+
+def default_argument(kind: FuzzType, size: int) -> Any:
+    """An input of a parameter type scaled to size: the number itself, or as many items."""
+    if kind.kind == "int":
+        return max(kind.low, min(kind.high, size))
+    if kind.kind == "float":
+        return float(size)
+    if kind.kind == "bool":
+        return True
+    if kind.kind == "str":
+        return "a" * size
+    return [default_argument(kind.item, index) for index in range(size)]
+
+
+def hot_functions(
+    python_code: str,
+    rust_code: str,
+    configured: Optional[Dict[str, List[Any]]] = None,
+    min_loop_depth: int = 2
+) -> List[HotFunction]:
+    """Functions worth benchmarking whose translation the harness can call with written inputs.
+
+    A function is hot when configured, with the given arguments, or when its loops nest
+    min_loop_depth deep or it calls itself. Its default arguments are scaled so that
+    nested loops run about DEFAULT_WORK innermost iterations; recursive functions get small ones.
+    """
+    configured = configured or {}
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    functions = {node.name: node for node in tree.body if isinstance(node, ast.FunctionDef)}
+
+    hot = []
+    for target in fuzz_targets(python_code, rust_code):
+        function = functions[target.name]
+        if target.name in configured:
+            args = list(configured[target.name])
+            if len(args) == len(target.params):
+                hot.append(HotFunction(target, args, "configured"))
+            continue
+        depth = loop_depth(function)
+        if _recursive(function):
+            hot.append(HotFunction(target, [default_argument(param, 10) for param in target.params],
+                                   "recursive"))
+        elif depth >= min_loop_depth:
+            size = max(4, round(DEFAULT_WORK ** (1 / depth)))
+            hot.append(HotFunction(
+                target, [default_argument(param, size) for param in target.params],
+                f"{depth} nested loops"))
+    return hot
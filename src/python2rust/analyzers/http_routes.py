@@ -0,0 +1,79 @@
+import ast
+import re
+from dataclasses import dataclass, field
+from typing import List, Optional
+
+# Decorator methods registering a route for one HTTP method (FastAPI, Flask 2, Starlette routers)
+METHOD_DECORATORS = ("get", "post", "put", "patch", "delete")
+# <name>, <int:name> and {name} path segments
+PATH_PARAMETER = re.compile(r"<[^>]*>|\{[^}]*\}")
+
+
+@dataclass
+class HttpRoute:
+    """A route of a Python web app, with the HTTP methods it answers."""
+    path: str
+    methods: List[str] = field(default_factory=lambda: ["GET"])
+    function: str = ""
+
+    @property
+    def parameterized(self) -> bool:
+        """Whether the path takes parameters, which requests cannot be made up for."""
+        return bool(PATH_PARAMETER.search(self.path))
+
+
+@dataclass
+class WebApp:
+    """The module-level app object of a Python web app and its routes."""
+    name: str
+    routes: List[HttpRoute] = field(default_factory=list)
+
+
+def _route(decorator: ast.expr) -> Optional[tuple]:
+    """(app name, route) of a route decorator: @app.route(path, methods=[...]) or @app.get(path)."""
+    if not isinstance(decorator, ast.Call) or not isinstance(decorator.func, ast.Attribute):
+        return None
+    target = decorator.func.value
+    method = decorator.func.attr
+    if not isinstance(target, ast.Name) or method not in ("route", *METHOD_DECORATORS):
+        return None
+    path = decorator.args[0] if decorator.args else next(
+        (keyword.value for keyword in decorator.keywords if keyword.arg in ("rule", "path")), None)
+    if not isinstance(path, ast.Constant) or not isinstance(path.value, str):
+        return None
+    if method != "route":
+        return target.id, HttpRoute(path.value, [method.upper()])
+    methods = ["GET"]
+    for keyword in decorator.keywords:
+        if keyword.arg == "methods" and isinstance(keyword.value, (ast.List, ast.Tuple, ast.Set)):
+            methods = [
+                element.value.upper() for element in keyword.value.elts
+                if isinstance(element, ast.Constant) and isinstance(element.value, str)
+            ]
+    return target.id, HttpRoute(path.value, methods)
+
+
+def find_web_app(python_code: str) -> Optional[WebApp]:
+    """The app a module registers its routes on, None when it defines no routes.
+
+    Routes are read from Flask and FastAPI style decorators on top-level functions;
+    when routes hang on several objects, the one with the most routes is the app.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return None
+    apps: dict = {}
+    for node in tree.body:
+        if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            continue
+        for decorator in node.decorator_list:
+            found = _route(decorator)
+            if found is not None:
+                name, route = found
+                route.function = node.name
+                apps.setdefault(name, []).append(route)
+    if not apps:
+        return None
+    name = max(apps, key=lambda app: len(apps[app]))
+    return WebApp(name, apps[name])
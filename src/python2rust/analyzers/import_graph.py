@@ -0,0 +1,207 @@
+import ast
+import os
+from pathlib import Path
+from typing import Dict, List, Optional, Set
+
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def module_name(path: Path, root: Path) -> str:
+    """Dotted module name of a file relative to its source root."""
+    return ".".join(path.relative_to(root).with_suffix("").parts)
+
+
+class ImportGraph:
+    """Dependencies between the Python files of a migration, derived from their imports.
+
+    Only imports that resolve to another file of the migration become edges;
+    standard library and third-party imports are ignored. A package name resolves
+    to its __init__.py.
+    """
+
+    def __init__(self, files: List[Path], roots: Optional[Dict[Path, Path]] = None):
+        self.files = list(files)
+        roots = roots or {}
+        default_root = (Path(os.path.commonpath([str(f.parent) for f in self.files]))
+                        if self.files else Path("."))
+        self.roots = {f: roots.get(f, default_root) for f in self.files}
+        self.modules = {module_name(f, self.roots[f]): f for f in self.files}
+        self.names = {f: name for name, f in self.modules.items()}
+        self.edges: Dict[Path, Set[Path]] = {f: self._resolve_imports(f) for f in self.files}
+        self._order: Optional[List[Path]] = None
+        self._components: Optional[List[List[Path]]] = None
+
+    def _candidates(self, name: str, file: Path) -> List[str]:
+        """Names under which an absolute import could refer to one of our modules."""
+        candidates = [name]
+        root_name = self.roots[file].name
+        if name.startswith(f"{root_name}."):
+            candidates.append(name[len(root_name) + 1:])
+        return candidates
+
+    def _lookup(self, name: str, file: Path) -> Optional[Path]:
+        """Find the longest module prefix of a dotted name that is part of the migration."""
+        for candidate in self._candidates(name, file):
+            parts = candidate.split(".")
+            for end in range(len(parts), 0, -1):
+                prefix = ".".join(parts[:end])
+                target = self.modules.get(prefix) or self.modules.get(f"{prefix}.__init__")
+                if target is not None:
+                    return target
+        return None
+
+    def _resolve_imports(self, file: Path) -> Set[Path]:
+        try:
+            tree = ast.parse(file.read_text(), filename=str(file))
+        except (OSError, SyntaxError, ValueError) as e:
+            logger.warning(f"Could not parse imports of {file}: {e}")
+            return set()
+
+        package = self.names[file].split(".")[:-1]
+        dependencies = set()
+        for node in ast.walk(tree):
+            names: List[str] = []
+            if isinstance(node, ast.Import):
+                names = [alias.name for alias in node.names]
+            elif isinstance(node, ast.ImportFrom):
+                if node.level:
+                    base = package[:len(package) - (node.level - 1)] if node.level > 1 else package
+                    prefix = ".".join(base + ([node.module] if node.module else []))
+                else:
+                    prefix = node.module or ""
+                names = [f"{prefix}.{alias.name}".lstrip(".") for alias in node.names]
+            targets = {self._lookup(name, file) for name in names} - {None, file}
+            if isinstance(node, ast.ImportFrom) and not targets and prefix:
+                # Names of the package itself, such as those its __init__.py defines
+                targets = {self._lookup(prefix, file)} - {None, file}
+            dependencies.update(targets)
+        return dependencies
+
+    def dependencies(self, file: Path) -> List[Path]:
+        """Files imported by a file, in migration order."""
+        return [f for f in self.files if f in self.edges.get(file, set())]
+
+    def dependents(self, file: Path) -> List[Path]:
+        """Files that import a file."""
+        return [f for f in self.files if file in self.edges[f]]
+
+    def imported_from(self, files: List[Path]) -> Set[Path]:
+        """The files and those they import, directly or not."""
+        seen: Set[Path] = set()
+        pending = [file for file in files if file in self.edges]
+        while pending:
+            file = pending.pop()
+            if file not in seen:
+                seen.add(file)
+                pending.extend(self.edges[file])
+        return seen
+
+    def _strong_components(self) -> List[List[Path]]:
+        """Groups of files importing each other, directly or not (Tarjan's algorithm), each in
+        the original order of its files."""
+        if self._components is not None:
+            return self._components
+        position = {f: i for i, f in enumerate(self.files)}
+        index: Dict[Path, int] = {}
+        lowlink: Dict[Path, int] = {}
+        stack: List[Path] = []
+        on_stack: Set[Path] = set()
+        components: List[List[Path]] = []
+        for start in self.files:
+            if start in index:
+                continue
+            # Iterative depth-first search: (file, its dependencies left to visit)
+            work = [(start, iter(self.dependencies(start)))]
+            index[start] = lowlink[start] = len(index)
+            stack.append(start)
+            on_stack.add(start)
+            while work:
+                file, pending = work[-1]
+                dependency = next(pending, None)
+                if dependency is None:
+                    work.pop()
+                    if work:
+                        parent = work[-1][0]
+                        lowlink[parent] = min(lowlink[parent], lowlink[file])
+                    if lowlink[file] == index[file]:
+                        component = []
+                        while True:
+                            member = stack.pop()
+                            on_stack.discard(member)
+                            component.append(member)
+                            if member == file:
+                                break
+                        components.append(sorted(component, key=position.__getitem__))
+                elif dependency not in index:
+                    index[dependency] = lowlink[dependency] = len(index)
+                    stack.append(dependency)
+                    on_stack.add(dependency)
+                    work.append((dependency, iter(self.dependencies(dependency))))
+                elif dependency in on_stack:
+                    lowlink[file] = min(lowlink[file], index[dependency])
+        self._components = components
+        return components
+
+    def cycles(self) -> List[List[Path]]:
+        """Groups of files that import each other, in migration order; each file becomes its own
+        crate, and crates cannot depend on each other in a cycle."""
+        order = self.topological_order()
+        cycles = [component for component in self._strong_components() if len(component) > 1]
+        return sorted(cycles, key=lambda cycle: order.index(cycle[0]))
+
+    def cycle_warnings(self) -> List[str]:
+        """A warning per import cycle, naming the imports its Rust crates are
+        restructured to avoid."""
+        warnings = []
+        for cycle in self.cycles():
+            back_edges = [f"{self.names[f]} -> {self.names[later]}"
+                          for f in cycle for later in self.later_dependencies(f)]
+            warnings.append(
+                f"Import cycle between {', '.join(self.names[f] for f in cycle)}: the Rust of "
+                f"{', '.join(back_edges)} is restructured, as crates cannot depend on "
+                "each other in a cycle")
+        return warnings
+
+    def cycle_of(self, file: Path) -> List[Path]:
+        """The files in an import cycle with a file, itself included; empty outside cycles."""
+        for component in self._strong_components():
+            if file in component:
+                return list(component) if len(component) > 1 else []
+        return []
+
+    def topological_order(self) -> List[Path]:
+        """Dependencies before dependents; files in an import cycle come together, in their
+        original order, after the dependencies of the whole cycle."""
+        if self._order is not None:
+            return list(self._order)
+        components = self._strong_components()
+        component_of = {f: i for i, component in enumerate(components) for f in component}
+        remaining = {
+            i: {component_of[dependency] for f in component for dependency in self.edges[f]} - {i}
+            for i, component in enumerate(components)
+        }
+        position = {f: i for i, f in enumerate(self.files)}
+        order: List[Path] = []
+        while remaining:
+            ready = min((i for i, deps in remaining.items() if not deps),
+                        key=lambda i: position[components[i][0]])
+            order.extend(components[ready])
+            del remaining[ready]
+            for deps in remaining.values():
+                deps.discard(ready)
+        self._order = order
+        return list(order)
+
+    def ordered_dependencies(self, file: Path) -> List[Path]:
+        """Dependencies that are migrated before a file (excludes back edges of cycles)."""
+        order = self.topological_order()
+        position = order.index(file)
+        return [f for f in order[:position] if f in self.edges[file]]
+
+    def later_dependencies(self, file: Path) -> List[Path]:
+        """Files of a file's import cycle that it imports but that are migrated after it."""
+        order = self.topological_order()
+        position = order.index(file)
+        return [f for f in order[position + 1:] if f in self.edges[file]]
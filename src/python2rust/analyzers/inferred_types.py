@@ -0,0 +1,179 @@
+import ast
+import json
+import re
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Tuple, Union
+
+MYPY_NOTE = re.compile(r'^(?P<file>.+?):(?P<line>\d+): note: Revealed type is "(?P<type>.*)"$')
+PYRIGHT_REVEAL = re.compile(r'^Type of "(?P<expression>.*)" is "(?P<type>.*)"$', re.DOTALL)
+# Types that tell nothing about the Rust one
+UNKNOWN_TYPES = {"Any", "Unknown", "<nothing>", "Never"}
+
+FunctionNode = Union[ast.FunctionDef, ast.AsyncFunctionDef]
+# Line of the probed copy -> function qualname and variable, SIGNATURE for the function itself
+Probes = Dict[int, Tuple[str, str]]
+SIGNATURE = ""
+
+
+@dataclass
+class FunctionTypes:
+    """What a type checker inferred for a function: its signature and its variables' types."""
+    signature: Optional[str] = None
+    variables: Dict[str, str] = field(default_factory=dict)
+
+
+def _starts_line(lines: List[str], node: ast.stmt) -> bool:
+    return not lines[node.lineno - 1][:node.col_offset].strip()
+
+
+def _bodies(node: ast.stmt) -> List[List[ast.stmt]]:
+    bodies = [getattr(node, name) for name in ("body", "orelse", "finalbody")
+              if getattr(node, name, None)]
+    bodies.extend(handler.body for handler in getattr(node, "handlers", []))
+    bodies.extend(case.body for case in getattr(node, "cases", []))
+    return bodies
+
+
+def _assigned_names(node: ast.stmt) -> List[str]:
+    if isinstance(node, ast.Assign):
+        targets = node.targets
+    elif isinstance(node, (ast.AnnAssign, ast.AugAssign)):
+        targets = [node.target]
+    elif isinstance(node, (ast.With, ast.AsyncWith)):
+        targets = [item.optional_vars for item in node.items if item.optional_vars is not None]
+    else:
+        return []
+    names = []
+    for target in targets:
+        for child in ast.walk(target):
+            if isinstance(child, ast.Name) and isinstance(child.ctx, ast.Store):
+                names.append(child.id)
+    return names
+
+
+def probe_source(python_code: str) -> Tuple[str, Probes]:
+    """A copy of a module with reveal_type() calls for the parameters and first assignments of
+    each function and method, and for the functions themselves at the end, with the line of
+    each call; both mypy and pyright report the type revealed on that line."""
+    tree = ast.parse(python_code)
+    lines = python_code.splitlines()
+    # Original line the probes go before (len(lines) + 1 for the end) -> indentation and probe
+    inserted: Dict[int, List[Tuple[str, str, str, str]]] = {}
+    signatures: List[Tuple[str, str]] = []
+
+    def probe(before: int, indent: str, qualname: str, name: str) -> None:
+        inserted.setdefault(before, []).append((indent, f"reveal_type({name})", qualname, name))
+
+    def visit(body: List[ast.stmt], qualname: str, seen: set) -> None:
+        for node in body:
+            if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)):
+                continue
+            if _starts_line(lines, node):
+                indent = lines[node.lineno - 1][:node.col_offset]
+                for name in _assigned_names(node):
+                    if name not in seen:
+                        seen.add(name)
+                        target = (node.body[0]
+                                  if isinstance(node, (ast.With, ast.AsyncWith)) else None)
+                        if target is None:
+                            probe(node.end_lineno + 1, indent, qualname, name)
+                        elif target.lineno > node.lineno and _starts_line(lines, target):
+                            probe(target.lineno,
+                                  lines[target.lineno - 1][:target.col_offset], qualname, name)
+            if isinstance(node, (ast.For, ast.AsyncFor)):
+                first = node.body[0]
+                if first.lineno > node.lineno and _starts_line(lines, first):
+                    for child in ast.walk(node.target):
+                        if isinstance(child, ast.Name) and child.id not in seen:
+                            seen.add(child.id)
+                            indent = lines[first.lineno - 1][:first.col_offset]
+                            probe(first.lineno, indent, qualname, child.id)
+            for inner in _bodies(node):
+                visit(inner, qualname, seen)
+
+    def function(node: FunctionNode, qualname: str) -> None:
+        signatures.append((qualname, qualname))
+        first = node.body[0]
+        if first.lineno <= node.lineno or not _starts_line(lines, first):
+            return
+        indent = lines[first.lineno - 1][:first.col_offset]
+        arguments = [*node.args.posonlyargs, *node.args.args, *node.args.kwonlyargs]
+        parameters = [argument.arg for argument in arguments if argument.arg not in ("self", "cls")]
+        for name in parameters:
+            probe(first.lineno, indent, qualname, name)
+        visit(node.body, qualname, set(parameters))
+
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            function(node, node.name)
+        elif isinstance(node, ast.ClassDef):
+            for child in node.body:
+                if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    function(child, f"{node.name}.{child.name}")
+
+    probed: List[str] = []
+    probes: Probes = {}
+    for number, line in enumerate([*lines, None], start=1):
+        for indent, call, qualname, name in inserted.get(number, []):
+            probed.append(f"{indent}{call}")
+            probes[len(probed)] = (qualname, name)
+        if line is not None:
+            probed.append(line)
+    for qualname, expression in signatures:
+        probed.append(f"reveal_type({expression})")
+        probes[len(probed)] = (qualname, SIGNATURE)
+    return "\n".join(probed) + "\n", probes
+
+
+def _clean(type_name: str) -> str:
+    # mypy qualifies builtins and marks inferred types with *
+    return re.sub(r"\bbuiltins\.", "", type_name).replace("*", "")
+
+
+def revealed_mypy(output: str) -> Dict[int, str]:
+    """Line -> revealed type, from mypy's notes."""
+    revealed = {}
+    for line in output.splitlines():
+        match = MYPY_NOTE.match(line.strip())
+        if match:
+            revealed[int(match.group("line"))] = _clean(match.group("type"))
+    return revealed
+
+
+def revealed_pyright(output: str) -> Dict[int, str]:
+    """Line -> revealed type, from pyright's --outputjson diagnostics."""
+    try:
+        diagnostics = json.loads(output).get("generalDiagnostics", [])
+    except (ValueError, AttributeError):
+        return {}
+    revealed = {}
+    for diagnostic in diagnostics:
+        match = PYRIGHT_REVEAL.match(diagnostic.get("message", ""))
+        if match and diagnostic.get("severity") == "information":
+            revealed[diagnostic["range"]["start"]["line"] + 1] = match.group("type")
+    return revealed
+
+
+def inferred_types(probes: Probes, revealed: Dict[int, str]) -> Dict[str, FunctionTypes]:
+    """The types of the probed functions and variables, leaving out those inferred as unknown."""
+    types: Dict[str, FunctionTypes] = {}
+    for line, (qualname, name) in probes.items():
+        type_name = revealed.get(line)
+        if type_name is None or type_name in UNKNOWN_TYPES:
+            continue
+        function = types.setdefault(qualname, FunctionTypes())
+        if name == SIGNATURE:
+            function.signature = type_name
+        else:
+            function.variables[name] = type_name
+    return types
+
+
+def types_summary(types: Dict[str, FunctionTypes]) -> str:
+    """The inferred types as prompt context, function by function."""
+    sections = []
+    for qualname, function in types.items():
+        lines = [f"`{qualname}`" + (f": `{function.signature}`" if function.signature else "")]
+        lines.extend(f"- {name}: `{type_name}`" for name, type_name in function.variables.items())
+        sections.append("\n".join(lines))
+    return "\n\n".join(sections)
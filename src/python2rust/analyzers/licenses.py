@@ -0,0 +1,177 @@
+import json
+import re
+import urllib.request
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+from .requirements import CrateDependency
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+CRATES_API = "https://crates.io/api/v1/crates/{}"
+# crates.io rejects requests without a user agent
+USER_AGENT = "python2rust (https://github.com/obierlaire/python2rust)"
+SPDX_TOKEN = re.compile(r"\(|\)|[^\s()]+")
+
+
+@dataclass
+class CrateLicense:
+    """The license of the version of a crate a requirement resolves to."""
+    crate: str
+    version: str
+    license: str  # SPDX expression, e.g. MIT OR Apache-2.0
+
+
+@dataclass
+class LicenseViolation:
+    """A crate replacing a library under a license outside the allowed ones, and the crates of
+    its category that are allowed."""
+    python: str
+    crate: str
+    version: str
+    license: str
+    alternatives: List[CrateLicense] = field(default_factory=list)
+
+    def describe(self) -> str:
+        instead = "; no other crate of its category is allowed"
+        if self.alternatives:
+            instead = "; allowed instead: " + ", ".join(
+                f"{alternative.crate} ({alternative.license})" for alternative in self.alternatives)
+        return f"{self.python} -> {self.crate} {self.version} ({self.license}){instead}"
+
+
+def license_allowed(expression: str, allowed: Iterable[str]) -> bool:
+    """Whether an SPDX expression is satisfied by the allowed licenses: one side of an OR, both
+    of an AND. The legacy `MIT/Apache-2.0` form is read as an OR."""
+    allowed = {license.lower() for license in allowed}
+    tokens = SPDX_TOKEN.findall(expression.replace("/", " OR "))
+    position = 0
+
+    def peek() -> str:
+        return tokens[position].upper() if position < len(tokens) else ""
+
+    def term() -> bool:
+        nonlocal position
+        if peek() == "(":
+            position += 1
+            value = disjunction()
+            position += 1  # the closing parenthesis
+            return value
+        license = tokens[position] if position < len(tokens) else ""
+        position += 1
+        if peek() == "WITH" and position + 1 < len(tokens):
+            license = f"{license} WITH {tokens[position + 1]}"
+            position += 2
+        return license.lower() in allowed or license.rstrip("+").lower() in allowed
+
+    def conjunction() -> bool:
+        nonlocal position
+        value = term()
+        while peek() == "AND":
+            position += 1
+            value = term() and value
+        return value
+
+    def disjunction() -> bool:
+        nonlocal position
+        value = conjunction()
+        while peek() == "OR":
+            position += 1
+            value = conjunction() or value
+        return value
+
+    return bool(tokens) and disjunction()
+
+
+def _numbers(version: str) -> List[int]:
+    return [int(part) if part.isdigit() else 0 for part in version.split("-", 1)[0].split(".")]
+
+
+def version_matches(requirement: str, version: str) -> bool:
+    """Whether a version satisfies a Cargo version requirement, read as a caret requirement."""
+    requirement = requirement.strip().lstrip("^=").strip()
+    if requirement in ("", "*"):
+        return True
+    wanted, found = _numbers(requirement), _numbers(version) + [0, 0, 0]
+    # ^1.2 allows 1.x from 1.2, ^0.12 allows 0.12.x: the left-most non-zero number is kept
+    fixed = next((index for index, number in enumerate(wanted) if number != 0), len(wanted) - 1)
+    if found[:fixed + 1] != wanted[:fixed + 1]:
+        return False
+    return found[:len(wanted)] >= wanted
+
+
+def _fetch(crate: str) -> Dict[str, Any]:
+    request = urllib.request.Request(CRATES_API.format(crate), headers={"User-Agent": USER_AGENT})
+    with urllib.request.urlopen(request, timeout=30) as response:
+        return json.loads(response.read().decode())
+
+
+class CrateLicenses:
+    """Licenses of crates fetched from crates.io, cached in a file by crate and requirement."""
+
+    def __init__(self, cache_file: Optional[Path] = None,
+                 fetch: Callable[[str], Dict[str, Any]] = _fetch):
+        self.cache_file = cache_file
+        self.fetch = fetch
+        self._cache: Dict[str, Dict[str, str]] = {}
+        if cache_file is not None and cache_file.exists():
+            try:
+                self._cache = json.loads(cache_file.read_text())
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read the crate license cache {cache_file}: {e}")
+
+    def license(self, crate: str, requirement: str = "*") -> Optional[CrateLicense]:
+        """The license of the newest version of a crate matching a requirement, None when crates.io
+        cannot tell."""
+        key = f"{crate}@{requirement}"
+        if key not in self._cache:
+            try:
+                data = self.fetch(crate)
+            except (OSError, ValueError) as e:  # urllib.error.URLError is an OSError
+                logger.warning(f"Could not fetch the license of {crate} from crates.io: {e}")
+                return None
+            versions = [version for version in data.get("versions", [])
+                        if not version.get("yanked") and version.get("license")
+                        and version_matches(requirement, version.get("num", ""))]
+            if not versions:
+                logger.warning(f"crates.io has no licensed version of {crate} "
+                               f"matching {requirement}")
+                return None
+            newest = max(versions, key=lambda version: _numbers(version["num"]))
+            self._cache[key] = {"version": newest["num"], "license": newest["license"]}
+            if self.cache_file is not None:
+                self.cache_file.parent.mkdir(parents=True, exist_ok=True)
+                self.cache_file.write_text(json.dumps(self._cache, indent=2, sort_keys=True))
+        cached = self._cache[key]
+        return CrateLicense(crate, cached["version"], cached["license"])
+
+
+def _requirement(line: str) -> str:
+    match = re.search(r'"([^"]*)"', line.split("=", 1)[1])
+    return match.group(1) if match else "*"
+
+
+def license_violations(
+    dependencies: List[CrateDependency], allowed: Iterable[str], licenses: CrateLicenses
+) -> Tuple[List[LicenseViolation], List[str]]:
+    """The dependencies whose crate is under a license outside the allowed ones, each with the
+    allowed crates of its category, and the crates whose license could not be fetched."""
+    allowed = list(allowed)
+    violations = []
+    unknown = []
+    for dependency in dependencies:
+        found = licenses.license(dependency.crate, _requirement(dependency.line))
+        if found is None:
+            unknown.append(dependency.crate)
+            continue
+        if license_allowed(found.license, allowed):
+            continue
+        alternatives = [alternative for alternative in
+                        (licenses.license(crate) for crate in dependency.alternatives)
+                        if alternative is not None
+                        and license_allowed(alternative.license, allowed)]
+        violations.append(LicenseViolation(
+            dependency.python, dependency.crate, found.version, found.license, alternatives))
+    return violations, list(dict.fromkeys(unknown))
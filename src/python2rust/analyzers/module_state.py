@@ -0,0 +1,151 @@
+import ast
+import re
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Set
+
+# Methods changing the container they are called on
+MUTATING_METHODS = {
+    "append", "extend", "insert", "remove", "pop", "popitem", "clear", "update",
+    "setdefault", "add", "discard", "appendleft", "popleft", "extendleft", "sort", "reverse",
+}
+CLASS_NAME = re.compile(r"^_?[A-Z][a-z0-9]+[A-Za-z0-9]*$")
+# Classes whose module-level instances are values or type declarations rather than shared objects
+VALUE_CLASSES = {
+    "TypeVar", "ParamSpec", "TypeVarTuple", "NewType", "NamedTuple", "TypedDict", "Enum", "IntEnum",
+    "Flag", "IntFlag", "Path", "PurePath", "Decimal", "Fraction", "Template",
+}
+
+# How each strategy holds a piece of module-level state in Rust
+STRATEGIES = {
+    "once_lock": "a `static` initialized once with `std::sync::OnceLock` (`LazyLock` when its "
+                 "value is computed on first use), behind a `Mutex` when the code changes it",
+    "app_state": "a field of a single `AppState` struct, created in `main` and passed by `&` or "
+                 "`&mut` to the functions using it",
+    "injection": "a value the caller creates and passes as a parameter to the functions and "
+                 "constructors using it",
+}
+
+
+@dataclass
+class ModuleState:
+    """A module-level global the code changes, or a singleton instance it shares."""
+    line: int
+    name: str
+    kind: str  # mutable (a global the code changes) or singleton (an instance it shares)
+    changed_by: List[str] = field(default_factory=list)  # functions rebinding or mutating it
+
+    @property
+    def mutable(self) -> bool:
+        return bool(self.changed_by)
+
+    def describe(self) -> str:
+        what = "mutable global" if self.kind == "mutable" else "singleton"
+        changed = f", changed by {', '.join(self.changed_by)}" if self.changed_by else ""
+        return f"line {self.line}: {self.name} ({what}{changed})"
+
+
+def state_strategy(state: ModuleState, strategy: str) -> str:
+    """The strategy holding a piece of state: state nothing changes after its initialization is
+    a OnceLock static unless state is injected."""
+    if strategy != "injection" and not state.mutable:
+        return "once_lock"
+    return strategy
+
+
+def _callee(node: ast.AST) -> Optional[str]:
+    if not isinstance(node, ast.Call):
+        return None
+    function = node.func
+    if isinstance(function, ast.Attribute):
+        return function.attr
+    return function.id if isinstance(function, ast.Name) else None
+
+
+def _is_instance(value: Optional[ast.AST]) -> bool:
+    callee = _callee(value)
+    return bool(callee) and CLASS_NAME.match(callee) is not None and callee not in VALUE_CLASSES
+
+
+def _module_assignments(tree: ast.Module) -> Dict[str, ast.stmt]:
+    """The first module-level binding of each name, in the module body or its if and try blocks."""
+    assigned: Dict[str, ast.stmt] = {}
+    pending = list(tree.body)
+    while pending:
+        node = pending.pop(0)
+        if isinstance(node, (ast.If, ast.Try)):
+            pending.extend([*node.body, *node.orelse, *getattr(node, "finalbody", []),
+                            *(statement for handler in getattr(node, "handlers", [])
+                              for statement in handler.body)])
+            continue
+        targets = node.targets if isinstance(node, ast.Assign) else [node.target] \
+            if isinstance(node, ast.AnnAssign) and node.value is not None else []
+        for target in targets:
+            if isinstance(target, ast.Name) and not target.id.startswith("__"):
+                assigned.setdefault(target.id, node)
+    return assigned
+
+
+def _changed_names(function: ast.AST) -> Set[str]:
+    """The module names a function rebinds through global, or mutates in place."""
+    declared = {name for node in ast.walk(function) if isinstance(node, ast.Global)
+                for name in node.names}
+    stored = {node.id for node in ast.walk(function)
+              if isinstance(node, ast.Name) and isinstance(node.ctx, ast.Store)}
+    local = (stored - declared) | {arg.arg for arg in ast.walk(function)
+                                   if isinstance(arg, ast.arg)}
+    changed = declared & stored
+    for node in ast.walk(function):
+        target = None
+        if isinstance(node, ast.Call) and isinstance(node.func, ast.Attribute) \
+                and node.func.attr in MUTATING_METHODS:
+            target = node.func.value
+        elif (isinstance(node, (ast.Subscript, ast.Attribute))
+              and isinstance(node.ctx, (ast.Store, ast.Del))):
+            target = node.value
+        if isinstance(target, ast.Name) and target.id not in local:
+            changed.add(target.id)
+    return changed
+
+
+def _singleton_classes(tree: ast.Module) -> List[ast.ClassDef]:
+    """Classes keeping their one instance, in a class attribute set in __new__ or a classmethod."""
+    found = []
+    for node in tree.body:
+        if not isinstance(node, ast.ClassDef):
+            continue
+        attributes = {target.id for statement in node.body
+                      for target in getattr(statement, "targets",
+                                            [getattr(statement, "target", None)])
+                      if isinstance(target, ast.Name)}
+        creating = any(
+            statement.name == "__new__" or any(
+                isinstance(decorator, ast.Name) and decorator.id == "classmethod"
+                for decorator in statement.decorator_list)
+            for statement in node.body if isinstance(statement, ast.FunctionDef))
+        if creating and any("instance" in attribute.lower() for attribute in attributes):
+            found.append(node)
+    return found
+
+
+def module_state(python_code: str) -> List[ModuleState]:
+    """The mutable globals of a module, which its functions rebind or change in place, and its
+    singletons (module-level instances, and classes keeping their one instance), in order.
+    Containers nothing changes are lookup tables rather than state, and are left out."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    assigned = _module_assignments(tree)
+    changers: Dict[str, List[str]] = {}
+    for node in ast.walk(tree):
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            for name in sorted(_changed_names(node) & set(assigned)):
+                changers.setdefault(name, []).append(node.name)
+    states = []
+    for name, node in assigned.items():
+        kind = "singleton" if _is_instance(node.value) else "mutable" if name in changers else None
+        if kind is not None:
+            states.append(ModuleState(node.lineno, name, kind, changers.get(name, [])))
+    states.extend(ModuleState(node.lineno, node.name, "singleton")
+                  for node in _singleton_classes(tree))
+    return sorted(states, key=lambda state: state.line)
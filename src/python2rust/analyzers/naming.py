@@ -0,0 +1,178 @@
+import ast
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Set, Tuple, Union
+
+# Keywords of every edition, and those reserved for later use
+RUST_KEYWORDS = {
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+    "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+    "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+    "gen", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+}
+# Keywords a raw identifier cannot escape, which are renamed whatever the style
+NOT_RAW = {"crate", "self", "Self", "super"}
+# How identifiers colliding with a keyword are escaped: r#type, or type_
+KEYWORD_STYLES = ("raw", "suffix")
+# Kinds of definitions, whose leading underscores only mark them private
+DEFINITIONS = ("function", "method", "class", "constant")
+WORD_BOUNDARY = re.compile(r"(?<=[a-z0-9])(?=[A-Z])|(?<=[A-Z])(?=[A-Z][a-z])")
+
+FunctionNode = Union[ast.FunctionDef, ast.AsyncFunctionDef]
+
+
+def snake_case(name: str) -> str:
+    """getHTTPResponse -> get_http_response, keeping leading underscores."""
+    stripped = name.lstrip("_")
+    return name[:len(name) - len(stripped)] + WORD_BOUNDARY.sub("_", stripped).lower()
+
+
+def camel_case(name: str) -> str:
+    """my_class -> MyClass, leaving names already in CamelCase as they are."""
+    return "".join(part[:1].upper() + part[1:] for part in name.split("_") if part)
+
+
+def escape_keyword(name: str, style: str = "raw") -> str:
+    """A name colliding with a Rust keyword as a raw identifier, or with a trailing underscore."""
+    if name not in RUST_KEYWORDS:
+        return name
+    return f"r#{name}" if style == "raw" and name not in NOT_RAW else f"{name}_"
+
+
+def _converted(name: str, kind: str) -> str:
+    if kind in DEFINITIONS:
+        name = name.lstrip("_") or name
+    if kind == "class":
+        return camel_case(name) or name
+    if kind == "constant":
+        return snake_case(name).upper()
+    return snake_case(name)
+
+
+def rust_identifier(name: str, kind: str, style: str = "raw") -> str:
+    """The idiomatic Rust name of a Python identifier: CamelCase for classes, SCREAMING_SNAKE_CASE
+    for constants, snake_case otherwise, definitions without the leading underscores marking
+    them private, and keywords escaped."""
+    return escape_keyword(_converted(name, kind), style)
+
+
+@dataclass
+class Renaming:
+    """A Python identifier whose Rust name differs, and why."""
+    python: str
+    rust: str
+    kind: str  # function, method, class, constant, parameter or variable
+    scope: str = ""  # the definition it belongs to, e.g. Shape.area
+    reason: str = ""  # case, private, keyword, or several of them, or collision
+    line: int = 0
+
+    def describe(self) -> str:
+        scope = f" of {self.scope}" if self.scope else ""
+        return f"line {self.line}: {self.kind} {self.python}{scope} -> {self.rust} ({self.reason})"
+
+
+def _renaming(name: str, kind: str, scope: str, line: int, style: str) -> Optional[Renaming]:
+    converted = _converted(name, kind)
+    rust = escape_keyword(converted, style)
+    if rust == name:
+        return None
+    reasons = []
+    if kind in DEFINITIONS and name.startswith("_") and name.strip("_"):
+        reasons.append("private")
+    bare = (name.lstrip("_") or name) if kind in DEFINITIONS else name
+    if converted != bare:
+        reasons.append("case")
+    if rust != converted:
+        reasons.append("keyword")
+    return Renaming(name, rust, kind, scope, ", ".join(reasons), line)
+
+
+def _disambiguated(name: str, kind: str, taken: Set[str]) -> str:
+    """The name of an identifier whose Rust name is taken in its scope: its Python name when free
+    and not a keyword, else the converted name with a number."""
+    if name not in taken and name not in RUST_KEYWORDS:
+        return name
+    base = _converted(name, kind)
+    number = 2
+    while f"{base}_{number}" in taken:
+        number += 1
+    return f"{base}_{number}"
+
+
+def _local_names(function: FunctionNode) -> Dict[str, int]:
+    """The variables a function assigns, with the line of the first assignment, not descending
+    into the functions, classes and lambdas it defines."""
+    names: Dict[str, int] = {}
+    stack: List[ast.AST] = list(function.body)
+    while stack:
+        node = stack.pop(0)
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef, ast.Lambda)):
+            continue
+        if isinstance(node, ast.Name) and isinstance(node.ctx, ast.Store):
+            names.setdefault(node.id, node.lineno)
+        stack.extend(ast.iter_child_nodes(node))
+    return names
+
+
+def renamings(python_code: str, style: str = "raw") -> List[Renaming]:
+    """The identifiers of a module whose Rust names differ from their Python ones: functions,
+    classes, methods and module constants, and the parameters and variables of functions.
+    Special methods are left out, as they become trait implementations. Identifiers of a scope
+    and kind converting to the same name, as _parse and parse, keep the ones unchanged or defined
+    first; the others keep their Python names, or are numbered, for the reason collision."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    found: List[Tuple[str, str, str, int]] = []  # name, kind, scope and line of each identifier
+
+    def function(node: FunctionNode, scope: str) -> None:
+        qualname = f"{scope}.{node.name}" if scope else node.name
+        arguments = node.args
+        parameters = [*arguments.posonlyargs, *arguments.args, *arguments.kwonlyargs,
+                      *(argument for argument in (arguments.vararg, arguments.kwarg) if argument)]
+        for parameter in parameters:
+            if parameter.arg not in ("self", "cls"):
+                found.append((parameter.arg, "parameter", qualname, parameter.lineno))
+        for name, line in _local_names(node).items():
+            if name not in {parameter.arg for parameter in parameters}:
+                found.append((name, "variable", qualname, line))
+        body(node.body, qualname, in_class=False)
+
+    def body(nodes: List[ast.stmt], scope: str, in_class: bool) -> None:
+        for node in nodes:
+            if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                if not (node.name.startswith("__") and node.name.endswith("__")):
+                    kind = "method" if in_class else "function"
+                    found.append((node.name, kind, scope, node.lineno))
+                function(node, scope)
+            elif isinstance(node, ast.ClassDef):
+                found.append((node.name, "class", scope, node.lineno))
+                body(node.body, f"{scope}.{node.name}" if scope else node.name, in_class=True)
+            elif not scope and isinstance(node, (ast.Assign, ast.AnnAssign)):
+                targets = node.targets if isinstance(node, ast.Assign) else [node.target]
+                for target in targets:
+                    if isinstance(target, ast.Name) and not target.id.startswith("__"):
+                        found.append((target.id, "constant", "", node.lineno))
+
+    body(tree.body, "", in_class=False)
+    identifiers: Dict[Tuple[str, str, str], int] = {}
+    for name, kind, scope, line in found:
+        identifiers.setdefault((scope, kind, name), line)
+    taken: Dict[Tuple[str, str], Set[str]] = {}
+    for scope, kind, name in identifiers:
+        if rust_identifier(name, kind, style) == name:
+            taken.setdefault((scope, kind), set()).add(name)
+    result: List[Renaming] = []
+    for (scope, kind, name), line in identifiers.items():
+        renaming = _renaming(name, kind, scope, line, style)
+        if renaming is None:
+            continue
+        names = taken.setdefault((scope, kind), set())
+        if renaming.rust in names:
+            renaming.rust = _disambiguated(name, kind, names)
+            renaming.reason = "collision"
+        names.add(renaming.rust)
+        result.append(renaming)
+    return result
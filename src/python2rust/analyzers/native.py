@@ -0,0 +1,136 @@
+import ast
+import importlib.machinery
+import importlib.util
+from dataclasses import dataclass
+from functools import lru_cache
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional, Tuple
+
+from ..config.crate_mappings import load_crate_mappings, mappings_by_name, normalize_name
+
+# Packages whose behavior lives in compiled code: the native library and the crate
+# replacing it, if any
+NATIVE_PACKAGES: Dict[str, Tuple[str, Optional[str]]] = {
+    "numpy": ("C array kernels over BLAS and LAPACK", "ndarray"),
+    "scipy": ("C and Fortran numerical routines over BLAS and LAPACK", None),
+    "pandas": ("Cython dataframe kernels", "polars"),
+    "sklearn": ("Cython estimators over BLAS, libsvm and liblinear", "linfa"),
+    "numba": ("LLVM compiled functions", None),
+    "torch": ("the libtorch C++ tensor library", "tch"),
+    "tensorflow": ("the TensorFlow C++ runtime", "tensorflow"),
+    "cv2": ("the OpenCV C++ library", "opencv"),
+    "PIL": ("the Pillow C imaging core", "image"),
+    "lxml": ("libxml2 and libxslt", "libxml"),
+    "cryptography": ("OpenSSL and Rust extension code", "openssl"),
+    "nacl": ("libsodium", "sodiumoxide"),
+    "psycopg2": ("libpq", "tokio-postgres"),
+    "MySQLdb": ("libmysqlclient", "mysql"),
+    "pyarrow": ("the Arrow C++ library", "arrow"),
+    "h5py": ("the HDF5 C library", "hdf5"),
+    "shapely": ("the GEOS C library", "geos"),
+    "zmq": ("libzmq", "zmq"),
+    "grpc": ("the gRPC C core", "tonic"),
+    "orjson": ("Rust extension code", "serde_json"),
+    "ujson": ("C JSON codecs", "serde_json"),
+    "msgpack": ("C MessagePack codecs", "rmp-serde"),
+    "lz4": ("the LZ4 C library", "lz4"),
+    "zstandard": ("the Zstandard C library", "zstd"),
+    "gmpy2": ("GMP, MPFR and MPC", "rug"),
+}
+EXTENSION_SUFFIXES = tuple(importlib.machinery.EXTENSION_SUFFIXES) + (".so", ".pyd")
+
+
+@dataclass
+class NativeImport:
+    """An import of a module whose behavior lives in a compiled extension or native library."""
+    line: int
+    module: str
+    library: str  # what the compiled code is, e.g. "libxml2 and libxslt"
+    crate: Optional[str] = None  # replacing it, when one is known
+
+    def describe(self) -> str:
+        replacement = (f"use the {self.crate} crate or bind it through FFI" if self.crate
+                       else "bind it through FFI")
+        return f"line {self.line} imports {self.module}, backed by {self.library}: {replacement}"
+
+
+def _is_extension(path: Path) -> bool:
+    return path.is_file() and path.name.endswith(EXTENSION_SUFFIXES)
+
+
+def _local_extension(module: str, search_paths: Iterable[Path]) -> Optional[Path]:
+    """The compiled module a project import resolves to, e.g.
+    fastmath.cpython-311-x86_64-linux-gnu.so."""
+    parts = module.split(".")
+    for root in search_paths:
+        parent = Path(root).joinpath(*parts[:-1])
+        if not parent.is_dir():
+            continue
+        for candidate in parent.glob(f"{parts[-1]}.*"):
+            if _is_extension(candidate) and candidate.name.split(".")[0] == parts[-1]:
+                return candidate
+    return None
+
+
+@lru_cache(maxsize=None)
+def _installed_extension(package: str) -> bool:
+    """Whether an installed third-party package is or ships a compiled extension."""
+    try:
+        spec = importlib.util.find_spec(package)
+    except (ImportError, ValueError):
+        return False
+    if spec is None or not spec.origin or "-packages" not in spec.origin:
+        return False
+    if spec.submodule_search_locations is None:
+        return spec.origin.endswith(EXTENSION_SUFFIXES)
+    return any(_is_extension(path) for location in spec.submodule_search_locations
+               for path in Path(location).rglob("*"))
+
+
+def _imports(tree: ast.AST) -> List[Tuple[int, str]]:
+    imports = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            imports.extend((node.lineno, alias.name) for alias in node.names)
+        elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+            # The imported names may be compiled submodules
+            imports.append((node.lineno, node.module))
+            imports.extend((node.lineno, f"{node.module}.{alias.name}") for alias in node.names)
+    return sorted(imports)
+
+
+def native_imports(
+    python_code: str,
+    search_paths: Iterable[Path] = (),
+    installed: bool = True
+) -> List[NativeImport]:
+    """The imports of a module backed by compiled code: well-known native packages, compiled
+    modules of the project under search_paths and, when installed, third-party packages
+    shipping extension modules."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    search_paths = list(search_paths)
+    crates = mappings_by_name(load_crate_mappings())
+    found: List[NativeImport] = []
+    seen = set()
+    for line, module in _imports(tree):
+        package = module.split(".")[0]
+        if package in seen or module in seen:
+            continue
+        mapping = crates.get(normalize_name(package))
+        crate = mapping.crate if mapping else None
+        if package in NATIVE_PACKAGES:
+            library, known_crate = NATIVE_PACKAGES[package]
+            found.append(NativeImport(line, package, library, crate or known_crate))
+            seen.add(package)
+            continue
+        extension = _local_extension(module, search_paths)
+        if extension is not None:
+            found.append(NativeImport(line, module, f"the compiled module {extension.name}"))
+            seen.add(module)
+        elif installed and _installed_extension(package):
+            found.append(NativeImport(line, package, "compiled extension modules", crate))
+            seen.add(package)
+    return found
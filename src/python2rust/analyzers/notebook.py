@@ -0,0 +1,82 @@
+import ast
+import json
+import re
+from dataclasses import dataclass
+from typing import List
+
+# Cell markers of the extracted code, in the percent format editors run as cells
+CELL_MARKER = re.compile(r"^# %% cell (\d+)$")
+# IPython magics and shell escapes, which are not Python
+MAGIC = re.compile(r"^\s*(%|!)|^\s*\w+\s*=\s*[%!]|\?\s*$")
+# Top-level statements a notebook read as a library keeps
+LIBRARY_NODES = (ast.Import, ast.ImportFrom, ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef,
+                 ast.Assign, ast.AnnAssign)
+
+
+@dataclass
+class NotebookCell:
+    """A code cell of a notebook and the lines of the extracted code it became."""
+    index: int  # position in the notebook, from 1, counting markdown cells
+    start_line: int
+    end_line: int
+
+
+def _cell_source(cell: dict) -> str:
+    source = cell.get("source", "")
+    return "".join(source) if isinstance(source, list) else str(source)
+
+
+def _python_lines(source: str) -> List[str]:
+    """The cell with its magics commented out, and all of it for cell magics like %%bash."""
+    lines = source.splitlines()
+    if lines and lines[0].lstrip().startswith("%%"):
+        return [f"# {line}" for line in lines]
+    return [f"# {line}" if MAGIC.search(line) else line for line in lines]
+
+
+def _library_lines(lines: List[str]) -> List[str]:
+    """The imports, definitions and assignments of a cell, without the code exploring them."""
+    code = "\n".join(lines)
+    try:
+        tree = ast.parse(code)
+    except SyntaxError:
+        return lines
+    kept = []
+    for node in tree.body:
+        if isinstance(node, LIBRARY_NODES):
+            start = min([node.lineno, *(d.lineno for d in getattr(node, "decorator_list", []))])
+            kept.extend(lines[start - 1:node.end_lineno])
+    return kept
+
+
+def notebook_source(text: str, mode: str = "script") -> str:
+    """The code cells of a notebook as one Python module, in order, each after a
+    "# %% cell N" marker.
+
+    As a script every cell is kept, and the top-level code becomes the program; as a
+    library only the imports, definitions and assignments are.
+    """
+    notebook = json.loads(text)
+    lines: List[str] = []
+    for index, cell in enumerate(notebook.get("cells", []), start=1):
+        if cell.get("cell_type") != "code":
+            continue
+        cell_lines = _python_lines(_cell_source(cell))
+        if mode == "library":
+            cell_lines = _library_lines(cell_lines)
+        lines.append(f"# %% cell {index}")
+        lines.extend(cell_lines)
+    return "\n".join(lines) + "\n"
+
+
+def notebook_cells(python_code: str) -> List[NotebookCell]:
+    """The cells of code extracted from a notebook, from their markers."""
+    cells: List[NotebookCell] = []
+    lines = python_code.splitlines()
+    for number, line in enumerate(lines, start=1):
+        match = CELL_MARKER.match(line)
+        if match:
+            if cells:
+                cells[-1].end_line = number - 1
+            cells.append(NotebookCell(int(match.group(1)), number, len(lines)))
+    return cells
@@ -0,0 +1,140 @@
+import ast
+import re
+from dataclasses import dataclass
+from typing import List, Optional
+
+from .fuzz_targets import RUST_INTEGERS, rust_functions
+from .rust_items import _strip_comment
+from .rust_quality import STRING_LITERAL, TEST_MODULE
+
+INTEGER_TYPE = re.compile(r"\b(?:" + "|".join(RUST_INTEGERS) + r"|i128|u128)\b")
+INTEGER_CAST = re.compile(r"\bas\s+(" + "|".join(RUST_INTEGERS) + r"|i128|u128)\b")
+TRUNCATING_CALLS = ("int", "round", "floor", "ceil", "trunc")
+
+OVERFLOW_OPERATORS = {
+    ast.Add: "+", ast.Sub: "-", ast.Mult: "*", ast.Pow: "**", ast.LShift: "<<"
+}
+
+
+@dataclass
+class NumericRisk:
+    """An arithmetic site whose Python and Rust semantics may differ."""
+    line: int
+    function: str
+    kind: str  # overflow, truncation or division
+    message: str
+    language: str = "python"
+
+    def __str__(self) -> str:
+        return f"{self.language} line {self.line} ({self.function}): {self.message}"
+
+
+def _is_text(node: ast.AST) -> bool:
+    return (isinstance(node, ast.JoinedStr)
+            or (isinstance(node, ast.Constant) and isinstance(node.value, str)))
+
+
+def _is_constant(node: ast.AST) -> bool:
+    return isinstance(node, ast.Constant) or (
+        isinstance(node, ast.UnaryOp) and isinstance(node.operand, ast.Constant))
+
+
+def _call_name(node: ast.Call) -> Optional[str]:
+    if isinstance(node.func, ast.Name):
+        return node.func.id
+    if isinstance(node.func, ast.Attribute) and isinstance(node.func.value, ast.Name):
+        return node.func.attr if node.func.value.id == "math" else None
+    return None
+
+
+def _function_risks(function: ast.AST, name: str, fixed_width: bool) -> List[NumericRisk]:
+    risks = []
+    for node in ast.walk(function):
+        if isinstance(node, (ast.BinOp, ast.AugAssign)):
+            left = node.left if isinstance(node, ast.BinOp) else node.target
+            right = node.right if isinstance(node, ast.BinOp) else node.value
+            if _is_text(left) or _is_text(right) or (_is_constant(left) and _is_constant(right)):
+                continue
+            operator = type(node.op)
+            if operator in (ast.FloorDiv, ast.Mod):
+                symbol = "//" if operator is ast.FloorDiv else "%"
+                risks.append(NumericRisk(
+                    node.lineno, name, "division",
+                    f"`{symbol}` rounds toward negative infinity in Python, while Rust `/` and `%` "
+                    f"truncate toward zero: results differ when the operands have opposite signs"))
+            elif operator is ast.Div and fixed_width:
+                risks.append(NumericRisk(
+                    node.lineno, name, "division",
+                    "`/` always gives a float in Python, while Rust `/` on integers truncates"))
+            elif operator in OVERFLOW_OPERATORS and fixed_width:
+                risks.append(NumericRisk(
+                    node.lineno, name, "overflow",
+                    f"`{OVERFLOW_OPERATORS[operator]}` cannot overflow on Python ints, while "
+                    "fixed-width Rust integers panic in debug builds and wrap in release builds"))
+        elif isinstance(node, ast.Call) and _call_name(node) in TRUNCATING_CALLS and node.args:
+            risks.append(NumericRisk(
+                node.lineno, name, "truncation",
+                f"`{_call_name(node)}()` of a float gives an unbounded int in Python and raises on "
+                "NaN or infinity, while Rust `as` casts saturate at the type's "
+                "bounds and turn NaN into 0"))
+    return risks
+
+
+def rust_integer_casts(rust_code: str) -> List[NumericRisk]:
+    """`as` casts to integer types in Rust code, outside test modules, which silently truncate."""
+    risks = []
+    function = ""
+    in_tests, depth = False, 0
+    for number, raw_line in enumerate(rust_code.splitlines(), start=1):
+        line = _strip_comment(STRING_LITERAL.sub('""', raw_line))
+        if TEST_MODULE.match(line):
+            in_tests, depth = True, 0
+            continue
+        if in_tests:
+            depth += line.count("{") - line.count("}")
+            if depth <= 0 and "}" in line:
+                in_tests = False
+            continue
+        header = re.search(r"\bfn\s+(\w+)", line)
+        function = header.group(1) if header else function
+        for match in INTEGER_CAST.finditer(line):
+            risks.append(NumericRisk(
+                number, function, "truncation",
+                f"`as {match.group(1)}` truncates, wraps or saturates values out of range "
+                f"instead of raising like Python", "rust"))
+    return risks
+
+
+def numeric_risks(python_code: str, rust_code: str) -> List[NumericRisk]:
+    """Arithmetic sites of a module at risk of overflow, float truncation or a different division.
+
+    Overflow and integer division are flagged in the functions whose Rust translation uses
+    fixed-width integers; flooring division and float conversions everywhere, as are the
+    integer casts of the Rust code.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    signatures = rust_functions(rust_code)
+    uses_integers = bool(INTEGER_TYPE.search(rust_code))
+
+    risks = []
+    functions = [
+        (node.name, node) for node in tree.body
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef))]
+    for node in tree.body:
+        if isinstance(node, ast.ClassDef):
+            functions.extend(
+                (f"{node.name}.{item.name}", item) for item in node.body
+                if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef)))
+    for name, function in functions:
+        signature = signatures.get(name)
+        if signature is not None:
+            types, returns = signature
+            fixed_width = bool(INTEGER_TYPE.search(" ".join([*types, returns])))
+        else:
+            fixed_width = uses_integers
+        risks.extend(_function_risks(function, name, fixed_width))
+    risks.extend(rust_integer_casts(rust_code))
+    return sorted(risks, key=lambda risk: (risk.language, risk.line))
@@ -0,0 +1,222 @@
+import json
+import re
+from typing import Any, Dict, Iterable, List, Optional
+
+HTTP_METHODS = ("get", "put", "post", "delete", "options", "head", "patch", "trace")
+PATH_TEMPLATE = re.compile(r"\{[^}]*\}")
+# Schema keys that describe rather than constrain, integer formats and defaults, which frameworks
+# fill in differently
+DESCRIPTIVE_KEYS = ("title", "description", "example", "examples", "format",
+                    "default", "deprecated", "externalDocs", "xml", "readOnly",
+                    "writeOnly", "discriminator")
+
+
+def _resolve(schema: Any, document: Dict[str, Any], seen: Iterable[str] = ()) -> Any:
+    """A schema with its $refs replaced by their targets; recursive references stay as refs."""
+    if isinstance(schema, list):
+        return [_resolve(item, document, seen) for item in schema]
+    if not isinstance(schema, dict):
+        return schema
+    reference = schema.get("$ref")
+    if isinstance(reference, str) and reference.startswith("#/"):
+        if reference in seen:
+            return {"$ref": reference.rsplit("/", 1)[-1]}
+        target: Any = document
+        for part in reference[2:].split("/"):
+            target = target.get(part, {}) if isinstance(target, dict) else {}
+        return _resolve(target, document, [*seen, reference])
+    return {key: _resolve(value, document, seen) for key, value in schema.items()}
+
+
+def normalize_schema(schema: Any) -> Any:
+    """A resolved schema without descriptive keys, nullability the same way in OpenAPI 3.0 and 3.1:
+    `nullable: true` for a null type or a null member of anyOf and oneOf."""
+    if isinstance(schema, list):
+        return [normalize_schema(item) for item in schema]
+    if not isinstance(schema, dict):
+        return schema
+    schema = {
+        key: ({name: normalize_schema(value) for name, value in value.items()}
+              if key == "properties" and isinstance(value, dict) else normalize_schema(value))
+        for key, value in schema.items() if key not in DESCRIPTIVE_KEYS
+    }
+    kind = schema.get("type")
+    if isinstance(kind, list) and "null" in kind:
+        others = [item for item in kind if item != "null"]
+        schema["type"] = others[0] if len(others) == 1 else others
+        schema["nullable"] = True
+    for combinator in ("anyOf", "oneOf"):
+        members = schema.get(combinator)
+        if isinstance(members, list) and {"type": "null"} in members:
+            others = [member for member in members if member != {"type": "null"}]
+            del schema[combinator]
+            schema.update(others[0] if len(others) == 1 else {combinator: others})
+            schema["nullable"] = True
+    if "required" in schema:
+        schema["required"] = sorted(schema["required"])
+    if schema.get("nullable") is False:
+        del schema["nullable"]
+    return schema
+
+
+def operations(document: Dict[str, Any]) -> Dict[str, Dict[str, Any]]:
+    """The operations of an OpenAPI document by "METHOD /path", with their path-level parameters."""
+    found = {}
+    for path, item in (document.get("paths") or {}).items():
+        if not isinstance(item, dict):
+            continue
+        shared = item.get("parameters", [])
+        for method, operation in item.items():
+            if method in HTTP_METHODS and isinstance(operation, dict):
+                parameters = {
+                    (parameter.get("in"), parameter.get("name")): parameter
+                    for parameter in [*shared, *operation.get("parameters", [])]
+                    if isinstance(parameter, dict)}
+                found[f"{method.upper()} {path}"] = {**operation,
+                                                     "parameters": list(parameters.values())}
+    return found
+
+
+def _shown(schema: Any) -> str:
+    return json.dumps(schema, sort_keys=True) if isinstance(schema, (dict, list)) else str(schema)
+
+
+def schema_differences(where: str, python: Any, rust: Any) -> List[str]:
+    """How a normalized Rust schema differs from the Python one: types, nullability, enums,
+    properties and their requiredness, and array items."""
+    if not isinstance(python, dict) or python == rust:
+        return []
+    if not isinstance(rust, dict):
+        return [f"{where}: no schema in Rust"]
+    if python.get("type") != rust.get("type"):
+        return [f"{where}: type {_shown(python.get('type', 'any'))} in Python, "
+                f"{_shown(rust.get('type', 'any'))} in Rust"]
+    differences = []
+    if python.get("nullable", False) != rust.get("nullable", False):
+        nullable = {True: "nullable", False: "not nullable"}
+        differences.append(f"{where}: {nullable[python.get('nullable', False)]} in Python, "
+                           f"{nullable[rust.get('nullable', False)]} in Rust")
+    if python.get("enum") != rust.get("enum"):
+        differences.append(
+            f"{where}: values {_shown(python.get('enum'))} in Python, "
+            f"{_shown(rust.get('enum'))} in Rust")
+    properties, rust_properties = python.get("properties", {}), rust.get("properties", {})
+    for name, schema in properties.items():
+        if name not in rust_properties:
+            differences.append(f"{where}: property {name} is missing")
+        else:
+            differences.extend(schema_differences(f"{where}.{name}", schema, rust_properties[name]))
+    differences.extend(f"{where}: property {name} is not in the Python schema"
+                       for name in rust_properties if name not in properties)
+    if sorted(python.get("required", [])) != sorted(rust.get("required", [])):
+        differences.append(f"{where}: required {_shown(python.get('required', []))} in Python, "
+                           f"{_shown(rust.get('required', []))} in Rust")
+    differences.extend(schema_differences(f"{where}[]", python.get("items"), rust.get("items")))
+    if not differences:
+        # Combinators and other constraints, compared as a whole
+        differences.append(f"{where}: {_shown(python)} in Python, {_shown(rust)} in Rust")
+    return differences
+
+
+def _content_schema(content: Optional[Dict[str, Any]]) -> Any:
+    if not content:
+        return None
+    media = content.get("application/json") or next(iter(content.values()))
+    return media.get("schema") if isinstance(media, dict) else None
+
+
+def _operation_differences(
+    python: Dict[str, Any],
+    rust: Dict[str, Any],
+    python_document: Dict[str, Any],
+    rust_document: Dict[str, Any],
+    ignore_responses: Iterable[str]
+) -> List[str]:
+    def schema(value: Any, document: Dict[str, Any]) -> Any:
+        return normalize_schema(_resolve(value, document))
+
+    # Operations without responses, such as those of Flask route maps, have only their path known
+    documented = "responses" in python
+    differences = []
+    rust_parameters = {(p.get("in"), p.get("name")): p for p in rust["parameters"]
+                       if documented or p.get("in") == "path"}
+    python_parameters = {(p.get("in"), p.get("name")): p for p in python["parameters"]}
+    for key, parameter in python_parameters.items():
+        label = f"parameter {key[1]} ({key[0]})"
+        other = rust_parameters.get(key)
+        if other is None:
+            differences.append(f"{label} is missing")
+            continue
+        if bool(parameter.get("required")) != bool(other.get("required")):
+            required = {True: "required", False: "optional"}
+            differences.append(f"{label}: {required[bool(parameter.get('required'))]} in Python, "
+                               f"{required[bool(other.get('required'))]} in Rust")
+        differences.extend(schema_differences(
+            label, schema(parameter.get("schema"), python_document),
+            schema(other.get("schema"), rust_document)))
+    differences.extend(f"parameter {name} ({place}) is not in the Python app"
+                       for place, name in rust_parameters if (place, name) not in python_parameters)
+    if not documented:
+        return differences
+
+    body = _resolve(python.get("requestBody"), python_document)
+    rust_body = _resolve(rust.get("requestBody"), rust_document)
+    if isinstance(body, dict):
+        if not isinstance(rust_body, dict):
+            differences.append("request body is missing")
+        else:
+            differences.extend(schema_differences(
+                "request body", normalize_schema(_content_schema(body.get("content"))),
+                normalize_schema(_content_schema(rust_body.get("content")))))
+    elif isinstance(rust_body, dict):
+        differences.append("request body is not in the Python app")
+
+    responses = {status: value for status, value in (python.get("responses") or {}).items()
+                 if str(status) not in ignore_responses}
+    rust_responses = {status: value for status, value in (rust.get("responses") or {}).items()
+                      if str(status) not in ignore_responses}
+    for status, response in responses.items():
+        other = rust_responses.get(status)
+        if other is None:
+            differences.append(f"response {status} is missing")
+            continue
+        expected = _content_schema((_resolve(response, python_document) or {}).get("content"))
+        actual = _content_schema((_resolve(other, rust_document) or {}).get("content"))
+        differences.extend(schema_differences(
+            f"response {status}", schema(expected, python_document), schema(actual, rust_document)))
+    differences.extend(f"response {status} is not in the Python app"
+                       for status in rust_responses if status not in responses)
+    return differences
+
+
+def openapi_differences(
+    python: Dict[str, Any],
+    rust: Dict[str, Any],
+    ignore_responses: Iterable[str] = ()
+) -> List[str]:
+    """How the operations of the Rust OpenAPI document differ from those of the Python one.
+
+    Operations are matched by method and path, path parameters by position, so that a
+    renamed path parameter shows as a parameter difference.
+    """
+    ignore_responses = {str(status) for status in ignore_responses}
+    python_operations, rust_operations = operations(python), operations(rust)
+
+    def template(key: str) -> str:
+        return PATH_TEMPLATE.sub("{}", key)
+
+    by_template = {template(key): key for key in rust_operations}
+    differences = []
+    matched = set()
+    for key, operation in python_operations.items():
+        rust_key = key if key in rust_operations else by_template.get(template(key))
+        if rust_key is None:
+            differences.append(f"{key}: missing in Rust")
+            continue
+        matched.add(rust_key)
+        differences.extend(
+            f"{key}: {difference}" for difference in _operation_differences(
+                operation, rust_operations[rust_key], python, rust, ignore_responses))
+    differences.extend(f"{key}: not in the Python app" for key in rust_operations
+                       if key not in matched)
+    return differences
@@ -0,0 +1,179 @@
+//! Reads a Rust file on stdin and prints, as JSON, its functions with their line spans and the
+//! constructs code policies restrict: unwrap and expect calls, unsafe code and macro
+//! invocations, each marked when it belongs to test code.
+use std::io::Read;
+use std::process::exit;
+
+use proc_macro2::Span;
+use serde_json::{json, Value};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Block, Expr, Signature, Token};
+
+#[derive(Default)]
+struct Facts {
+    functions: Vec<Value>,
+    constructs: Vec<Value>,
+    // Test items (#[cfg(test)], #[test]) the visitor is inside of
+    test_depth: usize,
+    // Types of the impl blocks the visitor is inside of
+    impls: Vec<String>,
+}
+
+/// Whether an item is test code: #[test], #[tokio::test] and the like, or #[cfg(test)].
+fn is_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let path = attr.path();
+        if path.segments.last().is_some_and(|segment| segment.ident == "test") {
+            return true;
+        }
+        if !path.is_ident("cfg") {
+            return false;
+        }
+        let tokens = attr.meta.require_list().map(|list| list.tokens.to_string()).unwrap_or_default();
+        let words: Vec<&str> = tokens.split(|c: char| !c.is_alphanumeric() && c != '_').collect();
+        words.contains(&"test") && !words.contains(&"not")
+    })
+}
+
+impl Facts {
+    fn in_test(&self) -> bool {
+        self.test_depth > 0
+    }
+
+    fn construct(&mut self, kind: &str, name: &str, span: Span) {
+        self.constructs.push(json!({
+            "kind": kind,
+            "name": name,
+            "line": span.start().line,
+            "test": self.in_test(),
+        }));
+    }
+
+    fn function(&mut self, sig: &Signature, block: &Block) {
+        let name = match self.impls.last() {
+            Some(parent) => format!("{}::{}", parent, sig.ident),
+            None => sig.ident.to_string(),
+        };
+        // From the signature, without the doc comments and attributes, to the closing brace
+        self.functions.push(json!({
+            "name": name,
+            "start_line": sig.fn_token.span.start().line,
+            "end_line": block.brace_token.span.close().end().line,
+            "test": self.in_test(),
+        }));
+        if let Some(unsafety) = &sig.unsafety {
+            self.construct("unsafe", "fn", unsafety.span);
+        }
+    }
+
+    fn scoped(&mut self, attrs: &[Attribute], visit: impl FnOnce(&mut Self)) {
+        let test = is_test(attrs);
+        self.test_depth += usize::from(test);
+        visit(self);
+        self.test_depth -= usize::from(test);
+    }
+}
+
+impl<'ast> Visit<'ast> for Facts {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.scoped(&node.attrs, |facts| visit::visit_item_mod(facts, node));
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.scoped(&node.attrs, |facts| {
+            facts.function(&node.sig, &node.block);
+            visit::visit_item_fn(facts, node);
+        });
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let name = match &*node.self_ty {
+            syn::Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        self.impls.push(name.unwrap_or_else(|| "_".to_string()));
+        self.scoped(&node.attrs, |facts| {
+            if let Some(unsafety) = &node.unsafety {
+                facts.construct("unsafe", "impl", unsafety.span);
+            }
+            visit::visit_item_impl(facts, node);
+        });
+        self.impls.pop();
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.scoped(&node.attrs, |facts| {
+            facts.function(&node.sig, &node.block);
+            visit::visit_impl_item_fn(facts, node);
+        });
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        self.impls.push(node.ident.to_string());
+        self.scoped(&node.attrs, |facts| {
+            if let Some(unsafety) = &node.unsafety {
+                facts.construct("unsafe", "trait", unsafety.span);
+            }
+            visit::visit_item_trait(facts, node);
+        });
+        self.impls.pop();
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.scoped(&node.attrs, |facts| {
+            if let Some(block) = &node.default {
+                facts.function(&node.sig, block);
+            } else if let Some(unsafety) = &node.sig.unsafety {
+                facts.construct("unsafe", "fn", unsafety.span);
+            }
+            visit::visit_trait_item_fn(facts, node);
+        });
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast syn::ExprUnsafe) {
+        self.construct("unsafe", "block", node.unsafe_token.span);
+        visit::visit_expr_unsafe(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if (method == "unwrap" && node.args.is_empty()) || (method == "expect" && node.args.len() == 1) {
+            self.construct("call", &method, node.method.span());
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if let Some(segment) = node.path.segments.last() {
+            self.construct("macro", &segment.ident.to_string(), node.path.span());
+        }
+        // Arguments of function-like macros such as println! and vec! are expressions too
+        if let Ok(args) = node.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+        visit::visit_macro(self, node);
+    }
+}
+
+fn main() {
+    let mut source = String::new();
+    if let Err(error) = std::io::stdin().read_to_string(&mut source) {
+        eprintln!("cannot read the code: {error}");
+        exit(2);
+    }
+    let file = match syn::parse_file(&source) {
+        Ok(file) => file,
+        Err(error) => {
+            let start = error.span().start();
+            eprintln!("{}:{}: {}", start.line, start.column + 1, error);
+            exit(2);
+        }
+    };
+    let mut facts = Facts::default();
+    facts.visit_file(&file);
+    println!("{}", json!({"functions": facts.functions, "constructs": facts.constructs}));
+}
@@ -0,0 +1,114 @@
+import ast
+import os
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+# pstats entries: (file, line, function name) -> (primitive calls, calls, own time,
+# cumulative time, callers)
+StatsEntry = Tuple[str, int, str]
+FunctionNodes = (ast.FunctionDef, ast.AsyncFunctionDef)
+
+
+@dataclass
+class HotPath:
+    """A function of the sources that took a large share of the CPU time of the
+    profiled workload."""
+    qualname: str
+    line: int
+    calls: int
+    own_time: float  # seconds in the function itself, with the builtins it calls
+    cumulative_time: float  # seconds with the functions it calls
+    share: float  # of the own time of the whole workload
+
+    def describe(self) -> str:
+        return (f"{self.qualname} (line {self.line}): {self.share:.0%} of the CPU time, "
+                f"{self.own_time:.3f}s in {self.calls} calls, "
+                f"{self.cumulative_time:.3f}s with its callees")
+
+
+def _functions(source: Path) -> List[Tuple[int, int, str]]:
+    """First line, decorators included as in code objects, last line and qualname of each
+    function and method."""
+    try:
+        tree = ast.parse(source.read_text())
+    except (OSError, SyntaxError, ValueError):
+        return []
+    functions = []
+    for node in tree.body:
+        children = [(node, node.name)] if isinstance(node, FunctionNodes) else []
+        if isinstance(node, ast.ClassDef):
+            children = [(child, f"{node.name}.{child.name}")
+                        for child in node.body if isinstance(child, FunctionNodes)]
+        for function, qualname in children:
+            start = min([function.lineno,
+                         *(decorator.lineno for decorator in function.decorator_list)])
+            functions.append((start, function.end_lineno, qualname))
+    return functions
+
+
+def _owner(functions: List[Tuple[int, int, str]], line: int) -> Optional[Tuple[int, str]]:
+    """The function or method whose lines include line, with its first line."""
+    for start, end, qualname in functions:
+        if start <= line <= end:
+            return start, qualname
+    return None
+
+
+def hot_paths(
+    stats: Dict[StatsEntry, tuple],
+    files: List[Path],
+    threshold: float = 0.05,
+    cwd: Path = Path(".")
+) -> Dict[Path, List[HotPath]]:
+    """The functions and methods of the files taking at least threshold of the workload's CPU
+    time, from pstats entries, hottest first.
+
+    The time of a function includes that of the builtins it calls and of the comprehensions,
+    lambdas and nested functions it defines, which cProfile gives apart.
+    """
+    total = sum(entry[2] for entry in stats.values())
+    if total <= 0:
+        return {}
+    own = {key: entry[2] for key, entry in stats.items()}
+    for key, (*_, callers) in stats.items():
+        if key[0] == "~":
+            # Built-in functions, whose time goes to their callers
+            for caller, timing in (callers or {}).items():
+                if caller in own:
+                    own[caller] += timing[2]
+            own[key] = 0.0
+
+    sources = {os.path.realpath(file): file for file in files}
+    functions: Dict[Path, List[Tuple[int, int, str]]] = {}
+    found: Dict[Tuple[Path, int], HotPath] = {}
+    for key, (_, calls, _, cumulative_time, *_) in stats.items():
+        filename, line, function = key
+        source = sources.get(os.path.realpath(Path(cwd) / filename))
+        if source is None or function == "<module>":
+            continue
+        if source not in functions:
+            functions[source] = _functions(source)
+        owner = _owner(functions[source], line)
+        if owner is None:
+            continue
+        start, qualname = owner
+        path = found.setdefault((source, start), HotPath(qualname, start, 0, 0.0, 0.0, 0.0))
+        path.own_time += own[key]
+        if line == start:
+            path.calls += calls
+            path.cumulative_time += cumulative_time
+
+    paths: Dict[Path, List[HotPath]] = {}
+    for (source, _), path in found.items():
+        path.share = path.own_time / total
+        if path.share >= threshold:
+            paths.setdefault(source, []).append(path)
+    for hot in paths.values():
+        hot.sort(key=lambda path: path.own_time, reverse=True)
+    return paths
+
+
+def hot_paths_summary(paths: List[HotPath]) -> str:
+    """The hot paths of a module as prompt context."""
+    return "\n".join(f"- {path.describe()}" for path in paths)
@@ -0,0 +1,149 @@
+import ast
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, Set
+
+from ..config.crate_mappings import load_crate_mappings, mappings_by_name, normalize_name
+from ..config.project_config import ProjectConfig, ProjectSection, tomllib
+from ..utils.logging import setup_logger
+from .python_symbols import is_main_guard
+from .requirements import project_requirements
+
+logger = setup_logger()
+
+ENTRY_POINT_NAMES = ("main.py", "app.py", "__main__.py", "manage.py", "cli.py", "server.py")
+FRAMEWORK_CATEGORIES = ("web framework", "cli")
+
+
+@dataclass
+class ProjectInspection:
+    """What init found out about a Python project."""
+    root: Path
+    name: str
+    source_dir: Path
+    entry_points: List[Path] = field(default_factory=list)
+    tests_dir: Optional[Path] = None
+    dependencies: List[str] = field(default_factory=list)
+    imports: Set[str] = field(default_factory=set)
+    frameworks: List[str] = field(default_factory=list)
+    crates: Dict[str, str] = field(default_factory=dict)
+
+    def to_config(self) -> ProjectConfig:
+        relative = lambda path: path.relative_to(self.root) if path.is_absolute() else path
+        return ProjectConfig(
+            project=ProjectSection(
+                name=self.name,
+                source_dir=relative(self.source_dir),
+                entry_points=[relative(p) for p in self.entry_points],
+                tests_dir=relative(self.tests_dir) if self.tests_dir else None
+            ),
+            frameworks=self.frameworks,
+            crates=self.crates
+        )
+
+
+def _read_pyproject(root: Path) -> Dict:
+    pyproject = root / "pyproject.toml"
+    if not pyproject.exists():
+        return {}
+    try:
+        with open(pyproject, "rb") as f:
+            return tomllib.load(f)
+    except (OSError, tomllib.TOMLDecodeError) as e:
+        logger.warning(f"Could not read {pyproject}: {e}")
+        return {}
+
+
+def _project_name(root: Path, pyproject: Dict) -> str:
+    return (
+        pyproject.get("project", {}).get("name")
+        or pyproject.get("tool", {}).get("poetry", {}).get("name")
+        or root.resolve().name
+    )
+
+
+def _source_dir(root: Path, name: str) -> Path:
+    """Prefer src/<package>, then <package>/, then the project root."""
+    package = normalize_name(name)
+    for candidate in (root / "src" / package, root / package):
+        if (candidate / "__init__.py").exists():
+            return candidate
+    src = root / "src"
+    if src.is_dir():
+        packages = [p for p in sorted(src.iterdir()) if (p / "__init__.py").exists()]
+        if len(packages) == 1:
+            return packages[0]
+        return src
+    return root
+
+
+def _script_entry_points(root: Path, source_dir: Path, pyproject: Dict) -> List[Path]:
+    """Files referenced by [project.scripts] or [tool.poetry.scripts] (module:function)."""
+    scripts = {
+        **pyproject.get("project", {}).get("scripts", {}),
+        **pyproject.get("tool", {}).get("poetry", {}).get("scripts", {})
+    }
+    paths = []
+    for target in scripts.values():
+        if not isinstance(target, str):
+            continue
+        module = target.split(":", 1)[0]
+        parts = module.split(".")
+        for base in (source_dir.parent, root, root / "src"):
+            candidate = base.joinpath(*parts).with_suffix(".py")
+            if candidate.exists():
+                paths.append(candidate)
+                break
+    return paths
+
+
+def inspect_project(root: Path) -> ProjectInspection:
+    """Detect layout, entry points, tests and libraries of a Python project."""
+    from ..migrator import discover_python_files
+
+    root = Path(root)
+    pyproject = _read_pyproject(root)
+    name = _project_name(root, pyproject)
+    source_dir = _source_dir(root, name)
+    inspection = ProjectInspection(
+        root=root,
+        name=name,
+        source_dir=source_dir,
+        dependencies=[requirement.name for requirement in project_requirements(root)]
+    )
+
+    for candidate in ("tests", "test"):
+        if (root / candidate).is_dir():
+            inspection.tests_dir = root / candidate
+            break
+
+    entry_points = _script_entry_points(root, source_dir, pyproject)
+    for path in discover_python_files(source_dir):
+        try:
+            tree = ast.parse(path.read_text(), filename=str(path))
+        except (OSError, SyntaxError, ValueError):
+            continue
+        for node in ast.walk(tree):
+            if isinstance(node, ast.Import):
+                inspection.imports.update(alias.name for alias in node.names)
+            elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+                inspection.imports.add(node.module)
+        has_main_guard = any(isinstance(node, ast.If) and is_main_guard(node.test)
+                             for node in tree.body)
+        if path not in entry_points and (has_main_guard or path.name in ENTRY_POINT_NAMES):
+            entry_points.append(path)
+    inspection.entry_points = entry_points
+
+    known = mappings_by_name(load_crate_mappings())
+    used = [normalize_name(d) for d in inspection.dependencies]
+    for module in sorted(inspection.imports):
+        parts = module.split(".")
+        used.extend(normalize_name(".".join(parts[:end])) for end in range(len(parts), 0, -1))
+    for library in used:
+        mapping = known.get(library)
+        if mapping is None or mapping.python in inspection.crates:
+            continue
+        inspection.crates[mapping.python] = mapping.crate
+        if mapping.category in FRAMEWORK_CATEGORIES and mapping.python not in inspection.frameworks:
+            inspection.frameworks.append(mapping.python)
+    return inspection
@@ -0,0 +1,126 @@
+import io
+import re
+import tokenize
+from dataclasses import dataclass
+from typing import List, Set
+
+# Builtins removed in Python 3, flagged when the module does not define or import them
+PYTHON2_BUILTINS = {
+    "xrange": "xrange()",
+    "raw_input": "raw_input()",
+    "unicode": "unicode",
+    "basestring": "basestring",
+    "unichr": "unichr()",
+    "execfile": "execfile()",
+    "long": "long",
+    "cmp": "cmp()",
+    "apply": "apply()",
+    "reduce": "builtin reduce()",
+}
+PYTHON2_METHODS = {"has_key": "dict.has_key()"}
+OCTAL = re.compile(r"^0[0-7]+$")
+LONG = re.compile(r"^\d+[lL]$")
+LINE_PATTERNS = [
+    (re.compile(r"^\s*print\s+[^\s(=.]"), "print statement"),
+    (re.compile(r"^\s*exec\s+[\"'\w]"), "exec statement"),
+    (re.compile(r"`[^`]+`"), "backtick repr"),
+    (re.compile(r"<>"), "<> operator"),
+    (re.compile(r"^\s*except\s+[\w.]+\s*,\s*\w+\s*:"), "except X, e"),
+]
+
+
+@dataclass
+class Python2Construct:
+    """A use of Python 2 only syntax or builtins."""
+    line: int
+    construct: str
+
+
+def _defined_names(tokens: List[tokenize.TokenInfo]) -> Set[str]:
+    """Names a module binds: definitions, imports, assignments and parameters, roughly."""
+    defined = set()
+    for index, token in enumerate(tokens):
+        previous = tokens[index - 1].string if index else ""
+        following = tokens[index + 1].string if index + 1 < len(tokens) else ""
+        if token.type == tokenize.NAME and (
+                previous in ("def", "class", "import", "as", ",") and following != "("
+                or following == "="):
+            defined.add(token.string)
+    return defined
+
+
+def _statement_constructs(line: List[tokenize.TokenInfo]) -> List[Python2Construct]:
+    """Python 2 statements of a logical line: print, exec, except X, e and raise E, message."""
+    if not line or line[0].type != tokenize.NAME:
+        return []
+    first, rest = line[0], line[1:]
+    following = rest[0] if rest else None
+    if first.string in ("print", "exec") and following is not None and (
+            following.type in (tokenize.NAME, tokenize.STRING, tokenize.NUMBER)
+            or following.string == ">>"):
+        return [Python2Construct(first.start[0], f"{first.string} statement")]
+    if first.string in ("except", "raise"):
+        depth = 0
+        for token in rest:
+            if token.string in "([{" and token.type == tokenize.OP:
+                depth += 1
+            elif token.string in ")]}" and token.type == tokenize.OP:
+                depth -= 1
+            elif token.string == "," and depth == 0:
+                construct = "except X, e" if first.string == "except" else "raise E, message"
+                return [Python2Construct(first.start[0], construct)]
+            elif token.string in (":", "from", "as"):
+                break
+    return []
+
+
+def python2_constructs(python_code: str) -> List[Python2Construct]:
+    """The Python 2 only syntax and builtins of a module, by line; empty for Python 3 code."""
+    try:
+        tokens = [token for token in tokenize.generate_tokens(io.StringIO(python_code).readline)
+                  if token.type not in (tokenize.COMMENT, tokenize.NL)]
+    except (tokenize.TokenError, SyntaxError, IndentationError):
+        # Newer tokenizers reject some Python 2 syntax outright; read the lines instead
+        return [Python2Construct(number, construct)
+                for number, text in enumerate(python_code.splitlines(), start=1)
+                for pattern, construct in LINE_PATTERNS if pattern.search(text)]
+
+    found: List[Python2Construct] = []
+    line: List[tokenize.TokenInfo] = []
+    for token in tokens:
+        if token.type in (tokenize.NEWLINE, tokenize.ENDMARKER):
+            found.extend(_statement_constructs(line))
+            line = []
+        elif token.type not in (tokenize.INDENT, tokenize.DEDENT):
+            line.append(token)
+
+    defined = _defined_names(tokens)
+    for index, token in enumerate(tokens):
+        previous = tokens[index - 1] if index else None
+        following = tokens[index + 1] if index + 1 < len(tokens) else None
+        adjacent = following is not None and following.start == token.end
+        construct = None
+        if token.string == "`" and token.type in (tokenize.ERRORTOKEN, tokenize.OP):
+            # Once per line rather than for each backtick
+            if not any(f.line == token.start[0] and f.construct == "backtick repr" for f in found):
+                construct = "backtick repr"
+        elif token.string == "<>" or token.string == "<" and adjacent and following.string == ">":
+            construct = "<> operator"
+        elif token.type == tokenize.NUMBER and (
+                OCTAL.match(token.string) or LONG.match(token.string)
+                or token.string == "0" and adjacent and following.type == tokenize.NUMBER):
+            construct = "long literal" if LONG.match(token.string) else "octal literal"
+        elif token.type == tokenize.NUMBER and adjacent and following.string in ("L", "l"):
+            construct = "long literal"
+        elif token.type == tokenize.NAME and token.string.lower() == "ur" and adjacent \
+                and following.type == tokenize.STRING:
+            construct = "ur string prefix"
+        elif token.type == tokenize.NAME and (previous is None or previous.string != "."):
+            if token.string in PYTHON2_BUILTINS and token.string not in defined:
+                construct = PYTHON2_BUILTINS[token.string]
+        elif token.type == tokenize.NAME and previous is not None and previous.string == "." \
+                and token.string in PYTHON2_METHODS:
+            construct = PYTHON2_METHODS[token.string]
+        if construct is not None:
+            found.append(Python2Construct(token.start[0], construct))
+    return sorted(found, key=lambda construct: construct.line)
@@ -0,0 +1,154 @@
+import ast
+import copy
+from dataclasses import dataclass, field
+from typing import List, Optional, Set, Tuple
+
+from .python_symbols import is_main_guard
+from .token_estimate import count_tokens
+
+DEFINITIONS = (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)
+
+
+@dataclass
+class PythonChunk:
+    """A run of top-level statements of a module, translated on its own.
+
+    `code` starts with the imports of the module and, when the chunk begins
+    inside a class, with the header of that class.
+    """
+    code: str
+    start_line: int
+    end_line: int
+    symbols: List[str] = field(default_factory=list)
+    has_main: bool = False
+
+    @property
+    def name(self) -> str:
+        return f"lines_{self.start_line}-{self.end_line}"
+
+
+def _start(node: ast.stmt) -> int:
+    """First line of a statement, decorators included."""
+    return min([node.lineno, *(d.lineno for d in getattr(node, "decorator_list", []))])
+
+
+def _units(tree: ast.Module, lines: List[str],
+           max_tokens: int) -> List[Tuple[int, int, Optional[ast.ClassDef], List[str]]]:
+    """Line spans that must stay together: top-level statements, or the methods of classes too
+    large for one chunk.
+
+    Comments and blank lines before a statement belong to it.
+    """
+    units = []
+    body = [node for node in tree.body if not isinstance(node, (ast.Import, ast.ImportFrom))]
+    for index, node in enumerate(body):
+        start = units[-1][1] + 1 if units else 1
+        end = _start(body[index + 1]) - 1 if index + 1 < len(body) else len(lines)
+        text = "\n".join(lines[start - 1:end])
+        if isinstance(node, ast.ClassDef) and count_tokens(text) > max_tokens:
+            methods = [child for child in node.body
+                       if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef))]
+            bounds = [start] + [method.end_lineno + 1 for method in methods[:-1]] + [end + 1]
+            for part, (part_start, next_start) in enumerate(zip(bounds, bounds[1:])):
+                symbols = [node.name] if part == 0 else []
+                if methods:
+                    symbols.append(f"{node.name}.{methods[part].name}")
+                units.append((part_start, next_start - 1, node, symbols))
+            continue
+        if isinstance(node, DEFINITIONS):
+            symbols = [node.name]
+        elif isinstance(node, ast.If) and is_main_guard(node.test):
+            symbols = ["__main__"]
+        else:
+            symbols = []
+        units.append((start, end, None, symbols))
+    return units
+
+
+def split_module(python_code: str, max_tokens: int) -> List[PythonChunk]:
+    """Split a module at class and function boundaries into chunks of about max_tokens.
+
+    Classes larger than a chunk are split between their methods. A single
+    function larger than max_tokens still makes one chunk. Source that does not
+    parse or fits is returned as one chunk.
+    """
+    lines = python_code.splitlines()
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        tree = None
+    if tree is None or count_tokens(python_code) <= max_tokens:
+        return [PythonChunk(python_code, 1, len(lines))]
+
+    import_lines: Set[int] = set()
+    imports = []
+    for node in tree.body:
+        if isinstance(node, (ast.Import, ast.ImportFrom)):
+            import_lines.update(range(node.lineno, node.end_lineno + 1))
+            imports.append(ast.get_source_segment(python_code, node))
+    preamble = "\n".join(imports)
+    budget = max(max_tokens - count_tokens(preamble), 1)
+
+    groups: List[list] = []
+    for unit in _units(tree, lines, budget):
+        text = "\n".join(lines[unit[0] - 1:unit[1]])
+        if groups and groups[-1][3] + count_tokens(text) <= budget:
+            group = groups[-1]
+            group[1] = unit[1]
+            group[3] += count_tokens(text)
+            group[4].extend(unit[3])
+        else:
+            groups.append([unit[0], unit[1], unit[2], count_tokens(text), list(unit[3])])
+
+    chunks = []
+    for start, end, continued_class, _, symbols in groups:
+        body = [lines[number - 1] for number in range(start, end + 1) if number not in import_lines]
+        header = []
+        if continued_class is not None and start > _start(continued_class):
+            header = [
+                lines[continued_class.lineno - 1],
+                "    # continued: the other members of this class are translated separately"
+            ]
+        code = "\n\n".join(part
+                           for part in [preamble, "\n".join(header + body).strip("\n")] if part)
+        chunks.append(PythonChunk(code, start, end, symbols, has_main="__main__" in symbols))
+    return chunks
+
+
+def _stub(node: ast.AST) -> ast.AST:
+    """A definition with its body replaced by its docstring and `...`."""
+    stub = copy.copy(node)
+    docstring = ast.get_docstring(node, clean=False)
+    stub.body = ([ast.Expr(ast.Constant(docstring))] if docstring
+                 else []) + [ast.Expr(ast.Constant(...))]
+    return stub
+
+
+def outline(python_code: str, chunk: PythonChunk) -> str:
+    """Signatures of the definitions of a module outside a chunk, so it can refer to them."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return ""
+
+    inside = lambda node: chunk.start_line <= node.lineno <= chunk.end_line
+    stubs = []
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)) and not inside(node):
+            stubs.append(_stub(node))
+        elif isinstance(node, ast.ClassDef):
+            members = [
+                _stub(child)
+                if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)) else child
+                for child in node.body
+                if isinstance(child,
+                              (ast.FunctionDef, ast.AsyncFunctionDef, ast.AnnAssign, ast.Assign))
+                and not inside(child)
+            ]
+            if members or not inside(node):
+                stub = copy.copy(node)
+                stub.body = members or [ast.Expr(ast.Constant(...))]
+                stubs.append(stub)
+        elif isinstance(node, (ast.Assign, ast.AnnAssign)) and not inside(node):
+            stubs.append(node)
+    return "\n".join(ast.unparse(stub) for stub in stubs)
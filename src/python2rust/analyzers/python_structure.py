@@ -0,0 +1,202 @@
+import ast
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Union
+
+from .python_symbols import is_main_guard
+
+FunctionNode = Union[ast.FunctionDef, ast.AsyncFunctionDef]
+
+# Python feature -> how its Rust translation usually goes, given with the lines using it
+FEATURE_HINTS = {
+    "generator": "functions that yield; an `impl Iterator` or a struct implementing Iterator",
+    "async": "async functions and await; async fn with an executor such as tokio",
+    "context manager": "with blocks; scopes whose values are dropped at their end, or Drop impls",
+    "exception handling": "try/except; Result and the ? operator, matching error variants",
+    "raise": "raised exceptions; Err values of an error enum",
+    "dataclass": "dataclasses; structs deriving Debug, Clone and PartialEq",
+    "property": "properties; getter methods",
+    "operator overloading": "dunder operator methods; std::ops and comparison trait impls",
+    "closure": "nested functions and lambdas; closures or private functions",
+    "nonlocal": "nonlocal writes; closures borrowing mutably or explicit state",
+    "global": "global writes; passed state, or a static behind a Mutex or OnceLock",
+    "comprehension": "comprehensions; iterator chains collecting into the same collection",
+    "star unpacking": "star unpacking; slices and explicit splitting",
+    "walrus": "assignment expressions; a let before the condition",
+    "loop else": "else clauses of loops; a flag or an Option found by the loop",
+    "mutable default argument": "mutable defaults shared between calls; a fresh value per call",
+    "slice step": "slices with a step; step_by or rev on iterators",
+}
+OPERATOR_METHODS = {
+    "__add__", "__sub__", "__mul__", "__truediv__", "__floordiv__", "__mod__", "__pow__", "__neg__",
+    "__eq__", "__ne__", "__lt__", "__le__", "__gt__", "__ge__", "__hash__", "__getitem__",
+    "__setitem__", "__contains__", "__len__", "__iter__", "__next__", "__call__", "__bool__",
+    "__and__", "__or__", "__xor__", "__invert__", "__lshift__", "__rshift__", "__matmul__"
+}
+MAX_LINES_SHOWN = 5
+
+
+@dataclass
+class FunctionSignature:
+    """A function or method with its parameters, annotations and decorators as written."""
+    qualname: str
+    signature: str
+    line: int
+    decorators: List[str] = field(default_factory=list)
+    is_async: bool = False
+
+
+@dataclass
+class ModuleStructure:
+    """What the AST of a module says before any model reads it."""
+    imports: List[str] = field(default_factory=list)
+    classes: Dict[str, List[str]] = field(default_factory=dict)  # name -> bases
+    functions: List[FunctionSignature] = field(default_factory=list)
+    constants: List[str] = field(default_factory=list)
+    # Feature of FEATURE_HINTS -> lines using it
+    features: Dict[str, List[int]] = field(default_factory=dict)
+    main_guard: bool = False
+
+
+def _signature(node: FunctionNode) -> str:
+    prefix = "async def" if isinstance(node, ast.AsyncFunctionDef) else "def"
+    returns = f" -> {ast.unparse(node.returns)}" if node.returns else ""
+    return f"{prefix} {node.name}({ast.unparse(node.args)}){returns}"
+
+
+def _walk_own(body: List[ast.stmt]):
+    """The nodes of a function body, without those of nested functions and classes."""
+    stack: List[ast.AST] = list(reversed(body))
+    while stack:
+        node = stack.pop()
+        yield node
+        if not isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef, ast.Lambda)):
+            stack.extend(reversed(list(ast.iter_child_nodes(node))))
+
+
+def _is_dataclass(decorator: ast.expr) -> bool:
+    target = decorator.func if isinstance(decorator, ast.Call) else decorator
+    name = target.attr if isinstance(target, ast.Attribute) else getattr(target, "id", "")
+    return name == "dataclass"
+
+
+def analyze_structure(python_code: str) -> Optional[ModuleStructure]:
+    """Imports, classes, function signatures, constants and the features of a module that
+    shape its translation; None when the code does not parse."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return None
+
+    structure = ModuleStructure()
+
+    def feature(name: str, node: ast.AST) -> None:
+        lines = structure.features.setdefault(name, [])
+        if node.lineno not in lines:
+            lines.append(node.lineno)
+
+    def function(node: FunctionNode, qualname: str, nested: bool) -> None:
+        structure.functions.append(FunctionSignature(
+            qualname, _signature(node), node.lineno,
+            [ast.unparse(decorator) for decorator in node.decorator_list],
+            isinstance(node, ast.AsyncFunctionDef)))
+        if nested:
+            feature("closure", node)
+        if isinstance(node, ast.AsyncFunctionDef):
+            feature("async", node)
+        if any(isinstance(default, (ast.List, ast.Dict, ast.Set))
+               for default in [*node.args.defaults, *node.args.kw_defaults] if default is not None):
+            feature("mutable default argument", node)
+        for child in _walk_own(node.body):
+            if isinstance(child, (ast.Yield, ast.YieldFrom)):
+                feature("generator", node)
+            elif isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                function(child, f"{qualname}.{child.name}", nested=True)
+            elif isinstance(child, ast.Nonlocal):
+                feature("nonlocal", child)
+            elif isinstance(child, ast.Global):
+                feature("global", child)
+
+    for node in tree.body:
+        if isinstance(node, (ast.Import, ast.ImportFrom)):
+            structure.imports.append(ast.unparse(node))
+        elif isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            function(node, node.name, nested=False)
+        elif isinstance(node, ast.ClassDef):
+            structure.classes[node.name] = [ast.unparse(base) for base in node.bases]
+            if any(_is_dataclass(decorator) for decorator in node.decorator_list):
+                feature("dataclass", node)
+            for child in node.body:
+                if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    function(child, f"{node.name}.{child.name}", nested=False)
+                    if child.name in OPERATOR_METHODS:
+                        feature("operator overloading", child)
+                    if any(ast.unparse(decorator).endswith(("property", ".setter"))
+                           for decorator in child.decorator_list):
+                        feature("property", child)
+        elif isinstance(node, (ast.Assign, ast.AnnAssign)):
+            targets = node.targets if isinstance(node, ast.Assign) else [node.target]
+            structure.constants.extend(
+                target.id for target in targets if isinstance(target, ast.Name)
+                and target.id.isupper())
+        elif isinstance(node, ast.If) and is_main_guard(node.test):
+            structure.main_guard = True
+
+    for node in ast.walk(tree):
+        if isinstance(node, (ast.With, ast.AsyncWith)):
+            feature("context manager", node)
+        elif isinstance(node, ast.Try):
+            feature("exception handling", node)
+        elif isinstance(node, ast.Raise):
+            feature("raise", node)
+        elif isinstance(node, ast.Await):
+            feature("async", node)
+        elif isinstance(node, ast.Lambda):
+            feature("closure", node)
+        elif isinstance(node, (ast.ListComp, ast.SetComp, ast.DictComp, ast.GeneratorExp)):
+            feature("comprehension", node)
+        elif isinstance(node, ast.Starred) and isinstance(node.ctx, ast.Store):
+            feature("star unpacking", node)
+        elif isinstance(node, ast.NamedExpr):
+            feature("walrus", node)
+        elif isinstance(node, (ast.For, ast.While, ast.AsyncFor)) and node.orelse:
+            feature("loop else", node)
+        elif isinstance(node, ast.Slice) and node.step is not None:
+            feature("slice step", node)
+    for lines in structure.features.values():
+        lines.sort()
+    return structure
+
+
+def structure_summary(structure: ModuleStructure) -> str:
+    """The structure of a module as prompt context: its API to keep and the
+    features to translate."""
+    sections = []
+    if structure.imports:
+        sections.append("Imports:\n" + "\n".join(f"- `{line}`" for line in structure.imports))
+    if structure.classes:
+        sections.append("Classes:\n" + "\n".join(
+            f"- `{name}`" + (f" (bases {', '.join(bases)})" if bases else "")
+            for name, bases in structure.classes.items()))
+    if structure.functions:
+        sections.append("Functions, line and signature:\n" + "\n".join(
+            f"- {function.qualname} (line {function.line}): `{function.signature}`"
+            + (f", decorated {', '.join(f'@{name}' for name in function.decorators)}"
+               if function.decorators else "")
+            for function in structure.functions))
+    if structure.constants:
+        sections.append(f"Module constants: {', '.join(structure.constants)}")
+    if structure.main_guard:
+        sections.append("The module runs as a script under `if __name__ == \"__main__\":`.")
+    if structure.features:
+        shown = []
+        for name, hint in FEATURE_HINTS.items():
+            lines = structure.features.get(name)
+            if not lines:
+                continue
+            where = ", ".join(str(line) for line in lines[:MAX_LINES_SHOWN])
+            more = (f" and {len(lines) - MAX_LINES_SHOWN} more"
+                    if len(lines) > MAX_LINES_SHOWN else "")
+            shown.append(f"- {hint} (lines {where}{more})")
+        sections.append("Python features used, with their usual Rust translation:\n"
+                        + "\n".join(shown))
+    return "\n\n".join(sections)
@@ -0,0 +1,52 @@
+import ast
+from dataclasses import dataclass
+from typing import List, Optional
+
+
+@dataclass
+class PythonSymbol:
+    """A function, class or method definition in Python source."""
+    name: str
+    qualname: str
+    kind: str  # function, class, method, main
+    start_line: int
+    end_line: int
+    parent: Optional[str] = None
+
+
+def collect_symbols(python_code: str) -> List[PythonSymbol]:
+    """Top-level functions and classes, and the methods of those classes."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+
+    symbols = []
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            symbols.append(PythonSymbol(node.name, node.name, "function",
+                                        node.lineno, node.end_lineno))
+        elif isinstance(node, ast.ClassDef):
+            symbols.append(PythonSymbol(node.name, node.name, "class",
+                                        node.lineno, node.end_lineno))
+            for child in node.body:
+                if isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    symbols.append(PythonSymbol(
+                        child.name, f"{node.name}.{child.name}", "method",
+                        child.lineno, child.end_lineno, parent=node.name))
+        elif isinstance(node, ast.If) and is_main_guard(node.test):
+            symbols.append(PythonSymbol("__main__", "__main__", "main",
+                                        node.lineno, node.end_lineno))
+    return symbols
+
+
+def is_main_guard(test: ast.expr) -> bool:
+    """Match `if __name__ == "__main__":`."""
+    return (
+        isinstance(test, ast.Compare)
+        and isinstance(test.left, ast.Name)
+        and test.left.id == "__name__"
+        and len(test.comparators) == 1
+        and isinstance(test.comparators[0], ast.Constant)
+        and test.comparators[0].value == "__main__"
+    )
@@ -0,0 +1,99 @@
+import ast
+from pathlib import Path
+from typing import List, Set
+
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+TEST_DIRS = ("tests", "test")
+SKIPPED_DIRS = {"__pycache__", "venv", ".venv", "build", "dist"}
+
+
+def is_test_file(path: Path) -> bool:
+    """Check whether a Python file is part of a test suite rather than the program."""
+    return (
+        path.name == "conftest.py"
+        or path.name.startswith("test_")
+        or path.stem.endswith("_test")
+    )
+
+
+def _imported_names(path: Path) -> Set[str]:
+    """Dotted names a file imports, with the modules of its from-imports."""
+    try:
+        tree = ast.parse(path.read_text(), filename=str(path))
+    except (OSError, SyntaxError, ValueError) as e:
+        logger.warning(f"Could not parse imports of {path}: {e}")
+        return set()
+    names = set()
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            names.update(alias.name for alias in node.names)
+        elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+            names.add(node.module)
+            names.update(f"{node.module}.{alias.name}" for alias in node.names)
+    return names
+
+
+def uses_hypothesis(python_code: str) -> bool:
+    """Whether Python code imports hypothesis, for property-based tests."""
+    try:
+        tree = ast.parse(python_code)
+    except (SyntaxError, ValueError):
+        return False
+    for node in ast.walk(tree):
+        if (isinstance(node, ast.Import)
+                and any(alias.name.split(".")[0] == "hypothesis" for alias in node.names)):
+            return True
+        if isinstance(node, ast.ImportFrom) and (node.module or "").split(".")[0] == "hypothesis":
+            return True
+    return False
+
+
+def _test_files(base: Path) -> List[Path]:
+    files = []
+    for path in sorted(base.rglob("*.py")):
+        parts = path.relative_to(base).parts[:-1]
+        if any(part in SKIPPED_DIRS or part.startswith(".") for part in parts):
+            continue
+        if is_test_file(path) and path.name != "conftest.py":
+            files.append(path)
+    return files
+
+
+def find_python_tests(root: Path, module: str) -> List[Path]:
+    """Pytest files exercising a module of the source root, after the conftest.py files
+    their fixtures may come from.
+
+    A test file is one under the root, or under a tests/ directory next to it, that
+    imports the module or is named after it (test_<name>.py, <name>_test.py).
+    """
+    root = Path(root)
+    bases = [root, *(root.parent / name for name in TEST_DIRS if (root.parent / name).is_dir())]
+    stem = module.split(".")[-1]
+    prefixes = (module, f"{root.name}.{module}")
+
+    tests: List[Path] = []
+    for base in bases:
+        for path in _test_files(base):
+            named = path.name in (f"test_{stem}.py", f"{stem}_test.py")
+            imports = any(
+                name == prefix or name.startswith(f"{prefix}.")
+                for name in _imported_names(path) for prefix in prefixes
+            )
+            if (named or imports) and path not in tests:
+                tests.append(path)
+
+    conftests: List[Path] = []
+    for path in tests:
+        base = next(base for base in bases if base in path.parents)
+        directory = path.parent
+        while True:
+            conftest = directory / "conftest.py"
+            if conftest.is_file() and conftest not in conftests:
+                conftests.append(conftest)
+            if directory == base:
+                break
+            directory = directory.parent
+    return sorted(conftests) + tests
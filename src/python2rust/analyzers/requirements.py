@@ -0,0 +1,154 @@
+import ast
+import re
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from ..config.crate_mappings import CrateMapping, normalize_name
+from ..config.project_config import tomllib
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+# name[extras] constraint ; marker, as in requirements.txt and [project] dependencies
+REQUIREMENT = re.compile(r"^\s*([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*([^;]*)")
+
+
+@dataclass
+class Requirement:
+    """A dependency of the Python project and its version constraint."""
+    name: str
+    constraint: str = ""  # e.g. >=2.31,<3
+    source: str = ""  # requirements.txt, pyproject.toml
+
+
+@dataclass
+class CrateDependency:
+    """The crate replacing a Python library, with the [dependencies] line declaring it."""
+    python: str  # the library, as mapped
+    crate: str
+    line: str
+    requirement: Optional[Requirement] = None  # when the project requires the library
+    alternatives: List[str] = field(default_factory=list)  # crates mapped to others in its category
+    aliases: List[str] = field(default_factory=list)  # other import and distribution names
+
+    @property
+    def identifier(self) -> str:
+        """The name of the crate in Rust paths."""
+        return self.crate.replace("-", "_")
+
+    def describe(self) -> str:
+        required = self.python
+        if self.requirement is not None:
+            required = f"{self.requirement.name}{self.requirement.constraint}"
+        return f"{required} -> {self.line}"
+
+
+def requirement_lines(lines: List[str], source: str = "") -> List[Requirement]:
+    """The requirements of requirements.txt lines, skipping options and comments."""
+    requirements = []
+    for line in lines:
+        line = line.split("#", 1)[0].strip()
+        if not line or line.startswith("-"):
+            continue
+        match = REQUIREMENT.match(line)
+        if match:
+            requirements.append(Requirement(match.group(1),
+                                            re.sub(r"\s+", "", match.group(2)), source))
+    return requirements
+
+
+def _poetry_constraint(spec: object) -> str:
+    if isinstance(spec, dict):
+        spec = spec.get("version", "")
+    return "" if spec in ("*", None) else str(spec)
+
+
+def project_requirements(root: Path) -> List[Requirement]:
+    """The dependencies declared by pyproject.toml ([project] and [tool.poetry]) and the
+    requirements*.txt files of a project, each once, in name order."""
+    root = Path(root)
+    requirements = []
+    pyproject = root / "pyproject.toml"
+    if pyproject.exists():
+        try:
+            with open(pyproject, "rb") as f:
+                data = tomllib.load(f)
+        except (OSError, tomllib.TOMLDecodeError) as e:
+            logger.warning(f"Could not read {pyproject}: {e}")
+            data = {}
+        project = data.get("project", {}).get("dependencies", [])
+        requirements.extend(requirement_lines(project, pyproject.name))
+        poetry = data.get("tool", {}).get("poetry", {}).get("dependencies", {})
+        requirements.extend(Requirement(name, _poetry_constraint(spec), pyproject.name)
+                            for name, spec in poetry.items() if name.lower() != "python")
+    for path in sorted(root.glob("requirements*.txt")):
+        requirements.extend(requirement_lines(path.read_text().splitlines(), path.name))
+    unique: Dict[str, Requirement] = {}
+    for requirement in requirements:
+        unique.setdefault(normalize_name(requirement.name), requirement)
+    return sorted(unique.values(), key=lambda requirement: requirement.name.lower())
+
+
+def find_requirements_root(source_root: Path) -> Optional[Path]:
+    """The nearest directory from a source root up declaring the project's requirements."""
+    for directory in [Path(source_root).resolve(), *Path(source_root).resolve().parents]:
+        if (directory / "pyproject.toml").exists() or any(directory.glob("requirements*.txt")):
+            return directory
+    return None
+
+
+def resolve_requirements(
+    requirements: List[Requirement], mappings: List[CrateMapping]
+) -> Tuple[List[CrateDependency], List[Requirement]]:
+    """The crate of every mapped library, the requirements naming them, and the requirements
+    without a mapping. Later mappings of a library replace earlier ones."""
+    by_library: Dict[str, CrateMapping] = {}
+    for mapping in mappings:
+        by_library.pop(next((library for library in by_library
+                             if normalize_name(library) == normalize_name(mapping.python)), ""),
+                       None)
+        by_library[mapping.python] = mapping
+    index = {normalize_name(name): mapping for mapping in by_library.values()
+             for name in [mapping.python, *mapping.aliases]}
+    required: Dict[str, Requirement] = {}
+    unmapped = []
+    for requirement in requirements:
+        mapping = index.get(normalize_name(requirement.name))
+        if mapping is None:
+            unmapped.append(requirement)
+        else:
+            required.setdefault(mapping.python, requirement)
+    dependencies = []
+    for library, mapping in by_library.items():
+        alternatives = sorted({other.crate for other in by_library.values()
+                               if other.category == mapping.category
+                               and other.crate != mapping.crate})
+        dependencies.append(CrateDependency(library, mapping.crate, mapping.dependency(),
+                                            required.get(library), alternatives,
+                                            list(mapping.aliases)))
+    return dependencies, unmapped
+
+
+def imported_dependencies(python_code: str,
+                          dependencies: List[CrateDependency]) -> List[CrateDependency]:
+    """The dependencies of the libraries a module imports, by their import or dotted names."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    imported = set()
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            names = [alias.name for alias in node.names]
+        elif isinstance(node, ast.ImportFrom) and node.module and not node.level:
+            names = [node.module]
+        else:
+            continue
+        for name in names:
+            parts = name.split(".")
+            imported.update(normalize_name(".".join(parts[:end]))
+                            for end in range(1, len(parts) + 1))
+    return [dependency for dependency in dependencies
+            if any(normalize_name(name) in imported
+                   for name in [dependency.python, *dependency.aliases])]
@@ -0,0 +1,149 @@
+import ast
+from dataclasses import dataclass
+from typing import List
+
+DYNAMIC_BUILTINS = {
+    "eval": "eval() executes code built at runtime, which has no Rust equivalent",
+    "exec": "exec() executes code built at runtime, which has no Rust equivalent",
+    "getattr": "getattr() looks up attributes by name at runtime",
+    "setattr": "setattr() adds or changes attributes at runtime",
+    "delattr": "delattr() removes attributes at runtime",
+    "__import__": "__import__() imports modules chosen at runtime",
+    "globals": "globals() exposes the module namespace as a dict",
+    "locals": "locals() exposes local variables as a dict",
+    "vars": "vars() exposes an object's attributes as a dict",
+}
+
+RISKY_MODULES = {
+    "ctypes": "ctypes calls into C libraries and needs manual FFI bindings",
+    "pickle": "pickle serializes arbitrary Python objects",
+    "importlib": "importlib loads modules dynamically",
+    "threading": "threads sharing Python objects need explicit synchronization in Rust",
+    "multiprocessing": "multiprocessing relies on pickling and process forking",
+    "inspect": "inspect reflects on live functions, frames and signatures",
+}
+
+# Attributes whose use reflects on or rewires objects at runtime
+REFLECTIVE_ATTRIBUTES = {
+    "__dict__": "reads or writes the attribute dict of an object",
+    "__subclasses__": "lists subclasses at runtime",
+    "_getframe": "inspects the call stack",
+}
+SEVERITIES = ("low", "medium", "high")
+# Fix attempts added to every workflow per finding of each severity, at most MAX_EXTRA_FIX_ATTEMPTS
+EXTRA_FIX_ATTEMPTS = {"low": 0, "medium": 1, "high": 2}
+MAX_EXTRA_FIX_ATTEMPTS = 4
+
+
+@dataclass
+class RiskFinding:
+    """A construct that is hard to translate faithfully."""
+    line: int
+    construct: str
+    message: str
+    severity: str = "medium"  # low, medium, high
+
+
+def scan_risks(python_code: str) -> List[RiskFinding]:
+    """Find dynamic or hard-to-translate Python constructs."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError as e:
+        return [RiskFinding(e.lineno or 0, "syntax error", str(e), "high")]
+
+    # Names of imported modules and objects, and of the module's classes, patched from outside
+    patchable = {
+        (alias.asname or alias.name).split(".")[0]
+        for node in ast.walk(tree) if isinstance(node, (ast.Import, ast.ImportFrom))
+        for alias in node.names
+    }
+    patchable.update(node.name for node in tree.body if isinstance(node, ast.ClassDef))
+
+    def patched(target: ast.expr) -> bool:
+        return isinstance(target, ast.Attribute) and isinstance(target.value, ast.Name) \
+            and target.value.id in patchable
+
+    findings = []
+    for node in ast.walk(tree):
+        if (isinstance(node, ast.Call) and isinstance(node.func, ast.Name)
+                and node.func.id in DYNAMIC_BUILTINS):
+            if node.func.id == "setattr" and node.args and isinstance(node.args[0], ast.Name) \
+                    and node.args[0].id in patchable:
+                findings.append(RiskFinding(
+                    node.lineno, "monkeypatching",
+                    f"setattr() replaces an attribute of {node.args[0].id}", "high"))
+                continue
+            severity = "high" if node.func.id in ("eval", "exec", "__import__") else "medium"
+            findings.append(RiskFinding(node.lineno, node.func.id,
+                                        DYNAMIC_BUILTINS[node.func.id], severity))
+        elif isinstance(node, ast.Call) and isinstance(node.func,
+                                                       ast.Name) and node.func.id == "type" \
+                and len(node.args) == 3:
+            findings.append(RiskFinding(node.lineno, "type()",
+                                        "type() with three arguments creates a class "
+                                        "at runtime", "high"))
+        elif isinstance(node, (ast.Assign, ast.AugAssign, ast.AnnAssign)):
+            targets = node.targets if isinstance(node, ast.Assign) else [node.target]
+            for target in targets:
+                if patched(target):
+                    findings.append(RiskFinding(
+                        node.lineno, "monkeypatching",
+                        f"assigning {target.value.id}.{target.attr} changes it for all its users",
+                        "high"))
+                elif isinstance(target, ast.Attribute) and target.attr == "__class__":
+                    findings.append(RiskFinding(
+                        node.lineno, "__class__ assignment",
+                        "changes the class of an object at runtime", "high"))
+        elif isinstance(node, ast.Attribute) and node.attr in REFLECTIVE_ATTRIBUTES \
+                and isinstance(node.ctx, ast.Load):
+            findings.append(RiskFinding(
+                node.lineno, node.attr, REFLECTIVE_ATTRIBUTES[node.attr],
+                "high" if node.attr == "_getframe" else "medium"))
+        elif isinstance(node, (ast.Import, ast.ImportFrom)):
+            names = ([alias.name for alias in node.names] if isinstance(node, ast.Import)
+                     else [node.module or ""])
+            for name in names:
+                root = name.split(".")[0]
+                if root in RISKY_MODULES:
+                    findings.append(RiskFinding(node.lineno, f"import {root}", RISKY_MODULES[root]))
+            if isinstance(node, ast.ImportFrom) and any(alias.name == "*" for alias in node.names):
+                findings.append(RiskFinding(node.lineno, "import *",
+                                            "star imports hide which names are used", "low"))
+        elif isinstance(node, ast.ClassDef):
+            if any(keyword.arg == "metaclass" for keyword in node.keywords):
+                findings.append(RiskFinding(node.lineno, "metaclass",
+                                            f"class {node.name} uses a metaclass", "high"))
+            if len(node.bases) > 1:
+                findings.append(RiskFinding(
+                    node.lineno, "multiple inheritance",
+                    f"class {node.name} has {len(node.bases)} base classes; "
+                    "Rust has no inheritance"))
+            for item in node.body:
+                if (isinstance(item, ast.FunctionDef)
+                        and item.name in ("__getattr__", "__getattribute__", "__setattr__")):
+                    findings.append(RiskFinding(
+                        item.lineno, item.name,
+                        f"{node.name}.{item.name} intercepts attribute access", "high"))
+        elif isinstance(node, ast.Global):
+            findings.append(RiskFinding(
+                node.lineno, "global", f"mutable module state ({', '.join(node.names)})", "low"))
+        elif isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            if node.args.vararg or node.args.kwarg:
+                findings.append(RiskFinding(
+                    node.lineno, "variadic arguments",
+                    f"{node.name} takes *args/**kwargs, which need explicit Rust types", "low"))
+    return sorted(findings, key=lambda f: f.line)
+
+
+def extra_fix_attempts(findings: List[RiskFinding]) -> int:
+    """Fix attempts to add to every workflow of a file with these findings."""
+    return min(MAX_EXTRA_FIX_ATTEMPTS,
+               sum(EXTRA_FIX_ATTEMPTS[finding.severity] for finding in findings))
+
+
+def risk_warnings(findings: List[RiskFinding]) -> List[str]:
+    """The medium and high risk findings, as report warnings."""
+    return [
+        f"line {finding.line} [{finding.severity}] {finding.construct}: {finding.message}"
+        for finding in findings if SEVERITIES.index(finding.severity) >= SEVERITIES.index("medium")
+    ]
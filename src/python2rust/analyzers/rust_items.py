@@ -0,0 +1,80 @@
+import re
+from dataclasses import dataclass
+from typing import List, Optional
+
+ITEM_PATTERN = re.compile(
+    r"^(pub(\([^)]*\))?\s+)?((async|const|unsafe|extern\s+\"[^\"]*\")\s+)*"
+    r"(?P<kind>fn|struct|enum|trait|type|const|static|mod)\s+(?P<name>[A-Za-z_][A-Za-z0-9_]*)")
+IMPL_PATTERN = re.compile(
+    r"^(unsafe\s+)?impl(<[^>]*>)?\s+(?:(?P<trait>[\w:]+(<[^>]*>)?)\s+for\s+)?"
+    r"(?P<name>[A-Za-z_][\w:]*)")
+
+
+@dataclass
+class RustItem:
+    """An item of a Rust source file with its 1-based line span."""
+    name: str
+    kind: str  # fn, struct, enum, trait, type, const, static, mod, impl, method
+    start_line: int
+    end_line: int
+    parent: Optional[str] = None  # impl target for methods
+    trait: Optional[str] = None  # implemented trait for impl blocks and their methods
+
+
+def _strip_comment(line: str) -> str:
+    return line.split("//", 1)[0]
+
+
+def scan_items(rust_code: str) -> List[RustItem]:
+    """Top-level items and impl methods, found by tracking brace depth.
+
+    This is a line-based scan meant for generated code; braces inside string
+    literals can throw the spans off.
+    """
+    lines = rust_code.splitlines()
+    items: List[RustItem] = []
+    open_items: List[list] = []  # [item, depth it returns to when closed, body opened]
+    impl: Optional[RustItem] = None
+    depth = 0
+
+    for number, raw in enumerate(lines, start=1):
+        line = _strip_comment(raw)
+        stripped = line.strip()
+        item = None
+        if depth == 0:
+            impl_match = IMPL_PATTERN.match(stripped)
+            item_match = None if impl_match else ITEM_PATTERN.match(stripped)
+            if impl_match:
+                trait = impl_match.group("trait")
+                item = RustItem(impl_match.group("name"), "impl", number, number,
+                                trait=trait.split("<")[0] if trait else None)
+                impl = item
+            elif item_match:
+                item = RustItem(item_match.group("name"), item_match.group("kind"), number, number)
+        elif impl is not None and depth == 1:
+            item_match = ITEM_PATTERN.match(stripped)
+            if item_match and item_match.group("kind") == "fn":
+                item = RustItem(item_match.group("name"), "method", number, number,
+                                parent=impl.name, trait=impl.trait)
+
+        if item is not None:
+            items.append(item)
+            open_items.append([item, depth, False])
+
+        depth += line.count("{") - line.count("}")
+
+        # Close items whose body ended on this line, or that have no body
+        while open_items:
+            current, closes_at, opened = open_items[-1]
+            if not opened:
+                if "{" in line:
+                    open_items[-1][2] = opened = True
+                elif ";" not in line:
+                    break
+            if opened and depth > closes_at:
+                break
+            current.end_line = number
+            open_items.pop()
+            if current is impl:
+                impl = None
+    return items
@@ -0,0 +1,194 @@
+"""
+Code policies of generated Rust: constructs it must not use outside tests, the longest function
+it may have and how it handles errors. The code is analyzed by a small syn-based program, built
+with cargo on first use.
+"""
+import asyncio
+import hashlib
+import json
+import os
+import re
+import shutil
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from ..config.settings import CodePolicies
+from ..utils.cargo_manifest import section_entries
+from .rust_items import scan_items
+from .rust_quality import code_lines, scan_rust_quality
+
+ANALYZER_DIR = Path(__file__).parent / "policy_analyzer"
+ANALYZER_NAME = "python2rust-policy-analyzer"
+# Macros of the no_panic policy
+PANIC_MACROS = ("panic", "todo", "unimplemented")
+# How code of each error style handles errors, as the prompts and fix requests ask for it
+ERROR_STYLES = {
+    "anyhow": "functions that can fail return `anyhow::Result<T>` and build their errors with "
+              "`anyhow!`, `bail!` and `.context()`; no thiserror, String or Box<dyn Error> errors",
+    "thiserror": "functions that can fail return `Result<T, E>` with error enums deriving "
+                 "`thiserror::Error`, those of the project's error module where it has one; no "
+                 "anyhow, String or Box<dyn Error> errors",
+    "std": "error types implement `std::fmt::Display` and `std::error::Error` by hand, or "
+           "functions return `Box<dyn std::error::Error>`; no anyhow, thiserror or String errors",
+}
+# The error crates each style leaves out
+FORBIDDEN_ERROR_CRATES = {"anyhow": ("thiserror",), "thiserror": ("anyhow",),
+                          "std": ("anyhow", "thiserror")}
+ERROR_CRATE = re.compile(r"\b(anyhow|thiserror)\b")
+# Results whose error is a String, a &str or a boxed error rather than the project's types
+AD_HOC_ERROR = re.compile(
+    r"\bResult\s*<[^;{]*?,\s*(String|&'static\s+str|Box\s*<\s*dyn\s+(std::error::)?Error\b)")
+
+
+@dataclass
+class PolicyViolation:
+    """A construct or function of the code breaking one of the policies, on line
+    0 for Cargo.toml."""
+    line: int
+    # no_unwrap, no_expect, no_unsafe, no_panic, forbidden_macros, max_function_lines or error_style
+    policy: str
+    message: str
+
+    def __str__(self) -> str:
+        return f"line {self.line}: {self.message}" if self.line else self.message
+
+
+def analyzer_cache_dir() -> Path:
+    return Path(os.environ.get("XDG_CACHE_HOME",
+                               Path.home() / ".cache")) / "python2rust" / "policy-analyzer"
+
+
+def _analyzer_digest() -> str:
+    digest = hashlib.sha256()
+    for path in sorted(ANALYZER_DIR.rglob("*")):
+        if path.is_file() and "target" not in path.parts:
+            digest.update(path.relative_to(ANALYZER_DIR).as_posix().encode())
+            digest.update(path.read_bytes())
+    return digest.hexdigest()[:16]
+
+
+async def build_analyzer(cache_dir: Optional[Path] = None, timeout: Optional[float] = 300) -> Path:
+    """The analyzer binary, built in the cache directory unless its sources were built already.
+
+    The sources are copied first, since the installed package may not be writable. Raises
+    RuntimeError when the build fails, for instance without cargo or network.
+    """
+    work_dir = (cache_dir or analyzer_cache_dir()) / _analyzer_digest()
+    binary = work_dir / "target" / "release" / (ANALYZER_NAME + (".exe" if os.name == "nt" else ""))
+    if binary.exists():
+        return binary
+    shutil.copytree(ANALYZER_DIR, work_dir / "crate", dirs_exist_ok=True,
+                    ignore=shutil.ignore_patterns("target", "Cargo.lock"))
+    try:
+        process = await asyncio.create_subprocess_exec(
+            "cargo", "build", "--release", "--quiet", "--target-dir", str(work_dir / "target"),
+            cwd=work_dir / "crate",
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+    except OSError as e:
+        raise RuntimeError(f"cargo is not available: {e}")
+    try:
+        _, stderr = await asyncio.wait_for(process.communicate(), timeout=timeout)
+    except asyncio.TimeoutError:
+        process.kill()
+        await process.wait()
+        raise RuntimeError(f"building the policy analyzer took more than {timeout:g} seconds")
+    if process.returncode != 0 or not binary.exists():
+        raise RuntimeError("the policy analyzer does not build: "
+                           f"{stderr.decode(errors='replace').strip()[-1000:]}")
+    return binary
+
+
+async def analyze_rust(rust_code: str, binary: Path) -> Dict[str, Any]:
+    """Functions and restricted constructs of the code, as reported by the analyzer.
+
+    Raises ValueError when the code does not parse.
+    """
+    process = await asyncio.create_subprocess_exec(
+        str(binary),
+        stdin=asyncio.subprocess.PIPE,
+        stdout=asyncio.subprocess.PIPE,
+        stderr=asyncio.subprocess.PIPE
+    )
+    stdout, stderr = await process.communicate(rust_code.encode())
+    if process.returncode != 0:
+        raise ValueError(f"Rust code does not parse: {stderr.decode(errors='replace').strip()}")
+    return json.loads(stdout)
+
+
+def scanned_facts(rust_code: str) -> Dict[str, Any]:
+    """The analyzer's report approximated by the line-based scans, used when it cannot be built.
+
+    Test modules are skipped, so every fact is outside tests.
+    """
+    constructs = []
+    for finding in scan_rust_quality(rust_code):
+        if finding.construct in ("unwrap", "expect"):
+            constructs.append({"kind": "call", "name": finding.construct,
+                               "line": finding.line, "test": False})
+        elif finding.construct == "unsafe":
+            constructs.append({"kind": "unsafe", "name": "block",
+                               "line": finding.line, "test": False})
+        else:
+            constructs.append({"kind": "macro", "name": finding.construct.rstrip("!"),
+                               "line": finding.line, "test": False})
+    functions = [
+        {"name": f"{item.parent}::{item.name}" if item.parent else item.name,
+         "start_line": item.start_line, "end_line": item.end_line, "test": False}
+        for item in scan_items(rust_code) if item.kind in ("fn", "method")
+    ]
+    return {"functions": functions, "constructs": constructs}
+
+
+def policy_violations(facts: Dict[str, Any], policies: CodePolicies) -> List[PolicyViolation]:
+    """Violations of the policies in an analyzer report, by line; test code is exempt."""
+    violations = []
+    for construct in facts.get("constructs", []):
+        if construct.get("test"):
+            continue
+        kind, name, line = construct["kind"], construct["name"], construct["line"]
+        if kind == "call" and name == "unwrap" and policies.no_unwrap:
+            violations.append(PolicyViolation(line, "no_unwrap", "`unwrap()` outside tests"))
+        elif kind == "call" and name == "expect" and policies.no_expect:
+            violations.append(PolicyViolation(line, "no_expect", "`expect()` outside tests"))
+        elif kind == "unsafe" and policies.no_unsafe:
+            code = "`unsafe` block" if name == "block" else f"`unsafe {name}`"
+            violations.append(PolicyViolation(line, "no_unsafe", f"{code} outside tests"))
+        elif kind == "macro" and name in PANIC_MACROS and policies.no_panic:
+            violations.append(PolicyViolation(line, "no_panic", f"`{name}!` outside tests"))
+        elif kind == "macro" and name in policies.forbidden_macros:
+            violations.append(PolicyViolation(line, "forbidden_macros", f"`{name}!` is forbidden"))
+    if policies.max_function_lines is not None:
+        for function in facts.get("functions", []):
+            lines = function["end_line"] - function["start_line"] + 1
+            if not function.get("test") and lines > policies.max_function_lines:
+                violations.append(PolicyViolation(
+                    function["start_line"], "max_function_lines",
+                    f"function `{function['name']}` is {lines} lines long, more than "
+                    f"{policies.max_function_lines}"))
+    return sorted(violations, key=lambda violation: violation.line)
+
+
+def error_style_violations(rust_code: str, toml_content: str, style: str) -> List[PolicyViolation]:
+    """Errors handled otherwise than the error style asks outside test modules: the error crates it
+    leaves out, in the code or the [dependencies] of Cargo.toml (line 0), and String errors, or
+    boxed ones but in the std style."""
+    forbidden = FORBIDDEN_ERROR_CRATES[style]
+    violations = [PolicyViolation(0, "error_style",
+                                  f"Cargo.toml depends on {crate}, which the {style} "
+                                                    "error style leaves out")
+                  for crate in section_entries(toml_content, "[dependencies]")
+                  if crate in forbidden]
+    for number, line in code_lines(rust_code):
+        crates = sorted({match.group(1) for match in ERROR_CRATE.finditer(line)} & set(forbidden))
+        violations.extend(PolicyViolation(number, "error_style",
+                                          f"`{crate}` in the {style} error style")
+                          for crate in crates)
+        ad_hoc = AD_HOC_ERROR.search(line)
+        if ad_hoc and not (style == "std" and ad_hoc.group(1).startswith("Box")):
+            error = "Box<dyn Error>" if ad_hoc.group(1).startswith("Box") else ad_hoc.group(1)
+            violations.append(PolicyViolation(number, "error_style",
+                                              f"{error} error in the {style} error style"))
+    return violations
@@ -0,0 +1,49 @@
+import re
+from typing import Iterator, List, Tuple
+
+from .risk import RiskFinding
+from .rust_items import _strip_comment
+
+# (construct, pattern, message, severity) for constructs generated code should avoid
+QUALITY_PATTERNS = [
+    ("unsafe", re.compile(r"\bunsafe\b"), "unsafe code bypasses the borrow checker", "high"),
+    ("todo!", re.compile(r"\b(todo|unimplemented)!"),
+     "part of the translation was left unimplemented", "high"),
+    ("panic!", re.compile(r"\bpanic!"),
+     "explicit panic where Python would raise an exception", "medium"),
+    ("unwrap", re.compile(r"\.unwrap\(\)"), "unwrap() panics on None or Err", "medium"),
+    ("expect", re.compile(r"\.expect\("), "expect() panics on None or Err", "low"),
+]
+
+STRING_LITERAL = re.compile(r'"(\\.|[^"\\])*"')
+TEST_MODULE = re.compile(r"^\s*#\[cfg\(test\)\]")
+
+
+def code_lines(rust_code: str) -> Iterator[Tuple[int, str]]:
+    """The numbered lines of generated Rust outside test modules, without string
+    literals and comments."""
+    in_tests, depth = False, 0
+    for number, raw_line in enumerate(rust_code.splitlines(), start=1):
+        line = _strip_comment(STRING_LITERAL.sub('""', raw_line))
+        if TEST_MODULE.match(line):
+            in_tests, depth = True, 0
+            continue
+        if in_tests:
+            depth += line.count("{") - line.count("}")
+            if depth <= 0 and "}" in line:
+                in_tests = False
+            continue
+        yield number, line
+
+
+def scan_rust_quality(rust_code: str) -> List[RiskFinding]:
+    """Find panics, unsafe blocks and unfinished code in generated Rust, outside test modules.
+
+    Like scan_items this is a line-based scan; string literals and comments are ignored.
+    """
+    findings = []
+    for number, line in code_lines(rust_code):
+        for construct, pattern, message, severity in QUALITY_PATTERNS:
+            if pattern.search(line):
+                findings.append(RiskFinding(number, construct, message, severity))
+    return findings
@@ -0,0 +1,105 @@
+import re
+from typing import List, Tuple
+
+ITEM_PATTERN = re.compile(
+    r"^pub(\([^)]*\))?\s+(async\s+|const\s+|unsafe\s+)*(fn|struct|enum|trait|type|const|static)\b")
+IMPL_PATTERN = re.compile(r"^impl\b")
+TYPE_PATTERN = re.compile(r"^pub(\([^)]*\))?\s+(struct|enum)\b")
+VALUE_PATTERN = re.compile(r"^pub(\([^)]*\))?\s+(const|static)\s+(?!fn\b)")
+
+
+def _header(lines: List[str], start: int) -> str:
+    """Join an item declaration up to its body or terminating semicolon."""
+    parts = []
+    for line in lines[start:]:
+        stripped = line.strip()
+        end = min((i for i in (stripped.find("{"), stripped.find(";")) if i >= 0), default=-1)
+        if end >= 0:
+            parts.append(stripped[:end].rstrip())
+            break
+        parts.append(stripped)
+    return " ".join(p for p in parts if p)
+
+
+def _public_items(lines: List[str]) -> List[Tuple[int, str]]:
+    """Line index and header of each public item, methods indented under their impl block."""
+    items = []
+    depth = 0
+    impl_depth = None
+    for index, line in enumerate(lines):
+        stripped = line.strip()
+        if impl_depth is None and depth == 0 and IMPL_PATTERN.match(stripped):
+            items.append((index, _header(lines, index)))
+            impl_depth = depth
+        elif ITEM_PATTERN.match(stripped):
+            if depth == 0:
+                items.append((index, _header(lines, index)))
+            elif impl_depth is not None and depth == impl_depth + 1:
+                items.append((index, f"    {_header(lines, index)}"))
+
+        depth += line.count("{") - line.count("}")
+        if impl_depth is not None and depth <= impl_depth and "}" in line:
+            impl_depth = None
+    # Drop impl headers that expose no public methods
+    return [
+        item for i, item in enumerate(items)
+        if not IMPL_PATTERN.match(item[1])
+        or (i + 1 < len(items) and items[i + 1][1].startswith("    "))
+    ]
+
+
+def extract_signatures(rust_code: str) -> List[str]:
+    """Public items of generated Rust code, without their bodies.
+
+    Methods are listed under the impl block they belong to, indented.
+    """
+    return [header for _, header in _public_items(rust_code.splitlines())]
+
+
+def _doc_comments(lines: List[str], index: int) -> List[str]:
+    """Doc comments and attributes right above an item."""
+    start = index
+    while start > 0 and lines[start - 1].strip().startswith(("///", "#[")):
+        start -= 1
+    return [line.strip() for line in lines[start:index]]
+
+
+def _body(lines: List[str], index: int) -> List[str]:
+    """An item from its declaration to its closing brace."""
+    depth = 0
+    for end in range(index, len(lines)):
+        depth += lines[end].count("{") - lines[end].count("}")
+        if depth <= 0 and ("}" in lines[end] or lines[end].rstrip().endswith(";")):
+            return [line.rstrip() for line in lines[index:end + 1]]
+    return [lines[index].rstrip()]
+
+
+def summarize_rust(rust_code: str) -> str:
+    """Digest of the public API of generated Rust code.
+
+    Keeps the module documentation, and for each public item its doc comments
+    and attributes with: the declaration of structs and enums, fields and
+    variants included; the signature of functions, methods and other items.
+    """
+    lines = rust_code.splitlines()
+    digest = [line.strip() for line in lines if line.strip().startswith("//!")]
+    in_impl = False
+    for index, header in _public_items(lines):
+        nested = header.startswith("    ")
+        if in_impl and not nested:
+            digest.append("}")
+            in_impl = False
+        indent = "    " if nested else ""
+        digest.extend(indent + line for line in _doc_comments(lines, index))
+        if IMPL_PATTERN.match(header):
+            digest.append(f"{header} {{")
+            in_impl = True
+        elif TYPE_PATTERN.match(header):
+            digest.extend(_body(lines, index))
+        else:
+            # Constants and statics without their values
+            digest.append(header.split(" = ")[0] + ";"
+                          if VALUE_PATTERN.match(header.strip()) else f"{header};")
+    if in_impl:
+        digest.append("}")
+    return "\n".join(digest)
@@ -0,0 +1,250 @@
+import json
+import re
+from dataclasses import asdict, dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from .naming import Renaming, camel_case, rust_identifier
+from .notebook import NotebookCell, notebook_cells
+from .python_symbols import PythonSymbol, collect_symbols
+from .risk import RiskFinding, scan_risks
+from .rust_items import RustItem, scan_items
+
+SOURCE_MAP_VERSION = 1
+
+# Rust names that Python special methods usually become
+SPECIAL_METHODS = {
+    "__init__": ["new"],
+    "__str__": ["fmt", "to_string"],
+    "__repr__": ["fmt"],
+    "__eq__": ["eq"],
+    "__lt__": ["partial_cmp", "cmp"],
+    "__hash__": ["hash"],
+    "__len__": ["len"],
+    "__iter__": ["iter", "into_iter"],
+    "__next__": ["next"],
+    "__call__": ["call"],
+    "__add__": ["add"],
+    "__exit__": ["drop"],
+}
+
+
+@dataclass
+class Span:
+    start_line: int
+    end_line: int
+    name: str
+    kind: str
+
+
+@dataclass
+class Mapping:
+    """A Rust item, the Python definition it was generated from and the prompt that wrote it."""
+    rust: Span
+    python: Optional[Span] = None
+    origin: Optional[str] = None  # e.g. "generation" or "build fix 2"
+    warnings: List[str] = field(default_factory=list)
+
+
+@dataclass
+class SourceMap:
+    """Provenance of the items of a generated Rust file."""
+    python_file: str
+    rust_file: str
+    mappings: List[Mapping] = field(default_factory=list)
+    unmapped_python: List[str] = field(default_factory=list)
+    # The notebook python_file was extracted from, and the lines of its code cells
+    notebook: Optional[str] = None
+    cells: List[NotebookCell] = field(default_factory=list)
+    # Python identifiers and the Rust names they were given
+    renames: List[Renaming] = field(default_factory=list)
+
+    def cell_at(self, python_line: int) -> Optional[NotebookCell]:
+        """Notebook cell a line of the Python file comes from."""
+        for cell in self.cells:
+            if cell.start_line <= python_line <= cell.end_line:
+                return cell
+        return None
+
+    def lookup(self, rust_line: int) -> Optional[Span]:
+        """Python definition behind a Rust line, preferring the innermost item."""
+        matches = [
+            m for m in self.mappings
+            if m.python is not None and m.rust.start_line <= rust_line <= m.rust.end_line
+        ]
+        if not matches:
+            return None
+        return min(matches, key=lambda m: m.rust.end_line - m.rust.start_line).python
+
+    def mapping_at(self, rust_line: int) -> Optional[Mapping]:
+        """Innermost Rust item containing a line, whether or not it maps to Python."""
+        matches = [m for m in self.mappings if m.rust.start_line <= rust_line <= m.rust.end_line]
+        if not matches:
+            return None
+        return min(matches, key=lambda m: m.rust.end_line - m.rust.start_line)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "version": SOURCE_MAP_VERSION,
+            "python_file": self.python_file,
+            "rust_file": self.rust_file,
+            "mappings": [asdict(m) for m in self.mappings],
+            "unmapped_python": self.unmapped_python,
+            "notebook": self.notebook,
+            "cells": [asdict(cell) for cell in self.cells],
+            "renames": [asdict(renaming) for renaming in self.renames]
+        }
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "SourceMap":
+        return cls(
+            python_file=data["python_file"],
+            rust_file=data["rust_file"],
+            mappings=[
+                Mapping(
+                    rust=Span(**m["rust"]),
+                    python=Span(**m["python"]) if m.get("python") else None,
+                    origin=m.get("origin"),
+                    warnings=m.get("warnings", [])
+                )
+                for m in data.get("mappings", [])
+            ],
+            unmapped_python=data.get("unmapped_python", []),
+            notebook=data.get("notebook"),
+            cells=[NotebookCell(**cell) for cell in data.get("cells", [])],
+            renames=[Renaming(**renaming) for renaming in data.get("renames", [])]
+        )
+
+    @classmethod
+    def load(cls, path: Path) -> "SourceMap":
+        return cls.from_dict(json.loads(Path(path).read_text()))
+
+
+def matching_symbol(item: RustItem, symbols: List[PythonSymbol]) -> Optional[PythonSymbol]:
+    """The Python definition a Rust item translates, by name."""
+    for symbol in symbols:
+        # As written, or converted to snake_case (getValue, _helper -> get_value, helper)
+        names = (symbol.name, rust_identifier(symbol.name, symbol.kind))
+        if item.kind == "method":
+            if symbol.kind != "method" or camel_case(symbol.parent) != camel_case(item.parent):
+                continue
+            if item.name in names or item.name in SPECIAL_METHODS.get(symbol.name, []):
+                return symbol
+        elif item.kind == "fn":
+            if symbol.kind == "function" and item.name in names:
+                return symbol
+            if item.name == "main" and symbol.kind == "main":
+                return symbol
+        elif item.kind in ("struct", "enum", "trait", "impl"):
+            if (symbol.kind == "class"
+                    and camel_case(symbol.name) == camel_case(item.name.split("::")[-1])):
+                return symbol
+    return None
+
+
+def _origin(item_text: str, history: List[Dict[str, Any]]) -> Optional[str]:
+    """Prompt that introduced the item as it is now: the start of its last unchanged stretch."""
+    origin = None
+    present = False
+    for entry in history:
+        contains = item_text in entry["rust_code"]
+        if contains and not present:
+            origin = entry["origin"]
+        present = contains
+    return origin
+
+
+def _issue_texts(issues: Any) -> List[str]:
+    if isinstance(issues, dict):
+        return [f"{key}: {value}" for key, value in issues.items()]
+    if isinstance(issues, (list, tuple)):
+        return [str(issue) for issue in issues]
+    return [str(issues)]
+
+
+def _warnings(
+    mapping: Mapping,
+    differences: Dict[str, Any],
+    risks: List[RiskFinding]
+) -> List[str]:
+    """Verification differences naming the item, and risky constructs inside its Python span."""
+    names = {mapping.rust.name.split("::")[-1]}
+    if mapping.python is not None:
+        names.add(mapping.python.name.split(".")[-1])
+    patterns = [re.compile(rf"\b{re.escape(name)}\b") for name in names]
+
+    warnings = []
+    for category, issues in differences.items():
+        for text in _issue_texts(issues):
+            if any(pattern.search(text) for pattern in patterns):
+                warnings.append(f"{category}: {text}")
+    if mapping.python is not None:
+        warnings.extend(
+            f"risk: line {risk.line}: {risk.message}" for risk in risks
+            if mapping.python.start_line <= risk.line <= mapping.python.end_line
+        )
+    return warnings
+
+
+def build_source_map(
+    python_code: str,
+    rust_code: str,
+    python_file: str,
+    rust_file: str,
+    history: Optional[List[Dict[str, Any]]] = None,
+    differences: Optional[Dict[str, Any]] = None,
+    notebook: Optional[str] = None,
+    renames: Optional[List[Renaming]] = None
+) -> SourceMap:
+    """Link Rust items to the Python functions, classes and methods they translate, by name.
+
+    history lists the code produced by each prompt, oldest first, and differences the
+    last verification findings; both are used to annotate each item. The code of a
+    notebook also maps its lines to the notebook's cells. renames is the table of the
+    identifiers given other names in Rust.
+    """
+    symbols = collect_symbols(python_code)
+    risks = scan_risks(python_code)
+    rust_lines = rust_code.splitlines()
+    source_map = SourceMap(python_file=python_file, rust_file=rust_file, notebook=notebook,
+                           cells=notebook_cells(python_code) if notebook else [],
+                           renames=list(renames or []))
+    mapped = set()
+    for item in scan_items(rust_code):
+        rust_name = f"{item.parent}::{item.name}" if item.parent else item.name
+        symbol = matching_symbol(item, symbols)
+        python = None
+        if symbol is not None:
+            mapped.add(symbol.qualname)
+            python = Span(symbol.start_line, symbol.end_line, symbol.qualname, symbol.kind)
+        mapping = Mapping(
+            rust=Span(item.start_line, item.end_line, rust_name, item.kind),
+            python=python
+        )
+        item_text = "\n".join(rust_lines[item.start_line - 1:item.end_line])
+        mapping.origin = _origin(item_text, history or [])
+        mapping.warnings = _warnings(mapping, differences or {}, risks)
+        source_map.mappings.append(mapping)
+    source_map.unmapped_python = [s.qualname for s in symbols if s.qualname not in mapped]
+    return source_map
+
+
+def write_source_map(
+    output_dir: Path,
+    python_file: Path,
+    python_code: str,
+    rust_code: str,
+    history: Optional[List[Dict[str, Any]]] = None,
+    differences: Optional[Dict[str, Any]] = None,
+    notebook: Optional[Path] = None,
+    renames: Optional[List[Renaming]] = None
+) -> Path:
+    """Write src/main.rs.map.json next to the generated Rust file."""
+    rust_file = Path(output_dir) / "src" / "main.rs"
+    source_map = build_source_map(
+        python_code, rust_code, str(python_file), "src/main.rs", history, differences,
+        str(notebook) if notebook else None, renames)
+    map_file = rust_file.with_name(f"{rust_file.name}.map.json")
+    map_file.parent.mkdir(parents=True, exist_ok=True)
+    map_file.write_text(json.dumps(source_map.to_dict(), indent=2))
+    return map_file
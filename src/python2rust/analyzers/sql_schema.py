@@ -0,0 +1,68 @@
+import ast
+import re
+from pathlib import Path
+from typing import List, Optional
+
+from .rust_quality import TEST_MODULE
+
+# String literals, raw ones first so that their quotes are not read as plain strings
+RUST_STRING = re.compile(r'r(#*)"(.*?)"\1|"((?:[^"\\]|\\.)*)"', re.DOTALL)
+RUST_CHAR = re.compile(r"'(?:[^'\\\n]|\\.)'")
+SCHEMA_STATEMENT = re.compile(r"^\s*(?:CREATE|ALTER)\s+(?:TABLE|(?:UNIQUE\s+)?INDEX)\b",
+                              re.IGNORECASE)
+ESCAPES = {"n": "\n", "t": "\t", "r": "\r", "0": "\0", "\\": "\\", '"': '"', "'": "'"}
+
+
+def orm_framework(python_code: str) -> Optional[str]:
+    """sqlalchemy or django when a module imports the models of either, None otherwise."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return None
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            modules = [alias.name for alias in node.names]
+        elif isinstance(node, ast.ImportFrom) and node.module:
+            modules = [node.module]
+        else:
+            continue
+        for module in modules:
+            root = module.split(".")[0]
+            if root == "sqlalchemy" or module.startswith("flask_sqlalchemy"):
+                return "sqlalchemy"
+            if module.startswith("django.db"):
+                return "django"
+    return None
+
+
+def _unescaped(text: str) -> str:
+    # A backslash at the end of a line continues the string without the newline and indentation
+    text = re.sub(r"\\\n\s*", "", text)
+    return re.sub(r"\\(.)", lambda match: ESCAPES.get(match.group(1), match.group(0)), text)
+
+
+def rust_schema_statements(rust_code: str) -> List[str]:
+    """The string literals of Rust code creating or altering tables and indexes, in order,
+    test modules left out."""
+    lines = rust_code.splitlines(keepends=True)
+    for index, line in enumerate(lines):
+        if TEST_MODULE.match(line):
+            rust_code = "".join(lines[:index])
+            break
+    statements = []
+    # Without char literals, whose quotes, such as '"', would open strings
+    for match in RUST_STRING.finditer(RUST_CHAR.sub("' '", rust_code)):
+        text = match.group(2) if match.group(2) is not None else _unescaped(match.group(3))
+        if SCHEMA_STATEMENT.match(text):
+            statements.append(text.strip())
+    return statements
+
+
+def migration_files(crate_dir: Path) -> List[Path]:
+    """The SQL migrations of a crate, sqlx and refinery files or diesel up.sql, in version order."""
+    migrations = Path(crate_dir) / "migrations"
+    if not migrations.is_dir():
+        return []
+    files = [path for path in migrations.rglob("*.sql")
+             if path.name != "down.sql" and not path.name.endswith(".down.sql")]
+    return sorted(files, key=lambda path: path.relative_to(migrations).as_posix())
@@ -0,0 +1,143 @@
+import ast
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Set
+
+from .fuzz_targets import rust_functions
+from .rust_items import _strip_comment
+from .rust_quality import STRING_LITERAL, TEST_MODULE
+
+STRING_TYPE = re.compile(r"\b(?:str|String)\b")
+RUST_FN = re.compile(r"\bfn\s+(\w+)\s*(?:<[^>]*>)?\s*\((.*)")
+RUST_STRING_PARAM = re.compile(r"(?:mut\s+)?(\w+)\s*:\s*&?(?:'\w+\s+)?(?:mut\s+)?(?:str|String)\b")
+
+
+@dataclass
+class StringRisk:
+    """A string operation counting code points in Python and bytes in Rust."""
+    line: int
+    function: str
+    kind: str  # length, indexing or slicing
+    message: str
+    language: str = "python"
+
+    def __str__(self) -> str:
+        return f"{self.language} line {self.line} ({self.function}): {self.message}"
+
+
+def _string_names(function: ast.AST, rust_types: List[str]) -> Set[str]:
+    """Parameters that are strings, from their annotation or the Rust parameter in their position,
+    and the variables assigned strings in the function."""
+    arguments = [arg for arg in function.args.args if arg.arg not in ("self", "cls")]
+    names = {
+        arg.arg for index, arg in enumerate(arguments)
+        if (isinstance(arg.annotation, ast.Name) and arg.annotation.id == "str")
+        or (index < len(rust_types) and STRING_TYPE.search(rust_types[index]))
+    }
+    for node in ast.walk(function):
+        if (isinstance(node, ast.Assign) and len(node.targets) == 1
+                and isinstance(node.targets[0], ast.Name)):
+            if _is_string(node.value, names):
+                names.add(node.targets[0].id)
+    return names
+
+
+def _is_string(node: ast.AST, names: Set[str]) -> bool:
+    if isinstance(node, ast.Name):
+        return node.id in names
+    if (isinstance(node, ast.JoinedStr)
+            or (isinstance(node, ast.Constant) and isinstance(node.value, str))):
+        return True
+    if isinstance(node, ast.Call) and isinstance(node.func, ast.Name):
+        return node.func.id == "str"
+    if isinstance(node, ast.Call) and isinstance(node.func, ast.Attribute):
+        # Methods of a string giving a string, such as strip() and lower()
+        return node.func.attr in ("strip", "lstrip", "rstrip", "lower", "upper", "title", "replace",
+                                  "join", "format",
+                                  "casefold") and _is_string(node.func.value, names)
+    return isinstance(node, ast.BinOp) and isinstance(node.op, ast.Add) and (
+        _is_string(node.left, names) or _is_string(node.right, names))
+
+
+def _function_risks(function: ast.AST, name: str, rust_types: List[str]) -> List[StringRisk]:
+    names = _string_names(function, rust_types)
+    risks = []
+    for node in ast.walk(function):
+        if (isinstance(node, ast.Call) and isinstance(node.func, ast.Name) and node.func.id == "len"
+                and len(node.args) == 1 and _is_string(node.args[0], names)):
+            risks.append(StringRisk(
+                node.lineno, name, "length",
+                "`len()` counts code points in Python, while Rust `len()` counts UTF-8 bytes; "
+                "use `chars().count()`"))
+        elif isinstance(node, ast.Subscript) and _is_string(node.value, names):
+            if isinstance(node.slice, ast.Slice):
+                risks.append(StringRisk(
+                    node.lineno, name, "slicing",
+                    "slices are in code points in Python, while Rust slices `&s[a..b]` are in "
+                    "bytes and panic inside a multi-byte character; slice `chars()` instead"))
+            else:
+                risks.append(StringRisk(
+                    node.lineno, name, "indexing",
+                    "indexing gives the n-th code point in Python, while Rust strings index by "
+                    "byte; use `chars().nth(n)`, counting negative indexes from the end"))
+    return risks
+
+
+def rust_byte_operations(rust_code: str) -> List[StringRisk]:
+    """Lengths and range slices of string parameters in Rust code, outside test modules."""
+    risks = []
+    function, strings = "", set()
+    in_tests, depth = False, 0
+    for number, raw_line in enumerate(rust_code.splitlines(), start=1):
+        line = _strip_comment(STRING_LITERAL.sub('""', raw_line))
+        if TEST_MODULE.match(line):
+            in_tests, depth = True, 0
+            continue
+        if in_tests:
+            depth += line.count("{") - line.count("}")
+            if depth <= 0 and "}" in line:
+                in_tests = False
+            continue
+        header = RUST_FN.search(line)
+        if header:
+            function, strings = header.group(1), set(RUST_STRING_PARAM.findall(header.group(2)))
+        for name in strings:
+            if re.search(rf"\b{name}\.len\(\)", line):
+                risks.append(StringRisk(
+                    number, function, "length",
+                    f"`{name}.len()` is the byte length, unlike Python's code point count", "rust"))
+            if (re.search(rf"\b{name}\s*\[[^\]]*\.\.", line)
+                    or re.search(rf"\b{name}\.as_bytes\(\)\s*\[", line)):
+                risks.append(StringRisk(
+                    number, function, "slicing",
+                    f"`{name}` is sliced or indexed by byte, unlike Python's code point offsets",
+                    "rust"))
+    return risks
+
+
+def string_risks(python_code: str, rust_code: str) -> List[StringRisk]:
+    """Lengths, indexes and slices of strings, which Python counts in code points and Rust in bytes.
+
+    A Python value is a string when its parameter is annotated str or translated to str or
+    String, when it is assigned a string, or when it is a literal or str() call.
+    """
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    signatures: Dict[str, List[str]] = {name: types
+                                        for name, (types, _) in rust_functions(rust_code).items()}
+
+    functions = [
+        (node.name, node) for node in tree.body
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef))]
+    for node in tree.body:
+        if isinstance(node, ast.ClassDef):
+            functions.extend(
+                (f"{node.name}.{item.name}", item) for item in node.body
+                if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef)))
+    risks = []
+    for name, function in functions:
+        risks.extend(_function_risks(function, name, signatures.get(name, [])))
+    risks.extend(rust_byte_operations(rust_code))
+    return sorted(risks, key=lambda risk: (risk.language, risk.line))
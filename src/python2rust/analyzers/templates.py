@@ -0,0 +1,198 @@
+import ast
+import re
+from dataclasses import dataclass, field, replace
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from ..config.crate_mappings import CrateMapping
+from .naming import camel_case
+
+# Templates a template pulls in: {% extends "base.html" %}, {% include "row.html" %}, ...
+REFERENCED = re.compile(r"""\{%-?\s*(?:extends|include|import|from)\s+["']([^"']+)["']""")
+ELIF = re.compile(r"\{%(-?)\s*elif\b")
+IF_VARIABLE = re.compile(r"\{%(-?)\s*if\s+(\w+)\s*(-?)%\}")
+
+
+@dataclass
+class TemplateEngine:
+    """A Rust template engine, and how generated code renders its templates."""
+    name: str
+    version: str
+    files: bool  # whether its templates are files of the crate's templates/ directory
+    render: str  # with {file}, {struct} and {files}, the templates of the crate
+
+    @property
+    def dependency(self) -> str:
+        return f'{self.name} = "{self.version}"'
+
+
+ENGINES = {
+    "askama": TemplateEngine(
+        "askama", "0.12", True,
+        render="`#[derive(askama::Template)] #[template(path = \"{file}\")] struct {struct}` with "
+               "a field per variable, rendered with `.render()`; askama compiles the "
+               "template into the crate"),
+    "tera": TemplateEngine(
+        "tera", "1", True,
+        render="`tera.render(\"{file}\", &context)` with a `tera::Context` of the variables, on a "
+               "`tera::Tera` built once (in a `std::sync::OnceLock`) registering every template of "
+               "the crate by name, embedded in the binary, for those they extend and include to "
+               "resolve: `tera.add_raw_templates(vec![{files}])`"),
+    "maud": TemplateEngine(
+        "maud", "0.26", False,
+        render="a function returning `maud::Markup`, the template written as "
+               "`maud::html! {{ ... }}` markup with the variables as its parameters, escaped "
+               "as Jinja escapes them"),
+}
+
+
+@dataclass
+class PythonTemplate:
+    """A Jinja template a module renders, inline or a file of its templates directory."""
+    name: str  # file name, e.g. index.html, or of an inline template, html_template.html
+    source: str
+    line: int  # of the render call
+    variables: List[str] = field(default_factory=list)  # given to the render call
+    inline: bool = False
+    constant: Optional[str] = None  # the module constant holding an inline template
+    files: Dict[str, str] = field(default_factory=dict)  # templates it extends or includes, by name
+
+    @property
+    def struct(self) -> str:
+        """The askama struct rendering it: index.html is IndexTemplate."""
+        name = camel_case(re.sub(r"\W+", "_", Path(self.name).stem.lower()))
+        return name if name.endswith("Template") else f"{name}Template"
+
+    def describe(self) -> str:
+        origin = ("a file" if not self.inline else f"inline in {self.constant}"
+                  if self.constant else "inline")
+        variables = ", ".join(self.variables) or "no variables"
+        return f"line {self.line}: {self.name} ({origin}), rendered with {variables}"
+
+
+def _call_name(node: ast.expr) -> Optional[str]:
+    if isinstance(node, ast.Name):
+        return node.id
+    if isinstance(node, ast.Attribute):
+        return node.attr
+    return None
+
+
+def _string(node: Optional[ast.expr], constants: Dict[str, str]) -> Optional[str]:
+    if isinstance(node, ast.Constant) and isinstance(node.value, str):
+        return node.value
+    if isinstance(node, ast.Name):
+        return constants.get(node.id)
+    return None
+
+
+def _referenced(source: str, directories: List[Path], found: Dict[str, str]) -> None:
+    for name in REFERENCED.findall(source):
+        if name in found:
+            continue
+        path = next((directory / name for directory in directories if (directory / name).is_file()),
+                    None)
+        if path is not None:
+            found[name] = path.read_text()
+            _referenced(found[name], directories, found)
+
+
+def python_templates(python_code: str,
+                     directories: Optional[List[Path]] = None) -> List[PythonTemplate]:
+    """The Jinja templates a module renders: with Flask's render_template and
+    render_template_string, jinja2's Template(...).render and Starlette's TemplateResponse, files
+    found in directories, and the templates they extend or include."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return []
+    directories = directories or []
+    constants = {target.id: node.value.value for node in tree.body if isinstance(node, ast.Assign)
+                 and isinstance(node.value, ast.Constant) and isinstance(node.value.value, str)
+                 for target in node.targets if isinstance(target, ast.Name)}
+    templates: Dict[str, PythonTemplate] = {}
+
+    def inline(argument: ast.expr, line: int, variables: List[str]) -> None:
+        source = _string(argument, constants)
+        if source is None:
+            return
+        constant = argument.id if isinstance(argument, ast.Name) else None
+        name = f"{(constant or f'template_{line}').lower()}.html"
+        templates.setdefault(name, PythonTemplate(name, source, line, variables, True, constant))
+
+    def from_file(argument: Optional[ast.expr], line: int, variables: List[str]) -> None:
+        name = _string(argument, {})
+        paths = [directory / name for directory in directories if name] if name else []
+        path = next((path for path in paths if path.is_file()), None)
+        if path is not None:
+            templates.setdefault(name, PythonTemplate(name, path.read_text(), line, variables))
+
+    for node in ast.walk(tree):
+        if not isinstance(node, ast.Call):
+            continue
+        name = _call_name(node.func)
+        keywords = [keyword.arg for keyword in node.keywords if keyword.arg]
+        first = node.args[0] if node.args else None
+        if name == "render_template_string" and first is not None:
+            inline(first, node.lineno, keywords)
+        elif name == "render_template":
+            from_file(first, node.lineno, keywords)
+        elif name == "TemplateResponse":
+            context = node.args[1] if len(node.args) > 1 else next(
+                (keyword.value for keyword in node.keywords if keyword.arg == "context"), None)
+            variables = [key.value for key in getattr(context, "keys", [])
+                         if isinstance(key, ast.Constant) and key.value != "request"]
+            named = next((keyword.value for keyword in node.keywords if keyword.arg == "name"),
+                         None)
+            from_file(first or named, node.lineno, variables)
+        elif name == "render" and isinstance(node.func, ast.Attribute):
+            template = node.func.value
+            if (isinstance(template, ast.Call)
+                    and _call_name(template.func) == "Template" and template.args):
+                inline(template.args[0], node.lineno, keywords)
+    for template in templates.values():
+        _referenced(template.source, directories, template.files)
+    return sorted(templates.values(), key=lambda template: template.line)
+
+
+def engine_source(source: str, variables: List[str], engine: str) -> str:
+    """A Jinja template in the engine's syntax. Tera reads Jinja as it is; askama wants `else if`
+    and tests the variables with `if let Some(...)`, which it gives as Options."""
+    if engine != "askama":
+        return source
+    source = ELIF.sub(r"{%\1 else if", source)
+
+    def optional(match: re.Match) -> str:
+        dash, name, closing = match.groups()
+        if name not in variables:
+            return match.group(0)
+        return f"{{%{dash} if let Some({name}) = {name} {closing}%}}"
+    return IF_VARIABLE.sub(optional, source)
+
+
+def write_templates(output_dir: Path, templates: List[PythonTemplate], engine: str) -> List[Path]:
+    """The templates in the engine's syntax, written into the templates/ directory of the crate:
+    those the module renders and those they extend or include. None for engines without files."""
+    if not ENGINES[engine].files:
+        return []
+    directory = Path(output_dir) / "templates"
+    sources: Dict[str, str] = {}
+    for template in templates:
+        sources.setdefault(template.name,
+                           engine_source(template.source, template.variables, engine))
+        for name, source in template.files.items():
+            sources.setdefault(name, engine_source(source, template.variables, engine))
+    written = []
+    for name, source in sources.items():
+        path = directory / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(source)
+        written.append(path)
+    return written
+
+
+def engine_mappings(mappings: List[CrateMapping], engine: str) -> List[CrateMapping]:
+    """The crate mappings with the Python template libraries mapped to the engine."""
+    target = ENGINES[engine]
+    return [replace(mapping, crate=target.name, version=target.version, features=[])
+            if mapping.category == "templating" else mapping for mapping in mappings]
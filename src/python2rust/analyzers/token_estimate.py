@@ -0,0 +1,115 @@
+from functools import lru_cache
+from typing import Callable, Dict, List, Optional
+
+from ..prompts import (
+    ANALYSIS_PROMPT,
+    FIX_PROMPT,
+    GENERATION_PROMPT,
+    IMPLEMENTATION_PROMPT,
+    SKELETON_PROMPT,
+    SPEC_PROMPT,
+    TESTS_PROMPT,
+    VERIFICATION_PROMPT
+)
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+# Rough size of model outputs relative to the Python source, in tokens
+RUST_TO_PYTHON_RATIO = 1.5
+SPEC_TO_PYTHON_RATIO = 0.6
+ANALYSIS_OUTPUT_TOKENS = 800
+VERIFICATION_OUTPUT_TOKENS = 600
+
+
+@lru_cache(maxsize=1)
+def _encoder() -> Callable[[str], int]:
+    try:
+        import tiktoken
+
+        encoding = tiktoken.get_encoding("cl100k_base")
+        return lambda text: len(encoding.encode(text))
+    except Exception as e:
+        logger.debug(f"tiktoken unavailable, estimating 4 characters per token: {e}")
+        return lambda text: len(text) // 4 + 1
+
+
+def count_tokens(text: str) -> int:
+    """Approximate token count of a text."""
+    return _encoder()(text)
+
+
+def estimate_stage_tokens(
+    python_code: str,
+    translation_passes: List[str],
+    fix_attempts: int = 1
+) -> Dict[str, Dict[str, int]]:
+    """Estimated input and output tokens of each LLM stage for one file.
+
+    Assumes fix_attempts rounds of fixing and re-verification after the first
+    verification fails.
+    """
+    python_tokens = count_tokens(python_code)
+    rust_tokens = int(python_tokens * RUST_TO_PYTHON_RATIO)
+    spec_tokens = int(python_tokens * SPEC_TO_PYTHON_RATIO)
+    template = lambda prompt: count_tokens(prompt.template)
+
+    stages = {
+        "analysis": {
+            "input": template(ANALYSIS_PROMPT) + python_tokens,
+            "output": ANALYSIS_OUTPUT_TOKENS
+        }
+    }
+    if not translation_passes:
+        stages["generation"] = {
+            "input": template(GENERATION_PROMPT) + python_tokens + ANALYSIS_OUTPUT_TOKENS,
+            "output": rust_tokens
+        }
+    if "spec" in translation_passes:
+        stages["spec"] = {
+            "input": template(SPEC_PROMPT) + python_tokens + ANALYSIS_OUTPUT_TOKENS,
+            "output": spec_tokens
+        }
+    if "skeleton" in translation_passes:
+        stages["skeleton"] = {
+            "input": template(SKELETON_PROMPT) + python_tokens + spec_tokens,
+            "output": rust_tokens // 2
+        }
+    if "implementation" in translation_passes:
+        stages["implementation"] = {
+            "input": template(IMPLEMENTATION_PROMPT) + python_tokens
+            + spec_tokens + rust_tokens // 2, "output": rust_tokens
+        }
+    if "tests" in translation_passes:
+        stages["tests"] = {
+            "input": template(TESTS_PROMPT) + python_tokens + spec_tokens + rust_tokens,
+            "output": rust_tokens // 2
+        }
+
+    verification_input = template(
+        VERIFICATION_PROMPT) + python_tokens + rust_tokens + ANALYSIS_OUTPUT_TOKENS
+    stages["verification"] = {
+        "input": verification_input * (1 + fix_attempts),
+        "output": VERIFICATION_OUTPUT_TOKENS * (1 + fix_attempts)
+    }
+    stages["fixes"] = {
+        "input": (template(FIX_PROMPT) + rust_tokens + VERIFICATION_OUTPUT_TOKENS) * fix_attempts,
+        "output": rust_tokens * fix_attempts
+    }
+    return stages
+
+
+def generation_token_budget(max_tokens: Optional[int],
+                            context_length: Optional[int]) -> Optional[int]:
+    """Python tokens a model can translate in one generation call, or None without limits.
+
+    The Rust output has to fit in max_tokens, and the generation prompt with its
+    output in the context window.
+    """
+    budgets = []
+    if max_tokens:
+        budgets.append(int(max_tokens / RUST_TO_PYTHON_RATIO))
+    if context_length:
+        prompt_tokens = count_tokens(GENERATION_PROMPT.template) + ANALYSIS_OUTPUT_TOKENS
+        budgets.append(int((context_length - prompt_tokens) / (1 + RUST_TO_PYTHON_RATIO)))
+    return max(min(budgets), 1) if budgets else None
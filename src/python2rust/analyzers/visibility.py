@@ -0,0 +1,111 @@
+import ast
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional
+
+from .naming import rust_identifier
+
+
+@dataclass
+class ReExport:
+    """A name a module imports from another module of its package and exports in turn."""
+    module: str  # dotted name of the module defining it
+    name: str  # * for all its public names
+    alias: Optional[str] = None
+
+
+@dataclass
+class ModuleVisibility:
+    """The public and private top-level names of a module, as Python's conventions tell them:
+    the names of __all__ when it has one, otherwise those without a leading underscore."""
+    # defined name -> function, class or constant
+    items: Dict[str, str] = field(default_factory=dict)
+    public: List[str] = field(default_factory=list)
+    exported: bool = False  # whether __all__ lists the public names
+    reexports: List[ReExport] = field(default_factory=list)
+
+    @property
+    def private(self) -> List[str]:
+        return [name for name in self.items if name not in self.public]
+
+    def rust_name(self, name: str) -> str:
+        """The Rust name of one of its items, private ones without their leading underscores."""
+        return rust_identifier(name, self.items.get(name, "function"))
+
+    def describe(self) -> str:
+        source = "listed in __all__" if self.exported else "without a leading underscore"
+        public = ", ".join(f"{name} ({self.items[name]})" for name in self.public
+                           if name in self.items)
+        private = ", ".join(f"{name} -> {self.rust_name(name)}" for name in self.private)
+        parts = [f"public, {source}: {public or 'none'}"]
+        if private:
+            parts.append(f"private: {private}")
+        return "; ".join(parts)
+
+
+def _all_names(node: ast.AST) -> Optional[List[str]]:
+    if isinstance(node, (ast.List, ast.Tuple)) and all(
+            isinstance(element, ast.Constant) and isinstance(element.value, str)
+            for element in node.elts):
+        return [element.value for element in node.elts]
+    return None
+
+
+def _imported_module(module: str, is_package: bool, node: ast.ImportFrom) -> Optional[str]:
+    """The dotted name of the module of a relative import, or of an absolute one of the package."""
+    if not node.level:
+        return node.module
+    parts = module.split(".") if is_package else module.split(".")[:-1]
+    if node.level - 1 > len(parts) or (node.level - 1 == len(parts) and not node.module):
+        return None
+    base = parts[:len(parts) - (node.level - 1)]
+    return ".".join(base + ([node.module] if node.module else []))
+
+
+def module_visibility(python_code: str, module: str = "",
+                      is_package: bool = False) -> Optional[ModuleVisibility]:
+    """The visibility of the top-level functions, classes and constants of a module named module,
+    and the names it imports from modules of its top-level package and exports: through __all__,
+    or for a package's __init__ without one, every name imported without a leading underscore.
+    None when the module defines nothing."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return None
+    items: Dict[str, str] = {}
+    exported: Optional[List[str]] = None
+    imports: List[ReExport] = []
+    top = module.split(".")[0]
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            items[node.name] = "function"
+        elif isinstance(node, ast.ClassDef):
+            items[node.name] = "class"
+        elif isinstance(node, (ast.Assign, ast.AnnAssign, ast.AugAssign)):
+            targets = node.targets if isinstance(node, ast.Assign) else [node.target]
+            for target in targets:
+                if not isinstance(target, ast.Name):
+                    continue
+                if target.id == "__all__" and node.value is not None:
+                    names = _all_names(node.value)
+                    if names is not None:
+                        exported = ((exported or []) + names
+                                    if isinstance(node, ast.AugAssign) else names)
+                elif not isinstance(node, ast.AugAssign) and not target.id.startswith("__"):
+                    items.setdefault(target.id, "constant")
+        elif isinstance(node, ast.ImportFrom):
+            imported = _imported_module(module, is_package, node)
+            if imported is None or imported.split(".")[0] != top:
+                continue
+            imports.extend(ReExport(imported, alias.name, alias.asname) for alias in node.names)
+    if not items and not imports:
+        return None
+
+    if exported is not None:
+        public = [name for name in exported if name in items]
+        reexports = [reexport for reexport in imports
+                     if (reexport.alias or reexport.name) in exported]
+    else:
+        public = [name for name in items if not name.startswith("_")]
+        reexports = [reexport for reexport in imports
+                     if is_package and not (reexport.alias or reexport.name).startswith("_")]
+    return ModuleVisibility(items, public, exported is not None, reexports)
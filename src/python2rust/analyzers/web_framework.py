@@ -0,0 +1,95 @@
+import re
+from dataclasses import dataclass, replace
+from typing import List, Tuple
+
+from ..config.crate_mappings import CrateMapping
+from .http_routes import HttpRoute
+
+# <int:name>, <name>, {name} and {name:path} path parameters of Flask, FastAPI and Starlette
+PYTHON_PARAMETER = re.compile(
+    r"<(?:(?P<converter>\w+):)?(?P<name>\w+)>|\{(?P<brace>\w+)(?::(?P<kind>\w+))?\}")
+
+
+@dataclass
+class Framework:
+    """A Rust web framework and its idioms for the routes, extractors, state and
+    server of an app."""
+    name: str
+    version: str
+    features: Tuple[str, ...]
+    runtime: Tuple[str, ...]  # [dependencies] lines of the runtime it needs besides its own
+    parameter: str  # path parameter syntax, with {name}
+    wildcard: str  # the syntax of a parameter matching the rest of the path
+    route: str  # how the handler of a method is registered, with {path}, {method} and {handler}
+    extractors: str
+    state: str
+    server: str
+
+    @property
+    def identifier(self) -> str:
+        return self.name.replace("-", "_")
+
+    def dependencies(self) -> List[str]:
+        """The [dependencies] lines of the framework and its runtime."""
+        mapping = CrateMapping("", self.name, "web framework", version=self.version,
+                               features=list(self.features))
+        return [mapping.dependency(), *self.runtime]
+
+    def path(self, python_path: str) -> str:
+        """A Python route path in the framework's syntax: /items/<int:id> is /items/:id in axum."""
+        def parameter(match: re.Match) -> str:
+            name = match.group("name") or match.group("brace")
+            rest = (match.group("converter") or match.group("kind")) == "path"
+            return (self.wildcard if rest else self.parameter).format(name=name)
+        return PYTHON_PARAMETER.sub(parameter, python_path)
+
+    def registration(self, route: HttpRoute) -> str:
+        """How the framework registers the handlers of a route, a method after the other."""
+        return " and ".join(self.route.format(path=self.path(route.path), method=method.lower(),
+                                              handler=route.function or "handler")
+                            for method in route.methods)
+
+
+FRAMEWORKS = {
+    "axum": Framework(
+        "axum", "0.7", (), ('tokio = { version = "1", features = ["full"] }',),
+        parameter=":{name}", wildcard="*{name}", route='`.route("{path}", {method}({handler}))`',
+        extractors="`Path<T>` for path parameters, `Query<T>` for query strings, `Json<T>` for "
+                   "JSON bodies, last, and `HeaderMap` for headers; handlers return "
+                   "`impl IntoResponse`, `(StatusCode, Json<T>)` for other statuses",
+        state="shared state is a `State<Arc<AppState>>` extractor, given with `Router::with_state`",
+        server="`#[tokio::main]` building the `Router` and serving it with "
+               "`axum::serve(tokio::net::TcpListener::bind(address).await?, app)`"),
+    "actix-web": Framework(
+        "actix-web", "4", (), (),
+        parameter="{{{name}}}", wildcard="{{{name}:.*}}",
+        route='`.route("{path}", web::{method}().to({handler}))`',
+        extractors="`web::Path<T>` for path parameters, `web::Query<T>` for query strings, "
+                   "`web::Json<T>` for JSON bodies and `HttpRequest` for headers; handlers return "
+                   "`HttpResponse` or `impl Responder`",
+        state="shared state is a `web::Data<AppState>` extractor, registered with "
+              "`App::app_data(web::Data::new(state))` outside the `HttpServer::new` closure",
+        server="`#[actix_web::main]` running "
+               "`HttpServer::new(move || App::new()...).bind(address)?.run().await`"),
+    "rocket": Framework(
+        "rocket", "0.5", ("json",), (),
+        parameter="<{name}>", wildcard="<{name}..>",
+        route='`#[{method}("{path}")]` on a handler',
+        extractors="path parameters are handler arguments named in the route, query strings "
+                   "`?<name>` arguments, JSON bodies `Json<T>` with `data = \"<body>\"` and "
+                   "headers request guards; handlers return `Json<T>`, or "
+                   "`(Status, Json<T>)` for other statuses",
+        state="shared state is a `&State<AppState>` argument, given with "
+              "`.manage(state)` on the `Rocket`",
+        server="`#[launch] fn rocket() -> _` returning `rocket::custom(figment)` mounting the "
+               "handlers with `.mount(\"/\", routes![...])`, the figment "
+               "`rocket::Config::figment()` with the port and address merged in"),
+}
+
+
+def framework_mappings(mappings: List[CrateMapping], framework: str) -> List[CrateMapping]:
+    """The crate mappings with the Python web frameworks mapped to the framework."""
+    target = FRAMEWORKS[framework]
+    return [replace(mapping, crate=target.name, version=target.version,
+                    features=list(target.features))
+            if mapping.category == "web framework" else mapping for mapping in mappings]
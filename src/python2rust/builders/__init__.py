@@ -1,4 +1,13 @@
+from .call_tracer import CallTracer, TracedCall
+from .profiler import Profiler
 from .rust_builder import RustBuilder
+from .sandbox import Sandbox
 from .server_tester import ServerTester
+from .type_inference import TypeInference
+from .benchmark import BenchResult, BenchWorkload, ServerBenchmark, format_comparison
 
-__all__ = ['RustBuilder', 'ServerTester']
\ No newline at end of file
+__all__ = [
+    'CallTracer', 'TracedCall', 'Profiler', 'RustBuilder', 'Sandbox',
+    'ServerTester', 'TypeInference', 'BenchResult', 'BenchWorkload',
+    'ServerBenchmark', 'format_comparison'
+]
@@ -0,0 +1,272 @@
+'''
+Runs the original Python server and the generated Rust server under the same workload.
+'''
+import asyncio
+import math
+import os
+import signal
+import sys
+import time
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+import aiohttp
+import psutil
+
+from ..config.settings import StepLimits
+from ..utils.limits import preexec
+from ..utils.logging import setup_logger
+
+if sys.version_info >= (3, 11):
+    import tomllib
+else:
+    import tomli as tomllib
+
+logger = setup_logger()
+
+
+@dataclass
+class BenchWorkload:
+    """Requests sent to each server; warmup requests are not measured."""
+    method: str = "POST"
+    path: str = "/"
+    requests: int = 20
+    concurrency: int = 1
+    warmup: int = 1
+
+
+def percentile(values: List[float], fraction: float) -> float:
+    """Nearest-rank percentile, 0.0 for no values."""
+    if not values:
+        return 0.0
+    ordered = sorted(values)
+    rank = max(0, min(len(ordered) - 1, math.ceil(fraction * len(ordered)) - 1))
+    return ordered[rank]
+
+
+@dataclass
+class BenchResult:
+    """Measurements of one server under a workload."""
+    name: str
+    requests: int = 0
+    failures: int = 0
+    wall_time: float = 0.0
+    latencies: List[float] = field(default_factory=list)
+    peak_memory_mb: float = 0.0
+    error: Optional[str] = None
+
+    @property
+    def throughput(self) -> float:
+        """Successful requests per second."""
+        if self.wall_time <= 0:
+            return 0.0
+        return (self.requests - self.failures) / self.wall_time
+
+    @property
+    def latency_mean(self) -> float:
+        return sum(self.latencies) / len(self.latencies) if self.latencies else 0.0
+
+    @property
+    def latency_p95(self) -> float:
+        return percentile(self.latencies, 0.95)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "name": self.name,
+            "requests": self.requests,
+            "failures": self.failures,
+            "wall_time": self.wall_time,
+            "throughput": self.throughput,
+            "latency_mean": self.latency_mean,
+            "latency_p95": self.latency_p95,
+            "peak_memory_mb": self.peak_memory_mb,
+            "error": self.error
+        }
+
+
+def rust_binary(project_dir: Path, profile: str = "release") -> Path:
+    """Binary of a generated crate built with a profile, named after its package."""
+    manifest = tomllib.loads((project_dir / "Cargo.toml").read_text())
+    return project_dir / "target" / profile / manifest["package"]["name"]
+
+
+def tree_memory_mb(pid: int) -> float:
+    """Resident memory of a process and its children."""
+    try:
+        process = psutil.Process(pid)
+        processes = [process, *process.children(recursive=True)]
+    except psutil.NoSuchProcess:
+        return 0.0
+    total = 0
+    for proc in processes:
+        try:
+            total += proc.memory_info().rss
+        except (psutil.NoSuchProcess, psutil.AccessDenied):
+            pass
+    return total / (1024 * 1024)
+
+
+class ServerBenchmark:
+    """Starts a server, waits until it answers, and measures a workload against it."""
+
+    def __init__(
+        self,
+        host: str = "127.0.0.1",
+        port: int = 8080,
+        startup_timeout: int = 60,
+        request_timeout: int = 120,
+        sample_interval: float = 0.05,
+        limits: Optional[StepLimits] = None
+    ):
+        self.host = host
+        self.port = port
+        self.base_url = f"http://{host}:{port}"
+        self.startup_timeout = startup_timeout
+        self.request_timeout = request_timeout
+        self.sample_interval = sample_interval
+        # Memory of each server and wall-clock time of its whole workload
+        self.limits = limits or StepLimits()
+
+    async def _wait_until_ready(
+        self,
+        session: aiohttp.ClientSession,
+        process: asyncio.subprocess.Process
+    ) -> bool:
+        deadline = time.monotonic() + self.startup_timeout
+        while time.monotonic() < deadline:
+            if process.returncode is not None:
+                return False
+            try:
+                async with session.get(f"{self.base_url}/", timeout=1) as response:
+                    if response.status < 500:
+                        return True
+            except Exception:
+                pass
+            await asyncio.sleep(0.5)
+        return False
+
+    async def _sample_memory(self, pid: int, result: BenchResult) -> None:
+        while True:
+            result.peak_memory_mb = max(result.peak_memory_mb, tree_memory_mb(pid))
+            await asyncio.sleep(self.sample_interval)
+
+    async def _request(self, session: aiohttp.ClientSession, workload: BenchWorkload) -> float:
+        """Send one request and return its latency; raises on an error status."""
+        start = time.perf_counter()
+        async with session.request(
+            workload.method,
+            f"{self.base_url}{workload.path}",
+            timeout=self.request_timeout
+        ) as response:
+            await response.read()
+            response.raise_for_status()
+        return time.perf_counter() - start
+
+    async def _send_requests(
+        self,
+        session: aiohttp.ClientSession,
+        workload: BenchWorkload,
+        result: BenchResult
+    ) -> None:
+        slots = asyncio.Semaphore(workload.concurrency)
+
+        async def measured() -> None:
+            async with slots:
+                try:
+                    result.latencies.append(await self._request(session, workload))
+                except Exception as e:
+                    logger.warning(f"{result.name}: request failed: {e}")
+                    result.failures += 1
+
+        for _ in range(workload.warmup):
+            try:
+                await self._request(session, workload)
+            except Exception as e:
+                logger.warning(f"{result.name}: warmup request failed: {e}")
+
+        result.requests = workload.requests
+        start = time.perf_counter()
+        await asyncio.gather(*(measured() for _ in range(workload.requests)))
+        result.wall_time = time.perf_counter() - start
+
+    def _stop(self, process: asyncio.subprocess.Process) -> None:
+        if process.returncode is not None:
+            return
+        try:
+            if os.name != 'nt':
+                os.killpg(os.getpgid(process.pid), signal.SIGTERM)
+            else:
+                process.terminate()
+        except ProcessLookupError:
+            pass
+
+    async def run(
+        self,
+        name: str,
+        command: List[str],
+        cwd: Path,
+        workload: BenchWorkload,
+        sandboxed: bool = False
+    ) -> BenchResult:
+        """Benchmark the server started by command. A sandboxed server has its memory limited by
+        the container and is not measured, command being the docker client."""
+        result = BenchResult(name=name)
+        logger.info(f"Benchmarking {name}: {' '.join(command)}")
+        process = await asyncio.create_subprocess_exec(
+            *command,
+            cwd=cwd,
+            stdout=asyncio.subprocess.DEVNULL,
+            stderr=asyncio.subprocess.DEVNULL,
+            preexec_fn=preexec(None if sandboxed else self.limits.memory_mb, new_session=True)
+        )
+        sampler = None
+        if not sandboxed:
+            sampler = asyncio.create_task(self._sample_memory(process.pid, result))
+        try:
+            async with aiohttp.ClientSession() as session:
+                if not await self._wait_until_ready(session, process):
+                    result.error = f"{name} server did not start on {self.base_url}"
+                    return result
+                try:
+                    await asyncio.wait_for(
+                        self._send_requests(session, workload, result), timeout=self.limits.timeout)
+                except asyncio.TimeoutError:
+                    result.error = (f"{name} server did not finish the workload within "
+                                    f"{self.limits.timeout:g} seconds")
+        finally:
+            if sampler is not None:
+                sampler.cancel()
+            self._stop(process)
+            try:
+                await asyncio.wait_for(process.wait(), timeout=5)
+            except asyncio.TimeoutError:
+                process.kill()
+        logger.info(f"{name}: {result.throughput:.2f} req/s, peak {result.peak_memory_mb:.1f} MB")
+        return result
+
+
+def format_comparison(python: BenchResult, rust: BenchResult) -> str:
+    """Side by side table of both servers with the Rust speedup."""
+    def ratio(numerator: float, denominator: float) -> str:
+        return f"{numerator / denominator:.2f}x" if denominator > 0 else "-"
+
+    rows = [
+        ("wall time (s)", f"{python.wall_time:.3f}", f"{rust.wall_time:.3f}",
+         ratio(python.wall_time, rust.wall_time)),
+        ("throughput (req/s)", f"{python.throughput:.2f}", f"{rust.throughput:.2f}",
+         ratio(rust.throughput, python.throughput)),
+        ("mean latency (ms)", f"{python.latency_mean * 1000:.1f}",
+         f"{rust.latency_mean * 1000:.1f}", ratio(python.latency_mean, rust.latency_mean)),
+        ("p95 latency (ms)", f"{python.latency_p95 * 1000:.1f}",
+         f"{rust.latency_p95 * 1000:.1f}", ratio(python.latency_p95, rust.latency_p95)),
+        ("peak memory (MB)", f"{python.peak_memory_mb:.1f}", f"{rust.peak_memory_mb:.1f}",
+         ratio(python.peak_memory_mb, rust.peak_memory_mb)),
+        ("failed requests", str(python.failures), str(rust.failures), ""),
+    ]
+    header = ("metric", "python", "rust", "rust gain")
+    widths = [max(len(row[i]) for row in [header, *rows]) for i in range(len(header))]
+    lines = ["  ".join(cell.ljust(width) for cell, width in zip(row, widths)).rstrip()
+             for row in [header, *rows]]
+    lines.insert(1, "  ".join("-" * width for width in widths))
+    return "\n".join(lines)
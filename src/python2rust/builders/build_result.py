@@ -1,5 +1,5 @@
-from dataclasses import dataclass
-from typing import Any, Dict, Optional
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
 
 
 @dataclass
@@ -10,3 +10,5 @@ class BuildResult:
     toml_content: str
     error: Optional[str] = None
     build_info: Optional[Dict[str, Any]] = None
+    warnings: List[str] = field(default_factory=list)  # of the checkers, on the final code
+    metrics: Dict[str, Any] = field(default_factory=dict)  # of the checkers, on the final code
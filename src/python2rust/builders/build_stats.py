@@ -0,0 +1,96 @@
+'''
+Release build time, rebuild time, binary size and dependency count of a generated crate.
+'''
+from dataclasses import asdict, dataclass
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from ..utils.logging import setup_logger
+from .benchmark import rust_binary
+from .rust_builder import RustBuilder
+
+logger = setup_logger()
+
+# A measure regresses when it grows by this ratio and at least this much, so small crates
+# whose builds take a moment do not flag noise
+REGRESSION_LIMITS = {
+    "release_seconds": (1.5, 10.0),
+    "rebuild_seconds": (1.5, 2.0),
+    "binary_bytes": (1.25, 256 * 1024),
+    "dependencies": (1.25, 3),
+}
+UNITS = {"release_seconds": "s", "rebuild_seconds": "s",
+         "binary_bytes": " bytes", "dependencies": ""}
+LABELS = {
+    "release_seconds": "release build",
+    "rebuild_seconds": "rebuild",
+    "binary_bytes": "binary size",
+    "dependencies": "dependencies",
+}
+
+
+@dataclass
+class BuildStats:
+    """Cost of building a crate in release mode; None for what could not be measured."""
+    release_seconds: Optional[float] = None  # from scratch, dependencies included
+    rebuild_seconds: Optional[float] = None  # after an edit of src/main.rs
+    binary_bytes: Optional[int] = None
+    dependencies: Optional[int] = None  # packages of Cargo.lock besides the crate
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "BuildStats":
+        return cls(**{name: data.get(name) for name in cls.__dataclass_fields__})
+
+
+def build_regressions(old: Dict[str, Any], new: Dict[str, Any]) -> List[str]:
+    """Measures of the new build grown past their REGRESSION_LIMITS since the old one."""
+    regressions = []
+    for name, (ratio, increase) in REGRESSION_LIMITS.items():
+        before, after = old.get(name), new.get(name)
+        if before is None or after is None or after - before < increase:
+            continue
+        if before and after / before < ratio:
+            continue
+        growth = f" ({after / before:.2f}x)" if before else ""
+        regressions.append(f"{LABELS[name]} {before}{UNITS[name]} -> {after}{UNITS[name]}{growth}")
+    return regressions
+
+
+def count_dependencies(project_dir: Path) -> Optional[int]:
+    """Packages locked by Cargo.lock, direct and transitive, besides the crate itself."""
+    try:
+        lock = (Path(project_dir) / "Cargo.lock").read_text()
+    except OSError:
+        return None
+    return max(0, lock.count("[[package]]") - 1)
+
+
+async def measure_build(builder: RustBuilder, rust_code: str,
+                        toml_content: str) -> Optional[BuildStats]:
+    """Build the crate in release mode from scratch, then again after touching src/main.rs.
+
+    None when the release build fails.
+    """
+    await builder.clean(release=True)
+    success, error, info = await builder.build(rust_code, toml_content, release=True)
+    if not success:
+        logger.warning(f"Not measuring the build of {builder.output_dir}: {error}")
+        return None
+    stats = BuildStats(release_seconds=round(info["duration"], 2),
+                       dependencies=count_dependencies(builder.output_dir))
+
+    # The build writes main.rs again, and its newer mtime makes cargo recompile the crate alone
+    success, _, info = await builder.build(rust_code, toml_content, release=True)
+    if success:
+        stats.rebuild_seconds = round(info["duration"], 2)
+    try:
+        stats.binary_bytes = rust_binary(builder.output_dir).stat().st_size
+    except (OSError, KeyError) as e:
+        logger.warning(f"No release binary in {builder.output_dir}: {e}")
+    logger.info(f"Release build of {builder.output_dir} in {stats.release_seconds}s, rebuilt in "
+                f"{stats.rebuild_seconds}s, {stats.binary_bytes} byte binary, "
+                f"{stats.dependencies} dependencies")
+    return stats
@@ -0,0 +1,128 @@
+import asyncio
+import json
+import os
+import shutil
+import tempfile
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+RECORDER = Path(__file__).with_name("trace_recorder.py")
+
+
+@dataclass
+class TracedCall:
+    """A call of a Python function, as source: its arguments and result or exception."""
+    function: str
+    args: str
+    result: Optional[str] = None
+    raises: Optional[str] = None
+
+    def describe(self) -> str:
+        call = f"{self.function}({self.args})"
+        if self.raises is not None:
+            return f"`{call}` raised `{self.raises}`"
+        return f"`{call}` returned `{self.result}`"
+
+
+class CallTracer:
+    """Runs a command with its Python processes recording the calls of the source files.
+
+    The recorder is installed as sitecustomize through PYTHONPATH, so any command
+    starting Python works: the program itself, a script driving it or pytest. Only
+    calls whose arguments and result are plain data (scalars, strings, lists,
+    tuples, sets and dicts of them) are kept, at most limit distinct calls per
+    function.
+    """
+
+    def __init__(self, command: str, cwd: Optional[Path] = None, limit: int = 20,
+                 timeout: float = 600):
+        self.command = command
+        self.cwd = cwd
+        self.limit = limit
+        self.timeout = timeout
+
+    def _environment(self, directory: Path, files: List[Path]) -> Dict[str, str]:
+        path = os.environ.get("PYTHONPATH")
+        return {
+            **os.environ,
+            "PYTHONPATH": os.pathsep.join([str(directory), *([path] if path else [])]),
+            "PYTHON2RUST_TRACE_OUTPUT": str(directory / "calls"),
+            "PYTHON2RUST_TRACE_FILES": json.dumps([str(file.resolve()) for file in files]),
+            "PYTHON2RUST_TRACE_LIMIT": str(self.limit)
+        }
+
+    async def _run(self, env: Dict[str, str]) -> bool:
+        process = await asyncio.create_subprocess_shell(
+            self.command,
+            cwd=self.cwd,
+            env=env,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        try:
+            _, stderr = await asyncio.wait_for(process.communicate(), timeout=self.timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError) as e:
+            process.kill()
+            await process.wait()
+            if isinstance(e, asyncio.CancelledError):
+                raise
+            logger.warning(f"`{self.command}` timed out after {self.timeout:.0f} seconds; "
+                           f"keeping the calls recorded so far")
+            return False
+        if process.returncode != 0:
+            logger.warning(f"`{self.command}` exited with code {process.returncode}; keeping the "
+                           "calls recorded before:"
+                           f" {stderr.decode(errors='replace').strip()[-500:]}")
+        return process.returncode == 0
+
+    def _merge(self, directory: Path, files: List[Path]) -> Dict[Path, List[TracedCall]]:
+        sources = {str(file.resolve()): file for file in files}
+        traces: Dict[Path, List[TracedCall]] = {}
+        seen = set()
+        counts: Dict[tuple, int] = {}
+        for path in sorted((directory / "calls").glob("*.json")):
+            try:
+                recorded = json.loads(path.read_text())
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read the calls recorded in {path}: {e}")
+                continue
+            for filename, functions in recorded.items():
+                source = sources.get(filename)
+                if source is None:
+                    continue
+                for function, calls in functions.items():
+                    for call in calls:
+                        key = (source, function, call.get("args"))
+                        if key in seen or counts.get((source, function), 0) >= self.limit:
+                            continue
+                        seen.add(key)
+                        counts[(source, function)] = counts.get((source, function), 0) + 1
+                        traces.setdefault(source, []).append(TracedCall(
+                            function=function,
+                            args=call.get("args", ""),
+                            result=call.get("result"),
+                            raises=call.get("raises")
+                        ))
+        return traces
+
+    async def trace(self, files: List[Path]) -> Dict[Path, List[TracedCall]]:
+        """Run the command and return the calls recorded in each of the files."""
+        logger.info(f"Recording the Python calls of `{self.command}`")
+        with tempfile.TemporaryDirectory(prefix="python2rust_trace_") as temp:
+            directory = Path(temp)
+            (directory / "calls").mkdir()
+            shutil.copyfile(RECORDER, directory / "sitecustomize.py")
+            try:
+                await self._run(self._environment(directory, files))
+            except OSError as e:
+                logger.warning(f"`{self.command}` could not run: {e}")
+                return {}
+            traces = self._merge(directory, files)
+        logger.info(f"Recorded {sum(len(calls) for calls in traces.values())} calls "
+                    f"in {len(traces)} files")
+        return traces
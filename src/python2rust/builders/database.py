@@ -0,0 +1,288 @@
+'''
+Throwaway SQLite and Postgres databases the Python and Rust schemas are created in, and the
+tables, columns, constraints and indexes they end up with.
+'''
+import asyncio
+import json
+import re
+import sqlite3
+import uuid
+from contextlib import asynccontextmanager
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, AsyncIterator, Dict, List, Set, Tuple, Union
+
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+CHECK = re.compile(r"\bCHECK\s*\(", re.IGNORECASE)
+INDEX_DEFINITION = re.compile(r"^CREATE (?:UNIQUE )?INDEX \S+ ON \S+ (.*)$")
+BTREE_COLUMNS = re.compile(r"^USING btree \(([^()]*)\)$")
+
+POSTGRES_COLUMNS = """
+SELECT coalesce(json_agg(row_to_json(c)), '[]') FROM (
+  SELECT cl.relname AS "table", a.attname AS "name", format_type(a.atttypid, a.atttypmod) AS "type",
+         NOT a.attnotnull AS "nullable", pg_get_expr(d.adbin, d.adrelid) AS "default"
+  FROM pg_attribute a
+  JOIN pg_class cl ON cl.oid = a.attrelid
+  LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+  WHERE cl.relnamespace = 'public'::regnamespace AND cl.relkind = 'r'
+    AND a.attnum > 0 AND NOT a.attisdropped
+) c
+"""
+POSTGRES_CONSTRAINTS = """
+SELECT coalesce(json_agg(row_to_json(c)), '[]') FROM (
+  SELECT cl.relname AS "table", pg_get_constraintdef(co.oid) AS "definition"
+  FROM pg_constraint co JOIN pg_class cl ON cl.oid = co.conrelid
+  WHERE co.connamespace = 'public'::regnamespace AND co.contype IN ('p', 'u', 'f', 'c')
+) c
+"""
+# Indexes other than those of primary keys and unique constraints
+POSTGRES_INDEXES = """
+SELECT coalesce(json_agg(row_to_json(c)), '[]') FROM (
+  SELECT cl.relname AS "table", i.indisunique AS "unique",
+         pg_get_indexdef(i.indexrelid) AS "definition"
+  FROM pg_index i JOIN pg_class cl ON cl.oid = i.indrelid
+  WHERE cl.relnamespace = 'public'::regnamespace AND NOT EXISTS (
+    SELECT 1 FROM pg_constraint co
+    WHERE co.conindid = i.indexrelid AND co.contype IN ('p', 'u', 'x'))
+) c
+"""
+
+
+class DatabaseError(Exception):
+    """A statement creating a schema failed."""
+
+
+@dataclass
+class TableSchema:
+    """Columns, constraints and indexes of a table, described by name-independent strings."""
+    # name -> type, nullable and default
+    columns: Dict[str, Dict[str, Any]] = field(default_factory=dict)
+    # Primary key, unique, foreign key and check constraints
+    constraints: Set[str] = field(default_factory=set)
+    indexes: Set[str] = field(default_factory=set)
+
+
+Schema = Dict[str, TableSchema]
+
+
+def _spaced(sql: str) -> str:
+    return " ".join(sql.split())
+
+
+def _type(declared: str) -> str:
+    return re.sub(r"\s*\(\s*", "(", _spaced(declared).upper())
+
+
+def check_constraints(create_sql: str) -> List[str]:
+    """The CHECK constraints of a CREATE TABLE statement, whitespace normalized."""
+    checks = []
+    for match in CHECK.finditer(create_sql):
+        depth, start = 0, match.end() - 1
+        for end in range(start, len(create_sql)):
+            depth += {"(": 1, ")": -1}.get(create_sql[end], 0)
+            if depth == 0:
+                checks.append(f"CHECK {_spaced(create_sql[start:end + 1])}")
+                break
+    return checks
+
+
+class SqliteDatabase:
+    """A SQLite database file."""
+
+    def __init__(self, path: Path):
+        self.path = Path(path)
+
+    @property
+    def url(self) -> str:
+        return f"sqlite:///{self.path.resolve()}"
+
+    async def execute(self, sql: str) -> None:
+        connection = sqlite3.connect(self.path)
+        try:
+            connection.executescript(sql)
+        except sqlite3.Error as e:
+            raise DatabaseError(str(e)) from e
+        finally:
+            connection.close()
+
+    async def schema(self) -> Schema:
+        connection = sqlite3.connect(self.path)
+        try:
+            return {name: self._table(connection, name, sql or "")
+                    for name, sql in connection.execute(
+                "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND "
+                "name NOT LIKE 'sqlite_%'")}
+        finally:
+            connection.close()
+
+    @staticmethod
+    def _table(connection: sqlite3.Connection, name: str, sql: str) -> TableSchema:
+        table = TableSchema()
+        columns = connection.execute(f'PRAGMA table_info("{name}")').fetchall()
+        for _, column, declared, not_null, default, primary in columns:
+            # Primary key columns count as NOT NULL, as in other databases, whether declared or not
+            table.columns[column] = {"type": _type(declared), "nullable": not (not_null or primary),
+                                     "default": default}
+        primary_key = [column[1]
+                       for column in sorted(columns, key=lambda column: column[5]) if column[5]]
+        if primary_key:
+            table.constraints.add(f"PRIMARY KEY ({', '.join(primary_key)})")
+
+        foreign_keys: Dict[int, Tuple[List[str], List[str], str, str, str]] = {}
+        for key, _, target, column, referenced, on_update, on_delete, _ in connection.execute(
+                f'PRAGMA foreign_key_list("{name}")'):
+            columns_from, columns_to, *_ = foreign_keys.setdefault(key, ([], [], target,
+                                                                         on_update, on_delete))
+            columns_from.append(column)
+            if referenced is not None:
+                columns_to.append(referenced)
+        for columns_from, columns_to, target, on_update, on_delete in foreign_keys.values():
+            definition = f"FOREIGN KEY ({', '.join(columns_from)}) REFERENCES {target}"
+            definition += f"({', '.join(columns_to)})" if columns_to else ""
+            for action, rule in (("UPDATE", on_update), ("DELETE", on_delete)):
+                if rule != "NO ACTION":
+                    definition += f" ON {action} {rule}"
+            table.constraints.add(definition)
+
+        for _, index, unique, origin, partial in connection.execute(f'PRAGMA index_list("{name}")'):
+            if origin == "pk":
+                continue
+            indexed = ", ".join(row[2] or "<expression>" for row in sorted(
+                connection.execute(f'PRAGMA index_info("{index}")').fetchall()))
+            where = ""
+            if partial:
+                (index_sql,) = connection.execute(
+                    "SELECT sql FROM sqlite_master WHERE type = 'index' AND name = ?",
+                    (index,)).fetchone()
+                where = " WHERE " + _spaced(re.split(r"\bWHERE\b", index_sql, 1,
+                                                     flags=re.IGNORECASE)[1])
+            # A unique index enforces the same as a UNIQUE constraint, which SQLite
+            # implements with one
+            if unique:
+                table.constraints.add(f"UNIQUE ({indexed}){where}")
+            else:
+                table.indexes.add(f"INDEX ({indexed}){where}")
+        table.constraints.update(check_constraints(sql))
+        return table
+
+
+class PostgresServer:
+    """A Postgres container started for one comparison, giving each side its own database."""
+
+    def __init__(self, image: str = "postgres:16", docker: str = "docker",
+                 startup_timeout: float = 60):
+        self.image = image
+        self.docker = docker
+        self.startup_timeout = startup_timeout
+        self.name = f"python2rust_schema_{uuid.uuid4().hex[:8]}"
+        self.port = 0
+
+    async def _docker(self, *args: str, stdin: str = "") -> Tuple[int, str, str]:
+        process = await asyncio.create_subprocess_exec(
+            self.docker, *args,
+            stdin=asyncio.subprocess.PIPE,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        stdout, stderr = await process.communicate(stdin.encode())
+        return process.returncode, stdout.decode(errors="replace"), stderr.decode(errors="replace")
+
+    async def start(self) -> None:
+        returncode, _, stderr = await self._docker(
+            "run", "-d", "--rm", "--name", self.name, "-e", "POSTGRES_HOST_AUTH_METHOD=trust",
+            "-p", "127.0.0.1::5432", self.image)
+        if returncode != 0:
+            raise RuntimeError(f"could not start {self.image}: {stderr.strip()}")
+        _, port, _ = await self._docker("port", self.name, "5432/tcp")
+        self.port = int(port.split()[0].rsplit(":", 1)[1])
+
+        # The server the image initializes the data with listens on no TCP port,
+        # unlike the final one
+        deadline = asyncio.get_running_loop().time() + self.startup_timeout
+        while (await self._docker("exec", self.name, "pg_isready", "-h", "127.0.0.1", "-U",
+                                  "postgres"))[0] != 0:
+            if asyncio.get_running_loop().time() > deadline:
+                raise RuntimeError(f"{self.image} did not accept connections within "
+                                   f"{self.startup_timeout:g}s")
+            await asyncio.sleep(0.5)
+        logger.info(f"Postgres {self.image} started on port {self.port}")
+
+    async def stop(self) -> None:
+        await self._docker("rm", "-f", self.name)
+
+    async def psql(self, database: str, sql: str) -> str:
+        returncode, stdout, stderr = await self._docker(
+            "exec", "-i", self.name, "psql", "-U", "postgres", "-d", database,
+            "-v", "ON_ERROR_STOP=1", "-q", "-A", "-t", stdin=sql)
+        if returncode != 0:
+            raise DatabaseError(stderr.strip())
+        return stdout
+
+    async def create_database(self, name: str) -> "PostgresDatabase":
+        await self.psql("postgres", f"CREATE DATABASE {name};")
+        return PostgresDatabase(self, name)
+
+
+class PostgresDatabase:
+    """A database of a PostgresServer."""
+
+    def __init__(self, server: PostgresServer, name: str):
+        self.server = server
+        self.name = name
+
+    @property
+    def url(self) -> str:
+        return f"postgresql://postgres@127.0.0.1:{self.server.port}/{self.name}"
+
+    async def execute(self, sql: str) -> None:
+        await self.server.psql(self.name, sql)
+
+    async def _rows(self, query: str) -> List[Dict[str, Any]]:
+        return json.loads(await self.server.psql(self.name, query))
+
+    async def schema(self) -> Schema:
+        tables: Schema = {}
+        for row in await self._rows(POSTGRES_COLUMNS):
+            tables.setdefault(row["table"], TableSchema()).columns[row["name"]] = {
+                "type": _type(row["type"]), "nullable": row["nullable"], "default": row["default"]}
+        for row in await self._rows(POSTGRES_CONSTRAINTS):
+            tables.setdefault(row["table"],
+                              TableSchema()).constraints.add(_spaced(row["definition"]))
+        for row in await self._rows(POSTGRES_INDEXES):
+            table = tables.setdefault(row["table"], TableSchema())
+            match = INDEX_DEFINITION.match(row["definition"])
+            definition = match.group(1) if match else row["definition"]
+            columns = BTREE_COLUMNS.match(definition)
+            # A unique btree index enforces the same as a UNIQUE constraint
+            if row["unique"] and columns:
+                table.constraints.add(f"UNIQUE ({columns.group(1)})")
+            else:
+                table.indexes.add(("UNIQUE " if row["unique"] else "") + definition)
+        return tables
+
+
+Database = Union[SqliteDatabase, PostgresDatabase]
+
+
+@asynccontextmanager
+async def ephemeral_databases(
+    engine: str,
+    directory: Path,
+    postgres_image: str = "postgres:16",
+    docker: str = "docker"
+) -> AsyncIterator[Tuple[Database, Database]]:
+    """Two empty databases, for the Python and the Rust schema: SQLite files in directory, or
+    databases of a Postgres container removed on exit."""
+    if engine == "sqlite":
+        yield SqliteDatabase(Path(directory) / "python.db"), SqliteDatabase(Path(
+            directory) / "rust.db")
+        return
+    server = PostgresServer(postgres_image, docker)
+    try:
+        await server.start()
+        yield await server.create_database("python"), await server.create_database("rust")
+    finally:
+        await server.stop()
@@ -0,0 +1,145 @@
+'''
+criterion benchmarks of the hot functions of a generated crate, with the Python script timing
+the same calls, so that later migrations can be compared with the current one.
+'''
+import os
+import re
+from pathlib import Path
+from typing import List, Optional
+
+from ..analyzers.fuzz_targets import FuzzType
+from ..analyzers.hot_functions import HotFunction
+from ..checkers.fuzz import rust_literal
+from ..utils.cargo_manifest import add_to_section, section_entries
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+BENCH_NAME = "python2rust_hot"
+PYTHON_TIMING = "python_timing.py"
+# Code include! cannot place in a module: inner attributes, paths from the crate root, module files
+NOT_INCLUDABLE = re.compile(r"^\s*#!\[|\bcrate::|^\s*(?:pub\s+)?mod\s+\w+\s*;", re.MULTILINE)
+
+RUST_BENCH = """//! criterion benchmarks of the hot functions of src/main.rs, generated by
+//! python2rust. `cargo bench -- --save-baseline before` records a baseline that
+//! `cargo bench -- --baseline before` compares a later migration with.
+use criterion::{criterion_group, criterion_main};
+
+#[allow(dead_code, unused_imports, clippy::all)]
+mod app {
+    include!("../src/main.rs");
+
+    pub fn benches(c: &mut criterion::Criterion) {
+BENCHES
+    }
+}
+
+criterion_group!(benches, app::benches);
+criterion_main!(benches);
+"""
+
+PYTHON_BENCH = '''"""
+Timings of the hot functions of SOURCE_NAME, the same calls as benches/BENCH_NAME.rs.
+Generated by python2rust; run `python benches/PYTHON_TIMING [--json]`.
+"""
+import importlib.util
+import json
+import sys
+import timeit
+from pathlib import Path
+
+SOURCE = Path(__file__).resolve().parent / SOURCE_PATH
+CALLS = CALLS_LIST
+
+spec = importlib.util.spec_from_file_location("python2rust_timed", SOURCE)
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+
+results = {}
+for name, args in CALLS:
+    timer = timeit.Timer(lambda: getattr(module, name)(*args))
+    number, _ = timer.autorange()
+    results[name] = min(timer.repeat(repeat=5, number=number)) / number
+    if "--json" not in sys.argv:
+        print(f"{name}: {results[name] * 1e6:.2f} µs per call")
+if "--json" in sys.argv:
+    print(json.dumps(results))
+'''
+
+
+def _owned(kind: FuzzType) -> FuzzType:
+    """The owned type of a borrowed string or list parameter."""
+    rust = "String" if kind.kind == "str" else f"Vec<{kind.item.rust}>"
+    return FuzzType(kind.kind, rust, kind.low, kind.high, kind.item)
+
+
+def rust_benchmark(function: HotFunction) -> str:
+    """The criterion benchmark of a call; strings and lists are built once, outside the measure."""
+    setup, args = [], []
+    for index, (value, kind) in enumerate(zip(function.args, function.target.params)):
+        if kind.kind in ("str", "list"):
+            setup.append(f"let arg{index} = {rust_literal(value, _owned(kind))};")
+            args.append(f"&arg{index}" if kind.rust.startswith("&") else f"arg{index}.clone()")
+        else:
+            args.append(rust_literal(value, kind))
+    call = f"{function.name}({', '.join(f'std::hint::black_box({arg})' for arg in args)})"
+    lines = [
+        "{",
+        *(f"    {line}" for line in setup),
+        f"    c.bench_function(\"{function.name}\", |b| b.iter(|| {call}));",
+        "}"
+    ]
+    return "\n".join(f"        {line}" for line in lines)
+
+
+def bench_manifest(toml_content: str, criterion: str) -> str:
+    """The manifest with criterion as a dev-dependency and the benchmark without
+    the default harness."""
+    if "criterion" not in section_entries(toml_content, "[dev-dependencies]"):
+        toml_content = add_to_section(toml_content, "[dev-dependencies]",
+                                      [f'criterion = "{criterion}"'])
+    if f'name = "{BENCH_NAME}"' not in toml_content:
+        toml_content = toml_content.rstrip("\n") + (f'\n\n[[bench]]\nname = "{BENCH_NAME}"\n'
+                                                    'harness = false\n')
+    return toml_content
+
+
+def write_hot_benchmarks(
+    output_dir: Path,
+    python_file: Path,
+    rust_code: str,
+    toml_content: str,
+    hot: List[HotFunction],
+    criterion: str = "0.5"
+) -> Optional[str]:
+    """Write benches/python2rust_hot.rs and benches/python_timing.py for the hot functions.
+
+    Returns the manifest declaring the benchmark, written to Cargo.toml, or None when there
+    is nothing to benchmark or the code cannot be included in a benchmark.
+    """
+    if not hot:
+        return None
+    if NOT_INCLUDABLE.search(rust_code):
+        logger.warning(f"Not benchmarking {output_dir}: its code uses inner attributes, crate:: "
+                       "paths or module files, which a benchmark cannot include")
+        return None
+    output_dir = Path(output_dir)
+    benches = output_dir / "benches"
+    benches.mkdir(parents=True, exist_ok=True)
+    (benches / f"{BENCH_NAME}.rs").write_text(
+        RUST_BENCH.replace("BENCHES", "\n".join(rust_benchmark(function) for function in hot)))
+
+    source_path = os.path.relpath(Path(python_file).resolve(), benches.resolve())
+    (benches / PYTHON_TIMING).write_text(
+        PYTHON_BENCH
+        .replace("SOURCE_NAME", Path(python_file).name)
+        .replace("BENCH_NAME", BENCH_NAME)
+        .replace("PYTHON_TIMING", PYTHON_TIMING)
+        .replace("SOURCE_PATH", repr(Path(source_path).as_posix()))
+        .replace("CALLS_LIST", repr([[function.name, function.args] for function in hot])))
+
+    manifest = bench_manifest(toml_content, criterion)
+    (output_dir / "Cargo.toml").write_text(manifest)
+    logger.info(f"Benchmarks of {', '.join(function.name for function in hot)} "
+                f"written to {benches}")
+    return manifest
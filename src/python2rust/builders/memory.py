@@ -0,0 +1,101 @@
+'''
+Peak resident memory and allocation counts of the Python and Rust runs compared in verification.
+'''
+import asyncio
+import os
+import re
+import shutil
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from ..utils.logging import setup_logger
+from .benchmark import tree_memory_mb
+
+logger = setup_logger()
+
+# valgrind runs programs this many times slower at most
+VALGRIND_SLOWDOWN = 50
+HEAP_USAGE = re.compile(r"total heap usage: ([\d,]+) allocs")
+
+
+@dataclass
+class MemoryUsage:
+    """Peak resident memory and heap allocations of a run; None when they were not measured."""
+    peak_rss_mb: Optional[float] = None
+    allocations: Optional[int] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"peak_rss_mb": self.peak_rss_mb, "allocations": self.allocations}
+
+
+def read_allocations(log_file: Path) -> Optional[int]:
+    """Heap allocations in the summary of a valgrind log, None without one."""
+    try:
+        match = HEAP_USAGE.search(Path(log_file).read_text(errors="replace"))
+    except OSError:
+        return None
+    return int(match.group(1).replace(",", "")) if match else None
+
+
+def memory_comparison(run: str, python: MemoryUsage, rust: MemoryUsage) -> Dict[str, Any]:
+    """Both usages of a workload with the ratio of each measure, Python over Rust."""
+    def ratio(expected: Optional[float], actual: Optional[float]) -> Optional[float]:
+        return round(expected / actual, 2) if expected is not None and actual else None
+
+    return {
+        "run": run,
+        "python": python.to_dict(),
+        "rust": rust.to_dict(),
+        "peak_rss_ratio": ratio(python.peak_rss_mb, rust.peak_rss_mb),
+        "allocations_ratio": ratio(python.allocations, rust.allocations)
+    }
+
+
+class MemoryProfiler:
+    """Measures the memory of runs: their peak resident memory, sampled while they run, and their
+    heap allocations in a second run under valgrind, when installed.
+
+    Runs shorter than the sample interval may end before their first sample. Python runs are
+    counted with PYTHONMALLOC=malloc, so that each allocation reaches valgrind rather than the
+    blocks of the interpreter's pools.
+    """
+
+    def __init__(self, allocations: bool = True, valgrind: str = "valgrind",
+                 sample_interval: float = 0.01):
+        self.allocations = allocations
+        self.valgrind = valgrind
+        self.sample_interval = sample_interval
+        self._warned = False
+
+    async def _sample(self, pid: int, usage: MemoryUsage) -> None:
+        while True:
+            memory_mb = tree_memory_mb(pid)
+            if memory_mb > 0:
+                usage.peak_rss_mb = max(usage.peak_rss_mb or 0.0, round(memory_mb, 2))
+            await asyncio.sleep(self.sample_interval)
+
+    def sample(self, pid: int, usage: MemoryUsage) -> "asyncio.Task[None]":
+        """Keep the peak resident memory of a process and its children in usage, until cancelled."""
+        return asyncio.create_task(self._sample(pid, usage))
+
+    @property
+    def counts_allocations(self) -> bool:
+        if not self.allocations or os.name == 'nt':
+            return False
+        if shutil.which(self.valgrind) is None:
+            if not self._warned:
+                logger.warning(f"Not counting allocations: {self.valgrind} is not installed")
+                self._warned = True
+            return False
+        return True
+
+    def counted(self, command: List[str], log_file: Path) -> List[str]:
+        """The command run under valgrind, which logs its heap usage to log_file."""
+        return [self.valgrind, "--tool=memcheck", "--leak-check=no", "--error-exitcode=0",
+                f"--log-file={log_file}", *command]
+
+    @staticmethod
+    def counted_env(python: bool) -> Dict[str, str]:
+        """Environment variables of a counted run."""
+        return {"PYTHONMALLOC": "malloc"} if python else {}
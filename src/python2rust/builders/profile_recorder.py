@@ -0,0 +1,34 @@
+"""
+Profiles the Python processes of a workload with cProfile.
+
+Profiler installs this file as the sitecustomize module of the workload command,
+so it only depends on the standard library. It does nothing unless
+PYTHON2RUST_PROFILE_OUTPUT is set; each process writes its pstats to
+<PYTHON2RUST_PROFILE_OUTPUT>/<pid>.prof when it exits, including when terminated.
+"""
+import atexit
+import cProfile
+import os
+import signal
+import sys
+
+
+def _install(output):
+    profiler = cProfile.Profile()
+
+    def write():
+        profiler.disable()
+        profiler.dump_stats(os.path.join(output, f"{os.getpid()}.prof"))
+
+    def terminated(signum, frame):
+        # Exit normally for atexit to write the profile
+        sys.exit(128 + signum)
+
+    atexit.register(write)
+    if signal.getsignal(signal.SIGTERM) is signal.SIG_DFL:
+        signal.signal(signal.SIGTERM, terminated)
+    profiler.enable()
+
+
+if os.environ.get("PYTHON2RUST_PROFILE_OUTPUT"):
+    _install(os.environ["PYTHON2RUST_PROFILE_OUTPUT"])
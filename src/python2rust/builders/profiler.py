@@ -0,0 +1,80 @@
+import os
+import pstats
+import shutil
+import subprocess
+import tempfile
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from ..analyzers.profile import HotPath, hot_paths
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+RECORDER = Path(__file__).with_name("profile_recorder.py")
+
+
+class Profiler:
+    """Runs a workload command with its Python processes under cProfile and keeps the functions
+    of the source files taking at least threshold of its CPU time.
+
+    Like CallTracer, the profiler is installed as sitecustomize through PYTHONPATH, so any
+    command starting Python works. A workload that outlives timeout is terminated, and keeps
+    the profile of what it ran until then.
+    """
+
+    def __init__(self, command: str, cwd: Optional[Path] = None, threshold: float = 0.05,
+                 timeout: float = 600):
+        self.command = command
+        self.cwd = cwd
+        self.threshold = threshold
+        self.timeout = timeout
+
+    def _environment(self, directory: Path) -> Dict[str, str]:
+        path = os.environ.get("PYTHONPATH")
+        return {
+            **os.environ,
+            "PYTHONPATH": os.pathsep.join([str(directory), *([path] if path else [])]),
+            "PYTHON2RUST_PROFILE_OUTPUT": str(directory / "profiles")
+        }
+
+    def _run(self, env: Dict[str, str]) -> None:
+        process = subprocess.Popen(self.command, shell=True, cwd=self.cwd, env=env,
+                                   stdout=subprocess.DEVNULL, stderr=subprocess.PIPE)
+        try:
+            _, stderr = process.communicate(timeout=self.timeout)
+        except subprocess.TimeoutExpired:
+            process.terminate()
+            try:
+                process.communicate(timeout=10)
+            except subprocess.TimeoutExpired:
+                process.kill()
+                process.communicate()
+            logger.warning(f"`{self.command}` timed out after {self.timeout:.0f} seconds; "
+                           f"keeping the profile of what it ran")
+            return
+        if process.returncode != 0:
+            logger.warning(f"`{self.command}` exited with code {process.returncode}; keeping its "
+                           f"profile: {stderr.decode(errors='replace').strip()[-500:]}")
+
+    def profile(self, files: List[Path]) -> Dict[Path, List[HotPath]]:
+        """Run the workload and return the hot paths of each of the files with any."""
+        logger.info(f"Profiling `{self.command}`")
+        with tempfile.TemporaryDirectory(prefix="python2rust_profile_") as temp:
+            directory = Path(temp)
+            (directory / "profiles").mkdir()
+            shutil.copyfile(RECORDER, directory / "sitecustomize.py")
+            try:
+                self._run(self._environment(directory))
+            except OSError as e:
+                logger.warning(f"`{self.command}` could not run: {e}")
+                return {}
+            profiles = sorted((directory / "profiles").glob("*.prof"))
+            if not profiles:
+                logger.warning(f"`{self.command}` ran no profiled Python process")
+                return {}
+            stats = pstats.Stats(*(str(profile) for profile in profiles))
+        paths = hot_paths(stats.stats, files, self.threshold, Path(self.cwd or "."))
+        logger.info(f"Found {sum(len(found) for found in paths.values())} hot paths "
+                    f"in {len(paths)} files")
+        return paths
@@ -0,0 +1,65 @@
+import ast
+import asyncio
+import shlex
+import tempfile
+from pathlib import Path
+from typing import Optional, Tuple
+
+from ..analyzers.python2 import python2_constructs
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+# {file} is replaced by the copy to convert in place
+DEFAULT_COMMAND = "2to3 --write --nobackups --no-diffs {file}"
+
+
+def _constructs_text(python_code: str) -> str:
+    return ", ".join(f"line {construct.line} {construct.construct}"
+                     for construct in python2_constructs(python_code))
+
+
+async def convert_python2(
+    source: Path,
+    python_code: str,
+    command: Optional[str] = None,
+    timeout: float = 120
+) -> Tuple[Optional[str], str]:
+    """Convert Python 2 code to Python 3 with 2to3 or the given command, run on a copy of it.
+
+    Returns the converted code, or None with the reason the conversion failed: the command
+    could not run, or its result still does not parse or still has Python 2 constructs.
+    """
+    with tempfile.TemporaryDirectory(prefix="python2rust_python2_") as temp:
+        copy = Path(temp) / source.name
+        copy.write_text(python_code)
+        arguments = [argument.replace("{file}", str(copy))
+                     for argument in shlex.split(command or DEFAULT_COMMAND)]
+        try:
+            process = await asyncio.create_subprocess_exec(
+                *arguments,
+                cwd=temp,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE
+            )
+        except OSError as e:
+            return None, f"{arguments[0]} could not run: {e}"
+        try:
+            _, stderr = await asyncio.wait_for(process.communicate(), timeout=timeout)
+        except asyncio.TimeoutError:
+            process.kill()
+            await process.wait()
+            return None, f"{arguments[0]} timed out after {timeout:.0f} seconds"
+        if process.returncode != 0:
+            return None, f"{arguments[0]} failed: {stderr.decode(errors='replace').strip()[-500:]}"
+        converted = copy.read_text()
+
+    try:
+        ast.parse(converted)
+    except SyntaxError as e:
+        return None, f"the converted code does not parse: line {e.lineno} {e.msg}"
+    remaining = _constructs_text(converted)
+    if remaining:
+        return None, f"the converted code still has Python 2 constructs: {remaining}"
+    logger.info(f"Converted {source.name} from Python 2")
+    return converted, ""
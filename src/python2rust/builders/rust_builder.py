@@ -2,11 +2,17 @@
 Modules that handles build and testing Rust code.
 '''
 import asyncio
+import os
+import shutil
+import signal
 from pathlib import Path
-from typing import Tuple, Optional, Dict, Any
+from typing import Tuple, Optional, Dict, Any, List
 from datetime import datetime
 
+from ..config.settings import StepLimits, VerificationLimits
+from ..utils.limits import preexec
 from ..utils.logging import setup_logger
+from .sandbox import Sandbox
 
 logger = setup_logger()
 
@@ -14,30 +20,87 @@ logger = setup_logger()
 class RustBuilder:
     """Handles building and testing Rust code."""
 
-    def __init__(self, output_dir: Path, build_timeout: int = 300):
+    def __init__(
+        self,
+        output_dir: Path,
+        build_timeout: int = 300,
+        log_dir: Optional[Path] = None,
+        sandbox: Optional[Sandbox] = None,
+        limits: Optional[VerificationLimits] = None,
+        env: Optional[Dict[str, str]] = None
+    ):
         self.output_dir = output_dir
         self.build_timeout = build_timeout
         self.src_dir = output_dir / "src"
-
-    async def _run_command(self, cmd: list[str], cwd: Path) -> Tuple[int, str, str]:
-        """Run a command asynchronously."""
+        self.log_dir = log_dir  # numbered log of every cargo command when set
+        self.sandbox = sandbox  # container cargo commands run in when set
+        # Time and memory of cargo commands; cargo test uses the test limits
+        self.limits = limits or VerificationLimits(
+            build=StepLimits(timeout=build_timeout), test=StepLimits(timeout=build_timeout))
+        self.env = env or {}  # added to the environment of every cargo command
+
+    async def _run_command(
+        self,
+        cmd: list[str],
+        cwd: Path,
+        step: str = "build",
+        env: Optional[Dict[str, str]] = None
+    ) -> Tuple[int, str, str]:
+        """Run a command asynchronously with the limits of its step, in the
+        sandbox when there is one.
+
+        env is added to the environment of the command, over that of the builder.
+        """
+        limits = getattr(self.limits, step)
+        env = {**self.env, **(env or {})} or None
+        if self.sandbox is None:
+            return await self._execute(cmd, cwd, limits, env)
+        if self.sandbox.needs_fetch(cwd):
+            # Downloads run on the host, the sandbox has no network
+            returncode, _, stderr = await self._execute(["cargo", "fetch"], cwd, self.limits.build)
+            if returncode == 0:
+                self.sandbox.fetched(cwd)
+            else:
+                logger.warning("cargo fetch failed, building offline anyway: %s",
+                               stderr.strip()[-500:])
+        command = self.sandbox.command(cmd, cwd, env=env, timeout=limits.timeout,
+                                       memory_mb=limits.memory_mb)
+        # The container enforces the memory limit
+        return await self._execute(command, cwd, StepLimits(timeout=limits.timeout))
+
+    async def _execute(
+        self,
+        cmd: list[str],
+        cwd: Path,
+        limits: StepLimits,
+        env: Optional[Dict[str, str]] = None
+    ) -> Tuple[int, str, str]:
         try:
             process = await asyncio.create_subprocess_exec(
                 *cmd,
                 cwd=cwd,
+                env={**os.environ, **env} if env else None,
                 stdout=asyncio.subprocess.PIPE,
-                stderr=asyncio.subprocess.PIPE
+                stderr=asyncio.subprocess.PIPE,
+                # Its own process group, so that a timeout also stops rustc and the test binaries
+                preexec_fn=preexec(limits.memory_mb, new_session=True)
             )
 
             try:
                 stdout, stderr = await asyncio.wait_for(
                     process.communicate(),
-                    timeout=self.build_timeout
+                    timeout=limits.timeout
                 )
             except asyncio.TimeoutError as e:
-                process.kill()
+                self._kill(process)
+                await process.wait()
                 raise TimeoutError(
-                    f"Command timed out after {self.build_timeout} seconds") from e
+                    f"Command timed out after {limits.timeout:g} seconds: "
+                    f"`{' '.join(cmd)}` did not finish") from e
+            except asyncio.CancelledError:
+                self._kill(process)
+                await process.wait()
+                raise
 
             return (
                 process.returncode,
@@ -49,6 +112,23 @@ class RustBuilder:
             logger.error("Command execution failed: %s", e)
             raise
 
+    @staticmethod
+    def _kill(process: asyncio.subprocess.Process) -> None:
+        try:
+            if os.name != 'nt':
+                os.killpg(os.getpgid(process.pid), signal.SIGKILL)
+            else:
+                process.kill()
+        except ProcessLookupError:
+            pass
+
+    def cleanup(self) -> None:
+        """Remove build artifacts left behind by an interrupted build."""
+        target_dir = self.output_dir / "target"
+        if target_dir.exists():
+            shutil.rmtree(target_dir, ignore_errors=True)
+            logger.info("Removed build directory %s", target_dir)
+
     def prepare_project(self, rust_code: str, toml_content: str) -> Path:
         """Prepare Rust project structure."""
         try:
@@ -65,6 +145,17 @@ class RustBuilder:
             logger.error("Failed to prepare project: %s", e)
             raise
 
+    def _archive_log(self, command: str, log_content: str) -> None:
+        """Keep the log of each cargo command as <log_dir>/<n>_<command>.log."""
+        if self.log_dir is None:
+            return
+        try:
+            self.log_dir.mkdir(parents=True, exist_ok=True)
+            number = len(list(self.log_dir.glob("*.log"))) + 1
+            (self.log_dir / f"{number:03d}_{command}.log").write_text(log_content)
+        except OSError as e:
+            logger.warning("Could not archive %s log: %s", command, e)
+
     def _create_build_log(
         self,
         cmd: list[str],
@@ -127,6 +218,7 @@ STDERR:
             # Save build log
             log_file = project_dir / "build.log"
             log_file.write_text(build_log)
+            self._archive_log("build", build_log)
 
             # Prepare build info
             build_info = {
@@ -145,7 +237,22 @@ STDERR:
 
         except Exception as e:
             logger.error("Build process failed: %s", str(e))
-            return False, str(e), {"error": str(e)}
+            return False, str(e), {"error": str(e), "timed_out": isinstance(e, TimeoutError)}
+
+    async def clean(self, release: bool = True) -> bool:
+        """Remove the artifacts of a profile, so that its next build compiles the
+        dependencies too."""
+        if not (self.output_dir / "target").exists():
+            return True
+        cmd = ["cargo", "clean", "--release"] if release else ["cargo", "clean"]
+        try:
+            returncode, _, stderr = await self._run_command(cmd, self.output_dir)
+        except Exception as e:
+            logger.warning(f"cargo clean failed: {e}")
+            return False
+        if returncode != 0:
+            logger.warning(f"cargo clean failed: {stderr.strip()[-500:]}")
+        return returncode == 0
 
     async def check(
         self,
@@ -159,7 +266,10 @@ STDERR:
 
             # Run check
             cmd = ["cargo", "check"]
+            start_time = datetime.now()
             returncode, stdout, stderr = await self._run_command(cmd, project_dir)
+            self._archive_log("check", self._create_build_log(
+                cmd, returncode, stdout, stderr, (datetime.now() - start_time).total_seconds()))
 
             # Create check info
             check_info = {
@@ -176,7 +286,7 @@ STDERR:
 
         except Exception as e:
             logger.error(f"Check process failed: {e}")
-            return False, str(e), {"error": str(e)}
+            return False, str(e), {"error": str(e), "timed_out": isinstance(e, TimeoutError)}
 
     async def test(
         self,
@@ -190,7 +300,10 @@ STDERR:
 
             # Run tests
             cmd = ["cargo", "test"]
-            returncode, stdout, stderr = await self._run_command(cmd, project_dir)
+            start_time = datetime.now()
+            returncode, stdout, stderr = await self._run_command(cmd, project_dir, step="test")
+            self._archive_log("test", self._create_build_log(
+                cmd, returncode, stdout, stderr, (datetime.now() - start_time).total_seconds()))
 
             # Create test info
             test_info = {
@@ -208,21 +321,61 @@ STDERR:
 
         except Exception as e:
             logger.error(f"Test process failed: {e}")
-            return False, str(e), {"error": str(e)}
+            return False, str(e), {"error": str(e), "timed_out": isinstance(e, TimeoutError)}
+
+    async def miri(
+        self,
+        rust_code: str,
+        toml_content: str,
+        flags: Optional[List[str]] = None,
+        toolchain: str = "nightly"
+    ) -> Tuple[bool, Optional[str], Dict[str, Any]]:
+        """Run the tests under Miri (`cargo +<toolchain> miri test`), with flags as MIRIFLAGS."""
+        try:
+            project_dir = self.prepare_project(rust_code, toml_content)
+
+            cmd = ["cargo", f"+{toolchain}", "miri", "test"]
+            start_time = datetime.now()
+            returncode, stdout, stderr = await self._run_command(
+                cmd, project_dir, step="miri", env={"MIRIFLAGS": " ".join(flags or [])})
+            self._archive_log("miri", self._create_build_log(
+                cmd, returncode, stdout, stderr, (datetime.now() - start_time).total_seconds()))
+
+            miri_info = {
+                "success": returncode == 0,
+                "output": stdout,
+                "output_dir": str(project_dir)
+            }
+
+            if returncode == 0:
+                logger.info("Tests passed under Miri")
+                return True, None, miri_info
+            else:
+                logger.error("Tests failed under Miri")
+                return False, stderr, miri_info
+
+        except Exception as e:
+            logger.error(f"Miri process failed: {e}")
+            return False, str(e), {"error": str(e), "timed_out": isinstance(e, TimeoutError)}
 
     async def clippy(
         self,
         rust_code: str,
-        toml_content: str
+        toml_content: str,
+        lint_args: Optional[List[str]] = None
     ) -> Tuple[bool, Optional[str], Dict[str, Any]]:
-        """Run clippy lints on the code."""
+        """Run clippy lints on the code; lint_args (-D/-A/-W <lint>) default to -D warnings."""
         try:
             # Prepare project
             project_dir = self.prepare_project(rust_code, toml_content)
 
             # Run clippy
-            cmd = ["cargo", "clippy", "--", "-D", "warnings"]
+            cmd = ["cargo", "clippy", "--",
+                   *(lint_args if lint_args is not None else ["-D", "warnings"])]
+            start_time = datetime.now()
             returncode, stdout, stderr = await self._run_command(cmd, project_dir)
+            self._archive_log("clippy", self._create_build_log(
+                cmd, returncode, stdout, stderr, (datetime.now() - start_time).total_seconds()))
 
             # Create clippy info
             clippy_info = {
@@ -240,4 +393,4 @@ STDERR:
 
         except Exception as e:
             logger.error(f"Clippy process failed: {e}")
-            return False, str(e), {"error": str(e)}
+            return False, str(e), {"error": str(e), "timed_out": isinstance(e, TimeoutError)}
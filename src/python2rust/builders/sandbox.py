@@ -0,0 +1,92 @@
+'''
+Runs the commands of generated code in a Docker container, so that model output never
+executes directly on the developer's machine.
+'''
+import hashlib
+import math
+import os
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional
+
+from ..config.settings import SandboxConfig
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+# CARGO_HOME of the official Rust images
+CONTAINER_CARGO_HOME = "/usr/local/cargo"
+
+
+def cargo_home() -> Path:
+    return Path(os.environ.get("CARGO_HOME", Path.home() / ".cargo"))
+
+
+class Sandbox:
+    """Wraps commands in `docker run` with resource limits and, by default, no network.
+
+    The working directory and the extra paths of a command are mounted at the same
+    paths in the container, so paths in arguments and output stay valid. Dependencies
+    cannot be downloaded without network: they are fetched on the host with
+    `cargo fetch`, which runs no build script, and the host's registry is mounted
+    read-only.
+    """
+
+    def __init__(self, config: SandboxConfig):
+        self.config = config
+        self._fetched: Dict[Path, str] = {}
+
+    def command(
+        self,
+        args: List[str],
+        cwd: Path,
+        env: Optional[Dict[str, str]] = None,
+        mounts: Iterable[Path] = (),
+        ports: Iterable[int] = (),
+        timeout: Optional[float] = None,
+        memory_mb: Optional[int] = None
+    ) -> List[str]:
+        """The docker command running args in cwd; ports are published on 127.0.0.1.
+
+        With a timeout the command is killed inside the container, which ends it, while
+        killing the docker client would not. memory_mb replaces the configured memory.
+        """
+        ports = list(ports)
+        command = [
+            self.config.docker, "run", "--rm", "-i", "--init",
+            "--network", self.config.server_network if ports else self.config.network,
+            "--memory", f"{memory_mb}m" if memory_mb is not None else self.config.memory,
+            "--cpus", str(self.config.cpus),
+            "--pids-limit", str(self.config.pids),
+            "-w", str(cwd),
+            "-e", "CARGO_NET_OFFLINE=true"
+        ]
+        if hasattr(os, "getuid"):
+            # Files written to the mounts belong to the developer, not root
+            command.extend(["--user", f"{os.getuid()}:{os.getgid()}"])
+        volumes = {Path(cwd).resolve(), *(Path(path).resolve() for path in mounts)}
+        for volume in sorted(volumes):
+            command.extend(["-v", f"{volume}:{volume}"])
+        for name in ("registry", "git"):
+            if (cargo_home() / name).is_dir():
+                command.extend(["-v", f"{cargo_home() / name}:{CONTAINER_CARGO_HOME}/{name}:ro"])
+        for mount in self.config.mounts:
+            command.extend(["-v", mount])
+        for port in ports:
+            command.extend(["-p", f"127.0.0.1:{port}:{port}"])
+        for name, value in (env or {}).items():
+            command.extend(["-e", f"{name}={value}"])
+        command.append(self.config.image)
+        if timeout is not None:
+            command.extend(["timeout", "-s", "KILL", str(math.ceil(timeout))])
+        return command + list(args)
+
+    def _manifest_digest(self, project_dir: Path) -> str:
+        manifest = Path(project_dir) / "Cargo.toml"
+        return hashlib.sha256(manifest.read_bytes()).hexdigest() if manifest.exists() else ""
+
+    def needs_fetch(self, project_dir: Path) -> bool:
+        """Whether the dependencies of a crate changed since they were last fetched."""
+        return self._fetched.get(Path(project_dir)) != self._manifest_digest(project_dir)
+
+    def fetched(self, project_dir: Path) -> None:
+        self._fetched[Path(project_dir)] = self._manifest_digest(project_dir)
@@ -8,7 +8,10 @@ import os
 from datetime import datetime
 import re
 
+from ..config.settings import StepLimits
+from ..utils.limits import preexec
 from ..utils.logging import setup_logger
+from .sandbox import Sandbox
 
 logger = setup_logger()
 
@@ -34,7 +37,9 @@ class ServerTester:
         port: int = 8080,
         startup_timeout: int = 60,
         request_timeout: int = 30,
-        test_script_path: Path = Path("./test.sh")
+        test_script_path: Path = Path("./test.sh"),
+        sandbox: Optional[Sandbox] = None,
+        limits: Optional[StepLimits] = None
     ):
         self.host = host
         self.port = port
@@ -42,6 +47,9 @@ class ServerTester:
         self.startup_timeout = startup_timeout
         self.request_timeout = request_timeout
         self.test_script_path = test_script_path
+        self.sandbox = sandbox  # container the server runs in when set
+        # Wall-clock time of the test script; memory is not limited, `cargo run` compiles too
+        self.limits = limits or StepLimits()
         self.process: Optional[asyncio.subprocess.Process] = None
         self.log_file: Optional[Path] = None
         self.log_errors: List[str] = []
@@ -99,14 +107,22 @@ class ServerTester:
                 **os.environ
             }
 
+            command = ["cargo", "run", "--release"]
+            if self.sandbox is not None:
+                # Published ports only reach servers listening on every interface
+                command = self.sandbox.command(
+                    command, project_dir,
+                    env={"RUST_BACKTRACE": "1", "RUST_LOG": "debug", "HOST": "0.0.0.0"},
+                    ports=[self.port])
+
             # Start server process
             process = await asyncio.create_subprocess_exec(
-                "cargo", "run", "--release",
+                *command,
                 cwd=project_dir,
                 stdout=log_handle,
                 stderr=asyncio.subprocess.STDOUT,
                 env=env,
-                preexec_fn=os.setsid if os.name != 'nt' else None
+                preexec_fn=preexec(new_session=True)
             )
 
             self.process = process
@@ -253,7 +269,19 @@ class ServerTester:
                 stderr=asyncio.subprocess.PIPE
             )
 
-            stdout, stderr = await process.communicate()
+            try:
+                stdout, stderr = await asyncio.wait_for(process.communicate(),
+                                                        timeout=self.limits.timeout)
+            except asyncio.TimeoutError:
+                process.kill()
+                await process.wait()
+                error_msg = (f"Test script did not finish within {self.limits.timeout:g} seconds: "
+                             f"a request never got a response or the server is far too slow")
+                logger.error(error_msg)
+                return False, error_msg, {"timed_out": True, "server_errors": self.log_errors}
+            except asyncio.CancelledError:
+                process.kill()
+                raise
 
             # Check for new server errors
             current_errors = self._check_log_for_errors()
@@ -0,0 +1,146 @@
+"""
+Records the calls of Python functions while a program runs.
+
+CallTracer installs this file as the sitecustomize module of the traced command,
+so it only depends on the standard library. It does nothing unless
+PYTHON2RUST_TRACE_OUTPUT is set; each process writes its calls to
+<PYTHON2RUST_TRACE_OUTPUT>/<pid>.json when it exits.
+"""
+import atexit
+import inspect
+import json
+import os
+import sys
+import threading
+
+MAX_ITEMS = 50
+MAX_DEPTH = 4
+MAX_REPR_CHARS = 500
+
+
+def _plain(value, depth=0):
+    """Whether a value is data a Rust test can rebuild: scalars and containers of them."""
+    if value is None or isinstance(value, (bool, int, float, str, bytes)):
+        return True
+    if depth >= MAX_DEPTH:
+        return False
+    if isinstance(value, (list, tuple, set, frozenset)):
+        return len(value) <= MAX_ITEMS and all(_plain(item, depth + 1) for item in value)
+    if isinstance(value, dict):
+        return len(value) <= MAX_ITEMS and all(
+            _plain(key, depth + 1) and _plain(item, depth + 1) for key, item in value.items())
+    return False
+
+
+def _repr(value):
+    text = repr(value)
+    return text if len(text) <= MAX_REPR_CHARS else None
+
+
+class Recorder:
+    """Calls of the functions defined in files, with plain-data arguments and results."""
+
+    def __init__(self, files, output, limit):
+        self.files = set(files)
+        self.output = output
+        self.limit = limit
+        # file -> function -> calls
+        self.calls = {}
+        self._traced = {}
+        self._seen = set()
+        self._pending = {}
+        self._lock = threading.Lock()
+
+    def _arguments(self, frame):
+        """The arguments of a call as Python source, or None when one is not plain data."""
+        code = frame.f_code
+        values = frame.f_locals
+        names = code.co_varnames
+        index = code.co_argcount + code.co_kwonlyargcount
+        varargs = names[index] if code.co_flags & inspect.CO_VARARGS else None
+        index += 1 if varargs else 0
+        varkeywords = names[index] if code.co_flags & inspect.CO_VARKEYWORDS else None
+
+        positional = names[:code.co_argcount]
+        keyword_only = names[code.co_argcount:code.co_argcount + code.co_kwonlyargcount]
+        arguments = [(None, values.get(name)) for name in positional]
+        arguments.extend((None, item) for item in (values.get(varargs) if varargs else ()))
+        arguments.extend((name, values.get(name)) for name in keyword_only)
+        arguments.extend((values.get(varkeywords) if varkeywords else {}).items())
+        if not all(_plain(value) for _, value in arguments):
+            return None
+        parts = [(name, _repr(value)) for name, value in arguments]
+        if any(text is None for _, text in parts):
+            return None
+        text = ", ".join(f"{name}={text}" if name else text for name, text in parts)
+        return text if len(text) <= MAX_REPR_CHARS else None
+
+    def _file(self, filename):
+        """The traced file a code object comes from, or None."""
+        if filename not in self._traced:
+            path = os.path.realpath(filename)
+            self._traced[filename] = path if path in self.files else None
+        return self._traced[filename]
+
+    def _record(self, frame, call):
+        code = frame.f_code
+        file = self._file(code.co_filename)
+        function = getattr(code, "co_qualname", code.co_name)
+        key = (file, function, call["args"])
+        with self._lock:
+            calls = self.calls.setdefault(file, {}).setdefault(function, [])
+            if key in self._seen or len(calls) >= self.limit:
+                return
+            self._seen.add(key)
+            calls.append({"function": function, **call})
+
+    def _local(self, frame, event, arg):
+        pending = self._pending.get(id(frame))
+        if pending is None:
+            return None
+        if event == "exception":
+            pending["raised"] = arg[1]
+        elif event == "line":
+            # Execution went on: the exception was handled inside the function
+            pending["raised"] = None
+        elif event == "return":
+            del self._pending[id(frame)]
+            raised = pending["raised"]
+            if arg is None and raised is not None:
+                self._record(frame, {"args": pending["args"], "raises": _repr(raised)})
+            elif _plain(arg) and _repr(arg) is not None:
+                self._record(frame, {"args": pending["args"], "result": _repr(arg)})
+        return self._local
+
+    def trace(self, frame, event, arg):
+        code = frame.f_code
+        if event != "call" or code.co_name.startswith("<") or self._file(code.co_filename) is None:
+            return None
+        if code.co_flags & (inspect.CO_GENERATOR | inspect.CO_COROUTINE
+                            | inspect.CO_ASYNC_GENERATOR):
+            # Their returns are suspensions, not results
+            return None
+        args = self._arguments(frame)
+        if args is None:
+            return None
+        self._pending[id(frame)] = {"args": args, "raised": None}
+        return self._local
+
+    def install(self):
+        sys.settrace(self.trace)
+        threading.settrace(self.trace)
+        atexit.register(self.write)
+
+    def write(self):
+        sys.settrace(None)
+        with self._lock:
+            with open(os.path.join(self.output, f"{os.getpid()}.json"), "w") as f:
+                json.dump(self.calls, f)
+
+
+if os.environ.get("PYTHON2RUST_TRACE_OUTPUT"):
+    Recorder(
+        json.loads(os.environ.get("PYTHON2RUST_TRACE_FILES", "[]")),
+        os.environ["PYTHON2RUST_TRACE_OUTPUT"],
+        int(os.environ.get("PYTHON2RUST_TRACE_LIMIT", "20"))
+    ).install()
@@ -0,0 +1,106 @@
+import asyncio
+import json
+import os
+import shlex
+import tempfile
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from ..analyzers.inferred_types import (
+    FunctionTypes, inferred_types, probe_source, revealed_mypy, revealed_pyright
+)
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+TYPE_CHECKERS = ("mypy", "pyright")
+
+
+class TypeInference:
+    """Runs mypy or pyright over a probed copy of each source file, in a temporary directory
+    with the file's own directory on the import path, and keeps what they reveal of the
+    parameters, variables and signatures of its functions.
+
+    mypy is run with --check-untyped-defs so that unannotated functions are inferred too;
+    pyright also infers the return types of functions without annotations.
+    """
+
+    def __init__(self, checker: str = "mypy", command: Optional[str] = None, timeout: float = 300):
+        if checker not in TYPE_CHECKERS:
+            raise ValueError(f"Unknown type checker {checker}, expected mypy or pyright")
+        self.checker = checker
+        self.command = command or checker
+        self.timeout = timeout
+
+    def _arguments(self, directory: Path, probed: Path) -> List[str]:
+        if self.checker == "mypy":
+            return ["--check-untyped-defs", "--follow-imports=silent", "--ignore-missing-imports",
+                    "--no-error-summary", "--hide-error-context", "--no-color-output",
+                    "--cache-dir", str(directory / ".mypy_cache"), str(probed)]
+        return ["--outputjson", "--project", str(directory / "pyrightconfig.json"), str(probed)]
+
+    async def _run(self, source: Path, directory: Path) -> Optional[str]:
+        """The output of the checker on the probed copy, None when it could not run."""
+        probed = directory / source.name
+        env = {**os.environ}
+        if self.checker == "mypy":
+            env["MYPYPATH"] = os.pathsep.join(
+                [str(source.parent.resolve()), *([env["MYPYPATH"]] if env.get("MYPYPATH") else [])])
+        else:
+            (directory / "pyrightconfig.json").write_text(json.dumps({
+                "include": [str(probed)], "extraPaths": [str(source.parent.resolve())],
+                "typeCheckingMode": "basic"}))
+        try:
+            process = await asyncio.create_subprocess_exec(
+                *shlex.split(self.command), *self._arguments(directory, probed),
+                cwd=directory,
+                env=env,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE
+            )
+        except OSError as e:
+            logger.warning(f"{self.checker} could not run: {e}")
+            return None
+        try:
+            stdout, stderr = await asyncio.wait_for(process.communicate(), timeout=self.timeout)
+        except asyncio.TimeoutError:
+            process.kill()
+            await process.wait()
+            logger.warning(f"{self.checker} timed out after {self.timeout:.0f} seconds on {source}")
+            return None
+        # Both exit with 1 when they report errors, which the revealed types come with
+        if process.returncode not in (0, 1):
+            error = stderr.decode(errors="replace").strip()[-500:]
+            logger.warning(f"{self.checker} failed on {source}: {error}")
+            return None
+        return stdout.decode(errors="replace")
+
+    async def infer_file(self, source: Path) -> Dict[str, FunctionTypes]:
+        """Function qualname -> its inferred types, empty when the file cannot be checked."""
+        try:
+            probed_code, probes = probe_source(source.read_text())
+        except (OSError, SyntaxError, ValueError) as e:
+            logger.warning(f"Not inferring the types of {source}: {e}")
+            return {}
+        if not probes:
+            return {}
+        with tempfile.TemporaryDirectory(prefix="python2rust_types_") as temp:
+            directory = Path(temp)
+            (directory / source.name).write_text(probed_code)
+            output = await self._run(source, directory)
+        if output is None:
+            return {}
+        revealed = revealed_mypy(output) if self.checker == "mypy" else revealed_pyright(output)
+        return inferred_types(probes, revealed)
+
+    async def infer(self, files: List[Path]) -> Dict[Path, Dict[str, FunctionTypes]]:
+        """The inferred types of each file with any."""
+        logger.info(f"Inferring the types of {len(files)} files with {self.checker}")
+        types = {}
+        for source in files:
+            inferred = await self.infer_file(source)
+            if inferred:
+                types[source] = inferred
+        functions = sum(len(inferred) for inferred in types.values())
+        logger.info(f"Inferred the types of {functions} functions in {len(types)} files")
+        return types
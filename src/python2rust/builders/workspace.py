@@ -0,0 +1,295 @@
+'''
+Cargo workspace of a project with several Python packages: a library crate per package, whose
+module tree mirrors the package's with the verified crates of its files as modules, re-exporting
+what its __init__ modules export, with path dependencies between the crates of packages importing
+each other and the metadata and dependencies they share in the workspace.
+'''
+import os
+import re
+from collections import Counter
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from ..analyzers.exceptions import ERRORS_CRATE
+from ..analyzers.import_graph import ImportGraph
+from ..analyzers.naming import escape_keyword
+from ..analyzers.visibility import module_visibility
+from ..utils.cargo_manifest import section_entries
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+WORKSPACE_DIR = "workspace"
+EDITION = re.compile(r'^\s*edition\s*=\s*"(\d+)"', re.MULTILINE)
+# A top-level item a translation declares pub, which a module can re-export
+PUB_ITEM = r"(?m)^pub\s+(?:async\s+|unsafe\s+)*(?:fn|struct|enum|trait|type|const|static)\s+{}\b"
+# Names a module or crate cannot take, even raw, or would shadow the crates of every path
+RESERVED_PATHS = {"crate", "self", "super", "std", "core", "alloc"}
+
+
+@dataclass
+class PackageCrate:
+    """The crate of a Python package, gathering the translations of its modules."""
+    name: str
+    package: str  # dotted name of the package
+    modules: List[str] = field(default_factory=list)  # dotted names
+    sources: List[Path] = field(default_factory=list)
+    dependencies: List[str] = field(default_factory=list)  # crates of the packages it imports
+
+    def module_path(self, module: str) -> Tuple[str, ...]:
+        """The path in the crate of the Rust module of one of its Python modules, mirroring the
+        package: pkg.sub.io is sub::io, and the __init__ of pkg.sub is sub itself."""
+        parts = module[len(self.package) + 1:].split(".") if module != self.package else []
+        return tuple(_module_name(part) for part in parts if part != "__init__")
+
+    def describe(self) -> str:
+        count = f"{len(self.modules)} module{'' if len(self.modules) == 1 else 's'}"
+        depends = f", depends on {', '.join(self.dependencies)}" if self.dependencies else ""
+        return f"{self.package} -> {WORKSPACE_DIR}/crates/{self.name} ({count}{depends})"
+
+
+def _module_name(name: str) -> str:
+    return f"{name}_module" if name in RESERVED_PATHS else escape_keyword(name)
+
+
+def _crate_name(name: str) -> str:
+    name = name.replace("-", "_")
+    # The errors crate is a member of the workspace too
+    return f"{name}_crate" if name in RESERVED_PATHS or name == ERRORS_CRATE else name
+
+
+def _package_of(graph: ImportGraph, source: Path) -> Optional[str]:
+    """The top-most package of a module: the first directory with an __init__.py, the top-level
+    directory of namespace packages, None for the modules outside packages."""
+    parts = graph.names[source].split(".")
+    for index in range(1, len(parts)):
+        if (graph.roots[source].joinpath(*parts[:index]) / "__init__.py").exists():
+            return ".".join(parts[:index])
+    return parts[0] if len(parts) > 1 else None
+
+
+def package_crates(graph: ImportGraph) -> Tuple[List[PackageCrate], List[str]]:
+    """The crates of a project with several packages, their dependencies first, and warnings
+    about the imports a crate cannot depend on, crates not depending on each other in a cycle.
+    No crates when the modules are in fewer than two packages."""
+    crates: Dict[str, PackageCrate] = {}
+    for source in graph.topological_order():
+        package = _package_of(graph, source)
+        if package is None:
+            continue
+        if package not in crates:
+            crates[package] = PackageCrate(_crate_name(package.split(".")[-1]), package)
+        crates[package].modules.append(graph.names[source])
+        crates[package].sources.append(source)
+    if len(crates) < 2:
+        return [], []
+    names = Counter(crate.name for crate in crates.values())
+    for crate in crates.values():
+        if names[crate.name] > 1:
+            crate.name = _crate_name(crate.package.replace(".", "_"))
+
+    imports: Dict[str, List[str]] = {package: [] for package in crates}
+    for package, crate in crates.items():
+        for source in crate.sources:
+            for dependency in sorted(graph.edges[source], key=lambda path: graph.names[path]):
+                imported = _package_of(graph, dependency)
+                if imported in crates and imported != package and imported not in imports[package]:
+                    imports[package].append(imported)
+
+    # Depth first, the dependencies of a package before it; an import of a package still being
+    # visited closes a cycle and is left out
+    ordered: List[str] = []
+    visiting: List[str] = []
+    warnings = []
+
+    def visit(package: str) -> None:
+        visiting.append(package)
+        for imported in imports[package]:
+            if imported in visiting:
+                warnings.append(f"Packages {imported} and {package} import each other: the crate "
+                                f"of {package} cannot depend on {crates[imported].name} as well, "
+                                "crates not depending on each other in a cycle")
+                continue
+            if imported not in ordered:
+                visit(imported)
+            crates[package].dependencies.append(crates[imported].name)
+        visiting.pop()
+        ordered.append(package)
+
+    for package in crates:
+        if package not in ordered:
+            visit(package)
+    return [crates[package] for package in ordered], warnings
+
+
+def _dependency(line: str) -> str:
+    return line.split("=", 1)[1].strip()
+
+
+def _translation(crate: PackageCrate, module: str, output_dirs: Dict[Path, Path]) -> Optional[Path]:
+    main = output_dirs[crate.sources[crate.modules.index(module)]] / "src" / "main.rs"
+    if not main.exists():
+        logger.warning(f"{module} has no translation to include in the crate {crate.name}")
+        return None
+    return main
+
+
+def _include(main: Path, directory: Path, name: str, public: bool = True) -> str:
+    path = Path(os.path.relpath(main, directory)).as_posix()
+    return f'#[allow(dead_code)]\n#[path = "{path}"]\n{"pub " if public else ""}mod {name};'
+
+
+def _reexports(crate: PackageCrate, module: str, output_dirs: Dict[Path, Path]) -> List[str]:
+    """The `pub use` of the names a module exports from other modules of the package, as
+    Python's __all__ or a package's imports tell, for those their translation declares pub."""
+    sources = dict(zip(crate.modules, crate.sources))
+    is_package = module.endswith(".__init__")
+    try:
+        visibility = module_visibility(sources[module].read_text(),
+                                       module[:-len(".__init__")] if is_package
+                                       else module, is_package)
+    except OSError:
+        return []
+    lines = []
+    for reexport in visibility.reexports if visibility else []:
+        target = next((candidate for candidate in (reexport.module, f"{reexport.module}.__init__")
+                       if candidate in sources), None)
+        if target is None or target == module:
+            continue
+        path = "::".join(("crate", *crate.module_path(target)))
+        if reexport.name == "*":
+            if crate.module_path(target):
+                lines.append(f"pub use {path}::*;")
+            continue
+        try:
+            defined = module_visibility(sources[target].read_text(), target)
+        except OSError:
+            continue
+        rust = defined.rust_name(reexport.name) if defined else reexport.name
+        main = output_dirs[sources[target]] / "src" / "main.rs"
+        if not main.exists() or not re.search(PUB_ITEM.format(re.escape(rust)), main.read_text()):
+            continue
+        alias = reexport.alias
+        if alias and defined and defined.items.get(reexport.name) == "constant":
+            alias = alias.upper()
+        lines.append(f"pub use {path}::{rust}{f' as {alias}' if alias and alias != rust else ''};")
+    return list(dict.fromkeys(lines))
+
+
+def _write_modules(crate: PackageCrate, crate_dir: Path, output_dirs: Dict[Path, Path],
+                   reexports: bool = True) -> None:
+    """Write the module tree of a crate: lib.rs for the package, and a file for each subpackage
+    declaring its modules, which include the translations of the Python modules."""
+    tree: Dict[Tuple[str, ...], Optional[str]] = {(): None}
+    for module in crate.modules:
+        path = crate.module_path(module)
+        for end in range(len(path)):
+            tree.setdefault(path[:end], None)
+        tree[path] = module
+    for path, module in tree.items():
+        children = sorted(child for child in tree
+                          if len(child) == len(path) + 1 and child[:len(path)] == path)
+        if path and not children:
+            continue  # included by its parent
+        # #[path] is relative to the directory of the file declaring the module
+        directory = crate_dir.joinpath("src", *(part.replace("r#", "") for part in path[:-1]))
+        items = []
+        main = _translation(crate, module, output_dirs) if module else None
+        if main is not None:
+            # The definitions of the __init__ module, re-exported as those of the package's module
+            glob = "#[allow(unused_imports)]\npub use init::*;"
+            items.append(f"{_include(main, directory, 'init', public=False)}\n{glob}")
+        for child in children:
+            if any(len(other) > len(child) and other[:len(child)] == child for other in tree):
+                items.append(f"pub mod {child[-1]};")
+                continue
+            main = _translation(crate, tree[child], output_dirs)
+            if main is not None:
+                items.append(_include(main, directory, child[-1]))
+        if module and reexports:
+            items.extend(_reexports(crate, module, output_dirs))
+        content = "\n\n".join(items) + "\n"
+        if not path:
+            content = (f"//! The modules of the Python package {crate.package}, generated by "
+                       "python2rust, in its\n"
+                       "//! layout: each is the translation of one module, verified "
+                       "in its own crate.\n\n" + content)
+        directory.mkdir(parents=True, exist_ok=True)
+        (directory / (f"{path[-1].replace('r#', '')}.rs" if path else "lib.rs")).write_text(content)
+
+
+def _write_errors(errors: Path, crate_dir: Path) -> Dict[str, str]:
+    """Copy the errors crate into the workspace, returning its dependencies."""
+    (crate_dir / "src").mkdir(parents=True, exist_ok=True)
+    (crate_dir / "src" / "lib.rs").write_text((errors / "src" / "lib.rs").read_text())
+    return {name: _dependency(line) for name, line
+            in section_entries((errors / "Cargo.toml").read_text(), "[dependencies]").items()}
+
+
+def write_workspace(target_dir: Path, crates: List[PackageCrate], output_dirs: Dict[Path, Path],
+                    reexports: bool = True, errors: Optional[Path] = None) -> Path:
+    """Write <target_dir>/workspace: its Cargo.toml and the crate of each package, whose module tree
+    includes the src/main.rs of each module's crate where the package has the module and, with
+    reexports, re-exports what the modules export. Dependencies declared the same way by several
+    crates go into [workspace.dependencies]. With the directory of the project's errors crate, it
+    is a member too, the crates of the modules using it depending on it. Returns the workspace
+    directory."""
+    directory = target_dir / WORKSPACE_DIR
+    manifests: Dict[str, Dict[str, str]] = {}
+    editions: Counter = Counter()
+    for crate in crates:
+        declared: Dict[str, str] = {}
+        for source in crate.sources:
+            manifest = output_dirs[source] / "Cargo.toml"
+            if not manifest.exists():
+                continue
+            content = manifest.read_text()
+            editions.update(EDITION.findall(content))
+            for name, line in section_entries(content, "[dependencies]").items():
+                declared.setdefault(name, _dependency(line))
+        manifests[crate.name] = declared
+    if errors is not None:
+        manifests[ERRORS_CRATE] = _write_errors(errors, directory / "crates" / ERRORS_CRATE)
+    uses = Counter((name, value) for declared in manifests.values()
+                   for name, value in declared.items()
+                   if name != ERRORS_CRATE)
+    shared = {name: value for (name, value), count in sorted(uses.items()) if count > 1}
+
+    paths = {crate.name: crate.dependencies for crate in crates}
+    if errors is not None:
+        # The errors crate the crates of the modules declare is the workspace's own
+        paths = {ERRORS_CRATE: [],
+                 **{name: [ERRORS_CRATE, *dependencies] if ERRORS_CRATE in manifests[name]
+                                      else dependencies for name, dependencies in paths.items()}}
+    for name, dependencies in paths.items():
+        crate_dir = directory / "crates" / name
+        (crate_dir / "src").mkdir(parents=True, exist_ok=True)
+        lines = [f'[package]\nname = "{name}"\nversion.workspace = true\n'
+                 'edition.workspace = true\n',
+                 "[dependencies]"]
+        lines.extend(f'{dependency} = {{ path = "../{dependency}" }}'
+                     for dependency in dependencies)
+        for dependency, value in manifests[name].items():
+            if dependency not in dependencies:
+                lines.append(f"{dependency}.workspace = true" if shared.get(dependency) == value
+                             else f"{dependency} = {value}")
+        (crate_dir / "Cargo.toml").write_text("\n".join(lines) + "\n")
+
+    for crate in crates:
+        _write_modules(crate, directory / "crates" / crate.name, output_dirs, reexports)
+
+    members = ", ".join(f'"crates/{name}"' for name in paths)
+    workspace = [
+        "# Workspace of the Python packages of the project, generated by python2rust",
+        f'[workspace]\nresolver = "2"\nmembers = [{members}]\n',
+        "[workspace.package]",
+        'version = "0.1.0"',
+        f'edition = "{editions.most_common(1)[0][0] if editions else "2021"}"',
+    ]
+    if shared:
+        workspace.extend(["", "[workspace.dependencies]"])
+        workspace.extend(f"{name} = {value}" for name, value in shared.items())
+    (directory / "Cargo.toml").write_text("\n".join(workspace) + "\n")
+    logger.info(f"Generated the Cargo workspace of {len(crates)} packages in {directory}")
+    return directory
@@ -3,10 +3,12 @@ from .analysis_chain import AnalysisChain
 from .generation_chain import GenerationChain
 from .verification_chain import VerificationChain
 from .fix_chain import FixChain
+from .pass_chain import PassChain
 
 __all__ = [
     'AnalysisChain',
     'GenerationChain', 
     'VerificationChain',
-    'FixChain'
+    'FixChain',
+    'PassChain'
 ]
\ No newline at end of file
@@ -2,8 +2,8 @@ import json
 from typing import Any, Dict, Optional, List
 from langchain_core.language_models import BaseLanguageModel
 from langchain.chains import LLMChain
-from ..prompts.analysis_prompts import SYSTEM_MESSAGE, ANALYSIS_PROMPT
-from langchain.prompts import ChatPromptTemplate
+from ..prompts.loader import default_prompts
+from langchain.prompts import ChatPromptTemplate, PromptTemplate
 from langchain.callbacks.base import BaseCallbackHandler
 from ..utils.logging import setup_logger
 
@@ -12,11 +12,17 @@ logger = setup_logger()
 class AnalysisChain:
     """Chain for analyzing Python code."""
     
-    def __init__(self, llm: BaseLanguageModel, callbacks: Optional[List[BaseCallbackHandler]] = None):
+    def __init__(
+        self,
+        llm: BaseLanguageModel,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        prompts: Optional[Dict[str, PromptTemplate]] = None
+    ):
+        prompts = prompts or default_prompts()
         # Create chat prompt template with system and human messages
         chat_prompt = ChatPromptTemplate.from_messages([
-            ("system", SYSTEM_MESSAGE),
-            ("human", ANALYSIS_PROMPT.template)
+            ("system", prompts["analysis_system"].template),
+            ("human", prompts["analysis"].template)
         ])
         
         self.chain = LLMChain(
@@ -27,11 +33,14 @@ class AnalysisChain:
             verbose=True 
         )
     
-    async def analyze(self, python_code: str) -> Dict[str, Any]:
+    async def analyze(self, python_code: str, project_context: str = "") -> Dict[str, Any]:
         """Run analysis on Python code."""
         try:
             # Get analysis from LLM
-            result = await self.chain.ainvoke({"python_code": python_code}, include_run_info=True)
+            result = await self.chain.ainvoke({
+                "python_code": python_code,
+                "project_context": project_context or "None"
+            }, include_run_info=True)
             
             # Parse analysis
             analysis = result["analysis"]
@@ -3,13 +3,15 @@ from langchain_core.language_models import BaseLanguageModel
 from langchain.chains import LLMChain
 from pathlib import Path
 from langchain.callbacks.base import BaseCallbackHandler
-from langchain.prompts import ChatPromptTemplate
+from langchain.prompts import ChatPromptTemplate, PromptTemplate
 import json
 import re
 
-from ..prompts.fix_prompts import FIX_PROMPT, SYSTEM_MESSAGE
+from ..prompts.loader import default_prompts
 from ..utils.logging import setup_logger
 from ..utils.code_extractor import CodeExtractor
+from ..utils.error_formatter import clippy_diagnostics
+from .structured_chain import StructuredCodeChain
 
 logger = setup_logger()
 
@@ -22,11 +24,15 @@ class FixChain:
         self, 
         llm: BaseLanguageModel,
         specs_file: Path, 
-        callbacks: Optional[List[BaseCallbackHandler]] = None
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        prompts: Optional[Dict[str, PromptTemplate]] = None,
+        structured: bool = False
     ):
+        """With structured, models supporting tool calling answer with GeneratedCode files."""
+        prompts = prompts or default_prompts()
         chat_prompt = ChatPromptTemplate.from_messages([
-            ("system", SYSTEM_MESSAGE),
-            ("human", FIX_PROMPT.template)
+            ("system", prompts["fix_system"].template),
+            ("human", prompts["fix"].template)
         ])
         
         self.chain = LLMChain(
@@ -36,6 +42,8 @@ class FixChain:
             callbacks=callbacks,
             verbose=True 
         )
+        self.structured = (StructuredCodeChain.create(llm, chat_prompt, callbacks)
+                           if structured else None)
         self.code_extractor = CodeExtractor()
         self.specs_file = specs_file
         self.migration_specs = self._load_migration_specs()
@@ -101,27 +109,17 @@ class FixChain:
         return "\n".join(sections) if sections else "No issues found"
 
     def _format_clippy_section(self, error: str) -> List[str]:
-        """Format Clippy errors in a clear, action-oriented way."""
-        sections = ["Clippy Fix Required:"]
-        
-        # Parse the error message
-        for line in error.split('\n'):
-            line = line.strip()
-            if line.startswith('error:'):
-                sections.append(f"ISSUE: {line[6:].strip()}")
-            elif '-->' in line:  # Location line
-                sections.append(f"LOCATION: {line.split('-->')[1].strip()}")
-            elif '|' in line and 'for k in' in line:  # Actual code line
-                code = line.split('|')[1].strip()
-                sections.append(f"CURRENT CODE: {code}")
-            elif 'for (k, <item>)' in line:  # The suggested fix
-                fix = line.strip()
-                sections.append(f"REPLACE WITH: {fix}")
-                break  # Stop after finding the fix
-        
+        """Clippy diagnostics verbatim, with their locations and suggestions."""
+        diagnostics = clippy_diagnostics(error)
+        if not diagnostics:
+            return ["Clippy Fix Required:", error]
+        sections = ["Clippy Fix Required: fix every lint below, the code is only accepted "
+                    "once clippy is clean."]
+        for diagnostic in diagnostics:
+            sections.append(diagnostic)
+            sections.append("---")
         return sections
 
-
     def _format_compilation_section(self, error: str) -> List[str]:
         """Format compilation errors into readable sections."""
         sections = ["Compilation Errors to Fix:"]
@@ -228,15 +226,20 @@ class FixChain:
             issues_summary = formatted_result["issues_summary"]
             logger.info(f"Issues summary: {issues_summary}")
             
-            response = await self.chain.ainvoke({
+            inputs = {
                 "rust_code": rust_code,
                 "toml_content": toml_content,
                 "verification_result": issues_summary,
                 "analysis": analysis,
                 "migration_specs": self.migration_specs,
-            }, include_run_info=True)
-            #logger.info(f"Fixed code: {response['fixed_code']}")
-            fixed_rust, fixed_toml = self.code_extractor.extract_code_blocks(response["fixed_code"])
+            }
+            if self.structured is not None:
+                fixed_rust, fixed_toml = await self.structured.ainvoke(inputs)
+            else:
+                response = await self.chain.ainvoke(inputs, include_run_info=True)
+                #logger.info(f"Fixed code: {response['fixed_code']}")
+                fixed_rust, fixed_toml = self.code_extractor.extract_code_blocks(response[
+                    "fixed_code"])
             
             if not self._validate_output(fixed_rust, fixed_toml, rust_code, toml_content):
                 raise ValueError(f"Model returned invalid or unchanged code")
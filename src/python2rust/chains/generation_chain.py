@@ -1,24 +1,33 @@
 from typing import Dict, Any, Optional, List
 from langchain_core.language_models import BaseLanguageModel
-from langchain.prompts import ChatPromptTemplate
+from langchain.prompts import ChatPromptTemplate, PromptTemplate
 from langchain.chains import LLMChain
 from langchain.callbacks.base import BaseCallbackHandler
 
 
-from ..prompts.generation_prompts import SYSTEM_MESSAGE, GENERATION_PROMPT
+from ..prompts.loader import default_prompts
 from ..utils.logging import setup_logger
 from ..utils.code_extractor import CodeExtractor
+from .structured_chain import StructuredCodeChain
 
 logger = setup_logger()
 
 class GenerationChain:
     """Chain for initial Python to Rust code conversion."""
     
-    def __init__(self, llm: BaseLanguageModel, callbacks: Optional[List[BaseCallbackHandler]] = None):
+    def __init__(
+        self,
+        llm: BaseLanguageModel,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        prompts: Optional[Dict[str, PromptTemplate]] = None,
+        structured: bool = False
+    ):
+        """With structured, models supporting tool calling answer with GeneratedCode files."""
+        prompts = prompts or default_prompts()
          # Create chat prompt template with system and human messages
         chat_prompt = ChatPromptTemplate.from_messages([
-            ("system", SYSTEM_MESSAGE),
-            ("human", GENERATION_PROMPT.template)
+            ("system", prompts["generation_system"].template),
+            ("human", prompts["generation"].template)
         ])
         
         self.chain = LLMChain(
@@ -28,27 +37,39 @@ class GenerationChain:
             callbacks=callbacks,
             verbose=True 
         )
+        self.structured = (StructuredCodeChain.create(llm, chat_prompt, callbacks)
+                           if structured else None)
         self.code_extractor = CodeExtractor()
     
-    async def generate(self, python_code: str, analysis: Dict[str, Any]) -> Dict[str, str]:
+    async def generate(
+        self,
+        python_code: str,
+        analysis: Dict[str, Any],
+        project_context: str = ""
+    ) -> Dict[str, str]:
         """Generate initial Rust code from Python code.
         
         Args:
             python_code: Source Python code to convert
             analysis: Analysis results from the analysis chain
+            project_context: Extra instructions and context for the model
             
         Returns:
             Dict containing generated rust_code and toml_content
         """
         try:
-            response = await self.chain.ainvoke({
+            inputs = {
                 "python_code": python_code,
-                "analysis": analysis
-            }, include_run_info=True)
-            result = response["generated_code"]
+                "analysis": analysis,
+                "project_context": project_context or "None"
+            }
+            if self.structured is not None:
+                rust_code, toml_content = await self.structured.ainvoke(inputs)
+            else:
+                response = await self.chain.ainvoke(inputs, include_run_info=True)
+                result = response["generated_code"]
+                rust_code, toml_content = self.code_extractor.extract_code_blocks(result)
             logger.info("Generated initial Rust code")
-
-            rust_code, toml_content = self.code_extractor.extract_code_blocks(result)
             
             logger.info("Initial code generation completed")
             return {
@@ -0,0 +1,131 @@
+import re
+from typing import Any, Dict, List, Optional, Tuple
+from langchain_core.language_models import BaseLanguageModel
+from langchain.prompts import ChatPromptTemplate, PromptTemplate
+from langchain.chains import LLMChain
+from langchain.callbacks.base import BaseCallbackHandler
+
+from ..config.settings import TRANSLATION_PASSES
+from ..prompts.loader import default_prompts
+from ..utils.logging import setup_logger
+from ..utils.code_extractor import CodeExtractor
+from .structured_chain import StructuredCodeChain
+
+logger = setup_logger()
+
+# Passes answering with a crate rather than a specification or a test module
+STRUCTURED_PASSES = ("skeleton", "implementation")
+
+
+class PassChain:
+    """Chains for the staged translation: spec, skeleton, implementation and tests."""
+
+    def __init__(
+        self,
+        llm: BaseLanguageModel,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        tests_llm: Optional[BaseLanguageModel] = None,
+        prompts: Optional[Dict[str, PromptTemplate]] = None,
+        structured: bool = False,
+        tests_prompts: Optional[Dict[str, PromptTemplate]] = None
+    ):
+        """tests_llm writes the tests pass, llm the other passes and by default the tests too;
+        tests_prompts likewise replace prompts for the tests pass.
+
+        With structured, the skeleton and implementation passes of models supporting
+        tool calling answer with GeneratedCode files.
+        """
+        prompts = prompts or default_prompts()
+        tests_prompts = tests_prompts or prompts
+        templates = {
+            name: ChatPromptTemplate.from_messages([
+                ("system",
+                 (tests_prompts if name == "tests" else prompts)["generation_system"].template),
+                ("human", (tests_prompts if name == "tests" else prompts)[name].template)
+            ])
+            for name in TRANSLATION_PASSES
+        }
+        self.chains = {
+            name: LLMChain(
+                llm=tests_llm if name == "tests" and tests_llm is not None else llm,
+                prompt=templates[name],
+                output_key="output",
+                callbacks=callbacks,
+                verbose=True
+            )
+            for name in TRANSLATION_PASSES
+        }
+        self.structured_chains: Dict[str, StructuredCodeChain] = {}
+        for name in STRUCTURED_PASSES if structured else ():
+            chain = StructuredCodeChain.create(llm, templates[name], callbacks)
+            if chain is not None:
+                self.structured_chains[name] = chain
+        self.code_extractor = CodeExtractor()
+
+    async def _invoke(self, name: str, inputs: Dict[str, Any]) -> str:
+        response = await self.chains[name].ainvoke(inputs, include_run_info=True)
+        return response["output"]
+
+    async def _invoke_code(self, name: str, inputs: Dict[str, Any]) -> Tuple[str, Optional[str]]:
+        if name in self.structured_chains:
+            return await self.structured_chains[name].ainvoke(inputs)
+        return self.code_extractor.extract_code_blocks(await self._invoke(name, inputs))
+
+    async def spec(self, python_code: str, analysis: Dict[str, Any],
+                   project_context: str = "") -> str:
+        """Extract a behavioral specification of the Python code."""
+        result = await self._invoke("spec", {
+            "python_code": python_code,
+            "analysis": analysis,
+            "project_context": project_context or "None"
+        })
+        match = re.search(r"```markdown\s+(.*?)```", result, re.DOTALL)
+        spec = (match.group(1) if match else result).strip()
+        if not spec:
+            raise ValueError("Empty specification")
+        return spec
+
+    async def skeleton(self, python_code: str, spec: str,
+                       project_context: str = "") -> Dict[str, str]:
+        """Generate Rust types and signatures with todo!() bodies."""
+        rust_code, toml_content = await self._invoke_code("skeleton", {
+            "python_code": python_code,
+            "spec": spec or "None",
+            "project_context": project_context or "None"
+        })
+        return {"rust_code": rust_code, "toml_content": toml_content}
+
+    async def implementation(
+        self,
+        python_code: str,
+        spec: str,
+        skeleton: Dict[str, str],
+        project_context: str = ""
+    ) -> Dict[str, str]:
+        """Fill in the skeleton, or translate directly when there is none."""
+        rust_code, toml_content = await self._invoke_code("implementation", {
+            "python_code": python_code,
+            "spec": spec or "None",
+            "skeleton": skeleton.get("rust_code")
+            or "// No skeleton: design the program from the specification",
+            "toml_content": skeleton.get("toml_content") or "",
+            "project_context": project_context or "None"
+        })
+        if "todo!()" in rust_code:
+            raise ValueError("Implementation still contains todo!() placeholders")
+        return {"rust_code": rust_code,
+                "toml_content": toml_content or skeleton.get("toml_content")}
+
+    async def tests(self, python_code: str, spec: str, rust_code: str,
+                    python_tests: str = "") -> str:
+        """Generate a Rust test module for the implementation, translating the project's tests."""
+        result = await self._invoke("tests", {
+            "python_code": python_code,
+            "spec": spec or "None",
+            "rust_code": rust_code,
+            "python_tests": python_tests or "None"
+        })
+        tests, _ = self.code_extractor.extract_code_blocks(result)
+        if "#[test]" not in tests:
+            raise ValueError("No #[test] functions in generated tests")
+        return tests
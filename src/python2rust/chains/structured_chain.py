@@ -0,0 +1,48 @@
+from typing import Any, Dict, List, Optional, Tuple
+from langchain_core.language_models import BaseLanguageModel
+from langchain.prompts import ChatPromptTemplate
+from langchain.callbacks.base import BaseCallbackHandler
+
+from ..utils.logging import setup_logger
+from ..utils.code_extractor import CodeExtractor, GeneratedCode
+
+logger = setup_logger()
+
+
+class StructuredCodeChain:
+    """A prompt answered through the model's tool calling, as GeneratedCode files.
+
+    The code arrives as JSON matching the schema rather than fenced blocks in
+    free text, so there is no prose around it to parse.
+    """
+
+    def __init__(
+        self,
+        llm: BaseLanguageModel,
+        prompt: ChatPromptTemplate,
+        callbacks: Optional[List[BaseCallbackHandler]] = None
+    ):
+        self.runnable = prompt | llm.with_structured_output(GeneratedCode)
+        self.callbacks = callbacks
+        self.code_extractor = CodeExtractor()
+
+    @classmethod
+    def create(
+        cls,
+        llm: BaseLanguageModel,
+        prompt: ChatPromptTemplate,
+        callbacks: Optional[List[BaseCallbackHandler]] = None
+    ) -> Optional["StructuredCodeChain"]:
+        """The chain, or None when the model has no structured output; fenced
+        blocks are parsed then."""
+        try:
+            return cls(llm, prompt, callbacks)
+        except (NotImplementedError, AttributeError) as e:
+            logger.warning(f"{type(llm).__name__} has no structured output, parsing "
+                           f"code blocks instead: {e}")
+            return None
+
+    async def ainvoke(self, inputs: Dict[str, Any]) -> Tuple[str, Optional[str]]:
+        """Rust code and Cargo.toml answered for the prompt inputs."""
+        generated = await self.runnable.ainvoke(inputs, config={"callbacks": self.callbacks})
+        return self.code_extractor.extract_files(generated)
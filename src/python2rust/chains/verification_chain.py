@@ -2,7 +2,8 @@ from typing import Dict, Any, Optional, List
 from pathlib import Path
 from langchain_core.language_models import BaseLanguageModel
 from langchain.chains import LLMChain
-from ..prompts.verification_prompts import VERIFICATION_PROMPT
+from langchain.prompts import ChatPromptTemplate, PromptTemplate
+from ..prompts.loader import default_prompts
 from ..utils.logging import setup_logger
 import json
 from langchain.callbacks.base import BaseCallbackHandler
@@ -13,10 +14,26 @@ logger = setup_logger()
 class VerificationChain:
     """Chain for verifying Rust implementation against Python original."""
     
-    def __init__(self, llm: BaseLanguageModel, specs_file: Path, callbacks: Optional[List[BaseCallbackHandler]] = None):
+    def __init__(
+        self,
+        llm: BaseLanguageModel,
+        specs_file: Path,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        prompts: Optional[Dict[str, PromptTemplate]] = None,
+        system_prompt: Optional[str] = None
+    ):
+        """system_prompt, a template without variables, is sent as a system message
+        before the prompt."""
+        prompts = prompts or default_prompts()
+        prompt = prompts["verification"]
+        if system_prompt:
+            prompt = ChatPromptTemplate.from_messages([
+                ("system", system_prompt),
+                ("human", prompt.template)
+            ])
         self.chain = LLMChain(
             llm=llm,
-            prompt=VERIFICATION_PROMPT,
+            prompt=prompt,
             output_key="verification",
             callbacks=callbacks,
             verbose=True 
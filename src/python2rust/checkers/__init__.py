@@ -0,0 +1,21 @@
+# checkers/__init__.py
+from .audit import DependencyAuditCheck
+from .base import Checker, CheckResult
+from .boundary import BoundaryCheck
+from .cargo import CargoCheck, CargoTest, Clippy, Miri
+from .cli import CliEquivalence
+from .comments import CommentCheck
+from .fuzz import DifferentialFuzzer
+from .http import HttpEquivalence
+from .numeric import NumericBoundaryCheck
+from .openapi import OpenApiEquivalence
+from .policy import PolicyCheck
+from .rustfmt import RustfmtCheck
+from .schema import SchemaEquivalence
+from .shell import ShellChecker
+from .strings import UnicodeCheck
+
+__all__ = ['BoundaryCheck', 'Checker', 'CheckResult', 'CargoCheck', 'CargoTest', 'Clippy',
+           'CliEquivalence', 'CommentCheck', 'DependencyAuditCheck', 'DifferentialFuzzer',
+           'HttpEquivalence', 'Miri', 'NumericBoundaryCheck', 'OpenApiEquivalence', 'PolicyCheck',
+           'RustfmtCheck', 'SchemaEquivalence', 'ShellChecker', 'UnicodeCheck']
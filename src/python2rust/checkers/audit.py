@@ -0,0 +1,173 @@
+import asyncio
+import json
+import sys
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from ..builders import RustBuilder
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+if sys.version_info >= (3, 11):
+    import tomllib
+else:
+    import tomli as tomllib
+
+logger = setup_logger()
+
+# Where the generated cargo-deny configuration is written in the crate, out of its sources
+CONFIG_PATH = Path("target") / "python2rust-deny.toml"
+INSTALL_HINT = "cargo install --locked cargo-deny"
+
+
+@dataclass
+class DependencyViolation:
+    """A dependency failing the audit: an advisory against it, a yanked version or a license."""
+    crate: str
+    version: str
+    code: str  # cargo-deny's diagnostic code, e.g. vulnerability, unmaintained, yanked, rejected
+    message: str
+    advisory: Optional[str] = None
+
+    def __str__(self) -> str:
+        advisory = f" {self.advisory}" if self.advisory else ""
+        return f"{self.crate} {self.version}: {self.code}{advisory}: {self.message}"
+
+
+def deny_config(licenses: Iterable[str], ignore: Iterable[str]) -> str:
+    """cargo-deny configuration allowing these licenses and ignoring these advisories."""
+    return "\n".join([
+        "[advisories]",
+        "version = 2",
+        'yanked = "deny"',
+        f"ignore = {json.dumps(list(ignore))}",
+        "",
+        "[licenses]",
+        "version = 2",
+        f"allow = {json.dumps(list(licenses))}",
+        "confidence-threshold = 0.8",
+        ""
+    ])
+
+
+def dependency_violations(output: str, package: Optional[str] = None) -> List[DependencyViolation]:
+    """Errors of `cargo deny --format json check` output, one per crate, except the package itself.
+
+    Warnings, such as allowed licenses no crate uses, are not violations.
+    """
+    violations = []
+    for line in (output or "").splitlines():
+        try:
+            entry = json.loads(line)
+        except ValueError:
+            continue
+        if not isinstance(entry, dict) or entry.get("type") != "diagnostic":
+            continue
+        fields = entry.get("fields", {})
+        if fields.get("severity") != "error":
+            continue
+        advisory = (fields.get("advisory") or {})
+        message = advisory.get("title") or fields.get("message", "")
+        for graph in fields.get("graphs") or [{}]:
+            krate = graph.get("Krate") or {}
+            name = krate.get("name") or advisory.get("package") or "?"
+            if name == package:
+                continue
+            violations.append(DependencyViolation(
+                crate=name,
+                version=krate.get("version", ""),
+                code=fields.get("code", "error"),
+                message=message,
+                advisory=advisory.get("id")
+            ))
+    return violations
+
+
+def _package_name(toml_content: str) -> Optional[str]:
+    try:
+        return tomllib.loads(toml_content).get("package", {}).get("name")
+    except tomllib.TOMLDecodeError:
+        return None
+
+
+class DependencyAuditCheck(Checker):
+    """`cargo deny check advisories licenses` on the dependency tree of the crate.
+
+    Vulnerable, unmaintained and yanked crates and licenses outside the allowed ones
+    are reported as warnings, or fail verification with fail, so that the model picks
+    other dependencies. cargo-deny fetches the advisory database, so it runs on the
+    host even with the sandbox; without cargo-deny the check is skipped with a warning.
+    """
+    name = "audit"
+
+    def __init__(
+        self,
+        fail: bool = False,
+        licenses: Iterable[str] = (),
+        ignore: Iterable[str] = ()
+    ):
+        self.fail = fail
+        self.licenses = list(licenses)
+        self.ignore = list(ignore)
+
+    async def _execute(self, project_dir: Path, config: Path,
+                       timeout: Optional[float]) -> Tuple[int, str, str]:
+        process = await asyncio.create_subprocess_exec(
+            "cargo", "deny", "--format", "json", "--color", "never", "check",
+            "--config", str(config), "advisories", "licenses",
+            cwd=project_dir,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        try:
+            stdout, stderr = await asyncio.wait_for(process.communicate(), timeout=timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError):
+            process.kill()
+            await process.wait()
+            raise
+        return process.returncode, stdout.decode(errors="replace"), stderr.decode(errors="replace")
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        project_dir = builder.prepare_project(rust_code, toml_content)
+        config = project_dir / CONFIG_PATH
+        config.parent.mkdir(parents=True, exist_ok=True)
+        config.write_text(deny_config(self.licenses, self.ignore))
+        logger.info("Auditing the dependencies with cargo-deny")
+        try:
+            returncode, _, stderr = await self._execute(project_dir, config,
+                                                        builder.limits.build.timeout)
+        except (OSError, asyncio.TimeoutError) as e:
+            logger.warning(f"Dependency audit skipped: {type(e).__name__} {e}")
+            return CheckResult(success=True, info={"skipped": str(e) or "timed out"})
+        if "no such command" in stderr and "deny" in stderr:
+            logger.warning("Dependency audit skipped: cargo-deny is not "
+                           f"installed ({INSTALL_HINT})")
+            return CheckResult(success=True, info={"skipped": "cargo-deny is not installed"})
+
+        # Diagnostics are printed on stderr, one JSON object per line
+        violations = dependency_violations(stderr, _package_name(toml_content))
+        info: Dict[str, Any] = {"dependency_violations": [str(violation)
+                                                          for violation in violations]}
+        if returncode != 0 and not violations and "diagnostic" not in stderr:
+            logger.warning(f"Dependency audit skipped: cargo-deny failed: {stderr.strip()[-500:]}")
+            return CheckResult(success=True, info={"skipped": stderr.strip()[-500:]})
+        if not violations:
+            return CheckResult(success=True, info=info)
+        report = "\n".join(f"- {violation}" for violation in violations)
+        if not self.fail:
+            logger.warning(f"{len(violations)} dependency audit violations:\n{report}")
+            return CheckResult(success=True, info=info,
+                               warnings=[str(violation) for violation in violations])
+        return CheckResult(success=False, output=report, info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        return {"critical_differences": {"dependencies": [
+            "These dependencies fail the advisory and license audit. Upgrade them past the "
+            "advisory, replace them with maintained crates under an allowed license, "
+            "or do without them:\n"
+            f"{result.output}"
+        ]}}
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Dependencies still fail the audit after {attempts} fix attempts:\n{result.output}"
@@ -0,0 +1,42 @@
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from ..builders import RustBuilder
+
+
+@dataclass
+class CheckResult:
+    """Outcome of one checker on a generated crate."""
+    success: bool
+    output: Optional[str] = None  # what failed, given to the fix prompt
+    info: Dict[str, Any] = field(default_factory=dict)
+    rust_code: Optional[str] = None  # normalized code replacing the checked code
+    warnings: List[str] = field(default_factory=list)  # reported without failing the check
+    metrics: Dict[str, Any] = field(default_factory=dict)  # of the final code, in the file report
+
+
+class Checker:
+    """Base class for the verification steps run on generated code after it is built.
+
+    Checkers run in order; when one fails, its output is sent to the build fix
+    prompt and the checkers up to it run again on the fixed code, until they
+    pass or the fix attempts run out. Subclasses implement run and may override
+    how a failure is described to the model and in the report.
+    """
+    name: str = "checker"
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        """Check the crate; builder writes it to the output directory and runs cargo there."""
+        raise NotImplementedError
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        """The verification result sent to the fix prompt for a failure: its output, verbatim."""
+        return {"critical_differences": {self.name: [result.output]}}
+
+    def fix_label(self) -> str:
+        """Prefix of the code history entries of its fixes."""
+        return self.name
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        """The error reported once fixes gave up."""
+        return f"Failed to fix {self.name} errors after {attempts} attempts: {result.output}"
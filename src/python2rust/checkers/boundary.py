@@ -0,0 +1,118 @@
+import asyncio
+import itertools
+import json
+import random
+import shutil
+import tempfile
+from pathlib import Path
+from typing import Any, Callable, Dict, List
+
+from ..analyzers.fuzz_targets import FuzzTarget, FuzzType, fuzz_targets
+from ..builders import RustBuilder
+from ..utils.logging import setup_logger
+from .base import CheckResult
+from .fuzz import DifferentialFuzzer, HarnessTimeout
+
+logger = setup_logger()
+
+# Boundary inputs of a function at most, sampled when their combinations are more
+MAX_BOUNDARY_CASES = 64
+
+
+def combined_cases(
+    target: FuzzTarget,
+    values: Callable[[FuzzType], List[Any]],
+    limit: int = MAX_BOUNDARY_CASES
+) -> List[List[Any]]:
+    """Combinations of the values of each parameter, sampled the same way on each run."""
+    cases = [list(args) for args in itertools.product(*(values(param) for param in target.params))]
+    if len(cases) <= limit:
+        return cases
+    return random.Random(target.name).sample(cases, limit)
+
+
+class BoundaryCheck(DifferentialFuzzer):
+    """Functions at risk of a semantic trap, called in both languages with inputs hitting it.
+
+    Subclasses find the risky sites statically (objects with a function, a kind and a
+    description) and give the inputs of each parameter type. Each function with such
+    sites that can be fuzzed is called in Python and in Rust with the combinations of
+    these inputs, and the first diverging call is reported with the sites of the
+    function. Sites of functions the harness cannot call become warnings.
+    """
+    name = "boundary"
+    # What the sites are at risk of, in warnings and logs
+    subject = "boundary"
+    # What the diverging inputs are, in the fix request
+    inputs = "on a boundary input"
+
+    def __init__(self, source: Path, **kwargs):
+        super().__init__(source, shrink_rounds=0, **kwargs)
+
+    def risks(self, python_code: str, rust_code: str) -> List[Any]:
+        raise NotImplementedError
+
+    def values(self, kind: FuzzType) -> List[Any]:
+        raise NotImplementedError
+
+    def advice(self) -> str:
+        """How to make the Rust code behave like the Python code, in the fix request."""
+        raise NotImplementedError
+
+    def case_inputs(self, target: FuzzTarget) -> List[List[Any]]:
+        return combined_cases(target, self.values)
+
+    def _warnings(self, risks: List[Any]) -> List[str]:
+        return [f"{self.subject} {risk.kind} risk at {risk}" for risk in risks]
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not checking the {self.subject} semantics of {self.source}: {e}")
+            return CheckResult(success=True)
+        risks = self.risks(python_code, rust_code)
+        at_risk = {risk.function for risk in risks}
+        targets = [target for target in fuzz_targets(python_code, rust_code)
+                   if target.name in at_risk]
+        tested = {target.name for target in targets}
+        info: Dict[str, Any] = {"risks": [str(risk) for risk in risks], "targets": sorted(tested)}
+        warnings = self._warnings([risk for risk in risks if risk.function not in tested])
+        if not targets:
+            return CheckResult(success=True, info=info, warnings=warnings)
+
+        cases = [(target, args) for target in targets for args in self.case_inputs(target)]
+        project_dir = builder.prepare_project(rust_code, toml_content)
+        self._sandbox = builder.sandbox
+        self._limits = builder.limits.test
+        logger.info(f"Checking the {self.subject} semantics of {', '.join(sorted(tested))} "
+                    f"with {len(cases)} inputs")
+        with tempfile.TemporaryDirectory(prefix=f"python2rust_{self.name}_") as temp:
+            directory = Path(temp)
+            try:
+                divergences = await self._divergences(cases, project_dir, rust_code,
+                                                      toml_content, directory)
+            except HarnessTimeout as e:
+                return CheckResult(success=False, output=str(e), info={**info, "timed_out": True})
+            except (RuntimeError, ValueError, OSError, asyncio.TimeoutError) as e:
+                logger.warning(f"{self.subject.capitalize()} tests of "
+                               f"{self.source.name} were skipped: {e}")
+                # Untested, every site is only flagged
+                return CheckResult(success=True, info={**info, "skipped": str(e) or "timed out"},
+                                   warnings=self._warnings(risks))
+            finally:
+                shutil.rmtree(directory / "crate", ignore_errors=True)
+        if not divergences:
+            return CheckResult(success=True, info={**info, "cases": len(cases)}, warnings=warnings)
+
+        target, args, python_result, rust_result = divergences[0]
+        call = f"{target.name}({', '.join(repr(arg) for arg in args)})"
+        sites = "\n".join(f"- {risk}" for risk in risks if risk.function == target.name)
+        return CheckResult(
+            success=False,
+            output=(f"`{call}` diverges {self.inputs}: Python returns {json.dumps(python_result)}, "
+                    f"Rust returns {json.dumps(rust_result)}. {self.advice()} "
+                    f"Sites at risk:\n{sites}"),
+            info={**info, "function": target.name, "args": args, "python": python_result,
+                  "rust": rust_result, "divergences": len(divergences)}
+        )
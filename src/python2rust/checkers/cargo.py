@@ -0,0 +1,193 @@
+import re
+from typing import Any, Dict, Iterable, List, Tuple
+
+from ..builders import RustBuilder
+from ..utils.error_formatter import format_error_for_fix
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+FAILED_TEST_PATTERN = re.compile(r"^test (\S+) \.\.\. FAILED$", re.MULTILINE)
+TEST_OUTPUT_PATTERN = re.compile(r"^---- (\S+) stdout ----$", re.MULTILINE)
+# Longest output of one failing test given to the fix prompt
+MAX_TEST_OUTPUT_CHARS = 4000
+# Errors Miri stops a test binary at: the code is wrong, not the interpreter
+MIRI_ERROR_PATTERN = re.compile(r"^error: (?:Undefined Behavior|memory leaked)", re.MULTILINE)
+MIRI_UNSUPPORTED_PATTERN = re.compile(r"^error: unsupported operation: .*$", re.MULTILINE)
+# The toolchain or its miri component is missing
+MIRI_MISSING_PATTERN = re.compile(r"no such (?:sub)?command: `?miri|"
+                                  r"toolchain '[^']+' is not installed|"
+                                  r"'cargo-miri' is not installed")
+
+
+def failing_tests(output: str) -> List[Tuple[str, str]]:
+    """Failing tests of `cargo test` output, each with its captured output and panic, verbatim."""
+    names = FAILED_TEST_PATTERN.findall(output or "")
+    sections: Dict[str, str] = {}
+    matches = list(TEST_OUTPUT_PATTERN.finditer(output or ""))
+    for index, match in enumerate(matches):
+        end = matches[index + 1].start() if index + 1 < len(matches) else len(output)
+        # The failures list and summary follow the last test's output
+        text = output[match.end():end].split("\nfailures:\n")[0].strip()
+        if len(text) > MAX_TEST_OUTPUT_CHARS:
+            text = text[:MAX_TEST_OUTPUT_CHARS] + "\n..."
+        sections[match.group(1)] = text
+    return [(name, sections.get(name, "")) for name in names]
+
+
+def miri_errors(output: str) -> List[str]:
+    """Undefined behavior and leaks Miri reports in its output, each with its span and notes."""
+    errors = []
+    for match in MIRI_ERROR_PATTERN.finditer(output or ""):
+        # A report ends at the next top-level error, such as "error: aborting due to ..."
+        end = output.find("\nerror", match.end())
+        text = output[match.start():end if end != -1 else len(output)].strip()
+        if len(text) > MAX_TEST_OUTPUT_CHARS:
+            text = text[:MAX_TEST_OUTPUT_CHARS] + "\n..."
+        errors.append(text)
+    return errors
+
+
+class CargoCheck(Checker):
+    """`cargo check`: the crate compiles."""
+    name = "check"
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        success, error, info = await builder.check(rust_code, toml_content)
+        return CheckResult(success=success, output=error, info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        clean_error = format_error_for_fix(result.output)
+        return {
+            "critical_differences": {
+                "build": clean_error,
+                "error_details": {
+                    "type": "compilation",
+                    "message": clean_error,
+                    "attempt": attempt,
+                    "max_attempts": max_attempts
+                }
+            }
+        }
+
+    def fix_label(self) -> str:
+        return "build"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return result.output or "Build failed"
+
+
+class Clippy(Checker):
+    """`cargo clippy`: none of the denied lints fire, by default any warning."""
+    name = "clippy"
+
+    def __init__(self, deny: Iterable[str] = ("warnings",), allow: Iterable[str] = ()):
+        self.deny = list(deny)
+        self.allow = list(allow)
+
+    def lint_args(self) -> List[str]:
+        return [*(arg for lint in self.deny for arg in ("-D", lint)),
+                *(arg for lint in self.allow for arg in ("-A", lint))]
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        success, error, info = await builder.clippy(rust_code, toml_content, self.lint_args())
+        return CheckResult(success=success, output=error, info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        return {"critical_differences": {"build": {"clippy": result.output}}}
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Failed to fix Clippy errors after {attempts} attempts: {result.output}"
+
+
+class CargoTest(Checker):
+    """`cargo test`: the generated tests pass."""
+    name = "cargo_test"
+
+    def fix_label(self) -> str:
+        return "test"
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        success, error, info = await builder.test(rust_code, toml_content)
+        if success:
+            return CheckResult(success=True, info=info)
+        failures = failing_tests(info.get("output", ""))
+        if not failures:
+            # The tests did not compile, or cargo failed before running them
+            return CheckResult(success=False, output=error, info=info)
+        info = {**info, "failed_tests": [name for name, _ in failures]}
+        return CheckResult(success=False, output="\n\n".join(
+            f"---- {name} ----\n{details}" if details else f"---- {name} ----"
+            for name, details in failures
+        ), info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        failed = result.info.get("failed_tests")
+        if result.info.get("timed_out"):
+            return {"critical_differences": {"tests": [
+                "The tests did not finish in time: a test or the code it calls loops forever or "
+                f"is far slower than the Python version. Find and fix it.\n{result.output}"
+            ]}}
+        if not failed:
+            return {"critical_differences": {"tests": ["The tests do not build:\n"
+                                                       f"{result.output}"]}}
+        return {"critical_differences": {"tests": [
+            f"Failing tests: {', '.join(failed)}. Fix the implementation, not the tests, unless a "
+            f"test contradicts the Python behavior.\n{result.output}"
+        ]}}
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        failed = result.info.get("failed_tests")
+        if failed:
+            return f"Tests still failing after {attempts} fix attempts: {', '.join(failed)}"
+        return super().failure(result, attempts)
+
+
+class Miri(Checker):
+    """`cargo miri test`: the tests run without undefined behavior.
+
+    Miri interprets the tests and stops at the first undefined behavior or leak, which
+    only unsafe code can cause. Tests calling what Miri cannot interpret, such as
+    sockets or FFI, and runs past the miri limit skip the check with a warning, as does
+    a missing toolchain.
+    """
+    name = "miri"
+
+    def __init__(self, flags: Iterable[str] = (), toolchain: str = "nightly"):
+        self.flags = list(flags)
+        self.toolchain = toolchain
+
+    def _skipped(self, reason: str, info: Dict[str, Any]) -> CheckResult:
+        logger.warning(f"Miri check skipped: {reason}")
+        return CheckResult(success=True, info={**info, "skipped": reason})
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        success, error, info = await builder.miri(rust_code, toml_content,
+                                                  self.flags, self.toolchain)
+        if success:
+            return CheckResult(success=True, info=info)
+        error = error or ""
+        errors = miri_errors(error)
+        if errors:
+            return CheckResult(success=False, output="\n\n".join(errors),
+                               info={**info, "miri_errors": len(errors)})
+        if MIRI_MISSING_PATTERN.search(error):
+            return self._skipped(
+                f"Miri is not installed: rustup +{self.toolchain} component add miri", info)
+        unsupported = MIRI_UNSUPPORTED_PATTERN.search(error)
+        if unsupported:
+            return self._skipped(unsupported.group(0), info)
+        if info.get("timed_out"):
+            return self._skipped(error, info)
+        # Tests failing only when interpreted, like code depending on addresses or timing
+        return CheckResult(success=False, output=error, info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        if not result.info.get("miri_errors"):
+            return {"critical_differences": {"miri": ["The tests fail under Miri:\n"
+                                                      f"{result.output}"]}}
+        return {"critical_differences": {"miri": [
+            f"Miri found undefined behavior running the tests. Make the unsafe code sound, or "
+            f"replace it with safe Rust, which is preferred.\n{result.output}"
+        ]}}
@@ -0,0 +1,250 @@
+import ast
+import asyncio
+import difflib
+import os
+import re
+import sys
+import tempfile
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional
+
+from ..analyzers.http_routes import find_web_app
+from ..builders import RustBuilder
+from ..builders.benchmark import rust_binary
+from ..builders.memory import (
+    VALGRIND_SLOWDOWN, MemoryProfiler, MemoryUsage, memory_comparison, read_allocations
+)
+from ..builders.sandbox import Sandbox
+from ..config.settings import CliCase, StepLimits
+from ..utils.limits import preexec
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+MAX_DIFF_LINES = 40
+MAX_MISMATCHES = 10
+PROGRAM = "<program>"
+
+
+@dataclass
+class CliRun:
+    """Exit code and output of one run of a program; exit code None when it timed out."""
+    exit_code: Optional[int]
+    stdout: str = ""
+    stderr: str = ""
+
+
+def is_cli_script(python_code: str) -> bool:
+    """Whether a module runs as a program: it has a main guard and is not a web app."""
+    try:
+        tree = ast.parse(python_code)
+    except SyntaxError:
+        return False
+    has_main_guard = any(
+        isinstance(node, ast.If) and isinstance(node.test, ast.Compare)
+        and isinstance(node.test.left, ast.Name) and node.test.left.id == "__name__"
+        for node in tree.body
+    )
+    return has_main_guard and find_web_app(python_code) is None
+
+
+def _diff(expected: str, actual: str) -> str:
+    diff = list(difflib.unified_diff(
+        expected.splitlines(), actual.splitlines(), "python", "rust", lineterm=""))
+    if len(diff) > MAX_DIFF_LINES:
+        diff = diff[:MAX_DIFF_LINES] + ["..."]
+    return "\n".join(diff)
+
+
+def _label(case: CliCase) -> str:
+    return " ".join([PROGRAM, *case.args]) + (f" with stdin {case.stdin!r}" if case.stdin else "")
+
+
+def run_differences(python: CliRun, rust: CliRun, stderr: bool = True,
+                    ignore: Iterable[str] = ()) -> List[str]:
+    """How the Rust run differs from the Python one: exit code, standard output and error."""
+    def masked(text: str) -> str:
+        for pattern in ignore:
+            text = re.sub(pattern, "<ignored>", text)
+        return text
+
+    if rust.exit_code is None:
+        return [f"the Rust program did not finish: {rust.stderr}; it loops forever, waits for "
+                "input the Python script does not read, or is far slower"]
+    differences = []
+    if python.exit_code != rust.exit_code:
+        differences.append(f"exit code {python.exit_code} in Python, {rust.exit_code} in Rust")
+    streams = [("stdout", python.stdout, rust.stdout)]
+    if stderr:
+        streams.append(("stderr", python.stderr, rust.stderr))
+    for name, expected, actual in streams:
+        expected, actual = masked(expected), masked(actual)
+        if expected != actual:
+            differences.append(f"{name} differs:\n{_diff(expected, actual)}")
+    return differences
+
+
+class CliEquivalence(Checker):
+    """Runs a command-line script and the generated Rust binary with the same arguments and input.
+
+    Both run from the script's directory, so relative paths in arguments name the
+    same files. Exit codes and standard output must agree, and standard error unless
+    disabled; the program names, which differ, are replaced in both outputs. With a
+    memory profiler, the peak resident memory of each run is sampled, and once all
+    runs agree their allocations are counted; sandboxed Rust runs are not measured.
+    """
+    name = "cli"
+
+    def __init__(
+        self,
+        source: Path,
+        cases: Iterable[CliCase] = (CliCase(),),
+        stderr: bool = True,
+        ignore: Iterable[str] = (),
+        timeout: Optional[float] = None,
+        python: str = sys.executable,
+        memory: Optional[MemoryProfiler] = None
+    ):
+        self.source = Path(source)
+        self.cases = list(cases)
+        self.stderr = stderr
+        self.ignore = list(ignore)
+        self.timeout = timeout
+        self.python = python
+        self.memory = memory
+
+    def fix_label(self) -> str:
+        return "cli"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Outputs still differ after {attempts} fix attempts: {result.output}"
+
+    async def _run(self, command: List[str], case: CliCase, names: List[str], limits: StepLimits,
+                   sandbox: Optional[Sandbox] = None, usage: Optional[MemoryUsage] = None,
+                   env: Optional[Dict[str, str]] = None) -> CliRun:
+        """Run the command with the arguments and input of case; with usage, its peak resident
+        memory is sampled into it by the memory profiler."""
+        command = [*command, *case.args]
+        memory_mb = limits.memory_mb
+        if sandbox is not None:
+            # Run from the script's directory like the script, with the binary's crate mounted too
+            command = sandbox.command(
+                command, self.source.parent.resolve(), mounts=[Path(command[0]).parent],
+                timeout=limits.timeout, memory_mb=memory_mb)
+            memory_mb = None
+        process = await asyncio.create_subprocess_exec(
+            *command,
+            cwd=self.source.parent,
+            stdin=asyncio.subprocess.PIPE,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+            env={**os.environ, **env} if env else None,
+            preexec_fn=preexec(memory_mb)
+        )
+        sampler = self.memory.sample(process.pid, usage) if usage is not None else None
+        try:
+            stdout, stderr = await asyncio.wait_for(
+                process.communicate((case.stdin or "").encode()), timeout=limits.timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError) as e:
+            process.kill()
+            await process.wait()
+            if isinstance(e, asyncio.CancelledError):
+                raise
+            return CliRun(exit_code=None, stderr=f"timed out after {limits.timeout:g} seconds")
+        finally:
+            if sampler is not None:
+                sampler.cancel()
+
+        def anonymous(output: bytes) -> str:
+            text = output.decode(errors="replace")
+            for name in names:
+                text = re.sub(rf"(?<![\w./-]){re.escape(name)}(?![\w.-])", PROGRAM, text)
+            return text
+
+        return CliRun(process.returncode, anonymous(stdout), anonymous(stderr))
+
+    async def _allocations(self, command: List[str], case: CliCase, timeout: Optional[float],
+                           log_file: Path, python: bool) -> Optional[int]:
+        """Heap allocations of a run under valgrind, with the time limit it
+        slows the run down to."""
+        command = self.memory.counted(command, log_file)
+        limits = StepLimits(timeout=timeout * VALGRIND_SLOWDOWN if timeout else None)
+        run = await self._run(command, case, [], limits, env=self.memory.counted_env(python))
+        return read_allocations(log_file) if run.exit_code is not None else None
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not comparing the runs of {self.source}: {e}")
+            return CheckResult(success=True)
+        if not is_cli_script(python_code) or not self.cases:
+            logger.info(f"{self.source.name} is not a command-line script")
+            return CheckResult(success=True, info={"runs": 0})
+
+        success, error, _ = await builder.build(rust_code, toml_content, release=False)
+        if not success:
+            return CheckResult(success=False, output=error)
+        binary = rust_binary(builder.output_dir, "debug").resolve()
+        # Longest first, so that a path is replaced before the name it ends with
+        names = sorted({str(self.source.resolve()), str(self.source), self.source.name,
+                        str(binary), binary.name}, key=len, reverse=True)
+
+        logger.info(f"Comparing {len(self.cases)} runs of the Python and Rust "
+                    f"{self.source.name} programs")
+        # The Rust runs get the run limits of the builder, the Python ones only its time limit
+        limits = builder.limits.run
+        if self.timeout is not None:
+            limits = StepLimits(timeout=self.timeout, memory_mb=limits.memory_mb)
+        script = [self.python, str(self.source.resolve())]
+        measured = self.memory is not None
+        mismatches = []
+        usages = []
+        for case in self.cases:
+            python_usage, rust_usage = MemoryUsage(), MemoryUsage()
+            python = await self._run(script, case, names, StepLimits(timeout=limits.timeout),
+                                     usage=python_usage if measured else None)
+            if python.exit_code is None:
+                logger.warning(f"Skipping `{' '.join(case.args)}`: the Python "
+                               f"script {python.stderr}")
+                continue
+            # In a sandbox the samples would be of docker rather than of the binary
+            rust = await self._run([str(binary)], case, names, limits, builder.sandbox,
+                                   usage=rust_usage if measured
+                                   and builder.sandbox is None else None)
+            differences = run_differences(python, rust, self.stderr, self.ignore)
+            if differences:
+                mismatches.append(f"`{_label(case)}`: " + "\n".join(differences))
+            if measured:
+                usages.append((case, python_usage, rust_usage))
+        if not mismatches and measured and self.memory.counts_allocations:
+            with tempfile.TemporaryDirectory(prefix="python2rust_cli_") as temp:
+                directory = Path(temp)
+                # Only the final code is counted, valgrind being slow
+                for index, (case, python_usage, rust_usage) in enumerate(usages):
+                    python_usage.allocations = await self._allocations(
+                        script, case, limits.timeout, directory / f"python{index}.log", python=True)
+                    if builder.sandbox is None:
+                        rust_usage.allocations = await self._allocations(
+                            [str(binary)], case, limits.timeout,
+                            directory / f"rust{index}.log", python=False)
+        info = {"runs": len(self.cases), "mismatches": len(mismatches)}
+        if not mismatches:
+            memory = [memory_comparison(_label(case), python, rust)
+                      for case, python, rust in usages]
+            for entry in memory:
+                logger.info(f"`{entry['run']}`: peak {entry['python']['peak_rss_mb']} MB in "
+                            f"Python, {entry['rust']['peak_rss_mb']} MB in Rust")
+            return CheckResult(success=True, info=info,
+                               metrics={"memory": memory} if memory else {})
+        shown = mismatches[:MAX_MISMATCHES]
+        if len(mismatches) > len(shown):
+            shown.append(f"... and {len(mismatches) - len(shown)} more")
+        return CheckResult(
+            success=False,
+            output=("The Rust program behaves differently from the Python script. Make its exit "
+                    "code and output match:\n\n" + "\n\n".join(shown)),
+            info=info
+        )
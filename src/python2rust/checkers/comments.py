@@ -0,0 +1,59 @@
+from typing import Any, Dict, List
+
+from ..analyzers.comments import PythonComment, missing_comments, python_comments
+from ..analyzers.rust_policy import analyze_rust, build_analyzer, scanned_facts
+from ..builders import RustBuilder
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+
+class CommentCheck(Checker):
+    """The comments of the Python code are kept in the functions translating the code they are
+    on, as Rust comments repeating most of their words.
+
+    The functions and their lines come from the syn-based policy analyzer, or from the
+    line-based scans when cargo cannot build it.
+    """
+    name = "comments"
+
+    def __init__(self, python_code: str):
+        self.python_code = python_code
+        self.comments = python_comments(python_code)
+
+    async def _functions(self, builder: RustBuilder, rust_code: str) -> List[Dict[str, Any]]:
+        try:
+            binary = await build_analyzer(timeout=builder.limits.build.timeout)
+            facts = await analyze_rust(rust_code, binary)
+        except (OSError, RuntimeError, ValueError) as e:
+            logger.warning(f"Locating the comments with the line-based scans: {e}")
+            facts = scanned_facts(rust_code)
+        return [function for function in facts.get("functions", []) if not function.get("test")]
+
+    def _describe(self, missing: List[PythonComment]) -> str:
+        return "\n".join(f"- {comment.describe()}" for comment in missing)
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        if not self.comments:
+            return CheckResult(success=True)
+        functions = await self._functions(builder, rust_code)
+        missing = missing_comments(self.comments, self.python_code, rust_code, functions)
+        metrics = {"comments": len(self.comments),
+                   "comments_kept": len(self.comments) - len(missing)}
+        info = {"missing_comments": [comment.describe() for comment in missing]}
+        if not missing:
+            return CheckResult(success=True, info=info, metrics=metrics)
+        return CheckResult(success=False, output=self._describe(missing),
+                           info=info, metrics=metrics)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        return {"critical_differences": {"comments": [
+            "These comments of the Python code are missing from the Rust code. Add each one as a "
+            "// comment next to the Rust code translating the statement it is at, in the function "
+            f"translating its Python function, without changing the code:\n{result.output}"
+        ]}}
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return (f"Comments of the Python code still missing after {attempts} fix attempts:\n"
+                f"{result.output}")
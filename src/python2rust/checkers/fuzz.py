@@ -0,0 +1,487 @@
+import asyncio
+import json
+import math
+import os
+import random
+import shutil
+import sys
+import tempfile
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from ..analyzers.fuzz_targets import FuzzTarget, FuzzType, fuzz_targets
+from ..builders import RustBuilder
+from ..builders.sandbox import Sandbox
+from ..config.settings import StepLimits
+from ..utils.limits import preexec
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+OUTPUT_PREFIX = "PYTHON2RUST_FUZZ"
+STRING_CHARS = "abcxyzABC019 -_.,:/'\"\\\n\té€"
+# Random integers and floats stay within this magnitude, besides the type's own limits
+MAGNITUDE = 10 ** 6
+MAX_STRING = 12
+MAX_LIST = 8
+SHRINK_CANDIDATES = 40
+# Relative difference allowed between float results, larger when f32 is involved
+FLOAT_TOLERANCE = 1e-9
+F32_TOLERANCE = 1e-5
+
+# Calls each case with the module imported under another name, so that its main guard stays off
+PYTHON_RUNNER = """
+import importlib.util, json, sys
+spec = importlib.util.spec_from_file_location("python2rust_fuzzed", sys.argv[1])
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+results = []
+for name, args in json.load(open(sys.argv[2])):
+    try:
+        value = getattr(module, name)(*args)
+        json.dumps(value, allow_nan=True)
+        results.append(value)
+    except TypeError as e:
+        if "not JSON serializable" in str(e):
+            results.append({"unsupported": repr(value)})
+        else:
+            results.append({"error": f"TypeError: {e}"})
+    except BaseException as e:
+        results.append({"error": f"{type(e).__name__}: {e}"})
+print(json.dumps(results, allow_nan=True))
+"""
+
+RUST_HARNESS = r"""
+#[cfg(test)]
+#[allow(unused_imports, dead_code, clippy::all)]
+mod python2rust_fuzz {
+    use super::*;
+
+    trait Json {
+        fn json(&self) -> String;
+    }
+
+    fn string(text: &str) -> String {
+        let mut out = String::from("\"");
+        for c in text.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    macro_rules! integers {
+        ($($t:ty),*) => { $(impl Json for $t { fn json(&self) -> String { self.to_string() } })* };
+    }
+    integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+    macro_rules! floats {
+        ($($t:ty),*) => { $(impl Json for $t {
+            fn json(&self) -> String {
+                if self.is_nan() { "NaN".into() }
+                else if self.is_infinite() {
+                    if *self > 0.0 { "Infinity".into() } else { "-Infinity".into() }
+                }
+                else { format!("{:?}", self) }
+            }
+        })* };
+    }
+    floats!(f32, f64);
+
+    impl Json for bool { fn json(&self) -> String { self.to_string() } }
+    impl Json for char { fn json(&self) -> String { string(&self.to_string()) } }
+    impl Json for str { fn json(&self) -> String { string(self) } }
+    impl Json for String { fn json(&self) -> String { string(self) } }
+    impl Json for () { fn json(&self) -> String { "null".into() } }
+    impl<T: Json + ?Sized> Json for &T { fn json(&self) -> String { (**self).json() } }
+    impl<T: Json + ?Sized> Json for Box<T> { fn json(&self) -> String { (**self).json() } }
+    impl<T: Json> Json for [T] {
+        fn json(&self) -> String {
+            format!("[{}]", self.iter().map(|v| v.json()).collect::<Vec<_>>().join(", "))
+        }
+    }
+    impl<T: Json> Json for Vec<T> { fn json(&self) -> String { self.as_slice().json() } }
+    impl<T: Json> Json for Option<T> {
+        fn json(&self) -> String { match self { Some(v) => v.json(), None => "null".into() } }
+    }
+    impl<T: Json, E: std::fmt::Debug> Json for Result<T, E> {
+        fn json(&self) -> String {
+            match self {
+                Ok(v) => v.json(),
+                Err(e) => format!("{{\"error\": {}}}", string(&format!("{:?}", e))),
+            }
+        }
+    }
+    fn key<K: Json>(k: &K) -> String {
+        let text = k.json();
+        if text.starts_with('"') { text } else { string(&text) }
+    }
+    impl<K: Json, V: Json, S> Json for std::collections::HashMap<K, V, S> {
+        fn json(&self) -> String {
+            let entries = self.iter().map(|(k, v)| format!("{}: {}", key(k), v.json()));
+            format!("{{{}}}", entries.collect::<Vec<_>>().join(", "))
+        }
+    }
+    impl<K: Json, V: Json> Json for std::collections::BTreeMap<K, V> {
+        fn json(&self) -> String {
+            let entries = self.iter().map(|(k, v)| format!("{}: {}", key(k), v.json()));
+            format!("{{{}}}", entries.collect::<Vec<_>>().join(", "))
+        }
+    }
+    macro_rules! tuples {
+        ($(($($name:ident),+)),*) => { $(impl<$($name: Json),+> Json for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn json(&self) -> String {
+                let ($($name,)+) = self;
+                format!("[{}]", vec![$($name.json()),+].join(", "))
+            }
+        })* };
+    }
+    tuples!((A, B), (A, B, C), (A, B, C, D));
+
+    fn report(case: usize, result: std::thread::Result<String>) {
+        match result {
+            Ok(json) => println!("PYTHON2RUST_FUZZ {} {}", case, json),
+            Err(_) => println!("PYTHON2RUST_FUZZ {} {{\"error\": \"panic\"}}", case),
+        }
+    }
+
+    #[test]
+    fn differential() {
+        std::panic::set_hook(Box::new(|_| {}));
+CASES
+    }
+}
+"""
+
+
+def random_value(kind: FuzzType, rng: random.Random) -> Any:
+    """A random input of a type, biased toward its edge cases."""
+    if kind.kind == "int":
+        low, high = max(kind.low, -MAGNITUDE), min(kind.high, MAGNITUDE)
+        edges = [value for value in (0, 1, -1, 2, low, high) if kind.low <= value <= kind.high]
+        return rng.choice(edges) if rng.random() < 0.3 else rng.randint(low, high)
+    if kind.kind == "float":
+        if rng.random() < 0.3:
+            return rng.choice([0.0, 1.0, -1.0, 0.5, 1e-9, -2.5])
+        return round(rng.uniform(-MAGNITUDE, MAGNITUDE), rng.randint(0, 6))
+    if kind.kind == "bool":
+        return rng.random() < 0.5
+    if kind.kind == "str":
+        return "".join(rng.choice(STRING_CHARS) for _ in range(rng.randint(0, MAX_STRING)))
+    return [random_value(kind.item, rng) for _ in range(rng.randint(0, MAX_LIST))]
+
+
+def _string_literal(text: str) -> str:
+    escaped = []
+    for char in text:
+        if char in "\\\"":
+            escaped.append("\\" + char)
+        elif char == "\n":
+            escaped.append("\\n")
+        elif char == "\t":
+            escaped.append("\\t")
+        elif char.isprintable():
+            escaped.append(char)
+        else:
+            escaped.append(f"\\u{{{ord(char):x}}}")
+    return f"\"{''.join(escaped)}\""
+
+
+def rust_literal(value: Any, kind: FuzzType) -> str:
+    """A Rust expression of the parameter type for an input."""
+    rust = kind.rust.replace(" ", "")
+    borrowed = rust.startswith("&")
+    reference = "&" if borrowed else ""
+    if kind.kind == "int":
+        return f"{reference}{value}"
+    if kind.kind == "float":
+        text = repr(float(value))
+        return reference + (text if any(c in text for c in ".e") else f"{text}.0")
+    if kind.kind == "bool":
+        return f"{reference}{'true' if value else 'false'}"
+    if kind.kind == "str":
+        literal = _string_literal(value)
+        if rust == "&str":
+            return literal
+        return f"&String::from({literal})" if borrowed else f"String::from({literal})"
+    items = ", ".join(rust_literal(item, kind.item) for item in value)
+    if "[" in rust:
+        return f"&[{items}]"
+    return f"&vec![{items}]" if borrowed else f"vec![{items}]"
+
+
+def _size(value: Any) -> float:
+    if isinstance(value, bool):
+        return int(value)
+    if isinstance(value, (int, float)):
+        return abs(value)
+    if isinstance(value, str):
+        return len(value) * 1000 + sum(ord(c) for c in value)
+    return len(value) * 10 ** 7 + sum(_size(item) for item in value)
+
+
+def _shrunk(value: Any, kind: FuzzType) -> List[Any]:
+    """Smaller variants of an input."""
+    if kind.kind == "int":
+        # Zero, then halvings toward it and the next value toward it
+        sign = 1 if value > 0 else -1
+        variants = {0, sign, value - sign,
+                    *(sign * (abs(value) >> shift) for shift in range(1, 64))}
+        return sorted(v for v in variants if kind.low <= v <= kind.high and abs(v) < abs(value))
+    if kind.kind == "float":
+        return sorted(v for v in {0.0, float(round(value)), value / 2, value / 16}
+                      if abs(v) < abs(value))
+    if kind.kind == "bool":
+        return [False] if value else []
+    if not value:
+        return []
+    variants = [value[:0], value[:len(value) // 2],
+                *(value[:i] + value[i + 1:] for i in range(len(value)))]
+    if kind.kind == "list":
+        for i, item in enumerate(value):
+            variants.extend(value[:i] + [smaller] + value[i + 1:]
+                            for smaller in _shrunk(item, kind.item))
+    else:
+        variants.extend(value[:i] + "a" + value[i + 1:] for i, c in enumerate(value) if c != "a")
+    return variants
+
+
+def same_result(python: Any, rust: Any, tolerance: float = FLOAT_TOLERANCE) -> bool:
+    """Whether a Python and a Rust result agree: errors on both sides, or equal values."""
+    python_error = isinstance(python, dict) and set(python) == {"error"}
+    rust_error = isinstance(rust, dict) and set(rust) == {"error"}
+    if python_error or rust_error:
+        return python_error and rust_error
+    if isinstance(python, bool) or isinstance(rust, bool):
+        return isinstance(python, bool) and isinstance(rust, bool) and python == rust
+    if isinstance(python, (int, float)) and isinstance(rust, (int, float)):
+        if math.isnan(python) or math.isnan(rust):
+            return math.isnan(python) and math.isnan(rust)
+        return math.isclose(python, rust, rel_tol=tolerance, abs_tol=tolerance)
+    if isinstance(python, (list, tuple)) and isinstance(rust, list):
+        return (len(python) == len(rust)
+                and all(same_result(p, r, tolerance) for p, r in zip(python, rust)))
+    if isinstance(python, dict) and isinstance(rust, dict):
+        python = {str(k): v for k, v in python.items()}
+        return (python.keys() == rust.keys()
+                and all(same_result(python[k], rust[k], tolerance) for k in python))
+    return python == rust
+
+
+class HarnessTimeout(Exception):
+    """The Rust side of the fuzzing did not finish: a verification failure, not
+    a skipped harness."""
+
+
+class DifferentialFuzzer(Checker):
+    """Calls the Python functions and their Rust translations with the same random inputs.
+
+    Functions are paired by name, for parameter types inputs can be generated for
+    (integers, floats, booleans, strings and lists of them). The Python module runs
+    in a subprocess; the Rust calls run in a test module added to a copy of the
+    crate. The first diverging input is shrunk while a smaller one still diverges,
+    and reported with both results.
+    """
+    name = "fuzz"
+
+    def __init__(
+        self,
+        source: Path,
+        cases: int = 100,
+        shrink_rounds: int = 8,
+        python: str = sys.executable,
+        timeout: float = 300
+    ):
+        self.source = Path(source)
+        self.cases = cases
+        self.shrink_rounds = shrink_rounds
+        self.python = python
+        self.timeout = timeout
+        self._sandbox: Optional[Sandbox] = None
+        # The harness runs with the test limits of the builder
+        self._limits = StepLimits(timeout=timeout)
+
+    def fix_label(self) -> str:
+        return "fuzzing"
+
+    async def _communicate(self, args: List[str], cwd: Optional[Path] = None,
+                           env: Optional[Dict[str, str]] = None,
+                           limits: Optional[StepLimits] = None) -> Tuple[int, str, str]:
+        limits = limits or StepLimits(timeout=self.timeout)
+        process = await asyncio.create_subprocess_exec(
+            *args, cwd=cwd, env=env, stdout=asyncio.subprocess.PIPE, stderr=asyncio.subprocess.PIPE,
+            preexec_fn=preexec(limits.memory_mb))
+        try:
+            stdout, stderr = await asyncio.wait_for(process.communicate(), timeout=limits.timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError):
+            process.kill()
+            await process.wait()
+            raise
+        return process.returncode, stdout.decode(errors="replace"), stderr.decode(errors="replace")
+
+    async def _python_results(self, cases: List[Tuple[str, List[Any]]],
+                              directory: Path) -> List[Any]:
+        cases_file = directory / "cases.json"
+        cases_file.write_text(json.dumps(cases))
+        code, stdout, stderr = await self._communicate(
+            [self.python, "-c", PYTHON_RUNNER, str(self.source.resolve()), str(cases_file)],
+            cwd=self.source.parent)
+        if code != 0:
+            raise RuntimeError(f"The Python module could not run: {stderr.strip()[-2000:]}")
+        return json.loads(stdout.strip().splitlines()[-1])
+
+    async def _rust_results(
+        self,
+        cases: List[Tuple[FuzzTarget, List[Any]]],
+        project_dir: Path,
+        rust_code: str,
+        toml_content: str,
+        directory: Path
+    ) -> List[Any]:
+        calls = "\n".join(
+            f"        report({index}, std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| "
+            f"super::{target.name}({', '.join(map(rust_literal, args, target.params))})"
+            f".json())));"
+            for index, (target, args) in enumerate(cases)
+        )
+        crate = directory / "crate"
+        (crate / "src").mkdir(parents=True, exist_ok=True)
+        (crate / "Cargo.toml").write_text(toml_content)
+        (crate / "src" / "main.rs").write_text(rust_code + "\n"
+                                               + RUST_HARNESS.replace("CASES", calls))
+        command = ["cargo", "test", "--quiet", "python2rust_fuzz::", "--", "--nocapture"]
+        target_dir = {"CARGO_TARGET_DIR": str(project_dir / "target")}
+        limits = self._limits
+        if self._sandbox is not None:
+            (project_dir / "target").mkdir(exist_ok=True)
+            command = self._sandbox.command(
+                command, crate, env=target_dir, mounts=[project_dir / "target"],
+                timeout=limits.timeout, memory_mb=limits.memory_mb)
+            limits = StepLimits(timeout=limits.timeout)
+        try:
+            code, stdout, stderr = await self._communicate(
+                command, cwd=crate, env={**os.environ, **target_dir}, limits=limits)
+        except asyncio.TimeoutError:
+            raise HarnessTimeout(
+                f"The Rust functions did not return within {limits.timeout:g} seconds for the "
+                f"{len(cases)} generated inputs of "
+                f"{', '.join(sorted({target.name for target, _ in cases}))}: one of them loops "
+                "forever or is far slower than the Python version")
+        results: Dict[int, Any] = {}
+        for line in stdout.splitlines():
+            if line.startswith(OUTPUT_PREFIX):
+                _, index, value = line.split(" ", 2)
+                results[int(index)] = json.loads(value)
+        if not results and code != 0:
+            raise RuntimeError(f"The fuzz harness did not build: {stderr.strip()[-2000:]}")
+        return [results.get(index, {"error": "no result"}) for index in range(len(cases))]
+
+    async def _divergences(
+        self,
+        cases: List[Tuple[FuzzTarget, List[Any]]],
+        project_dir: Path,
+        rust_code: str,
+        toml_content: str,
+        directory: Path
+    ) -> List[Tuple[FuzzTarget, List[Any], Any, Any]]:
+        python = await self._python_results([(target.name, args) for target, args in cases],
+                                            directory)
+        rust = await self._rust_results(cases, project_dir, rust_code, toml_content, directory)
+        return [
+            (target, args, python_result, rust_result)
+            for (target, args), python_result, rust_result in zip(cases, python, rust)
+            if not (isinstance(python_result, dict) and "unsupported" in python_result)
+            and not same_result(python_result, rust_result,
+                                F32_TOLERANCE if target.single_precision else FLOAT_TOLERANCE)
+        ]
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not fuzzing {self.source}: {e}")
+            return CheckResult(success=True)
+        targets = fuzz_targets(python_code, rust_code)
+        if not targets:
+            logger.info(f"No function of {self.source.name} takes inputs that can be fuzzed")
+            return CheckResult(success=True, info={"targets": []})
+
+        rng = random.Random(self.source.name)
+        cases = [
+            (target, [random_value(param, rng) for param in target.params])
+            for target in targets for _ in range(self.cases)
+        ]
+        project_dir = builder.prepare_project(rust_code, toml_content)
+        self._sandbox = builder.sandbox
+        self._limits = builder.limits.test
+        logger.info(f"Fuzzing {', '.join(target.name for target in targets)} "
+                    f"with {len(cases)} inputs")
+        with tempfile.TemporaryDirectory(prefix="python2rust_fuzz_") as temp:
+            directory = Path(temp)
+            try:
+                divergences = await self._divergences(cases, project_dir, rust_code,
+                                                      toml_content, directory)
+                if not divergences:
+                    return CheckResult(success=True, info={"targets": [t.name for t in targets],
+                                                           "cases": len(cases)})
+                divergence = min(
+                    (d for d in divergences if d[0] is divergences[0][0]),
+                    key=lambda d: sum(_size(value) for value in d[1]))
+                divergence = await self._shrink(divergence, project_dir, rust_code,
+                                                toml_content, directory)
+            except HarnessTimeout as e:
+                return CheckResult(success=False, output=str(e), info={"timed_out": True})
+            except (RuntimeError, ValueError, OSError, asyncio.TimeoutError) as e:
+                # A harness that cannot run says nothing about the translation
+                logger.warning(f"Fuzzing {self.source.name} was skipped: {e}")
+                return CheckResult(success=True, info={"skipped": str(e)})
+            finally:
+                shutil.rmtree(directory / "crate", ignore_errors=True)
+
+        target, args, python_result, rust_result = divergence
+        call = f"{target.name}({', '.join(repr(arg) for arg in args)})"
+        return CheckResult(
+            success=False,
+            output=(f"`{call}` diverges: Python returns {json.dumps(python_result)}, Rust returns "
+                    f"{json.dumps(rust_result)}. Make the Rust function behave like the "
+                    "Python one for this input"),
+            info={"function": target.name, "args": args, "python": python_result,
+                  "rust": rust_result, "divergences": len(divergences)}
+        )
+
+    async def _shrink(
+        self,
+        divergence: Tuple[FuzzTarget, List[Any], Any, Any],
+        project_dir: Path,
+        rust_code: str,
+        toml_content: str,
+        directory: Path
+    ) -> Tuple[FuzzTarget, List[Any], Any, Any]:
+        """The smallest diverging variant of an input found within the shrink rounds."""
+        def size(args: List[Any]) -> float:
+            return sum(_size(value) for value in args)
+
+        for _ in range(self.shrink_rounds):
+            target, args = divergence[0], divergence[1]
+            candidates = []
+            for index, (value, param) in enumerate(zip(args, target.params)):
+                candidates.extend(args[:index] + [smaller] + args[index + 1:]
+                                  for smaller in _shrunk(value, param))
+            candidates = sorted((c for c in candidates if size(c) < size(args)),
+                                key=size)[:SHRINK_CANDIDATES]
+            if not candidates:
+                break
+            smaller = await self._divergences(
+                [(target, candidate) for candidate in candidates], project_dir, rust_code,
+                toml_content, directory)
+            if not smaller:
+                break
+            divergence = min(smaller, key=lambda d: size(d[1]))
+        return divergence
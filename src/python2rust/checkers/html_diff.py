@@ -0,0 +1,121 @@
+import re
+from html.parser import HTMLParser
+from typing import Iterable, List, Optional, Tuple
+
+VOID_ELEMENTS = {
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta",
+    "source", "track", "wbr"
+}
+# Elements an opening sibling closes, such as <li>one<li>two
+IMPLICITLY_CLOSED = {"li", "p", "dt", "dd", "option", "tr", "td", "th"}
+# Elements whose whitespace is content
+PREFORMATTED = {"pre", "textarea"}
+SELECTOR_PATTERN = re.compile(r"^([a-zA-Z][\w-]*)?(?:#([\w-]+))?((?:\.[\w-]+)*)$")
+IGNORED = "<ignored>"
+
+
+def parse_selector(text: str) -> Optional[Tuple[Optional[str], Optional[str], List[str]]]:
+    """(tag, id, classes) of a simple selector: tag, #id, .class or a
+    combination like div.timing."""
+    match = SELECTOR_PATTERN.match(text.strip())
+    if not match or not text.strip():
+        return None
+    tag, element_id, classes = match.groups()
+    return (tag.lower() if tag else None), element_id, [c for c in classes.split(".") if c]
+
+
+class _Outline(HTMLParser):
+    """One line per element and text of a document, indented by nesting depth.
+
+    Whitespace runs are collapsed outside preformatted elements, attributes are
+    sorted, comments dropped, and elements matching an ignored selector keep their
+    tag but not their content.
+    """
+
+    def __init__(self, ignore: Iterable[str], selectors: Iterable[str]):
+        super().__init__(convert_charrefs=True)
+        self.ignore = [re.compile(pattern) for pattern in ignore]
+        self.selectors = []
+        for text in selectors:
+            selector = parse_selector(text)
+            if selector is None:
+                raise ValueError(f"Unsupported selector {text!r}: use tag, #id, "
+                                 ".class or tag#id.class")
+            self.selectors.append(selector)
+        self.lines: List[str] = []
+        self.stack: List[str] = []
+        self.ignored_depth: Optional[int] = None
+
+    def _mask(self, text: str) -> str:
+        for pattern in self.ignore:
+            text = pattern.sub(IGNORED, text)
+        return text
+
+    def _matches(self, tag: str, attributes: dict) -> bool:
+        classes = set((attributes.get("class") or "").split())
+        return any(
+            (selector_tag is None or selector_tag == tag)
+            and (element_id is None or attributes.get("id") == element_id)
+            and classes.issuperset(selector_classes)
+            for selector_tag, element_id, selector_classes in self.selectors
+        )
+
+    def _element(self, tag: str, attrs: List[Tuple[str, Optional[str]]], void: bool) -> None:
+        if tag in IMPLICITLY_CLOSED and self.stack and self.stack[-1] == tag:
+            self.handle_endtag(tag)
+        if self.ignored_depth is not None:
+            if not void:
+                self.stack.append(tag)
+            return
+        attributes = {name: value or "" for name, value in attrs}
+        if "class" in attributes:
+            attributes["class"] = " ".join(sorted(attributes["class"].split()))
+        text = "".join(f' {name}="{self._mask(value)}"'
+                       for name, value in sorted(attributes.items()))
+        ignored = self._matches(tag, attributes)
+        self.lines.append("  " * len(self.stack) + f"<{tag}{text}>"
+                          + (f" {IGNORED}" if ignored else ""))
+        if not void:
+            if ignored:
+                self.ignored_depth = len(self.stack)
+            self.stack.append(tag)
+
+    def handle_starttag(self, tag, attrs):
+        self._element(tag, attrs, tag in VOID_ELEMENTS)
+
+    def handle_startendtag(self, tag, attrs):
+        self._element(tag, attrs, True)
+
+    def handle_endtag(self, tag):
+        if tag not in self.stack:
+            return
+        # Closes the elements left open inside it, like an HTML parser would
+        while self.stack.pop() != tag:
+            pass
+        if self.ignored_depth is not None and len(self.stack) <= self.ignored_depth:
+            self.ignored_depth = None
+
+    def handle_data(self, data):
+        if self.ignored_depth is not None:
+            return
+        if not PREFORMATTED.intersection(self.stack):
+            data = " ".join(data.split())
+        if data:
+            self.lines.append("  " * len(self.stack) + self._mask(data))
+
+
+def is_html(content_type: Optional[str]) -> bool:
+    return bool(content_type) and "html" in content_type.lower()
+
+
+def html_outline(text: str, ignore: Iterable[str] = (),
+                 ignore_elements: Iterable[str] = ()) -> List[str]:
+    """The structure and text of an HTML document, as compared between responses.
+
+    Documents differing only in whitespace, attribute order, comments, the ignore
+    regexes or the content of the ignore_elements selectors have the same outline.
+    """
+    parser = _Outline(ignore, ignore_elements)
+    parser.feed(text)
+    parser.close()
+    return parser.lines
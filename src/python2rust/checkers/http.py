@@ -0,0 +1,410 @@
+import asyncio
+import difflib
+import json
+import os
+import re
+import signal
+import socket
+import sys
+import tempfile
+import time
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional, Tuple
+
+import aiohttp
+
+from ..analyzers.http_routes import find_web_app
+from ..builders import RustBuilder
+from ..builders.benchmark import rust_binary
+from ..builders.memory import (
+    VALGRIND_SLOWDOWN, MemoryProfiler, MemoryUsage, memory_comparison, read_allocations
+)
+from ..builders.sandbox import Sandbox
+from ..config.settings import HttpCase, StepLimits
+from ..utils.limits import preexec
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+from .html_diff import html_outline, is_html
+
+logger = setup_logger()
+
+HOST = "127.0.0.1"
+# Methods requested on discovered routes; others need bodies or have no response to compare
+REQUESTED_METHODS = ("GET", "POST", "PUT", "PATCH", "DELETE")
+MAX_DIFF_LINES = 40
+MAX_MISMATCHES = 10
+
+# Serves the app object of the module, imported under another name so that its main guard stays off
+PYTHON_SERVER = """
+import asyncio, importlib.util, os, sys
+sys.path.insert(0, os.path.dirname(os.path.abspath(sys.argv[1])))
+spec = importlib.util.spec_from_file_location("python2rust_served", sys.argv[1])
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+app, host, port = getattr(module, sys.argv[2]), sys.argv[3], int(os.environ["PORT"])
+if hasattr(app, "wsgi_app"):
+    app.run(host=host, port=port, use_reloader=False, debug=False)
+elif asyncio.iscoroutinefunction(getattr(app, "__call__", None)):
+    import uvicorn
+    uvicorn.run(app, host=host, port=port, log_level="warning")
+else:
+    from wsgiref.simple_server import make_server
+    make_server(host, port, app).serve_forever()
+"""
+
+
+@dataclass
+class HttpResponse:
+    """What a server answered to a request; status 0 when the request itself failed."""
+    status: int
+    headers: Dict[str, str] = field(default_factory=dict)
+    body: str = ""
+    error: Optional[str] = None
+
+
+def free_port() -> int:
+    """A port nothing listens on, picked by the system."""
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as sock:
+        sock.bind((HOST, 0))
+        return sock.getsockname()[1]
+
+
+def _header(value: Optional[str]) -> Optional[str]:
+    return value.lower().replace(" ", "") if value is not None else None
+
+
+def _body(text: str, ignore: Iterable[str]):
+    """A body as compared: JSON documents parsed, other text with the ignored parts replaced."""
+    for pattern in ignore:
+        text = re.sub(pattern, "<ignored>", text)
+    try:
+        return json.loads(text)
+    except ValueError:
+        return text
+
+
+def response_differences(
+    python: HttpResponse,
+    rust: HttpResponse,
+    headers: Iterable[str] = ("content-type",),
+    ignore: Iterable[str] = (),
+    ignore_elements: Iterable[str] = ()
+) -> List[str]:
+    """How the Rust response differs from the Python one: status, compared headers and body.
+
+    HTML bodies are compared by structure and text, see html_outline.
+    """
+    if rust.error is not None:
+        return [f"the Rust request failed: {rust.error}"]
+    differences = []
+    if python.status != rust.status:
+        differences.append(f"status {python.status} in Python, {rust.status} in Rust")
+    for name in headers:
+        expected, actual = python.headers.get(name.lower()), rust.headers.get(name.lower())
+        if _header(expected) != _header(actual):
+            differences.append(f"header {name}: {expected!r} in Python, {actual!r} in Rust")
+    if is_html(python.headers.get("content-type")) or is_html(rust.headers.get("content-type")):
+        expected = html_outline(python.body, ignore, ignore_elements)
+        actual = html_outline(rust.body, ignore, ignore_elements)
+        if expected != actual:
+            diff = list(difflib.unified_diff(expected, actual, "python", "rust", lineterm=""))
+            if len(diff) > MAX_DIFF_LINES:
+                diff = diff[:MAX_DIFF_LINES] + ["..."]
+            differences.append("HTML differs:\n" + "\n".join(diff))
+        return differences
+    expected, actual = _body(python.body, ignore), _body(rust.body, ignore)
+    if expected != actual:
+        if isinstance(expected, str) and isinstance(actual, str):
+            diff = list(difflib.unified_diff(
+                expected.splitlines(), actual.splitlines(), "python", "rust", lineterm=""))
+        else:
+            diff = ["--- python", json.dumps(expected, indent=2), "+++ rust",
+                    json.dumps(actual, indent=2)]
+        if len(diff) > MAX_DIFF_LINES:
+            diff = diff[:MAX_DIFF_LINES] + ["..."]
+        differences.append("body differs:\n" + "\n".join(diff))
+    return differences
+
+
+def write_report(project_dir: Path, report: List[Dict]) -> Path:
+    """Save the pass or fail of each request as debug/http_report.json of the crate."""
+    report_file = project_dir / "debug" / "http_report.json"
+    report_file.parent.mkdir(parents=True, exist_ok=True)
+    passed = sum(1 for entry in report if entry["passed"])
+    report_file.write_text(json.dumps(
+        {"passed": passed, "failed": len(report) - passed, "requests": report}, indent=2))
+    return report_file
+
+
+class HttpEquivalence(Checker):
+    """Starts the Python web app and the generated Rust server, and compares their answers.
+
+    The same requests go to both: each method of the app's routes without path
+    parameters, then the configured cases, such as the requests of HAR recordings. Statuses, the
+    compared headers and the bodies must agree, JSON bodies as documents and HTML ones by structure
+    and text. Both servers listen on free ports; the Rust one is told its port through the PORT
+    environment variable. With a memory profiler, the peak resident memory of both servers over the
+    requests is sampled, and once they agree their allocations are counted over the same requests; a
+    sandboxed Rust server is not measured.
+    """
+    name = "http"
+
+    def __init__(
+        self,
+        source: Path,
+        cases: Iterable[HttpCase] = (),
+        headers: Iterable[str] = ("content-type",),
+        ignore: Iterable[str] = (),
+        startup_timeout: float = 60,
+        ignore_elements: Iterable[str] = (),
+        request_timeout: Optional[float] = None,
+        python: str = sys.executable,
+        memory: Optional[MemoryProfiler] = None
+    ):
+        self.source = Path(source)
+        self.cases = list(cases)
+        self.headers = list(headers)
+        self.ignore = list(ignore)
+        self.ignore_elements = list(ignore_elements)
+        self.startup_timeout = startup_timeout
+        self.request_timeout = request_timeout
+        self.python = python
+        self.memory = memory
+
+    def fix_label(self) -> str:
+        return "http"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Responses still differ after {attempts} fix attempts: {result.output}"
+
+    def requests(self, python_code: str) -> Tuple[Optional[str], List[HttpCase]]:
+        """The app object of the module and the requests sent to both servers."""
+        app = find_web_app(python_code)
+        if app is None:
+            return None, []
+        requests = [
+            HttpCase(method=method, path=route.path)
+            for route in app.routes if not route.parameterized
+            for method in route.methods if method in REQUESTED_METHODS
+        ]
+        return app.name, requests + self.cases
+
+    async def _start(self, command: List[str], cwd: Path, port: int, log: Path,
+                     memory_mb: Optional[int] = None, env: Optional[Dict[str, str]] = None
+                     ) -> asyncio.subprocess.Process:
+        with open(log, "wb") as output:
+            return await asyncio.create_subprocess_exec(
+                *command,
+                cwd=cwd,
+                env={**os.environ, "PORT": str(port), "SERVER_PORT": str(port), **(env or {})},
+                stdout=output,
+                stderr=output,
+                preexec_fn=preexec(memory_mb, new_session=True)
+            )
+
+    async def _wait_until_ready(self, session: aiohttp.ClientSession, process, port: int) -> bool:
+        deadline = time.monotonic() + self.startup_timeout
+        while time.monotonic() < deadline:
+            if process.returncode is not None:
+                return False
+            try:
+                async with session.get(f"http://{HOST}:{port}/", timeout=1, allow_redirects=False):
+                    return True
+            except Exception:
+                pass
+            await asyncio.sleep(0.5)
+        return False
+
+    async def _stop(self, process) -> None:
+        if process.returncode is None:
+            try:
+                if os.name != 'nt':
+                    os.killpg(os.getpgid(process.pid), signal.SIGTERM)
+                else:
+                    process.terminate()
+            except ProcessLookupError:
+                pass
+        try:
+            await asyncio.wait_for(process.wait(), timeout=5)
+        except asyncio.TimeoutError:
+            process.kill()
+
+    async def _send(self, session: aiohttp.ClientSession, port: int, case: HttpCase,
+                    timeout: Optional[float]) -> HttpResponse:
+        try:
+            async with session.request(
+                case.method,
+                f"http://{HOST}:{port}{case.path}",
+                data=case.body.encode() if case.body is not None else None,
+                headers=case.headers,
+                allow_redirects=False,
+                timeout=aiohttp.ClientTimeout(total=timeout)
+            ) as response:
+                body = await response.read()
+                return HttpResponse(
+                    status=response.status,
+                    headers={name.lower(): value for name, value in response.headers.items()},
+                    body=body.decode(errors="replace")
+                )
+        except asyncio.TimeoutError:
+            return HttpResponse(status=0, error=(
+                f"no response within {timeout:g} seconds: the handler loops "
+                "forever or is far slower"))
+        except Exception as e:
+            return HttpResponse(status=0, error=f"{type(e).__name__}: {e}")
+
+    async def _responses(
+        self,
+        session: aiohttp.ClientSession,
+        command: List[str],
+        cwd: Path,
+        requests: List[HttpCase],
+        log: Path,
+        limits: StepLimits,
+        sandbox: Optional[Sandbox] = None,
+        usage: Optional[MemoryUsage] = None,
+        env: Optional[Dict[str, str]] = None
+    ) -> Optional[List[HttpResponse]]:
+        """Responses of the server started by command, None when it did not come up.
+
+        limits.timeout applies to each request, limits.memory_mb to the server. With usage, the
+        peak resident memory of the server is sampled into it by the memory profiler.
+        """
+        port = free_port()
+        memory_mb = limits.memory_mb
+        if sandbox is not None:
+            # Published ports only reach servers listening on every interface
+            command = sandbox.command(
+                command, cwd, env={"PORT": str(port), "SERVER_PORT": str(port), "HOST": "0.0.0.0"},
+                ports=[port], memory_mb=memory_mb)
+            memory_mb = None
+        process = await self._start(command, cwd, port, log, memory_mb, env)
+        sampler = self.memory.sample(process.pid, usage) if usage is not None else None
+        try:
+            if not await self._wait_until_ready(session, process, port):
+                return None
+            return [await self._send(session, port, case, limits.timeout) for case in requests]
+        finally:
+            if sampler is not None:
+                sampler.cancel()
+            await self._stop(process)
+
+    async def _allocations(
+        self,
+        command: List[str],
+        cwd: Path,
+        requests: List[HttpCase],
+        limits: StepLimits,
+        python: bool
+    ) -> Optional[int]:
+        """Heap allocations of a server under valgrind over the requests, None when
+        it did not come up."""
+        timeout = limits.timeout * VALGRIND_SLOWDOWN if limits.timeout else None
+        with tempfile.TemporaryDirectory(prefix="python2rust_http_") as temp:
+            log_file = Path(temp) / "valgrind.log"
+            async with aiohttp.ClientSession() as session:
+                responses = await self._responses(
+                    session, self.memory.counted(command, log_file), cwd, requests,
+                    Path(temp) / "server.log", StepLimits(timeout=timeout),
+                    env=self.memory.counted_env(python))
+            return read_allocations(log_file) if responses is not None else None
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not comparing the responses of {self.source}: {e}")
+            return CheckResult(success=True)
+        app, requests = self.requests(python_code)
+        if app is None or not requests:
+            logger.info(f"{self.source.name} defines no web app routes to compare")
+            return CheckResult(success=True, info={"requests": 0})
+
+        success, error, _ = await builder.build(rust_code, toml_content, release=False)
+        if not success:
+            return CheckResult(success=False, output=error)
+        project_dir = builder.output_dir
+        binary = rust_binary(project_dir, "debug").resolve()
+
+        # The Rust server gets the run limits of the builder, the Python one only its time limit
+        limits = builder.limits.run
+        if self.request_timeout is not None:
+            limits = StepLimits(timeout=self.request_timeout, memory_mb=limits.memory_mb)
+        logger.info(f"Comparing {len(requests)} responses of the Python and Rust "
+                    f"{self.source.name} servers")
+        server = [self.python, "-c", PYTHON_SERVER, str(self.source.resolve()), app, HOST]
+        measured = self.memory is not None
+        usage = (MemoryUsage(), MemoryUsage()) if measured else None
+        with tempfile.TemporaryDirectory(prefix="python2rust_http_") as temp:
+            logs = Path(temp)
+            async with aiohttp.ClientSession() as session:
+                python = await self._responses(
+                    session, server, self.source.parent, requests, logs / "python.log",
+                    StepLimits(timeout=limits.timeout), usage=usage[0] if measured else None)
+                if python is None:
+                    # A Python app that cannot run says nothing about the translation
+                    output = (logs / "python.log").read_text(errors="replace").strip()[-500:]
+                    logger.warning("Comparing responses was skipped: the Python app "
+                                   f"did not start: {output}")
+                    return CheckResult(success=True,
+                                       info={"skipped": "the Python app did not start"})
+                rust = await self._responses(
+                    session, [str(binary)], project_dir, requests, logs / "rust.log",
+                    limits, builder.sandbox,
+                    usage=usage[1] if measured and builder.sandbox is None else None)
+                if rust is None:
+                    output = (logs / "rust.log").read_text(errors="replace").strip()[-2000:]
+                    return CheckResult(
+                        success=False,
+                        output=(f"The Rust server did not answer within {self.startup_timeout:.0f} "
+                                "seconds. Listen on the port given by the PORT environment "
+                                "variable, and on the host given by HOST when it is set, "
+                                f"defaulting to the original ones. Server output:\n{output}")
+                    )
+
+        mismatches = []
+        report = []
+        for case, expected, actual in zip(requests, python, rust):
+            differences = response_differences(
+                expected, actual, self.headers, self.ignore, self.ignore_elements)
+            request = f"{case.method} {case.path}"
+            report.append({
+                "request": request,
+                "passed": not differences,
+                "python_status": expected.status,
+                "rust_status": actual.status,
+                "differences": differences
+            })
+            if differences:
+                body = f" with body {case.body!r}" if case.body else ""
+                mismatches.append(f"{request}{body}: " + "\n".join(differences))
+        report_file = write_report(project_dir, report)
+        logger.info(f"{len(requests) - len(mismatches)}/{len(requests)} responses match; "
+                    f"per-request report in {report_file}")
+        info = {"requests": len(requests), "mismatches": len(mismatches),
+                "report": str(report_file)}
+        if not mismatches:
+            metrics = {}
+            if usage is not None:
+                if self.memory.counts_allocations:
+                    # Only the final code is counted, valgrind being slow
+                    usage[0].allocations = await self._allocations(
+                        server, self.source.parent, requests, limits, python=True)
+                    if builder.sandbox is None:
+                        usage[1].allocations = await self._allocations(
+                            [str(binary)], project_dir, requests, limits, python=False)
+                memory = memory_comparison(f"{len(requests)} requests", *usage)
+                logger.info(f"Peak {memory['python']['peak_rss_mb']} MB in Python, "
+                            f"{memory['rust']['peak_rss_mb']} MB in Rust over the requests")
+                metrics = {"memory": [memory]}
+            return CheckResult(success=True, info=info, metrics=metrics)
+        shown = mismatches[:MAX_MISMATCHES]
+        if len(mismatches) > len(shown):
+            shown.append(f"... and {len(mismatches) - len(shown)} more")
+        return CheckResult(
+            success=False,
+            output=("The Rust server answers differently from the Python app. Make its responses "
+                    "match:\n\n" + "\n\n".join(shown)),
+            info=info
+        )
@@ -0,0 +1,60 @@
+from typing import Any, List
+
+from ..analyzers.fuzz_targets import FuzzTarget, FuzzType
+from ..analyzers.numeric import NumericRisk, numeric_risks
+from .boundary import MAX_BOUNDARY_CASES, BoundaryCheck, combined_cases
+
+# Small values of both signs, for flooring division and modulo
+SIGNED_VALUES = (0, 1, -1, 2, -2, 7, -7)
+# Halves for rounding, and values past the 64-bit integers for float to int conversions
+FLOAT_VALUES = (0.0, 0.5, -0.5, 1.5, -2.5, 7.9, -7.9, 1e19, -1e19, 1e300)
+
+
+def boundary_values(kind: FuzzType) -> List[Any]:
+    """Inputs of a type at the edges of its semantics: the integer bounds and their neighbours,
+    small values of both signs, halves and huge floats."""
+    if kind.kind == "int":
+        values = [kind.low, kind.low + 1, kind.high - 1, kind.high, *SIGNED_VALUES]
+        return sorted({value for value in values if kind.low <= value <= kind.high}, key=abs)
+    if kind.kind == "float":
+        return list(FLOAT_VALUES)
+    if kind.kind == "bool":
+        return [False, True]
+    if kind.kind == "str":
+        return [""]
+    if kind.item.kind in ("int", "float"):
+        # Sums and products of the items overflow before any item does
+        edges = boundary_values(kind.item)
+        return [[], [max(edges), max(edges)], [min(edges), min(edges)]]
+    return [[]]
+
+
+def boundary_cases(target: FuzzTarget, limit: int = MAX_BOUNDARY_CASES) -> List[List[Any]]:
+    """Combinations of the boundary values of each parameter, sampled the same way on each run."""
+    return combined_cases(target, boundary_values, limit)
+
+
+class NumericBoundaryCheck(BoundaryCheck):
+    """Integer bounds, signed divisions and float conversions given to both languages.
+
+    The sites are the arithmetic where Python's unbounded ints, flooring division and
+    float conversions may behave unlike the Rust translation.
+    """
+    name = "numeric"
+    subject = "numeric"
+    inputs = "at a numeric boundary"
+
+    def fix_label(self) -> str:
+        return "numeric semantics"
+
+    def risks(self, python_code: str, rust_code: str) -> List[NumericRisk]:
+        return numeric_risks(python_code, rust_code)
+
+    def values(self, kind: FuzzType) -> List[Any]:
+        return boundary_values(kind)
+
+    def advice(self) -> str:
+        return ("Reproduce Python's semantics: widen the integer types or use checked arithmetic "
+                "and return an error where the value cannot be represented, floor divisions with "
+                "div_euclid/rem_euclid adjusted for negative divisors, and convert floats with "
+                "explicit range checks.")
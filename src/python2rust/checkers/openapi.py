@@ -0,0 +1,182 @@
+import asyncio
+import json
+import sys
+import tempfile
+from pathlib import Path
+from typing import Any, Dict, Iterable, Optional, Tuple
+
+import aiohttp
+
+from ..analyzers.http_routes import find_web_app
+from ..analyzers.openapi import openapi_differences, operations
+from ..builders import RustBuilder
+from ..builders.benchmark import rust_binary
+from ..config.settings import HttpCase
+from ..utils.logging import setup_logger
+from .base import CheckResult
+from .http import HttpEquivalence
+
+logger = setup_logger()
+
+MAX_DIFFERENCES = 30
+
+# Prints the OpenAPI document of the app object of the module: FastAPI's own, or for Flask the
+# paths, methods and path parameters of its URL map, which documents no bodies or responses
+PYTHON_OPENAPI = r"""
+import importlib.util, json, os, re, sys
+sys.path.insert(0, os.path.dirname(os.path.abspath(sys.argv[1])))
+spec = importlib.util.spec_from_file_location("python2rust_documented", sys.argv[1])
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+app = getattr(module, sys.argv[2])
+if callable(getattr(app, "openapi", None)):
+    document = app.openapi()
+elif hasattr(app, "url_map"):
+    types = {"IntegerConverter": "integer", "FloatConverter": "number"}
+    paths = {}
+    for rule in app.url_map.iter_rules():
+        if rule.endpoint == "static":
+            continue
+        path = re.sub(r"<(?:[^:<>]+:)?(\w+)>", r"{\1}", rule.rule)
+        parameters = [
+            {"name": name, "in": "path", "required": True,
+             "schema": {"type": types.get(type(converter).__name__, "string")}}
+            for name, converter in rule._converters.items()
+        ]
+        for method in sorted(rule.methods - {"HEAD", "OPTIONS"}):
+            paths.setdefault(path, {})[method.lower()] = {"parameters": parameters}
+    document = {"openapi": "3.0.3", "paths": paths}
+else:
+    sys.exit(f"{sys.argv[2]} is neither a FastAPI nor a Flask app")
+print(json.dumps(document))
+"""
+
+
+def write_documents(project_dir: Path, python: Dict[str, Any], rust: Dict[str, Any]) -> Path:
+    """Save both documents as debug/openapi_python.json and debug/openapi_rust.json of the crate."""
+    debug_dir = project_dir / "debug"
+    debug_dir.mkdir(parents=True, exist_ok=True)
+    (debug_dir / "openapi_python.json").write_text(json.dumps(python, indent=2))
+    (debug_dir / "openapi_rust.json").write_text(json.dumps(rust, indent=2))
+    return debug_dir
+
+
+class OpenApiEquivalence(HttpEquivalence):
+    """Compares the OpenAPI description of the Python web app with the one the Rust service serves.
+
+    The Python one is FastAPI's, or for Flask apps one introspected from their routes, of
+    paths and path parameters only. The Rust server, started like for the response comparison,
+    must serve its document at path, which the generation prompt asks to build with utoipa.
+    Operations are compared by method and path: their parameters, request bodies, and the
+    statuses and schemas of their responses, where the Python document has them.
+    """
+    name = "openapi"
+
+    def __init__(
+        self,
+        source: Path,
+        path: str = "/openapi.json",
+        ignore_responses: Iterable[str] = ("422",),
+        startup_timeout: float = 60,
+        python: str = sys.executable
+    ):
+        super().__init__(source, startup_timeout=startup_timeout, python=python)
+        self.path = path
+        self.ignore_responses = [str(status) for status in ignore_responses]
+
+    def fix_label(self) -> str:
+        return "openapi"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"API descriptions still differ after {attempts} fix attempts: {result.output}"
+
+    async def _python_document(
+        self,
+        app: str,
+        timeout: Optional[float]
+    ) -> Tuple[Optional[Dict[str, Any]], str]:
+        """The document of the Python app, or None with the reason it could not be made."""
+        process = await asyncio.create_subprocess_exec(
+            self.python, "-c", PYTHON_OPENAPI, str(self.source.resolve()), app,
+            cwd=self.source.parent,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        try:
+            stdout, stderr = await asyncio.wait_for(process.communicate(), timeout=timeout)
+        except asyncio.TimeoutError:
+            process.kill()
+            await process.wait()
+            return None, f"timed out after {timeout:g} seconds"
+        if process.returncode != 0:
+            return None, stderr.decode(errors="replace").strip()[-500:]
+        try:
+            return json.loads(stdout.decode(errors="replace").strip().splitlines()[-1]), ""
+        except (json.JSONDecodeError, IndexError) as e:
+            return None, f"no OpenAPI document: {e}"
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not comparing the API of {self.source}: {e}")
+            return CheckResult(success=True)
+        app = find_web_app(python_code)
+        if app is None:
+            return CheckResult(success=True, info={"operations": 0})
+        limits = builder.limits.run
+        python, reason = await self._python_document(app.name, limits.timeout)
+        if python is None:
+            # An app that cannot be documented says nothing about the translation
+            logger.warning(f"Comparing the API of {self.source.name} was skipped: {reason}")
+            return CheckResult(success=True, info={"skipped": reason})
+
+        success, error, _ = await builder.build(rust_code, toml_content, release=False)
+        if not success:
+            return CheckResult(success=False, output=error)
+        project_dir = builder.output_dir
+        binary = rust_binary(project_dir, "debug").resolve()
+        with tempfile.TemporaryDirectory(prefix="python2rust_openapi_") as temp:
+            log = Path(temp) / "rust.log"
+            async with aiohttp.ClientSession() as session:
+                responses = await self._responses(
+                    session, [str(binary)], project_dir,
+                    [HttpCase(method="GET", path=self.path)], log, limits, builder.sandbox)
+            output = log.read_text(errors="replace").strip()[-2000:]
+        if responses is None:
+            return CheckResult(success=False, output=(
+                f"The Rust server did not answer within {self.startup_timeout:.0f} seconds. Listen "
+                f"on the port given by the PORT environment variable. Server output:\n{output}"))
+        (response,) = responses
+        try:
+            rust = json.loads(response.body) if response.status == 200 else None
+        except json.JSONDecodeError:
+            rust = None
+        if not isinstance(rust, dict):
+            return CheckResult(success=False, output=(
+                f"GET {self.path} answered {response.status or response.error} rather than the "
+                "OpenAPI document of the service. Describe each handler with "
+                "`#[utoipa::path(...)]` (method, path, params, request_body, responses with their "
+                "status and body), derive `utoipa::ToSchema` for the bodies and "
+                "`utoipa::IntoParams` for query parameters, and serve the "
+                f"`#[derive(utoipa::OpenApi)]` document listing them as JSON at GET {self.path}."))
+
+        report_dir = write_documents(project_dir, python, rust)
+        differences = openapi_differences(python, rust, self.ignore_responses)
+        info = {"operations": len(operations(python)), "differences": len(differences),
+                "documents": str(report_dir)}
+        if not differences:
+            logger.info("The OpenAPI descriptions of the Python and Rust "
+                        f"{self.source.name} services match")
+            return CheckResult(success=True, info=info)
+        shown = differences[:MAX_DIFFERENCES]
+        if len(differences) > len(shown):
+            shown.append(f"... and {len(differences) - len(shown)} more")
+        return CheckResult(
+            success=False,
+            output=(f"The OpenAPI document the Rust service serves at {self.path} differs from the "
+                    "Python app's. Make its routes, parameters and bodies, and their utoipa "
+                    "annotations, match:\n"
+                    + "\n".join(f"- {difference}" for difference in shown)),
+            info=info
+        )
@@ -0,0 +1,79 @@
+from typing import Any, Dict, List
+
+from ..analyzers.rust_policy import (
+    ERROR_STYLES, PolicyViolation, analyze_rust, build_analyzer,
+    error_style_violations, policy_violations, scanned_facts
+)
+from ..builders import RustBuilder
+from ..config.settings import CodePolicies
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+# How the fix prompt is asked to resolve the violations of each policy
+POLICY_FIXES = {
+    "no_unwrap": "handle the None or Err case, or propagate it with `?`",
+    "no_expect": "handle the None or Err case, or propagate it with `?`",
+    "no_unsafe": "use safe Rust, the standard library usually has a safe equivalent",
+    "no_panic": "return an error where Python raises one, and implement what todo! and "
+                "unimplemented! stand for",
+    "forbidden_macros": "remove the call or use something else",
+    "max_function_lines": "split the function into smaller helper functions",
+}
+
+
+class PolicyCheck(Checker):
+    """The code follows the project's policies outside tests: no unwrap, expect, unsafe or panics,
+    no forbidden macros, no function longer than the maximum and errors of the error style, as
+    enabled.
+
+    The code is analyzed with the syn-based policy analyzer; when cargo cannot build it, the
+    line-based scans stand in with a warning.
+    """
+    name = "policy"
+
+    def __init__(self, policies: CodePolicies):
+        self.policies = policies
+
+    async def _facts(self, builder: RustBuilder, rust_code: str) -> Dict[str, Any]:
+        try:
+            binary = await build_analyzer(timeout=builder.limits.build.timeout)
+            return await analyze_rust(rust_code, binary)
+        except (OSError, RuntimeError, ValueError) as e:
+            logger.warning(f"Checking the code policies with the line-based scans: {e}")
+            return scanned_facts(rust_code)
+
+    def _describe(self, violations: List[PolicyViolation], rust_code: str) -> str:
+        lines = rust_code.splitlines()
+        described = []
+        for violation in violations:
+            code = lines[violation.line - 1].strip() if 0 < violation.line <= len(lines) else ""
+            described.append(f"- {violation}" + (f": `{code}`" if code else ""))
+        return "\n".join(described)
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        violations = policy_violations(await self._facts(builder, rust_code), self.policies)
+        if self.policies.error_style is not None:
+            violations = sorted([*violations, *error_style_violations(
+                rust_code, toml_content, self.policies.error_style)],
+                                key=lambda violation: violation.line)
+        info = {"policy_violations": [str(violation) for violation in violations]}
+        if not violations:
+            return CheckResult(success=True, info=info)
+        info["policies"] = sorted({violation.policy for violation in violations})
+        return CheckResult(success=False, output=self._describe(violations, rust_code), info=info)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        how = {**POLICY_FIXES, "error_style": ERROR_STYLES.get(self.policies.error_style or "", "")}
+        fixes = "\n".join(f"- {policy}: {how[policy]}"
+                          for policy in result.info.get("policies", []))
+        return {"critical_differences": {"policy": [
+            "The code breaks these policies of the project, which apply outside tests. Fix every "
+            f"violation without changing the behavior:\n{result.output}\n\n"
+            f"How to fix them:\n{fixes}"
+        ]}}
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return (f"Code still breaks the project's policies after {attempts} fix attempts:\n"
+                f"{result.output}")
@@ -0,0 +1,28 @@
+from typing import Any, Dict
+
+from ..builders import RustBuilder
+from ..utils.rustfmt import Rustfmt
+from .base import Checker, CheckResult
+
+
+class RustfmtCheck(Checker):
+    """rustfmt: the code parses, and is replaced by its formatted version."""
+    name = "rustfmt"
+
+    def __init__(self, formatter: Rustfmt):
+        self.formatter = formatter
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        formatted, error = await self.formatter.format(rust_code, toml_content)
+        if error is not None:
+            return CheckResult(success=False, output=error)
+        return CheckResult(success=True, rust_code=formatted)
+
+    def fix_request(self, result: CheckResult, attempt: int, max_attempts: int) -> Dict[str, Any]:
+        return {"critical_differences": {"syntax": [f"The code does not parse:\n{result.output}"]}}
+
+    def fix_label(self) -> str:
+        return "syntax"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Generated code does not parse after {attempts} fix attempts: {result.output}"
@@ -0,0 +1,265 @@
+import asyncio
+import os
+import sys
+import tempfile
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional
+
+from ..analyzers.sql_schema import migration_files, orm_framework, rust_schema_statements
+from ..builders import RustBuilder
+from ..builders.benchmark import rust_binary
+from ..builders.database import Database, DatabaseError, Schema, TableSchema, ephemeral_databases
+from ..utils.limits import preexec
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+MAX_DIFFERENCES = 30
+# Tables migration tools keep their history in
+MIGRATION_TABLES = (
+    "_sqlx_migrations", "__diesel_schema_migrations", "refinery_schema_history", "seaql_migrations",
+    "alembic_version", "django_migrations"
+)
+
+# Creates the tables of the SQLAlchemy metadata of a module: python script.py module.py url
+SQLALCHEMY_SCRIPT = '''import importlib.util
+import sys
+from pathlib import Path
+
+from sqlalchemy import MetaData, create_engine
+
+sys.path.insert(0, str(Path(sys.argv[1]).parent))
+spec = importlib.util.spec_from_file_location("python2rust_models", sys.argv[1])
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+
+metadatas = {}
+for value in list(vars(module).values()):
+    metadata = value if isinstance(value, MetaData) else getattr(value, "metadata", None)
+    if isinstance(metadata, MetaData):
+        metadatas[id(metadata)] = metadata
+engine = create_engine(sys.argv[2])
+for metadata in metadatas.values():
+    metadata.create_all(engine)
+'''
+
+
+def _compared(name: str, python: object, rust: object) -> str:
+    def shown(value: object) -> str:
+        return "none" if value is None else str(value)
+
+    return f"{name} {shown(python)} in Python, {shown(rust)} in Rust"
+
+
+def _table_differences(name: str, python: TableSchema, rust: TableSchema) -> List[str]:
+    differences = []
+    nullable = {True: "nullable", False: "NOT NULL"}
+    for column, expected in python.columns.items():
+        actual = rust.columns.get(column)
+        if actual is None:
+            differences.append(f"{name}.{column}: column is missing")
+            continue
+        if expected["type"] != actual["type"]:
+            differences.append(f"{name}.{column}: "
+                               + _compared("type", expected["type"], actual["type"]))
+        if expected["nullable"] != actual["nullable"]:
+            differences.append(f"{name}.{column}: {nullable[expected['nullable']]} in Python, "
+                               f"{nullable[actual['nullable']]} in Rust")
+        if expected["default"] != actual["default"]:
+            differences.append(
+                f"{name}.{column}: " + _compared("default", expected["default"], actual["default"]))
+    differences.extend(
+        f"{name}.{column}: column is not in the Python schema" for column in rust.columns
+        if column not in python.columns)
+    for kind, expected, actual in (("constraint", python.constraints, rust.constraints),
+                                   ("index", python.indexes, rust.indexes)):
+        differences.extend(f"{name}: {kind} {item} is missing"
+                           for item in sorted(expected - actual))
+        differences.extend(
+            f"{name}: {kind} {item} is not in the Python schema"
+            for item in sorted(actual - expected))
+    return differences
+
+
+def schema_differences(python: Schema, rust: Schema) -> List[str]:
+    """How the Rust schema differs from the Python one: tables, columns, constraints and indexes."""
+    differences = []
+    for name, table in python.items():
+        if name not in rust:
+            differences.append(f"table {name} is missing")
+        else:
+            differences.extend(_table_differences(name, table, rust[name]))
+    differences.extend(f"table {name} is not in the Python schema" for name in rust
+                       if name not in python)
+    return differences
+
+
+class SchemaEquivalence(Checker):
+    """Creates the database schema of the Python models and of the Rust code in throwaway
+    databases, SQLite files or a Postgres container, and compares their tables, columns,
+    constraints and indexes.
+
+    The Python schema comes from python_command, a shell command given the database in
+    $DATABASE_URL, or else from the create_all of the SQLAlchemy metadata of the module.
+    The Rust one comes from rust_command, where {binary} is the debug binary, or else from
+    the crate's migrations/ SQL files, or else from the statements of the code creating
+    tables and indexes. Modules without models and without python_command are skipped;
+    with the sandbox enabled, rust_command is not run and the SQL is applied instead.
+    """
+    name = "schema"
+
+    def __init__(
+        self,
+        source: Path,
+        engine: str = "sqlite",
+        python_command: Optional[str] = None,
+        rust_command: Optional[str] = None,
+        ignore_tables: Iterable[str] = MIGRATION_TABLES,
+        postgres_image: str = "postgres:16",
+        docker: str = "docker",
+        python: str = sys.executable
+    ):
+        self.source = Path(source)
+        self.engine = engine
+        self.python_command = python_command
+        self.rust_command = rust_command
+        self.ignore_tables = set(ignore_tables)
+        self.postgres_image = postgres_image
+        self.docker = docker
+        self.python = python
+
+    def fix_label(self) -> str:
+        return "schema"
+
+    def failure(self, result: CheckResult, attempts: int) -> str:
+        return f"Schemas still differ after {attempts} fix attempts: {result.output}"
+
+    @property
+    def _dialect(self) -> str:
+        return {"sqlite": "SQLite", "postgres": "Postgres"}[self.engine]
+
+    async def _shell(self, command: str, cwd: Path, url: str, timeout: Optional[float],
+                     memory_mb: Optional[int] = None) -> Optional[str]:
+        """Run a command creating a schema in url; the reason it failed, None when it succeeded."""
+        process = await asyncio.create_subprocess_shell(
+            command,
+            cwd=cwd,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.STDOUT,
+            env={**os.environ, "DATABASE_URL": url},
+            preexec_fn=preexec(memory_mb)
+        )
+        try:
+            output, _ = await asyncio.wait_for(process.communicate(), timeout=timeout)
+        except asyncio.TimeoutError:
+            process.kill()
+            await process.wait()
+            return f"`{command}` timed out after {timeout:g} seconds"
+        if process.returncode != 0:
+            return (f"`{command}` exited with {process.returncode}:\n"
+                    f"{output.decode(errors='replace')[-2000:]}")
+        return None
+
+    async def _python_schema(
+        self,
+        database: Database,
+        directory: Path,
+        timeout: Optional[float]
+    ) -> Optional[str]:
+        if self.python_command is not None:
+            return await self._shell(self.python_command, self.source.parent, database.url, timeout)
+        script = directory / "create_all.py"
+        script.write_text(SQLALCHEMY_SCRIPT)
+        command = f'"{self.python}" "{script}" "{self.source.resolve()}" "{database.url}"'
+        return await self._shell(command, self.source.parent, database.url, timeout)
+
+    def _rust_sql(self, output_dir: Path, rust_code: str) -> str:
+        files = migration_files(output_dir)
+        if files:
+            return "\n".join(path.read_text() for path in files)
+        return "\n".join(statement.rstrip(";") + ";"
+                         for statement in rust_schema_statements(rust_code))
+
+    async def _rust_schema(self, builder: RustBuilder, rust_code: str, toml_content: str,
+                           database: Database) -> Optional[str]:
+        """Create the Rust schema; the reason it failed, in a fix request, None
+        when it succeeded."""
+        limits = builder.limits.run
+        if self.rust_command is not None and builder.sandbox is None:
+            command = self.rust_command
+            if "{binary}" in command:
+                success, error, _ = await builder.build(rust_code, toml_content, release=False)
+                if not success:
+                    return error
+                command = command.replace("{binary}",
+                                          str(rust_binary(builder.output_dir, "debug").resolve()))
+            return await self._shell(command, builder.output_dir, database.url,
+                                     limits.timeout, limits.memory_mb)
+        sql = self._rust_sql(builder.output_dir, rust_code)
+        if not sql:
+            return ("The Rust code creates no tables. Create the schema of the Python models with "
+                    f"{self._dialect} CREATE TABLE and CREATE INDEX statements in string literals, "
+                    "run when the program starts.")
+        try:
+            await database.execute(sql)
+        except DatabaseError as e:
+            return f"The Rust schema statements fail in {self._dialect}: {e}"
+        return None
+
+    def _kept(self, schema: Schema) -> Schema:
+        return {name: table for name, table in schema.items() if name not in self.ignore_tables}
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        try:
+            python_code = self.source.read_text()
+        except OSError as e:
+            logger.warning(f"Not comparing the schema of {self.source}: {e}")
+            return CheckResult(success=True)
+        framework = orm_framework(python_code)
+        if self.python_command is None and framework != "sqlalchemy":
+            if framework == "django":
+                logger.warning(f"Not comparing the schema of {self.source.name}: Django models "
+                               "need a python_command running their migrations, e.g. "
+                               "`python manage.py migrate`")
+            return CheckResult(success=True, info={"tables": 0})
+
+        timeout = builder.limits.run.timeout
+        error = None
+        with tempfile.TemporaryDirectory(prefix="python2rust_schema_") as temp:
+            try:
+                async with ephemeral_databases(
+                        self.engine, Path(temp), self.postgres_image,
+                        self.docker) as (python_db, rust_db):
+                    failed = await self._python_schema(python_db, Path(temp), timeout)
+                    if failed is not None:
+                        logger.warning(f"Not comparing the schema of {self.source.name}: {failed}")
+                        return CheckResult(success=True, info={"skipped": failed})
+                    python = self._kept(await python_db.schema())
+                    if not python:
+                        return CheckResult(success=True, info={"tables": 0})
+                    logger.info(f"Comparing the {self._dialect} schema of "
+                                f"{', '.join(sorted(python))}")
+                    error = await self._rust_schema(builder, rust_code, toml_content, rust_db)
+                    rust = self._kept(await rust_db.schema()) if error is None else {}
+            except (RuntimeError, OSError, DatabaseError, asyncio.TimeoutError) as e:
+                logger.warning(f"Schema comparison of {self.source.name} was skipped: {e}")
+                return CheckResult(success=True, info={"skipped": str(e) or "timed out"})
+
+        info: Dict[str, Any] = {"engine": self.engine, "tables": len(python)}
+        if error is not None:
+            return CheckResult(success=False, output=error, info=info)
+        differences = schema_differences(python, rust)
+        info["differences"] = len(differences)
+        if not differences:
+            return CheckResult(success=True, info=info)
+        shown = differences[:MAX_DIFFERENCES]
+        if len(differences) > len(shown):
+            shown.append(f"... and {len(differences) - len(shown)} more")
+        return CheckResult(
+            success=False,
+            output=(f"The {self._dialect} schema the Rust code creates differs from the one of the "
+                    "Python models. Make its tables, columns, constraints and indexes match:\n"
+                    + "\n".join(f"- {difference}" for difference in shown)),
+            info=info
+        )
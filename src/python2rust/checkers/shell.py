@@ -0,0 +1,65 @@
+import asyncio
+from pathlib import Path
+from typing import Iterable
+
+from ..builders import RustBuilder
+from ..utils.logging import setup_logger
+from .base import Checker, CheckResult
+
+logger = setup_logger()
+
+# Longest command output given to the fix prompt, its end is kept
+MAX_OUTPUT_CHARS = 20000
+
+
+class ShellChecker(Checker):
+    """A shell command run in the generated crate, passing when it exits with an expected code.
+
+    Its stdout, followed by its stderr, is what the fix prompt sees on failure.
+    """
+
+    def __init__(self, name: str, command: str, exit_codes: Iterable[int] = (0,),
+                 timeout: float = 300):
+        self.name = name
+        self.command = command
+        self.exit_codes = set(exit_codes)
+        self.timeout = timeout
+
+    async def _execute(self, project_dir: Path) -> CheckResult:
+        process = await asyncio.create_subprocess_shell(
+            self.command,
+            cwd=project_dir,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        try:
+            stdout, stderr = await asyncio.wait_for(process.communicate(), timeout=self.timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError) as e:
+            process.kill()
+            await process.wait()
+            if isinstance(e, asyncio.CancelledError):
+                raise
+            return CheckResult(success=False,
+                               output=f"`{self.command}` timed out after "
+                                      f"{self.timeout:.0f} seconds")
+
+        output = "\n".join(part.decode(errors="replace").strip()
+                           for part in (stdout, stderr) if part.strip())
+        info = {"exit_code": process.returncode, "output_dir": str(project_dir)}
+        if process.returncode in self.exit_codes:
+            return CheckResult(success=True, output=output, info=info)
+        if len(output) > MAX_OUTPUT_CHARS:
+            output = output[-MAX_OUTPUT_CHARS:]
+        return CheckResult(
+            success=False,
+            output=f"`{self.command}` exited with code {process.returncode}:\n{output}",
+            info=info
+        )
+
+    async def run(self, builder: RustBuilder, rust_code: str, toml_content: str) -> CheckResult:
+        project_dir = builder.prepare_project(rust_code, toml_content)
+        logger.info(f"Running the {self.name} checker: {self.command}")
+        try:
+            return await self._execute(project_dir)
+        except OSError as e:
+            return CheckResult(success=False, output=f"`{self.command}` could not run: {e}")
@@ -0,0 +1,51 @@
+from typing import Any, List
+
+from ..analyzers.fuzz_targets import FuzzType
+from ..analyzers.strings import StringRisk, string_risks
+from .boundary import BoundaryCheck
+
+# Text of one to four UTF-8 bytes per character, a combining accent and an empty string
+MULTIBYTE_TEXT = ("", "é", "naïve", "日本語", "🎉 ok", "e\u0301", "abc")
+# Offsets landing at the start, inside and past the end of multi-byte characters
+OFFSETS = (0, 1, 2, 3, -1, -2)
+
+
+def multibyte_values(kind: FuzzType) -> List[Any]:
+    """Inputs of a type for string operations: multi-byte text and the offsets into it."""
+    if kind.kind == "str":
+        return list(MULTIBYTE_TEXT)
+    if kind.kind == "int":
+        return [offset for offset in OFFSETS if kind.low <= offset <= kind.high]
+    if kind.kind == "float":
+        return [0.0, 1.5]
+    if kind.kind == "bool":
+        return [False, True]
+    if kind.item.kind == "str":
+        return [[], ["日本", "é"]]
+    return [[], multibyte_values(kind.item)[:2]]
+
+
+class UnicodeCheck(BoundaryCheck):
+    """Multi-byte text given to both languages where strings are measured, indexed or sliced.
+
+    The sites are the lengths, indexes and slices of strings, which Python counts in
+    code points and Rust in UTF-8 bytes.
+    """
+    name = "unicode"
+    subject = "string"
+    inputs = "on multi-byte text"
+
+    def fix_label(self) -> str:
+        return "string semantics"
+
+    def risks(self, python_code: str, rust_code: str) -> List[StringRisk]:
+        return string_risks(python_code, rust_code)
+
+    def values(self, kind: FuzzType) -> List[Any]:
+        return multibyte_values(kind)
+
+    def advice(self) -> str:
+        return ("Count, index and slice strings in chars as Python does: `chars().count()` for "
+                "len(), `chars().nth(i)` for s[i] with negative indexes from the end, and "
+                "`chars().skip(a).take(b - a)` for s[a:b], clamping "
+                "out-of-range bounds like Python.")
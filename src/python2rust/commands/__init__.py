@@ -0,0 +1,13 @@
+# commands/__init__.py
+from . import (
+    bench, blame, cache, check, classes, coverage, diff, doctor, init, migrate,
+    plan, prompts, resume, review, sarif, status, tui, watch
+)
+
+# Subcommands in the order they appear in --help
+COMMANDS = [
+    doctor, init, plan, classes, migrate, watch, status, review, resume, check, diff, blame, sarif,
+    bench, coverage, prompts, cache, tui
+]
+
+__all__ = ['COMMANDS']
@@ -0,0 +1,117 @@
+"""
+The bench subcommand: compare the speed and memory of the Python and generated Rust servers.
+"""
+import argparse
+import asyncio
+import json
+import sys
+from pathlib import Path
+
+from .common import fail, json_output, print_json
+from ..builders import BenchWorkload, RustBuilder, ServerBenchmark, format_comparison
+from ..builders.benchmark import rust_binary
+from ..builders.sandbox import Sandbox
+from ..config.project_config import ProjectConfig, find_project_config
+from ..config.settings import Settings
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "bench",
+        help="Run the Python and generated Rust servers under the same workload",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "--python-file",
+        type=Path,
+        required=True,
+        help="Python server the Rust code was generated from"
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated"),
+        help="Generated Rust crate"
+    )
+    parser.add_argument("--method", default="POST", help="HTTP method of the workload requests")
+    parser.add_argument("--path", default="/", help="Path requested by the workload")
+    parser.add_argument("--requests", type=int, default=20, help="Measured requests per server")
+    parser.add_argument("--concurrency", type=int, default=1, help="Requests in flight at once")
+    parser.add_argument("--warmup", type=int, default=1, help="Unmeasured requests sent first")
+    parser.set_defaults(handler=run)
+
+
+def bench_settings(output_dir: Path) -> Settings:
+    """The settings the benchmark runs with: the sandbox and limits of python2rust.toml, if any."""
+    settings = Settings(output_dir=output_dir)
+    config_file = find_project_config()
+    if config_file is not None:
+        config = ProjectConfig.load(config_file)
+        settings.sandbox = config.sandbox
+        settings.limits = config.limits
+    return settings
+
+
+async def run_bench(python_file: Path, output_dir: Path, workload: BenchWorkload) -> dict:
+    """Build the Rust crate in release mode, then benchmark both servers one after the other; with
+    the sandbox enabled, the Rust crate is built and its server run in the container."""
+    settings = bench_settings(output_dir)
+    sandbox = Sandbox(settings.sandbox) if settings.sandbox.enabled else None
+    builder = RustBuilder(output_dir=output_dir, build_timeout=settings.build_timeout,
+                          sandbox=sandbox, limits=settings.limits)
+    success, error, _ = await builder.build(
+        (output_dir / "src" / "main.rs").read_text(),
+        (output_dir / "Cargo.toml").read_text(),
+        release=True
+    )
+    if not success:
+        raise RuntimeError(f"Release build failed: {error}")
+
+    port = settings.server_port
+    limits = settings.limits.benchmark
+    benchmark = ServerBenchmark(host=settings.server_host, port=port, limits=limits)
+    python = await benchmark.run(
+        "python", [sys.executable, python_file.name], python_file.parent, workload)
+    command = [str(rust_binary(output_dir).resolve())]
+    if sandbox is not None:
+        # Published ports only reach servers listening on every interface
+        env = {"PORT": str(port), "SERVER_PORT": str(port), "HOST": "0.0.0.0"}
+        command = sandbox.command(
+            command, output_dir, env=env, ports=[port], memory_mb=limits.memory_mb)
+    rust = await benchmark.run("rust", command, output_dir, workload, sandboxed=sandbox is not None)
+    return {"python": python, "rust": rust}
+
+
+def run(args: argparse.Namespace) -> bool:
+    python_file = args.python_file.resolve()
+    output_dir = args.output_dir.resolve()
+    workload = BenchWorkload(
+        method=args.method,
+        path=args.path,
+        requests=args.requests,
+        concurrency=args.concurrency,
+        warmup=args.warmup
+    )
+    try:
+        results = asyncio.run(run_bench(python_file, output_dir, workload))
+    except (OSError, RuntimeError, KeyError) as e:
+        logger.error(f"Benchmark error: {e}")
+        return fail(args, str(e))
+
+    report = {name: result.to_dict() for name, result in results.items()}
+    report_file = output_dir / "debug" / "benchmark.json"
+    report_file.parent.mkdir(parents=True, exist_ok=True)
+    report_file.write_text(json.dumps(report, indent=2))
+
+    failed = [result.error for result in results.values() if result.error]
+    if json_output(args):
+        return print_json(args, not failed, report, error="; ".join(failed) or None)
+
+    print(format_comparison(results["python"], results["rust"]))
+    print(f"\nResults saved to {report_file}")
+    for error in failed:
+        print(f"Error: {error}")
+    return not failed
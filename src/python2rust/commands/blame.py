@@ -0,0 +1,93 @@
+"""
+The blame subcommand: trace a line of generated Rust back to the Python it came from.
+"""
+import argparse
+from dataclasses import asdict
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from .common import fail, json_output, print_json
+from ..analyzers import SourceMap
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "blame",
+        help="Show the Python definition, prompt and warnings behind a Rust line",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "location",
+        help="Generated Rust file and line, e.g. generated/src/main.rs:42"
+    )
+    parser.set_defaults(handler=run)
+
+
+def parse_location(location: str) -> Optional[Tuple[Path, int]]:
+    path, _, line = location.rpartition(":")
+    if not path or not line.isdigit():
+        return None
+    return Path(path), int(line)
+
+
+def blame(source_map: SourceMap, rust_file: Path, line: int) -> List[str]:
+    """Describe the provenance of a Rust line from its source map."""
+    mapping = source_map.mapping_at(line)
+    if mapping is None:
+        return [f"{rust_file}:{line} is not inside a Rust item"]
+
+    rust = mapping.rust
+    lines = [f"{rust_file}:{line} in {rust.kind} {rust.name} (lines "
+             f"{rust.start_line}-{rust.end_line})"]
+    python = source_map.lookup(line)
+    if python is None:
+        lines.append("  python: no matching Python definition")
+    else:
+        lines.append(f"  python: {source_map.python_file}:{python.start_line}-{python.end_line} "
+                     f"{python.kind} {python.name}")
+        cell = source_map.cell_at(python.start_line)
+        if cell is not None:
+            lines.append(f"  notebook: {source_map.notebook} cell {cell.index}")
+    lines.append(f"  produced by: {mapping.origin or 'unknown'}")
+    if mapping.warnings:
+        lines.append("  warnings:")
+        lines.extend(f"    - {warning}" for warning in mapping.warnings)
+    return lines
+
+
+def blame_dict(source_map: SourceMap, rust_file: Path, line: int) -> Dict[str, Any]:
+    """The provenance of a Rust line as plain data."""
+    mapping = source_map.mapping_at(line)
+    python = source_map.lookup(line)
+    cell = source_map.cell_at(python.start_line) if python else None
+    return {
+        "rust_file": str(rust_file),
+        "line": line,
+        "rust": asdict(mapping.rust) if mapping else None,
+        "python_file": source_map.python_file,
+        "python": asdict(python) if python else None,
+        "notebook": source_map.notebook,
+        "cell": cell.index if cell else None,
+        "origin": mapping.origin if mapping else None,
+        "warnings": mapping.warnings if mapping else []
+    }
+
+
+def run(args: argparse.Namespace) -> bool:
+    parsed = parse_location(args.location)
+    if parsed is None:
+        return fail(args, f"expected <rust-file>:<line>, got {args.location}")
+
+    rust_file, line = parsed
+    map_file = rust_file.with_name(f"{rust_file.name}.map.json")
+    if not map_file.exists():
+        return fail(args, f"no source map at {map_file}")
+
+    source_map = SourceMap.load(map_file)
+    if json_output(args):
+        return print_json(args, True, blame_dict(source_map, rust_file, line))
+    print("\n".join(blame(source_map, rust_file, line)))
+    return True
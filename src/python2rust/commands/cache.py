@@ -0,0 +1,86 @@
+"""
+The cache subcommand: show or clear the stored model responses of a target directory.
+"""
+import argparse
+from pathlib import Path
+from typing import Optional
+
+from .common import add_output_arguments, fail, json_output, print_json
+from ..config.project_config import ProjectConfig, find_project_config
+from ..runs import get_state_dir
+from ..utils.logging import setup_logger
+from ..utils.response_cache import CACHE_FILE_NAME, ResponseCache
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "cache",
+        help="Show or clear the model responses reused by later runs",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    actions = parser.add_subparsers(dest="cache_command", metavar="action")
+    actions.required = True
+
+    stats = actions.add_parser(
+        "stats",
+        help="Show the number of stored responses, cache hits and models",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    stats.set_defaults(handler=run_stats)
+
+    clear = actions.add_parser(
+        "clear",
+        help="Delete every stored response",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    clear.set_defaults(handler=run_clear)
+
+    for action in (stats, clear):
+        action.add_argument(
+            "--output-dir",
+            type=Path,
+            default=None,
+            help="Target directory of the runs (defaults to python2rust.toml or generated)"
+        )
+        add_output_arguments(action)
+
+
+def cache_dir(output_dir: Optional[Path]) -> Path:
+    """Response cache directory of a target directory, from python2rust.toml when not given."""
+    if output_dir is None:
+        config_file = find_project_config()
+        output_dir = (
+            config_file.parent / ProjectConfig.load(config_file).project.target_dir
+            if config_file else Path("generated")
+        )
+    return get_state_dir(output_dir) / "responses"
+
+
+def run_stats(args: argparse.Namespace) -> bool:
+    directory = cache_dir(args.output_dir)
+    if not (directory / CACHE_FILE_NAME).exists():
+        return fail(args, f"No response cache in {directory}")
+    stats = ResponseCache(directory).stats()
+    if json_output(args):
+        return print_json(args, True, stats.to_dict())
+    print(stats.format())
+    return True
+
+
+def run_clear(args: argparse.Namespace) -> bool:
+    directory = cache_dir(args.output_dir)
+    if not (directory / CACHE_FILE_NAME).exists():
+        if json_output(args):
+            return print_json(args, True, {"path": str(directory), "cleared": 0})
+        print(f"No response cache in {directory}")
+        return True
+    cache = ResponseCache(directory)
+    cleared = cache.stats().entries
+    cache.clear()
+    logger.info(f"Cleared {cleared} responses from {cache.path}")
+    if json_output(args):
+        return print_json(args, True, {"path": str(cache.path), "cleared": cleared})
+    print(f"Cleared {cleared} stored responses")
+    return True
@@ -0,0 +1,96 @@
+"""
+The check subcommand: re-verify generated Rust code against its Python source, for CI gating.
+"""
+import argparse
+import asyncio
+from typing import Optional
+
+from .common import (
+    add_source_arguments,
+    attach_progress,
+    check_token_files,
+    default_settings,
+    fail,
+    install_cancel_handler,
+    json_output,
+    print_json,
+    resolve_targets,
+    Targets
+)
+from ..config.project_config import ProjectConfig
+from ..migrator import AVAILABLE_CHECKERS, Migrator
+from ..report import MigrationReport
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+CHECKS = ("verify", *AVAILABLE_CHECKERS)
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "check",
+        help="Re-run verification on an existing migration without regenerating code",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--checks",
+        nargs="+",
+        choices=CHECKS,
+        default=list(CHECKS),
+        help="Checks to run: LLM behavioral comparison, cargo build, server tests"
+    )
+    parser.set_defaults(handler=run)
+
+
+def print_check_report(report: MigrationReport, args: Optional[argparse.Namespace] = None) -> bool:
+    """Print one line per file and return whether every check passed."""
+    if args is not None and json_output(args):
+        return print_json(args, report.success, report.to_dict())
+    for file in report.files:
+        status = "ok" if file.success else f"FAILED: {file.error}"
+        print(f"{file.source} -> {file.output_dir}: {status}")
+
+    if report.cancelled:
+        print("\nCheck cancelled")
+        return False
+    print(f"\n{len(report.succeeded)} passed, {len(report.failed)} failed")
+    return report.success
+
+
+async def run_check(targets: Targets, checks: list,
+                    args: Optional[argparse.Namespace] = None) -> bool:
+    """Check every generated crate of the given targets."""
+    try:
+        tokens = check_token_files()
+        migrator = Migrator(default_settings(targets.output_dir, tokens))
+        if targets.config_file is not None:
+            migrator.project_config(
+                ProjectConfig.load(targets.config_file), targets.config_file.parent)
+        migrator.target_dir(targets.output_dir).tokens(**tokens)
+        migrator.checkers(*[name for name in AVAILABLE_CHECKERS if name in checks])
+        if targets.python_file is not None:
+            migrator.source_file(targets.python_file)
+        else:
+            migrator.source_dir(targets.source_dir)
+        install_cancel_handler(migrator)
+        progress = attach_progress(migrator, args, len(migrator.outputs()))
+
+        try:
+            report = await migrator.check(compare_behavior="verify" in checks)
+        finally:
+            if progress is not None:
+                progress.close()
+        return print_check_report(report, args)
+
+    except Exception as e:
+        logger.exception(f"Check error: {str(e)}")
+        return fail(args, str(e))
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+    return asyncio.run(run_check(targets, args.checks, args))
@@ -0,0 +1,126 @@
+"""
+The classes subcommand: show the Rust item planned for each class, and approve or change the plan.
+"""
+import argparse
+import sys
+from pathlib import Path
+from typing import Callable, Dict
+
+from .common import add_source_arguments, json_output, print_json, resolve_targets
+from ..analyzers import class_mappings
+from ..analyzers.class_hierarchy import KINDS
+from ..migrator import Migrator
+from ..runs import ClassPlanStore
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+class PlanQuit(Exception):
+    """Raised when the user stops approving plans."""
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "classes",
+        help="Show the struct, trait or enum planned for each class, and "
+             "approve or change the plan",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--list",
+        action="store_true",
+        help="List the plans and whether they are approved instead of asking"
+    )
+    parser.set_defaults(handler=run)
+
+
+def review_plan(
+    store: ClassPlanStore,
+    source: Path,
+    ask: Callable[[str], str] = input,
+    say: Callable[[str], None] = print
+) -> None:
+    """Show the class plan of one file and record its approval, with the kinds changed."""
+    python_code = source.read_text()
+    approval = store.approved(source, python_code)
+    overrides: Dict[str, str] = dict(approval.overrides) if approval else {}
+    while True:
+        mappings = class_mappings(python_code, overrides)
+        if not mappings:
+            return
+        say(f"\n== {source} ({'approved' if approval else 'not approved'})")
+        for mapping in mappings:
+            say(f"   {mapping.describe()}")
+        answer = ask("[a]pprove, [c]hange a class, [s]kip, [q]uit: ").strip().lower()
+        if answer == "a":
+            store.approve(source, python_code, overrides)
+            return
+        if answer == "s":
+            return
+        if answer == "q":
+            raise PlanQuit()
+        if answer != "c":
+            continue
+        change = (ask(f"Class and what it becomes ({', '.join(KINDS)}), e.g. `Shape trait`: ")
+                  .split())
+        names = {mapping.name for mapping in mappings}
+        if len(change) != 2 or change[0] not in names or change[1] not in KINDS:
+            say(f"   expected a class of the file and one of {', '.join(KINDS)}")
+            continue
+        overrides[change[0]] = change[1]
+        approval = None
+
+
+def _ask_on_stderr(prompt: str) -> str:
+    print(prompt, end="", file=sys.stderr, flush=True)
+    return input()
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator().target_dir(targets.output_dir)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+    sources = list(migrator.outputs())
+    store = ClassPlanStore(targets.output_dir)
+
+    as_json = json_output(args)
+    if not args.list:
+        # With --format json the session runs on stderr so stdout only holds the document
+        ask, say = ((_ask_on_stderr, lambda text: print(text, file=sys.stderr)) if as_json
+                    else (input, print))
+        try:
+            for source in sources:
+                review_plan(store, source, ask=ask, say=say)
+        except (PlanQuit, EOFError):
+            say("\nStopped, approvals so far are saved")
+
+    plans = []
+    for source in sources:
+        python_code = source.read_text()
+        approval = store.approved(source, python_code)
+        mappings = class_mappings(python_code, approval.overrides if approval else None)
+        if mappings:
+            plans.append((source, approval is not None, mappings))
+    if as_json:
+        return print_json(args, True, {"files": [
+            {"source": str(source), "approved": approved,
+             "classes": [vars(mapping) for mapping in mappings]}
+            for source, approved, mappings in plans
+        ]})
+    if args.list:
+        for source, approved, mappings in plans:
+            print(f"{'approved' if approved else 'not approved':<13} {source}")
+            for mapping in mappings:
+                print(f"   {mapping.describe()}")
+        return True
+    print("\nApproved plans go into the prompts of `python2rust migrate`, which with "
+          "approve_class_plans only migrates the files whose plan is approved.")
+    return True
@@ -0,0 +1,350 @@
+"""
+Helpers shared by the command line subcommands.
+"""
+import argparse
+import asyncio
+import json
+import logging
+import signal
+import sys
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+from ..config.project_config import CONFIG_FILE_NAME, ProjectConfig, find_project_config
+from ..config.settings import LLMChoice, Settings
+from ..migrator import Migrator
+from ..providers import get_provider
+from ..report import MigrationReport
+from ..utils.logging import set_console_level, setup_logger
+from ..utils.progress import ProgressDisplay
+
+logger = setup_logger()
+
+# Version of the --format json document; bumped only on incompatible changes
+OUTPUT_SCHEMA_VERSION = 1
+OUTPUT_FORMATS = ("text", "json")
+
+
+# API token files, looked up at the project root
+TOKEN_FILES = {
+    "claude": ".claude_token",
+    "openai": ".openai_token",
+    "gemini": ".gemini_token",
+    "azure": ".azure_token",
+    "hf": ".hf_token",
+    "mistral": ".mistral_token",
+    "openai_compatible": ".openai_compatible_token"
+}
+
+
+def token_file_path(name: str) -> Path:
+    return Path(__file__).parent.parent.parent.parent / TOKEN_FILES[name]
+
+
+def read_token_files() -> Dict[str, Optional[str]]:
+    """Read the token files that exist, without logging or exiting; missing or
+    empty ones are None."""
+    tokens = {}
+    for name in TOKEN_FILES:
+        try:
+            tokens[name] = token_file_path(name).read_text().strip() or None
+        except FileNotFoundError:
+            tokens[name] = None
+    return tokens
+
+
+def check_token_files() -> Dict[str, Optional[str]]:
+    """Check and load token files."""
+    project_root = Path(__file__).parent.parent.parent.parent
+    tokens = {}
+
+    # Check Claude, OpenAI, Azure OpenAI and Gemini tokens; without any, or AWS credentials,
+    # only local models can run
+    main_tokens = (("claude", "Claude"), ("openai", "OpenAI"), ("azure", "Azure OpenAI"),
+                   ("gemini", "Gemini"))
+    for name, label in main_tokens:
+        try:
+            tokens[name] = (project_root / TOKEN_FILES[name]).read_text().strip() or None
+        except FileNotFoundError:
+            tokens[name] = None
+        if tokens[name]:
+            logger.info(f"{label} token found")
+    if not any(tokens.values()) and get_provider("bedrock").credentials(tokens):
+        logger.info("AWS credentials found - Bedrock available")
+    elif not any(tokens.values()):
+        logger.warning("No Claude, OpenAI, Azure OpenAI or Gemini token and no "
+                       "AWS credentials found")
+        print(f"""
+Warning: no Claude, OpenAI, Azure OpenAI or Gemini API token found; only local models (Ollama)
+can be used. To use hosted models, create {project_root / TOKEN_FILES["claude"]} with your Claude
+API key from https://console.anthropic.com/,
+{project_root / TOKEN_FILES["openai"]} with your OpenAI API key from
+https://platform.openai.com/api-keys,
+{project_root / TOKEN_FILES["azure"]} with the key of your Azure OpenAI resource
+or {project_root / TOKEN_FILES["gemini"]} with your Gemini API key from
+https://aistudio.google.com/apikey,
+or configure AWS credentials to use Bedrock.
+""", file=sys.stderr)
+
+    # Check HuggingFace token (optional)
+    hf_token_path = project_root / ".hf_token"
+    try:
+        tokens["hf"] = hf_token_path.read_text().strip()
+        if tokens["hf"]:
+            logger.info(
+                "HuggingFace token found - CodeLlama and StarCoder available")
+        else:
+            logger.warning("HuggingFace token file is empty")
+            tokens["hf"] = None
+    except FileNotFoundError:
+        logger.info(
+            "No HuggingFace token found - will use Claude for all operations")
+        tokens["hf"] = None
+
+     # Check Mistral token (optional)
+    mistral_token_path = project_root / ".mistral_token"
+    try:
+        tokens["mistral"] = mistral_token_path.read_text().strip()
+        if tokens["mistral"]:
+            logger.info("Mistral token found - Codestral available")
+        else:
+            logger.warning("Mistral token file is empty")
+            tokens["mistral"] = None
+    except FileNotFoundError:
+        logger.info(
+            "No Mistral token found - will use Claude for all operations")
+        tokens["mistral"] = None
+
+    # Key of the OpenAI-compatible server (optional, most self-hosted servers need none)
+    try:
+        tokens["openai_compatible"] = (token_file_path("openai_compatible")
+                                       .read_text().strip() or None)
+    except FileNotFoundError:
+        tokens["openai_compatible"] = None
+
+    return tokens
+
+
+def validate_python_file(file_path: Path) -> str:
+    """Validate and read Python input file."""
+    try:
+        if not file_path.exists():
+            raise FileNotFoundError(f"Python file not found: {file_path}")
+
+        if file_path.suffix not in ('.py', '.ipynb'):
+            raise ValueError(f"File must have .py or .ipynb extension: {file_path}")
+
+        content = file_path.read_text()
+        if not content.strip():
+            raise ValueError(f"Python file is empty: {file_path}")
+
+        return content
+
+    except Exception as e:
+        logger.error(f"Input file error: {str(e)}")
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+
+def default_settings(output_dir: Path, tokens: Dict[str, Optional[str]]) -> Settings:
+    """Pick models for each step based on the available tokens."""
+    # Configure settings - using available models
+    if tokens["hf"]:
+        return Settings(
+            output_dir=output_dir,
+            llm_steps={
+                "analysis": LLMChoice.CLAUDE,      # Good at understanding code
+                "generation": LLMChoice.CLAUDE,  # Good at code generation
+                "verification": LLMChoice.CLAUDE,   # Good at comparison
+                "fixes": LLMChoice.CODELLAMA       # Good at code fixes
+            }
+        )
+    else:
+        # Fall back to Claude for everything if no HF token
+        # Uses default Claude settings; without a Claude token the steps fall back to OpenAI
+        return Settings(output_dir=output_dir)
+
+
+def add_output_arguments(parser: argparse.ArgumentParser) -> None:
+    """--format and --quiet, added to every subcommand; --json is kept as a shorthand."""
+    parser.add_argument(
+        "--format",
+        choices=OUTPUT_FORMATS,
+        default="text",
+        help="Output format; json prints a single document with a stable schema"
+    )
+    parser.add_argument(
+        "--json",
+        action="store_const",
+        const="json",
+        dest="format",
+        help="Shorthand for --format json"
+    )
+    parser.add_argument(
+        "--quiet",
+        action="store_true",
+        help="No progress display and only warnings in the console log"
+    )
+
+
+def json_output(args: Optional[argparse.Namespace]) -> bool:
+    return getattr(args, "format", "text") == "json"
+
+
+def print_json(
+    args: argparse.Namespace,
+    success: bool,
+    result: Any = None,
+    error: Optional[str] = None,
+    indent: Optional[int] = 2
+) -> bool:
+    """Print the JSON document of a command and return success."""
+    print(json.dumps({
+        "schema_version": OUTPUT_SCHEMA_VERSION,
+        "command": args.command,
+        "success": success,
+        "error": error,
+        "result": result
+    }, indent=indent, default=str), flush=True)
+    return success
+
+
+def fail(args: Optional[argparse.Namespace], message: str) -> bool:
+    """Report an error as text or as a JSON document, and return False."""
+    if json_output(args):
+        return print_json(args, False, error=message)
+    print(f"Error: {message}")
+    return False
+
+
+def add_source_arguments(parser: argparse.ArgumentParser) -> None:
+    """Options selecting what to migrate, shared by the commands working on sources."""
+    source = parser.add_mutually_exclusive_group()
+    source.add_argument(
+        "--python-file",
+        type=Path,
+        help="Path to Python source file"
+    )
+    source.add_argument(
+        "--source-dir",
+        type=Path,
+        help="Migrate every module of a Python package, dependencies first"
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=None,
+        help="Output directory for generated code (defaults to the config "
+             "target_dir, then ./generated)"
+    )
+    parser.add_argument(
+        "--config",
+        type=Path,
+        default=None,
+        help=f"Project config file (defaults to the nearest {CONFIG_FILE_NAME})"
+    )
+
+
+@dataclass
+class Targets:
+    """Sources and output directory selected on the command line or in python2rust.toml."""
+    python_file: Optional[Path]
+    source_dir: Optional[Path]
+    output_dir: Path
+    config_file: Optional[Path]
+
+
+def resolve_targets(args: argparse.Namespace) -> Optional[Targets]:
+    """Fill in sources and output directory from python2rust.toml when not given."""
+    config_file = args.config or find_project_config()
+    config = ProjectConfig.load(config_file) if config_file else None
+
+    source_dir = args.source_dir
+    if args.python_file is None and source_dir is None:
+        if config is None:
+            fail(args, "pass --python-file or --source-dir, or run `python2rust init` first")
+            return None
+        source_dir = config_file.parent / config.project.source_dir
+
+    output_dir = args.output_dir
+    if output_dir is None:
+        output_dir = config_file.parent / config.project.target_dir if config else Path("generated")
+
+    return Targets(args.python_file, source_dir, output_dir, config_file)
+
+
+def attach_progress(
+    migrator: Migrator,
+    args: Optional[argparse.Namespace],
+    total: int
+) -> Optional[ProgressDisplay]:
+    """Show live progress on stderr unless --quiet; on a terminal model output is streamed."""
+    if args is None or getattr(args, "quiet", False):
+        return None
+    display = ProgressDisplay(total=total)
+    migrator.observer(display)
+    if display.live:
+        migrator.stream_responses()
+        # Info logs would scroll the display away; they are still in the log file
+        set_console_level(logging.WARNING)
+    return display
+
+
+def install_cancel_handler(migrator: Migrator) -> None:
+    """First Ctrl-C cancels gracefully, saving partial state."""
+    loop = asyncio.get_running_loop()
+    try:
+        loop.add_signal_handler(signal.SIGINT, migrator.cancel)
+    except (NotImplementedError, RuntimeError):
+        pass  # Not supported on Windows event loops
+
+
+def print_report(report: MigrationReport, output_dir: Path,
+                 args: Optional[argparse.Namespace] = None) -> bool:
+    """Print the outcome of a migration run and return whether it succeeded."""
+    if args is not None and json_output(args):
+        return print_json(args, report.success, report.to_dict())
+
+    for warning in report.warnings:
+        print(f"Warning: {warning}")
+    for file in report.files:
+        for warning in file.warnings:
+            print(f"Warning: {file.source}: {warning}")
+        if file.dead_code:
+            print(f"Not translated, unused: {file.source}: {', '.join(file.dead_code)}")
+    unmapped = [file for file in report.files if file.unknown_decorators]
+    if unmapped:
+        print("Decorators without a translation strategy, map them under [decorators] "
+              "in python2rust.toml:")
+        for file in unmapped:
+            for decorator in file.unknown_decorators:
+                print(f"  {file.source}: {decorator}")
+
+    exhausted = (report.budget or {}).get("exhausted")
+    if report.cancelled:
+        if exhausted:
+            print(f"\n{exhausted} - raise the [budget] limits of python2rust.toml to continue")
+        else:
+            print("\nMigration cancelled - partial state saved in the debug directory")
+        if report.run_id:
+            print(f"Resume with: python -m python2rust resume {report.run_id} "
+                  f"--output-dir {output_dir}")
+        return False
+
+    if report.success:
+        logger.info("Migration successful!")
+        print("\nMigration successful!")
+        print(f"Generated files in: {output_dir}")
+        if report.workspace:
+            print(f"Cargo workspace of the packages in: {report.workspace}")
+        print(f"Debug information in: {output_dir}/debug")
+        print(f"Logs in: {Path('logs')}")
+        return True
+
+    logger.error("Migration failed!")
+    print("\nMigration failed - check logs for details")
+    if report.run_id:
+        print(f"Retry failed files with: python -m python2rust resume {report.run_id} "
+              f"--output-dir {output_dir}")
+    return False
@@ -0,0 +1,50 @@
+"""
+The coverage subcommand: compare the test coverage of each Python module and of its Rust crate.
+"""
+import argparse
+import asyncio
+import json
+
+from .common import add_source_arguments, fail, json_output, print_json, resolve_targets
+from ..migrator import Migrator
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "coverage",
+        help="Measure the coverage of the Python tests and of the translated Rust tests per module",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator().target_dir(targets.output_dir)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+
+    try:
+        report = asyncio.run(migrator.coverage())
+    except (OSError, ValueError) as e:
+        logger.error(f"Coverage error: {e}")
+        return fail(args, str(e))
+
+    report_file = targets.output_dir / "debug" / "coverage.json"
+    report_file.parent.mkdir(parents=True, exist_ok=True)
+    report_file.write_text(json.dumps(report.to_dict(), indent=2))
+
+    if json_output(args):
+        return print_json(args, True, report.to_dict())
+    print(report.format())
+    print(f"\nResults saved to {report_file}")
+    return True
@@ -0,0 +1,63 @@
+"""
+The diff subcommand: compare the Rust generated by two runs of the same target.
+"""
+import argparse
+from pathlib import Path
+
+from .common import fail, json_output, print_json
+from ..runs import RunStore, diff_runs
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "diff",
+        help="Diff the generated Rust of two runs (defaults to the last two)",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "runs",
+        nargs="*",
+        metavar="run_id",
+        help="Old and new run; a single run is compared with the one before it"
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated"),
+        help="Output directory of the runs"
+    )
+    parser.add_argument(
+        "--stat",
+        action="store_true",
+        help="Only print per-file summary statistics"
+    )
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    if len(args.runs) > 2:
+        return fail(args, "pass at most two run ids")
+
+    store = RunStore(args.output_dir)
+    if len(args.runs) == 2:
+        old_id, new_id = args.runs
+    else:
+        run_ids = [checkpoint.run_id for checkpoint in store.list_runs()]
+        new_id = args.runs[0] if args.runs else (run_ids[-1] if run_ids else None)
+        position = run_ids.index(new_id) if new_id in run_ids else 0
+        if position == 0:
+            return fail(args, f"no earlier run to compare with in {store.runs_dir}")
+        old_id = run_ids[position - 1]
+
+    try:
+        result = diff_runs(store, store.load(old_id), store.load(new_id))
+    except FileNotFoundError as e:
+        return fail(args, str(e))
+
+    if json_output(args):
+        return print_json(args, True, result.to_dict())
+    print(result.format(stat_only=args.stat))
+    return True
@@ -0,0 +1,54 @@
+"""
+The doctor subcommand: check the toolchain, API keys and disk space before migrating.
+"""
+import argparse
+import asyncio
+from pathlib import Path
+
+from .common import json_output, print_json, read_token_files
+from ..doctor import MIN_FREE_DISK_GB, run_doctor
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "doctor",
+        help="Check rustc, cargo, clippy, rustfmt, Python, API keys and disk space",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated"),
+        help="Directory the Rust projects will be built in"
+    )
+    parser.add_argument(
+        "--offline",
+        action="store_true",
+        help="Only check that API keys are present, without calling the APIs"
+    )
+    parser.add_argument(
+        "--min-free-gb",
+        type=float,
+        default=MIN_FREE_DISK_GB,
+        help="Free disk space required for build artifacts"
+    )
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    report = asyncio.run(run_doctor(
+        args.output_dir,
+        read_token_files(),
+        online=not args.offline,
+        min_free_gb=args.min_free_gb
+    ))
+    for check in report.checks:
+        logger.info(f"doctor: {check.name}: {check.status}: {check.detail}")
+
+    if json_output(args):
+        return print_json(args, report.ok, report.to_dict())
+    print(report.format())
+    return report.ok
@@ -0,0 +1,72 @@
+"""
+The init subcommand: inspect a Python project and write a starter python2rust.toml.
+"""
+import argparse
+from pathlib import Path
+
+from .common import fail, json_output, print_json
+from ..analyzers import inspect_project
+from ..config.project_config import CONFIG_FILE_NAME
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+HEADER = """# python2rust project configuration, generated by `python2rust init`.
+# Review the detected values; [crates] maps Python libraries to the Rust crates
+# the generated code should use.
+
+"""
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "init",
+        help=f"Detect the project layout and write a starter {CONFIG_FILE_NAME}",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "project_dir",
+        type=Path,
+        nargs="?",
+        default=Path("."),
+        help="Root of the Python project"
+    )
+    parser.add_argument(
+        "--force",
+        action="store_true",
+        help=f"Overwrite an existing {CONFIG_FILE_NAME}"
+    )
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    config_file = args.project_dir / CONFIG_FILE_NAME
+    if config_file.exists() and not args.force:
+        if json_output(args):
+            return print_json(args, False,
+                              error=f"{config_file} already exists (use --force to overwrite)")
+        print(f"{config_file} already exists (use --force to overwrite)")
+        return False
+    if not args.project_dir.is_dir():
+        return fail(args, f"project directory not found: {args.project_dir}")
+
+    inspection = inspect_project(args.project_dir)
+    config = inspection.to_config()
+    config.save(config_file)
+    config_file.write_text(HEADER + config_file.read_text())
+
+    if json_output(args):
+        return print_json(args, True, {
+            "config_file": str(config_file),
+            "config": config.model_dump(mode="json", exclude_none=True)
+        })
+    print(f"Wrote {config_file}")
+    print(f"  Project:      {config.project.name}")
+    print(f"  Sources:      {config.project.source_dir}")
+    print("  Entry points:"
+          f" {', '.join(str(p) for p in config.project.entry_points) or 'none found'}")
+    print(f"  Tests:        {config.project.tests_dir or 'none found'}")
+    print(f"  Frameworks:   {', '.join(config.frameworks) or 'none detected'}")
+    for library, crate in config.crates.items():
+        print(f"  {library} -> {crate}")
+    return True
@@ -0,0 +1,156 @@
+"""
+The migrate subcommand: translate a Python file into a Rust project.
+"""
+import argparse
+import asyncio
+from pathlib import Path
+from typing import Dict, Optional
+
+from .common import (
+    add_source_arguments,
+    attach_progress,
+    check_token_files,
+    default_settings,
+    install_cancel_handler,
+    json_output,
+    print_json,
+    print_report,
+    resolve_targets,
+    validate_python_file
+)
+from ..config.project_config import ProjectConfig
+from ..config.settings import MIGRATION_STEPS
+from ..migrator import Migrator
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "migrate",
+        help="Migrate a Python file or package to Rust",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--workers",
+        type=int,
+        default=None,
+        help="Number of files migrated concurrently (defaults to the max_workers setting)"
+    )
+    parser.add_argument(
+        "--force",
+        action="store_true",
+        help="Migrate every file again, even those unchanged since their last verified migration"
+    )
+    parser.add_argument(
+        "--no-cache",
+        action="store_true",
+        help="Call the models even for prompts with a stored response"
+    )
+    parser.add_argument(
+        "--max-cost",
+        type=float,
+        default=None,
+        help="Stop the run, resumably, once it has spent this many dollars on model calls"
+    )
+    parser.add_argument(
+        "--deterministic",
+        action="store_true",
+        help="Use temperature 0 and record every model response for auditing"
+    )
+    parser.add_argument(
+        "--replay",
+        action="store_true",
+        help="With --deterministic, replay recorded responses instead of calling the models"
+    )
+    parser.set_defaults(handler=run)
+
+
+async def migrate_code(
+    python_file: Optional[Path],
+    output_dir: Path,
+    tokens: Dict[str, Optional[str]],
+    source_dir: Optional[Path] = None,
+    workers: Optional[int] = None,
+    force: bool = False,
+    deterministic: bool = False,
+    replay: bool = False,
+    no_cache: bool = False,
+    max_cost: Optional[float] = None,
+    config_file: Optional[Path] = None,
+    args: Optional[argparse.Namespace] = None
+) -> bool:
+    """Execute the migration process."""
+    try:
+        # Validate Python code before spending any tokens
+        if python_file is not None:
+            validate_python_file(python_file)
+
+        settings = default_settings(output_dir, tokens)
+
+        # Log configuration
+        logger.info("Migration configuration:")
+        logger.info(f"- Input: {python_file or source_dir}")
+        logger.info(f"- Output directory: {output_dir}")
+        logger.info("Models used:")
+        for task in MIGRATION_STEPS:
+            logger.info(f"  - {task}: {settings.llm_steps.model_for(task).value}")
+
+        migrator = Migrator(settings)
+        if config_file is not None:
+            logger.info(f"- Project config: {config_file}")
+            migrator.project_config(ProjectConfig.load(config_file), config_file.parent)
+        migrator.target_dir(output_dir).tokens(**tokens)
+        if python_file is not None:
+            migrator.source_file(python_file)
+        else:
+            migrator.source_dir(source_dir)
+        if workers is not None:
+            migrator.workers(workers)
+        migrator.incremental(not force)
+        if no_cache:
+            migrator.response_cache(False)
+        if max_cost is not None:
+            migrator.budget(run_cost=max_cost)
+        if deterministic or replay:
+            migrator.deterministic(replay=replay)
+        install_cancel_handler(migrator)
+        progress = attach_progress(migrator, args, len(migrator.outputs()))
+
+        try:
+            report = await migrator.migrate()
+        finally:
+            if progress is not None:
+                progress.close()
+        return print_report(report, output_dir, args)
+
+    except Exception as e:
+        logger.exception(f"Migration error: {str(e)}")
+        if args is not None and json_output(args):
+            return print_json(args, False, error=str(e))
+        return False
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    tokens = check_token_files()
+    targets.output_dir.mkdir(parents=True, exist_ok=True)
+    return asyncio.run(migrate_code(
+        python_file=targets.python_file,
+        output_dir=targets.output_dir,
+        tokens=tokens,
+        source_dir=targets.source_dir,
+        workers=args.workers,
+        force=args.force,
+        deterministic=args.deterministic,
+        replay=args.replay,
+        no_cache=args.no_cache,
+        max_cost=args.max_cost,
+        config_file=targets.config_file,
+        args=args
+    ))
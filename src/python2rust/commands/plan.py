@@ -0,0 +1,50 @@
+"""
+The plan subcommand: show what a migration would do and cost, without calling a model.
+"""
+import argparse
+
+from .common import add_source_arguments, json_output, print_json, resolve_targets
+from ..config.project_config import ProjectConfig
+from ..migrator import Migrator
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "plan",
+        help="Dry run: file order, token and cost estimates, risky constructs",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--force",
+        action="store_true",
+        help="Plan as if every file had to be migrated again"
+    )
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator()
+    if targets.config_file is not None:
+        migrator.project_config(ProjectConfig.load(targets.config_file), targets.config_file.parent)
+    migrator.target_dir(targets.output_dir).incremental(not args.force)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+
+    plan = migrator.plan()
+    # Crates under licenses outside the allowed ones fail the plan, as they would the migration
+    allowed = not plan.license_violations
+    if json_output(args):
+        return print_json(args, allowed, plan.to_dict(),
+                          None if allowed else "Crates under licenses outside the allowed ones")
+    print(plan.format())
+    return allowed
@@ -0,0 +1,109 @@
+"""
+The prompts subcommand: show the version of a prompt set, or A/B compare two
+prompt sets on a corpus.
+"""
+import argparse
+import asyncio
+from pathlib import Path
+
+from .common import (
+    add_output_arguments, check_token_files, default_settings, fail, json_output, print_json
+)
+from ..config.project_config import ProjectConfig, find_project_config
+from ..migrator import Migrator
+from ..prompt_eval import DEFAULT_PROMPT_SET, compare_prompt_sets, prompts_dir_of
+from ..prompts.loader import prompt_set_version
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "prompts",
+        help="Version prompt sets and compare them on a corpus of examples",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    actions = parser.add_subparsers(dest="prompts_command", metavar="action")
+    actions.required = True
+
+    version = actions.add_parser(
+        "version",
+        help="Print the version a prompt set tags runs with",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    version.add_argument(
+        "prompt_set",
+        nargs="?",
+        default=DEFAULT_PROMPT_SET,
+        help=f"Prompts directory, or {DEFAULT_PROMPT_SET} for the packaged templates"
+    )
+    version.set_defaults(handler=run_version)
+
+    compare = actions.add_parser(
+        "compare",
+        help="Migrate a corpus with two prompt sets and report success rate, iterations and cost",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    compare.add_argument("baseline", help=f"Prompts directory, or {DEFAULT_PROMPT_SET}")
+    compare.add_argument("candidate", help=f"Prompts directory, or {DEFAULT_PROMPT_SET}")
+    compare.add_argument(
+        "--corpus",
+        type=Path,
+        default=Path("examples"),
+        help="Directory of Python examples migrated with each prompt set"
+    )
+    compare.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated/prompts-compare"),
+        help="Each prompt set migrates into <output-dir>/baseline and <output-dir>/candidate"
+    )
+    compare.add_argument(
+        "--checkers",
+        nargs="*",
+        help="Verification stages to run (build, test); defaults to python2rust.toml or all"
+    )
+    compare.set_defaults(handler=run_compare)
+
+    for action in (version, compare):
+        add_output_arguments(action)
+
+
+def run_version(args: argparse.Namespace) -> bool:
+    try:
+        version = prompt_set_version(prompts_dir_of(args.prompt_set))
+    except ValueError as e:
+        return fail(args, str(e))
+    if json_output(args):
+        return print_json(args, True, {"prompt_set": args.prompt_set, "version": version})
+    print(version)
+    return True
+
+
+def run_compare(args: argparse.Namespace) -> bool:
+    if not args.corpus.is_dir():
+        return fail(args, f"Corpus directory not found: {args.corpus}")
+    config_file = find_project_config()
+    tokens = check_token_files()
+
+    def make_migrator() -> Migrator:
+        migrator = Migrator(default_settings(args.output_dir, tokens))
+        if config_file is not None:
+            migrator.project_config(ProjectConfig.load(config_file), config_file.parent)
+        if args.checkers is not None:
+            migrator.checkers(*args.checkers)
+        return migrator.tokens(**tokens)
+
+    try:
+        comparison = asyncio.run(compare_prompt_sets(
+            args.baseline, args.candidate, args.corpus, args.output_dir, make_migrator))
+    except ValueError as e:
+        logger.error(f"Prompt comparison error: {e}")
+        return fail(args, str(e))
+
+    if json_output(args):
+        return print_json(args, True, comparison.to_dict())
+    print(comparison.format())
+    print(f"\nRuns in {args.output_dir / 'baseline'} and {args.output_dir / 'candidate'}")
+    return True
@@ -0,0 +1,132 @@
+"""
+The resume subcommand: continue an interrupted or failed run from its checkpoint.
+"""
+import argparse
+import asyncio
+from pathlib import Path
+from typing import List, Optional
+
+from .common import (
+    attach_progress,
+    check_token_files,
+    default_settings,
+    fail,
+    install_cancel_handler,
+    json_output,
+    print_json,
+    print_report
+)
+from ..config.project_config import ProjectConfig, find_project_config
+from ..migrator import Migrator
+from ..runs import FileStatus, RunCheckpoint, RunStore
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "resume",
+        help="Resume a previous run, skipping files that were already verified",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "run_id",
+        nargs="?",
+        help="Run to resume (defaults to the latest run)"
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated"),
+        help="Output directory of the run"
+    )
+    parser.add_argument(
+        "--max-cost",
+        type=float,
+        default=None,
+        help="Dollar limit of the resumed run, replacing the one of python2rust.toml"
+    )
+    parser.add_argument(
+        "--list",
+        action="store_true",
+        help="List runs and the status of each file instead of resuming"
+    )
+    parser.set_defaults(handler=run)
+
+
+def format_run(checkpoint: RunCheckpoint) -> List[str]:
+    """One summary line for a run, then the status of each of its files."""
+    done = len(checkpoint.files) - len(checkpoint.pending_files())
+    lines = [f"{checkpoint.run_id}  {checkpoint.status.value:<10} "
+             f"{done}/{len(checkpoint.files)} verified  (updated {checkpoint.updated_at})"]
+    for file in checkpoint.files.values():
+        progress = f" at {file.stage}, iteration {file.iteration}" if file.stage else ""
+        error = f": {file.error}" if file.error and file.status != FileStatus.VERIFIED else ""
+        lines.append(f"    {file.status.value:<12} {file.source}{progress}{error}")
+    return lines
+
+
+def list_runs(store: RunStore, run_id: Optional[str] = None,
+              args: Optional[argparse.Namespace] = None) -> bool:
+    runs = [store.load(run_id)] if run_id else store.list_runs()
+    if args is not None and json_output(args):
+        return print_json(args, True, {"runs": [checkpoint.to_dict() for checkpoint in runs]})
+    if not runs:
+        print(f"No runs found in {store.runs_dir}")
+        return True
+    for checkpoint in runs:
+        print("\n".join(format_run(checkpoint)))
+    return True
+
+
+async def resume_run(
+    output_dir: Path,
+    run_id: str,
+    args: Optional[argparse.Namespace] = None,
+    max_cost: Optional[float] = None
+) -> bool:
+    """Resume the given run with the settings and budget of the project config."""
+    try:
+        tokens = check_token_files()
+        migrator = Migrator(default_settings(output_dir, tokens))
+        config_file = find_project_config()
+        if config_file is not None:
+            migrator.project_config(ProjectConfig.load(config_file), config_file.parent)
+        migrator.target_dir(output_dir).tokens(**tokens)
+        if max_cost is not None:
+            migrator.budget(run_cost=max_cost)
+        install_cancel_handler(migrator)
+        pending = RunStore(output_dir).load(run_id).pending_files()
+        progress = attach_progress(migrator, args, len(pending))
+
+        try:
+            report = await migrator.resume(run_id)
+        finally:
+            if progress is not None:
+                progress.close()
+        return print_report(report, output_dir, args)
+
+    except Exception as e:
+        logger.exception(f"Resume error: {str(e)}")
+        if args is not None and json_output(args):
+            return print_json(args, False, error=str(e))
+        return False
+
+
+def run(args: argparse.Namespace) -> bool:
+    store = RunStore(args.output_dir)
+    if args.list:
+        try:
+            return list_runs(store, args.run_id, args)
+        except FileNotFoundError as e:
+            return fail(args, str(e))
+
+    run_id = args.run_id
+    if run_id is None:
+        latest = store.latest()
+        if latest is None:
+            return fail(args, f"No runs found in {store.runs_dir}")
+        run_id = latest.run_id
+
+    return asyncio.run(resume_run(args.output_dir, run_id, args, args.max_cost))
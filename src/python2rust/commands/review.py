@@ -0,0 +1,171 @@
+"""
+The review subcommand: accept, reject or keep in Python each translated file or function.
+"""
+import argparse
+import sys
+from pathlib import Path
+from typing import Callable, List, Optional
+
+from .common import add_source_arguments, json_output, print_json, resolve_targets
+from ..analyzers import SourceMap
+from ..migrator import Migrator
+from ..runs import ReviewDecision, ReviewStore
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+CHOICES = {
+    "a": ReviewDecision.ACCEPTED,
+    "r": ReviewDecision.REJECTED,
+    "k": ReviewDecision.KEEP_PYTHON,
+}
+
+
+class ReviewQuit(Exception):
+    """Raised when the reviewer stops the session."""
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "review",
+        help="Accept, reject (with a comment) or keep in Python each translated file or function",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--functions",
+        action="store_true",
+        help="Review every function of a file instead of the file as a whole"
+    )
+    parser.add_argument(
+        "--list",
+        action="store_true",
+        help="List recorded decisions instead of reviewing"
+    )
+    parser.set_defaults(handler=run)
+
+
+def ask_decision(ask: Callable[[str], str], prompt: str) -> Optional[ReviewDecision]:
+    """Ask until the answer is a decision, or s to skip; q raises ReviewQuit."""
+    while True:
+        answer = (ask(f"{prompt} [a]ccept, [r]eject, [k]eep Python, [s]kip, [q]uit: ").strip()
+                  .lower())
+        if answer in CHOICES:
+            return CHOICES[answer]
+        if answer == "s":
+            return None
+        if answer == "q":
+            raise ReviewQuit()
+
+
+def review_file(
+    store: ReviewStore,
+    source: Path,
+    output_dir: Path,
+    functions: bool = False,
+    ask: Callable[[str], str] = input,
+    say: Callable[[str], None] = print
+) -> None:
+    """Review one generated crate and record the decisions."""
+    rust_file = output_dir / "src" / "main.rs"
+    if not rust_file.exists():
+        say(f"{source}: not generated yet")
+        return
+    python_code = source.read_text()
+    rust_code = rust_file.read_text()
+    map_file = rust_file.with_name(f"{rust_file.name}.map.json")
+    source_map = SourceMap.load(map_file) if map_file.exists() else None
+
+    say(f"\n== {source} -> {rust_file}")
+    current = store.file_review(source)
+    if current is not None:
+        say(f"   previous decision: {current.decision.value}")
+
+    if not functions or source_map is None:
+        if source_map is not None:
+            for mapping in source_map.mappings:
+                python = mapping.python.name if mapping.python else "-"
+                warnings = f"  ({len(mapping.warnings)} warnings)" if mapping.warnings else ""
+                say(f"   {mapping.rust.kind} {mapping.rust.name} <- {python}{warnings}")
+        _record(store, source, python_code, rust_code, None, ask)
+        return
+
+    rust_lines = rust_code.splitlines()
+    for mapping in source_map.mappings:
+        if mapping.python is None or mapping.python.kind not in ("function", "method"):
+            continue
+        say(f"\n-- {mapping.python.name} -> {mapping.rust.name}")
+        snippet = "\n".join(rust_lines[mapping.rust.start_line - 1:mapping.rust.end_line])
+        say(snippet)
+        for warning in mapping.warnings:
+            say(f"   warning: {warning}")
+        _record(store, source, python_code, rust_code, mapping.python.name, ask, snippet)
+
+
+def _record(
+    store: ReviewStore,
+    source: Path,
+    python_code: str,
+    rust_code: str,
+    item: Optional[str],
+    ask: Callable[[str], str],
+    rust_snippet: str = ""
+) -> None:
+    decision = ask_decision(ask, f"{item or source}:")
+    if decision is None:
+        return
+    comment = (ask("Comment for the regeneration: ").strip()
+               if decision == ReviewDecision.REJECTED else "")
+    store.record(source, decision, python_code, rust_code,
+                 item=item, comment=comment, rust_snippet=rust_snippet)
+
+
+def list_reviews(store: ReviewStore, sources: List[Path]) -> None:
+    for source in sources:
+        for review in store.for_source(source):
+            target = f"{source}::{review.item}" if review.item else str(source)
+            comment = f"  \"{review.comment}\"" if review.comment else ""
+            print(f"{review.decision.value:<12} {target}{comment}")
+
+
+def _ask_on_stderr(prompt: str) -> str:
+    print(prompt, end="", file=sys.stderr, flush=True)
+    return input()
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator().target_dir(targets.output_dir)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+    outputs = migrator.outputs()
+    store = ReviewStore(targets.output_dir)
+
+    as_json = json_output(args)
+    if args.list and not as_json:
+        list_reviews(store, list(outputs))
+        return True
+
+    if not args.list:
+        # With --format json the session runs on stderr so stdout only holds the document
+        ask, say = ((_ask_on_stderr, lambda text: print(text, file=sys.stderr)) if as_json
+                    else (input, print))
+        try:
+            for source, output_dir in outputs.items():
+                review_file(store, source, output_dir, functions=args.functions, ask=ask, say=say)
+        except (ReviewQuit, EOFError):
+            say("\nReview stopped, decisions so far are saved")
+
+    if as_json:
+        return print_json(args, True, {
+            "reviews": [review.to_dict() for source in outputs
+                        for review in store.for_source(source)]
+        })
+    print("\nRe-run `python2rust migrate` to regenerate rejected code; "
+          "accepted and kept files are left as they are.")
+    return True
@@ -0,0 +1,67 @@
+"""
+The sarif subcommand: export divergence warnings and generated-code issues as a SARIF log.
+"""
+import argparse
+import json
+from pathlib import Path
+
+from .common import add_source_arguments, json_output, print_json, resolve_targets
+from .. import __version__
+from ..migrator import Migrator
+from ..sarif import collect_findings, to_sarif
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "sarif",
+        help="Write semantic divergences, panics, unsafe code and unmapped definitions as SARIF",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--output",
+        type=Path,
+        default=None,
+        help="SARIF file to write (defaults to <output-dir>/python2rust.sarif)"
+    )
+    parser.add_argument(
+        "--root",
+        type=Path,
+        default=Path("."),
+        help="Directory the paths in the SARIF file are relative to, usually the repository root"
+    )
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator().target_dir(targets.output_dir)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+
+    findings = []
+    for source, output_dir in migrator.outputs().items():
+        findings.extend(collect_findings(source, output_dir))
+
+    output = args.output or targets.output_dir / "python2rust.sarif"
+    output.parent.mkdir(parents=True, exist_ok=True)
+    output.write_text(json.dumps(to_sarif(findings, args.root, __version__), indent=2))
+    logger.info(f"Wrote {len(findings)} findings to {output}")
+
+    counts = {}
+    for finding in findings:
+        counts[finding.rule] = counts.get(finding.rule, 0) + 1
+    if json_output(args):
+        return print_json(args, True, {"sarif_file": str(output), "findings": counts})
+    print(f"Wrote {output}: {len(findings)} findings")
+    for rule, count in sorted(counts.items()):
+        print(f"  {rule}: {count}")
+    return True
@@ -0,0 +1,38 @@
+"""
+The status subcommand: show the migration state of each Python module.
+"""
+import argparse
+
+from .common import add_source_arguments, json_output, print_json, resolve_targets
+from ..migrator import Migrator
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "status",
+        help="Show each module as not started, generated, build failing, verified or stale",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.set_defaults(handler=run)
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+
+    migrator = Migrator().target_dir(targets.output_dir)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+
+    status = migrator.status()
+    if json_output(args):
+        return print_json(args, True, status.to_dict())
+    print(status.format())
+    return True
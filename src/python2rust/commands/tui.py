@@ -0,0 +1,177 @@
+"""
+The tui subcommand: browse a migration run in the terminal.
+"""
+import argparse
+import curses
+from pathlib import Path
+from typing import List
+
+from .common import fail, json_output, print_json
+from ..config.pricing import load_pricing
+from ..runs import FileStatus, RunBrowser, RunStore
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+STATUS_MARKS = {
+    FileStatus.VERIFIED: "+",
+    FileStatus.GENERATED: "~",
+    FileStatus.FAILED: "x",
+    FileStatus.IN_PROGRESS: ">",
+    FileStatus.CANCELLED: "-",
+    FileStatus.PENDING: " ",
+}
+
+HELP = "up/down file  tab code/logs  left/right log  pgup/pgdn scroll  q quit"
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "tui",
+        help="Browse the files, cargo logs, code and token usage of a run",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    parser.add_argument(
+        "run_id",
+        nargs="?",
+        help="Run to browse (defaults to the latest run)"
+    )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=Path("generated"),
+        help="Output directory of the run"
+    )
+    parser.set_defaults(handler=run)
+
+
+def side_by_side(left: List[str], right: List[str], width: int) -> List[str]:
+    """Two numbered columns sharing width characters."""
+    column = max(1, (width - 3) // 2)
+    rows = []
+    for number in range(max(len(left), len(right))):
+        cells = []
+        for lines in (left, right):
+            text = f"{number + 1:>4} {lines[number]}" if number < len(lines) else ""
+            cells.append(text.expandtabs(4)[:column].ljust(column))
+        rows.append(" | ".join(cells))
+    return rows
+
+
+class RunView:
+    """Curses screen over a RunBrowser: file list on the left, code or logs on the right."""
+
+    def __init__(self, browser: RunBrowser):
+        self.browser = browser
+        self.selected = 0
+        self.show_logs = False
+        self.log_index = 0
+        self.scroll = 0
+        self.offset = 0  # first file of the list shown
+
+    @property
+    def record(self):
+        return self.browser.files[self.selected]
+
+    def _content(self, width: int) -> List[str]:
+        record = self.record
+        if not self.show_logs:
+            python = record.python_code().splitlines()
+            rust = record.rust_code().splitlines() or ["(no Rust code generated)"]
+            return side_by_side(python, rust, width)
+        if not record.logs:
+            return ["(no cargo or server logs for this file in this run)"]
+        log = record.logs[min(self.log_index, len(record.logs) - 1)]
+        lines = [f"log {self.log_index + 1}/{len(record.logs)}: {log.name}", ""]
+        try:
+            return lines + log.read_text(errors="replace").splitlines()
+        except OSError as e:
+            return lines + [f"(unreadable: {e})"]
+
+    def _put(self, screen, row: int, col: int, text: str, width: int, attr: int = 0) -> None:
+        try:
+            screen.addnstr(row, col, text, max(0, width), attr)
+        except curses.error:
+            pass  # writing the bottom-right cell raises
+
+    def draw(self, screen) -> None:
+        screen.erase()
+        height, width = screen.getmaxyx()
+        checkpoint = self.browser.checkpoint
+        self._put(screen, 0, 0, f"run {checkpoint.run_id}  {checkpoint.status.value}  "
+                  f"total: {self.browser.total_usage().format()}", width, curses.A_BOLD)
+        self._put(screen, height - 1, 0, HELP, width, curses.A_DIM)
+
+        list_width = min(32, max(12, width // 4))
+        rows = max(1, height - 3)
+        # Scroll the list to keep the selected file in view
+        self.offset = min(self.offset, self.selected)
+        self.offset = max(self.offset, self.selected - rows + 1)
+        for row, record in enumerate(self.browser.files[self.offset:self.offset + rows]):
+            mark = STATUS_MARKS.get(record.checkpoint.status, "?")
+            attr = curses.A_REVERSE if self.offset + row == self.selected else 0
+            self._put(screen, row + 2, 0, f"{mark} {record.source.name}".ljust(list_width - 1),
+                      list_width - 1, attr)
+
+        record = self.record
+        file = record.checkpoint
+        left = list_width + 1
+        stage = f", {file.stage} iteration {file.iteration}" if file.stage else ""
+        self._put(screen, 1, 0, f"{file.status.value}{stage}  {record.usage.format()}", width)
+        if file.error and file.status != FileStatus.VERIFIED:
+            self._put(screen, 2, left, f"error: {file.error.splitlines()[0]}", width - left)
+
+        content = self._content(width - left)
+        top = 3
+        visible = max(0, height - top - 1)
+        self.scroll = max(0, min(self.scroll, len(content) - visible))
+        for row, line in enumerate(content[self.scroll:self.scroll + visible]):
+            self._put(screen, top + row, left, line, width - left)
+        screen.refresh()
+
+    def handle(self, key: int, page: int) -> bool:
+        """Apply a key press; False to quit."""
+        if key in (ord("q"), 27):
+            return False
+        if key in (curses.KEY_UP, ord("k")) and self.selected > 0:
+            self.selected, self.scroll, self.log_index = self.selected - 1, 0, 0
+        elif key in (curses.KEY_DOWN, ord("j")) and self.selected < len(self.browser.files) - 1:
+            self.selected, self.scroll, self.log_index = self.selected + 1, 0, 0
+        elif key == ord("\t"):
+            self.show_logs, self.scroll = not self.show_logs, 0
+        elif key in (curses.KEY_LEFT, ord("h")) and self.log_index > 0:
+            self.log_index, self.scroll = self.log_index - 1, 0
+        elif key in (curses.KEY_RIGHT, ord("l")) and self.log_index < len(self.record.logs) - 1:
+            self.log_index, self.scroll = self.log_index + 1, 0
+        elif key == curses.KEY_NPAGE:
+            self.scroll += page
+        elif key == curses.KEY_PPAGE:
+            self.scroll = max(0, self.scroll - page)
+        return True
+
+    def loop(self, screen) -> None:
+        curses.curs_set(0)
+        screen.keypad(True)
+        while True:
+            self.draw(screen)
+            if not self.handle(screen.getch(), max(1, screen.getmaxyx()[0] - 4)):
+                return
+
+
+def run(args: argparse.Namespace) -> bool:
+    store = RunStore(args.output_dir)
+    try:
+        checkpoint = store.load(args.run_id) if args.run_id else store.latest()
+    except FileNotFoundError as e:
+        return fail(args, str(e))
+    if checkpoint is None:
+        return fail(args, f"No runs found in {store.runs_dir}")
+
+    browser = RunBrowser(store, checkpoint, pricing=load_pricing())
+    if json_output(args):
+        # The data behind the interface, for scripts
+        return print_json(args, True, browser.to_dict())
+    if not checkpoint.files:
+        return fail(args, f"Run {checkpoint.run_id} has no files")
+    curses.wrapper(RunView(browser).loop)
+    return True
@@ -0,0 +1,155 @@
+"""
+The watch subcommand: keep the Rust translation in sync while the Python code is edited.
+"""
+import argparse
+import asyncio
+import signal
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from .common import (
+    add_source_arguments,
+    attach_progress,
+    check_token_files,
+    default_settings,
+    json_output,
+    print_json,
+    resolve_targets,
+    Targets
+)
+from ..config.project_config import ProjectConfig
+from ..migrator import Migrator, discover_python_files
+from ..report import MigrationReport
+from ..utils.file_watcher import FileWatcher
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def register(subparsers: argparse._SubParsersAction) -> None:
+    parser = subparsers.add_parser(
+        "watch",
+        help="Re-migrate and re-verify modules whenever their Python source changes",
+        formatter_class=argparse.ArgumentDefaultsHelpFormatter
+    )
+    add_source_arguments(parser)
+    parser.add_argument(
+        "--workers",
+        type=int,
+        default=None,
+        help="Number of files migrated concurrently (defaults to the max_workers setting)"
+    )
+    parser.add_argument(
+        "--interval",
+        type=float,
+        default=1.0,
+        help="Seconds between checks for changed files"
+    )
+    parser.set_defaults(handler=run)
+
+
+def watched_files(targets: Targets) -> List[Path]:
+    if targets.python_file is not None:
+        return [targets.python_file]
+    return discover_python_files(targets.source_dir)
+
+
+def build_migrator(
+    targets: Targets,
+    tokens: Dict[str, Optional[str]],
+    workers: Optional[int]
+) -> Migrator:
+    migrator = Migrator(default_settings(targets.output_dir, tokens))
+    if targets.config_file is not None:
+        migrator.project_config(ProjectConfig.load(targets.config_file), targets.config_file.parent)
+    migrator.target_dir(targets.output_dir).tokens(**tokens)
+    if targets.python_file is not None:
+        migrator.source_file(targets.python_file)
+    else:
+        migrator.source_dir(targets.source_dir)
+    if workers is not None:
+        migrator.workers(workers)
+    return migrator
+
+
+def print_sync(report: MigrationReport) -> None:
+    """One line per file that was migrated again; unchanged files are only counted."""
+    reused = 0
+    for file in report.files:
+        if (file.metrics.get("reused") or file.metrics.get("accepted")
+                or file.metrics.get("kept_python")):
+            reused += 1
+            continue
+        status = "verified" if file.success else f"FAILED: {file.error}"
+        print(f"  {file.source}: {status}")
+    print(f"  {reused} unchanged, {len(report.succeeded)}/{len(report.files)} in sync")
+
+
+async def watch(
+    targets: Targets,
+    workers: Optional[int],
+    interval: float,
+    args: Optional[argparse.Namespace] = None
+) -> bool:
+    """Sync until interrupted; with --format json each sync prints one JSON document per line."""
+    as_json = json_output(args)
+    tokens = check_token_files()
+    targets.output_dir.mkdir(parents=True, exist_ok=True)
+    stop = asyncio.Event()
+    running: List[Migrator] = []
+
+    def interrupt() -> None:
+        stop.set()
+        for migrator in running:
+            migrator.cancel()
+
+    try:
+        asyncio.get_running_loop().add_signal_handler(signal.SIGINT, interrupt)
+    except (NotImplementedError, RuntimeError):
+        pass  # Not supported on Windows event loops
+
+    watcher = FileWatcher(lambda: watched_files(targets), interval=interval)
+    snapshot = watcher.snapshot()
+    changed: List[Path] = list(snapshot)
+    while not stop.is_set():
+        if not as_json:
+            print(f"\nSyncing {len(changed)} changed files")
+        migrator = build_migrator(targets, tokens, workers)
+        running[:] = [migrator]
+        progress = attach_progress(migrator, args, len(migrator.outputs()))
+        try:
+            report, error = await migrator.migrate(), None
+        except Exception as e:
+            logger.exception(f"Watch migration error: {str(e)}")
+            report, error = None, e
+        if progress is not None:
+            progress.close()
+
+        changed_files = [str(path) for path in changed]
+        if report is not None and as_json:
+            print_json(args, report.success,
+                       {"changed": changed_files, **report.to_dict()}, indent=None)
+        elif report is not None:
+            print_sync(report)
+        elif as_json:
+            print_json(args, False, {"changed": changed_files}, error=str(error), indent=None)
+        else:
+            print(f"  Migration error: {error}")
+        running.clear()
+
+        if stop.is_set():
+            break
+        if not as_json:
+            print(f"Watching {len(snapshot)} files for changes (Ctrl-C to stop)")
+        changed, snapshot = await watcher.wait_for_changes(snapshot, stop)
+
+    if not as_json:
+        print("\nStopped watching")
+    return True
+
+
+def run(args: argparse.Namespace) -> bool:
+    targets = resolve_targets(args)
+    if targets is None:
+        return False
+    return asyncio.run(watch(targets, args.workers, args.interval, args))
@@ -0,0 +1,8 @@
+# config/__init__.py
+from .crate_mappings import CrateMapping, load_crate_mappings, mappings_by_name, normalize_name
+from .project_config import CONFIG_FILE_NAME, ProjectConfig, find_project_config
+
+__all__ = [
+    'CrateMapping', 'load_crate_mappings', 'mappings_by_name', 'normalize_name',
+    'CONFIG_FILE_NAME', 'ProjectConfig', 'find_project_config'
+]
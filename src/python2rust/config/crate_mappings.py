@@ -0,0 +1,54 @@
+import json
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from .settings import get_config_path
+
+
+@dataclass
+class CrateMapping:
+    """A Python library and the Rust crate usually replacing it."""
+    python: str
+    crate: str
+    category: str
+    aliases: List[str] = field(default_factory=list)
+    version: str = "*"  # version requirement of the crate
+    features: List[str] = field(default_factory=list)
+
+    def dependency(self) -> str:
+        """The [dependencies] line of the crate."""
+        if not self.features:
+            return f'{self.crate} = "{self.version}"'
+        features = ", ".join(f'"{feature}"' for feature in self.features)
+        return f'{self.crate} = {{ version = "{self.version}", features = [{features}] }}'
+
+
+def load_crate_mappings(path: Optional[Path] = None,
+                        extra: Optional[Path] = None) -> List[CrateMapping]:
+    """Load the built-in Python library to Rust crate mappings, and those of a file of the same
+    format replacing the built-in mappings of the libraries it maps and adding the others."""
+    path = path or get_config_path() / "crate_mappings.json"
+    data = json.loads(Path(path).read_text())
+    mappings = [CrateMapping(**mapping) for mapping in data["mappings"]]
+    if extra is not None:
+        added = [CrateMapping(**mapping)
+                 for mapping in json.loads(Path(extra).read_text())["mappings"]]
+        replaced = {normalize_name(mapping.python) for mapping in added}
+        mappings = [mapping for mapping in mappings
+                    if normalize_name(mapping.python) not in replaced] + added
+    return mappings
+
+
+def normalize_name(name: str) -> str:
+    """Compare distribution and import names case and separator insensitively."""
+    return name.lower().replace("-", "_")
+
+
+def mappings_by_name(mappings: List[CrateMapping]) -> Dict[str, CrateMapping]:
+    """Index mappings by import name and every alias."""
+    index = {}
+    for mapping in mappings:
+        for name in [mapping.python, *mapping.aliases]:
+            index[normalize_name(name)] = mapping
+    return index
@@ -0,0 +1,20 @@
+import json
+from dataclasses import dataclass
+from pathlib import Path
+from typing import List, Optional
+
+from .settings import get_config_path
+
+
+@dataclass
+class DecoratorStrategy:
+    """A known decorator and what its decorated function or class becomes in Rust."""
+    name: str  # as imported, e.g. functools.wraps; * matches any object, as in *.route
+    strategy: str
+
+
+def load_decorator_strategies(path: Optional[Path] = None) -> List[DecoratorStrategy]:
+    """Load the built-in catalog of decorators and their translation strategies."""
+    path = path or get_config_path() / "decorators.json"
+    data = json.loads(Path(path).read_text())
+    return [DecoratorStrategy(**decorator) for decorator in data["decorators"]]
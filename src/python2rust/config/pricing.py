@@ -0,0 +1,25 @@
+import json
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, Optional
+
+from .settings import get_config_path
+
+
+@dataclass
+class ModelPricing:
+    """Price of a model in dollars per million tokens."""
+    model: str
+    input_per_mtok: float
+    output_per_mtok: float
+
+    def cost(self, input_tokens: int, output_tokens: int) -> float:
+        return (input_tokens * self.input_per_mtok
+                + output_tokens * self.output_per_mtok) / 1_000_000
+
+
+def load_pricing(path: Optional[Path] = None) -> Dict[str, ModelPricing]:
+    """Load model prices keyed by model name."""
+    path = path or get_config_path() / "pricing.json"
+    data = json.loads(Path(path).read_text())
+    return {entry["model"]: ModelPricing(**entry) for entry in data["models"]}
@@ -0,0 +1,251 @@
+import sys
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Union
+
+from pydantic import BaseModel, Field
+
+from .settings import (
+    BudgetLimits, CliComparison, CodePolicies, DependencyAudit,
+    HotFunctionBenchmarks, HttpComparison, LicenseCheck, MemoryComparison, OpenApiComparison,
+    SandboxConfig, SchemaComparison, ShellCheck, StageParameters,
+    VerificationLimits, WorkloadProfile
+)
+
+if sys.version_info >= (3, 11):
+    import tomllib
+else:
+    import tomli as tomllib
+
+CONFIG_FILE_NAME = "python2rust.toml"
+
+
+class ProjectSection(BaseModel):
+    """Where the Python project lives and where the Rust output goes."""
+    name: str = ""
+    source_dir: Path = Path(".")
+    target_dir: Path = Path("generated")
+    entry_points: List[Path] = Field(default_factory=list)
+    tests_dir: Optional[Path] = None
+
+
+class MigrationSection(BaseModel):
+    """Defaults for the migrate command."""
+    checkers: List[str] = Field(default_factory=lambda: ["build", "test"])
+    workers: int = Field(default=4, ge=1)
+    translation_passes: List[str] = Field(
+        default_factory=lambda: ["spec", "skeleton", "implementation", "tests"])
+    # Models generating competing candidates, the best verified one is kept
+    consensus: List[str] = Field(default_factory=list)
+    # Python tokens per generation call before modules are chunked; unset follows the model limits
+    chunk_tokens: Optional[int] = Field(default=None, ge=1)
+    # Tokens of imported modules included in full before using digests of their public API
+    dependency_context_tokens: int = Field(default=4000, ge=0)
+    # Related project definitions retrieved into each prompt, 0 to disable
+    context_definitions: int = Field(default=5, ge=0)
+    # Tokens of a module's pytest files translated into its Rust tests, 0 to disable
+    test_context_tokens: int = Field(default=8000, ge=0)
+    # Warn prompts and report about dynamic constructs, and give their files more fix attempts
+    risk_report: bool = True
+    # Require crates or FFI bindings for imports backed by compiled code, and list them in plans
+    native_dependencies: bool = True
+    # once_lock, app_state, injection or none: how every module holds module-level state
+    module_state: Optional[str] = "app_state"
+    # fail or convert Python 2 files, and the command converting them ({file} is the copy)
+    python2: str = "fail"
+    python2_command: Optional[str] = None
+    # Translate the code cells of notebooks as a "script" or only their definitions as a "library"
+    notebook_mode: str = "script"
+    # Leave unused functions and classes untranslated, listing them in the report
+    skip_dead_code: bool = False
+    # Plan the Rust struct, trait or enum of each class, migrating only approved plans if asked
+    class_plan: bool = True
+    approve_class_plans: bool = False
+    # Imports, signatures and features of each module, from its syntax tree, in the prompts
+    structure_context: bool = True
+    # Carry the docstrings onto the Rust items as rustdoc comments
+    rust_docs: bool = True
+    # Keep the comments in the Rust code, checking each function repeats those of its Python one
+    keep_comments: bool = True
+    # Translate the doctests of the docstrings into Rust tests
+    doctests: bool = True
+    # Translate generators into lazy Rust iterators, tested element by element against Python
+    generators: bool = True
+    # Translate context managers into RAII guards cleaning up in Drop, tested on the error path
+    context_managers: bool = True
+    # Share one thiserror error module, generated from the exception classes, across the crates
+    error_module: bool = True
+    # Convert f-string, str.format and % format specs, tested against Python's formatted output
+    format_specs: bool = True
+    # Gather the crates of the modules of each package into a Cargo workspace, a crate per package
+    workspace: bool = True
+    # Mirror the visibility of items and the package layout, re-exporting what __init__ exports
+    visibility: bool = True
+    # Convert identifiers to Rust's conventions, escaping keywords the keyword_style
+    # way: raw or suffix
+    naming: bool = True
+    keyword_style: str = "raw"
+    # Async runtime the generated code targets: tokio, async-std, or sync for synchronous code
+    async_runtime: str = "tokio"
+    # Web framework of the services generated from web apps: axum, actix-web or rocket
+    web_framework: str = "axum"
+    # Template engine of the Jinja templates: askama, tera or maud
+    template_engine: str = "askama"
+    # Data access stack of the modules using a SQL database: sqlx, diesel or sea-orm
+    database_layer: str = "sqlx"
+    # Pin the crates of the requirements and imported libraries, resolved through the mappings
+    requirement_crates: bool = True
+    # JSON file of crate mappings over the built-in ones, relative to the project root
+    crate_mappings: Optional[Path] = None
+    # mypy or pyright, whose inferred types go into the prompts, and the command running it
+    type_checker: Optional[str] = None
+    type_checker_command: Optional[str] = None
+    # Command whose Python calls are recorded as regression tests, and the calls kept per function
+    trace_command: Optional[str] = None
+    traced_calls: int = Field(default=20, ge=1)
+    # Format generated code with rustfmt, fixing code that does not parse
+    rustfmt: bool = True
+    # Clippy lints denied (-D) and allowed (-A) in generated code; an empty clippy_deny skips clippy
+    clippy_deny: List[str] = Field(default_factory=lambda: ["warnings"])
+    clippy_allow: List[str] = Field(default_factory=list)
+    # Run cargo test on the generated crates, fixing the code until the tests pass
+    cargo_test: bool = True
+    # Run the tests under Miri with these MIRIFLAGS and toolchain, fixing undefined behavior
+    miri: bool = False
+    miri_flags: List[str] = Field(default_factory=list)
+    miri_toolchain: str = "nightly"
+    # Random inputs per function compared between Python and Rust, 0 to disable fuzzing
+    fuzz_cases: int = Field(default=0, ge=0)
+    # Flag numeric semantics risks and compare boundary values between Python and Rust
+    numeric_checks: bool = False
+    # Flag string operations counting bytes in Rust and compare them on multi-byte text
+    unicode_checks: bool = False
+    # Measure the release builds of verified crates, warning when they grew since the previous run
+    build_stats: bool = False
+    # Answers as tool-calling JSON rather than fenced code blocks
+    structured_output: bool = False
+    # Keep secrets out of prompts; secret_patterns are regexes of project-specific secrets
+    redact_secrets: bool = True
+    secret_patterns: List[str] = Field(default_factory=list)
+    # Model requests in flight at once across all files
+    max_concurrent_requests: int = Field(default=8, ge=1)
+    # Directory of prompt templates (<template>.txt) overriding the packaged ones
+    prompts_dir: Optional[Path] = None
+
+
+class CrateSpec(BaseModel):
+    """A [crates] entry given as a table: the crate, its version requirement and features."""
+    crate: str
+    version: Optional[str] = None
+    features: Optional[List[str]] = None
+
+
+class ProjectConfig(BaseModel):
+    """Contents of python2rust.toml."""
+    project: ProjectSection = Field(default_factory=ProjectSection)
+    frameworks: List[str] = Field(default_factory=list)
+    # [crates] crate replacing a Python library, by name or as a {crate, version, features} table
+    crates: Dict[str, Union[str, CrateSpec]] = Field(default_factory=dict)
+    # [decorators] translation strategy of decorators, by dotted name or pattern like "*.route"
+    decorators: Dict[str, str] = Field(default_factory=dict)
+    migration: MigrationSection = Field(default_factory=MigrationSection)
+    # [budget] run_cost, run_tokens, file_cost, file_tokens and warn_at fractions
+    budget: BudgetLimits = Field(default_factory=BudgetLimits)
+    # [http] enabled, headers, ignore and startup_timeout of the comparison of web app responses,
+    # and [http.cases.<name>] method, path, body and headers of requests sent besides the routes
+    http: HttpComparison = Field(default_factory=HttpComparison)
+    # [openapi] enabled, path, ignore_responses and startup_timeout of the comparison of the
+    # OpenAPI descriptions of web apps
+    openapi: OpenApiComparison = Field(default_factory=OpenApiComparison)
+    # [cli] enabled, argv, stdin, stderr, ignore and timeout of the comparison of command-line
+    # scripts, and [cli.cases.<name>] args and stdin of runs besides the argv x stdin matrix
+    cli: CliComparison = Field(default_factory=CliComparison)
+    # [memory] enabled, allocations and valgrind of the memory measures of the compared runs
+    memory: MemoryComparison = Field(default_factory=MemoryComparison)
+    # [database] enabled, engine, python_command, rust_command, ignore_tables, postgres_image and
+    # docker of the comparison of the database schemas of the Python models and the Rust code
+    database: SchemaComparison = Field(default_factory=SchemaComparison)
+    # [limits.build], [limits.test], [limits.miri], [limits.run] and [limits.benchmark] timeout
+    # (seconds) and memory_mb of the verification steps
+    limits: VerificationLimits = Field(default_factory=VerificationLimits)
+    # [policies] no_unwrap, no_expect, no_unsafe, no_panic, max_function_lines and forbidden_macros
+    # of generated code outside tests
+    policies: CodePolicies = Field(default_factory=CodePolicies)
+    # [audit] enabled, fail, licenses and ignore of the cargo-deny audit of the dependencies
+    audit: DependencyAudit = Field(default_factory=DependencyAudit)
+    # [licenses] enabled and allow of the check of the licenses of the crates of the requirements
+    licenses: LicenseCheck = Field(default_factory=LicenseCheck)
+    # [benchmarks] enabled, functions, min_loop_depth and criterion of the benchmarks of hot
+    # functions
+    benchmarks: HotFunctionBenchmarks = Field(default_factory=HotFunctionBenchmarks)
+    # [profile] command, threshold, limit and timeout of the cProfile run of a workload whose hot
+    # functions are migrated first, or alone
+    profile: WorkloadProfile = Field(default_factory=WorkloadProfile)
+    # [sandbox] enabled, image, network, server_network, memory, cpus, pids, mounts and docker
+    # of the container generated code is built and run in
+    sandbox: SandboxConfig = Field(default_factory=SandboxConfig)
+    # [shell_checkers.<name>] command, exit_codes and timeout of extra checks of
+    # the generated crates
+    shell_checkers: Dict[str, ShellCheck] = Field(default_factory=dict)
+    # provider -> requests per minute, e.g. [requests_per_minute] anthropic = 50
+    requests_per_minute: Dict[str, int] = Field(default_factory=dict)
+    # migration step -> model (claude, openai, azure, gemini, bedrock, ollama, codellama, ...)
+    models: Dict[str, str] = Field(default_factory=dict)
+    # migration step -> temperature, max_tokens, top_p, stop and system_prompt of its requests,
+    # e.g. [stages.tests] temperature = 0.7
+    stages: Dict[str, StageParameters] = Field(default_factory=dict)
+    # model -> LLMConfig fields to override, e.g. [model_settings.azure] deployment = "..."
+    model_settings: Dict[str, Dict[str, Any]] = Field(default_factory=dict)
+
+    @classmethod
+    def load(cls, path: Path) -> "ProjectConfig":
+        with open(path, "rb") as f:
+            return cls.model_validate(tomllib.load(f))
+
+    def save(self, path: Path) -> None:
+        Path(path).write_text(dump_toml(self.model_dump(mode="json", exclude_none=True)))
+
+
+def find_project_config(start: Path = Path(".")) -> Optional[Path]:
+    """Look for python2rust.toml in a directory and its parents."""
+    start = Path(start).resolve()
+    for directory in [start, *start.parents]:
+        candidate = directory / CONFIG_FILE_NAME
+        if candidate.exists():
+            return candidate
+    return None
+
+
+def _toml_value(value: Any) -> str:
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, (int, float)):
+        return str(value)
+    if isinstance(value, (list, tuple)):
+        return "[" + ", ".join(_toml_value(v) for v in value) + "]"
+    text = str(value).replace("\\", "\\\\").replace('"', '\\"')
+    return f'"{text}"'
+
+
+def _toml_key(key: str) -> str:
+    if key and all(c.isalnum() or c in "_-" for c in key):
+        return key
+    return _toml_value(key)
+
+
+def _dump_table(data: Dict[str, Any], path: List[str]) -> List[str]:
+    lines = [f"{_toml_key(k)} = {_toml_value(v)}" for k, v in data.items()
+             if not isinstance(v, dict)]
+    for table, values in data.items():
+        if not isinstance(values, dict):
+            continue
+        name = [*path, _toml_key(table)]
+        if lines:
+            lines.append("")
+        lines.append(f"[{'.'.join(name)}]")
+        lines.extend(_dump_table(values, name))
+    return lines
+
+
+def dump_toml(data: Dict[str, Any]) -> str:
+    """Serialize a dict to TOML, nested dicts becoming (dotted) tables."""
+    return "\n".join(_dump_table(data, [])) + "\n"
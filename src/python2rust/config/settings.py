@@ -1,7 +1,7 @@
 # config/settings.py
 from pathlib import Path
-from typing import Dict, List, Optional, Tuple
-from pydantic import BaseModel, Field
+from typing import Any, Dict, List, Optional, Tuple
+from pydantic import BaseModel, Field, field_validator
 from pydantic_settings import BaseSettings
 import json
 from enum import Enum
@@ -35,6 +35,12 @@ class LLMChoice(str, Enum):
     CODELLAMA = "codellama"
     STARCODER = "starcoder"
     CODESTRAL = "codestral"
+    OPENAI = "openai"
+    GEMINI = "gemini"
+    BEDROCK = "bedrock"
+    AZURE = "azure"
+    OLLAMA = "ollama"
+    OPENAI_COMPATIBLE = "openai_compatible"
 
 
 class ModelParameters(BaseModel):
@@ -52,19 +58,282 @@ class ModelParameters(BaseModel):
 class LLMConfig(BaseModel):
     """Configuration for a specific LLM."""
     model: str
+    provider: str = "anthropic"  # name of the Provider serving this model
     endpoint_url: Optional[str] = None
+    region: Optional[str] = None  # cloud region, for providers hosted per region
+    # Azure deployment serving the model, defaults to the model name
+    deployment: Optional[str] = None
+    api_version: Optional[str] = None
+    context_length: Optional[int] = None  # context window in tokens, for local models
+    headers: Dict[str, str] = Field(default_factory=dict)  # extra HTTP headers of each request
     temperature: float = Field(default=0.1, ge=0.0, le=1.0)
     max_tokens: Optional[int] = None
     model_params: Optional[ModelParameters] = None
     fallback_model: Optional[str] = None  # Fallback model if this one fails
 
 
+# Staged translation passes, in the order they run
+TRANSLATION_PASSES = ("spec", "skeleton", "implementation", "tests")
+
+# Pipeline stages that each run on their own model
+MIGRATION_STEPS = ("analysis", "generation", "verification", "fixes", "tests", "build_fixes")
+
+
 class MigrationSteps(BaseModel):
     """Configuration for each migration step."""
     analysis: LLMChoice = Field(default=LLMChoice.CLAUDE)
     generation: LLMChoice = Field(default=LLMChoice.CLAUDE)
-    verification: LLMChoice = Field(default=LLMChoice.CLAUDE)
-    fixes: LLMChoice = Field(default=LLMChoice.CLAUDE)
+    verification: LLMChoice = Field(default=LLMChoice.CLAUDE)  # reviews the translation
+    fixes: LLMChoice = Field(default=LLMChoice.CLAUDE)         # fixes behavioral differences
+    tests: Optional[LLMChoice] = None        # writes the tests pass, defaults to generation
+    build_fixes: Optional[LLMChoice] = None  # fixes compiler and clippy errors, defaults to fixes
+
+    def model_for(self, step: str) -> LLMChoice:
+        """The model of a step, resolving the steps that default to another one."""
+        choice = getattr(self, step)
+        if choice is None:
+            return self.model_for({"tests": "generation", "build_fixes": "fixes"}[step])
+        return choice
+
+
+class StageParameters(BaseModel):
+    """Sampling parameters and system prompt of one pipeline stage; unset ones are the model's."""
+    temperature: Optional[float] = Field(default=None, ge=0.0, le=1.0)
+    max_tokens: Optional[int] = Field(default=None, ge=1)
+    top_p: Optional[float] = Field(default=None, gt=0.0, le=1.0)
+    stop: Optional[List[str]] = None
+    system_prompt: Optional[str] = None  # replaces the stage's system message
+
+
+class ShellCheck(BaseModel):
+    """A command run in each generated crate after it builds, e.g. an internal lint."""
+    command: str
+    exit_codes: List[int] = Field(default_factory=lambda: [0])  # codes meaning the check passed
+    timeout: float = Field(default=300, gt=0)  # seconds
+
+
+class HttpCase(BaseModel):
+    """A request sent to both the Python and the Rust server, besides those to the
+    discovered routes."""
+    method: str = "GET"
+    path: str = "/"
+    body: Optional[str] = None
+    headers: Dict[str, str] = Field(default_factory=dict)
+
+
+class HttpComparison(BaseModel):
+    """Comparison of the responses of a Python web app and of its Rust translation."""
+    enabled: bool = False
+    cases: Dict[str, HttpCase] = Field(default_factory=dict)
+    # HAR recordings of real traffic whose requests are replayed after the cases
+    har_files: List[Path] = Field(default_factory=list)
+    # Response headers that must agree, besides the status and the body
+    headers: List[str] = Field(default_factory=lambda: ["content-type"])
+    # Regexes of body parts that may differ, e.g. timings, replaced before comparing
+    ignore: List[str] = Field(default_factory=list)
+    # Selectors (tag, #id, .class) of HTML elements whose content may differ
+    ignore_elements: List[str] = Field(default_factory=list)
+    startup_timeout: float = Field(default=60, gt=0)  # seconds
+
+
+class StepLimits(BaseModel):
+    """Wall-clock and memory limits of the processes of a verification step; unset is unlimited."""
+    timeout: Optional[float] = Field(default=None, gt=0)  # seconds
+    memory_mb: Optional[int] = Field(default=None, ge=1)
+
+
+class VerificationLimits(BaseModel):
+    """Limits of each verification step; a step running out of time fails like a wrong result."""
+    # cargo check, build and clippy
+    build: StepLimits = Field(default_factory=lambda: StepLimits(timeout=300))
+    # cargo test and the fuzz harness
+    test: StepLimits = Field(default_factory=lambda: StepLimits(timeout=300))
+    # cargo miri test, which interprets the tests a hundred times slower or more
+    miri: StepLimits = Field(default_factory=lambda: StepLimits(timeout=1800))
+    # Generated programs: each command-line run, server request and server test script
+    run: StepLimits = Field(default_factory=lambda: StepLimits(timeout=60))
+    # The whole workload of each server of the bench command
+    benchmark: StepLimits = Field(default_factory=lambda: StepLimits(timeout=600))
+
+
+class CliCase(BaseModel):
+    """Arguments and standard input a command-line script and its Rust binary are both run with."""
+    args: List[str] = Field(default_factory=list)
+    stdin: Optional[str] = None
+
+
+class CliComparison(BaseModel):
+    """Comparison of the output of a Python command-line script and of its Rust binary."""
+    enabled: bool = False
+    # Every argument list is run with every standard input, then the cases
+    argv: List[List[str]] = Field(default_factory=lambda: [[]])
+    stdin: List[str] = Field(default_factory=lambda: [""])
+    cases: Dict[str, CliCase] = Field(default_factory=dict)
+    # Compare standard error too, besides standard output and the exit code
+    stderr: bool = True
+    # Regexes of output parts that may differ, e.g. timings, replaced before comparing
+    ignore: List[str] = Field(default_factory=list)
+    # Seconds per run, the run limit when unset
+    timeout: Optional[float] = Field(default=None, gt=0)
+
+
+class OpenApiComparison(BaseModel):
+    """Comparison of the OpenAPI description of a Python web app with the one its
+    Rust service serves."""
+    enabled: bool = False
+    # Where the Rust service serves the OpenAPI document the generation prompt asks it to build
+    path: str = "/openapi.json"
+    # Response statuses left out, by default the validation errors FastAPI documents on every route
+    ignore_responses: List[str] = Field(default_factory=lambda: ["422"])
+    # Seconds the Rust service has to start
+    startup_timeout: float = Field(default=60, gt=0)
+
+
+class MemoryComparison(BaseModel):
+    """Memory usage of the Python and Rust runs of the web app and command-line comparisons."""
+    enabled: bool = False
+    # Count heap allocations too, in a second run of each under valgrind once the outputs agree
+    allocations: bool = True
+    valgrind: str = "valgrind"
+
+
+class SchemaComparison(BaseModel):
+    """Comparison of the database schemas the Python models and the Rust code create."""
+    enabled: bool = False
+    # sqlite, or postgres in a throwaway container of postgres_image
+    engine: str = "sqlite"
+    # Shell commands creating each schema in $DATABASE_URL, from the module's and the crate's
+    # directory; by default the SQLAlchemy metadata of the module is created with create_all,
+    # and the SQL of the crate's migrations/ or of its code is applied. {binary} is the Rust binary
+    python_command: Optional[str] = None
+    rust_command: Optional[str] = None
+    # Tables left out, such as the history tables of migration tools
+    ignore_tables: List[str] = Field(default_factory=lambda: [
+        "_sqlx_migrations", "__diesel_schema_migrations",
+        "refinery_schema_history", "seaql_migrations", "alembic_version", "django_migrations"
+    ])
+    postgres_image: str = "postgres:16"
+    docker: str = "docker"
+
+    @field_validator("engine")
+    @classmethod
+    def _check_engine(cls, engine: str) -> str:
+        if engine not in ("sqlite", "postgres"):
+            raise ValueError(f"Unknown database engine {engine}, expected sqlite or postgres")
+        return engine
+
+
+class CodePolicies(BaseModel):
+    """Hard rules for generated code outside tests; violations are fixed like build errors."""
+    no_unwrap: bool = False
+    no_expect: bool = False
+    no_unsafe: bool = False
+    # panic!, todo! and unimplemented!
+    no_panic: bool = False
+    # Lines from the signature to the closing brace
+    max_function_lines: Optional[int] = Field(default=None, ge=1)
+    # Other macros generated code may not call, e.g. dbg
+    forbidden_macros: List[str] = Field(default_factory=list)
+    # How every crate of the project handles errors: anyhow (application style), thiserror enums
+    # (library style) or std (std::error::Error implemented by hand)
+    error_style: Optional[str] = None
+
+    @property
+    def enabled(self) -> bool:
+        return (self.no_unwrap or self.no_expect or self.no_unsafe or self.no_panic
+                or self.max_function_lines is not None or bool(self.forbidden_macros)
+                or self.error_style is not None)
+
+    @field_validator("error_style")
+    @classmethod
+    def _check_error_style(cls, style: Optional[str]) -> Optional[str]:
+        if style not in (None, "anyhow", "thiserror", "std"):
+            raise ValueError(f"Unknown error style {style}, expected anyhow, thiserror or std")
+        return style
+
+
+# Permissive licenses, allowed by default
+PERMISSIVE_LICENSES = [
+    "MIT", "Apache-2.0", "Apache-2.0 WITH LLVM-exception", "BSD-2-Clause", "BSD-3-Clause", "ISC",
+    "Zlib", "Unicode-3.0", "Unicode-DFS-2016", "MPL-2.0", "CC0-1.0", "Unlicense", "BSL-1.0"
+]
+
+
+class DependencyAudit(BaseModel):
+    """cargo-deny check of the advisories and licenses of the dependency tree of
+    generated crates."""
+    enabled: bool = False
+    # Fail verification on a violation, asking for other dependencies, rather than only report it
+    fail: bool = False
+    # SPDX identifiers of the licenses dependencies may use
+    licenses: List[str] = Field(default_factory=lambda: list(PERMISSIVE_LICENSES))
+    # Advisories accepted anyway, e.g. RUSTSEC-2023-0071
+    ignore: List[str] = Field(default_factory=list)
+
+
+class LicenseCheck(BaseModel):
+    """Check of the crates.io licenses of the crates replacing the project's libraries, before
+    any code is generated."""
+    enabled: bool = False
+    # SPDX identifiers of the licenses the crates may use, e.g. without the GPL for a proprietary
+    # product
+    allow: List[str] = Field(default_factory=lambda: list(PERMISSIVE_LICENSES))
+
+
+class HotFunctionBenchmarks(BaseModel):
+    """criterion benchmarks and a Python timing script written to benches/ for the hot functions of
+    verified crates."""
+    enabled: bool = False
+    # Functions benchmarked whatever their loops, by name: the arguments of the measured call
+    functions: Dict[str, List[Any]] = Field(default_factory=dict)
+    # Nesting of loops from which a function is hot
+    min_loop_depth: int = Field(default=2, ge=1)
+    # Version of the criterion dev-dependency
+    criterion: str = "0.5"
+
+
+class WorkloadProfile(BaseModel):
+    """cProfile run of a Python workload finding the functions that take its CPU time."""
+    # Command running the workload, such as "python bench.py" or "pytest tests/perf";
+    # None profiles nothing
+    command: Optional[str] = None
+    # Share of the workload's CPU time from which a function is hot
+    threshold: float = Field(default=0.05, gt=0, le=1)
+    # Migrate only the hot functions, what they use and the files they need
+    limit: bool = False
+    # Seconds the workload may run before it is stopped, keeping its profile so far
+    timeout: float = Field(default=600, gt=0)
+
+
+class SandboxConfig(BaseModel):
+    """Docker container the generated code is built and run in, away from the
+    developer's machine."""
+    enabled: bool = False
+    image: str = "rust:1"
+    # Network of the builds and programs; servers need one their published port works on
+    network: str = "none"
+    server_network: str = "bridge"
+    memory: str = "2g"
+    cpus: float = Field(default=2.0, gt=0)
+    pids: int = Field(default=512, ge=1)
+    # Extra volumes as host:container[:ro]
+    mounts: List[str] = Field(default_factory=list)
+    docker: str = "docker"
+
+
+class BudgetLimits(BaseModel):
+    """Spending limits of a migration; unset limits are unlimited."""
+    run_cost: Optional[float] = Field(default=None, gt=0)  # USD per run
+    run_tokens: Optional[int] = Field(default=None, ge=1)
+    file_cost: Optional[float] = Field(default=None, gt=0)  # USD per file
+    file_tokens: Optional[int] = Field(default=None, ge=1)
+    # Fractions of a limit at which a warning is logged
+    warn_at: List[float] = Field(default_factory=lambda: [0.5, 0.8])
+
+    @property
+    def enabled(self) -> bool:
+        return any(limit is not None for limit in (
+            self.run_cost, self.run_tokens, self.file_cost, self.file_tokens))
 
 
 class Settings(BaseSettings):
@@ -74,9 +343,14 @@ class Settings(BaseSettings):
     output_dir: Path = Field(default=Path("generated"))
     debug_dir: Path = Field(default=Path("generated/debug"))
     specs_file: Path = Field(default_factory=get_default_specs_path)
+    plugins_dir: Path = Field(default=Path("plugins"))
+    # <template>.txt files overriding the packaged prompts
+    prompts_dir: Optional[Path] = Field(default=None)
 
     # LLM Configuration
     llm_steps: MigrationSteps = Field(default_factory=MigrationSteps)
+    # migration step -> parameters of its requests, e.g. a warmer tests step
+    stage_parameters: Dict[str, StageParameters] = Field(default_factory=dict)
     llm_configs: Dict[LLMChoice, LLMConfig] = Field(
         default_factory=lambda: {
             LLMChoice.CLAUDE: LLMConfig(
@@ -89,6 +363,7 @@ class Settings(BaseSettings):
             ),
             LLMChoice.CODELLAMA: LLMConfig(
                 model="codellama/CodeLlama-34b-Instruct-hf",
+                provider="huggingface",
                 temperature=0.2,
                 max_tokens=4000,
                 fallback_model=LLMChoice.CLAUDE,
@@ -103,6 +378,7 @@ class Settings(BaseSettings):
             ),
             LLMChoice.STARCODER: LLMConfig(
                 model="bigcode/starcoder2-15b",
+                provider="huggingface",
                 temperature=0.2,
                 max_tokens=4000,
                 fallback_model=LLMChoice.CLAUDE,
@@ -116,10 +392,51 @@ class Settings(BaseSettings):
             ),
             LLMChoice.CODESTRAL: LLMConfig(
                 model="codestral-latest",
+                provider="mistral",
                 temperature=0.1,
                 max_tokens=4000,
                 endpoint_url="https://codestral.mistral.ai/v1/chat/completions"
             ),
+            LLMChoice.OPENAI: LLMConfig(
+                model="gpt-4o",
+                provider="openai",
+                temperature=0.1,
+                max_tokens=4000
+            ),
+            LLMChoice.GEMINI: LLMConfig(
+                model="gemini-1.5-pro",
+                provider="google",
+                temperature=0.1,
+                max_tokens=4000
+            ),
+            LLMChoice.BEDROCK: LLMConfig(
+                model="anthropic.claude-3-5-sonnet-20241022-v2:0",  # Bedrock model id
+                provider="bedrock",
+                temperature=0.1,
+                max_tokens=4000
+            ),
+            LLMChoice.AZURE: LLMConfig(
+                model="gpt-4o",
+                provider="azure",
+                api_version="2024-10-21",
+                temperature=0.1,
+                max_tokens=4000
+            ),
+            LLMChoice.OLLAMA: LLMConfig(
+                model="qwen2.5-coder:14b",
+                provider="ollama",
+                endpoint_url="http://localhost:11434",
+                temperature=0.1,
+                max_tokens=4000,
+                context_length=16384
+            ),
+            # Set endpoint_url and model to the server, e.g. http://localhost:8000/v1 for vLLM
+            LLMChoice.OPENAI_COMPATIBLE: LLMConfig(
+                model="default",
+                provider="openai_compatible",
+                temperature=0.1,
+                max_tokens=4000
+            ),
         }
     )
 
@@ -127,6 +444,257 @@ class Settings(BaseSettings):
     max_attempts: int = Field(default=10)
     max_fixes_per_attempt: int = Field(default=10)
     build_timeout: int = Field(default=300)  # seconds
+    # Environment of the cargo commands building and testing the code, e.g. the DATABASE_URL
+    # sqlx's query! macros check the queries against
+    build_env: Dict[str, str] = Field(default_factory=dict)
+    max_workers: int = Field(default=4, ge=1)  # files migrated concurrently
+    apply_fixes: bool = Field(default=True)  # False only reports failures, as in check mode
+    # Fix attempts added to those of each workflow, raised per file for its risky constructs
+    extra_fix_attempts: int = Field(default=0, ge=0)
+    # Scan each file for dynamic constructs (eval, monkeypatching, metaclasses, reflection...),
+    # warning its prompts and report about them and giving it more fix attempts
+    risk_report: bool = Field(default=True)
+    # Find the imports backed by compiled code (numpy, lxml, the project's .so modules...), asking
+    # for their replacement crates or FFI bindings and failing verification without either
+    native_dependencies: bool = Field(default=True)
+    # How the module-level globals the code changes and its singletons are held, the same way in
+    # every module: "once_lock" statics, fields of an "app_state" struct or "injection" as
+    # parameters; singletons nothing changes are OnceLock statics unless injected. "none" (or
+    # None) leaves it to each generation
+    module_state: Optional[str] = Field(default="app_state")
+    # What to do with Python 2 source: "fail" reports its files as failed with the constructs
+    # found, "convert" runs python2_command (2to3 by default) on a copy before translating it
+    python2: str = Field(default="fail")
+    python2_command: Optional[str] = Field(default=None)
+    # How the code cells of .ipynb sources are translated: "script" keeps them all as a program,
+    # "library" only their imports, definitions and assignments
+    notebook_mode: str = Field(default="script")
+    # Leave out of the translation the top-level functions and classes that nothing reachable
+    # from the entry points (or, without any, nothing at all) uses, listing them in the report
+    skip_dead_code: bool = Field(default=False)
+    # Plan the Rust item of each class from its hierarchy (structs, traits for the bases
+    # subclasses extend, enums for those told apart with isinstance) for the prompts and
+    # verification; with approve_class_plans a file with classes is only migrated once its plan
+    # is approved with `python2rust classes`
+    class_plan: bool = Field(default=True)
+    approve_class_plans: bool = Field(default=False)
+    # Translation strategy of decorators by dotted name or pattern (*.route), from the
+    # [decorators] table of python2rust.toml, taking precedence over the built-in catalog
+    decorators: Dict[str, str] = Field(default_factory=dict)
+
+    # Model requests in flight at once, shared by the files migrated concurrently
+    max_concurrent_requests: int = Field(default=8, ge=1)
+    # provider name -> requests per minute it accepts, e.g. {"anthropic": 50}
+    requests_per_minute: Dict[str, int] = Field(default_factory=dict)
+    # Retries of a request answered with a rate-limit or overloaded error, with
+    # exponential backoff (in seconds) and jitter
+    max_request_retries: int = Field(default=6, ge=0)
+    retry_base_delay: float = Field(default=1.0, gt=0)
+    retry_max_delay: float = Field(default=60.0, gt=0)
+    # Seconds before a model request counts as stalled and fails; None waits indefinitely
+    request_timeout: Optional[float] = Field(default=600.0, gt=0)
+
+    # Staged translation; an empty list uses single-shot generation
+    translation_passes: List[str] = Field(default_factory=lambda: list(TRANSLATION_PASSES))
+    max_pass_attempts: int = Field(default=2, ge=1)
+
+    # Generate with each of these models and keep the candidate verification scores best;
+    # empty generates with the generation model alone
+    consensus_models: List[LLMChoice] = Field(default_factory=list)
+
+    # Python tokens per generation call; larger modules are translated in chunks split at
+    # class and function boundaries. None derives it from the generation model's limits
+    chunk_tokens: Optional[int] = Field(default=None, ge=1)
+
+    # Tokens of imported modules' generated Rust included in a prompt in full; the other
+    # imported modules are given as digests of their public API
+    dependency_context_tokens: int = Field(default=4000, ge=0)
+
+    # Project definitions most similar to a file added to its prompts; 0 disables retrieval
+    context_definitions: int = Field(default=5, ge=0)
+
+    # Tokens of the project's pytest files of a module given to translate into its Rust
+    # tests; 0 leaves the Python tests untranslated
+    test_context_tokens: int = Field(default=8000, ge=0)
+
+    # Give the prompts the structure the syntax tree of each module shows: imports, classes,
+    # signatures, and the lines using generators, exceptions and other features to map
+    structure_context: bool = Field(default=True)
+    # Ask for the docstrings as /// comments on the Rust items, their Google, NumPy or reST
+    # sections converted to rustdoc ones, and report the items left without them
+    rust_docs: bool = Field(default=True)
+    # Ask for the comments next to the Rust code translating the statements they are at, and
+    # fix the code until its functions repeat them
+    keep_comments: bool = Field(default=True)
+    # Ask for a #[test] per doctest of the docstrings, and report the examples left without one
+    doctests: bool = Field(default=True)
+    # Translate generator functions and expressions into lazy iterators, with a test per
+    # generator function comparing its elements with those of the Python generator
+    generators: bool = Field(default=True)
+    # Translate context managers into guards cleaning up in Drop, on early returns and errors
+    # too, with a test of the error path of each context manager the module defines
+    context_managers: bool = Field(default=True)
+    # Generate one error module from the project's exception classes (thiserror enums mirroring
+    # the hierarchy, From conversions, the Python messages) as an errors crate every crate depends
+    # on with `mod error;`, failing code with String or Box<dyn Error> errors of its own
+    error_module: bool = Field(default=True)
+    # Convert the format specs of f-strings, str.format and % formatting into Rust placeholders,
+    # or into helpers where Rust cannot reproduce them, tested against Python's output
+    format_specs: bool = Field(default=True)
+    # When the modules are in several packages, also generate a Cargo workspace with a library
+    # crate per package, the package crates it imports as path dependencies
+    workspace: bool = Field(default=True)
+    # Mirror the visibility of module items from __all__ and leading underscores, and lay out the
+    # package crates of the workspace as the packages, re-exporting what their __init__ exports
+    visibility: bool = Field(default=True)
+    # Give the Python identifiers their Rust names (snake_case, CamelCase types,
+    # SCREAMING_SNAKE_CASE # constants), converted the same way in every module and recorded
+    # in the source maps
+    naming: bool = Field(default=True)
+    # How identifiers colliding with a Rust keyword are escaped: raw (r#type) or suffix (type_)
+    keyword_style: str = Field(default="raw")
+    # Async runtime of the generated code: tokio, async-std, or sync for synchronous code, which
+    # picks its dependencies, main and test attributes and sleep, spawn and gather idioms
+    async_runtime: str = Field(default="tokio")
+    # Web framework the services of Flask and FastAPI apps are generated against: axum, actix-web
+    # or rocket, whose route, extractor, state and server idioms the prompts give
+    web_framework: str = Field(default="axum")
+    # Template engine the Jinja templates are rendered with: askama or tera, templates written into
+    # the crate's templates/ directory, or maud, converted into html! markup
+    template_engine: str = Field(default="askama")
+    # Data access stack the modules using a SQL driver are generated against: sqlx, diesel or
+    # sea-orm, with its connection pool, checked queries and migrations of the tables they create
+    database_layer: str = Field(default="sqlx")
+    # Resolve the project's requirements and the libraries each module imports through the crate
+    # mapping database, pinning the [dependencies] lines of their crates in every Cargo.toml
+    requirement_crates: bool = Field(default=True)
+    # Directory of the project's pyproject.toml and requirements*.txt, by default the nearest one
+    # up from the sources
+    requirements_dir: Optional[Path] = Field(default=None)
+    # JSON file of crate mappings in the format of config/crate_mappings.json, replacing the
+    # built-in mappings of the libraries it maps
+    crate_mappings_file: Optional[Path] = Field(default=None)
+    # Crate of a library by name, or as a {crate, version, features} table, from the [crates]
+    # table of python2rust.toml, over the mapping files
+    crates: Dict[str, Any] = Field(default_factory=dict)
+
+    # Type checker, mypy or pyright, whose inferred parameter, variable and return types of
+    # each function go into the prompts; type_checker_command replaces its executable
+    type_checker: Optional[str] = Field(default=None)
+    type_checker_command: Optional[str] = Field(default=None)
+
+    # Command run with the calls of the source files' functions recorded, to write Rust
+    # regression tests asserting the same results; at most traced_calls per function
+    trace_command: Optional[str] = Field(default=None)
+    traced_calls: int = Field(default=20, ge=1)
+
+    # Receive code through the models' tool calling as {files, notes, confidence} JSON
+    # instead of parsing fenced blocks; models without tool calling keep the blocks
+    structured_output: bool = Field(default=False)
+
+    # Store model responses so that re-runs and resumes do not pay for the same request twice
+    response_cache: bool = Field(default=True)
+
+    # Replace API keys, passwords, connection strings and private keys in prompts with
+    # placeholders, restored in the model output; extra regexes mark more secrets
+    redact_secrets: bool = Field(default=True)
+    secret_patterns: List[str] = Field(default_factory=list)
+
+    # Format every revision of the generated code with rustfmt; code that does not parse
+    # goes back to the build fix prompt
+    rustfmt: bool = Field(default=True)
+
+    # Clippy lints denied in generated code (-D), e.g. clippy::pedantic, and those allowed
+    # anyway (-A); generated code is only accepted once clippy is clean. Empty skips clippy
+    clippy_deny: List[str] = Field(default_factory=lambda: ["warnings"])
+    clippy_allow: List[str] = Field(default_factory=list)
+
+    # Run the generated tests with cargo test after clippy; failing tests and their
+    # panics go to the build fix prompt until they pass
+    cargo_test: bool = Field(default=True)
+
+    # Run the tests under Miri to catch undefined behavior in unsafe code; slow, so off by
+    # default. miri_flags become MIRIFLAGS, e.g. -Zmiri-disable-isolation for file access
+    miri: bool = Field(default=False)
+    miri_flags: List[str] = Field(default_factory=list)
+    miri_toolchain: str = Field(default="nightly")
+
+    # Random inputs per function given to both the Python function and its Rust
+    # translation, whose results must agree; 0 disables differential fuzzing
+    fuzz_cases: int = Field(default=0, ge=0)
+
+    # Flag arithmetic at risk of overflow, float truncation or another rounding of divisions,
+    # and call the functions involved with boundary values in both languages
+    numeric_checks: bool = Field(default=False)
+
+    # Flag string lengths, indexes and slices, counted in code points by Python and in bytes
+    # by Rust, and call the functions involved with multi-byte text in both languages
+    unicode_checks: bool = Field(default=False)
+
+    # Start web apps in Python and in Rust, send both the same requests and fix the Rust
+    # code until the statuses, compared headers and bodies of the responses agree
+    http: HttpComparison = Field(default_factory=HttpComparison)
+
+    # Diff the OpenAPI description of Python web apps with the one their Rust service serves,
+    # built from utoipa annotations, fixing the Rust code until their operations agree
+    openapi: OpenApiComparison = Field(default_factory=OpenApiComparison)
+
+    # Run command-line scripts in Python and in Rust with the same arguments and input and fix
+    # the Rust code until their exit codes, standard output and standard error agree
+    cli: CliComparison = Field(default_factory=CliComparison)
+
+    # Measure the peak resident memory and the allocations of the Python and Rust runs compared
+    # above, in the metrics of the file reports
+    memory: MemoryComparison = Field(default_factory=MemoryComparison)
+
+    # Create the database schema of the Python models and of the Rust code and fix the Rust
+    # code until their tables, columns, constraints and indexes agree
+    database_schema: SchemaComparison = Field(default_factory=SchemaComparison)
+
+    # Wall-clock and memory limits of the build, test, miri, run and benchmark steps; running out
+    # of time fails verification and is fed back to the fix-up prompts
+    limits: VerificationLimits = Field(default_factory=VerificationLimits)
+
+    # Rules generated code must follow outside tests, checked after clippy and fixed until it does
+    policies: CodePolicies = Field(default_factory=CodePolicies)
+
+    # Audit the advisories (vulnerable, unmaintained, yanked crates) and licenses of the
+    # dependencies the model picked, reported or failing verification
+    audit: DependencyAudit = Field(default_factory=DependencyAudit)
+
+    # Check the licenses of the crates replacing the project's libraries against an allow-list,
+    # failing plans and runs with the allowed crates of their category before any generation
+    licenses: LicenseCheck = Field(default_factory=LicenseCheck)
+
+    # Benchmark the hot functions of verified crates in Rust and in Python, so that the speed
+    # of a later migration can be compared
+    benchmarks: HotFunctionBenchmarks = Field(default_factory=HotFunctionBenchmarks)
+
+    # Profile a workload of the Python program, pointing the prompts at its hot functions and
+    # migrating the files with them first, or only them
+    profile: WorkloadProfile = Field(default_factory=WorkloadProfile)
+
+    # Measure the release build time, rebuild time, binary size and dependencies of verified
+    # crates, warning when they grew since the previous run
+    build_stats: bool = False
+
+    # Build, test and run the generated code in a container with limited resources and no network
+    sandbox: SandboxConfig = Field(default_factory=SandboxConfig)
+
+    # name -> command run after cargo check and clippy; its output goes to the build fix prompt
+    shell_checkers: Dict[str, ShellCheck] = Field(default_factory=dict)
+
+    # Spend after which the run, or a file, stops with a resumable checkpoint
+    budget: BudgetLimits = Field(default_factory=BudgetLimits)
+
+    # Reproducibility: temperature 0 and recorded responses, optionally replayed
+    deterministic: bool = Field(default=False)
+    replay: bool = Field(default=False)
+    response_cache_dir: Optional[Path] = Field(default=None)
+
+    # Stream model output token by token, for live progress displays and into
+    # debug/passes/<pass>/attempt_<n>/partial.md, where interrupted output can be inspected
+    stream_responses: bool = Field(default=True)
 
     # Model Selection Strategy
     preferred_models: Dict[str, List[LLMChoice]] = Field(
@@ -146,6 +714,84 @@ class Settings(BaseSettings):
     server_port: int = Field(default=8080)
     server_timeout: int = Field(default=30)  # seconds
 
+    @field_validator("python2")
+    @classmethod
+    def _check_python2(cls, mode: str) -> str:
+        if mode not in ("fail", "convert"):
+            raise ValueError(f"Unknown Python 2 handling {mode}, expected fail or convert")
+        return mode
+
+    @field_validator("notebook_mode")
+    @classmethod
+    def _check_notebook_mode(cls, mode: str) -> str:
+        if mode not in ("script", "library"):
+            raise ValueError(f"Unknown notebook mode {mode}, expected script or library")
+        return mode
+
+    @field_validator("module_state")
+    @classmethod
+    def _check_module_state(cls, strategy: Optional[str]) -> Optional[str]:
+        if strategy == "none":
+            return None
+        if strategy not in (None, "once_lock", "app_state", "injection"):
+            raise ValueError(f"Unknown module state strategy {strategy}, expected once_lock, "
+                             "app_state, injection or none")
+        return strategy
+
+    @field_validator("async_runtime")
+    @classmethod
+    def _check_async_runtime(cls, runtime: str) -> str:
+        if runtime not in ("tokio", "async-std", "sync"):
+            raise ValueError(f"Unknown async runtime {runtime}, expected tokio, async-std or sync")
+        return runtime
+
+    @field_validator("web_framework")
+    @classmethod
+    def _check_web_framework(cls, framework: str) -> str:
+        if framework not in ("axum", "actix-web", "rocket"):
+            raise ValueError(f"Unknown web framework {framework}, expected "
+                             "axum, actix-web or rocket")
+        return framework
+
+    @field_validator("template_engine")
+    @classmethod
+    def _check_template_engine(cls, engine: str) -> str:
+        if engine not in ("askama", "tera", "maud"):
+            raise ValueError(f"Unknown template engine {engine}, expected askama, tera or maud")
+        return engine
+
+    @field_validator("database_layer")
+    @classmethod
+    def _check_database_layer(cls, layer: str) -> str:
+        if layer not in ("sqlx", "diesel", "sea-orm"):
+            raise ValueError(f"Unknown database layer {layer}, expected sqlx, diesel or sea-orm")
+        return layer
+
+    @field_validator("type_checker")
+    @classmethod
+    def _check_type_checker(cls, checker: Optional[str]) -> Optional[str]:
+        if checker not in (None, "mypy", "pyright"):
+            raise ValueError(f"Unknown type checker {checker}, expected mypy or pyright")
+        return checker
+
+    @field_validator("stage_parameters")
+    @classmethod
+    def _check_stages(cls, stages: Dict[str, StageParameters]) -> Dict[str, StageParameters]:
+        unknown = [step for step in stages if step not in MIGRATION_STEPS]
+        if unknown:
+            raise ValueError(f"Unknown migration steps: {', '.join(unknown)}")
+        return stages
+
+    @field_validator("translation_passes")
+    @classmethod
+    def _check_passes(cls, passes: List[str]) -> List[str]:
+        unknown = [p for p in passes if p not in TRANSLATION_PASSES]
+        if unknown:
+            raise ValueError(f"Unknown translation passes: {', '.join(unknown)}")
+        if passes and "implementation" not in passes:
+            raise ValueError("The implementation pass is required")
+        return [p for p in TRANSLATION_PASSES if p in passes]
+
     class Config:
         env_file = ".env"
         env_prefix = "PYTHON2RUST_"
@@ -161,6 +807,21 @@ class Settings(BaseSettings):
             default_specs_path = Path(__file__).parent / "default_specs.json"
             return json.loads(default_specs_path.read_text())
 
+    def model_temperature(self, choice: LLMChoice) -> float:
+        """Sampling temperature of a model, pinned to 0 in deterministic mode."""
+        return 0.0 if self.deterministic else self.llm_configs[choice].temperature
+
+    def stage_model_kwargs(self, step: str) -> Dict[str, Any]:
+        """Request parameters of a step overriding its model's; the temperature stays pinned in
+        deterministic mode."""
+        parameters = self.stage_parameters.get(step)
+        if parameters is None:
+            return {}
+        kwargs = parameters.model_dump(exclude={"system_prompt"}, exclude_none=True)
+        if self.deterministic:
+            kwargs.pop("temperature", None)
+        return kwargs
+
     def get_model_chain(self, task: str) -> List[LLMChoice]:
         # Convert Field value to dict
         preferred_models = dict(self.preferred_models)
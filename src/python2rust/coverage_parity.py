@@ -0,0 +1,251 @@
+"""
+Test coverage of each Python module under its pytest suite (coverage.py) and of its Rust crate
+under the translated tests (cargo llvm-cov), to see which behavior lost test protection.
+"""
+import asyncio
+import json
+import os
+import sys
+import tempfile
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Set, Tuple
+
+from .analyzers import module_name, scan_items
+from .analyzers.python_tests import find_python_tests
+from .utils.logging import setup_logger
+
+logger = setup_logger()
+
+LLVM_COV_HINT = "cargo install cargo-llvm-cov"
+COVERAGE_HINT = "pip install coverage pytest"
+
+
+@dataclass
+class ModuleCoverage:
+    """Covered and coverable lines of a module and of its Rust crate, with why one is missing."""
+    source: Path
+    output_dir: Path
+    python_lines: int = 0
+    python_covered: int = 0
+    rust_lines: int = 0
+    rust_covered: int = 0
+    python_error: Optional[str] = None
+    rust_error: Optional[str] = None
+
+    @property
+    def python_percent(self) -> Optional[float]:
+        if self.python_error or not self.python_lines:
+            return None
+        return 100 * self.python_covered / self.python_lines
+
+    @property
+    def rust_percent(self) -> Optional[float]:
+        if self.rust_error or not self.rust_lines:
+            return None
+        return 100 * self.rust_covered / self.rust_lines
+
+    @property
+    def delta(self) -> Optional[float]:
+        """Percentage points gained in Rust, negative when it is less tested than the Python."""
+        if self.python_percent is None or self.rust_percent is None:
+            return None
+        return self.rust_percent - self.python_percent
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "source": str(self.source),
+            "output_dir": str(self.output_dir),
+            "python": {"lines": self.python_lines, "covered": self.python_covered,
+                       "percent": self.python_percent, "error": self.python_error},
+            "rust": {"lines": self.rust_lines, "covered": self.rust_covered,
+                     "percent": self.rust_percent, "error": self.rust_error},
+            "delta": self.delta
+        }
+
+
+@dataclass
+class CoverageReport:
+    """Coverage of every module, those that lost most coverage in translation first."""
+    modules: List[ModuleCoverage] = field(default_factory=list)
+
+    def sorted(self) -> List[ModuleCoverage]:
+        return sorted(self.modules, key=lambda module: (module.delta is None, module.delta or 0))
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"modules": [module.to_dict() for module in self.sorted()]}
+
+    def format(self) -> str:
+        def percent(value: Optional[float]) -> str:
+            return f"{value:.1f}%" if value is not None else "-"
+
+        rows = [("Module", "Python", "Rust", "Delta")]
+        notes = []
+        for module in self.sorted():
+            delta = f"{module.delta:+.1f}" if module.delta is not None else "-"
+            rows.append((str(module.source), percent(module.python_percent),
+                         percent(module.rust_percent), delta))
+            for side, error in (("Python", module.python_error), ("Rust", module.rust_error)):
+                if error:
+                    notes.append(f"{module.source}: {side} coverage not measured: {error}")
+        widths = [max(len(row[column]) for row in rows) for column in range(4)]
+        lines = [
+            "  ".join(cell.ljust(width) if column == 0 else cell.rjust(width)
+                      for column, (cell, width) in enumerate(zip(row, widths)))
+            for row in rows
+        ]
+        return "\n".join(lines + ([""] + notes if notes else []))
+
+
+def test_module_lines(rust_code: str) -> Set[int]:
+    """Lines of the #[cfg(test)] modules of a Rust file, which coverage of the code leaves out."""
+    lines = rust_code.splitlines()
+    excluded: Set[int] = set()
+    for item in scan_items(rust_code):
+        attribute = lines[item.start_line - 2].strip() if item.start_line > 1 else ""
+        if item.kind == "mod" and attribute == "#[cfg(test)]":
+            excluded.update(range(item.start_line - 1, item.end_line + 1))
+    return excluded
+
+
+def lcov_lines(lcov: str, crate_dir: Path) -> Tuple[int, int]:
+    """(coverable, covered) lines of an lcov report in the sources of a crate,
+    without test modules."""
+    crate_src = (Path(crate_dir) / "src").resolve()
+    total = covered = 0
+    source: Optional[Path] = None
+    counts: Dict[int, int] = {}
+
+    def finish() -> Tuple[int, int]:
+        if source is None or crate_src not in source.parents:
+            return 0, 0
+        try:
+            excluded = test_module_lines(source.read_text())
+        except OSError:
+            excluded = set()
+        kept = {line: count for line, count in counts.items() if line not in excluded}
+        return len(kept), sum(1 for count in kept.values() if count > 0)
+
+    for line in lcov.splitlines():
+        if line.startswith("SF:"):
+            source, counts = Path(line[3:]).resolve(), {}
+        elif line.startswith("DA:") and source is not None:
+            number, count = line[3:].split(",")[:2]
+            counts[int(number)] = max(counts.get(int(number), 0), int(float(count)))
+        elif line == "end_of_record":
+            lines, hits = finish()
+            total, covered = total + lines, covered + hits
+            source = None
+    return total, covered
+
+
+async def _run(command: List[str], cwd: Path, timeout: Optional[float],
+               env: Optional[Dict[str, str]] = None) -> Tuple[int, str]:
+    process = await asyncio.create_subprocess_exec(
+        *command, cwd=cwd, env={**os.environ, **env} if env else None,
+        stdout=asyncio.subprocess.PIPE, stderr=asyncio.subprocess.STDOUT)
+    try:
+        output, _ = await asyncio.wait_for(process.communicate(), timeout=timeout)
+    except asyncio.TimeoutError:
+        process.kill()
+        await process.wait()
+        raise
+    return process.returncode, output.decode(errors="replace")
+
+
+async def python_coverage(
+    sources: List[Path],
+    root: Path,
+    python: str = sys.executable,
+    timeout: Optional[float] = None
+) -> Dict[Path, Tuple[int, int]]:
+    """(statements, covered) of each source under the pytest files exercising any of them.
+
+    Raises RuntimeError when coverage.py or pytest cannot run, or no test file was found.
+    """
+    root = Path(root).resolve()
+    tests: List[Path] = []
+    for source in sources:
+        for test in find_python_tests(root, module_name(Path(source).resolve(), root)):
+            if test not in tests and test.name != "conftest.py":
+                tests.append(test)
+    if not tests:
+        raise RuntimeError(f"no pytest file exercises the modules of {root}")
+
+    with tempfile.TemporaryDirectory() as work:
+        data, report = Path(work) / ".coverage", Path(work) / "coverage.json"
+        # Tests import the modules either by package name or from the root
+        path = [str(root.parent), str(root), os.environ.get("PYTHONPATH", "")]
+        env = {"PYTHONPATH": os.pathsep.join(path)}
+        _, output = await _run(
+            [python, "-m", "coverage", "run", f"--data-file={data}", f"--source={root}",
+             "-m", "pytest", "-q", "-p", "no:cacheprovider", *map(str, tests)],
+            root.parent, timeout, env)
+        if "No module named coverage" in output or "No module named pytest" in output:
+            raise RuntimeError(f"coverage.py or pytest is not installed: {COVERAGE_HINT}")
+        returncode, output = await _run(
+            [python, "-m", "coverage", "json", f"--data-file={data}", "-o", str(report)],
+            root.parent, timeout)
+        if returncode != 0 or not report.exists():
+            raise RuntimeError(f"coverage.py measured nothing: {output.strip()[-500:]}")
+        files = json.loads(report.read_text()).get("files", {})
+
+    measured = {}
+    for name, entry in files.items():
+        summary = entry.get("summary", {})
+        measured[(root.parent / name).resolve()] = (
+            summary.get("num_statements", 0), summary.get("covered_lines", 0))
+    return {Path(source): measured.get(Path(source).resolve(), (0, 0)) for source in sources}
+
+
+async def rust_coverage(crate_dir: Path, timeout: Optional[float] = None) -> Tuple[int, int]:
+    """(coverable, covered) lines of a crate under `cargo llvm-cov`, test modules left out.
+
+    Raises RuntimeError when cargo-llvm-cov is missing or the tests could not run.
+    """
+    crate_dir = Path(crate_dir)
+    if not (crate_dir / "Cargo.toml").exists():
+        raise RuntimeError("not generated")
+    report = crate_dir / "target" / "python2rust-lcov.info"
+    report.parent.mkdir(parents=True, exist_ok=True)
+    returncode, output = await _run(
+        ["cargo", "llvm-cov", "--lcov", "--output-path", str(report)], crate_dir, timeout)
+    if "no such command" in output and "llvm-cov" in output:
+        raise RuntimeError(f"cargo-llvm-cov is not installed: {LLVM_COV_HINT}")
+    if returncode != 0 or not report.exists():
+        raise RuntimeError(f"cargo llvm-cov failed: {output.strip()[-500:]}")
+    return lcov_lines(report.read_text(), crate_dir)
+
+
+async def measure_coverage(
+    outputs: Dict[Path, Path],
+    roots: Dict[Path, Path],
+    python: str = sys.executable,
+    timeout: Optional[float] = None
+) -> CoverageReport:
+    """Coverage of each source (source -> output crate, source -> source root) and of its crate.
+
+    The pytest suite of each source root runs once, then the crates one at a time.
+    """
+    report = CoverageReport(
+        [ModuleCoverage(source, output_dir) for source, output_dir in outputs.items()])
+    by_root: Dict[Path, List[ModuleCoverage]] = {}
+    for module in report.modules:
+        by_root.setdefault(roots.get(module.source, module.source.parent), []).append(module)
+    for root, modules in by_root.items():
+        try:
+            results = await python_coverage([module.source for module in modules],
+                                            root, python, timeout)
+        except (RuntimeError, OSError, asyncio.TimeoutError) as e:
+            logger.warning(f"Python coverage of {root} not measured: {e}")
+            for module in modules:
+                module.python_error = str(e) or "timed out"
+            continue
+        for module in modules:
+            module.python_lines, module.python_covered = results[module.source]
+    for module in report.modules:
+        try:
+            module.rust_lines, module.rust_covered = await rust_coverage(module.output_dir, timeout)
+        except (RuntimeError, OSError, asyncio.TimeoutError) as e:
+            module.rust_error = str(e) or "timed out"
+    return report
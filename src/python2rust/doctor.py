@@ -0,0 +1,277 @@
+"""
+Environment preflight: everything a migration needs, checked before any model is called.
+"""
+import re
+import shutil
+import subprocess
+import sys
+from dataclasses import asdict, dataclass, field
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+from .config.settings import SandboxConfig, Settings
+from .providers import MIGRATION_STEPS, PROVIDERS, missing_tokens, provider_for, resolve_model
+
+OK, WARNING, ERROR = "ok", "warning", "error"
+
+# Generated crates use edition 2021
+MIN_RUST_VERSION = (1, 56)
+MIN_PYTHON_VERSION = (3, 9)
+# A release build of a web server with its dependencies takes about 1 GB
+MIN_FREE_DISK_GB = 2.0
+
+# component -> (cargo subcommand, install hint)
+RUST_COMPONENTS = {
+    "clippy": ("clippy", "rustup component add clippy"),
+    "rustfmt": ("fmt", "rustup component add rustfmt"),
+}
+
+VERSION = re.compile(r"(\d+)\.(\d+)(?:\.(\d+))?")
+
+
+@dataclass
+class DoctorCheck:
+    """Outcome of one preflight check."""
+    name: str
+    status: str
+    detail: str
+    hint: Optional[str] = None
+
+
+@dataclass
+class DoctorReport:
+    """All preflight checks; the environment is usable when none of them is an error."""
+    checks: List[DoctorCheck] = field(default_factory=list)
+
+    @property
+    def ok(self) -> bool:
+        return all(check.status != ERROR for check in self.checks)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"ok": self.ok, "checks": [asdict(check) for check in self.checks]}
+
+    def format(self) -> str:
+        width = max((len(check.name) for check in self.checks), default=0)
+        lines = []
+        for check in self.checks:
+            lines.append(f"{check.status.upper():<7} {check.name:<{width}}  {check.detail}")
+            if check.hint and check.status != OK:
+                lines.append(f"{'':<7} {'':<{width}}  -> {check.hint}")
+        errors = sum(check.status == ERROR for check in self.checks)
+        warnings = sum(check.status == WARNING for check in self.checks)
+        lines.append("")
+        lines.append("Ready to migrate" if self.ok
+                     else f"{errors} problem(s) to fix before migrating")
+        if warnings:
+            lines[-1] += f" ({warnings} warning(s))"
+        return "\n".join(lines)
+
+
+def run_version(command: List[str]) -> Optional[str]:
+    """First line printed by a --version command, or None when it is not available."""
+    try:
+        result = subprocess.run(command, capture_output=True, text=True, timeout=30)
+    except (OSError, subprocess.TimeoutExpired):
+        return None
+    if result.returncode != 0:
+        return None
+    output = (result.stdout or result.stderr).strip()
+    return output.splitlines()[0] if output else None
+
+
+def parse_version(text: str) -> Optional[Tuple[int, ...]]:
+    match = VERSION.search(text)
+    return tuple(int(part) for part in match.groups() if part is not None) if match else None
+
+
+def check_rust_toolchain(run: Callable[[List[str]], Optional[str]] = run_version) -> List[
+        DoctorCheck]:
+    """rustc and cargo are installed and recent enough for the generated crates."""
+    checks = []
+    minimum = ".".join(map(str, MIN_RUST_VERSION))
+    for tool in ("rustc", "cargo"):
+        output = run([tool, "--version"])
+        if output is None:
+            checks.append(DoctorCheck(tool, ERROR, "not found",
+                                      "Install Rust from https://rustup.rs"))
+            continue
+        version = parse_version(output)
+        if version is not None and version[:2] < MIN_RUST_VERSION:
+            checks.append(DoctorCheck(tool, ERROR, f"{output} (needs {minimum} or later)",
+                                      "rustup update stable"))
+        else:
+            checks.append(DoctorCheck(tool, OK, output))
+    return checks
+
+
+def check_rust_components(run: Callable[[List[str]], Optional[str]] = run_version) -> List[
+        DoctorCheck]:
+    """clippy and rustfmt, used by the lint and formatting checks."""
+    checks = []
+    for component, (subcommand, hint) in RUST_COMPONENTS.items():
+        output = run(["cargo", subcommand, "--version"])
+        if output is None:
+            checks.append(DoctorCheck(component, ERROR, "not installed", hint))
+        else:
+            checks.append(DoctorCheck(component, OK, output))
+    return checks
+
+
+def check_miri(settings: Settings,
+               run: Callable[[List[str]], Optional[str]] = run_version) -> List[DoctorCheck]:
+    """Miri on its toolchain, when the tests are run under it."""
+    if not settings.miri:
+        return []
+    toolchain = settings.miri_toolchain
+    output = run(["cargo", f"+{toolchain}", "miri", "--version"])
+    if output is None:
+        # The check is skipped rather than failed without it
+        return [DoctorCheck("miri", WARNING, f"not installed on {toolchain}",
+                            f"rustup toolchain install {toolchain} --component miri")]
+    return [DoctorCheck("miri", OK, output)]
+
+
+def check_cargo_deny(settings: Settings,
+                     run: Callable[[List[str]], Optional[str]] = run_version) -> List[DoctorCheck]:
+    """cargo-deny, when the dependencies of generated crates are audited."""
+    if not settings.audit.enabled:
+        return []
+    output = run(["cargo", "deny", "--version"])
+    if output is None:
+        # The audit is skipped rather than failed without it
+        return [DoctorCheck("cargo-deny", WARNING, "not installed",
+                            "cargo install --locked cargo-deny")]
+    return [DoctorCheck("cargo-deny", OK, output)]
+
+
+def check_python(executable: Optional[str] = sys.executable) -> DoctorCheck:
+    """The interpreter the original Python code is run with for comparisons and benchmarks."""
+    if not executable or not Path(executable).exists():
+        return DoctorCheck("python", ERROR, "interpreter not found",
+                           "Run python2rust with a Python 3 interpreter")
+    version = ".".join(map(str, sys.version_info[:3]))
+    if sys.version_info[:2] < MIN_PYTHON_VERSION:
+        minimum = ".".join(map(str, MIN_PYTHON_VERSION))
+        return DoctorCheck("python", ERROR, f"{version} at {executable} (needs {minimum} or later)")
+    return DoctorCheck("python", OK, f"{version} at {executable}")
+
+
+def check_sandbox(
+    config: SandboxConfig,
+    run: Callable[[List[str]], Optional[str]] = run_version
+) -> List[DoctorCheck]:
+    """Docker and the sandbox image, when generated code is built in a container."""
+    if not config.enabled:
+        return []
+    output = run([config.docker, "--version"])
+    if output is None:
+        return [DoctorCheck("docker", ERROR, "not found", "Install Docker or disable [sandbox]")]
+    checks = [DoctorCheck("docker", OK, output)]
+    if run([config.docker, "image", "inspect", "--format", "{{.Id}}", config.image]) is None:
+        checks.append(DoctorCheck(
+            "sandbox image", ERROR, f"{config.image} is not available",
+            f"docker pull {config.image}"))
+    else:
+        checks.append(DoctorCheck("sandbox image", OK, config.image))
+    return checks
+
+
+def check_disk_space(path: Path, min_free_gb: float = MIN_FREE_DISK_GB) -> DoctorCheck:
+    """Free space where the Rust projects and their target directories are built."""
+    existing = Path(path).resolve()
+    while not existing.exists() and existing != existing.parent:
+        existing = existing.parent
+    free_gb = shutil.disk_usage(existing).free / 1024 ** 3
+    detail = f"{free_gb:.1f} GB free at {existing}"
+    if free_gb < min_free_gb:
+        return DoctorCheck(
+            "disk space", ERROR, f"{detail} (needs {min_free_gb:g} GB)",
+            "Free some space or choose another --output-dir"
+        )
+    return DoctorCheck("disk space", OK, detail)
+
+
+async def check_api_keys(
+    tokens: Dict[str, Optional[str]],
+    settings: Settings,
+    online: bool = True
+) -> List[DoctorCheck]:
+    """Keys are present for the models of every step and, when online, accepted by their API."""
+    missing = missing_tokens(settings, tokens)
+    checks = []
+    for provider in PROVIDERS.values():
+        check_name = f"{provider.token} {provider.key_name}"
+        steps = [
+            step for step in MIGRATION_STEPS
+            if provider_for(settings, settings.llm_steps.model_for(step)) is provider
+        ]
+        # Providers usable without a key (local servers) are only checked when a step uses them
+        if not steps and provider.credentials({}) is not None:
+            checks.append(DoctorCheck(check_name, OK, "not used"))
+            continue
+        token = provider.credentials(tokens)
+        if not token:
+            needed = [step for step, name in missing.items() if name == provider.token]
+            if needed:
+                checks.append(DoctorCheck(
+                    check_name, ERROR, f"missing; needed for {', '.join(needed)}",
+                    provider.key_hint
+                ))
+            elif steps:
+                fallback = resolve_model(settings, settings.llm_steps.model_for(steps[0]), tokens)
+                checks.append(DoctorCheck(check_name, OK,
+                                          f"not set; {fallback.value} is used "
+                                          f"for {', '.join(steps)}"))
+            else:
+                checks.append(DoctorCheck(check_name, OK, "not set"))
+            continue
+        if not online:
+            checks.append(DoctorCheck(check_name, OK, "present (not tested)"))
+            continue
+
+        # Test with the model of a step when there is one
+        choices = [settings.llm_steps.model_for(step) for step in steps] or [
+            choice for choice, config in settings.llm_configs.items()
+            if config.provider == provider.name
+        ]
+        try:
+            result = await provider.test(settings.llm_configs[choices[0]], token)
+        except Exception as e:
+            result = {"error": str(e) or e.__class__.__name__}
+
+        status = result.get("status")
+        if status == 200:
+            checks.append(DoctorCheck(check_name, OK, "accepted"))
+        elif status in (401, 403):
+            checks.append(DoctorCheck(
+                check_name, ERROR if steps else WARNING, f"rejected (HTTP {status})",
+                f"Check your {provider.label} {provider.key_name}"
+            ))
+        else:
+            reason = f"HTTP {status}" if status else result.get("error", "no response")
+            checks.append(DoctorCheck(
+                check_name, ERROR if steps else WARNING, f"could not be verified ({reason})",
+                "Check your network connection, or use --offline"
+            ))
+    return checks
+
+
+async def run_doctor(
+    output_dir: Path,
+    tokens: Dict[str, Optional[str]],
+    settings: Optional[Settings] = None,
+    online: bool = True,
+    min_free_gb: float = MIN_FREE_DISK_GB
+) -> DoctorReport:
+    """Run every preflight check."""
+    settings = settings or Settings(output_dir=output_dir)
+    report = DoctorReport()
+    report.checks.extend(check_rust_toolchain())
+    report.checks.extend(check_rust_components())
+    report.checks.extend(check_miri(settings))
+    report.checks.extend(check_cargo_deny(settings))
+    report.checks.append(check_python())
+    report.checks.extend(check_sandbox(settings.sandbox))
+    report.checks.extend(await check_api_keys(tokens, settings, online))
+    report.checks.append(check_disk_space(output_dir, min_free_gb))
+    return report
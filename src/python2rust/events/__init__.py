@@ -0,0 +1,6 @@
+# events/__init__.py
+from .event import EventType, MigrationEvent
+from .observer import EventBus, MigrationObserver
+from .callbacks import EventCallbackHandler
+
+__all__ = ['EventType', 'MigrationEvent', 'EventBus', 'MigrationObserver', 'EventCallbackHandler']
@@ -0,0 +1,66 @@
+from typing import Any, Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+
+from .event import EventType
+from .observer import EventBus
+
+
+def response_usage(response: Any) -> Dict[str, Any]:
+    """Input and output tokens and model reported by a provider response, when it reports them."""
+    llm_output = getattr(response, "llm_output", None) or {}
+    generations = getattr(response, "generations", None) or []
+    message = (getattr(generations[0][0], "message", None) if generations
+               and generations[0] else None)
+    response_metadata = getattr(message, "response_metadata", None) or {}
+
+    usage: Dict[str, Any] = {}
+    metadata = getattr(message, "usage_metadata", None)
+    raw = llm_output.get("usage") or llm_output.get("token_usage") or {}
+    if metadata:
+        usage["input_tokens"] = metadata.get("input_tokens", 0)
+        usage["output_tokens"] = metadata.get("output_tokens", 0)
+    elif raw:
+        raw = raw if isinstance(raw, dict) else vars(raw)
+        usage["input_tokens"] = raw.get("input_tokens", raw.get("prompt_tokens", 0)) or 0
+        usage["output_tokens"] = raw.get("output_tokens", raw.get("completion_tokens", 0)) or 0
+
+    model = (
+        llm_output.get("model_name") or llm_output.get("model")
+        or response_metadata.get("model_name") or response_metadata.get("model")
+    )
+    if model:
+        usage["model"] = model
+    return usage
+
+
+class EventCallbackHandler(BaseCallbackHandler):
+    """Bridges LangChain LLM callbacks into migration events."""
+
+    def __init__(self, events: EventBus):
+        super().__init__()
+        self.events = events
+        # Requested model of each call, for responses that do not name theirs
+        self._models: Dict[Any, Optional[str]] = {}
+
+    def on_llm_start(self, serialized: Dict[str, Any], prompts: List[str], **kwargs: Any) -> None:
+        params = kwargs.get("invocation_params") or {}
+        self._models[kwargs.get("run_id")] = params.get("model") or params.get("model_name")
+        self.events.emit(
+            EventType.PROMPT_SENT,
+            model=serialized.get("name", "unknown"),
+            prompt_chars=sum(len(prompt) for prompt in prompts)
+        )
+
+    def on_llm_new_token(self, token: str, **kwargs: Any) -> None:
+        self.events.emit(EventType.TOKEN_RECEIVED, token=token)
+
+    def on_llm_end(self, response: Any, **kwargs: Any) -> None:
+        completion = ""
+        if getattr(response, "generations", None):
+            completion = response.generations[0][0].text
+        usage = response_usage(response)
+        requested = self._models.pop(kwargs.get("run_id"), None)
+        if requested and "model" not in usage:
+            usage["model"] = requested
+        self.events.emit(EventType.RESPONSE_RECEIVED, completion_chars=len(completion), **usage)
@@ -0,0 +1,34 @@
+from dataclasses import dataclass, field
+from datetime import datetime
+from enum import Enum
+from typing import Any, Dict, Optional
+
+
+class EventType(str, Enum):
+    """Kinds of events emitted while a migration runs."""
+    FILE_STARTED = "file_started"
+    FILE_FINISHED = "file_finished"
+    STAGE_STARTED = "stage_started"
+    PROMPT_SENT = "prompt_sent"
+    RESPONSE_RECEIVED = "response_received"
+    TOKEN_RECEIVED = "token_received"      # only when responses are streamed
+    BUILD_ATTEMPT = "build_attempt"
+    CHECK_FAILED = "check_failed"
+    ITERATION_RETRIED = "iteration_retried"
+
+
+@dataclass
+class MigrationEvent:
+    """A single pipeline event."""
+    type: EventType
+    file: Optional[str] = None
+    data: Dict[str, Any] = field(default_factory=dict)
+    timestamp: datetime = field(default_factory=datetime.now)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "type": self.type.value,
+            "file": self.file,
+            "data": self.data,
+            "timestamp": self.timestamp.isoformat()
+        }
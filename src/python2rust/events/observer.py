@@ -0,0 +1,47 @@
+from typing import Any, List, Optional
+
+from .event import EventType, MigrationEvent
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+class MigrationObserver:
+    """Base class for migration event subscribers.
+
+    Override on_event to receive everything, or the on_<event type> methods
+    (e.g. on_build_attempt) to handle specific events.
+    """
+
+    def on_event(self, event: MigrationEvent) -> None:
+        handler = getattr(self, f"on_{event.type.value}", None)
+        if handler is not None:
+            handler(event)
+
+
+class EventBus:
+    """Fans events out to observers.
+
+    A bus bound to a file (see for_file) shares its observers with the parent
+    and tags every event it emits with that file.
+    """
+
+    def __init__(self, observers: Optional[List[MigrationObserver]] = None,
+                 file: Optional[str] = None):
+        self.observers = observers if observers is not None else []
+        self.file = file
+
+    def subscribe(self, observer: MigrationObserver) -> None:
+        self.observers.append(observer)
+
+    def for_file(self, file: Any) -> "EventBus":
+        return EventBus(self.observers, file=str(file))
+
+    def emit(self, event_type: EventType, **data: Any) -> None:
+        event = MigrationEvent(type=event_type, file=self.file, data=data)
+        for observer in self.observers:
+            try:
+                observer.on_event(event)
+            except Exception as e:
+                logger.error(f"Observer {observer.__class__.__name__} failed on "
+                             f"{event_type.value}: {e}")
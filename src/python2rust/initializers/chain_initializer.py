@@ -1,9 +1,12 @@
 # initializers/chain_initializer.py
 from typing import Dict, Any, Optional, List
 from langchain_core.language_models import BaseLanguageModel
+from langchain.prompts import PromptTemplate
 from ..config.settings import Settings, LLMChoice
-from ..chains import AnalysisChain, GenerationChain, VerificationChain, FixChain
+from ..chains import AnalysisChain, GenerationChain, VerificationChain, FixChain, PassChain
+from ..prompts.loader import load_prompts
 from ..utils.logging import setup_logger
+from ..utils.stage_model import StageModel
 from langchain.callbacks.base import BaseCallbackHandler
 
 logger = setup_logger()
@@ -15,7 +18,9 @@ class ChainInitializer:
         callbacks: Optional[List[BaseCallbackHandler]] = None
     ):
         self.settings = settings
-        self.callbacks = callbacks or [] 
+        self.callbacks = callbacks or []
+        # Packaged templates, overridden by the files of settings.prompts_dir
+        self.prompts = load_prompts(settings.prompts_dir)
 
     def initialize(
         self,
@@ -26,19 +31,49 @@ class ChainInitializer:
         
         chains["analysis"] = self._initialize_analysis_chain(llms)
         chains["generation"] = self._initialize_generation_chain(llms)
+        chains["passes"] = self._initialize_pass_chain(llms)
         chains["verification"] = self._initialize_verification_chain(llms)
         chains["fix"] = self._initialize_fix_chain(llms)
+        chains["build_fix"] = self._initialize_build_fix_chain(llms)
+        chains["candidates"] = self._initialize_candidate_chains(llms)
         
         return chains
 
+    def _llm(
+        self,
+        llms: Dict[str, BaseLanguageModel],
+        step: str,
+        choice: Optional[LLMChoice] = None
+    ) -> BaseLanguageModel:
+        """The model of a step, or the given one, sending the step's request parameters."""
+        llm = llms[choice if choice is not None else self.settings.llm_steps.model_for(step)]
+        parameters = self.settings.stage_model_kwargs(step)
+        return StageModel(llm, parameters) if parameters else llm
+
+    def _system_prompt(self, step: str) -> Optional[str]:
+        parameters = self.settings.stage_parameters.get(step)
+        if parameters is None or not parameters.system_prompt:
+            return None
+        # Configured text, not a template: braces are literal
+        return parameters.system_prompt.replace("{", "{{").replace("}", "}}")
+
+    def _prompts(self, step: str, system: str) -> Dict[str, PromptTemplate]:
+        """The prompts of a step, with its configured system prompt replacing
+        the system template."""
+        system_prompt = self._system_prompt(step)
+        if system_prompt is None:
+            return self.prompts
+        return {**self.prompts, system: PromptTemplate.from_template(system_prompt)}
+
     def _initialize_analysis_chain(
         self,
         llms: Dict[str, BaseLanguageModel]
     ) -> AnalysisChain:
         """Initialize analysis chain."""
         return AnalysisChain(
-            llm=llms[self.settings.llm_steps.analysis],
-            callbacks=self.callbacks
+            llm=self._llm(llms, "analysis"),
+            callbacks=self.callbacks,
+            prompts=self._prompts("analysis", "analysis_system")
         )
 
     def _initialize_generation_chain(
@@ -47,8 +82,24 @@ class ChainInitializer:
     ) -> GenerationChain:
         """Initialize generation chain."""
         return GenerationChain(
-            llm=llms[self.settings.llm_steps.generation],
-            callbacks=self.callbacks
+            llm=self._llm(llms, "generation"),
+            callbacks=self.callbacks,
+            prompts=self._prompts("generation", "generation_system"),
+            structured=self.settings.structured_output
+        )
+
+    def _initialize_pass_chain(
+        self,
+        llms: Dict[str, BaseLanguageModel]
+    ) -> PassChain:
+        """Initialize the staged translation chains."""
+        return PassChain(
+            llm=self._llm(llms, "generation"),
+            callbacks=self.callbacks,
+            tests_llm=self._llm(llms, "tests"),
+            prompts=self._prompts("generation", "generation_system"),
+            structured=self.settings.structured_output,
+            tests_prompts=self._prompts("tests", "generation_system")
         )
 
     def _initialize_verification_chain(
@@ -57,9 +108,11 @@ class ChainInitializer:
     ) -> VerificationChain:
         """Initialize verification chain."""
         return VerificationChain(
-            llm=llms[self.settings.llm_steps.verification],
+            llm=self._llm(llms, "verification"),
             specs_file=self.settings.specs_file,
-            callbacks=self.callbacks
+            callbacks=self.callbacks,
+            prompts=self.prompts,
+            system_prompt=self._system_prompt("verification")
         )
 
     def _initialize_fix_chain(
@@ -68,7 +121,47 @@ class ChainInitializer:
     ) -> FixChain:
         """Initialize fix chain."""
         return FixChain(
-            llm=llms[self.settings.llm_steps.fixes],
+            llm=self._llm(llms, "fixes"),
             specs_file=self.settings.specs_file,
-            callbacks=self.callbacks
-        )
\ No newline at end of file
+            callbacks=self.callbacks,
+            prompts=self._prompts("fixes", "fix_system"),
+            structured=self.settings.structured_output
+        )
+
+    def _initialize_build_fix_chain(
+        self,
+        llms: Dict[str, BaseLanguageModel]
+    ) -> FixChain:
+        """Initialize the chain fixing compiler and clippy errors."""
+        return FixChain(
+            llm=self._llm(llms, "build_fixes"),
+            specs_file=self.settings.specs_file,
+            callbacks=self.callbacks,
+            prompts=self._prompts("build_fixes", "fix_system"),
+            structured=self.settings.structured_output
+        )
+
+    def _initialize_candidate_chains(
+        self,
+        llms: Dict[str, BaseLanguageModel]
+    ) -> Dict[str, Dict[str, Any]]:
+        """Initialize the generation chains of each available consensus model."""
+        # A dedicated tests model writes the tests of every candidate
+        tests = self.settings.llm_steps.tests
+        structured = self.settings.structured_output
+        prompts = self._prompts("generation", "generation_system")
+        return {
+            choice.value: {
+                "generation": GenerationChain(
+                    llm=self._llm(llms, "generation", choice), callbacks=self.callbacks,
+                    prompts=prompts, structured=structured),
+                "passes": PassChain(llm=self._llm(llms, "generation", choice),
+                                    callbacks=self.callbacks,
+                                    tests_llm=self._llm(llms, "tests",
+                                                        tests if tests is not None else choice),
+                                    prompts=prompts, structured=structured,
+                                    tests_prompts=self._prompts("tests", "generation_system"))
+            }
+            for choice in self.settings.consensus_models
+            if choice in llms
+        }
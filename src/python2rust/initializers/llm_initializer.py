@@ -2,15 +2,13 @@
 This module is responsible for initializing language models and setting up callbacks.
 '''
 from typing import Dict, Optional, List
+from langchain_core.caches import BaseCache
 from langchain_core.language_models import BaseLanguageModel
-from langchain_anthropic import ChatAnthropic
-from langchain_huggingface import HuggingFaceEndpoint
 from langchain.callbacks.base import BaseCallbackHandler
 from ..config.settings import Settings, LLMChoice
+from ..providers import MIGRATION_STEPS, fallback_models, provider_for
 from ..utils.logging import setup_logger
-import aiohttp
 import json
-from .codestral_llm import CodestralLLM
 
 logger = setup_logger()
 
@@ -18,113 +16,49 @@ logger = setup_logger()
 class LLMInitializer:
     '''Initialize language models and set up callbacks.'''
 
-    def __init__(self, settings: Settings):
+    def __init__(self, settings: Settings, cache: Optional[BaseCache] = None):
         self.settings = settings
+        # Shared by every model so deterministic runs record and replay all responses
+        self.cache = cache
+        # model -> the model actually serving it, which differs after a fallback
+        self.serving: Dict[LLMChoice, LLMChoice] = {}
 
-    async def _test_hf_endpoint(self, endpoint_url: str, token: str) -> Dict:
-        """Test HuggingFace endpoint with detailed error reporting."""
-        headers = {
-            "Authorization": f"Bearer {token}",
-            "Accept": "application/json",
-            "Content-Type": "application/json"
-        }
-        test_payload = {
-            "inputs": "Test request",
-            "parameters": {
-                "max_new_tokens": 10,
-                "return_full_text": False
-            }
-        }
-
-        async with aiohttp.ClientSession() as session:
-            try:
-                async with session.post(
-                    endpoint_url,
-                    headers=headers,
-                    json=test_payload,
-                    timeout=30
-                ) as response:
-                    response_text = await response.text()
-                    try:
-                        response_json = json.loads(response_text)
-                    except json.JSONDecodeError:
-                        response_json = {"raw_response": response_text}
-
-                    status = response.status
-                    headers_dict = dict(response.headers)
-
-                    return {
-                        "status": status,
-                        "headers": headers_dict,
-                        "body": response_json,
-                        "url": str(response.url)
-                    }
-            except aiohttp.ClientError as e:
-                return {
-                    "error": str(e),
-                    "type": e.__class__.__name__,
-                    "url": endpoint_url
-                }
-
-    def _initialize_claude(
+    async def _initialize_model(
         self,
-        claude_token: str,
+        choice: LLMChoice,
+        token: str,
         callbacks: Optional[List[BaseCallbackHandler]] = None
-    ) -> ChatAnthropic:
-        """Initialize Claude model."""
-        logger.info("Initializing Claude")
-        config = self.settings.llm_configs[LLMChoice.CLAUDE]
-
-        llm = ChatAnthropic(
-            anthropic_api_key=claude_token,
-            model=config.model,
-            temperature=config.temperature,
-            max_tokens=config.max_tokens,
-            callbacks=callbacks
-        )
-
+    ) -> Optional[BaseLanguageModel]:
+        """Create one model with its provider, or None when its endpoint does not work."""
+        config = self.settings.llm_configs[choice]
+        provider = provider_for(self.settings, choice)
+
+        if provider.probe_before_use:
+            logger.info(f"Testing {provider.label} endpoint for {choice.value}...")
+            test_result = await provider.test(config, token)
+            if test_result.get("status") != 200:
+                logger.error(f"Endpoint test failed for {choice.value}")
+                logger.error(f"Status: {test_result.get('status')}")
+                logger.error(
+                    f"Response: {json.dumps(test_result.get('body', {}), indent=2)}")
+                return None
+            logger.info(f"Endpoint test successful for {choice.value}")
+
+        try:
+            llm = provider.create(
+                config,
+                token,
+                temperature=self.settings.model_temperature(choice),
+                streaming=self.settings.stream_responses,
+                callbacks=callbacks,
+                cache=self.cache
+            )
+        except Exception as e:
+            logger.error(f"Failed to initialize {choice.value}: {e}")
+            return None
+        logger.info(f"Successfully initialized {choice.value} ({provider.label} {config.model})")
         return llm
 
-    async def _test_mistral_endpoint(self, endpoint_url: str, token: str) -> Dict:
-        """Test Mistral/Codestral endpoint."""
-        headers = {
-            "Authorization": f"Bearer {token}",
-            "Content-Type": "application/json"
-        }
-
-        test_payload = {
-            "messages": [{"role": "user", "content": "Test request"}],
-            "model": "codestral-latest",
-            "max_tokens": 10
-        }
-
-        async with aiohttp.ClientSession() as session:
-            try:
-                async with session.post(
-                    endpoint_url,
-                    headers=headers,
-                    json=test_payload,
-                    timeout=30
-                ) as response:
-                    response_text = await response.text()
-                    try:
-                        response_json = json.loads(response_text)
-                    except json.JSONDecodeError:
-                        response_json = {"raw_response": response_text}
-
-                    return {
-                        "status": response.status,
-                        "headers": dict(response.headers),
-                        "body": response_json,
-                        "url": str(response.url)
-                    }
-            except aiohttp.ClientError as e:
-                return {
-                    "error": str(e),
-                    "type": e.__class__.__name__,
-                    "url": endpoint_url
-                }
-
     async def initialize(
         self,
         tokens: Dict[str, str],
@@ -133,84 +67,39 @@ class LLMInitializer:
         """Initialize LLMs based on available tokens."""
         logger.info("Initializing LLMs with callbacks")
         llms: Dict[str, BaseLanguageModel] = {}
-
-        # Initialize Claude (primary model)
-        llms[LLMChoice.CLAUDE] = self._initialize_claude(
-            tokens["claude"], callbacks)
-
-        if tokens.get("hf"):
-            logger.info("Testing Huggingface endpoints...")
-
-            for model in [LLMChoice.CODELLAMA, LLMChoice.STARCODER]:
-                config = self.settings.llm_configs[model]
-                endpoint_url = config.endpoint_url or f"https://api-inference.huggingface.co/models/{config.model}"
-
-                test_result = await self._test_hf_endpoint(endpoint_url, tokens["hf"])
-
-                if test_result.get("status") == 200:
-                    logger.info(f"Endpoint test successful for {model}")
-                    try:
-                        llms[model] = HuggingFaceEndpoint(
-                            endpoint_url=endpoint_url,
-                            huggingfacehub_api_token=tokens["hf"],
-                            temperature=config.temperature,
-                            task="text-completion",
-                            max_new_tokens=config.max_tokens or 4000,
-                            callbacks=callbacks,
-                        )
-                        if config.model_params is not None:
-                            if config.model_params.return_full_text is not None:
-                                llms[model].return_full_text = config.model_params.return_full_text
-                            if config.model_params.stop_sequences is not None:
-                                llms[model].stop_sequences = config.model_params.stop_sequences
-                            if config.model_params.top_k is not None:
-                                llms[model].top_k = config.model_params.top_k
-                            if config.model_params.top_p is not None:
-                                llms[model].top_p = config.model_params.top_p
-                            if config.model_params.repetition_penalty is not None:
-                                llms[model].repetition_penalty = config.model_params.repetition_penalty
-                            if config.model_params.pad_token_id is not None:
-                                llms[model].pad_token_id = config.model_params.pad_token_id
-
-                        logger.info(f"Successfully initialized {model}")
-                    except Exception as e:
-                        logger.error(f"Failed to initialize {model}: {e}")
-                        llms[model] = llms[LLMChoice.CLAUDE]
-                else:
-                    logger.error(f"Endpoint test failed for {model}")
-                    logger.error(f"Status: {test_result.get('status')}")
-                    logger.error(
-                        f"Response: {json.dumps(test_result.get('body', {}), indent=2)}")
-                    logger.info(f"Falling back to Claude for {model}")
-                    llms[model] = llms[LLMChoice.CLAUDE]
-
-        if tokens.get("mistral"):
-            logger.info("Testing Mistral endpoint...")
-            config = self.settings.llm_configs[LLMChoice.CODESTRAL]
-            endpoint_url = config.endpoint_url or "https://codestral.mistral.ai/v1/chat/completions"
-
-            test_result = await self._test_mistral_endpoint(endpoint_url, tokens["mistral"])
-
-            if test_result.get("status") == 200:
-                logger.info("Mistral endpoint test successful")
-                try:
-                    llms[LLMChoice.CODESTRAL] = CodestralLLM(
-                        api_key=tokens["mistral"],
-                        model=config.model,
-                        temperature=config.temperature,
-                        max_tokens=config.max_tokens,
-                        callbacks=callbacks
-                    )
-                    logger.info("Successfully initialized Mistral")
-                except Exception as e:
-                    logger.error(f"Failed to initialize Mistral: {e}")
-                    llms[LLMChoice.CODESTRAL] = llms[LLMChoice.CLAUDE]
+        created: Dict[LLMChoice, Optional[BaseLanguageModel]] = {}
+
+        async def model(choice: LLMChoice) -> Optional[BaseLanguageModel]:
+            if choice not in created:
+                token = provider_for(self.settings, choice).credentials(tokens)
+                created[choice] = (await self._initialize_model(choice, token, callbacks) if token
+                                   else None)
+            return created[choice]
+
+        # Only the models of the steps are created; a step whose model has no key or
+        # fails to initialize uses the first fallback model that works
+        for step in MIGRATION_STEPS:
+            choice = self.settings.llm_steps.model_for(step)
+            if choice in llms:
+                continue
+            for candidate in [choice, *fallback_models(self.settings, choice)]:
+                llm = await model(candidate)
+                if llm is not None:
+                    break
             else:
-                logger.error("Mistral endpoint test failed")
-                logger.error(f"Status: {test_result.get('status')}")
-                logger.error(
-                    f"Response: {json.dumps(test_result.get('body', {}), indent=2)}")
-                logger.info("Falling back to Claude for Mistral")
-                llms[LLMChoice.CODESTRAL] = llms[LLMChoice.CLAUDE]
+                raise ValueError(f"No model available for the {step} step ({choice.value})")
+            if candidate != choice:
+                logger.info(f"Falling back to {candidate.value} for {choice.value}")
+            llms[choice] = llm
+            self.serving[choice] = candidate
+
+        # Consensus candidates do not fall back: another model would only duplicate a candidate
+        for choice in self.settings.consensus_models:
+            llm = await model(choice)
+            if llm is None:
+                logger.warning(f"Skipping the {choice.value} consensus candidate: no working model")
+            else:
+                llms[choice] = llm
+                self.serving[choice] = choice
 
         return llms
@@ -0,0 +1,2771 @@
+"""
+Programmatic API for embedding the migration pipeline in other tools.
+
+Example:
+    report = await (
+        Migrator()
+        .source_dir(Path("my_app"))
+        .target_dir(Path("generated"))
+        .tokens(claude=api_key)
+        .model("fixes", LLMChoice.CODESTRAL)
+        .migrate()
+    )
+"""
+import asyncio
+import inspect
+import json
+import os
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+from .analyzers import (
+    DeadDefinition, Definition, DefinitionIndex, ImportGraph, find_dead_code, python_definitions,
+    referencing_files, remove_definitions, rust_definitions, write_source_map
+)
+from .analyzers.async_runtime import RUNTIMES, async_uses, runtime_mappings
+from .analyzers.class_hierarchy import ClassMapping, class_mappings
+from .analyzers.context_managers import ContextManager, context_managers
+from .analyzers.database import (
+    LAYERS, DatabaseUse, database_mappings, database_use, write_migrations
+)
+from .analyzers.decorators import DecoratorUse, decorator_uses
+from .analyzers.exceptions import (
+    ERRORS_DIR, ExceptionHierarchy, exception_sites, write_error_crate, write_error_module
+)
+from .analyzers.formatting import FormatConversion, format_conversions
+from .analyzers.generators import PythonGenerator, python_generators
+from .analyzers.hot_functions import hot_functions
+from .analyzers.http_routes import find_web_app
+from .analyzers.inferred_types import FunctionTypes, types_summary
+from .analyzers.licenses import CrateLicenses, LicenseViolation, license_violations
+from .analyzers.module_state import ModuleState, module_state, state_strategy
+from .analyzers.naming import KEYWORD_STYLES, Renaming, renamings
+from .analyzers.native import NativeImport, native_imports
+from .analyzers.requirements import (
+    CrateDependency, Requirement, find_requirements_root, imported_dependencies,
+    project_requirements, resolve_requirements
+)
+from .analyzers.notebook import notebook_source
+from .analyzers.profile import HotPath
+from .analyzers.python2 import python2_constructs
+from .analyzers.python_tests import find_python_tests, is_test_file
+from .analyzers.risk import extra_fix_attempts, risk_warnings, scan_risks
+from .analyzers.templates import ENGINES, engine_mappings, python_templates, write_templates
+from .analyzers.token_estimate import estimate_stage_tokens
+from .analyzers.web_framework import FRAMEWORKS, framework_mappings
+from .analyzers.visibility import ModuleVisibility, module_visibility
+from .agent.cancellation import CancellationToken, MigrationCancelled
+from .agent.migration_agent import MigrationAgent
+from .builders import CallTracer, Profiler, RustBuilder, Sandbox, TracedCall, TypeInference
+from .builders.build_stats import build_regressions, measure_build
+from .builders.database import DatabaseError, SqliteDatabase
+from .builders.hot_benchmarks import write_hot_benchmarks
+from .builders.memory import MemoryProfiler
+from .builders.python2 import convert_python2
+from .builders.workspace import package_crates, write_workspace
+from .checkers import (
+    Checker, CliEquivalence, CommentCheck, DifferentialFuzzer, HttpEquivalence,
+    NumericBoundaryCheck, OpenApiEquivalence, SchemaEquivalence, UnicodeCheck
+)
+from .checkers.html_diff import parse_selector
+from .config.crate_mappings import CrateMapping, load_crate_mappings, normalize_name
+from .config.pricing import load_pricing
+from .config.project_config import ProjectConfig
+from .config.settings import (
+    BudgetLimits, CliCase, CliComparison, CodePolicies, DependencyAudit,
+    HotFunctionBenchmarks, HttpCase, HttpComparison, LicenseCheck, LLMChoice, LLMConfig,
+    MemoryComparison, OpenApiComparison, SandboxConfig, SchemaComparison, Settings, ShellCheck,
+    StageParameters, StepLimits, WorkloadProfile
+)
+from .plugins import (
+    AsyncRuntime, CallTraces, ClassPlan, DatabaseAccess, DecoratorStrategies, DependencySignatures,
+    ErrorModule, ErrorStyle, FormatConversions, GeneratorIterators, GlobalState,
+    HotPaths, HtmlTemplates, IdentifierNames, ImportCycle, InferredTypes, ItemVisibility,
+    NativeDependencies, OpenApiAnnotations, Plugin, ProjectCrates, PythonComments, PythonDoctests,
+    PythonStructure, PythonTests, RaiiGuards, RelevantDefinitions, RequirementCrates,
+    ReviewFeedback, RustDocs, TranslationRisks, WebFramework
+)
+from .providers import MIGRATION_STEPS, get_provider, missing_tokens, provider_for, resolve_model
+from .coverage_parity import CoverageReport, measure_coverage
+from .events import EventBus, EventType, MigrationObserver
+from .plan import FilePlan, MigrationPlan
+from .prompts.loader import load_prompts, prompt_overrides, prompt_set_version
+from .report import FileReport, MigrationReport
+from .runs import (
+    BudgetTracker,
+    ClassPlanStore,
+    DigestStore,
+    FileCheckpoint,
+    FileStatus,
+    MigrationManifest,
+    ProjectStatus,
+    ReviewDecision,
+    ReviewStore,
+    RunCheckpoint,
+    RunStatus,
+    RunStore,
+    collect_status,
+    get_state_dir,
+    hash_api,
+    hash_inputs,
+    hash_rust,
+    hash_source
+)
+from .utils.har import load_har
+from .utils.logging import setup_logger
+from .utils.rate_limit import RequestLimiter
+from .utils.redaction import SecretRedactor, load_redaction_key
+
+logger = setup_logger()
+
+AVAILABLE_CHECKERS = ("build", "test")
+# Checkers of the build stage that always run
+BUILTIN_CHECKERS = (
+    "rustfmt", "check", "clippy", "policy", "audit", "cargo_test", "miri", "fuzz",
+    "numeric", "unicode", "http", "cli", "comments"
+)
+# Verification steps with their own time and memory limits
+VERIFICATION_STEPS = ("build", "test", "miri", "run", "benchmark")
+
+IGNORED_DIRS = {"__pycache__", "tests", "test", "venv", ".venv", "build", "dist"}
+
+
+def discover_python_files(source_dir: Path) -> List[Path]:
+    """List migratable Python files and notebooks under a directory, skipping tests
+    and tooling dirs."""
+    files = []
+    for path in sorted([*source_dir.rglob("*.py"), *source_dir.rglob("*.ipynb")]):
+        relative_parts = path.relative_to(source_dir).parts[:-1]
+        if any(part in IGNORED_DIRS or part.startswith(".") for part in relative_parts):
+            continue
+        if is_test_file(path) or path.name == "__init__.py":
+            continue
+        files.append(path)
+    return files
+
+
+class CheckpointTracker(MigrationObserver):
+    """Records the current stage and iteration of each file in the run checkpoint."""
+
+    def __init__(self, migrator: "Migrator"):
+        self.migrator = migrator
+
+    def on_event(self, event) -> None:
+        file = self.migrator._file_checkpoint(Path(event.file)) if event.file else None
+        if file is None:
+            return
+        if event.type == EventType.STAGE_STARTED:
+            file.stage = event.data.get("stage")
+        elif event.type == EventType.ITERATION_RETRIED:
+            file.iteration += 1
+        else:
+            return
+        self.migrator._save_checkpoint()
+
+
+class BudgetGuard(MigrationObserver):
+    """Charges the usage reported by model responses to the budget of the run."""
+
+    def __init__(self, migrator: "Migrator"):
+        self.migrator = migrator
+
+    def on_event(self, event) -> None:
+        if event.type != EventType.RESPONSE_RECEIVED or self.migrator._budget is None:
+            return
+        if "input_tokens" not in event.data and "output_tokens" not in event.data:
+            return
+        self.migrator._charge(
+            event.file, event.data.get("model"),
+            event.data.get("input_tokens", 0), event.data.get("output_tokens", 0))
+
+
+def _kinds(items: Iterable[Any], kinds: Optional[Iterable[str]] = None) -> Dict[str, int]:
+    """How many of the items are of each kind, of those given or else of their own."""
+    items = list(items)
+    return {kind: sum(item.kind == kind for item in items)
+            for kind in (kinds if kinds is not None else sorted({item.kind for item in items}))}
+
+
+@dataclass
+class FileFeature:
+    """What a file's code has of a feature: what was found, the plugin prompting for it, and its
+    entry in the metrics of the file's report."""
+    name: str  # of its metric
+    found: Any
+    plugin: Plugin
+    metric: Any = None  # None for no entry
+
+
+@dataclass
+class FileContext:
+    """A file as the finders of its features see it: its code, the crate it goes into, the
+    settings of its migration, which a finder may extend, its class plan, and the features
+    found before."""
+    source: Path
+    python_code: str
+    output_dir: Path
+    settings: Settings
+    classes: List[ClassMapping]
+    approved: bool
+    features: Dict[str, FileFeature] = field(default_factory=dict)
+
+
+class Migrator:
+    """Builder that configures and runs a migration without going through the CLI."""
+
+    def __init__(self, settings: Optional[Settings] = None):
+        self._settings = settings or Settings()
+        self._source_files: List[Path] = []
+        self._source_roots: Dict[Path, Path] = {}
+        self._target_dir: Path = self._settings.output_dir
+        self._tokens: Dict[str, Optional[str]] = {}
+        self._checkers: List[str] = list(AVAILABLE_CHECKERS)
+        self._test_script: Optional[Path] = None
+        self._entry_points: List[Path] = []
+        # Notebook each source extracted from one was, migrated in its place
+        self._notebooks: Dict[Path, Path] = {}
+        self._plugins: List[Plugin] = []
+        self._build_checkers: List[Checker] = []
+        self._events = EventBus()
+        self._cancel_token = CancellationToken()
+        self._checkpoints = True
+        self._checkpoint: Optional[RunCheckpoint] = None
+        self._output_dirs: Dict[Path, Path] = {}
+        self._incremental = True
+        self._manifest: Optional[MigrationManifest] = None
+        self._reviews: Optional[ReviewStore] = None
+        self._embeddings: Optional[Any] = None
+        self._index: Optional[DefinitionIndex] = None
+        self._traces: Dict[Path, List[TracedCall]] = {}
+        self._types: Dict[Path, Dict[str, FunctionTypes]] = {}
+        self._dead_code: Dict[Path, List[DeadDefinition]] = {}
+        # Exception classes of the whole project, translated into the error module of every crate
+        self._errors: Optional[ExceptionHierarchy] = None
+        # Crates of the libraries of the mapping database and the project's requirements
+        self._crate_dependencies: List[CrateDependency] = []
+        self._hot_paths: Dict[Path, List[HotPath]] = {}
+        # Definitions that are not hot nor used by hot ones, left out when profiling
+        # limits the migration
+        self._cold_code: Dict[Path, List[DeadDefinition]] = {}
+        self._digests: Optional[DigestStore] = None
+        self._budget: Optional[BudgetTracker] = None
+        self._budget_warnings: List[str] = []
+        self._file_tokens: Dict[str, CancellationToken] = {}
+        self._limiter: Optional[RequestLimiter] = None
+        self._redactor: Optional[SecretRedactor] = None
+        self._events.subscribe(CheckpointTracker(self))
+        self._events.subscribe(BudgetGuard(self))
+
+    def source_file(self, path: Path) -> "Migrator":
+        """Add a single Python file or notebook to migrate."""
+        path = Path(path)
+        self._source_files.append(path)
+        self._source_roots[path] = path.parent
+        return self
+
+    def source_dir(self, path: Path) -> "Migrator":
+        """Add every migratable Python file and notebook found under a directory."""
+        path = Path(path)
+        if not path.is_dir():
+            raise NotADirectoryError(f"Source directory not found: {path}")
+        for file in discover_python_files(path):
+            self._source_files.append(file)
+            self._source_roots[file] = path
+        return self
+
+    def entry_points(self, *paths: Path) -> "Migrator":
+        """Set the scripts the program starts from, which dead code is looked for from."""
+        self._entry_points = [Path(path) for path in paths]
+        return self
+
+    def target_dir(self, path: Path) -> "Migrator":
+        """Set the directory where generated Rust projects are written."""
+        self._target_dir = Path(path)
+        return self
+
+    def tokens(self, **tokens: Optional[str]) -> "Migrator":
+        """Set API tokens by provider name (claude, openai, gemini, azure, hf, mistral,
+        openai_compatible; bedrock takes an AWS profile)."""
+        self._tokens.update(tokens)
+        return self
+
+    def model(self, step: str, choice: LLMChoice) -> "Migrator":
+        """Select which model runs a migration step (analysis, generation, verification, fixes,
+        tests, build_fixes)."""
+        if not hasattr(self._settings.llm_steps, step):
+            raise ValueError(f"Unknown migration step: {step}")
+        setattr(self._settings.llm_steps, step, LLMChoice(choice))
+        return self
+
+    def stage(self, step: str, **parameters: Any) -> "Migrator":
+        """Set request parameters of a migration step: temperature, max_tokens, top_p, stop and
+        system_prompt, which replaces the step's system message.
+
+        They override those of the step's model for its requests only; in deterministic
+        mode the temperature stays 0.
+        """
+        if step not in MIGRATION_STEPS:
+            raise ValueError(f"Unknown migration step: {step}")
+        current = self._settings.stage_parameters.get(step, StageParameters())
+        self._settings.stage_parameters[step] = StageParameters.model_validate(
+            {**current.model_dump(exclude_none=True), **parameters})
+        return self
+
+    def llm_config(self, choice: LLMChoice, config: LLMConfig) -> "Migrator":
+        """Override the configuration of a model."""
+        self._settings.llm_configs[LLMChoice(choice)] = config
+        return self
+
+    def passes(self, *names: str) -> "Migrator":
+        """Select the staged translation passes (spec, skeleton, implementation, tests).
+
+        Calling it without names falls back to single-shot generation.
+        """
+        self._settings.translation_passes = Settings.model_validate(
+            {"translation_passes": list(names)}).translation_passes
+        return self
+
+    def consensus(self, *choices: LLMChoice) -> "Migrator":
+        """Generate each file with several models and keep the candidate verification scores best.
+
+        All candidates are kept under <output-dir>/debug/candidates; calling it without models
+        generates with the generation model alone.
+        """
+        self._settings.consensus_models = [LLMChoice(choice) for choice in choices]
+        return self
+
+    def chunk_tokens(self, tokens: Optional[int]) -> "Migrator":
+        """Translate modules larger than this many tokens in chunks split at class and function
+        boundaries.
+
+        None derives the size from the max_tokens and context_length of the generation model.
+        """
+        self._settings.chunk_tokens = Settings.model_validate({"chunk_tokens": tokens}).chunk_tokens
+        return self
+
+    def dependency_context_tokens(self, tokens: int) -> "Migrator":
+        """Include imported modules' Rust in prompts in full up to this many tokens,
+        then as API digests."""
+        self._settings.dependency_context_tokens = Settings.model_validate(
+            {"dependency_context_tokens": tokens}).dependency_context_tokens
+        return self
+
+    def context_definitions(self, limit: int) -> "Migrator":
+        """Add the limit project definitions most relevant to each file to its prompts; 0 disables
+        retrieval."""
+        self._settings.context_definitions = Settings.model_validate(
+            {"context_definitions": limit}).context_definitions
+        return self
+
+    def test_context_tokens(self, tokens: int) -> "Migrator":
+        """Translate the project's pytest files of each module, up to this many tokens, into its
+        Rust tests; 0 leaves them untranslated."""
+        self._settings.test_context_tokens = Settings.model_validate(
+            {"test_context_tokens": tokens}).test_context_tokens
+        return self
+
+    def trace_calls(self, command: Optional[str], limit: int = 20) -> "Migrator":
+        """Run command before translating, recording the calls of the source files' functions, and
+        have each file's Rust tests assert the same results; None stops tracing.
+
+        Keeps at most limit distinct calls per function, those with plain-data
+        arguments and results.
+        """
+        traced = Settings.model_validate({"trace_command": command, "traced_calls": limit})
+        self._settings.trace_command = traced.trace_command
+        self._settings.traced_calls = traced.traced_calls
+        return self
+
+    def infer_types(self, checker: Optional[str] = "mypy",
+                    command: Optional[str] = None) -> "Migrator":
+        """Run mypy or pyright over the source files before translating, and give each file's
+        prompts the parameter, variable and return types inferred for its functions; None stops it.
+
+        command replaces the checker's executable, e.g. "poetry run mypy".
+        """
+        inferred = Settings.model_validate({"type_checker": checker,
+                                            "type_checker_command": command})
+        self._settings.type_checker = inferred.type_checker
+        self._settings.type_checker_command = inferred.type_checker_command
+        return self
+
+    def embeddings(self, embeddings: Any) -> "Migrator":
+        """Retrieve project definitions with a LangChain embeddings model.
+
+        Its vectors are cached in <target_dir>/.python2rust/embeddings.json; by default
+        definitions are embedded locally from the words of their identifiers.
+        """
+        self._embeddings = embeddings
+        return self
+
+    def deterministic(self, replay: bool = False) -> "Migrator":
+        """Pin temperatures to 0 and record model responses under .python2rust/responses.
+
+        With replay, responses are read back from that cache and a prompt without a
+        recorded response fails the migration instead of calling the model.
+        """
+        self._settings.deterministic = True
+        self._settings.replay = replay
+        return self
+
+    def response_cache(self, enabled: bool = True) -> "Migrator":
+        """Store model responses in <target_dir>/.python2rust/responses and
+        reuse them in later runs.
+
+        On by default; a prompt sent again within a run still reaches the model.
+        """
+        self._settings.response_cache = enabled
+        return self
+
+    def budget(self, **limits: Any) -> "Migrator":
+        """Limit spending: run_cost and file_cost in USD, run_tokens and file_tokens.
+
+        Warnings are logged at the warn_at fractions of each limit. A file over its
+        limit stops; a run over its limit is cancelled, and can be resumed after
+        raising it.
+        """
+        self._settings.budget = BudgetLimits.model_validate(
+            {**self._settings.budget.model_dump(), **limits})
+        return self
+
+    def redact_secrets(self, enabled: bool = True, *patterns: str) -> "Migrator":
+        """Keep secrets out of prompts, replacing them with placeholders restored
+        in the generated code.
+
+        On by default; patterns are regexes of project-specific secrets, whose first
+        group is the secret when they have groups.
+        """
+        self._settings.redact_secrets = enabled
+        self._settings.secret_patterns = list(patterns)
+        return self
+
+    def max_concurrent_requests(self, count: int) -> "Migrator":
+        """Send at most this many model requests at once, across all files."""
+        self._settings.max_concurrent_requests = Settings.model_validate(
+            {"max_concurrent_requests": count}).max_concurrent_requests
+        return self
+
+    def requests_per_minute(self, provider: str, rate: int) -> "Migrator":
+        """Send a provider (anthropic, openai, ...) no more than this many requests a minute."""
+        get_provider(provider)
+        if rate < 1:
+            raise ValueError("requests_per_minute must be at least 1")
+        self._settings.requests_per_minute[provider] = rate
+        return self
+
+    def structured_output(self, enabled: bool = True) -> "Migrator":
+        """Have models answer code prompts through tool calling, as files in a JSON schema.
+
+        Models without tool calling answer with fenced code blocks as usual.
+        """
+        self._settings.structured_output = enabled
+        return self
+
+    def stream_responses(self, enabled: bool = True) -> "Migrator":
+        """Stream model output, emitting a TOKEN_RECEIVED event per token and writing it to
+        the debug artifacts.
+
+        On by default.
+        """
+        self._settings.stream_responses = enabled
+        return self
+
+    def project_config(self, config: ProjectConfig, root: Path = Path(".")) -> "Migrator":
+        """Apply python2rust.toml: the target dir, and each setting of [migration] and of the other
+        sections through its builder method, with the prompts, models and crate mappings it names.
+
+        Sources are not added; call source_dir with config.project.source_dir for that.
+        """
+        root = Path(root)
+        self.target_dir(root / config.project.target_dir)
+        self.checkers(*config.migration.checkers)
+        self.workers(config.migration.workers)
+        self.max_concurrent_requests(config.migration.max_concurrent_requests)
+        self.redact_secrets(config.migration.redact_secrets, *config.migration.secret_patterns)
+        self.structured_output(config.migration.structured_output)
+        for provider, rate in config.requests_per_minute.items():
+            self.requests_per_minute(provider, rate)
+        self.rustfmt(config.migration.rustfmt)
+        self.clippy_lints(*config.migration.clippy_deny, allow=config.migration.clippy_allow)
+        self.policies(**config.policies.model_dump())
+        self.audit_dependencies(config.audit.enabled, config.audit.fail, config.audit.licenses,
+                                config.audit.ignore)
+        self.crate_licenses(config.licenses.enabled, config.licenses.allow)
+        self.cargo_test(config.migration.cargo_test)
+        self.miri(config.migration.miri, config.migration.miri_flags,
+                  config.migration.miri_toolchain)
+        self.fuzz(config.migration.fuzz_cases)
+        self.numeric_checks(config.migration.numeric_checks)
+        self.unicode_checks(config.migration.unicode_checks)
+        self.structure_context(config.migration.structure_context)
+        self.rust_docs(config.migration.rust_docs)
+        self.keep_comments(config.migration.keep_comments)
+        self.doctests(config.migration.doctests)
+        self.generators(config.migration.generators)
+        self.context_managers(config.migration.context_managers)
+        self.error_module(config.migration.error_module)
+        self.format_specs(config.migration.format_specs)
+        self.workspace(config.migration.workspace)
+        self.visibility(config.migration.visibility)
+        self.naming(config.migration.naming, config.migration.keyword_style)
+        self.async_runtime(config.migration.async_runtime)
+        self.web_framework(config.migration.web_framework)
+        self.template_engine(config.migration.template_engine)
+        self.database_layer(config.migration.database_layer)
+        mappings = (root / config.migration.crate_mappings
+                    if config.migration.crate_mappings else None)
+        self.requirement_crates(config.migration.requirement_crates, root, mappings)
+        self.crates({library: crate if isinstance(crate, str)
+                     else crate.model_dump(exclude_none=True)
+                     for library, crate in config.crates.items()})
+        self.risk_report(config.migration.risk_report)
+        self.native_dependencies(config.migration.native_dependencies)
+        self.module_state(config.migration.module_state)
+        self.python2(config.migration.python2, config.migration.python2_command)
+        self.notebooks(config.migration.notebook_mode)
+        self.entry_points(*(root / path for path in config.project.entry_points))
+        self.skip_dead_code(config.migration.skip_dead_code)
+        self.class_plan(config.migration.class_plan, config.migration.approve_class_plans)
+        self.decorators(config.decorators)
+        self.benchmarks(config.benchmarks.enabled, config.benchmarks.functions,
+                        config.benchmarks.min_loop_depth, config.benchmarks.criterion)
+        self.build_stats(config.migration.build_stats)
+        self.profile(**config.profile.model_dump())
+        self.compare_http(config.http.enabled, config.http.headers, config.http.ignore,
+                          config.http.startup_timeout, config.http.ignore_elements)
+        for name, case in config.http.cases.items():
+            self.http_case(name, case.method, case.path, case.body, case.headers)
+        self.replay_har(*(root / path for path in config.http.har_files))
+        self.compare_openapi(config.openapi.enabled, config.openapi.path,
+                             config.openapi.ignore_responses, config.openapi.startup_timeout)
+        self.compare_cli(config.cli.enabled, config.cli.argv, config.cli.stdin, config.cli.stderr,
+                         config.cli.ignore, config.cli.timeout)
+        self.compare_memory(config.memory.enabled, config.memory.allocations,
+                            config.memory.valgrind)
+        self.compare_schema(**config.database.model_dump())
+        for name, case in config.cli.cases.items():
+            self.cli_case(name, case.args, case.stdin)
+        for step in VERIFICATION_STEPS:
+            self.limits(step, **getattr(config.limits, step).model_dump())
+        self.sandbox(**config.sandbox.model_dump())
+        for name, check in config.shell_checkers.items():
+            self.shell_checker(name, check.command, check.exit_codes, check.timeout)
+        self.passes(*config.migration.translation_passes)
+        self.consensus(*config.migration.consensus)
+        if config.migration.chunk_tokens is not None:
+            self.chunk_tokens(config.migration.chunk_tokens)
+        self.dependency_context_tokens(config.migration.dependency_context_tokens)
+        self.context_definitions(config.migration.context_definitions)
+        self.test_context_tokens(config.migration.test_context_tokens)
+        if config.migration.trace_command:
+            self.trace_calls(config.migration.trace_command, config.migration.traced_calls)
+        if config.migration.type_checker:
+            self.infer_types(config.migration.type_checker, config.migration.type_checker_command)
+        if config.migration.prompts_dir is not None:
+            self.prompts_dir(root / config.migration.prompts_dir)
+        self.budget(**config.budget.model_dump(exclude_unset=True))
+        for step, choice in config.models.items():
+            self.model(step, choice)
+        for step, parameters in config.stages.items():
+            self.stage(step, **parameters.model_dump(exclude_none=True))
+        for choice, overrides in config.model_settings.items():
+            current = self._settings.llm_configs[LLMChoice(choice)]
+            self.llm_config(choice, LLMConfig.model_validate({**current.model_dump(), **overrides}))
+        if config.crates:
+            self.plugin(ProjectCrates({library: crate if isinstance(crate, str) else crate.crate
+                                       for library, crate in config.crates.items()}))
+        return self
+
+    def checkers(self, *names: str) -> "Migrator":
+        """Select which verification stages run after generation (build, test)."""
+        unknown = [name for name in names if name not in AVAILABLE_CHECKERS]
+        if unknown:
+            raise ValueError(f"Unknown checkers: {', '.join(unknown)}")
+        if "test" in names and "build" not in names:
+            raise ValueError("The test checker requires the build checker")
+        self._checkers = list(names)
+        return self
+
+    def test_script(self, path: Path) -> "Migrator":
+        """Use a specific server test script instead of the one next to each source file."""
+        self._test_script = Path(path)
+        return self
+
+    def rustfmt(self, enabled: bool = True) -> "Migrator":
+        """Format each revision of the generated code with rustfmt before it is
+        recorded and checked.
+
+        On by default; code rustfmt cannot parse gets a fix iteration like a build error.
+        """
+        self._settings.rustfmt = enabled
+        return self
+
+    def policies(self, **rules: Any) -> "Migrator":
+        """Enforce policies on generated code outside tests, fixing it until it follows them.
+
+        rules are the CodePolicies fields: no_unwrap, no_expect, no_unsafe, no_panic (panic!,
+        todo!, unimplemented!), max_function_lines, forbidden_macros and error_style: anyhow,
+        thiserror or std, which every prompt asks for too. Code still breaking them after the fix
+        attempts fails its file.
+        """
+        self._settings.policies = CodePolicies.model_validate({
+            **self._settings.policies.model_dump(), **rules})
+        return self
+
+    def audit_dependencies(
+        self,
+        enabled: bool = True,
+        fail: bool = False,
+        licenses: Optional[Iterable[str]] = None,
+        ignore: Iterable[str] = ()
+    ) -> "Migrator":
+        """Audit the dependency tree of each crate that builds with cargo-deny: advisories against
+        its crates (vulnerable, unmaintained), yanked versions and licenses outside licenses.
+
+        Violations are file warnings of the report, or with fail verification failures the build
+        fix prompt resolves by changing the dependencies. ignore lists accepted advisory IDs.
+        """
+        self._settings.audit = DependencyAudit(
+            enabled=enabled,
+            fail=fail,
+            licenses=list(licenses) if licenses is not None else DependencyAudit().licenses,
+            ignore=list(ignore)
+        )
+        return self
+
+    def crate_licenses(self, enabled: bool = True,
+                       allow: Optional[Iterable[str]] = None) -> "Migrator":
+        """Fetch from crates.io the licenses of the crates replacing the libraries of the project
+        (those it requires or its modules import), and fail plans and runs before any code is
+        generated when one is outside allow, the SPDX identifiers of the licenses crates may use,
+        permissive ones by default. Each violation lists the crates of its category that are
+        allowed, to choose from under [crates]. Licenses are cached in the state directory."""
+        self._settings.licenses = LicenseCheck(
+            enabled=enabled,
+            allow=list(allow) if allow is not None else LicenseCheck().allow
+        )
+        return self
+
+    def cargo_test(self, enabled: bool = True) -> "Migrator":
+        """Run `cargo test` on each crate that builds and fix the code until its tests pass.
+
+        On by default; the failing tests and their panics go to the build fix prompt.
+        """
+        self._settings.cargo_test = enabled
+        return self
+
+    def miri(self, enabled: bool = True, flags: Iterable[str] = (),
+             toolchain: str = "nightly") -> "Migrator":
+        """Run the tests of each crate under Miri after cargo test, fixing the code until Miri finds
+        no undefined behavior; flags are passed as MIRIFLAGS.
+
+        Off by default since Miri is slow; it needs the miri component of the toolchain.
+        """
+        self._settings.miri = enabled
+        self._settings.miri_flags = list(flags)
+        self._settings.miri_toolchain = toolchain
+        return self
+
+    def fuzz(self, cases: int = 100) -> "Migrator":
+        """Call each Python function and its Rust translation with cases random inputs, fixing the
+        code until their results agree; 0 disables fuzzing."""
+        self._settings.fuzz_cases = Settings.model_validate({"fuzz_cases": cases}).fuzz_cases
+        return self
+
+    def numeric_checks(self, enabled: bool = True) -> "Migrator":
+        """Flag the arithmetic where Python ints, flooring division and float conversions may not
+        match the Rust code, and fix the code until both agree on boundary
+        values; off by default."""
+        self._settings.numeric_checks = enabled
+        return self
+
+    def risk_report(self, enabled: bool = True) -> "Migrator":
+        """Scan each file for eval/exec, monkeypatching, metaclasses, __getattr__ and runtime
+        reflection, warning its prompts and its report about them and giving it up to 4 more fix
+        attempts per workflow (2 per high risk, 1 per medium one); on by default."""
+        self._settings.risk_report = enabled
+        return self
+
+    def native_dependencies(self, enabled: bool = True) -> "Migrator":
+        """Find the imports backed by compiled code: well-known native packages like numpy or lxml,
+        the project's extension modules and installed packages shipping any. The prompts ask for
+        their replacement crates or FFI bindings, code with neither fails verification, and plans
+        and reports list them; on by default."""
+        self._settings.native_dependencies = enabled
+        return self
+
+    def module_state(self, strategy: Optional[str] = "app_state") -> "Migrator":
+        """How every module holds its module-level state: the globals its functions rebind or
+        change in place and its singletons. "once_lock" makes them OnceLock or LazyLock statics
+        (behind a Mutex when changed), "app_state" (the default) fields of one AppState struct
+        created in main, "injection" parameters of the code using them; singletons nothing changes
+        stay OnceLock statics unless injected. Plans list the state found with its strategy, and
+        code holding it otherwise fails verification. None or "none" leaves it
+        to each generation."""
+        self._settings.module_state = (Settings.model_validate({"module_state": strategy})
+                                       .module_state)
+        return self
+
+    def class_plan(self, enabled: bool = True, approve: bool = False) -> "Migrator":
+        """Plan the Rust item of each class from the module's class hierarchy, its inheritance
+        depth, overridden methods and isinstance checks: concrete classes become structs, bases
+        their subclasses extend traits, closed hierarchies told apart with isinstance enums with a
+        variant per subclass. The plan goes into the generation prompt and verification, and plans
+        list it; on by default. With approve, a file with classes is only migrated once the user
+        approved its plan, possibly changing kinds, with `python2rust classes`."""
+        self._settings.class_plan = enabled
+        self._settings.approve_class_plans = approve
+        return self
+
+    def decorators(self, strategies: Dict[str, str]) -> "Migrator":
+        """Translate decorators with these strategies, keyed by dotted name as imported
+        ("functools.wraps") or by pattern ("*.route"), over those of the built-in catalog of
+        config/decorators.json. The prompts name the strategy of each decorator a module uses,
+        and reports and plans list those neither maps, nor the project defines."""
+        self._settings.decorators.update(strategies)
+        return self
+
+    def skip_dead_code(self, enabled: bool = True) -> "Migrator":
+        """Leave out the top-level functions and classes nothing reachable uses, listing them in the
+        file reports; off by default.
+
+        Reachable is the module-level code of the files imported from the entry points, the
+        decorated definitions and what the tests and __init__.py files name; without entry
+        points every file is imported and only private definitions can be unused.
+        """
+        self._settings.skip_dead_code = enabled
+        return self
+
+    def python2(self, mode: str = "convert", command: Optional[str] = None) -> "Migrator":
+        """How to handle files with Python 2 only syntax or builtins: "fail" (the default without
+        this call) reports them as failed with their constructs, "convert" translates a copy
+        normalized by 2to3, or by command with {file} for the copy, kept in debug/python3.
+        """
+        python2 = Settings.model_validate({"python2": mode, "python2_command": command})
+        self._settings.python2 = python2.python2
+        self._settings.python2_command = python2.python2_command
+        return self
+
+    def notebooks(self, mode: str = "script") -> "Migrator":
+        """How to translate .ipynb sources, whose code cells are extracted in order: as a "script"
+        (the default) whose top-level code is the program, or as a "library" of the imports,
+        definitions and assignments of its cells. Source maps trace their items to the cells."""
+        self._settings.notebook_mode = (Settings.model_validate({"notebook_mode": mode})
+                                        .notebook_mode)
+        return self
+
+    def rust_docs(self, enabled: bool = True) -> "Migrator":
+        """Carry the docstrings onto the Rust items translating each definition as /// comments
+        (//! for the module's), with their Google, NumPy or reST parameter, return, exception and
+        example sections as rustdoc Arguments, Returns, Errors and Examples sections; items left
+        without them fail verification. On by default."""
+        self._settings.rust_docs = enabled
+        return self
+
+    def keep_comments(self, enabled: bool = True) -> "Migrator":
+        """Keep the comments of the Python code: the generation prompt lists them with the
+        statements they are at, and a checker locating the Rust functions with the syn-based
+        analyzer fixes the code until each repeats the comments of the Python function it
+        translates. On by default."""
+        self._settings.keep_comments = enabled
+        return self
+
+    def doctests(self, enabled: bool = True) -> "Migrator":
+        """Translate the doctests of the docstrings into #[test] functions named after the
+        definitions they document, asserting values with assert_eq!, comparing printed output as
+        text and expecting an Err or a panic for tracebacks; examples left without their test fail
+        verification. Doc tests would not run for the binary crates generated. On by default."""
+        self._settings.doctests = enabled
+        return self
+
+    def generators(self, enabled: bool = True) -> "Migrator":
+        """Translate generator functions into Iterator implementations and generator expressions
+        into iterator chains, both producing their elements on demand, with a #[test] per
+        generator function comparing its elements one by one with those Python yields. Generator
+        functions returning a Vec, or without their test, fail verification. On by default."""
+        self._settings.generators = enabled
+        return self
+
+    def context_managers(self, enabled: bool = True) -> "Migrator":
+        """Translate with blocks and the context managers of the module, classes with __enter__
+        and __exit__ and @contextmanager functions, into RAII guards whose Drop does the cleanup,
+        so that it runs on early returns, errors and panics, with a #[test] per context manager
+        checking its cleanup on the error path. Context managers without their Drop implementation
+        or test fail verification. On by default."""
+        self._settings.context_managers = enabled
+        return self
+
+    def error_module(self, enabled: bool = True) -> "Migrator":
+        """Generate one error module from the exception classes of the whole project and their
+        raise and except sites: a thiserror enum per exception with subclasses, a struct per other
+        exception keeping the message its __init__ formats, an Error enum over the root exceptions
+        and the built-in ones raised or caught, and the From conversions between their levels.
+        The module is the errors crate written to <target_dir>/shared/errors, a member of the
+        workspace too; every crate declares `mod error;`, its src/error.rs re-exporting the crate it
+        depends on, and code without it, defining the error types again or with String and
+        Box<dyn Error> errors fails verification. On by default, it is left out when the policies
+        choose the anyhow or std error style."""
+        self._settings.error_module = enabled
+        return self
+
+    def format_specs(self, enabled: bool = True) -> "Migrator":
+        """Convert the format specs of f-strings, str.format calls and % formatting (`{x:>8.3f}`,
+        `%05d`, `!r`) into the Rust placeholders reproducing them, or into what a helper has to
+        reproduce where Rust's formatting cannot (repr, exponents, grouping, the g and % types),
+        with a #[test] comparing the Rust formatting of representative values with Python's output,
+        computed with Python. Code without the test or keeping Python specs in its format strings
+        fails verification. On by default."""
+        self._settings.format_specs = enabled
+        return self
+
+    def workspace(self, enabled: bool = True) -> "Migrator":
+        """When the modules are in several packages (a monorepo, a src layout with several top-level
+        packages), also generate <target_dir>/workspace: a Cargo workspace with a library crate per
+        package whose modules are the verified crates of its files, path dependencies on the crates
+        of the packages it imports, and the version, edition and dependencies the crates share as
+        workspace metadata. Packages importing each other in a cycle are reported, the import
+        closing it left out. On by default."""
+        self._settings.workspace = enabled
+        return self
+
+    def visibility(self, enabled: bool = True) -> "Migrator":
+        """Mirror the visibility of each module's items, `pub` for the names of __all__ or those
+        without a leading underscore and private otherwise, failing code declaring them otherwise;
+        the package crates of the workspace then follow the package layout (pkg/sub/io.py is
+        sub::io) and re-export the names each __init__ exports, so that their paths stay the
+        familiar ones. On by default."""
+        self._settings.visibility = enabled
+        return self
+
+    def naming(self, enabled: bool = True, keywords: str = "raw") -> "Migrator":
+        """Give the Python identifiers their idiomatic Rust names before generation: snake_case
+        functions, methods, parameters and variables (getValue is get_value), CamelCase types,
+        SCREAMING_SNAKE_CASE constants, private definitions without their leading underscore, and
+        names colliding with a Rust keyword escaped as raw identifiers (r#type) or, with keywords
+        "suffix", with a trailing underscore (type_). Every module converts them the same way, so
+        that calls across crates agree, and the renaming table goes into the source map. Code
+        declaring the Python names instead fails verification. On by default."""
+        if keywords not in KEYWORD_STYLES:
+            raise ValueError(f"Unknown keyword style {keywords}, expected one of "
+                             f"{', '.join(KEYWORD_STYLES)}")
+        self._settings.naming = enabled
+        self._settings.keyword_style = keywords
+        return self
+
+    def async_runtime(self, runtime: str = "tokio") -> "Migrator":
+        """Target an async runtime: tokio, async-std, or sync for synchronous code. The prompts of
+        modules with async functions, awaits or calls sleeping, spawning or gathering work (asyncio,
+        time.sleep, threading.Thread) give the runtime's main and test attributes and its idiom for
+        each of them, time.sleep and threading.Thread in plain functions keeping std's blocking
+        ones, the libraries mapped to tokio take the runtime's crate, or none, and code using
+        another runtime, or async code when synchronous, fails verification. tokio by default."""
+        if runtime not in RUNTIMES:
+            raise ValueError(f"Unknown async runtime {runtime}, expected one of "
+                             f"{', '.join(RUNTIMES)}")
+        self._settings.async_runtime = runtime
+        return self
+
+    def web_framework(self, framework: str = "axum") -> "Migrator":
+        """Generate the services of Flask and FastAPI apps against a web framework: axum, actix-web
+        or rocket. The prompts register each route with its path in the framework's syntax
+        (/items/<int:id> is /items/:id in axum, /items/{id} in actix-web, /items/<id> in rocket)
+        and give its extractors, shared state and server idioms, the web libraries are mapped to
+        its crate, and code using another framework or leaving routes out fails verification.
+        The apps run on the framework's runtime, tokio, whatever the async runtime. axum by
+        default."""
+        if framework not in FRAMEWORKS:
+            raise ValueError(f"Unknown web framework {framework}, expected one of "
+                             f"{', '.join(FRAMEWORKS)}")
+        self._settings.web_framework = framework
+        return self
+
+    def template_engine(self, engine: str = "askama") -> "Migrator":
+        """Render the Jinja templates of the sources, inline or files of a templates directory next
+        to them, with a template engine: askama, tera or maud. askama and tera templates are written
+        in the engine's syntax into the templates/ directory of the crate, compiled or embedded into
+        it, maud ones converted into html! markup. jinja2 is mapped to the engine's crate, and code
+        building HTML by string substitution or leaving a template out fails verification. askama
+        by default."""
+        if engine not in ENGINES:
+            raise ValueError(f"Unknown template engine {engine}, expected one of "
+                             f"{', '.join(ENGINES)}")
+        self._settings.template_engine = engine
+        return self
+
+    def database_layer(self, layer: str = "sqlx") -> "Migrator":
+        """Generate the database access of sources using a SQL driver (sqlite3, psycopg2, pymysql,
+        SQLAlchemy, ...) against a data access stack: sqlx, diesel or sea-orm. The prompts give the
+        layer's connection pool for the backend of the driver or database URL and its checked
+        queries, diesel's and sea-orm's typed ones or sqlx's query! macros, built against a
+        scratch SQLite database of the tables (sqlx's runtime queries on other backends), and the
+        tables the sources create are written as migrations into the migrations/ directory of the
+        crate, run at startup. The drivers are mapped to the layer's crate, with the features of
+        their backend and of the async runtime, and code using a driver crate, creating the tables
+        itself or not running the migrations fails verification. sqlx by default."""
+        if layer not in LAYERS:
+            raise ValueError(f"Unknown database layer {layer}, expected one of {', '.join(LAYERS)}")
+        self._settings.database_layer = layer
+        return self
+
+    def requirement_crates(self, enabled: bool = True, project_dir: Optional[Path] = None,
+                           mappings: Optional[Path] = None) -> "Migrator":
+        """Resolve the requirements of the project (pyproject.toml and requirements*.txt in
+        project_dir, by default the nearest directory up from the sources declaring them) and the
+        libraries each module imports through the crate mapping database of
+        config/crate_mappings.json, extended by a mappings file of the same format and by crates().
+        The [dependencies] line of each crate, its version and features, replaces the one the model
+        wrote in every Cargo.toml and is added when the code uses the crate without declaring it, so
+        that manifests do not depend on the model. Requirements no mapping covers are reported and
+        listed by plans. On by default."""
+        self._settings.requirement_crates = enabled
+        self._settings.requirements_dir = Path(project_dir) if project_dir is not None else None
+        self._settings.crate_mappings_file = Path(mappings) if mappings is not None else None
+        return self
+
+    def crates(self, mappings: Dict[str, Any]) -> "Migrator":
+        """Replace the crate of Python libraries, given by name ("requests": "ureq") or as a table
+        with its version requirement and features ({"crate": "reqwest", "version": "0.12",
+        "features": ["json"]}); a crate given by name keeps the version of its mapping, if any."""
+        self._settings.crates.update(mappings)
+        return self
+
+    def structure_context(self, enabled: bool = True) -> "Migrator":
+        """Give the analysis and generation prompts the imports, classes, signatures and Python
+        features of each module, read from its syntax tree; on by default."""
+        self._settings.structure_context = enabled
+        return self
+
+    def unicode_checks(self, enabled: bool = True) -> "Migrator":
+        """Flag the string lengths, indexes and slices Python counts in code points and Rust in
+        bytes, and fix the code until both agree on multi-byte text; off by default."""
+        self._settings.unicode_checks = enabled
+        return self
+
+    def benchmarks(
+        self,
+        enabled: bool = True,
+        functions: Optional[Dict[str, List[Any]]] = None,
+        min_loop_depth: int = 2,
+        criterion: str = "0.5"
+    ) -> "Migrator":
+        """Write criterion benchmarks and a Python timing script of the hot functions to the
+        benches/ directory of each verified crate.
+
+        A function is hot when named in functions, which gives the arguments of its measured call,
+        or when its loops nest min_loop_depth deep or it calls itself; only functions taking
+        numbers, booleans, strings or lists of them are benchmarked.
+        """
+        self._settings.benchmarks = HotFunctionBenchmarks(
+            enabled=enabled, functions=dict(functions or {}), min_loop_depth=min_loop_depth,
+            criterion=criterion)
+        return self
+
+    def profile(
+        self,
+        command: Optional[str],
+        threshold: float = 0.05,
+        limit: bool = False,
+        timeout: float = 600
+    ) -> "Migrator":
+        """Run a workload command under cProfile before migrating, and point the prompts of each
+        file at its functions taking at least threshold of the CPU time, migrating the files with
+        the most first; None stops it.
+
+        With limit only the hot functions and what they use are migrated, in their files and the
+        files these import; the plan lists the hot functions before anything runs.
+        """
+        self._settings.profile = WorkloadProfile(
+            command=command, threshold=threshold, limit=limit, timeout=timeout)
+        return self
+
+    def build_stats(self, enabled: bool = True) -> "Migrator":
+        """Measure the release build time, rebuild time after an edit, binary size and dependencies
+        of each verified crate, in the "build" metrics of the file reports and the run checkpoint.
+
+        A measure grown past its limit since the latest earlier run of the same file is a
+        warning of the file report and a regression of `python2rust diff`.
+        """
+        self._settings.build_stats = enabled
+        return self
+
+    def compare_http(
+        self,
+        enabled: bool = True,
+        headers: Iterable[str] = ("content-type",),
+        ignore: Iterable[str] = (),
+        startup_timeout: float = 60,
+        ignore_elements: Iterable[str] = ()
+    ) -> "Migrator":
+        """Start each web app in Python and in Rust, send both the same requests and fix the Rust
+        code until their responses agree.
+
+        Requests go to the routes of the app without path parameters, then to the http_case
+        ones. Statuses, headers and bodies are compared, bodies after replacing the ignore regexes;
+        HTML is compared by structure and text, without the content of the ignore_elements
+        selectors (tag, #id, .class).
+        """
+        unsupported = [selector for selector in ignore_elements if parse_selector(selector) is None]
+        if unsupported:
+            raise ValueError(f"Unsupported selectors: {', '.join(unsupported)}")
+        self._settings.http = HttpComparison(
+            enabled=enabled,
+            cases=self._settings.http.cases,
+            har_files=self._settings.http.har_files,
+            headers=list(headers),
+            ignore=list(ignore),
+            ignore_elements=list(ignore_elements),
+            startup_timeout=startup_timeout
+        )
+        return self
+
+    def compare_openapi(
+        self,
+        enabled: bool = True,
+        path: str = "/openapi.json",
+        ignore_responses: Iterable[str] = ("422",),
+        startup_timeout: float = 60
+    ) -> "Migrator":
+        """Ask the generation of web apps for utoipa annotations and an OpenAPI document served at
+        path, and fix the Rust code until its operations match the OpenAPI description of the Python
+        app: FastAPI's, or for Flask the paths and path parameters of its routes.
+
+        Parameters, request bodies and response schemas are compared, without the responses in
+        ignore_responses, FastAPI's validation errors by default.
+        """
+        self._settings.openapi = OpenApiComparison(
+            enabled=enabled, path=path,
+            ignore_responses=[str(status) for status in ignore_responses],
+            startup_timeout=startup_timeout)
+        return self
+
+    def http_case(
+        self,
+        name: str,
+        method: str = "GET",
+        path: str = "/",
+        body: Optional[str] = None,
+        headers: Optional[Dict[str, str]] = None
+    ) -> "Migrator":
+        """Send this request to both servers when comparing web app responses."""
+        self._settings.http.cases[name] = HttpCase(
+            method=method.upper(), path=path, body=body, headers=headers or {})
+        return self
+
+    def replay_har(self, *paths: Path) -> "Migrator":
+        """Also send the requests recorded in these HAR files to both servers when comparing web
+        app responses; the pass or fail of each lands in the crate's debug/http_report.json."""
+        for path in paths:
+            load_har(path)
+        self._settings.http.har_files.extend(Path(path) for path in paths)
+        return self
+
+    def compare_cli(
+        self,
+        enabled: bool = True,
+        argv: Iterable[Iterable[str]] = ((),),
+        stdin: Iterable[str] = ("",),
+        stderr: bool = True,
+        ignore: Iterable[str] = (),
+        timeout: Optional[float] = None
+    ) -> "Migrator":
+        """Run each command-line script and its Rust binary with every argument list of argv and
+        every standard input, then the cli_case runs, fixing the Rust code until their exit codes
+        and outputs agree.
+
+        Standard error is compared unless stderr is False; ignore regexes are replaced first.
+        timeout replaces the run limit of each run.
+        """
+        self._settings.cli = CliComparison(
+            enabled=enabled,
+            argv=[list(args) for args in argv],
+            stdin=list(stdin),
+            cases=self._settings.cli.cases,
+            stderr=stderr,
+            ignore=list(ignore),
+            timeout=timeout
+        )
+        return self
+
+    def cli_case(self, name: str, args: Iterable[str] = (),
+                 stdin: Optional[str] = None) -> "Migrator":
+        """Also run command-line scripts with these arguments and standard input
+        when comparing them."""
+        self._settings.cli.cases[name] = CliCase(args=list(args), stdin=stdin)
+        return self
+
+    def compare_memory(
+        self,
+        enabled: bool = True,
+        allocations: bool = True,
+        valgrind: str = "valgrind"
+    ) -> "Migrator":
+        """Measure the peak resident memory of the Python and Rust runs of compare_http and
+        compare_cli, and their heap allocations in runs under valgrind once their outputs agree
+        (unless allocations is False), in the "memory" metrics of the file reports.
+
+        Allocations are not counted when valgrind is not installed, and sandboxed Rust runs are
+        not measured.
+        """
+        self._settings.memory = MemoryComparison(enabled=enabled,
+                                                 allocations=allocations, valgrind=valgrind)
+        return self
+
+    def compare_schema(
+        self,
+        enabled: bool = True,
+        engine: str = "sqlite",
+        python_command: Optional[str] = None,
+        rust_command: Optional[str] = None,
+        ignore_tables: Optional[Iterable[str]] = None,
+        postgres_image: str = "postgres:16",
+        docker: str = "docker"
+    ) -> "Migrator":
+        """Create the database schema of the Python models and of the Rust code in throwaway
+        databases, SQLite files or a Postgres container (engine "postgres"), and fix the Rust
+        code until their tables, columns, constraints and indexes agree.
+
+        python_command and rust_command create a schema in $DATABASE_URL, e.g. by running
+        migrations; {binary} in rust_command is the Rust binary. By default the SQLAlchemy
+        metadata of a module is created, and the crate's migrations/ SQL files or the CREATE
+        statements of its code are applied. Tables in ignore_tables are left out, the history
+        tables of migration tools by default.
+        """
+        schema = SchemaComparison(
+            enabled=enabled, engine=engine, python_command=python_command,
+            rust_command=rust_command, postgres_image=postgres_image, docker=docker)
+        if ignore_tables is not None:
+            schema.ignore_tables = list(ignore_tables)
+        self._settings.database_schema = schema
+        return self
+
+    def limits(self, step: str, timeout: Optional[float] = None,
+               memory_mb: Optional[int] = None) -> "Migrator":
+        """Limit the wall-clock seconds and memory of a verification step; None is unlimited.
+
+        The steps are build (cargo check, build and clippy), test (cargo test and the fuzz
+        harness), miri (cargo miri test), run (each run of a generated program, request to a
+        generated server and server test script) and benchmark (the workload of each server in
+        the bench command). A step running out of time fails verification, and the fix-up prompt
+        is told it timed out.
+        """
+        if step not in VERIFICATION_STEPS:
+            raise ValueError(f"Unknown verification step {step!r}: use one of "
+                             f"{', '.join(VERIFICATION_STEPS)}")
+        setattr(self._settings.limits, step, StepLimits(timeout=timeout, memory_mb=memory_mb))
+        return self
+
+    def sandbox(self, enabled: bool = True, **options: Any) -> "Migrator":
+        """Build, test and run the generated code in a Docker container rather than on this machine.
+
+        options are the SandboxConfig fields: image, network (none by default), server_network,
+        memory, cpus, pids, mounts (host:container[:ro]) and docker.
+        """
+        self._settings.sandbox = SandboxConfig.model_validate({
+            **self._settings.sandbox.model_dump(), **options, "enabled": enabled})
+        return self
+
+    def clippy_lints(self, *deny: str, allow: Iterable[str] = ()) -> "Migrator":
+        """Deny these clippy lints or groups in generated code (warnings and clippy::pedantic, ...),
+        except the allowed ones.
+
+        Code is only accepted once they are all fixed. Calling it without lints skips clippy.
+        """
+        self._settings.clippy_deny = list(deny)
+        self._settings.clippy_allow = list(allow)
+        return self
+
+    def add_checker(self, checker: Checker) -> "Migrator":
+        """Run a Checker on each generated crate after cargo check and clippy.
+
+        Its failures are sent to the build fix prompt like compiler errors.
+        """
+        self._build_checkers.append(checker)
+        return self
+
+    def shell_checker(
+        self,
+        name: str,
+        command: str,
+        exit_codes: Iterable[int] = (0,),
+        timeout: float = 300
+    ) -> "Migrator":
+        """Run a shell command in each generated crate after cargo check and clippy.
+
+        The check passes when the command exits with one of exit_codes; otherwise its
+        output is sent to the build fix prompt.
+        """
+        if name in BUILTIN_CHECKERS:
+            raise ValueError(f"{name} is a built-in checker")
+        self._settings.shell_checkers[name] = ShellCheck(
+            command=command, exit_codes=list(exit_codes), timeout=timeout)
+        return self
+
+    def plugin(self, plugin: Plugin) -> "Migrator":
+        """Register a plugin in addition to those found in the plugins directory."""
+        self._plugins.append(plugin)
+        return self
+
+    def plugins_dir(self, path: Path) -> "Migrator":
+        """Load plugins from a directory other than ./plugins."""
+        self._settings.plugins_dir = Path(path)
+        return self
+
+    def prompts_dir(self, path: Optional[Path]) -> "Migrator":
+        """Override the packaged prompt templates with the <template>.txt files of a directory.
+
+        The templates are checked right away: unknown template names or variables raise
+        ValueError. None restores the packaged templates.
+        """
+        if path is not None:
+            load_prompts(path)
+        self._settings.prompts_dir = Path(path) if path is not None else None
+        return self
+
+    def observer(self, observer: MigrationObserver) -> "Migrator":
+        """Subscribe an observer to pipeline events."""
+        self._events.subscribe(observer)
+        return self
+
+    def workers(self, count: int) -> "Migrator":
+        """Set how many independent files are migrated concurrently."""
+        if count < 1:
+            raise ValueError("At least one worker is required")
+        self._settings.max_workers = count
+        return self
+
+    def cancellation_token(self, token: CancellationToken) -> "Migrator":
+        """Use a caller-owned token to abort the migration."""
+        self._cancel_token = token
+        return self
+
+    def cancel(self) -> None:
+        """Abort the running migration; safe to call from another thread."""
+        self._cancel_token.cancel()
+
+    def checkpoints(self, enabled: bool = True) -> "Migrator":
+        """Enable or disable run checkpoints under <target_dir>/.python2rust/runs."""
+        self._checkpoints = enabled
+        return self
+
+    def incremental(self, enabled: bool = True) -> "Migrator":
+        """Reuse verified output for files whose inputs did not change since the last run."""
+        self._incremental = enabled
+        return self
+
+    def _check_tokens(self) -> None:
+        missing = missing_tokens(self._settings, self._tokens)
+        if missing:
+            steps = ", ".join(f"{step} ({token})" for step, token in missing.items())
+            raise ValueError(f"No API token for the models of: {steps}")
+
+    def _check_licenses(self) -> None:
+        violations = self._license_violations(self._crate_dependencies)
+        if violations:
+            listed = "\n".join(f"- {violation.describe()}" for violation in violations)
+            raise ValueError("Crates under licenses outside the allowed ones, replace them under "
+                             f"[crates] in python2rust.toml:\n{listed}")
+
+    def _extract_notebooks(self) -> None:
+        """Replace the notebook sources by their code, extracted under the state directory with
+        the same module names."""
+        directory = get_state_dir(self._target_dir) / "notebooks"
+        extracted: Dict[Path, Path] = {}
+        for index, source in enumerate(self._source_files):
+            if source.suffix != ".ipynb":
+                continue
+            root = self._source_roots.pop(source)
+            try:
+                code = notebook_source(source.read_text(), self._settings.notebook_mode)
+            except (OSError, ValueError) as e:
+                raise ValueError(f"Cannot read notebook {source}: {e}") from e
+            file = directory / source.relative_to(root).with_suffix(".py")
+            file.parent.mkdir(parents=True, exist_ok=True)
+            file.write_text(code)
+            logger.info(f"Extracted the code cells of {source} into {file}")
+            self._source_files[index] = file
+            self._source_roots[file] = directory
+            self._notebooks[file] = source
+            extracted[source] = file
+        self._entry_points = [extracted.get(path, path) for path in self._entry_points]
+
+    def _output_dir_for(self, source: Path) -> Path:
+        """Place each file in its own crate, unless there is only one."""
+        if source in self._output_dirs:
+            return self._output_dirs[source]
+        if len(self._source_files) == 1:
+            return self._target_dir
+        relative = source.relative_to(self._source_roots[source]).with_suffix("")
+        return self._target_dir / "_".join(relative.parts)
+
+    async def _query_database(self, output_dir: Path, database: DatabaseUse) -> Optional[str]:
+        """The URL of a scratch SQLite database with the tables of the module, for the query!
+        macros of sqlx to check the queries against when the code builds. None for layers not
+        checking them and other backends, which no database is at hand for: their queries are
+        checked at run time."""
+        if not (LAYERS[self._settings.database_layer].checks_queries
+                and database.backend == "sqlite"
+                and database.tables):
+            return None
+        path = output_dir / "debug" / "queries.db"
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.unlink(missing_ok=True)
+        scratch = SqliteDatabase(path)
+        try:
+            await scratch.execute("\n".join(table.sql.rstrip(";") + ";"
+                                            for table in database.tables))
+        except DatabaseError as e:
+            logger.warning(f"Could not create the tables of {output_dir.name} to "
+                           f"check its queries: {e}")
+            return None
+        return scratch.url
+
+    def _settings_for(self, output_dir: Path) -> Settings:
+        settings = self._settings.model_copy(deep=True)
+        settings.output_dir = output_dir
+        settings.debug_dir = output_dir / "debug"
+        if ((settings.deterministic or settings.response_cache)
+                and settings.response_cache_dir is None):
+            settings.response_cache_dir = get_state_dir(self._target_dir) / "responses"
+        return settings
+
+    @property
+    def _store(self) -> RunStore:
+        return RunStore(self._target_dir)
+
+    def _save_checkpoint(self) -> None:
+        if self._checkpoint is not None:
+            self._store.save(self._checkpoint)
+
+    def _file_checkpoint(self, source: Path) -> Optional[FileCheckpoint]:
+        if self._checkpoint is None:
+            return None
+        return self._checkpoint.files.get(str(source))
+
+    def _record_outputs(
+        self,
+        file: FileCheckpoint,
+        status: FileStatus,
+        rust_code: Optional[str],
+        toml_content: Optional[str]
+    ) -> None:
+        """Update a file checkpoint with its outcome and any code produced."""
+        file.status = status
+        if rust_code:
+            self._store.save_outputs(self._checkpoint, file.source, rust_code, toml_content)
+            if status == FileStatus.VERIFIED:
+                file.last_successful_iteration = file.iteration
+
+        summary_file = Path(file.output_dir) / "debug" / "summary.json"
+        if summary_file.exists():
+            try:
+                totals = json.loads(summary_file.read_text()).get("totals", {})
+                file.tokens_used = totals.get("total_tokens", 0)
+            except json.JSONDecodeError:
+                pass
+
+        self._checkpoint.budget["tokens_used"] = sum(
+            f.tokens_used for f in self._checkpoint.files.values())
+        self._save_checkpoint()
+
+    def _charge(self, file: Optional[str], model: Optional[str], input_tokens: int,
+                output_tokens: int) -> None:
+        """Add the usage of a response to the budget, stopping the file or run that exceeds it."""
+        for warning in self._budget.record(file, model, input_tokens, output_tokens):
+            logger.warning(warning)
+            self._budget_warnings.append(warning)
+        if self._checkpoint is not None:
+            self._checkpoint.budget.update(self._budget.to_dict())
+            self._save_checkpoint()
+
+        reason = self._budget.exhausted()
+        if reason is not None:
+            if not self._cancel_token.cancelled:
+                logger.error(f"{reason}; stopping the run, resume it after raising the limit")
+                self._cancel_token.cancel()
+            return
+        token = self._file_tokens.get(file) if file is not None else None
+        if token is not None and not token.cancelled and self._budget.exhausted(file) is not None:
+            logger.error(f"{file}: {self._budget.exhausted(file)}; stopping the file")
+            token.cancel()
+
+    def _stop_reason(self, source: Path) -> str:
+        """Why a file was cancelled: its budget, the run budget, or the caller."""
+        reason = self._budget.exhausted(str(source)) if self._budget is not None else None
+        return reason or "Cancelled"
+
+    def _start_requests(self) -> None:
+        """Request limits and secret placeholders shared by the files of a run."""
+        self._limiter = RequestLimiter.from_settings(self._settings)
+        self._redactor = SecretRedactor(
+            load_redaction_key(get_state_dir(self._target_dir) / "redaction.key"),
+            self._settings.secret_patterns
+        ) if self._settings.redact_secrets else None
+
+    def _fingerprint(self) -> str:
+        """Settings and plugins that influence translations, as a stable string."""
+        plugin_sources = sorted(
+            path.read_text() for path in Path(self._settings.plugins_dir).glob("*.py")
+        ) if Path(self._settings.plugins_dir).is_dir() else []
+        fingerprint = {
+            "llm_steps": self._settings.llm_steps.model_dump(mode="json"),
+            "llm_configs": {
+                choice.value: config.model_dump(mode="json")
+                for choice, config in self._settings.llm_configs.items()
+            },
+            "stage_parameters": {
+                step: parameters.model_dump(mode="json", exclude_none=True)
+                for step, parameters in self._settings.stage_parameters.items()
+            },
+            "checkers": self._checkers,
+            "rustfmt": self._settings.rustfmt,
+            "policies": self._settings.policies.model_dump(mode="json"),
+            "audit": self._settings.audit.model_dump(mode="json"),
+            "cargo_test": self._settings.cargo_test,
+            "miri": {"enabled": self._settings.miri, "flags": self._settings.miri_flags,
+                     "toolchain": self._settings.miri_toolchain},
+            "fuzz_cases": self._settings.fuzz_cases,
+            "numeric_checks": self._settings.numeric_checks,
+            "unicode_checks": self._settings.unicode_checks,
+            "structure_context": self._settings.structure_context,
+            "rust_docs": self._settings.rust_docs,
+            "keep_comments": self._settings.keep_comments,
+            "doctests": self._settings.doctests,
+            "generators": self._settings.generators,
+            "context_managers": self._settings.context_managers,
+            "error_module": self._settings.error_module,
+            "format_specs": self._settings.format_specs,
+            "visibility": self._settings.visibility,
+            "naming": self._settings.naming,
+            "keyword_style": self._settings.keyword_style,
+            "async_runtime": self._settings.async_runtime,
+            "web_framework": self._settings.web_framework,
+            "template_engine": self._settings.template_engine,
+            "database_layer": self._settings.database_layer,
+            "workspace": self._settings.workspace,
+            "requirement_crates": self._settings.requirement_crates,
+            "crates": self._settings.crates,
+            "risk_report": self._settings.risk_report,
+            "native_dependencies": self._settings.native_dependencies,
+            "module_state": self._settings.module_state,
+            "python2": {"mode": self._settings.python2, "command": self._settings.python2_command},
+            "notebook_mode": self._settings.notebook_mode,
+            "skip_dead_code": self._settings.skip_dead_code,
+            "class_plan": self._settings.class_plan,
+            "decorators": self._settings.decorators,
+            "profile": self._settings.profile.model_dump(mode="json"),
+            "http": self._settings.http.model_dump(mode="json"),
+            "openapi": self._settings.openapi.model_dump(mode="json"),
+            "cli": self._settings.cli.model_dump(mode="json"),
+            "memory": self._settings.memory.model_dump(mode="json"),
+            "database_schema": self._settings.database_schema.model_dump(mode="json"),
+            "limits": self._settings.limits.model_dump(mode="json"),
+            "sandbox": self._settings.sandbox.model_dump(mode="json"),
+            "clippy": {"deny": self._settings.clippy_deny, "allow": self._settings.clippy_allow},
+            "shell_checkers": {
+                name: check.model_dump(mode="json")
+                for name, check in self._settings.shell_checkers.items()
+            },
+            "build_checkers": [checker.name for checker in self._build_checkers],
+            "translation_passes": self._settings.translation_passes,
+            "consensus_models": [choice.value for choice in self._settings.consensus_models],
+            "chunk_tokens": self._settings.chunk_tokens,
+            "dependency_context_tokens": self._settings.dependency_context_tokens,
+            "context_definitions": self._settings.context_definitions,
+            "test_context_tokens": self._settings.test_context_tokens,
+            "trace_calls": [self._settings.trace_command, self._settings.traced_calls],
+            "type_checker": self._settings.type_checker,
+            "structured_output": self._settings.structured_output,
+            "deterministic": self._settings.deterministic,
+            "plugins": [plugin.name for plugin in self._plugins],
+            "plugin_sources": plugin_sources
+        }
+        overrides = prompt_overrides(self._settings.prompts_dir)
+        if overrides:
+            fingerprint["prompts"] = {name: path.read_text() for name, path in overrides.items()}
+        return json.dumps(fingerprint, sort_keys=True)
+
+    def _input_hash(
+        self,
+        source: Path,
+        dependencies: List[Path],
+        results: Dict[Path, FileReport],
+        tests: Optional[Dict[str, str]] = None
+    ) -> str:
+        """Hash a file with the public API its dependencies were translated to and its tests."""
+        try:
+            python_code = source.read_text()
+        except OSError:
+            python_code = ""
+        dependency_apis = [
+            hash_api(results[dependency].rust_code if dependency in results else None)
+            for dependency in dependencies
+        ]
+        fingerprint = self._fingerprint()
+        kept = sorted(
+            review.item for review in self._reviews.item_reviews(source)
+            if review.decision == ReviewDecision.KEEP_PYTHON
+        ) if self._reviews is not None else []
+        if kept:
+            fingerprint += json.dumps({"kept_python": kept})
+        if tests:
+            fingerprint += json.dumps({"python_tests": tests}, sort_keys=True)
+        if self._traces.get(source):
+            fingerprint += json.dumps({"traces": [call.describe()
+                                                  for call in self._traces[source]]})
+        if self._types.get(source):
+            fingerprint += json.dumps({"types": types_summary(self._types[source])})
+        if self._hot_paths.get(source):
+            fingerprint += json.dumps({"hot_paths": [path.qualname
+                                                     for path in self._hot_paths[source]]})
+        if self._cold_code.get(source):
+            fingerprint += json.dumps(
+                {"cold_code": [definition.name for definition in self._cold_code[source]]})
+        if self._dead_code.get(source):
+            fingerprint += json.dumps(
+                {"dead_code": [definition.name for definition in self._dead_code[source]]})
+        return hash_inputs(python_code, fingerprint, dependency_apis)
+
+    def _reuse_previous(self, source: Path, input_hash: str) -> Optional[FileReport]:
+        """Reuse verified output when neither the file nor its dependencies' API changed."""
+        if self._manifest is None:
+            return None
+        previous = self._manifest.lookup(source, input_hash)
+        if previous is None:
+            return None
+
+        logger.info(f"Skipping {source}: unchanged since its last verified migration")
+        rust_code, toml_content = previous
+        file_checkpoint = self._file_checkpoint(source)
+        if file_checkpoint is not None:
+            self._record_outputs(file_checkpoint, FileStatus.VERIFIED, rust_code, toml_content)
+        return FileReport(source=source, output_dir=self._output_dir_for(source), success=True,
+                          rust_code=rust_code, toml_content=toml_content,
+                          metrics={"reused": True})
+
+    def _record_manifest(self, report: FileReport, input_hash: str) -> None:
+        if self._manifest is None:
+            return
+        if not report.success or not report.rust_code:
+            self._manifest.forget(report.source)
+            return
+        file_checkpoint = self._file_checkpoint(report.source)
+        outputs_dir = (
+            Path(file_checkpoint.outputs_dir)
+            if file_checkpoint is not None and file_checkpoint.has_outputs
+            else report.output_dir
+        )
+        try:
+            source_hash = hash_source(report.source.read_text())
+        except OSError:
+            source_hash = ""
+        self._manifest.record(report.source, input_hash, report.rust_code, outputs_dir, source_hash)
+
+    def _plugins_for(
+        self,
+        source: Path,
+        graph: ImportGraph,
+        dependencies: List[Path],
+        results: Dict[Path, FileReport],
+        related: Optional[List[Definition]] = None,
+        tests: Optional[Dict[str, str]] = None
+    ) -> List[Plugin]:
+        """The plugins of a file: the project's, those of what the project knows of the file, and
+        between them the context of its dependencies, its import cycle, related definitions and
+        tests."""
+        plugins = [*self._plugins, *self._context_plugins(source, after_dependencies=False)]
+        generated = {
+            graph.names[dependency]: results[dependency].rust_code
+            for dependency in dependencies
+            if dependency in results and results[dependency].rust_code
+        }
+        if generated:
+            digests = {
+                module: self._digests.digest(module, rust_code)
+                for module, rust_code in generated.items()
+            } if self._digests is not None else None
+            plugins.append(DependencySignatures(
+                generated, self._settings.dependency_context_tokens, digests))
+        later = {}
+        for dependency in graph.later_dependencies(source):
+            try:
+                later[graph.names[dependency]] = dependency.read_text()
+            except OSError as e:
+                logger.warning(f"Could not read {dependency}: {e}")
+        if later:
+            plugins.append(ImportCycle([graph.names[f] for f in graph.cycle_of(source)], later))
+        if related:
+            plugins.append(RelevantDefinitions(related))
+        if tests:
+            plugins.append(PythonTests(tests, self._settings.test_context_tokens))
+        return [*plugins, *self._context_plugins(source, after_dependencies=True)]
+
+    def _context_plugins(self, source: Path, after_dependencies: bool) -> List[Plugin]:
+        """The plugins of what the project knows of a file, in the order they prompt: those before
+        the context of its dependencies, or those after it."""
+        if not after_dependencies:
+            finders = [self._structure_plugin, self._docs_plugin, self._comments_plugin]
+        else:
+            finders = [self._doctests_plugin, self._traces_plugin, self._types_plugin,
+                       self._hot_paths_plugin, self._reviews_plugin, self._openapi_plugin]
+        plugins = [finder(source) for finder in finders]
+        return [plugin for plugin in plugins if plugin is not None]
+
+    def _structure_plugin(self, source: Path) -> Optional[Plugin]:
+        return PythonStructure() if self._settings.structure_context else None
+
+    def _docs_plugin(self, source: Path) -> Optional[Plugin]:
+        return RustDocs() if self._settings.rust_docs else None
+
+    def _comments_plugin(self, source: Path) -> Optional[Plugin]:
+        return PythonComments() if self._settings.keep_comments else None
+
+    def _doctests_plugin(self, source: Path) -> Optional[Plugin]:
+        return PythonDoctests() if self._settings.doctests else None
+
+    def _traces_plugin(self, source: Path) -> Optional[Plugin]:
+        traces = self._traces.get(source)
+        return CallTraces(traces, self._settings.trace_command) if traces else None
+
+    def _types_plugin(self, source: Path) -> Optional[Plugin]:
+        types = self._types.get(source)
+        return InferredTypes(types, self._settings.type_checker) if types else None
+
+    def _hot_paths_plugin(self, source: Path) -> Optional[Plugin]:
+        paths = self._hot_paths.get(source)
+        return HotPaths(paths, self._settings.profile.command) if paths else None
+
+    def _reviews_plugin(self, source: Path) -> Optional[Plugin]:
+        reviews = self._reviews.for_source(source) if self._reviews is not None else []
+        return ReviewFeedback(reviews) if reviews else None
+
+    def _openapi_plugin(self, source: Path) -> Optional[Plugin]:
+        openapi = self._settings.openapi
+        return OpenApiAnnotations(openapi.path) if openapi.enabled else None
+
+    def _python_tests(self, source: Path, graph: ImportGraph) -> Dict[str, str]:
+        """The pytest files testing a source file, by path relative to its source root."""
+        if not self._settings.test_context_tokens:
+            return {}
+        root = self._source_roots.get(source, source.parent)
+        tests = {}
+        for path in find_python_tests(root, graph.names[source]):
+            try:
+                tests[str(path.relative_to(root.parent))] = path.read_text()
+            except OSError:
+                continue
+        return tests
+
+    def _profile_workload(self, files: List[Path]) -> Dict[Path, List[HotPath]]:
+        """The hot paths of each file in a profiled run of the workload."""
+        profile = self._settings.profile
+        return (Profiler(profile.command, threshold=profile.threshold, timeout=profile.timeout)
+                .profile(files))
+
+    def _limit_to_hot_paths(self, graph: ImportGraph, order: List[Path],
+                            warnings: List[str]) -> List[Path]:
+        """The files with hot paths and those they import, in order, recording the definitions of
+        these that are neither hot nor used by the hot ones."""
+        hot = [source for source in order if self._hot_paths.get(source)]
+        if not hot:
+            warnings.append("The profiled workload has no hot path in the "
+                            "sources; migrating them all")
+            return order
+        kept = graph.imported_from(hot)
+        skipped = [graph.names[source] for source in order if source not in kept]
+        if skipped:
+            warnings.append(f"Not migrated, without hot paths when profiled: {', '.join(skipped)}")
+        kept_graph = ImportGraph([source for source in order if source in kept], self._source_roots)
+        used = {path.qualname.split(".")[0] for paths in self._hot_paths.values() for path in paths}
+        self._cold_code = find_dead_code(kept_graph, kept_graph.files, used=used)
+        return [source for source in order if source in kept]
+
+    def _class_plan(self, source: Path, python_code: str) -> Tuple[List[ClassMapping], bool]:
+        """The file's class mappings, as approved when they are, and whether they are."""
+        if not self._settings.class_plan:
+            return [], False
+        approval = ClassPlanStore(self._target_dir).approved(source, python_code)
+        return class_mappings(python_code,
+                              approval.overrides if approval else None), approval is not None
+
+    def _exception_hierarchy(self, graph: ImportGraph) -> Optional[ExceptionHierarchy]:
+        if (not self._settings.error_module
+                or self._settings.policies.error_style not in (None, "thiserror")):
+            return None
+        sources = {}
+        for source in self._source_files:
+            try:
+                sources[graph.names[source]] = source.read_text()
+            except OSError:
+                continue
+        return ExceptionHierarchy(sources)
+
+    def _context_managers(self, python_code: str) -> List[ContextManager]:
+        return context_managers(python_code) if self._settings.context_managers else []
+
+    def _resolve_requirements(self) -> Tuple[List[CrateDependency], List[Requirement]]:
+        """The crates of the mapped libraries, and the project's requirements without a mapping."""
+        if not self._settings.requirement_crates:
+            return [], []
+        root = self._settings.requirements_dir
+        if root is None and self._source_files:
+            first = self._source_files[0]
+            root = find_requirements_root(self._source_roots.get(first, first.parent))
+        mappings = runtime_mappings(load_crate_mappings(extra=self._settings.crate_mappings_file),
+                                    self._settings.async_runtime)
+        mappings = framework_mappings(mappings, self._settings.web_framework)
+        mappings = engine_mappings(mappings, self._settings.template_engine)
+        mappings = database_mappings(mappings, self._settings.database_layer,
+                                     self._settings.async_runtime)
+        for library, crate in self._settings.crates.items():
+            crate = {"crate": crate} if isinstance(crate, str) else dict(crate)
+            known = next((mapping for mapping in mappings if mapping.crate == crate["crate"]), None)
+            category = next((mapping.category for mapping in mappings
+                             if normalize_name(mapping.python) == normalize_name(library)),
+                            "project")
+            mappings.append(CrateMapping(
+                library, crate["crate"], category,
+                version=crate.get("version", known.version if known else "*"),
+                features=crate.get("features", known.features if known else [])))
+        return resolve_requirements(project_requirements(root) if root else [], mappings)
+
+    def _license_violations(self, dependencies: List[CrateDependency]) -> List[LicenseViolation]:
+        """The crates of the project's libraries under a license outside the allowed ones."""
+        if not self._settings.licenses.enabled:
+            return []
+        used = set()
+        for source in self._source_files:
+            try:
+                used.update(dependency.python for dependency in
+                            imported_dependencies(source.read_text(), dependencies))
+            except OSError:
+                continue
+        checked = [dependency for dependency in dependencies
+                   if dependency.requirement is not None or dependency.python in used]
+        licenses = CrateLicenses(get_state_dir(self._target_dir) / "crate_licenses.json")
+        violations, unknown = license_violations(checked, self._settings.licenses.allow, licenses)
+        if unknown:
+            logger.warning("Licenses not checked, crates.io did not give them: "
+                           f"{', '.join(unknown)}")
+        return violations
+
+    def _visibility(self, python_code: str) -> Optional[ModuleVisibility]:
+        return module_visibility(python_code) if self._settings.visibility else None
+
+    def _renamings(self, python_code: str) -> List[Renaming]:
+        return renamings(python_code, self._settings.keyword_style) if self._settings.naming else []
+
+    def _format_conversions(self, python_code: str) -> List[FormatConversion]:
+        return format_conversions(python_code) if self._settings.format_specs else []
+
+    def _generators(self, python_code: str) -> List[PythonGenerator]:
+        return python_generators(python_code) if self._settings.generators else []
+
+    def _module_state(self, python_code: str) -> List[ModuleState]:
+        return module_state(python_code) if self._settings.module_state else []
+
+    def _decorator_uses(self, python_code: str) -> List[DecoratorUse]:
+        return decorator_uses(python_code, self._settings.decorators)
+
+    def _native_imports(self, python_code: str) -> List[NativeImport]:
+        if not self._settings.native_dependencies:
+            return []
+        return native_imports(python_code, sorted(set(self._source_roots.values())))
+
+    def _feature_finders(self) -> List[Callable[[FileContext], Any]]:
+        """The finders of the features of a file's code, in the order their plugins prompt. Each
+        gives a FileFeature, or None when the code has none of its feature; the database one is
+        awaited."""
+        return [
+            self._native_feature, self._class_feature, self._decorator_feature,
+            self._generator_feature, self._context_manager_feature, self._error_feature,
+            self._error_style_feature, self._crate_feature, self._format_feature,
+            self._visibility_feature, self._naming_feature, self._web_feature,
+            self._template_feature, self._async_feature, self._database_feature,
+            self._module_state_feature,
+        ]
+
+    def _native_feature(self, file: FileContext) -> Optional[FileFeature]:
+        natives = self._native_imports(file.python_code)
+        if not natives:
+            return None
+        logger.info(f"{file.source.name} imports {len(natives)} modules backed by compiled code")
+        return FileFeature("native_imports", natives, NativeDependencies(natives),
+                           [native.module for native in natives])
+
+    def _class_feature(self, file: FileContext) -> Optional[FileFeature]:
+        if not file.classes:
+            return None
+        return FileFeature("class_plan", file.classes, ClassPlan(file.classes, file.approved),
+                           {mapping.name: mapping.kind for mapping in file.classes})
+
+    def _decorator_feature(self, file: FileContext) -> Optional[FileFeature]:
+        decorators = self._decorator_uses(file.python_code)
+        if not decorators:
+            return None
+        return FileFeature("decorators", decorators, DecoratorStrategies(decorators),
+                           sorted({use.name for use in decorators}))
+
+    def _generator_feature(self, file: FileContext) -> Optional[FileFeature]:
+        generators = self._generators(file.python_code)
+        if not generators:
+            return None
+        return FileFeature("generators", generators, GeneratorIterators(generators),
+                           _kinds(generators, ("function", "expression")))
+
+    def _context_manager_feature(self, file: FileContext) -> Optional[FileFeature]:
+        managers = self._context_managers(file.python_code)
+        if not managers:
+            return None
+        return FileFeature("context_managers", managers, RaiiGuards(managers),
+                           _kinds(managers, ("class", "function", "with")))
+
+    def _error_feature(self, file: FileContext) -> Optional[FileFeature]:
+        if not self._errors:
+            return None
+        write_error_module(file.output_dir)
+        crate_path = Path(os.path.relpath(self._target_dir / ERRORS_DIR, file.output_dir))
+        sites = exception_sites(file.python_code)
+        plugin = ErrorModule(self._errors, crate_path.as_posix())
+        return FileFeature("exception_sites", self._errors, plugin,
+                           _kinds(sites, ("raise", "except")) if sites else None)
+
+    def _error_style_feature(self, file: FileContext) -> Optional[FileFeature]:
+        style = self._settings.policies.error_style
+        return FileFeature("error_style", style, ErrorStyle(style)) if style is not None else None
+
+    def _crate_feature(self, file: FileContext) -> Optional[FileFeature]:
+        crates = imported_dependencies(file.python_code, self._crate_dependencies)
+        if not crates:
+            return None
+        return FileFeature("crates", crates, RequirementCrates(crates),
+                           sorted({dependency.crate for dependency in crates}))
+
+    def _format_feature(self, file: FileContext) -> Optional[FileFeature]:
+        conversions = self._format_conversions(file.python_code)
+        if not conversions:
+            return None
+        helpers = sum(conversion.rust is None for conversion in conversions)
+        return FileFeature("format_specs", conversions, FormatConversions(conversions),
+                           {"native": len(conversions) - helpers, "helper": helpers})
+
+    def _visibility_feature(self, file: FileContext) -> Optional[FileFeature]:
+        visibility = self._visibility(file.python_code)
+        if visibility is None or not visibility.items:
+            return None
+        return FileFeature("visibility", visibility, ItemVisibility(visibility),
+                           {"public": len(visibility.public), "private": len(visibility.private)})
+
+    def _naming_feature(self, file: FileContext) -> Optional[FileFeature]:
+        renames = self._renamings(file.python_code)
+        if not renames:
+            return None
+        return FileFeature("renames", renames, IdentifierNames(renames), _kinds(renames))
+
+    def _web_feature(self, file: FileContext) -> Optional[FileFeature]:
+        app = find_web_app(file.python_code)
+        if app is None:
+            return None
+        framework = self._settings.web_framework
+        return FileFeature("web_framework", app, WebFramework(framework, app),
+                           {"framework": framework, "routes": len(app.routes)})
+
+    def _template_feature(self, file: FileContext) -> Optional[FileFeature]:
+        templates = python_templates(file.python_code, [file.source.parent / "templates"])
+        if not templates:
+            return None
+        engine = self._settings.template_engine
+        written = write_templates(file.output_dir, templates, engine)
+        if written:
+            logger.info(f"Wrote {len(written)} {engine} templates of {file.source.name} into "
+                        f"{file.output_dir / 'templates'}")
+        inline = sum(template.inline for template in templates)
+        return FileFeature("templates", templates, HtmlTemplates(engine, templates),
+                           {"engine": engine, "inline": inline, "files": len(templates) - inline})
+
+    def _runtime(self, file: FileContext) -> str:
+        # Web apps run on the runtime of their framework
+        return "tokio" if "web_framework" in file.features else self._settings.async_runtime
+
+    def _async_feature(self, file: FileContext) -> Optional[FileFeature]:
+        asynchronous = async_uses(file.python_code)
+        if all(use.blocking for use in asynchronous):
+            return None
+        runtime = self._runtime(file)
+        return FileFeature("async", asynchronous, AsyncRuntime(runtime, asynchronous),
+                           {"runtime": runtime, "uses": _kinds(asynchronous)})
+
+    async def _database_feature(self, file: FileContext) -> Optional[FileFeature]:
+        database = database_use(file.python_code)
+        if database is None:
+            return None
+        layer = self._settings.database_layer
+        if database.tables:
+            write_migrations(file.output_dir, database.tables, layer)
+            logger.info(f"Wrote {len(database.tables)} {layer} migrations of {file.source.name} "
+                        f"into {file.output_dir / 'migrations'}")
+        url = await self._query_database(file.output_dir, database)
+        if url is not None:
+            file.settings.build_env = {**file.settings.build_env, "DATABASE_URL": url}
+        plugin = DatabaseAccess(layer, database, self._runtime(file), checked=url is not None)
+        return FileFeature("database", database, plugin, {
+            "layer": layer, "backend": database.backend, "tables": len(database.tables),
+            "queries": len(database.queries)})
+
+    def _module_state_feature(self, file: FileContext) -> Optional[FileFeature]:
+        states = self._module_state(file.python_code)
+        if not states:
+            return None
+        strategy = self._settings.module_state
+        logger.info(f"{file.source.name} has {len(states)} module-level globals and singletons, "
+                    f"held with the {strategy} strategy")
+        return FileFeature("module_state", states, GlobalState(states, strategy),
+                           {state.name: state_strategy(state, strategy) for state in states})
+
+    def _find_dead_code(self, graph: ImportGraph) -> Dict[Path, List[DeadDefinition]]:
+        """The unused definitions of each source file, which are not translated."""
+        references = referencing_files(self._source_roots.values(), self._source_files)
+        dead_code = find_dead_code(graph, self._entry_points, references)
+        unused = sum(len(definitions) for definitions in dead_code.values())
+        logger.info(f"Found {unused} unused definitions in {len(dead_code)} files")
+        return dead_code
+
+    def _build_index(self, graph: ImportGraph) -> DefinitionIndex:
+        """Index the Python definitions of every source module."""
+        cache_file = (get_state_dir(self._target_dir) / "embeddings.json"
+                      if self._embeddings else None)
+        index = DefinitionIndex(self._embeddings, cache_file=cache_file)
+        for source in self._source_files:
+            try:
+                index.update(graph.names[source], "python", python_definitions(
+                    graph.names[source], source.read_text()))
+            except OSError:
+                continue
+        logger.info(f"Indexed {len(index)} project definitions for context retrieval")
+        return index
+
+    async def _related_definitions(
+        self,
+        source: Path,
+        graph: ImportGraph,
+        dependencies: List[Path]
+    ) -> List[Definition]:
+        """Definitions most similar to a source file in the modules it does not import, whose
+        signatures reach it already."""
+        if self._index is None:
+            return []
+        try:
+            python_code = source.read_text()
+        except OSError:
+            return []
+        return await asyncio.to_thread(
+            self._index.search, python_code, self._settings.context_definitions,
+            exclude_modules=[graph.names[path] for path in [source, *dependencies]])
+
+    def _summarize_translation(self, module: str, result: Optional[FileReport]) -> None:
+        """Bring the API digest of a module up to date with its translation."""
+        if result is not None and result.rust_code and self._digests is not None:
+            self._digests.digest(module, result.rust_code)
+
+    def _index_translation(self, source: Path, graph: ImportGraph,
+                           result: Optional[FileReport]) -> None:
+        """Make the Rust definitions of a translated file retrievable by the files after it."""
+        if self._index is None or result is None or not result.rust_code:
+            return
+        module = graph.names[source]
+        try:
+            self._index.update(module, "rust", rust_definitions(module, result.rust_code))
+        except Exception as e:
+            logger.warning(f"Could not index the translation of {source}: {e}")
+
+    def _reviewed(self, source: Path) -> Optional[FileReport]:
+        """Outcome settled by review: kept in Python, or an accepted translation left as is."""
+        review = self._reviews.file_review(source) if self._reviews is not None else None
+        if review is None or review.decision == ReviewDecision.REJECTED:
+            return None
+        output_dir = self._output_dir_for(source)
+        try:
+            python_code = source.read_text()
+        except OSError:
+            return None
+        if not self._reviews.is_current(review, python_code):
+            logger.info(f"{source} changed since it was reviewed, migrating again")
+            return None
+
+        if review.decision == ReviewDecision.KEEP_PYTHON:
+            logger.info(f"Skipping {source}: kept in Python by review")
+            return FileReport(source=source, output_dir=output_dir, success=True,
+                              metrics={"kept_python": True})
+
+        try:
+            rust_code = (output_dir / "src" / "main.rs").read_text()
+            toml_content = (output_dir / "Cargo.toml").read_text()
+        except OSError:
+            return None
+        if review.rust_hash and hash_rust(rust_code) != review.rust_hash:
+            logger.info(f"{output_dir} changed since it was accepted, migrating again")
+            return None
+
+        logger.info(f"Skipping {source}: translation accepted by review")
+        file_checkpoint = self._file_checkpoint(source)
+        if file_checkpoint is not None:
+            self._record_outputs(file_checkpoint, FileStatus.VERIFIED, rust_code, toml_content)
+        return FileReport(source=source, output_dir=output_dir, success=True,
+                          rust_code=rust_code, toml_content=toml_content,
+                          metrics={"accepted": True})
+
+    async def _migrate_file(self, source: Path, plugins: List[Plugin]) -> FileReport:
+        events = self._events.for_file(source)
+        events.emit(EventType.FILE_STARTED, output_dir=str(self._output_dir_for(source)))
+        report = await self._run_file(source, events, plugins)
+        if report.success and report.rust_code and self._settings.benchmarks.enabled:
+            self._write_benchmarks(report)
+        if report.success and report.rust_code and self._settings.build_stats:
+            await self._measure_build(report)
+        events.emit(EventType.FILE_FINISHED, success=report.success, error=report.error)
+        return report
+
+    def _write_benchmarks(self, report: FileReport) -> None:
+        """Benchmark the hot functions of a verified crate, recording the
+        manifest declaring them."""
+        settings = self._settings.benchmarks
+        try:
+            hot = hot_functions(report.source.read_text(), report.rust_code, settings.functions,
+                                settings.min_loop_depth)
+            toml_content = report.toml_content or (report.output_dir / "Cargo.toml").read_text()
+            manifest = write_hot_benchmarks(
+                report.output_dir, report.source, report.rust_code, toml_content,
+                hot, settings.criterion)
+        except OSError as e:
+            logger.warning(f"Could not write the benchmarks of {report.source}: {e}")
+            return
+        if manifest is not None:
+            report.toml_content = manifest
+            report.metrics["benchmarks"] = [function.name for function in hot]
+
+    async def _measure_build(self, report: FileReport) -> None:
+        """Measure the release build of a verified crate and compare it with the previous run."""
+        file_checkpoint = self._file_checkpoint(report.source)
+        if (file_checkpoint is not None and file_checkpoint.build_stats
+                and report.metrics.get("resumed")):
+            report.metrics["build"] = file_checkpoint.build_stats
+            return
+        sandbox = Sandbox(self._settings.sandbox) if self._settings.sandbox.enabled else None
+        builder = RustBuilder(output_dir=report.output_dir, sandbox=sandbox,
+                              limits=self._settings.limits)
+        try:
+            toml_content = report.toml_content or (report.output_dir / "Cargo.toml").read_text()
+        except OSError as e:
+            logger.warning(f"Could not measure the build of {report.source}: {e}")
+            return
+        stats = await measure_build(builder, report.rust_code, toml_content)
+        if stats is None:
+            return
+        report.metrics["build"] = stats.to_dict()
+        previous = self._previous_build(report.source)
+        if previous is not None:
+            run_id, old = previous
+            report.warnings.extend(
+                f"{regression} since run {run_id}"
+                for regression in build_regressions(old, stats.to_dict()))
+        if file_checkpoint is not None:
+            file_checkpoint.build_stats = stats.to_dict()
+            self._save_checkpoint()
+
+    def _previous_build(self, source: Path) -> Optional[Tuple[str, Dict[str, Any]]]:
+        """Build measures of a file in the latest earlier run recording them, with its run id."""
+        current = self._checkpoint.run_id if self._checkpoint is not None else None
+        for run in reversed(self._store.list_runs()):
+            file = run.files.get(str(source))
+            if run.run_id != current and file is not None and file.build_stats:
+                return run.run_id, file.build_stats
+        return None
+
+    async def _run_file(self, source: Path, events: EventBus, plugins: List[Plugin]) -> FileReport:
+        output_dir = self._output_dir_for(source)
+        file_checkpoint = self._file_checkpoint(source)
+
+        if file_checkpoint is not None and file_checkpoint.status == FileStatus.VERIFIED:
+            logger.info(f"Skipping {source}: already verified in run {self._checkpoint.run_id}")
+            rust_code, toml_content = self._store.load_outputs(file_checkpoint)
+            return FileReport(source=source, output_dir=output_dir, success=True,
+                              rust_code=rust_code, toml_content=toml_content,
+                              metrics={"resumed": True})
+
+        try:
+            python_code = source.read_text()
+        except OSError as e:
+            return FileReport(source=source, output_dir=output_dir, success=False, error=str(e))
+
+        if not python_code.strip():
+            return FileReport(source=source, output_dir=output_dir, success=False,
+                              error=f"Python file is empty: {source}")
+
+        classes, approved = self._class_plan(source, python_code)
+        if classes and self._settings.approve_class_plans and not approved:
+            return FileReport(source=source, output_dir=output_dir, success=False, error=(
+                f"The class mapping plan of {source} is not approved; review it with "
+                "`python2rust classes`"))
+
+        python2 = python2_constructs(python_code)
+        checked_source = source
+        if python2:
+            found = ", ".join(f"line {construct.line} {construct.construct}"
+                              for construct in python2)
+            if self._settings.python2 != "convert":
+                return FileReport(source=source, output_dir=output_dir, success=False, error=(
+                    f"Python 2 source, convert it first or set python2 to convert: {found}"))
+            converted, reason = await convert_python2(source, python_code,
+                                                      self._settings.python2_command)
+            if converted is None:
+                return FileReport(source=source, output_dir=output_dir, success=False,
+                                  error="Python 2 source could not be "
+                                        f"converted, {reason}: {found}")
+            # The checkers running the Python code run the converted copy
+            checked_source = output_dir / "debug" / "python3" / source.name
+            checked_source.parent.mkdir(parents=True, exist_ok=True)
+            checked_source.write_text(converted)
+            python_code = converted
+
+        dead_code = self._dead_code.get(source, [])
+        dead_lines = {definition.start_line for definition in dead_code}
+        cold_code = [definition for definition in self._cold_code.get(source, [])
+                     if definition.start_line not in dead_lines]
+        if dead_code or cold_code:
+            python_code = remove_definitions(python_code, dead_code + cold_code)
+            logger.info(f"Leaving {len(dead_code)} unused and {len(cold_code)} cold definitions of "
+                        f"{source.name} untranslated")
+
+        output_dir.mkdir(parents=True, exist_ok=True)
+        logger.info(f"Migrating {source} into {output_dir}")
+        settings = self._settings_for(output_dir)
+        risks = scan_risks(python_code) if self._settings.risk_report else []
+        if risks:
+            settings.extra_fix_attempts += extra_fix_attempts(risks)
+            plugins = [*plugins, TranslationRisks(risks)]
+            logger.info(f"{source.name} has {len(risks)} risky constructs; "
+                        f"{settings.extra_fix_attempts} more fix attempts per workflow")
+        file = FileContext(source, python_code, output_dir, settings, classes, approved)
+        for finder in self._feature_finders():
+            feature = finder(file)
+            if inspect.isawaitable(feature):
+                feature = await feature
+            if feature is not None:
+                file.features[feature.name] = feature
+                plugins = [*plugins, feature.plugin]
+        found_features = {name: feature.found for name, feature in file.features.items()}
+
+        # Resume from previously generated code when only verification is left
+        workflows = ["migration", *self._checkers]
+        existing_code = None
+        if (
+            file_checkpoint is not None
+            and file_checkpoint.status in (FileStatus.GENERATED, FileStatus.CANCELLED)
+            and file_checkpoint.has_outputs
+            and "build" in self._checkers
+        ):
+            logger.info(f"Resuming {source} from generated code")
+            existing_code = self._store.load_outputs(file_checkpoint)
+            workflows = list(self._checkers)
+
+        if file_checkpoint is not None:
+            file_checkpoint.status = FileStatus.IN_PROGRESS
+            self._save_checkpoint()
+
+        file_token = self._file_tokens[str(source)] = self._cancel_token.child()
+        async with MigrationAgent(
+            tokens=self._tokens,
+            output_dir=output_dir,
+            settings=settings,
+            workflows=workflows,
+            test_script_path=self._test_script or source.parent / "test.sh",
+            plugins=plugins,
+            events=events,
+            cancel_token=file_token,
+            request_limiter=self._limiter,
+            redactor=self._redactor,
+            checkers=self._checkers_for(checked_source, python_code)
+        ) as agent:
+            try:
+                success, rust_code, toml_content = await agent.migrate(
+                    python_code, existing_code=existing_code)
+            except MigrationCancelled:
+                if file_checkpoint is not None:
+                    file_checkpoint.error = self._stop_reason(source)
+                    latest = agent.state.latest_generation or {}
+                    self._record_outputs(file_checkpoint, FileStatus.CANCELLED,
+                                         latest.get("rust_code"), latest.get("toml_content"))
+                raise
+
+            result = agent.last_result or {}
+            error = None if success else (agent.last_error or "Migration failed")
+
+            if file_checkpoint is not None:
+                file_checkpoint.error = error
+                if success:
+                    self._record_outputs(file_checkpoint, FileStatus.VERIFIED,
+                                         rust_code, toml_content)
+                elif result.get("rust_code"):
+                    self._record_outputs(file_checkpoint, FileStatus.GENERATED,
+                                         result["rust_code"], result.get("toml_content"))
+                else:
+                    self._record_outputs(file_checkpoint, FileStatus.FAILED, None, None)
+
+            source_map = None
+            if rust_code:
+                source_map = write_source_map(
+                    output_dir, source, python_code, rust_code,
+                    history=agent.state.code_history,
+                    differences=(agent.state.last_verification_result or {}).get(
+                        "critical_differences"),
+                    notebook=self._notebooks.get(source), renames=found_features.get("renames", []))
+
+            metrics = result.get("metrics", {})
+            if risks:
+                metrics["risks"] = {severity: sum(risk.severity == severity for risk in risks)
+                                    for severity in ("low", "medium", "high")}
+                metrics["extra_fix_attempts"] = settings.extra_fix_attempts
+            if cold_code:
+                metrics["cold_code"] = [definition.name for definition in cold_code]
+            warnings = risk_warnings(risks)
+            natives = found_features.get("native_imports", [])
+            warnings.extend(native.describe() for native in natives)
+            for feature in file.features.values():
+                if feature.metric is not None:
+                    metrics[feature.name] = feature.metric
+            if source in self._notebooks:
+                metrics["notebook"] = str(self._notebooks[source])
+                warnings.insert(0, f"Translated from the code cells of {self._notebooks[source]} "
+                                   f"as a {self._settings.notebook_mode}, extracted into {source}")
+            if python2:
+                metrics["python2_converted"] = len(python2)
+                warnings.insert(0, f"Translated from a conversion of its Python 2 source, saved as "
+                                   f"{checked_source}: {found}")
+            return FileReport(
+                source=source,
+                output_dir=output_dir,
+                success=success,
+                rust_code=rust_code,
+                toml_content=toml_content,
+                error=error,
+                metrics=metrics,
+                source_map=source_map,
+                verification=result.get("verification"),
+                warnings=warnings + result.get("warnings", []),
+                dead_code=[definition.name for definition in dead_code],
+                unknown_decorators=[use.describe() for use in found_features.get("decorators", [])
+                                    if not use.known],
+                tokens_used=file_checkpoint.tokens_used if file_checkpoint is not None else 0
+            )
+
+    def _checkers_for(self, source: Path, python_code: str) -> List[Checker]:
+        """Checkers added to the built-in ones for a file: the custom ones, the comments, the
+        fuzzer, the numeric and multi-byte text tests, then the comparisons of database schemas, of
+        OpenAPI descriptions, of web app responses and of command-line runs."""
+        checkers = list(self._build_checkers)
+        if self._settings.keep_comments:
+            checkers.append(CommentCheck(python_code))
+        if self._settings.fuzz_cases:
+            checkers.append(DifferentialFuzzer(source, self._settings.fuzz_cases))
+        if self._settings.numeric_checks:
+            checkers.append(NumericBoundaryCheck(source))
+        if self._settings.unicode_checks:
+            checkers.append(UnicodeCheck(source))
+        schema = self._settings.database_schema
+        if schema.enabled:
+            checkers.append(SchemaEquivalence(
+                source, schema.engine, schema.python_command, schema.rust_command,
+                schema.ignore_tables, schema.postgres_image, schema.docker))
+        openapi = self._settings.openapi
+        if openapi.enabled:
+            checkers.append(OpenApiEquivalence(
+                source, openapi.path, openapi.ignore_responses, openapi.startup_timeout))
+        memory = None
+        if self._settings.memory.enabled:
+            memory = MemoryProfiler(self._settings.memory.allocations,
+                                    self._settings.memory.valgrind)
+        http = self._settings.http
+        if http.enabled:
+            cases = [*http.cases.values(),
+                     *(case for path in http.har_files for case in load_har(path))]
+            checkers.append(HttpEquivalence(
+                source, cases, http.headers, http.ignore, http.startup_timeout,
+                http.ignore_elements, memory=memory))
+        cli = self._settings.cli
+        if cli.enabled:
+            runs = [CliCase(args=args, stdin=stdin or None) for args in cli.argv
+                    for stdin in cli.stdin]
+            checkers.append(CliEquivalence(
+                source, [*runs, *cli.cases.values()], cli.stderr, cli.ignore,
+                cli.timeout, memory=memory))
+        return checkers
+
+    def _local_model_warnings(self, sources: List[Path]) -> List[str]:
+        """Steps that run on a local model, and prompts that do not fit its context window."""
+        step_models = {}
+        for step in MIGRATION_STEPS:
+            choice = resolve_model(self._settings,
+                                   self._settings.llm_steps.model_for(step), self._tokens)
+            if choice is not None and provider_for(self._settings, choice).local:
+                step_models[step] = choice
+        if not step_models:
+            return []
+
+        warnings = []
+        for choice in dict.fromkeys(step_models.values()):
+            steps = [step for step, model in step_models.items() if model == choice]
+            warnings.append(
+                f"{', '.join(steps)} run on the local model "
+                f"{self._settings.llm_configs[choice].model}: expect less faithful translations "
+                "and more fix iterations than with hosted models"
+            )
+
+        passes = self._settings.translation_passes
+        for source in sources:
+            try:
+                python_code = source.read_text()
+            except OSError:
+                continue
+            # One call of each stage
+            stages = {
+                **estimate_stage_tokens(python_code, passes, fix_attempts=0),
+                "fixes": estimate_stage_tokens(python_code, passes, fix_attempts=1)["fixes"]
+            }
+            for stage, tokens in stages.items():
+                choice = step_models.get(stage if stage in MIGRATION_STEPS else "generation")
+                if choice is None:
+                    continue
+                config = self._settings.llm_configs[choice]
+                needed = tokens["input"] + tokens["output"]
+                if config.context_length and needed > config.context_length:
+                    warnings.append(
+                        f"{source.name}: the {stage} prompt needs about {needed} tokens but "
+                        f"{config.model} has a {config.context_length}-token context; "
+                        "raise its context_length or split the module"
+                    )
+        return warnings
+
+    async def _run(self) -> MigrationReport:
+        self._check_tokens()
+        self._crate_dependencies, unmapped = self._resolve_requirements()
+        self._check_licenses()
+
+        self._manifest = MigrationManifest(self._target_dir) if self._incremental else None
+        self._reviews = ReviewStore(self._target_dir)
+        self._digests = DigestStore(self._target_dir)
+        report = MigrationReport(
+            target_dir=self._target_dir,
+            run_id=self._checkpoint.run_id if self._checkpoint else None,
+            prompt_version=prompt_set_version(self._settings.prompts_dir)
+        )
+        self._budget = BudgetTracker(
+            self._settings.budget, load_pricing(),
+            self._checkpoint.budget if self._checkpoint else None
+        ) if self._settings.budget.enabled else None
+        self._budget_warnings = []
+        self._start_requests()
+        graph = ImportGraph(self._source_files, self._source_roots)
+        order = graph.topological_order()
+        report.warnings = self._local_model_warnings(order) + graph.cycle_warnings()
+        self._hot_paths = await asyncio.to_thread(
+            self._profile_workload, order) if self._settings.profile.command else {}
+        self._cold_code = {}
+        if self._settings.profile.limit:
+            order = self._limit_to_hot_paths(graph, order, report.warnings)
+        for warning in report.warnings:
+            logger.warning(warning)
+        finished = {source: asyncio.Event() for source in order}
+        workers = asyncio.Semaphore(self._settings.max_workers)
+        results: Dict[Path, FileReport] = {}
+        self._index = (
+            await asyncio.to_thread(self._build_index, graph)
+            if self._settings.context_definitions else None
+        )
+        self._traces = await CallTracer(
+            self._settings.trace_command, limit=self._settings.traced_calls
+        ).trace(order) if self._settings.trace_command else {}
+        self._dead_code = self._find_dead_code(graph) if self._settings.skip_dead_code else {}
+        if unmapped:
+            report.warnings.append(
+                "Requirements without a crate mapping, map them under [crates] in "
+                f"python2rust.toml: {', '.join(requirement.name for requirement in unmapped)}")
+        self._errors = self._exception_hierarchy(graph)
+        if self._errors:
+            errors_dir = write_error_crate(self._target_dir, self._errors)
+            logger.info(f"Generated the error module of {len(self._errors.classes)} exception "
+                        f"classes in the crate {errors_dir}")
+        self._types = await TypeInference(
+            self._settings.type_checker, self._settings.type_checker_command
+        ).infer(order) if self._settings.type_checker else {}
+
+        async def migrate_in_order(source: Path) -> None:
+            try:
+                dependencies = graph.ordered_dependencies(source)
+                for dependency in dependencies:
+                    await finished[dependency].wait()
+                reviewed = self._reviewed(source)
+                if reviewed is not None:
+                    results[source] = reviewed
+                    return
+                tests = self._python_tests(source, graph)
+                input_hash = self._input_hash(source, dependencies, results, tests)
+                rejected = any(
+                    review.decision == ReviewDecision.REJECTED
+                    for review in self._reviews.for_source(source)
+                )
+                reused = None if rejected else self._reuse_previous(source, input_hash)
+                if reused is not None:
+                    results[source] = reused
+                    return
+                related = await self._related_definitions(source, graph, dependencies)
+                async with workers:
+                    self._cancel_token.raise_if_cancelled()
+                    if self._budget is not None and self._budget.exhausted(str(source)) is not None:
+                        raise MigrationCancelled(self._budget.exhausted(str(source)))
+                    results[source] = await self._migrate_file(
+                        source,
+                        self._plugins_for(source, graph, dependencies, results, related, tests))
+                self._record_manifest(results[source], input_hash)
+                if rejected and results[source].rust_code:
+                    # The comments were used for this translation; it can be reviewed again
+                    self._reviews.clear(source, [ReviewDecision.REJECTED])
+            except MigrationCancelled:
+                logger.warning(f"Migration cancelled while processing {source}")
+                results[source] = FileReport(
+                    source=source,
+                    output_dir=self._output_dir_for(source),
+                    success=False,
+                    error=self._stop_reason(source)
+                )
+            finally:
+                self._summarize_translation(graph.names[source], results.get(source))
+                self._index_translation(source, graph, results.get(source))
+                finished[source].set()
+
+        logger.info(f"Migrating {len(order)} files with up to {self._settings.max_workers} workers")
+        # Files with hot paths take the workers first, their dependencies allowing
+        started = sorted(order, key=lambda source: -sum(
+            path.share for path in self._hot_paths.get(source, [])))
+        await asyncio.gather(*(migrate_in_order(source) for source in started))
+        report.files = [results[source] for source in order]
+        report.cancelled = self._cancel_token.cancelled
+        if self._settings.workspace and not report.cancelled:
+            crates, workspace_warnings = package_crates(graph)
+            if crates:
+                output_dirs = {source: self._output_dir_for(source) for source in graph.files}
+                errors_dir = self._target_dir / ERRORS_DIR if self._errors else None
+                report.workspace = write_workspace(self._target_dir, crates, output_dirs,
+                                                   self._settings.visibility, errors_dir)
+                report.warnings.extend(workspace_warnings)
+        if self._budget is not None:
+            report.warnings.extend(self._budget_warnings)
+            report.budget = {**self._budget.to_dict(), "exhausted": self._budget.exhausted()}
+
+        report.finished_at = datetime.now()
+        if self._checkpoint is not None:
+            if report.cancelled:
+                self._checkpoint.status = RunStatus.CANCELLED
+            elif report.success:
+                self._checkpoint.status = RunStatus.COMPLETED
+            else:
+                self._checkpoint.status = RunStatus.FAILED
+            self._save_checkpoint()
+
+        logger.info(
+            f"Migration finished: {len(report.succeeded)} succeeded, {len(report.failed)} failed")
+        return report
+
+    def _verification_steps(self) -> List[str]:
+        steps = [f"LLM behavioral comparison ({self._settings.llm_steps.verification.value})"]
+        if self._plugins or Path(self._settings.plugins_dir).is_dir():
+            steps.append("plugin checks")
+        if "build" in self._checkers:
+            steps.append("cargo build, with LLM fixes for build errors")
+            if self._settings.clippy_deny:
+                steps.append(f"clippy denying {', '.join(self._settings.clippy_deny)}, with LLM "
+                             "fixes")
+            if self._settings.policies.enabled:
+                steps.append("code policies, with LLM fixes for violations")
+            if self._settings.audit.enabled:
+                outcome = "with LLM fixes" if self._settings.audit.fail else "reported as warnings"
+                steps.append(f"cargo-deny audit of dependency advisories and licenses, {outcome}")
+            if self._settings.cargo_test:
+                steps.append("cargo test, with LLM fixes for failing tests")
+            if self._settings.miri:
+                steps.append(f"cargo +{self._settings.miri_toolchain} miri test, with LLM fixes "
+                             "for undefined behavior")
+            steps.extend(f"{name} checker" for name in [*self._settings.shell_checkers, *(
+                checker.name for checker in self._build_checkers)])
+            if self._settings.fuzz_cases:
+                steps.append(f"differential fuzzing with {self._settings.fuzz_cases} inputs per "
+                             "function")
+            if self._settings.numeric_checks:
+                steps.append("boundary-value comparison of arithmetic at risk of overflow, "
+                             "truncation or rounding")
+            if self._settings.unicode_checks:
+                steps.append("multi-byte text comparison of string lengths, indexes and slices")
+            if self._settings.openapi.enabled:
+                steps.append("comparison of the Python app's OpenAPI description with the Rust "
+                             f"one at {self._settings.openapi.path}, with LLM fixes")
+            if self._settings.http.enabled:
+                steps.append("comparison of the Python and Rust web app responses, with LLM fixes")
+            if self._settings.cli.enabled:
+                steps.append("comparison of the Python and Rust command-line runs, with LLM fixes")
+            if self._settings.database_schema.enabled:
+                steps.append(f"comparison of the {self._settings.database_schema.engine} schemas "
+                             "of the Python models and the Rust code, with LLM fixes")
+            memory = self._settings.memory
+            if memory.enabled and (self._settings.http.enabled or self._settings.cli.enabled):
+                counted = " and allocations" if memory.allocations else ""
+                steps.append(f"peak memory{counted} of the compared Python and Rust runs")
+        if "test" in self._checkers:
+            script = self._test_script or "test.sh next to each source file"
+            steps.append(f"server test script ({script})")
+        if self._settings.sandbox.enabled and "build" in self._checkers:
+            sandbox = self._settings.sandbox
+            steps.append(f"builds and runs in a {sandbox.image} container (network "
+                         f"{sandbox.network}, {sandbox.memory} memory, {sandbox.cpus:g} CPUs)")
+        return steps
+
+    def plan(self) -> MigrationPlan:
+        """Describe what migrate() would do, without calling any model."""
+        if not self._source_files:
+            raise ValueError("No Python sources configured")
+
+        self._extract_notebooks()
+        self._output_dirs = {source: self._output_dir_for(source) for source in self._source_files}
+        graph = ImportGraph(self._source_files, self._source_roots)
+        manifest = MigrationManifest(self._target_dir) if self._incremental else None
+        pricing = load_pricing()
+        stage_models = {
+            "analysis": self._settings.llm_steps.analysis,
+            "verification": self._settings.llm_steps.verification,
+            "fixes": self._settings.llm_steps.fixes
+        }
+        if self._settings.llm_steps.tests is not None:
+            stage_models["tests"] = self._settings.llm_steps.tests
+        plan = MigrationPlan(
+            verification_steps=self._verification_steps(),
+            cycles=[[graph.names[f] for f in cycle] for cycle in graph.cycles()],
+            state_strategy=self._settings.module_state
+        )
+
+        dead_code = self._find_dead_code(graph) if self._settings.skip_dead_code else {}
+        errors = self._exception_hierarchy(graph)
+        plan.errors = list(errors.classes.values()) if errors is not None else []
+        if self._settings.workspace:
+            plan.workspace = package_crates(graph)[0]
+        crates, unmapped = self._resolve_requirements()
+        plan.requirements = [dependency for dependency in crates
+                             if dependency.requirement is not None]
+        plan.unmapped_requirements = [requirement.name + requirement.constraint
+                                      for requirement in unmapped]
+        plan.license_violations = self._license_violations(crates)
+        order = graph.topological_order()
+        self._hot_paths = self._profile_workload(order) if self._settings.profile.command else {}
+        self._cold_code = {}
+        if self._settings.profile.limit:
+            kept = self._limit_to_hot_paths(graph, order, [])
+            plan.skipped = [graph.names[source] for source in order if source not in kept]
+            order = kept
+
+        for source in order:
+            try:
+                python_code = source.read_text()
+            except OSError:
+                python_code = ""
+            dependencies = graph.ordered_dependencies(source)
+            file_plan = FilePlan(
+                source=source,
+                output_dir=self._output_dir_for(source),
+                module=graph.names[source],
+                dependencies=[graph.names[d] for d in dependencies],
+                lines=len(python_code.splitlines()),
+                risks=scan_risks(python_code),
+                native_imports=self._native_imports(python_code),
+                module_state=self._module_state(python_code),
+                decorators=self._decorator_uses(python_code),
+                dead_code=[definition.name for definition in dead_code.get(source, [])],
+                hot_paths=self._hot_paths.get(source, []),
+                cold_code=[definition.name for definition in self._cold_code.get(source, [])]
+            )
+            file_plan.classes, file_plan.classes_approved = self._class_plan(source, python_code)
+
+            if manifest is not None and all(
+                plan_file.unchanged for plan_file in plan.files if plan_file.source in dependencies
+            ):
+                dependency_apis = [
+                    manifest.entries[str(d)].api_hash
+                    if str(d) in manifest.entries else hash_api(None)
+                    for d in dependencies
+                ]
+                input_hash = hash_inputs(python_code, self._fingerprint(), dependency_apis)
+                file_plan.unchanged = manifest.lookup(source, input_hash) is not None
+
+            if source in dead_code or source in self._cold_code:
+                python_code = remove_definitions(
+                    python_code, dead_code.get(source, []) + self._cold_code.get(source, []))
+            stages = estimate_stage_tokens(python_code, self._settings.translation_passes)
+            planned = []
+            for stage, tokens in stages.items():
+                if stage in stage_models:
+                    planned.append((stage, stage_models[stage], tokens))
+                elif not self._settings.consensus_models:
+                    planned.append((stage, self._settings.llm_steps.generation, tokens))
+                else:
+                    planned.extend((f"{stage} ({choice.value})", choice, tokens)
+                                   for choice in self._settings.consensus_models)
+            if len(self._settings.consensus_models) > 1:
+                # Every other candidate is verified once more to pick the best one
+                extra = len(self._settings.consensus_models) - 1
+                verification = estimate_stage_tokens(
+                    python_code, self._settings.translation_passes, fix_attempts=0)["verification"]
+                planned.append(("consensus", stage_models["verification"],
+                                {key: value * extra for key, value in verification.items()}))
+
+            for stage, choice, tokens in planned:
+                model = self._settings.llm_configs[choice].model
+                price = pricing.get(model)
+                if price is None and model not in plan.unpriced_models:
+                    plan.unpriced_models.append(model)
+                file_plan.stages[stage] = {
+                    **tokens,
+                    "model": model,
+                    "cost": price.cost(tokens["input"], tokens["output"]) if price else 0.0
+                }
+            plan.files.append(file_plan)
+        return plan
+
+    def outputs(self) -> Dict[Path, Path]:
+        """Output crate of each configured source, in migration order."""
+        self._extract_notebooks()
+        graph = ImportGraph(self._source_files, self._source_roots)
+        return {source: self._output_dir_for(source) for source in graph.topological_order()}
+
+    async def coverage(self) -> CoverageReport:
+        """Coverage of each configured source by its pytest suite and of its crate
+        by the Rust tests."""
+        if not self._source_files:
+            raise ValueError("No Python sources configured")
+        return await measure_coverage(
+            self.outputs(), self._source_roots, timeout=self._settings.limits.test.timeout)
+
+    def status(self) -> ProjectStatus:
+        """State of each configured source from previous runs, without migrating anything."""
+        if not self._source_files:
+            raise ValueError("No Python sources configured")
+        self._extract_notebooks()
+        return collect_status(self._target_dir, ImportGraph(self._source_files, self._source_roots))
+
+    async def migrate(self) -> MigrationReport:
+        """Run the migration pipeline over all configured sources."""
+        if not self._source_files:
+            raise ValueError("No Python sources configured")
+
+        self._extract_notebooks()
+        self._output_dirs = {source: self._output_dir_for(source) for source in self._source_files}
+        if self._checkpoints:
+            self._checkpoint = self._store.create(
+                self._output_dirs, prompt_set_version(self._settings.prompts_dir))
+        return await self._run()
+
+    async def resume(self, run_id: str) -> MigrationReport:
+        """Continue an interrupted run, skipping files that were already verified."""
+        self._checkpoint = self._store.load(run_id)
+        self._target_dir = Path(self._checkpoint.target_dir)
+        self._source_files = [Path(f.source) for f in self._checkpoint.files.values()]
+        self._output_dirs = {
+            Path(f.source): Path(f.output_dir) for f in self._checkpoint.files.values()
+        }
+        self._checkpoint.status = RunStatus.RUNNING
+        self._save_checkpoint()
+        logger.info(
+            f"Resuming run {run_id}: {len(self._checkpoint.pending_files())} files left")
+        return await self._run()
+
+    async def _check_file(self, source: Path, workflows: List[str]) -> FileReport:
+        output_dir = self._output_dir_for(source)
+        try:
+            python_code = source.read_text()
+            existing_code = (
+                (output_dir / "src" / "main.rs").read_text(),
+                (output_dir / "Cargo.toml").read_text()
+            )
+        except OSError as e:
+            return FileReport(source=source, output_dir=output_dir, success=False, error=str(e))
+
+        settings = self._settings_for(output_dir)
+        settings.apply_fixes = False
+        events = self._events.for_file(source)
+        events.emit(EventType.FILE_STARTED, output_dir=str(output_dir))
+        logger.info(f"Checking {output_dir} against {source}")
+
+        async with MigrationAgent(
+            tokens=self._tokens,
+            output_dir=output_dir,
+            settings=settings,
+            workflows=workflows,
+            test_script_path=self._test_script or source.parent / "test.sh",
+            plugins=self._plugins,
+            events=events,
+            cancel_token=self._cancel_token,
+            request_limiter=self._limiter,
+            redactor=self._redactor,
+            checkers=self._checkers_for(source, python_code)
+        ) as agent:
+            try:
+                success, _, _ = await agent.migrate(python_code, existing_code=existing_code)
+                error = None
+            except MigrationCancelled:
+                success, error = False, "Cancelled"
+
+            metrics = (agent.last_result or {}).get("metrics", {})
+            if not success and error is None:
+                error = agent.last_error or (
+                    "Behavior differs from the Python source"
+                    if metrics.get("verification_matches") is False
+                    else "Build or tests failed"
+                )
+
+        events.emit(EventType.FILE_FINISHED, success=success, error=error)
+        return FileReport(
+            source=source,
+            output_dir=output_dir,
+            success=success,
+            rust_code=existing_code[0],
+            toml_content=existing_code[1],
+            error=error,
+            metrics=metrics,
+            verification=(agent.last_result or {}).get("verification")
+        )
+
+    async def check(self, compare_behavior: bool = True) -> MigrationReport:
+        """Re-run verification on previously generated code, without regenerating or fixing it."""
+        if not self._source_files:
+            raise ValueError("No Python sources configured")
+        self._check_tokens()
+
+        workflows = (["verify"] if compare_behavior else []) + self._checkers
+        if not workflows:
+            raise ValueError("No checks selected")
+
+        self._extract_notebooks()
+        self._output_dirs = {source: self._output_dir_for(source) for source in self._source_files}
+        report = MigrationReport(
+            target_dir=self._target_dir,
+            prompt_version=prompt_set_version(self._settings.prompts_dir))
+        report.warnings = self._local_model_warnings(self._source_files)
+        self._start_requests()
+        workers = asyncio.Semaphore(self._settings.max_workers)
+
+        async def check_with_worker(source: Path) -> FileReport:
+            async with workers:
+                return await self._check_file(source, workflows)
+
+        report.files = list(await asyncio.gather(
+            *(check_with_worker(source) for source in self._source_files)))
+        report.cancelled = self._cancel_token.cancelled
+        report.finished_at = datetime.now()
+        logger.info(f"Check finished: {len(report.succeeded)} passed, {len(report.failed)} failed")
+        return report
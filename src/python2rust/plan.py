@@ -0,0 +1,245 @@
+"""
+Dry-run migration plans: what would be migrated, in which order and at what cost.
+"""
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from .analyzers.class_hierarchy import ClassMapping
+from .analyzers.decorators import DecoratorUse
+from .analyzers.exceptions import ExceptionClass
+from .analyzers.licenses import LicenseViolation
+from .analyzers.module_state import ModuleState, state_strategy
+from .analyzers.native import NativeImport
+from .analyzers.profile import HotPath
+from .analyzers.requirements import CrateDependency
+from .analyzers.risk import RiskFinding
+from .builders.workspace import PackageCrate
+
+
+@dataclass
+class FilePlan:
+    """Planned migration of one source file."""
+    source: Path
+    output_dir: Path
+    module: str
+    dependencies: List[str] = field(default_factory=list)
+    lines: int = 0
+    stages: Dict[str, Dict[str, Any]] = field(default_factory=dict)
+    risks: List[RiskFinding] = field(default_factory=list)
+    # Imports backed by compiled code, which need a replacement crate or FFI bindings
+    native_imports: List[NativeImport] = field(default_factory=list)
+    # Globals the code changes and singletons, held with the plan's state strategy
+    module_state: List[ModuleState] = field(default_factory=list)
+    # Decorators used, with the strategy translating each, if the catalog or the project maps it
+    decorators: List[DecoratorUse] = field(default_factory=list)
+    dead_code: List[str] = field(default_factory=list)  # unused definitions left untranslated
+    # Rust item planned for each class, and whether the user approved the plan
+    classes: List[ClassMapping] = field(default_factory=list)
+    classes_approved: bool = False
+    hot_paths: List[HotPath] = field(default_factory=list)  # of the profiled workload
+    cold_code: List[str] = field(default_factory=list)  # neither hot nor used by hot ones, left out
+    unchanged: bool = False  # would be reused from the last verified migration
+
+    @property
+    def input_tokens(self) -> int:
+        return sum(stage["input"] for stage in self.stages.values())
+
+    @property
+    def output_tokens(self) -> int:
+        return sum(stage["output"] for stage in self.stages.values())
+
+    @property
+    def cost(self) -> float:
+        return sum(stage["cost"] for stage in self.stages.values())
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "source": str(self.source),
+            "output_dir": str(self.output_dir),
+            "module": self.module,
+            "dependencies": self.dependencies,
+            "lines": self.lines,
+            "unchanged": self.unchanged,
+            "input_tokens": self.input_tokens,
+            "output_tokens": self.output_tokens,
+            "cost": round(self.cost, 4),
+            "stages": self.stages,
+            "risks": [vars(r) for r in self.risks],
+            "native_imports": [vars(native) for native in self.native_imports],
+            "module_state": [vars(state) for state in self.module_state],
+            "decorators": [vars(use) for use in self.decorators],
+            "dead_code": self.dead_code,
+            "classes": [vars(mapping) for mapping in self.classes],
+            "classes_approved": self.classes_approved,
+            "hot_paths": [vars(path) for path in self.hot_paths],
+            "cold_code": self.cold_code
+        }
+
+
+@dataclass
+class MigrationPlan:
+    """Everything a migration would do, computed without calling any model."""
+    files: List[FilePlan] = field(default_factory=list)
+    verification_steps: List[str] = field(default_factory=list)
+    # Modules importing each other, whose crates are restructured to break the cycle
+    cycles: List[List[str]] = field(default_factory=list)
+    # Modules left out because the profiled workload spends no time in them or what they import
+    skipped: List[str] = field(default_factory=list)
+    # How module-level state is held in every module: once_lock, app_state or injection
+    state_strategy: Optional[str] = None
+    # Exception classes of the project, translated into the error module every crate includes
+    errors: List[ExceptionClass] = field(default_factory=list)
+    # Crates of the project's packages, gathered in a Cargo workspace when there are several
+    workspace: List[PackageCrate] = field(default_factory=list)
+    # Requirements of the project and the crates pinned for them, and those no mapping covers
+    requirements: List[CrateDependency] = field(default_factory=list)
+    unmapped_requirements: List[str] = field(default_factory=list)
+    # Crates of the requirements under licenses outside the allowed ones, which fail the plan
+    license_violations: List[LicenseViolation] = field(default_factory=list)
+    currency: str = "USD"
+    unpriced_models: List[str] = field(default_factory=list)
+
+    @property
+    def pending(self) -> List[FilePlan]:
+        return [f for f in self.files if not f.unchanged]
+
+    @property
+    def total_tokens(self) -> int:
+        return sum(f.input_tokens + f.output_tokens for f in self.pending)
+
+    @property
+    def total_cost(self) -> float:
+        return sum(f.cost for f in self.pending)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "files": [f.to_dict() for f in self.files],
+            "verification_steps": self.verification_steps,
+            "cycles": self.cycles,
+            "skipped": self.skipped,
+            "state_strategy": self.state_strategy,
+            "errors": [vars(exception) for exception in self.errors],
+            "workspace": [{**vars(crate), "sources": [str(source) for source in crate.sources]}
+                          for crate in self.workspace],
+            "requirements": [{**vars(dependency), "requirement": vars(dependency.requirement)}
+                             for dependency in self.requirements],
+            "unmapped_requirements": self.unmapped_requirements,
+            "license_violations": [
+                {**vars(violation),
+                 "alternatives": [vars(crate) for crate in violation.alternatives]}
+                for violation in self.license_violations
+            ],
+            "total_tokens": self.total_tokens,
+            "total_cost": round(self.total_cost, 4),
+            "currency": self.currency,
+            "unpriced_models": self.unpriced_models
+        }
+
+    def format(self) -> str:
+        """Human readable plan."""
+        lines = ["Migration plan", ""]
+        width = max([len(f.module) for f in self.files] + [6])
+        lines.append(f"  #  {'Module':<{width}}  {'Lines':>6}  {'Tokens':>8}  "
+                     f"{'Cost':>8}  Risks  Depends on")
+        for index, f in enumerate(self.files, start=1):
+            tokens = "reused" if f.unchanged else f"{f.input_tokens + f.output_tokens:,}"
+            cost = "-" if f.unchanged else f"${f.cost:.2f}"
+            lines.append(
+                f"{index:>3}  {f.module:<{width}}  {f.lines:>6}  {tokens:>8}  {cost:>8}  "
+                f"{len(f.risks):>5}  {', '.join(f.dependencies) or '-'}")
+
+        risky = [f for f in self.files if f.risks]
+        if risky:
+            lines.extend(["", "Risky constructs:"])
+            for f in risky:
+                for risk in f.risks:
+                    lines.append(f"  {f.source}:{risk.line} [{risk.severity}] "
+                                 f"{risk.construct}: {risk.message}")
+
+        native = [f for f in self.files if f.native_imports]
+        if native:
+            lines.extend(["", "Native dependencies (need a replacement crate or FFI bindings):"])
+            for f in native:
+                lines.extend(f"  {f.source}: {found.describe()}" for found in f.native_imports)
+
+        planned = [f for f in self.files if f.classes]
+        if planned:
+            lines.extend(["", "Class mapping (review and approve with `python2rust classes`):"])
+            for f in planned:
+                lines.append(f"  {f.source} "
+                             f"({'approved' if f.classes_approved else 'not approved'}):")
+                lines.extend(f"    {mapping.describe()}" for mapping in f.classes)
+
+        stateful = [f for f in self.files if f.module_state]
+        if stateful and self.state_strategy:
+            lines.extend(["",
+                          f"Module-level state (held with the {self.state_strategy} strategy):"])
+            for f in stateful:
+                lines.extend(f"  {f.source}: {state.describe()} -> "
+                             f"{state_strategy(state, self.state_strategy)}"
+                             for state in f.module_state)
+
+        if self.errors:
+            lines.extend(["",
+                          "Error module (thiserror types of the errors crate every crate shares):"])
+            lines.extend(f"  {exception.describe()}" for exception in self.errors)
+
+        if self.requirements:
+            lines.extend(["", "Requirements and their crates (pinned in every Cargo.toml):"])
+            lines.extend(f"  {dependency.describe()}" for dependency in self.requirements)
+        if self.unmapped_requirements:
+            lines.extend(["", "Requirements without a crate mapping (map them under [crates] in "
+                              "python2rust.toml):"])
+            lines.append(f"  {', '.join(self.unmapped_requirements)}")
+        if self.license_violations:
+            lines.extend(["", "Crates under licenses outside the allowed ones (replace them under "
+                              "[crates] in python2rust.toml before migrating):"])
+            lines.extend(f"  {violation.describe()}" for violation in self.license_violations)
+
+        if self.workspace:
+            lines.extend(["", "Cargo workspace (a library crate per package, including the "
+                              "crates of its modules):"])
+            lines.extend(f"  {crate.describe()}" for crate in self.workspace)
+
+        unmapped = [f for f in self.files if any(not use.known for use in f.decorators)]
+        if unmapped:
+            lines.extend(["",
+                          "Decorators without a translation strategy (map them under [decorators] "
+                              "in python2rust.toml):"])
+            for f in unmapped:
+                lines.extend(f"  {f.source}: {use.describe()}"
+                             for use in f.decorators if not use.known)
+
+        unused = [f for f in self.files if f.dead_code]
+        if unused:
+            lines.extend(["", "Left untranslated as unused:"])
+            lines.extend(f"  {f.source}: {', '.join(f.dead_code)}" for f in unused)
+
+        hot = [f for f in self.files if f.hot_paths]
+        if hot:
+            lines.extend(["", "Hot paths of the profiled workload:"])
+            for f in hot:
+                lines.extend(f"  {f.source}: {path.describe()}" for path in f.hot_paths)
+        cold = [f for f in self.files if f.cold_code]
+        if cold:
+            lines.extend(["", "Left out as neither hot nor used by hot functions:"])
+            lines.extend(f"  {f.source}: {', '.join(f.cold_code)}" for f in cold)
+        if self.skipped:
+            lines.extend(["", f"Not migrated, without hot paths: {', '.join(self.skipped)}"])
+
+        if self.cycles:
+            lines.extend(["", "Import cycles (each module imports only those "
+                              "migrated before it in Rust):"])
+            lines.extend(f"  {' -> '.join(cycle)}" for cycle in self.cycles)
+
+        lines.extend(["", "Verification steps:"])
+        lines.extend(f"  - {step}" for step in self.verification_steps)
+        lines.extend([
+            "",
+            f"Estimated total: {self.total_tokens:,} tokens, ${self.total_cost:.2f} {self.currency}"
+            f" for {len(self.pending)} of {len(self.files)} files"
+        ])
+        if self.unpriced_models:
+            lines.append(f"No price known for: {', '.join(self.unpriced_models)} (counted as free)")
+        return "\n".join(lines)
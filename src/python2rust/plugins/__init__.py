@@ -0,0 +1,44 @@
+# plugins/__init__.py
+from .async_runtime import AsyncRuntime
+from .base import Plugin
+from .classes import ClassPlan
+from .comments import PythonComments
+from .context_managers import RaiiGuards
+from .cycles import ImportCycle
+from .database import DatabaseAccess
+from .decorators import DecoratorStrategies
+from .dependencies import DependencySignatures
+from .docs import RustDocs
+from .doctests import PythonDoctests
+from .error_style import ErrorStyle
+from .errors import ErrorModule
+from .formatting import FormatConversions
+from .generators import GeneratorIterators
+from .hot_paths import HotPaths
+from .loader import load_plugins
+from .manager import PluginManager
+from .naming import IdentifierNames
+from .native import NativeDependencies
+from .openapi import OpenApiAnnotations
+from .project import ProjectCrates
+from .requirements import RequirementCrates
+from .retrieval import RelevantDefinitions
+from .risks import TranslationRisks
+from .state import GlobalState
+from .structure import PythonStructure
+from .templates import HtmlTemplates
+from .tests import PythonTests
+from .traces import CallTraces
+from .types import InferredTypes
+from .visibility import ItemVisibility
+from .web_framework import WebFramework
+from .review import ReviewFeedback
+
+__all__ = ['Plugin', 'AsyncRuntime', 'CallTraces', 'ClassPlan', 'DatabaseAccess',
+           'DecoratorStrategies', 'DependencySignatures', 'ErrorModule', 'ErrorStyle',
+           'FormatConversions', 'GeneratorIterators', 'GlobalState', 'HotPaths', 'HtmlTemplates',
+           'IdentifierNames', 'ImportCycle', 'InferredTypes', 'ItemVisibility',
+           'NativeDependencies', 'OpenApiAnnotations', 'PluginManager', 'ProjectCrates',
+           'PythonComments', 'PythonDoctests', 'PythonStructure', 'PythonTests', 'RaiiGuards',
+           'RelevantDefinitions', 'RequirementCrates', 'ReviewFeedback', 'RustDocs',
+           'TranslationRisks', 'WebFramework', 'load_plugins']
@@ -0,0 +1,78 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.async_runtime import RUNTIMES, AsyncUse
+from ..analyzers.rust_quality import code_lines
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+ASYNC_CODE = re.compile(r"\basync\s+(fn|move\b|\{)|\.await\b")
+
+
+class AsyncRuntime(Plugin):
+    """Targets the project's async runtime, tokio, async-std, or none for synchronous code: the
+    prompts give the attribute macros of its main and tests and its sleep, spawn and gather idioms
+    for the async constructs of the module, the runtime crate is added to Cargo.toml when the code
+    uses it undeclared, and verification fails code using another runtime, or async code when
+    the output is synchronous."""
+    name = "async_runtime"
+
+    def __init__(self, runtime: str, uses: List[AsyncUse]):
+        self.runtime = RUNTIMES[runtime]
+        self.uses = uses
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        runtime = self.runtime
+        listed = "\n".join(f"- {use.describe(runtime)}" for use in self.uses)
+        if runtime.crate is None:
+            return (
+                "The output is synchronous: no async fn, .await or async runtime. Async functions "
+                f"become plain functions, awaits plain calls, {runtime.main} runs the program, "
+                f"waits are {runtime.sleep}, spawned work is {runtime.spawn} and gathered work "
+                f"is {runtime.gather}:\n"
+                + listed
+            )
+        return (
+            f"Target the {runtime.name} runtime, and no other: the program runs with "
+            f"{runtime.main}, waits are {runtime.sleep}, spawned work is {runtime.spawn} and "
+            f"gathered work is {runtime.gather}. Declare `{runtime.dependency()}` in "
+            f"[dependencies]:\n{listed}"
+        )
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        if not any(use.kind == "async def" for use in self.uses):
+            return None
+        if self.runtime.crate is None:
+            return ("The translated async functions are synchronous: test them with "
+                    "plain #[test] functions.")
+        return f"Test the async functions with {self.runtime.test} tests, not #[test] functions."
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        runtime = self.runtime
+        if (runtime.crate is None
+                or runtime.crate in section_entries(toml_content, "[dependencies]")):
+            return toml_content
+        if not re.search(rf"\b{runtime.identifier}::", rust_code):
+            return toml_content
+        return add_to_section(toml_content, "[dependencies]", [runtime.dependency()])
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        others = [other for other in RUNTIMES.values()
+                  if other.crate not in (None, self.runtime.crate)]
+        issues = []
+        for other in others:
+            used = re.search(rf"\b{other.identifier}::", rust_code)
+            if not (used or other.crate in section_entries(toml_content, "[dependencies]")):
+                continue
+            if self.runtime.crate is None:
+                issues.append(f"the output must be synchronous, but the code uses {other.name}")
+            else:
+                issues.append(f"the code uses {other.name} instead of {self.runtime.name}; use "
+                              f"{self.runtime.name}'s idioms and crate only")
+        if self.runtime.crate is None:
+            lines = [number for number, line in code_lines(rust_code) if ASYNC_CODE.search(line)]
+            if lines:
+                issues.append("the output must be synchronous, but lines "
+                              f"{', '.join(map(str, lines))} are async; use plain "
+                              "functions and threads")
+        return issues
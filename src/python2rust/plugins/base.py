@@ -0,0 +1,33 @@
+from typing import Any, Dict, List, Optional
+
+
+class Plugin:
+    """Base class for migration plugins.
+
+    Subclasses override only the hooks they need; every hook has a no-op default.
+    """
+    name: str = "plugin"
+
+    def crate_mappings(self) -> Dict[str, str]:
+        """Map Python libraries to the Rust crates that should replace them."""
+        return {}
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        """Extra context added to the analysis prompt."""
+        return None
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        """Extra context added to the generation prompt."""
+        return None
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        """Python tests to translate into the Rust tests of the code."""
+        return None
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        """Edit the Cargo.toml generated for the code, e.g. pinning dependencies."""
+        return toml_content
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        """Check generated code and return a list of issues (empty when the code is fine)."""
+        return []
@@ -0,0 +1,72 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.class_hierarchy import ClassMapping
+from ..analyzers.rust_items import RustItem, scan_items
+
+
+class ClassPlan(Plugin):
+    """Gives the generation prompt the planned Rust item of each class (struct, trait, enum or
+    enum variant), from the class hierarchy or the plan the user approved; verification reports
+    the classes translated as something else, traits missing their methods or default bodies,
+    and structs not implementing their traits."""
+    name = "classes"
+
+    def __init__(self, mappings: List[ClassMapping], approved: bool = False):
+        self.mappings = mappings
+        self.approved = approved
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.mappings:
+            return None
+        listed = "\n".join(f"- {mapping.describe()}" for mapping in self.mappings)
+        source = "the user approved" if self.approved else "planned from the class hierarchy"
+        return (
+            f"Translate the classes as {source}: structs hold the fields of their class, traits "
+            "their required methods without a body and their default methods with the Python "
+            "body, and each struct implements the traits listed for it with "
+            "`impl Trait for Struct` rather than copies of their methods; enums get a variant per "
+            f"subclass, isinstance checks becoming match arms:\n{listed}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        items = scan_items(rust_code)
+        lines = rust_code.splitlines()
+        kinds = {(item.kind, item.name) for item in items}
+        issues = []
+        for mapping in self.mappings:
+            if mapping.kind == "variant":
+                enums = [item for item in items if item.kind == "enum"
+                         and item.name == mapping.parent]
+                body = "\n".join(lines[enums[0].start_line:enums[0].end_line]) if enums else ""
+                if not re.search(rf"^\s*{re.escape(mapping.name)}\b", body, re.MULTILINE):
+                    issues.append(f"class {mapping.name} is planned as the variant "
+                                  f"{mapping.parent}::{mapping.name}, which the Rust code lacks")
+            elif (mapping.kind, mapping.name) not in kinds:
+                issues.append(f"class {mapping.name} is planned as the {mapping.kind} "
+                              f"{mapping.name}, which the Rust code lacks")
+                continue
+            if mapping.kind == "trait":
+                issues.extend(self._trait_issues(mapping, items, lines))
+            implemented = {item.trait for item in items if item.kind == "impl"
+                           and item.name == mapping.name}
+            issues.extend(
+                f"{mapping.name} does not implement the trait {trait}; write `impl {trait} for "
+                f"{mapping.name}` instead of copying its methods"
+                for trait in mapping.implements if trait not in implemented)
+        return issues
+
+    def _trait_issues(self, mapping: ClassMapping, items: List[RustItem],
+                      lines: List[str]) -> List[str]:
+        trait = next(item for item in items if item.kind == "trait" and item.name == mapping.name)
+        body = "\n".join(lines[trait.start_line - 1:trait.end_line])
+        issues = []
+        for method in mapping.required_methods + mapping.default_methods:
+            declared = re.search(rf"\bfn\s+{re.escape(method)}\b[^;{{]*(?P<end>[;{{])", body)
+            if declared is None:
+                issues.append(f"the trait {mapping.name} lacks the method {method}")
+            elif method in mapping.default_methods and declared.group("end") != "{":
+                issues.append(f"the trait {mapping.name} declares {method} without the default "
+                              "body of the Python method")
+        return issues
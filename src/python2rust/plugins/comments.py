@@ -0,0 +1,21 @@
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.comments import python_comments
+
+
+class PythonComments(Plugin):
+    """Lists the comments of the module with the statements they are at, for the generation
+    prompt to keep them next to the Rust code translating those."""
+    name = "comments"
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        comments = python_comments(python_code)
+        if not comments:
+            return None
+        listed = "\n".join(f"- {comment.describe()}" for comment in comments)
+        return (
+            "Keep the comments of the Python code: write each one as a // comment on or above the "
+            "Rust code translating the statement it is at, in the function translating its Python "
+            f"function, adapting the names it mentions:\n{listed}"
+        )
@@ -0,0 +1,63 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.context_managers import ContextManager
+
+
+class RaiiGuards(Plugin):
+    """Translates context managers into RAII guards: the cleanup of __exit__ or of the code after
+    the yield of a @contextmanager goes into the Drop of a guard, so that it runs when the guarded
+    code returns early, fails with `?` or panics, and a test per context manager the module
+    defines checks the cleanup runs on the error path. Verification fails a context manager
+    without its Drop implementation or its test."""
+    name = "context_managers"
+
+    def __init__(self, managers: List[ContextManager]):
+        self.managers = managers
+
+    def _defined(self) -> List[ContextManager]:
+        return [manager for manager in self.managers if manager.kind != "with"]
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.managers:
+            return None
+        listed = "\n".join(f"- {manager.describe()}" for manager in self.managers)
+        return (
+            "Translate the context managers of the module into RAII guards. A class with "
+            "__enter__ and __exit__ implements Drop with the cleanup of __exit__; a "
+            "@contextmanager function becomes the guard struct named, its code before the yield "
+            "the constructor returning it and its code after the yield the Drop. A guard whose "
+            "__exit__ can swallow the exception is paired with a method running the block and "
+            "turning its Err into Ok, and an async one gets an async close method awaited on "
+            "every path, its Drop the synchronous fallback. Each with block binds its guard to a "
+            "named local (`let _guard = ...`, never `let _ = ...`, which drops at once) for the "
+            "scope of the block, with std types doing their own cleanup on drop (File, "
+            "MutexGuard, tempfile). Never put cleanup after the last statement of the block: it "
+            f"has to run on the early returns, `?` errors and panics listed too:\n{listed}"
+        )
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        defined = self._defined()
+        if not defined:
+            return None
+        listed = "\n".join(f"- `{manager.test_name}` for {manager.name}, guarded by {manager.guard}"
+                           for manager in defined)
+        return (
+            "Test the error path of each context manager with the #[test] named below: run a block "
+            "under its guard that fails, returning an Err through `?` (or panicking inside "
+            "std::panic::catch_unwind), and assert the cleanup happened anyway, as "
+            f"`with` guarantees in Python:\n{listed}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        for manager in self._defined():
+            if not re.search(rf"\bimpl\s*(<[^>]*>)?\s*Drop\s+for\s+{manager.guard}\b", rust_code):
+                issues.append(f"the context manager {manager.name} has no "
+                              f"`impl Drop for {manager.guard}`, so its cleanup does not run on "
+                              "early returns and errors")
+            if not re.search(rf"\bfn\s+{manager.test_name}\s*\(", rust_code):
+                issues.append(f"the context manager {manager.name} has no test of its error path; "
+                              f"add #[test] fn {manager.test_name}")
+        return issues
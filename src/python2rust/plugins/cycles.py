@@ -0,0 +1,39 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.python_structure import analyze_structure
+
+
+class ImportCycle(Plugin):
+    """Tells the generation prompt of a module in an import cycle which of the modules it imports
+    are migrated after it, with their Python API, as its crate cannot depend on theirs."""
+    name = "cycle"
+
+    def __init__(self, cycle: List[str], later: Dict[str, str]):
+        # Modules of the cycle, in migration order
+        self.cycle = cycle
+        # Module name -> Python code of the imported modules migrated after this one
+        self.later = later
+
+    def _api(self, module: str, python_code: str) -> str:
+        structure = analyze_structure(python_code)
+        if structure is None:
+            return f"Module `{module}`: could not be parsed"
+        lines = [f"- class `{name}`" for name in structure.classes]
+        lines.extend(
+            f"- `{function.signature}`" for function in structure.functions
+            if "." not in function.qualname)
+        return f"Module `{module}`:\n" + ("\n".join(lines) or "- no functions or classes")
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.later:
+            return None
+        return (
+            f"This module is part of the import cycle {' -> '.join(f'`{m}`' for m in self.cycle)}. "
+            "Each module becomes a crate of its own and crates cannot depend on each other in a "
+            f"cycle, so this crate must not depend on {', '.join(f'`{m}`' for m in self.later)}, "
+            "which are migrated after it and will depend on this crate. Restructure the code "
+            "instead: define here the types this module needs from them, and take the behavior it "
+            "calls as a trait they implement or as function parameters. Their Python API:\n\n"
+            + "\n\n".join(self._api(module, code) for module, code in self.later.items())
+        )
@@ -0,0 +1,85 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.database import LAYERS, DatabaseUse, migration_files
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+# Crates of the Python drivers the crate mappings know, replaced by the layer
+DRIVER_CRATES = ("rusqlite", "tokio-postgres", "postgres", "mysql")
+
+
+class DatabaseAccess(Plugin):
+    """Generates the database access of a module using a SQL driver against the project's data
+    access stack, sqlx, diesel or sea-orm: the prompt gives the layer's connection pool, the
+    queries of the module to write with its checked queries, and the migrations written
+    beforehand into the migrations/ directory of the crate for the tables it creates. sqlx's
+    query! macros are asked for only when the build has a database with the tables, checked
+    tells; without one the queries are checked at run time. Its crates are added to Cargo.toml
+    when the code uses them undeclared, and verification fails code using another layer or a
+    driver crate, creating the tables in code or not running the migrations."""
+    name = "database"
+
+    def __init__(self, layer: str, use: DatabaseUse, runtime: str, checked: bool = False):
+        self.layer = LAYERS[layer]
+        self.use = use
+        self.runtime = runtime
+        self.checked = checked  # whether the build has a database to check the queries against
+
+    @property
+    def queries(self) -> str:
+        if self.layer.checks_queries and not self.checked:
+            return self.layer.unchecked
+        return self.layer.queries
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        layer, use = self.layer, self.use
+        pool = layer.pool.format(connection=layer.connections.get(use.backend, ""))
+        parts = [
+            f"Access the {use.backend} database of the module with {layer.name}, and no other "
+            f"database crate. Open a single connection pool, {pool}, from the DATABASE_URL "
+            "environment variable, and share it instead of connecting for each call. Write the "
+            f"queries with {self.queries}."
+        ]
+        if use.queries:
+            parts.append("The queries of the module:\n"
+                         + "\n".join(f"- {statement.describe()}" for statement in use.queries))
+        if use.tables:
+            files = ", ".join(name for name, _ in migration_files(use.tables, layer.name))
+            parts.append(f"The tables {', '.join(table.name for table in use.tables)} are created "
+                         f"by the migrations written in the crate ({files}), {layer.migrations}; "
+                         "do not create them in code.")
+        declared = ", ".join(f"`{line}`" for line in layer.dependencies(use.backend, self.runtime))
+        parts.append(f"Declare {declared} in [dependencies].")
+        return "\n".join(parts)
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        declared = section_entries(toml_content, "[dependencies]")
+        missing = []
+        for line in self.layer.dependencies(self.use.backend, self.runtime):
+            crate = line.split("=", 1)[0].strip()
+            if crate not in declared and re.search(rf"\b{crate.replace('-', '_')}::", rust_code):
+                missing.append(line)
+        return add_to_section(toml_content, "[dependencies]", missing)
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        declared = section_entries(toml_content, "[dependencies]")
+        others = [other.name for other in LAYERS.values() if other.name != self.layer.name]
+        for crate in [*others, *DRIVER_CRATES]:
+            # sqlx::postgres:: is a module of sqlx, not the postgres crate
+            if crate in declared or re.search(rf"(?<!::)\b{crate.replace('-', '_')}::", rust_code):
+                issues.append(f"the database is accessed with {crate} instead of {self.layer.name}")
+        if self.use.tables:
+            created = [table.name for table in self.use.tables if re.search(
+                rf"CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?[\"`]?{table.name}\b",
+                rust_code, re.IGNORECASE)]
+            if created:
+                issues.append("tables are created in code instead of by the migrations: "
+                              + ", ".join(created))
+            if not re.search(r"\bmigrate!|\bembed_migrations!|\bMigratorTrait\b", rust_code):
+                issues.append("the migrations of the crate are not run at startup")
+        if (self.layer.checks_queries and self.checked and self.use.queries
+                and not re.search(r"\bquery(?:_as|_scalar)?!", rust_code)):
+            issues.append("queries are not checked at compile time with sqlx's query! macros")
+        return issues
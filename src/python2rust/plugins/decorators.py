@@ -0,0 +1,37 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.decorators import DecoratorUse
+
+
+class DecoratorStrategies(Plugin):
+    """Gives the generation prompt the strategy translating each decorator of the module, from the
+    built-in catalog, the [decorators] table of python2rust.toml or the project's own decorators,
+    so decorators are translated the same way in every module rather than dropped."""
+    name = "decorators"
+
+    def __init__(self, uses: List[DecoratorUse]):
+        self.uses = uses
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.uses:
+            return None
+        lines: Dict[str, List[int]] = {}
+        strategies: Dict[str, Optional[str]] = {}
+        for use in self.uses:
+            lines.setdefault(use.name, []).append(use.line)
+            strategies[use.name] = use.strategy
+        known = [f"- @{name} (lines {', '.join(map(str, lines[name]))}): {strategy}"
+                 for name, strategy in strategies.items() if strategy is not None]
+        unknown = [f"- @{name} (lines {', '.join(map(str, lines[name]))})"
+                   for name, strategy in strategies.items() if strategy is None]
+        sections = []
+        if known:
+            sections.append("Translate the decorators of the module with the "
+                            "project's strategies:\n"
+                            + "\n".join(known))
+        if unknown:
+            sections.append("No strategy is configured for these decorators; reproduce what they "
+                            "add to the decorated code from their documented behavior, with a "
+                            "comment naming the decorator:\n" + "\n".join(unknown))
+        return "\n\n".join(sections)
@@ -0,0 +1,47 @@
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.rust_signatures import summarize_rust
+from ..analyzers.token_estimate import count_tokens
+
+
+class DependencySignatures(Plugin):
+    """Feeds the Rust generated for imported modules into the generation prompt.
+
+    Modules are included in full while they fit in full_tokens; the others, and
+    every module once the budget is spent, are given as digests of their public
+    API: doc comments, types and signatures.
+    """
+    name = "dependencies"
+
+    def __init__(
+        self,
+        generated: Dict[str, str],
+        full_tokens: int = 0,
+        digests: Optional[Dict[str, str]] = None
+    ):
+        # Module name -> generated Rust code
+        self.generated = generated
+        self.full_tokens = full_tokens
+        # Module name -> digest, computed from the code when missing
+        self.digests = digests or {}
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        sections = []
+        remaining = self.full_tokens
+        for module, rust_code in self.generated.items():
+            tokens = count_tokens(rust_code)
+            if tokens <= remaining:
+                remaining -= tokens
+                sections.append(f"Module `{module}`:\n```rust\n{rust_code.strip()}\n```")
+                continue
+            digest = self.digests.get(module) or summarize_rust(rust_code)
+            if digest:
+                sections.append(f"Module `{module}` (public API):\n```rust\n{digest}\n```")
+        if not sections:
+            return None
+        return (
+            "The following imported modules were already migrated. "
+            "Keep calls to them consistent with their Rust API:\n\n"
+            + "\n\n".join(sections)
+        )
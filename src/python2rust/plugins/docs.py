@@ -0,0 +1,64 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.docstrings import doc_comment, documented_symbols
+from ..analyzers.rust_items import scan_items
+from ..analyzers.source_map import matching_symbol
+
+# Rust items a documented Python definition can become
+DOCUMENTED_KINDS = ("fn", "method", "struct", "enum", "trait")
+
+
+def _has_doc(rust_lines: List[str], start_line: int) -> bool:
+    """Whether doc comments or a doc attribute precede an item, above its attributes."""
+    index = start_line - 2
+    while index >= 0:
+        line = rust_lines[index].strip()
+        if line.startswith(("///", "#[doc")):
+            return True
+        if not line.startswith("#["):
+            return False
+        index -= 1
+    return False
+
+class RustDocs(Plugin):
+    """Carries the module's docstrings onto the Rust items translating them, as rustdoc comments
+    with their parameter sections converted; verification reports the documented definitions
+    whose items lost them."""
+    name = "docs"
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        documented = documented_symbols(python_code)
+        if not documented:
+            return None
+        comments = []
+        for symbol in documented:
+            if symbol.kind == "module":
+                comments.append("The crate (at the top of the file):\n"
+                                f"{doc_comment(symbol.rustdoc, '//!')}")
+            else:
+                comments.append(f"`{symbol.qualname}` ({symbol.kind}):\n"
+                                f"{doc_comment(symbol.rustdoc)}")
+        return (
+            "Keep the documentation of the Python code: put these doc comments on the Rust items "
+            "translating each definition, with the Rust names and types where they differ:\n\n"
+            + "\n\n".join(comments)
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        documented = {symbol.qualname: symbol
+                      for symbol in documented_symbols(python_code) if symbol.symbol}
+        if not documented:
+            return []
+        symbols = [symbol.symbol for symbol in documented.values()]
+        rust_lines = rust_code.splitlines()
+        items: Dict[str, List[bool]] = {}
+        for item in scan_items(rust_code):
+            symbol = matching_symbol(item, symbols) if item.kind in DOCUMENTED_KINDS else None
+            if symbol is not None:
+                items.setdefault(symbol.qualname, []).append(_has_doc(rust_lines, item.start_line))
+        return [
+            f"the Rust translation of {qualname} has no doc comment; carry its "
+            "docstring over as ///"
+            for qualname, documented_items in items.items() if not any(documented_items)
+        ]
@@ -0,0 +1,58 @@
+import re
+from typing import Dict, List, Optional, Tuple
+
+from .base import Plugin
+from ..analyzers.doctests import Doctest, python_doctests, doctest_name
+
+# How the test checks each kind of example
+CHECKS = {
+    "value": "assert_eq! the call against the value",
+    "output": "compare what the code formats or prints against the text, as a formatted string",
+    "error": "check that the call returns Err, or mark the test #[should_panic] when it panics",
+}
+
+
+def named_doctests(python_code: str) -> List[Tuple[str, Doctest]]:
+    """The doctests of the module with the Rust tests translating them."""
+    counts: Dict[str, int] = {}
+    named = []
+    for example in python_doctests(python_code):
+        counts[example.qualname] = counts.get(example.qualname, 0) + 1
+        named.append((doctest_name(example.qualname, counts[example.qualname]), example))
+    return named
+
+
+def _listed(name: str, example: Doctest) -> str:
+    session = [f">>> {line}" if index == 0 else f"... {line}"
+               for source in [*example.setup, example.source]
+               for index, line in enumerate(source.splitlines())]
+    check = CHECKS[example.kind] + (f" ({example.exception})" if example.kind == "error" else "")
+    return (f"`{name}` ({example.qualname}, line {example.line}): {check}\n"
+            "```python\n" + "\n".join(session) + f"\n{example.want}\n```")
+
+
+class PythonDoctests(Plugin):
+    """Turns the doctests of the module's docstrings into Rust tests, one #[test] per example
+    with a result, since cargo runs no doc tests for the binary crates generated; verification
+    reports the examples left without their test."""
+    name = "doctests"
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        named = named_doctests(python_code)
+        if not named:
+            return None
+        return (
+            "The docstrings give these examples as doctests. Translate each into the #[test] "
+            "function named, in the crate's `#[cfg(test)] mod tests` module, running its setup "
+            "lines first and keeping the expected result as written so the examples stay "
+            "executable specifications:\n\n"
+            + "\n\n".join(_listed(name, example) for name, example in named)
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        return [
+            f"the doctest of {example.qualname} at line {example.line} has no Rust test; "
+            f"add #[test] fn {name}"
+            for name, example in named_doctests(python_code)
+            if not re.search(rf"\bfn\s+{name}\s*\(", rust_code)
+        ]
@@ -0,0 +1,37 @@
+import re
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.rust_policy import ERROR_STYLES
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+# The [dependencies] line of the crate of each error style, and what uses it
+STYLE_CRATES = {"anyhow": ('anyhow = "1"', r"\banyhow(::|!)|\b(bail|ensure)!"),
+                "thiserror": ('thiserror = "1"', r"\bthiserror::")}
+
+
+class ErrorStyle(Plugin):
+    """Has every crate of the project handle errors the way the project chose: anyhow in the
+    application style, thiserror enums in the library style, or std::error::Error implemented by
+    hand. The prompt asks for the style and the crate it uses is added to Cargo.toml when the code
+    uses it without declaring it; the policy checker fails code of another style."""
+    name = "error_style"
+
+    def __init__(self, style: str):
+        self.style = style
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        context = (f"The project handles errors in the {self.style} style, the same way in every "
+                   f"crate: {ERROR_STYLES[self.style]}.")
+        if self.style in STYLE_CRATES:
+            context += f" Add `{STYLE_CRATES[self.style][0]}` to [dependencies]."
+        return context
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        if self.style not in STYLE_CRATES:
+            return toml_content
+        line, pattern = STYLE_CRATES[self.style]
+        if (self.style in section_entries(toml_content, "[dependencies]")
+                or not re.search(pattern, rust_code)):
+            return toml_content
+        return add_to_section(toml_content, "[dependencies]", [line])
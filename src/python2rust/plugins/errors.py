@@ -0,0 +1,93 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.exceptions import (
+    BUILTIN_VARIANTS, ERRORS_CRATE, ExceptionHierarchy, ExceptionSite, exception_sites
+)
+from ..analyzers.rust_policy import AD_HOC_ERROR
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+MOD_ERROR = re.compile(r"\bmod\s+error\s*;")
+INLINE_MOD_ERROR = re.compile(r"\bmod\s+error\s*\{")
+
+
+class ErrorModule(Plugin):
+    """Has every crate of the project use the same error module, generated from the project's
+    exception classes and their raise and except sites: thiserror enums mirroring the hierarchy,
+    From conversions between its levels and the messages of the Python exceptions. The module is
+    the errors crate, written once at crate_path from the crate; each crate declares `mod error;`,
+    its src/error.rs re-exporting the errors crate, which is added to Cargo.toml. The prompt maps
+    each raise and except clause of the file onto it; verification fails code without the
+    declaration, defining the error types again or with String and Box<dyn Error> errors."""
+    name = "errors"
+
+    def __init__(self, hierarchy: ExceptionHierarchy, crate_path: str):
+        self.hierarchy = hierarchy
+        self.crate_path = crate_path  # of the errors crate, relative to the crate
+
+    def _relevant(self, python_code: str) -> bool:
+        return bool(exception_sites(python_code)) or any(
+            re.search(rf"^\s*class\s+{name}\b", python_code, re.MULTILINE)
+            for name in self.hierarchy.classes)
+
+    def _rust_type(self, name: str) -> str:
+        if name in self.hierarchy.classes:
+            exception = self.hierarchy.classes[name]
+            return f"error::{name}::Message" if exception.children else f"error::{name}"
+        if name in BUILTIN_VARIANTS:
+            return f"error::Error::{BUILTIN_VARIANTS[name]}"
+        return "the error of the crate replacing its library, converted with `?`"
+
+    def _mapped(self, site: ExceptionSite) -> str:
+        if site.kind == "raise":
+            raised = site.exceptions[0] + (f"({site.message})" if site.message else "")
+            return f"- line {site.line}: raise {raised} -> {self._rust_type(site.exceptions[0])}"
+        patterns = " | ".join(self.hierarchy.pattern(name) for name in site.exceptions)
+        return f"- line {site.line}: except {', '.join(site.exceptions)} -> Err({patterns})"
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self._relevant(python_code):
+            return None
+        sites = [self._mapped(site) for site in exception_sites(python_code)]
+        listed = ("\nRaise and except sites of the module:\n" + "\n".join(sites)) if sites else ""
+        return (
+            "The project's errors live in one error module shared by all its crates, generated "
+            f"from its exception classes: the `{ERRORS_CRATE}` crate, which src/error.rs of the "
+            "crate re-exports. Declare it with `mod error;`, and do not define the module or the "
+            "exception classes again. Functions that can fail return `error::Result<T>` and "
+            "propagate errors with `?`, never `String` or `Box<dyn Error>` errors; a raise "
+            "returns `Err` with the type below, `.into()` converting it to the error returned and "
+            "keeping the message of the Python exception, and an except clause matches the "
+            "patterns below. The error module:\n\n"
+            f"```rust\n{self.hierarchy.rust_module()}```{listed}"
+        )
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        if (not MOD_ERROR.search(rust_code)
+                or ERRORS_CRATE in section_entries(toml_content, "[dependencies]")):
+            return toml_content
+        return add_to_section(toml_content, "[dependencies]",
+                              [f'{ERRORS_CRATE} = {{ path = "{self.crate_path}" }}'])
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        if not self._relevant(python_code):
+            return []
+        issues = []
+        if not MOD_ERROR.search(rust_code):
+            issues.append("the project's error module is not declared with `mod error;`")
+        if INLINE_MOD_ERROR.search(rust_code):
+            issues.append("the error module is defined in the code instead of "
+                          "declared with `mod error;`")
+        else:
+            defined = [name for name in ["Error", *self.hierarchy.classes]
+                       if re.search(rf"\b(enum|struct)\s+{name}\b", rust_code)]
+            if defined:
+                issues.append(f"{', '.join(defined)} {'is' if len(defined) == 1 else 'are'} "
+                              "defined again instead of used from the error module")
+        ad_hoc = AD_HOC_ERROR.search(rust_code)
+        if ad_hoc:
+            error = "Box<dyn Error>" if ad_hoc.group(1).startswith("Box") else ad_hoc.group(1)
+            issues.append(f"functions return ad-hoc {error} errors; return error::Result with the "
+                          "project's error types")
+        return issues
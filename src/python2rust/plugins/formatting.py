@@ -0,0 +1,60 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.formatting import FormatConversion
+
+TEST_NAME = "formatting_matches_python"
+STRING_LITERAL = re.compile(r'"(?:[^"\\\n]|\\.)*"')
+# Placeholders with the grouping or the types of Python format specs, which Rust rejects
+PYTHON_SPEC = re.compile(r"(?<!\{)\{[^{}:]*:[^{}]*?(?:[,_]|\d[fFdsgG%]|\.\d+[fFgG%])\}")
+
+
+class FormatConversions(Plugin):
+    """Translates the format specs of f-strings, str.format calls and % formatting, converted
+    beforehand into the Rust placeholders reproducing them, or into what a helper has to
+    reproduce where Rust's formatting cannot (repr, exponents, thousands separators, the g and %
+    types). A test compares the Rust formatting with Python's output for representative values,
+    computed with Python. Verification fails code without the test and format strings keeping
+    Python specs."""
+    name = "formatting"
+
+    def __init__(self, conversions: List[FormatConversion]):
+        self.conversions = conversions
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.conversions:
+            return None
+        listed = "\n".join(f"- {conversion.describe()}" for conversion in self.conversions)
+        return (
+            "Format the values of the module as Python does, with the Rust placeholders the "
+            "format specs below convert to. Where Rust's formatting cannot reproduce a spec, "
+            "write a small helper function returning the String Python would, and use it wherever "
+            "that spec appears rather than approximating it with format!; mind that floats "
+            f"formatted without a type print 1.0 as 1 with {{}}, unlike Python's str:\n{listed}"
+        )
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        cases = [f"- {conversion.python} of {literal}: {expected!r}"
+                 for conversion in self.conversions for literal, expected in conversion.cases]
+        if not cases:
+            return None
+        listed = "\n".join(cases)
+        return (
+            f"Add #[test] fn {TEST_NAME} asserting with `assert_eq!` that the Rust formatting, or "
+            "the helper, of each value below produces exactly the String Python's does, which "
+            f"follows it:\n{listed}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        if any(conversion.cases for conversion in self.conversions) and not re.search(
+                rf"\bfn\s+{TEST_NAME}\s*\(", rust_code):
+            issues.append("the formatting of the module has no test comparing it with Python's "
+                          f"output; add #[test] fn {TEST_NAME}")
+        kept = sorted({match.group(0) for literal in STRING_LITERAL.finditer(rust_code)
+                       for match in PYTHON_SPEC.finditer(literal.group(0))})
+        if kept:
+            issues.append(f"format strings keep Python format specs ({', '.join(kept)}); use the "
+                          "Rust placeholders or the helpers converted from them")
+        return issues
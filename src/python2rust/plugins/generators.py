@@ -0,0 +1,68 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.generators import PythonGenerator
+
+
+def _rust_name(generator: PythonGenerator) -> str:
+    name = generator.qualname.split(".")[-1]
+    return re.sub(r"(?<=[a-z0-9])(?=[A-Z])", "_", name).lower()
+
+
+class GeneratorIterators(Plugin):
+    """Translates generators into Rust iterators that stay lazy: generator functions become
+    Iterator implementations, generator expressions iterator chains, and a test per generator
+    function compares the elements it yields with those of the Python generator. Verification
+    fails generator functions returning a Vec and those left without their test."""
+    name = "generators"
+
+    def __init__(self, generators: List[PythonGenerator]):
+        self.generators = generators
+
+    def _functions(self) -> List[PythonGenerator]:
+        return [generator for generator in self.generators if generator.kind == "function"]
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.generators:
+            return None
+        listed = "\n".join(f"- {generator.describe()}" for generator in self.generators)
+        return (
+            "The module produces values lazily with these generators. Keep them lazy, producing "
+            "each element when next() asks for it, and never collect them into a Vec up front. A "
+            "generator function becomes a struct implementing Iterator, its locals fields and the "
+            "position of its yields a state enum when it has several (a function returning "
+            "`impl Iterator` built from adapters, `std::iter::from_fn` or `successors` when that "
+            "is simpler); `yield from` chains the delegated iterator, an infinite generator an "
+            "iterator that never returns None, and one receiving sent values a struct with a "
+            "`send` method. A generator expression becomes an iterator adapter chain, fed "
+            "directly to the equivalent of what consumes it (`.sum()`, `.any()`, `.collect()`), "
+            f"and returned or stored as `impl Iterator`:\n{listed}"
+        )
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        functions = self._functions()
+        if not functions:
+            return None
+        listed = "\n".join(f"- `{generator.test_name}` for {generator.qualname}"
+                           + (" (infinite: compare a prefix with `.take(n)`)"
+                              if generator.infinite else "")
+                           for generator in functions)
+        return (
+            "Test each generator function with the #[test] named below: call it with "
+            "representative arguments and compare the Rust iterator with the elements the Python "
+            "generator yields for them, written as literals, one by one and in order with "
+            "`assert_eq!` on each `next()`, then check that a finite one returns None after its "
+            f"last element:\n{listed}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        for generator in self._functions():
+            if re.search(rf"\bfn\s+{_rust_name(generator)}\b[^{{;]*->\s*Vec\s*<", rust_code):
+                issues.append(f"the generator {generator.qualname} returns a Vec, producing its "
+                              "elements eagerly; return an Iterator producing them on demand")
+            if not re.search(rf"\bfn\s+{generator.test_name}\s*\(", rust_code):
+                issues.append(f"the generator {generator.qualname} has no test comparing its "
+                              f"elements with Python's; add #[test] fn {generator.test_name}")
+        return issues
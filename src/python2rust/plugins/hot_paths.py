@@ -0,0 +1,33 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.profile import HotPath, hot_paths_summary
+
+
+class HotPaths(Plugin):
+    """Points the analysis and generation prompts at the functions of a module that took most of
+    the CPU time of the profiled workload, for their translation to be fast rather than literal."""
+    name = "hot_paths"
+
+    def __init__(self, paths: List[HotPath], command: str):
+        self.paths = paths
+        self.command = command
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        if not self.paths:
+            return None
+        return (f"Functions of this module hot when profiling `{self.command}`:\n"
+                f"{hot_paths_summary(self.paths)}")
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.paths:
+            return None
+        return (
+            "These functions took most of the CPU time of a profiled run of the Python program; "
+            "give their translation the most care for speed while keeping their results the same: "
+            "no allocation or clone in their inner loops, borrowed slices rather than owned "
+            "vectors, iterators instead of index arithmetic where bounds checks add up, integer "
+            "types as narrow as the values allow, and precomputed values "
+            "hoisted out of the loops:\n"
+            f"{hot_paths_summary(self.paths)}"
+        )
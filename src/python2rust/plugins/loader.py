@@ -0,0 +1,35 @@
+import importlib.util
+import inspect
+from pathlib import Path
+from typing import List
+
+from .base import Plugin
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def load_plugins(plugins_dir: Path) -> List[Plugin]:
+    """Instantiate every Plugin subclass defined in the Python files of a directory."""
+    if not plugins_dir.is_dir():
+        return []
+
+    plugins: List[Plugin] = []
+    for path in sorted(plugins_dir.glob("*.py")):
+        if path.name.startswith("_"):
+            continue
+
+        try:
+            spec = importlib.util.spec_from_file_location(f"python2rust_plugin_{path.stem}", path)
+            module = importlib.util.module_from_spec(spec)
+            spec.loader.exec_module(module)
+        except Exception as e:
+            logger.error(f"Failed to load plugin file {path}: {e}")
+            continue
+
+        for _, obj in inspect.getmembers(module, inspect.isclass):
+            if issubclass(obj, Plugin) and obj is not Plugin and obj.__module__ == module.__name__:
+                plugins.append(obj())
+                logger.info(f"Loaded plugin {obj.name} from {path}")
+
+    return plugins
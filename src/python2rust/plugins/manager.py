@@ -0,0 +1,94 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+class PluginManager:
+    """Dispatches pipeline hooks to all registered plugins."""
+
+    def __init__(self, plugins: Optional[List[Plugin]] = None):
+        self.plugins = list(plugins or [])
+
+    def register(self, plugin: Plugin) -> None:
+        self.plugins.append(plugin)
+
+    def crate_mappings(self) -> Dict[str, str]:
+        """Merge crate mappings, later plugins overriding earlier ones."""
+        mappings: Dict[str, str] = {}
+        for plugin in self.plugins:
+            try:
+                mappings.update(plugin.crate_mappings())
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} crate_mappings failed: {e}")
+        return mappings
+
+    def _format_mappings(self) -> List[str]:
+        mappings = self.crate_mappings()
+        if not mappings:
+            return []
+        lines = ["Use these Rust crates for the following Python libraries:"]
+        lines.extend(f"- {python_lib} -> {crate}" for python_lib, crate in mappings.items())
+        return ["\n".join(lines)]
+
+    def analysis_context(self, python_code: str) -> str:
+        """Collect extra analysis prompt context from plugins."""
+        sections = self._format_mappings()
+        for plugin in self.plugins:
+            try:
+                context = plugin.analysis_context(python_code)
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} analysis_context failed: {e}")
+                continue
+            if context:
+                sections.append(context)
+        return "\n\n".join(sections)
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> str:
+        """Collect extra generation prompt context from plugins."""
+        sections = self._format_mappings()
+        for plugin in self.plugins:
+            try:
+                context = plugin.generation_context(python_code, analysis)
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} generation_context failed: {e}")
+                continue
+            if context:
+                sections.append(context)
+        return "\n\n".join(sections)
+
+    def tests_context(self, python_code: str) -> str:
+        """Collect the Python tests plugins give to translate."""
+        sections = []
+        for plugin in self.plugins:
+            try:
+                context = plugin.tests_context(python_code)
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} tests_context failed: {e}")
+                continue
+            if context:
+                sections.append(context)
+        return "\n\n".join(sections)
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        """Apply the Cargo.toml edits of all plugins, in order."""
+        for plugin in self.plugins:
+            try:
+                toml_content = plugin.cargo_manifest(rust_code, toml_content)
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} cargo_manifest failed: {e}")
+        return toml_content
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        """Run plugin checkers and return all reported issues, prefixed by plugin name."""
+        issues: List[str] = []
+        for plugin in self.plugins:
+            try:
+                found = plugin.verify(python_code, rust_code, toml_content or "")
+            except Exception as e:
+                logger.error(f"Plugin {plugin.name} verify failed: {e}")
+                continue
+            issues.extend(f"[{plugin.name}] {issue}" for issue in found)
+        return issues
@@ -0,0 +1,49 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.naming import Renaming
+
+DECLARATIONS = {
+    "function": r"fn", "method": r"fn", "class": r"struct|enum|trait|type",
+    "constant": r"const|static", "parameter": r"[(,]\s*(?:mut\s+)?",
+    "variable": r"let\s+(?:mut\s+)?",
+}
+
+
+class IdentifierNames(Plugin):
+    """Gives the Python identifiers of the module their idiomatic Rust names, converted
+    beforehand the same way in every module: snake_case functions and variables, CamelCase
+    types, SCREAMING_SNAKE_CASE constants, private names without their underscore, and
+    keywords escaped as raw identifiers or with a trailing underscore. The table is recorded
+    in the source map. Verification fails code declaring the Python names instead, but for those
+    kept because their Rust name was taken."""
+    name = "naming"
+
+    def __init__(self, renames: List[Renaming]):
+        self.renames = renames
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.renames:
+            return None
+        listed = "\n".join(f"- {renaming.describe()}" for renaming in self.renames)
+        return (
+            "Name the Rust items after the Python identifiers, converted to Rust's conventions. "
+            "These identifiers change, use exactly the Rust names given, "
+            "wherever they appear:\n" + listed
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        kept = []
+        for renaming in self.renames:
+            if renaming.reason in ("keyword", "collision"):
+                continue  # the compiler rejects the keyword unescaped, a collision keeps its name
+            declaration = DECLARATIONS[renaming.kind]
+            separator = r"\s*:" if renaming.kind == "parameter" else r"\b"
+            if re.search(rf"(?:{declaration})\s*\b{re.escape(renaming.python)}{separator}",
+                         rust_code):
+                kept.append(f"{renaming.python} -> {renaming.rust}")
+        if not kept:
+            return []
+        return ["Rust declarations keep Python names instead of their Rust "
+                f"ones: {', '.join(kept)}"]
@@ -0,0 +1,52 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.native import NativeImport
+
+# Rust code reaching native code rather than reimplementing it
+FFI_PATTERN = re.compile(r'extern\s+"C"|#\[link\(|unimplemented!')
+
+
+class NativeDependencies(Plugin):
+    """Keeps the model from reimplementing in plain Rust what the native libraries behind a
+    module's imports do: the prompts ask for their replacement crates or FFI bindings, and
+    code using neither fails verification."""
+    name = "native"
+
+    def __init__(self, imports: List[NativeImport]):
+        self.imports = imports
+
+    def _listed(self) -> str:
+        return "\n".join(f"- {native.describe()}" for native in self.imports)
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        if not self.imports:
+            return None
+        return ("Imports backed by compiled code, whose behavior is not in this module:\n"
+                f"{self._listed()}")
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.imports:
+            return None
+        return (
+            "What these imports do is implemented in native libraries, not in the Python code. Do "
+            "not write your own version of it: call the named crate, or declare extern \"C\" "
+            "bindings (or depend on a -sys crate) to the library, and where neither can work keep "
+            f"an unimplemented!() naming the missing library:\n{self._listed()}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        if (FFI_PATTERN.search(rust_code)
+                or re.search(r"^[\w-]+-sys\s*=", toml_content, re.MULTILINE)):
+            return []
+        issues = []
+        for native in self.imports:
+            if not native.crate:
+                issues.append(f"{native.module} is backed by {native.library}, but the Rust code "
+                              "does not bind it through FFI")
+            elif not re.search(rf"^{re.escape(native.crate)}\s*=", toml_content, re.MULTILINE):
+                issues.append(f"{native.module} is backed by {native.library}, but the Rust code "
+                              f"neither depends on the {native.crate} crate nor binds "
+                              "the library through FFI")
+        return issues
@@ -0,0 +1,26 @@
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.http_routes import find_web_app
+
+
+class OpenApiAnnotations(Plugin):
+    """Asks the generation prompt of web apps for utoipa annotations and a served OpenAPI document,
+    which the OpenAPI comparison diffs with the Python app's."""
+    name = "openapi"
+
+    def __init__(self, path: str = "/openapi.json"):
+        self.path = path
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if find_web_app(python_code) is None:
+            return None
+        return (
+            "Document the API of the Rust service with utoipa, as the Python app's OpenAPI "
+            "description is compared with it: annotate each handler with `#[utoipa::path(...)]` "
+            "giving its method, path, path and query params, request_body and responses (status "
+            "and body type), derive `utoipa::ToSchema` for request and response bodies and "
+            "`utoipa::IntoParams` for query parameter structs, keep the field names, types and "
+            "optionality of the Python models, and serve the `#[derive(utoipa::OpenApi)]` "
+            f"document of all handlers as JSON at GET {self.path}. Add utoipa to Cargo.toml."
+        )
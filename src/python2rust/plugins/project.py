@@ -0,0 +1,14 @@
+from typing import Dict
+
+from .base import Plugin
+
+
+class ProjectCrates(Plugin):
+    """Crate mappings from the [crates] table of python2rust.toml."""
+    name = "project"
+
+    def __init__(self, crates: Dict[str, str]):
+        self.crates = dict(crates)
+
+    def crate_mappings(self) -> Dict[str, str]:
+        return self.crates
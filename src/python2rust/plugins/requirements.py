@@ -0,0 +1,53 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.requirements import CrateDependency
+from ..utils.cargo_manifest import add_to_section, replace_entries, section_entries
+
+
+class RequirementCrates(Plugin):
+    """Pins the crates replacing the libraries a module imports, resolved from the project's
+    requirements through the crate mapping database and the [crates] overrides, so that each
+    Cargo.toml declares them the same way whatever the model wrote: their lines replace those
+    the model gave, and are added for the crates the code uses without declaring them.
+    Verification fails manifests declaring another crate of the category instead."""
+    name = "requirements"
+
+    def __init__(self, dependencies: List[CrateDependency]):
+        self.dependencies = dependencies
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.dependencies:
+            return None
+        listed = "\n".join(f"- {dependency.describe()}" for dependency in self.dependencies)
+        return (
+            "The libraries the module imports are replaced by these crates: where the Rust code "
+            "needs a crate for one of them, use this one rather than another, declared in "
+            f"[dependencies] exactly as written:\n{listed}"
+        )
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        declared = section_entries(toml_content, "[dependencies]")
+        pinned = {dependency.crate: dependency.line for dependency in self.dependencies
+                  if dependency.crate in declared}
+        toml_content = replace_entries(toml_content, "[dependencies]", pinned)
+        used = [dependency.line for dependency in self.dependencies
+                if dependency.crate not in declared
+                and re.search(rf"\b{dependency.identifier}(::|!)", rust_code)]
+        return add_to_section(toml_content, "[dependencies]", list(dict.fromkeys(used)))
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        declared = section_entries(toml_content, "[dependencies]")
+        mapped = {dependency.crate for dependency in self.dependencies}
+        issues = []
+        for dependency in self.dependencies:
+            if dependency.crate in declared:
+                continue
+            instead = [crate for crate in dependency.alternatives
+                       if crate in declared and crate not in mapped]
+            if instead:
+                issues.append(f"{dependency.python} is replaced by {dependency.crate}, but "
+                              f"Cargo.toml declares {', '.join(instead)} instead; "
+                              f"use {dependency.line}")
+        return issues
@@ -0,0 +1,34 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers import Definition
+
+
+class RelevantDefinitions(Plugin):
+    """Feeds the project definitions most similar to a module into its prompts."""
+    name = "retrieval"
+
+    def __init__(self, definitions: List[Definition]):
+        self.definitions = definitions
+
+    def _sections(self) -> List[str]:
+        return [
+            f"`{definition.module}.{definition.name}` ({definition.language}):\n"
+            f"```{definition.language}\n{definition.code}\n```"
+            for definition in self.definitions
+        ]
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        if not self.definitions:
+            return None
+        return "Related definitions elsewhere in the project:\n\n" + "\n\n".join(self._sections())
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.definitions:
+            return None
+        return (
+            "These definitions from other modules of the project are likely referenced by this "
+            "code. Refer to the Rust versions as they are written instead of redefining them, and "
+            "keep types and signatures consistent with the Python ones:\n\n"
+            + "\n\n".join(self._sections())
+        )
@@ -0,0 +1,32 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..runs import Review, ReviewDecision
+
+
+class ReviewFeedback(Plugin):
+    """Feeds reviewer comments and "keep Python" decisions into the generation prompt."""
+    name = "review"
+
+    def __init__(self, reviews: List[Review]):
+        self.reviews = reviews
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        lines = []
+        for review in self.reviews:
+            target = f"`{review.item}`" if review.item else "this file"
+            if review.decision == ReviewDecision.REJECTED:
+                comment = review.comment or "no comment given"
+                lines.append("- A reviewer rejected the previous translation "
+                             f"of {target}: {comment}")
+            elif review.decision == ReviewDecision.ACCEPTED and review.item and review.rust_snippet:
+                lines.append(
+                    f"- A reviewer accepted the translation of {target}, reuse it unchanged:\n"
+                    f"```rust\n{review.rust_snippet}\n```")
+            elif review.decision == ReviewDecision.KEEP_PYTHON and review.item:
+                lines.append(
+                    f"- Do not translate {target}, it stays in Python. Keep its signature and "
+                    f"use `unimplemented!(\"kept in Python: {review.item}\")` as its body.")
+        if not lines:
+            return None
+        return "Code review feedback on earlier translations:\n" + "\n".join(lines)
@@ -0,0 +1,37 @@
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.risk import RiskFinding
+
+
+class TranslationRisks(Plugin):
+    """Warns the analysis and generation prompts of the dynamic constructs of a module, which
+    have no mechanical Rust translation."""
+    name = "risks"
+
+    def __init__(self, findings: List[RiskFinding]):
+        self.findings = findings
+
+    def _listed(self) -> str:
+        return "\n".join(
+            f"- line {finding.line} [{finding.severity}] {finding.construct}: {finding.message}"
+            for finding in self.findings)
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        if not self.findings:
+            return None
+        return f"Dynamic constructs found in this module:\n{self._listed()}"
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.findings:
+            return None
+        return (
+            "This module uses constructs with no direct Rust equivalent. Translate what each one "
+            "does in this program rather than the mechanism: `eval`/`exec` and runtime-built "
+            "classes become the code they produce, or an enum or parser over the inputs they "
+            "accept; `getattr`/`setattr`, `__getattr__` and `__dict__` a match on the names used "
+            "or a HashMap; monkeypatched functions a trait or function parameter whose "
+            "implementation is chosen at startup; metaclasses and reflection explicit code or a "
+            "macro_rules! macro. Comment in the Rust code where the behavior differs:\n"
+            f"{self._listed()}"
+        )
@@ -0,0 +1,53 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.module_state import STRATEGIES, ModuleState, state_strategy
+
+STATIC_MUT = re.compile(r"\bstatic\s+mut\b")
+ONCE_STATIC = re.compile(r"\b(OnceLock|LazyLock|OnceCell|Lazy)\s*<|\blazy_static!")
+APP_STATE = re.compile(r"\bstruct\s+AppState\b")
+SHARED_STATIC = re.compile(r"\bstatic\s+\w+\s*:\s*[\w:]*(OnceLock|LazyLock|OnceCell|Lazy|Mutex|"
+                           r"RwLock)\b")
+
+
+class GlobalState(Plugin):
+    """Has the module-level state of a module held the way the project chose, instead of the way
+    each generation comes up with: the prompt names the strategy of each global and singleton,
+    and verification fails code using another one or `static mut`."""
+    name = "state"
+
+    def __init__(self, states: List[ModuleState], strategy: str):
+        self.states = states
+        self.strategy = strategy
+
+    def _strategies(self) -> List[str]:
+        return [state_strategy(state, self.strategy) for state in self.states]
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.states:
+            return None
+        listed = "\n".join(f"- {state.describe()}: {STRATEGIES[strategy]}"
+                           for state, strategy in zip(self.states, self._strategies()))
+        return (
+            f"The project holds module-level state with the {self.strategy} strategy, the same way "
+            "in every module. Translate each global and singleton below as said, without "
+            f"`static mut`, thread locals or another scheme of your own:\n{listed}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        if not self.states:
+            return []
+        strategies = set(self._strategies())
+        issues = []
+        if STATIC_MUT.search(rust_code):
+            issues.append("module-level state is held in a `static mut`; use the project's "
+                          f"{self.strategy} strategy")
+        if "app_state" in strategies and not APP_STATE.search(rust_code):
+            issues.append("the mutable module-level state is not gathered in an `AppState` struct")
+        if "once_lock" in strategies and not ONCE_STATIC.search(rust_code):
+            issues.append("the module's singletons are not statics initialized once with "
+                          "OnceLock or LazyLock")
+        if strategies == {"injection"} and SHARED_STATIC.search(rust_code):
+            issues.append("module-level state is held in a static; pass it as a parameter instead")
+        return issues
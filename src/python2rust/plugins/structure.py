@@ -0,0 +1,33 @@
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.python_structure import analyze_structure, structure_summary
+
+
+class PythonStructure(Plugin):
+    """Feeds what the AST of a module says into its prompts: imports, classes, the signatures
+    to keep, and the lines using generators, exceptions, context managers and other features
+    the translation has to map."""
+    name = "structure"
+
+    def _summary(self, python_code: str) -> Optional[str]:
+        structure = analyze_structure(python_code)
+        if structure is None:
+            return None
+        return structure_summary(structure) or None
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        summary = self._summary(python_code)
+        if summary is None:
+            return None
+        return f"Structure of the module, from its syntax tree:\n\n{summary}"
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        summary = self._summary(python_code)
+        if summary is None:
+            return None
+        return (
+            "Structure of the module, from its syntax tree. Translate every function and method "
+            "listed, keeping their names in snake_case, their parameters and defaults, and map "
+            f"each feature at the lines given:\n\n{summary}"
+        )
@@ -0,0 +1,85 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.templates import ENGINES, PythonTemplate
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+
+class HtmlTemplates(Plugin):
+    """Renders the Jinja templates of the module, inline or files of its templates directory,
+    with the project's template engine, askama, tera or maud. The templates are written in the
+    engine's syntax into the templates/ directory of the crate beforehand, and the prompt gives
+    how the engine renders each, its dependency is added to Cargo.toml when the code uses it
+    undeclared, and verification fails code building HTML by string substitution or leaving a
+    template out."""
+    name = "templates"
+
+    def __init__(self, engine: str, templates: List[PythonTemplate]):
+        self.engine = ENGINES[engine]
+        self.templates = templates
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        engine = self.engine
+        files = ", ".join(f'("{name}", include_str!("../templates/{name}"))'
+                          for name in self._files())
+        if engine.files:
+            listed = "\n".join(
+                f"- {template.describe()}: templates/{template.name}, rendered with "
+                + engine.render.format(file=template.name, struct=template.struct, files=files)
+                for template in self.templates)
+            where = ("The templates are written in the crate's templates/ directory, "
+                     "render them from there:")
+        else:
+            listed = "\n".join(self._markup(template) for template in self.templates)
+            where = "Write each template as markup in the Rust code:"
+        return (
+            f"Render the HTML of the module with {engine.name}, and no other "
+            f"template engine. {where}\n"
+            f"{listed}\nDo not build HTML with format! or str::replace, and do not keep the "
+            f"templates as string constants. Declare `{engine.dependency}` in [dependencies]."
+        )
+
+    def _files(self) -> List[str]:
+        """The template files of the crate: those the module renders and those they
+        extend or include."""
+        return list(dict.fromkeys(name for template in self.templates
+                                  for name in [template.name, *template.files]))
+
+    def _markup(self, template: PythonTemplate) -> str:
+        """A template maud writes as markup, with the Jinja sources of it and of the templates it
+        extends or includes, whose markup it takes in."""
+        render = self.engine.render.format(file=template.name, struct=template.struct, files="")
+        sources = [f"- {template.describe()}: {render}, converted from:\n"
+                   f"```jinja\n{template.source.strip()}\n```"]
+        sources.extend(f"  extending or including {name}:\n```jinja\n{source.strip()}\n```"
+                       for name, source in template.files.items())
+        return "\n".join(sources)
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        name = self.engine.name
+        if name in section_entries(toml_content, "[dependencies]") or not re.search(
+                rf"\b{name}::", rust_code):
+            return toml_content
+        return add_to_section(toml_content, "[dependencies]", [self.engine.dependency])
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        if re.search(r"\.replace\(\s*\"\{", rust_code):
+            issues.append("HTML is built by replacing placeholders in strings instead of rendering "
+                          f"templates with {self.engine.name}")
+        kept = [template.constant for template in self.templates if template.constant
+                and re.search(rf"\b(?:const|static)\s+{template.constant}\b", rust_code)]
+        if kept:
+            issues.append(f"inline templates are kept as string constants: {', '.join(kept)}")
+        if self.engine.name == "askama":
+            missing = [template.name for template in self.templates if not re.search(
+                rf'#\[template\(\s*path\s*=\s*"{re.escape(template.name)}"', rust_code)]
+        elif self.engine.name == "tera":
+            missing = [name for name in self._files() if f'"{name}"' not in rust_code]
+        else:
+            missing = [] if "html!" in rust_code else [template.name for template in self.templates]
+        if missing:
+            issues.append(f"templates are not rendered with {self.engine.name}: "
+                          f"{', '.join(missing)}")
+        return issues
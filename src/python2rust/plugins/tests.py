@@ -0,0 +1,61 @@
+from typing import Dict, Optional
+
+from .base import Plugin
+from ..analyzers.python_tests import uses_hypothesis
+from ..analyzers.token_estimate import count_tokens
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+HYPOTHESIS_GUIDE = (
+    "Translate the hypothesis property tests into `proptest!` tests with the same strategies "
+    "and invariants, declaring `proptest = \"1\"` under [dev-dependencies]: `st.integers(a, b)` "
+    "becomes `a..=b` (`any::<i64>()` when unbounded), `st.floats` a float range or `any::<f64>()`, "
+    "`st.booleans()` `any::<bool>()`, `st.text()` `any::<String>()` or a regex strategy, "
+    "`st.lists(s, min_size=m, max_size=n)` `prop::collection::vec(s, m..=n)`, `st.tuples` a tuple "
+    "of strategies, `st.sampled_from(xs)` `prop::sample::select(xs)`, `st.one_of` `prop_oneof!`, "
+    "`.map`/`.filter` `prop_map`/`prop_filter`, `assume(c)` `prop_assume!(c)`, each `@example` a "
+    "plain `#[test]`, and `@settings(max_examples=n)` `ProptestConfig::with_cases(n)`. Keep each "
+    "property's assertions as `prop_assert!` and `prop_assert_eq!`."
+)
+
+
+class PythonTests(Plugin):
+    """Feeds the project's pytest tests of a module into the prompt writing its Rust tests.
+
+    Files are given in order while they fit in max_tokens; conftest.py files come
+    first, as the fixtures the tests use.
+    """
+    name = "pytest"
+
+    def __init__(self, tests: Dict[str, str], max_tokens: int):
+        # Path relative to the project -> test code
+        self.tests = tests
+        self.max_tokens = max_tokens
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        sections = []
+        hypothesis = False
+        remaining = self.max_tokens
+        for path, code in self.tests.items():
+            tokens = count_tokens(code)
+            if tokens > remaining:
+                logger.info(f"Leaving {path} out of the tests prompt: {tokens} "
+                            f"tokens, {remaining} left")
+                continue
+            remaining -= tokens
+            hypothesis = hypothesis or uses_hypothesis(code)
+            sections.append(f"`{path}`:\n```python\n{code.strip()}\n```")
+        if not sections:
+            return None
+        return (
+            "The project tests this code with the pytest files below. Translate them into the "
+            "crate's `#[cfg(test)] mod tests` module, keeping their names and what they assert: "
+            "fixtures become helper functions the tests call, each `@pytest.mark.parametrize` case "
+            "a test of its own or a row of a table the test loops over, `assert` becomes `assert!` "
+            "or `assert_eq!`, and `pytest.raises` a check that the call returns `Err`, or "
+            "`#[should_panic]` when it panics. Leave out tests that only make sense in Python, "
+            "such as monkeypatching, with a comment saying why."
+            + (f" {HYPOTHESIS_GUIDE}" if hypothesis else "") + "\n\n"
+            + "\n\n".join(sections)
+        )
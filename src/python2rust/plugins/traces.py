@@ -0,0 +1,23 @@
+from typing import List, Optional
+
+from .base import Plugin
+from ..builders.call_tracer import TracedCall
+
+
+class CallTraces(Plugin):
+    """Feeds calls recorded while the Python program ran into the prompt writing its Rust tests."""
+    name = "traces"
+
+    def __init__(self, calls: List[TracedCall], command: str):
+        self.calls = calls
+        self.command = command
+
+    def tests_context(self, python_code: str) -> Optional[str]:
+        if not self.calls:
+            return None
+        return (
+            f"These calls of the Python functions were recorded while running `{self.command}`. "
+            "Write a regression test for each function asserting that the Rust translation returns "
+            "the same result for the same arguments, or fails where the Python call raised:\n"
+            + "\n".join(f"- {call.describe()}" for call in self.calls)
+        )
@@ -0,0 +1,34 @@
+from typing import Any, Dict, Optional
+
+from .base import Plugin
+from ..analyzers.inferred_types import FunctionTypes, types_summary
+
+
+class InferredTypes(Plugin):
+    """Feeds the types a type checker inferred for a module's functions into its prompts, for the
+    Rust types to follow them rather than guesses."""
+    name = "types"
+
+    def __init__(self, types: Dict[str, FunctionTypes], checker: str):
+        # Function qualname -> its signature and variable types
+        self.types = types
+        self.checker = checker
+
+    def analysis_context(self, python_code: str) -> Optional[str]:
+        if not self.types:
+            return None
+        return (f"Types {self.checker} inferred for this module's functions:\n\n"
+                f"{types_summary(self.types)}")
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        if not self.types:
+            return None
+        return (
+            f"Choose the Rust types from those {self.checker} inferred for this module below: "
+            "`X | None` and `Optional[X]` become `Option<X>`, `list[X]` `Vec<X>`, `dict[K, V]` "
+            "`HashMap<K, V>`, `set[X]` `HashSet<X>`, `tuple[...]` tuples, `float` `f64`, `str` "
+            "`String` or `&str`, `bytes` `Vec<u8>`. Python ints have no width: use i64, usize for "
+            "lengths and indexes, and i128 or a big integer type where the values can exceed 64 "
+            "bits. Parameters that are never `None` are not `Option`.\n\n"
+            f"{types_summary(self.types)}"
+        )
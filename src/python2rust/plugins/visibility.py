@@ -0,0 +1,48 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.visibility import ModuleVisibility
+
+KINDS = {"function": "fn", "class": "struct|enum|trait|type", "constant": "const|static"}
+
+
+def _declarations(rust_code: str, kind: str, name: str) -> List[str]:
+    """The visibility (pub, pub(crate), or empty) of the top-level declarations of an item."""
+    pattern = (rf"(?m)^(pub(?:\([^)]*\))?\s+)?(?:async\s+|unsafe\s+)*(?:{KINDS[kind]})"
+               rf"\s+{re.escape(name)}\b")
+    return [(match.group(1) or "").strip() for match in re.finditer(pattern, rust_code)]
+
+
+class ItemVisibility(Plugin):
+    """Keeps the visibility of the module's items: `pub` for the names of __all__, or without a
+    leading underscore, private for the others, so that the crate's API is the module's.
+    Verification fails public items declared without `pub` and private ones declared `pub`."""
+    name = "visibility"
+
+    def __init__(self, visibility: ModuleVisibility):
+        self.visibility = visibility
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        return (
+            "Mirror the visibility of the module's top-level items: declare the public ones "
+            "`pub`, the private ones without `pub` and named without their leading underscore. "
+            f"Items you add that Python does not have stay private:\n- {self.visibility.describe()}"
+        )
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        public = {self.visibility.rust_name(name) for name in self.visibility.public}
+        for name, kind in self.visibility.items.items():
+            rust = self.visibility.rust_name(name)
+            if name not in self.visibility.public and rust in public:
+                continue  # _helper beside a public helper
+            declared = _declarations(rust_code, kind, rust)
+            if not declared:
+                continue
+            if name in self.visibility.public and "pub" not in declared:
+                issues.append(f"{name} is public in Python, but {rust} is not declared pub")
+            elif name not in self.visibility.public and "pub" in declared:
+                issues.append(f"{name} is private in Python, but {rust} is declared pub; "
+                              "leave out pub or use pub(crate)")
+        return issues
@@ -0,0 +1,58 @@
+import re
+from typing import Any, Dict, List, Optional
+
+from .base import Plugin
+from ..analyzers.http_routes import WebApp
+from ..analyzers.web_framework import FRAMEWORKS
+from ..utils.cargo_manifest import add_to_section, section_entries
+
+
+class WebFramework(Plugin):
+    """Generates the service of a Flask or FastAPI app against the project's web framework, axum,
+    actix-web or rocket: the prompt registers each route of the app with its path in the
+    framework's syntax and gives the framework's extractors, shared state and server idioms, its
+    crates are added to Cargo.toml when the code uses them undeclared, and verification fails
+    code using another framework or leaving routes out."""
+    name = "web_framework"
+
+    def __init__(self, framework: str, app: WebApp):
+        self.framework = FRAMEWORKS[framework]
+        self.app = app
+
+    def generation_context(self, python_code: str, analysis: Dict[str, Any]) -> Optional[str]:
+        framework = self.framework
+        routes = "\n".join(f"- {' '.join(route.methods)} {route.path} ({route.function}): "
+                           f"{framework.registration(route)}" for route in self.app.routes)
+        declared = ", ".join(f"`{line}`" for line in framework.dependencies())
+        return (
+            f"Build the service of `{self.app.name}` with {framework.name}, and no other web "
+            "framework. Register each route of the app with its path in "
+            f"{framework.name}'s syntax:\n{routes}\n"
+            f"Handlers take {framework.extractors}; {framework.state}. The server is "
+            f"{framework.server}, listening on the port of the PORT environment variable, and on "
+            f"the host of HOST when it is set. Declare {declared} in [dependencies]."
+        )
+
+    def cargo_manifest(self, rust_code: str, toml_content: str) -> str:
+        declared = section_entries(toml_content, "[dependencies]")
+        missing = []
+        for line in self.framework.dependencies():
+            crate = line.split("=", 1)[0].strip()
+            if crate not in declared and re.search(rf"\b{crate.replace('-', '_')}::", rust_code):
+                missing.append(line)
+        return add_to_section(toml_content, "[dependencies]", missing)
+
+    def verify(self, python_code: str, rust_code: str, toml_content: str) -> List[str]:
+        issues = []
+        declared = section_entries(toml_content, "[dependencies]")
+        for other in FRAMEWORKS.values():
+            if other.name != self.framework.name and (
+                    other.name in declared or re.search(rf"\b{other.identifier}::", rust_code)):
+                issues.append(f"the service uses {other.name} instead of {self.framework.name}")
+        missing = [f"{'/'.join(route.methods)} {route.path}" for route in self.app.routes
+                   if f'"{self.framework.path(route.path)}"' not in rust_code]
+        if missing:
+            issues.append("routes of the app are not registered with their "
+                          f"{self.framework.name} paths: "
+                          + ", ".join(missing))
+        return issues
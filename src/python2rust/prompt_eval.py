@@ -0,0 +1,152 @@
+"""
+A/B evaluation of prompt sets: migrate the same corpus with each and compare the outcomes.
+"""
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+from .config.pricing import load_pricing
+from .migrator import Migrator
+from .prompts.loader import prompt_set_version
+from .report import MigrationReport
+from .runs import RunBrowser, RunStore
+from .utils.logging import setup_logger
+
+logger = setup_logger()
+
+# Name of the packaged templates when comparing prompt sets
+DEFAULT_PROMPT_SET = "default"
+
+
+@dataclass
+class PromptSetResult:
+    """How one prompt set did on the corpus."""
+    name: str
+    version: str
+    target_dir: Path
+    run_id: Optional[str] = None
+    files: int = 0
+    succeeded: int = 0
+    iterations: List[int] = field(default_factory=list)  # fix iterations of each migrated file
+    tokens: int = 0
+    cost: Optional[float] = 0.0  # None when a model has no known price
+
+    @property
+    def success_rate(self) -> float:
+        return self.succeeded / self.files if self.files else 0.0
+
+    @property
+    def mean_iterations(self) -> float:
+        return sum(self.iterations) / len(self.iterations) if self.iterations else 0.0
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "name": self.name,
+            "version": self.version,
+            "target_dir": str(self.target_dir),
+            "run_id": self.run_id,
+            "files": self.files,
+            "succeeded": self.succeeded,
+            "success_rate": self.success_rate,
+            "mean_iterations": self.mean_iterations,
+            "tokens": self.tokens,
+            "cost": self.cost
+        }
+
+
+@dataclass
+class PromptComparison:
+    """Outcomes of a baseline and a candidate prompt set on the same corpus."""
+    corpus: Path
+    baseline: PromptSetResult
+    candidate: PromptSetResult
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "corpus": str(self.corpus),
+            "baseline": self.baseline.to_dict(),
+            "candidate": self.candidate.to_dict()
+        }
+
+    def format(self) -> str:
+        """Side by side table of both prompt sets."""
+        def cost(result: PromptSetResult) -> str:
+            return f"${result.cost:.4f}" if result.cost is not None else "unknown"
+
+        a, b = self.baseline, self.candidate
+        rows = [
+            ("version", a.version, b.version),
+            ("success rate", f"{a.success_rate:.0%} ({a.succeeded}/{a.files})",
+             f"{b.success_rate:.0%} ({b.succeeded}/{b.files})"),
+            ("mean iterations", f"{a.mean_iterations:.2f}", f"{b.mean_iterations:.2f}"),
+            ("tokens", f"{a.tokens:,}", f"{b.tokens:,}"),
+            ("cost", cost(a), cost(b)),
+        ]
+        header = ("metric", a.name, b.name)
+        widths = [max(len(row[i]) for row in [header, *rows]) for i in range(len(header))]
+        lines = ["  ".join(cell.ljust(width) for cell, width in zip(row, widths)).rstrip()
+                 for row in [header, *rows]]
+        lines.insert(1, "  ".join("-" * width for width in widths))
+        return "\n".join(lines)
+
+
+def prompts_dir_of(prompt_set: str) -> Optional[Path]:
+    """The prompts directory of a prompt set argument, None for the packaged templates."""
+    return None if prompt_set == DEFAULT_PROMPT_SET else Path(prompt_set)
+
+
+def summarize(name: str, prompts_dir: Optional[Path], report: MigrationReport) -> PromptSetResult:
+    """Success, iterations and spending of a finished run."""
+    result = PromptSetResult(
+        name=name,
+        version=report.prompt_version or prompt_set_version(prompts_dir),
+        target_dir=report.target_dir,
+        run_id=report.run_id,
+        files=len(report.files),
+        succeeded=len(report.succeeded),
+        iterations=[f.metrics["iterations"] for f in report.files if "iterations" in f.metrics],
+        tokens=sum(f.tokens_used for f in report.files)
+    )
+    if report.run_id is not None:
+        store = RunStore(report.target_dir)
+        usage = RunBrowser(store, store.load(report.run_id), load_pricing()).total_usage()
+        result.tokens = usage.total_tokens or result.tokens
+        result.cost = usage.cost
+    return result
+
+
+async def evaluate_prompt_set(
+    name: str,
+    corpus: Path,
+    target_dir: Path,
+    make_migrator: Callable[[], Migrator] = Migrator
+) -> PromptSetResult:
+    """Migrate the corpus from scratch with one prompt set.
+
+    name is a prompts directory or "default"; make_migrator returns a Migrator with the
+    tokens, models and checkers both prompt sets share.
+    """
+    prompts_dir = prompts_dir_of(name)
+    migrator = make_migrator().source_dir(corpus).target_dir(target_dir).incremental(False)
+    migrator.prompts_dir(prompts_dir)
+    logger.info(f"Evaluating prompt set {name} on {corpus}")
+    return summarize(name, prompts_dir, await migrator.migrate())
+
+
+async def compare_prompt_sets(
+    baseline: str,
+    candidate: str,
+    corpus: Path,
+    output_dir: Path,
+    make_migrator: Callable[[], Migrator] = Migrator
+) -> PromptComparison:
+    """Migrate the corpus with both prompt sets, one after the other, into output_dir/baseline
+    and output_dir/candidate."""
+    output_dir = Path(output_dir)
+    return PromptComparison(
+        corpus=Path(corpus),
+        baseline=await evaluate_prompt_set(baseline, corpus,
+                                           output_dir / "baseline", make_migrator),
+        candidate=await evaluate_prompt_set(candidate, corpus,
+                                            output_dir / "candidate", make_migrator)
+    )
@@ -3,10 +3,15 @@ from .analysis_prompts import ANALYSIS_PROMPT
 from .generation_prompts import GENERATION_PROMPT
 from .verification_prompts import VERIFICATION_PROMPT
 from .fix_prompts import FIX_PROMPT
+from .pass_prompts import SPEC_PROMPT, SKELETON_PROMPT, IMPLEMENTATION_PROMPT, TESTS_PROMPT
 
 __all__ = [
     'ANALYSIS_PROMPT',
     'GENERATION_PROMPT',
     'VERIFICATION_PROMPT',
-    'FIX_PROMPT'
+    'FIX_PROMPT',
+    'SPEC_PROMPT',
+    'SKELETON_PROMPT',
+    'IMPLEMENTATION_PROMPT',
+    'TESTS_PROMPT'
 ]
\ No newline at end of file
@@ -0,0 +1,113 @@
+"""Prompt templates, read from the package or from a project's prompts directory."""
+import hashlib
+import string
+from functools import lru_cache
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from langchain.prompts import PromptTemplate
+
+TEMPLATES_DIR = Path(__file__).parent / "templates"
+
+# Every template, with the variables it may use; system messages take none
+PROMPT_VARIABLES: Dict[str, List[str]] = {
+    "analysis_system": [],
+    "analysis": ["python_code", "project_context"],
+    "generation_system": [],
+    "generation": ["python_code", "analysis", "project_context"],
+    "verification": ["python_code", "rust_code", "analysis", "migration_specs"],
+    "fix_system": [],
+    "fix": ["rust_code", "toml_content", "verification_result", "analysis"],
+    "spec": ["python_code", "analysis", "project_context"],
+    "skeleton": ["python_code", "spec", "project_context"],
+    "implementation": ["python_code", "spec", "skeleton", "toml_content", "project_context"],
+    "tests": ["python_code", "spec", "rust_code", "python_tests"],
+}
+
+
+def template_variables(text: str) -> List[str]:
+    """Variables of a template in {variable} syntax, in order of appearance; {{ and }}
+    are literal braces."""
+    variables = []
+    for _, field, _, _ in string.Formatter().parse(text):
+        if field is None:
+            continue
+        name = field.split(".")[0].split("[")[0]
+        if name not in variables:
+            variables.append(name)
+    return variables
+
+
+def template_path(name: str, prompts_dir: Optional[Path] = None) -> Path:
+    """The override of a template in prompts_dir when there is one, else the packaged template."""
+    if prompts_dir is not None and (Path(prompts_dir) / f"{name}.txt").is_file():
+        return Path(prompts_dir) / f"{name}.txt"
+    return TEMPLATES_DIR / f"{name}.txt"
+
+
+def load_template(name: str, prompts_dir: Optional[Path] = None) -> PromptTemplate:
+    """Read and check a template; unknown variables or unbalanced braces raise ValueError."""
+    path = template_path(name, prompts_dir)
+    text = path.read_text(encoding="utf-8")
+    try:
+        variables = template_variables(text)
+    except ValueError as e:
+        raise ValueError(f"Invalid prompt template {path}: {e} (write {{{{ and "
+                         f"}}}} for literal braces)")
+    allowed = PROMPT_VARIABLES[name]
+    unknown = [variable for variable in variables if variable not in allowed]
+    if unknown:
+        raise ValueError(
+            f"Prompt template {path} uses unknown variables {', '.join(unknown)}; "
+            f"{name} can use {', '.join(allowed) or 'none'}")
+    return PromptTemplate(input_variables=variables, template=text)
+
+
+def load_prompts(prompts_dir: Optional[Path] = None) -> Dict[str, PromptTemplate]:
+    """Every template, overridden by the <name>.txt files of prompts_dir."""
+    if prompts_dir is not None:
+        prompts_dir = Path(prompts_dir)
+        if not prompts_dir.is_dir():
+            raise ValueError(f"Prompts directory not found: {prompts_dir}")
+        unknown = sorted(path.stem for path in prompts_dir.glob("*.txt")
+                         if path.stem not in PROMPT_VARIABLES)
+        if unknown:
+            raise ValueError(
+                f"Unknown prompt templates in {prompts_dir}: {', '.join(unknown)} "
+                f"(templates: {', '.join(PROMPT_VARIABLES)})")
+    return {name: load_template(name, prompts_dir) for name in PROMPT_VARIABLES}
+
+
+@lru_cache(maxsize=None)
+def default_prompts() -> Dict[str, PromptTemplate]:
+    """The packaged templates."""
+    return load_prompts()
+
+
+def prompt_overrides(prompts_dir: Optional[Path]) -> Dict[str, Path]:
+    """The templates prompts_dir overrides, by name."""
+    if prompts_dir is None:
+        return {}
+    return {
+        name: template_path(name, prompts_dir)
+        for name in PROMPT_VARIABLES
+        if template_path(name, prompts_dir) != TEMPLATES_DIR / f"{name}.txt"
+    }
+
+
+def prompt_set_version(prompts_dir: Optional[Path] = None) -> str:
+    """Identifies the templates a run used: a label and a hash of every template.
+
+    The label is the first line of prompts_dir/VERSION, else the directory name, or
+    "default" for the packaged templates.
+    """
+    digest = hashlib.sha256()
+    for name, prompt in load_prompts(prompts_dir).items():
+        digest.update(f"{name}\0{prompt.template}\0".encode("utf-8"))
+    label = "default"
+    if prompts_dir is not None:
+        version_file = Path(prompts_dir) / "VERSION"
+        lines = (version_file.read_text(encoding="utf-8").split("\n")
+                 if version_file.is_file() else [])
+        label = lines[0].strip() if lines and lines[0].strip() else Path(prompts_dir).resolve().name
+    return f"{label}-{digest.hexdigest()[:12]}"
@@ -0,0 +1,9 @@
+from .loader import load_template
+
+SPEC_PROMPT = load_template("spec")
+
+SKELETON_PROMPT = load_template("skeleton")
+
+IMPLEMENTATION_PROMPT = load_template("implementation")
+
+TESTS_PROMPT = load_template("tests")
@@ -0,0 +1,13 @@
+# providers/__init__.py
+from .base import Provider, probe_endpoint
+from .google import SafetyBlockedError
+from .registry import (
+    PROVIDERS, FALLBACK_MODELS, MIGRATION_STEPS,
+    get_provider, provider_for, fallback_models, resolve_model, missing_tokens
+)
+
+__all__ = [
+    'Provider', 'probe_endpoint', 'SafetyBlockedError',
+    'PROVIDERS', 'FALLBACK_MODELS', 'MIGRATION_STEPS',
+    'get_provider', 'provider_for', 'fallback_models', 'resolve_model', 'missing_tokens'
+]
@@ -0,0 +1,44 @@
+'''
+Claude models through the Anthropic API.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_anthropic import ChatAnthropic
+from langchain_core.caches import BaseCache
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+
+class AnthropicProvider(Provider):
+    name = "anthropic"
+    token = "claude"
+    label = "Claude"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> ChatAnthropic:
+        return ChatAnthropic(
+            anthropic_api_key=token,
+            model=config.model,
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            streaming=streaming,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """List the models, which uses no tokens."""
+        return await probe_endpoint(
+            "GET",
+            "https://api.anthropic.com/v1/models",
+            {"x-api-key": token, "anthropic-version": "2023-06-01"}
+        )
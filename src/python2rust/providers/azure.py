@@ -0,0 +1,73 @@
+'''
+OpenAI models deployed on Azure OpenAI, addressed by deployment name.
+'''
+import os
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_openai import AzureChatOpenAI
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+DEFAULT_API_VERSION = "2024-10-21"
+
+
+def azure_endpoint(config: LLMConfig) -> str:
+    """https://<resource>.openai.azure.com, from the config or AZURE_OPENAI_ENDPOINT."""
+    endpoint = config.endpoint_url or os.environ.get("AZURE_OPENAI_ENDPOINT")
+    if not endpoint:
+        raise ValueError("Azure OpenAI needs an endpoint_url or AZURE_OPENAI_ENDPOINT")
+    return endpoint.rstrip("/")
+
+
+def deployment_name(config: LLMConfig) -> str:
+    return config.deployment or config.model
+
+
+def api_version(config: LLMConfig) -> str:
+    return config.api_version or os.environ.get("OPENAI_API_VERSION") or DEFAULT_API_VERSION
+
+
+class AzureOpenAIProvider(Provider):
+    name = "azure"
+    token = "azure"
+    label = "Azure OpenAI"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> AzureChatOpenAI:
+        return AzureChatOpenAI(
+            api_key=token,
+            azure_endpoint=azure_endpoint(config),
+            azure_deployment=deployment_name(config),
+            api_version=api_version(config),
+            # Reported in traces and used for pricing; the deployment decides the actual model
+            model=config.model,
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            streaming=streaming,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """Ask the deployment for one token, which checks the key, endpoint and deployment name."""
+        try:
+            endpoint = azure_endpoint(config)
+        except ValueError as e:
+            return {"error": str(e)}
+        return await probe_endpoint(
+            "POST",
+            f"{endpoint}/openai/deployments/{deployment_name(config)}/chat/completions"
+            f"?api-version={api_version(config)}",
+            {"api-key": token, "Content-Type": "application/json"},
+            {"messages": [{"role": "user", "content": "Test request"}], "max_tokens": 1}
+        )
@@ -0,0 +1,90 @@
+'''
+Base class of the model APIs the migration can run on.
+'''
+import json
+from abc import ABC, abstractmethod
+from typing import Any, Dict, List, Optional
+
+import aiohttp
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_core.language_models import BaseLanguageModel
+
+from ..config.settings import LLMConfig
+
+
+async def probe_endpoint(
+    method: str,
+    endpoint_url: str,
+    headers: Dict[str, str],
+    payload: Optional[Dict[str, Any]] = None
+) -> Dict:
+    """Send a small request to an API, returning its status and body or the connection error."""
+    async with aiohttp.ClientSession() as session:
+        try:
+            async with session.request(
+                method,
+                endpoint_url,
+                headers=headers,
+                json=payload,
+                timeout=30
+            ) as response:
+                response_text = await response.text()
+                try:
+                    response_json = json.loads(response_text)
+                except json.JSONDecodeError:
+                    response_json = {"raw_response": response_text}
+
+                return {
+                    "status": response.status,
+                    "headers": dict(response.headers),
+                    "body": response_json,
+                    "url": str(response.url)
+                }
+        except aiohttp.ClientError as e:
+            return {
+                "error": str(e),
+                "type": e.__class__.__name__,
+                "url": endpoint_url
+            }
+
+
+class Provider(ABC):
+    """A model API: builds the LangChain model of each config served by it and checks its key.
+
+    Configs select their provider by name (LLMConfig.provider); the key is looked up
+    in the tokens under `token`.
+    """
+
+    name: str = ""
+    token: str = ""
+    label: str = ""
+    key_name: str = "api key"
+    # Runs on the user's machine: no usage costs, but weaker translations
+    local: bool = False
+    # Test the endpoint before each run and fall back to another model when it fails
+    probe_before_use: bool = False
+
+    def credentials(self, tokens: Dict[str, Optional[str]]) -> Optional[str]:
+        """The key passed to create and test, or None when the provider cannot be used."""
+        return tokens.get(self.token)
+
+    @property
+    def key_hint(self) -> str:
+        return f"Create .{self.token}_token with your {self.label} API key"
+
+    @abstractmethod
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> BaseLanguageModel:
+        """The LangChain model for a config."""
+
+    @abstractmethod
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """Check the key with a cheap request; a "status" of 200 means it works."""
@@ -0,0 +1,89 @@
+'''
+Models hosted on AWS Bedrock, called with the AWS credentials of the environment.
+'''
+import asyncio
+import os
+from typing import Dict, List, Optional
+
+import boto3
+from botocore.exceptions import BotoCoreError, ClientError
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_aws import ChatBedrockConverse
+from langchain_core.caches import BaseCache
+
+from .base import Provider
+from ..config.settings import LLMConfig
+
+DEFAULT_REGION = "us-east-1"
+# Credentials of the default chain (environment, ~/.aws, instance role) rather than a profile
+DEFAULT_CREDENTIALS = "default"
+
+
+def bedrock_region(config: LLMConfig) -> str:
+    return (config.region or os.environ.get("AWS_REGION")
+            or os.environ.get("AWS_DEFAULT_REGION") or DEFAULT_REGION)
+
+
+def aws_session(profile: str, config: LLMConfig) -> boto3.Session:
+    return boto3.Session(
+        profile_name=None if profile == DEFAULT_CREDENTIALS else profile,
+        region_name=bedrock_region(config)
+    )
+
+
+class BedrockProvider(Provider):
+    """Requests are signed with SigV4 by boto3; the token, when set, is an AWS profile name."""
+
+    name = "bedrock"
+    token = "bedrock"
+    label = "AWS Bedrock"
+    key_name = "credentials"
+
+    def credentials(self, tokens: Dict[str, Optional[str]]) -> Optional[str]:
+        profile = tokens.get(self.token) or os.environ.get("AWS_PROFILE") or DEFAULT_CREDENTIALS
+        try:
+            found = (boto3.Session(profile_name=None if profile == DEFAULT_CREDENTIALS else profile)
+                     .get_credentials())
+        except BotoCoreError:
+            return None
+        return profile if found is not None else None
+
+    @property
+    def key_hint(self) -> str:
+        return "Configure AWS credentials (aws configure, AWS_PROFILE or an instance role)"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> ChatBedrockConverse:
+        # ChatBedrockConverse has no streaming flag: Bedrock responses arrive whole
+        return ChatBedrockConverse(
+            client=aws_session(token, config).client("bedrock-runtime"),
+            model=config.model,
+            region_name=bedrock_region(config),
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """Look the model id up in the region, which checks the credentials and model access."""
+        def get_model() -> Dict:
+            client = aws_session(token, config).client("bedrock")
+            try:
+                client.get_foundation_model(modelIdentifier=config.model)
+            except ClientError as e:
+                return {
+                    "status": e.response.get("ResponseMetadata", {}).get("HTTPStatusCode"),
+                    "body": e.response.get("Error", {})
+                }
+            except BotoCoreError as e:
+                return {"error": str(e), "type": e.__class__.__name__}
+            return {"status": 200}
+        return await asyncio.to_thread(get_model)
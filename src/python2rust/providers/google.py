@@ -0,0 +1,97 @@
+'''
+Gemini models through the Google AI (Generative Language) API.
+'''
+from typing import Any, Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_core.outputs import ChatResult
+from langchain_google_genai import ChatGoogleGenerativeAI, HarmBlockThreshold, HarmCategory
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+GEMINI_API_URL = "https://generativelanguage.googleapis.com/v1beta"
+
+# Finish reasons of a response cut off by Google's filters rather than finished
+BLOCKED_FINISH_REASONS = {"SAFETY", "BLOCKLIST", "PROHIBITED_CONTENT", "SPII", "RECITATION"}
+
+# Code that kills processes or parses untrusted input trips the default thresholds
+SAFETY_SETTINGS = {
+    HarmCategory.HARM_CATEGORY_DANGEROUS_CONTENT: HarmBlockThreshold.BLOCK_ONLY_HIGH,
+    HarmCategory.HARM_CATEGORY_HARASSMENT: HarmBlockThreshold.BLOCK_ONLY_HIGH,
+    HarmCategory.HARM_CATEGORY_HATE_SPEECH: HarmBlockThreshold.BLOCK_ONLY_HIGH,
+    HarmCategory.HARM_CATEGORY_SEXUALLY_EXPLICIT: HarmBlockThreshold.BLOCK_ONLY_HIGH,
+}
+
+
+class SafetyBlockedError(ValueError):
+    """Gemini stopped a response on a safety or recitation filter."""
+
+
+def blocked_reason(result: ChatResult) -> Optional[str]:
+    """Why a Gemini response was blocked, or None when it finished normally."""
+    for generation in result.generations:
+        info = generation.generation_info or {}
+        finish_reason = str(info.get("finish_reason") or "").rsplit(".", 1)[-1]
+        if finish_reason in BLOCKED_FINISH_REASONS:
+            categories = [
+                str(rating.get("category", "")).rsplit(".", 1)[-1]
+                for rating in info.get("safety_ratings") or []
+                if rating.get("blocked")
+            ]
+            return finish_reason + (f" ({', '.join(categories)})" if categories else "")
+    return None
+
+
+class SafeChatGoogleGenerativeAI(ChatGoogleGenerativeAI):
+    """Raises SafetyBlockedError instead of returning the empty text of a blocked response."""
+
+    def _check(self, result: ChatResult) -> ChatResult:
+        reason = blocked_reason(result)
+        if reason is not None:
+            raise SafetyBlockedError(f"Gemini blocked the response: {reason}")
+        return result
+
+    def _generate(self, messages: List[Any], stop: Optional[List[str]] = None,
+                  run_manager: Any = None, **kwargs: Any) -> ChatResult:
+        return self._check(super()._generate(messages, stop=stop,
+                                             run_manager=run_manager, **kwargs))
+
+    async def _agenerate(self, messages: List[Any], stop: Optional[List[str]] = None,
+                         run_manager: Any = None, **kwargs: Any) -> ChatResult:
+        return self._check(await super()._agenerate(messages, stop=stop,
+                                                    run_manager=run_manager, **kwargs))
+
+
+class GoogleProvider(Provider):
+    name = "google"
+    token = "gemini"
+    label = "Gemini"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> SafeChatGoogleGenerativeAI:
+        return SafeChatGoogleGenerativeAI(
+            google_api_key=token,
+            model=config.model,
+            temperature=temperature,
+            max_output_tokens=config.max_tokens,
+            top_k=config.model_params.top_k if config.model_params else None,
+            top_p=config.model_params.top_p if config.model_params else None,
+            safety_settings=SAFETY_SETTINGS,
+            streaming=streaming,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """List the models, which uses no tokens."""
+        base_url = (config.endpoint_url or GEMINI_API_URL).rstrip("/")
+        return await probe_endpoint("GET", f"{base_url}/models", {"x-goog-api-key": token})
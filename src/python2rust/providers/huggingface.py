@@ -0,0 +1,77 @@
+'''
+Open models (CodeLlama, StarCoder) through HuggingFace inference endpoints.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_huggingface import HuggingFaceEndpoint
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+
+def endpoint_url(config: LLMConfig) -> str:
+    return config.endpoint_url or f"https://api-inference.huggingface.co/models/{config.model}"
+
+
+class HuggingFaceProvider(Provider):
+    name = "huggingface"
+    token = "hf"
+    label = "HuggingFace"
+    probe_before_use = True
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> HuggingFaceEndpoint:
+        llm = HuggingFaceEndpoint(
+            endpoint_url=endpoint_url(config),
+            huggingfacehub_api_token=token,
+            # HF rejects a zero temperature; greedy decoding is used instead
+            temperature=temperature or None,
+            task="text-completion",
+            max_new_tokens=config.max_tokens or 4000,
+            callbacks=callbacks,
+            cache=cache,
+        )
+        if not temperature:
+            llm.do_sample = False
+        if config.model_params is not None:
+            if config.model_params.return_full_text is not None:
+                llm.return_full_text = config.model_params.return_full_text
+            if config.model_params.stop_sequences is not None:
+                llm.stop_sequences = config.model_params.stop_sequences
+            if config.model_params.top_k is not None:
+                llm.top_k = config.model_params.top_k
+            if config.model_params.top_p is not None:
+                llm.top_p = config.model_params.top_p
+            if config.model_params.repetition_penalty is not None:
+                llm.repetition_penalty = config.model_params.repetition_penalty
+            if config.model_params.pad_token_id is not None:
+                llm.pad_token_id = config.model_params.pad_token_id
+        return llm
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """Generate a few tokens on the endpoint of the model."""
+        return await probe_endpoint(
+            "POST",
+            endpoint_url(config),
+            {
+                "Authorization": f"Bearer {token}",
+                "Accept": "application/json",
+                "Content-Type": "application/json"
+            },
+            {
+                "inputs": "Test request",
+                "parameters": {
+                    "max_new_tokens": 10,
+                    "return_full_text": False
+                }
+            }
+        )
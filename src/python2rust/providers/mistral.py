@@ -0,0 +1,54 @@
+'''
+Codestral through the Mistral API.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+
+from .base import Provider, probe_endpoint
+from .codestral_llm import CodestralLLM
+from ..config.settings import LLMConfig
+
+CODESTRAL_URL = "https://codestral.mistral.ai/v1/chat/completions"
+
+
+class MistralProvider(Provider):
+    name = "mistral"
+    token = "mistral"
+    label = "Mistral"
+    probe_before_use = True
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> CodestralLLM:
+        return CodestralLLM(
+            api_key=token,
+            model=config.model,
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """Generate a few tokens with the model."""
+        return await probe_endpoint(
+            "POST",
+            config.endpoint_url or CODESTRAL_URL,
+            {
+                "Authorization": f"Bearer {token}",
+                "Content-Type": "application/json"
+            },
+            {
+                "messages": [{"role": "user", "content": "Test request"}],
+                "model": config.model,
+                "max_tokens": 10
+            }
+        )
@@ -0,0 +1,65 @@
+'''
+Local models served by Ollama, for migrations that must not leave the machine.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_ollama import ChatOllama
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+OLLAMA_URL = "http://localhost:11434"
+# Key used for local servers, which need none
+LOCAL = "local"
+
+
+class OllamaProvider(Provider):
+    name = "ollama"
+    token = "ollama"
+    label = "Ollama"
+    key_name = "server"
+    probe_before_use = True
+    local = True
+
+    def credentials(self, tokens: Dict[str, Optional[str]]) -> Optional[str]:
+        return tokens.get(self.token) or LOCAL
+
+    @property
+    def key_hint(self) -> str:
+        return ("Start the server with `ollama serve` and pull the model with "
+                f"`ollama pull` ({OLLAMA_URL})")
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> ChatOllama:
+        return ChatOllama(
+            base_url=config.endpoint_url or OLLAMA_URL,
+            model=config.model,
+            temperature=temperature,
+            # Ollama silently truncates prompts longer than its default 2048-token context
+            num_ctx=config.context_length,
+            num_predict=config.max_tokens,
+            top_k=config.model_params.top_k if config.model_params else None,
+            top_p=config.model_params.top_p if config.model_params else None,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """List the pulled models, failing with a 404 when the configured one is missing."""
+        base_url = (config.endpoint_url or OLLAMA_URL).rstrip("/")
+        result = await probe_endpoint("GET", f"{base_url}/api/tags", {})
+        if result.get("status") != 200:
+            return result
+        names = {model.get("name") for model in result["body"].get("models", [])}
+        if config.model not in names and f"{config.model}:latest" not in names:
+            return {"status": 404, "body": {"error": f"model {config.model} is not pulled"}}
+        return result
@@ -0,0 +1,45 @@
+'''
+GPT models through the OpenAI API, or any API compatible with it via endpoint_url.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_openai import ChatOpenAI
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+OPENAI_API_URL = "https://api.openai.com/v1"
+
+
+class OpenAIProvider(Provider):
+    name = "openai"
+    token = "openai"
+    label = "OpenAI"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> ChatOpenAI:
+        return ChatOpenAI(
+            api_key=token,
+            model=config.model,
+            base_url=config.endpoint_url,
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            streaming=streaming,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """List the models, which uses no tokens."""
+        base_url = (config.endpoint_url or OPENAI_API_URL).rstrip("/")
+        return await probe_endpoint("GET", f"{base_url}/models",
+                                    {"Authorization": f"Bearer {token}"})
@@ -0,0 +1,77 @@
+'''
+Any server speaking the OpenAI chat API (vLLM, LM Studio, TGI, llama.cpp), configured by URL.
+'''
+from typing import Dict, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+from langchain_core.caches import BaseCache
+from langchain_openai import ChatOpenAI
+
+from .base import Provider, probe_endpoint
+from ..config.settings import LLMConfig
+
+# Placeholder key for servers without authentication; the OpenAI client requires one
+NO_KEY = "EMPTY"
+
+
+def base_url(config: LLMConfig) -> str:
+    """The API root, e.g. http://localhost:8000/v1."""
+    if not config.endpoint_url:
+        raise ValueError("An OpenAI-compatible model needs an endpoint_url, e.g. "
+                         "http://localhost:8000/v1")
+    return config.endpoint_url.rstrip("/")
+
+
+class OpenAICompatibleProvider(Provider):
+    name = "openai_compatible"
+    token = "openai_compatible"
+    label = "OpenAI-compatible server"
+    key_name = "server"
+    probe_before_use = True
+
+    def credentials(self, tokens: Dict[str, Optional[str]]) -> Optional[str]:
+        return tokens.get(self.token) or NO_KEY
+
+    @property
+    def key_hint(self) -> str:
+        return "Check the endpoint_url, headers and model of the openai_compatible config"
+
+    def create(
+        self,
+        config: LLMConfig,
+        token: str,
+        temperature: float,
+        streaming: bool = False,
+        callbacks: Optional[List[BaseCallbackHandler]] = None,
+        cache: Optional[BaseCache] = None
+    ) -> ChatOpenAI:
+        return ChatOpenAI(
+            api_key=token,
+            base_url=base_url(config),
+            model=config.model,
+            default_headers=config.headers or None,
+            temperature=temperature,
+            max_tokens=config.max_tokens,
+            streaming=streaming,
+            callbacks=callbacks,
+            cache=cache
+        )
+
+    async def test(self, config: LLMConfig, token: str) -> Dict:
+        """List the served models, failing with a 404 when the configured one is not among them."""
+        try:
+            url = f"{base_url(config)}/models"
+        except ValueError as e:
+            return {"error": str(e)}
+        headers = dict(config.headers)
+        if token != NO_KEY:
+            headers["Authorization"] = f"Bearer {token}"
+        result = await probe_endpoint("GET", url, headers)
+        if result.get("status") != 200:
+            return result
+        served = {model.get("id") for model in result["body"].get("data", [])}
+        if served and config.model not in served:
+            return {"status": 404,
+                    "body": {"error": f"{config.model} is not served (serving: "
+                                      f"{', '.join(sorted(served))})"}}
+        return result
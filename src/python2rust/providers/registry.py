@@ -0,0 +1,73 @@
+'''
+Lookup of providers by name, and the model each migration step ends up using.
+'''
+from typing import Dict, List, Optional
+
+from .anthropic import AnthropicProvider
+from .azure import AzureOpenAIProvider
+from .base import Provider
+from .bedrock import BedrockProvider
+from .google import GoogleProvider
+from .huggingface import HuggingFaceProvider
+from .mistral import MistralProvider
+from .ollama import OllamaProvider
+from .openai import OpenAIProvider
+from .openai_compatible import OpenAICompatibleProvider
+from ..config.settings import MIGRATION_STEPS, LLMChoice, Settings
+
+PROVIDERS: Dict[str, Provider] = {
+    provider.name: provider
+    for provider in (
+        AnthropicProvider(), OpenAIProvider(), AzureOpenAIProvider(), GoogleProvider(),
+        BedrockProvider(), HuggingFaceProvider(), MistralProvider(), OllamaProvider(),
+        OpenAICompatibleProvider()
+    )
+}
+
+# Tried, in order, for a model that has no key or fails to initialize
+FALLBACK_MODELS = (
+    LLMChoice.CLAUDE, LLMChoice.OPENAI, LLMChoice.AZURE, LLMChoice.GEMINI, LLMChoice.BEDROCK
+)
+
+
+def get_provider(name: str) -> Provider:
+    if name not in PROVIDERS:
+        raise ValueError(f"Unknown provider: {name} (available: {', '.join(PROVIDERS)})")
+    return PROVIDERS[name]
+
+
+def provider_for(settings: Settings, choice: LLMChoice) -> Provider:
+    return get_provider(settings.llm_configs[choice].provider)
+
+
+def fallback_models(settings: Settings, choice: LLMChoice) -> List[LLMChoice]:
+    """Models used instead of a choice when it is unavailable: its fallback_model first."""
+    fallback = settings.llm_configs[choice].fallback_model
+    candidates = ([LLMChoice(fallback)] if fallback else []) + list(FALLBACK_MODELS)
+    ordered = []
+    for candidate in candidates:
+        if candidate != choice and candidate in settings.llm_configs and candidate not in ordered:
+            ordered.append(candidate)
+    return ordered
+
+
+def resolve_model(
+    settings: Settings,
+    choice: LLMChoice,
+    tokens: Dict[str, Optional[str]]
+) -> Optional[LLMChoice]:
+    """The model that will serve a choice given the available tokens, or None."""
+    for candidate in [choice, *fallback_models(settings, choice)]:
+        if provider_for(settings, candidate).credentials(tokens):
+            return candidate
+    return None
+
+
+def missing_tokens(settings: Settings, tokens: Dict[str, Optional[str]]) -> Dict[str, str]:
+    """Migration steps no available model can run, with the token their model needs."""
+    missing = {}
+    for step in MIGRATION_STEPS:
+        choice = settings.llm_steps.model_for(step)
+        if resolve_model(settings, choice, tokens) is None:
+            missing[step] = provider_for(settings, choice).token
+    return missing
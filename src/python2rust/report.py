@@ -0,0 +1,91 @@
+"""
+Structured results returned by the programmatic migration API.
+"""
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+
+@dataclass
+class FileReport:
+    """Outcome of migrating a single Python source file."""
+    source: Path
+    output_dir: Path
+    success: bool
+    rust_code: Optional[str] = None
+    toml_content: Optional[str] = None
+    error: Optional[str] = None
+    metrics: Dict[str, Any] = field(default_factory=dict)
+    source_map: Optional[Path] = None
+    verification: Optional[Dict[str, Any]] = None  # last behavioral comparison with the Python code
+    tokens_used: int = 0
+    warnings: List[str] = field(default_factory=list)  # e.g. dependency audit violations
+    dead_code: List[str] = field(default_factory=list)  # unused definitions left untranslated
+    # Decorators without a translation strategy, to map under [decorators] in python2rust.toml
+    unknown_decorators: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "source": str(self.source),
+            "output_dir": str(self.output_dir),
+            "success": self.success,
+            "error": self.error,
+            "metrics": self.metrics,
+            "verification": self.verification,
+            "tokens_used": self.tokens_used,
+            "warnings": self.warnings,
+            "dead_code": self.dead_code,
+            "unknown_decorators": self.unknown_decorators,
+            "source_map": str(self.source_map) if self.source_map else None
+        }
+
+
+@dataclass
+class MigrationReport:
+    """Aggregated outcome of a migration run."""
+    target_dir: Path
+    files: List[FileReport] = field(default_factory=list)
+    started_at: datetime = field(default_factory=datetime.now)
+    finished_at: Optional[datetime] = None
+    cancelled: bool = False
+    run_id: Optional[str] = None
+    warnings: List[str] = field(default_factory=list)  # e.g. steps run on weaker local models
+    prompt_version: Optional[str] = None  # prompt set used, see prompt_set_version
+    budget: Optional[Dict[str, Any]] = None  # spend of a run with limits, and why it stopped
+    workspace: Optional[Path] = None  # Cargo workspace of the project's packages, when several
+
+    @property
+    def success(self) -> bool:
+        return bool(self.files) and all(f.success for f in self.files)
+
+    @property
+    def succeeded(self) -> List[FileReport]:
+        return [f for f in self.files if f.success]
+
+    @property
+    def failed(self) -> List[FileReport]:
+        return [f for f in self.files if not f.success]
+
+    @property
+    def duration(self) -> Optional[float]:
+        if self.finished_at is None:
+            return None
+        return (self.finished_at - self.started_at).total_seconds()
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "success": self.success,
+            "cancelled": self.cancelled,
+            "run_id": self.run_id,
+            "prompt_version": self.prompt_version,
+            "target_dir": str(self.target_dir),
+            "started_at": self.started_at.isoformat(),
+            "finished_at": self.finished_at.isoformat() if self.finished_at else None,
+            "duration": self.duration,
+            "tokens_used": sum(f.tokens_used for f in self.files),
+            "warnings": self.warnings,
+            "budget": self.budget,
+            "workspace": str(self.workspace) if self.workspace else None,
+            "files": [f.to_dict() for f in self.files]
+        }
@@ -0,0 +1,21 @@
+# runs/__init__.py
+from .browser import FileRecord, RunBrowser, Usage
+from .budget import BudgetTracker, Spend
+from .checkpoint import FileCheckpoint, FileStatus, RunCheckpoint, RunStatus
+from .class_plans import ApprovedClassPlan, ClassPlanStore
+from .digests import DigestStore
+from .diff import FileDiff, RunDiff, diff_runs
+from .manifest import ManifestEntry, MigrationManifest, hash_api, hash_inputs, hash_source
+from .review import Review, ReviewDecision, ReviewStore, hash_rust
+from .status import ModuleState, ModuleStatus, ProjectStatus, collect_status
+from .store import RunStore, get_state_dir
+
+__all__ = [
+    'BudgetTracker', 'Spend',
+    'FileCheckpoint', 'FileStatus', 'RunCheckpoint', 'RunStatus', 'RunStore', 'get_state_dir',
+    'ManifestEntry', 'MigrationManifest', 'hash_api', 'hash_inputs', 'hash_source',
+    'ModuleState', 'ModuleStatus', 'ProjectStatus', 'collect_status',
+    'FileDiff', 'RunDiff', 'diff_runs', 'DigestStore',
+    'Review', 'ReviewDecision', 'ReviewStore', 'hash_rust',
+    'FileRecord', 'RunBrowser', 'Usage', 'ApprovedClassPlan', 'ClassPlanStore'
+]
@@ -0,0 +1,174 @@
+import json
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from .checkpoint import FileCheckpoint, RunCheckpoint
+from .store import RunStore
+from ..config.pricing import ModelPricing
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+@dataclass
+class Usage:
+    """Tokens spent on LLM calls and their price, None when a model has no known price."""
+    calls: int = 0
+    prompt_tokens: int = 0
+    completion_tokens: int = 0
+    cost: Optional[float] = 0.0
+
+    @property
+    def total_tokens(self) -> int:
+        return self.prompt_tokens + self.completion_tokens
+
+    def add(self, other: "Usage") -> None:
+        self.calls += other.calls
+        self.prompt_tokens += other.prompt_tokens
+        self.completion_tokens += other.completion_tokens
+        self.cost = None if self.cost is None or other.cost is None else self.cost + other.cost
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "calls": self.calls,
+            "prompt_tokens": self.prompt_tokens,
+            "completion_tokens": self.completion_tokens,
+            "total_tokens": self.total_tokens,
+            "cost": self.cost
+        }
+
+    def format(self) -> str:
+        cost = f"${self.cost:.4f}" if self.cost is not None else "unknown cost"
+        return (f"{self.calls} calls, {self.total_tokens:,} tokens "
+                f"({self.prompt_tokens:,} in / {self.completion_tokens:,} out), {cost}")
+
+
+@dataclass
+class FileRecord:
+    """What a run left behind for one source file."""
+    checkpoint: FileCheckpoint
+    logs: List[Path] = field(default_factory=list)
+    usage: Usage = field(default_factory=Usage)
+
+    @property
+    def source(self) -> Path:
+        return Path(self.checkpoint.source)
+
+    @property
+    def output_dir(self) -> Path:
+        return Path(self.checkpoint.output_dir)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            **self.checkpoint.to_dict(),
+            "logs": [str(log) for log in self.logs],
+            "usage": self.usage.to_dict()
+        }
+
+    def python_code(self) -> str:
+        try:
+            return self.source.read_text()
+        except OSError:
+            return ""
+
+    def rust_code(self) -> str:
+        """Code saved with the run, falling back to the current output directory."""
+        for path in [
+            Path(self.checkpoint.outputs_dir) / "src" / "main.rs"
+            if self.checkpoint.has_outputs else None, self.output_dir / "src" / "main.rs"
+        ]:
+            if path is not None and path.exists():
+                return path.read_text()
+        return ""
+
+
+def _timestamp(value: str) -> Optional[float]:
+    try:
+        return datetime.fromisoformat(value).timestamp()
+    except (TypeError, ValueError):
+        return None
+
+
+def _in_window(path: Path, start: Optional[float], end: Optional[float]) -> bool:
+    modified = path.stat().st_mtime
+    return (start is None or modified >= start) and (end is None or modified < end)
+
+
+def _trace_usage(trace: Dict, pricing: Dict[str, ModelPricing]) -> Usage:
+    tokens = trace.get("token_usage") or {}
+    usage = Usage(
+        calls=1,
+        prompt_tokens=tokens.get("prompt_tokens", 0),
+        completion_tokens=tokens.get("completion_tokens", 0)
+    )
+    price = pricing.get(trace.get("model_id") or "") or pricing.get(trace.get("model") or "")
+    usage.cost = price.cost(usage.prompt_tokens, usage.completion_tokens) if price else None
+    return usage
+
+
+class RunBrowser:
+    """Collects the files, cargo logs and LLM usage of a run for browsing.
+
+    Logs and traces live in each file's debug directory, shared by all runs;
+    those written between the start of this run and the start of the next
+    one are attributed to it.
+    """
+
+    def __init__(
+        self,
+        store: RunStore,
+        checkpoint: RunCheckpoint,
+        pricing: Optional[Dict[str, ModelPricing]] = None
+    ):
+        self.store = store
+        self.checkpoint = checkpoint
+        self.pricing = pricing or {}
+        runs = store.list_runs()
+        run_ids = [run.run_id for run in runs]
+        later = runs[run_ids.index(checkpoint.run_id) + 1:] if checkpoint.run_id in run_ids else []
+        self.start = _timestamp(checkpoint.created_at)
+        self.end = _timestamp(later[0].created_at) if later else None
+        self.files = [self._record(file) for file in checkpoint.files.values()]
+
+    def _record(self, file: FileCheckpoint) -> FileRecord:
+        record = FileRecord(checkpoint=file)
+        debug_dir = Path(file.output_dir) / "debug"
+        logs_dir = debug_dir / "logs"
+        if logs_dir.exists():
+            record.logs = sorted(
+                path for path in logs_dir.glob("*.log") if _in_window(path, self.start, self.end))
+        server_log = Path(file.output_dir) / "server.log"
+        if server_log.exists() and _in_window(server_log, self.start, self.end):
+            record.logs.append(server_log)
+
+        traces_dir = debug_dir / "traces"
+        if traces_dir.exists():
+            for trace_file in sorted(traces_dir.glob("trace_*.json")):
+                if not _in_window(trace_file, self.start, self.end):
+                    continue
+                try:
+                    record.usage.add(_trace_usage(json.loads(trace_file.read_text()), self.pricing))
+                except (json.JSONDecodeError, OSError) as e:
+                    logger.warning(f"Skipping unreadable trace {trace_file}: {e}")
+        if not record.usage.calls and file.tokens_used:
+            record.usage = Usage(prompt_tokens=file.tokens_used, cost=None)
+        return record
+
+    def total_usage(self) -> Usage:
+        total = Usage()
+        for record in self.files:
+            total.add(record.usage)
+        return total
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "run_id": self.checkpoint.run_id,
+            "status": self.checkpoint.status.value,
+            "prompt_version": self.checkpoint.prompt_version,
+            "created_at": self.checkpoint.created_at,
+            "updated_at": self.checkpoint.updated_at,
+            "usage": self.total_usage().to_dict(),
+            "files": [record.to_dict() for record in self.files]
+        }
@@ -0,0 +1,111 @@
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional, Set, Tuple
+
+from ..config.pricing import ModelPricing
+from ..config.settings import BudgetLimits
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+@dataclass
+class Spend:
+    """Tokens and dollars reported by model responses."""
+    tokens: int = 0
+    cost: float = 0.0
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"tokens": self.tokens, "cost": round(self.cost, 6)}
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "Spend":
+        return cls(tokens=data.get("tokens", 0), cost=data.get("cost", 0.0))
+
+
+class BudgetTracker:
+    """Adds up the usage of model responses against the run and per-file limits.
+
+    Responses are priced with the pricing table; a model missing from it only
+    counts toward token limits. Spend is kept as the `budget` of a run checkpoint,
+    so a resumed run continues from what it already spent.
+    """
+
+    def __init__(
+        self,
+        limits: BudgetLimits,
+        pricing: Dict[str, ModelPricing],
+        spent: Optional[Dict[str, Any]] = None
+    ):
+        self.limits = limits
+        self.pricing = pricing
+        spent = spent or {}
+        self.run = Spend.from_dict(spent)
+        self.files: Dict[str, Spend] = {
+            source: Spend.from_dict(data) for source, data in spent.get("files", {}).items()
+        }
+        self._warned: Set[Tuple[str, str, float]] = set()
+        self._unpriced: Set[str] = set()
+
+    def record(self, file: Optional[str], model: Optional[str], input_tokens: int,
+               output_tokens: int) -> List[str]:
+        """Add the usage of one response; returns the warnings of thresholds it crossed."""
+        cost = 0.0
+        pricing = self.pricing.get(model) if model else None
+        if pricing is not None:
+            cost = pricing.cost(input_tokens, output_tokens)
+        elif model not in self._unpriced:
+            self._unpriced.add(model)
+            logger.warning(f"No price for {model or 'an unnamed model'}: its usage only "
+                           "counts toward token limits")
+
+        spends = [("run", self.run)]
+        if file is not None:
+            spends.append((file, self.files.setdefault(file, Spend())))
+        for _, spend in spends:
+            spend.tokens += input_tokens + output_tokens
+            spend.cost += cost
+
+        warnings = []
+        for scope, spend in spends:
+            for kind, used, limit in self._limits(scope, spend):
+                for fraction in sorted(self.limits.warn_at):
+                    key = (scope, kind, fraction)
+                    if used >= limit * fraction and used < limit and key not in self._warned:
+                        self._warned.add(key)
+                        warnings.append(
+                            f"{'Run' if scope == 'run' else scope} has used {fraction:.0%} of its "
+                            f"{kind} budget ({self._format(kind, used)} of "
+                            f"{self._format(kind, limit)})")
+        return warnings
+
+    def _limits(self, scope: str, spend: Spend) -> List[Tuple[str, float, float]]:
+        if scope == "run":
+            limits = [("cost", spend.cost, self.limits.run_cost),
+                      ("token", spend.tokens, self.limits.run_tokens)]
+        else:
+            limits = [("cost", spend.cost, self.limits.file_cost),
+                      ("token", spend.tokens, self.limits.file_tokens)]
+        return [(kind, used, limit) for kind, used, limit in limits if limit is not None]
+
+    @staticmethod
+    def _format(kind: str, amount: float) -> str:
+        return f"${amount:.2f}" if kind == "cost" else f"{int(amount)} tokens"
+
+    def exhausted(self, file: Optional[str] = None) -> Optional[str]:
+        """Why the run, or else the file, may not spend more; None while within budget."""
+        scopes = [("run", self.run)]
+        if file is not None:
+            scopes.append((file, self.files.get(file, Spend())))
+        for scope, spend in scopes:
+            for kind, used, limit in self._limits(scope, spend):
+                if used >= limit:
+                    owner = "run" if scope == "run" else "file"
+                    return (f"Budget exhausted: the {owner} {kind} limit of "
+                            f"{self._format(kind, limit)} was reached ({self._format(kind, used)})")
+        return None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            **self.run.to_dict(),
+            "files": {source: spend.to_dict() for source, spend in self.files.items()}
+        }
@@ -0,0 +1,102 @@
+from dataclasses import asdict, dataclass, field
+from datetime import datetime
+from enum import Enum
+from typing import Any, Dict, List, Optional
+
+
+class FileStatus(str, Enum):
+    """Migration state of a single source file within a run."""
+    PENDING = "pending"
+    IN_PROGRESS = "in_progress"
+    GENERATED = "generated"    # Rust code exists but did not pass verification
+    VERIFIED = "verified"      # Accepted output, skipped on resume
+    FAILED = "failed"
+    CANCELLED = "cancelled"
+
+
+class RunStatus(str, Enum):
+    RUNNING = "running"
+    COMPLETED = "completed"
+    FAILED = "failed"
+    CANCELLED = "cancelled"
+
+
+@dataclass
+class FileCheckpoint:
+    """Persisted progress of one source file."""
+    source: str
+    output_dir: str
+    status: FileStatus = FileStatus.PENDING
+    stage: Optional[str] = None
+    iteration: int = 0
+    last_successful_iteration: Optional[int] = None
+    outputs_dir: Optional[str] = None
+    error: Optional[str] = None
+    tokens_used: int = 0
+    # Release build measures of the verified code, see builders.build_stats
+    build_stats: Dict[str, Any] = field(default_factory=dict)
+    updated_at: str = field(default_factory=lambda: datetime.now().isoformat())
+
+    @property
+    def has_outputs(self) -> bool:
+        return self.outputs_dir is not None
+
+    def to_dict(self) -> Dict[str, Any]:
+        data = asdict(self)
+        data["status"] = self.status.value
+        return data
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "FileCheckpoint":
+        data = dict(data)
+        data["status"] = FileStatus(data.get("status", FileStatus.PENDING))
+        return cls(**data)
+
+
+@dataclass
+class RunCheckpoint:
+    """Persisted state of a whole migration run."""
+    run_id: str
+    target_dir: str
+    files: Dict[str, FileCheckpoint] = field(default_factory=dict)
+    status: RunStatus = RunStatus.RUNNING
+    budget: Dict[str, Any] = field(default_factory=dict)
+    prompt_version: Optional[str] = None  # prompt set the run was started with
+    created_at: str = field(default_factory=lambda: datetime.now().isoformat())
+    updated_at: str = field(default_factory=lambda: datetime.now().isoformat())
+
+    @property
+    def sources(self) -> List[str]:
+        return list(self.files)
+
+    def pending_files(self) -> List[FileCheckpoint]:
+        """Files that still need work when resuming."""
+        return [f for f in self.files.values() if f.status != FileStatus.VERIFIED]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "run_id": self.run_id,
+            "target_dir": self.target_dir,
+            "status": self.status.value,
+            "budget": self.budget,
+            "prompt_version": self.prompt_version,
+            "created_at": self.created_at,
+            "updated_at": self.updated_at,
+            "files": {source: f.to_dict() for source, f in self.files.items()}
+        }
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "RunCheckpoint":
+        return cls(
+            run_id=data["run_id"],
+            target_dir=data["target_dir"],
+            status=RunStatus(data.get("status", RunStatus.RUNNING)),
+            budget=data.get("budget", {}),
+            prompt_version=data.get("prompt_version"),
+            created_at=data.get("created_at", ""),
+            updated_at=data.get("updated_at", ""),
+            files={
+                source: FileCheckpoint.from_dict(f)
+                for source, f in data.get("files", {}).items()
+            }
+        )
@@ -0,0 +1,71 @@
+import json
+import os
+from dataclasses import asdict, dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+from .manifest import hash_source
+from .store import get_state_dir
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+@dataclass
+class ApprovedClassPlan:
+    """A user's approval of the class mapping plan of a file, with the kinds they changed."""
+    source: str
+    source_hash: str  # Python source the plan was approved on
+    overrides: Dict[str, str] = field(default_factory=dict)  # class -> struct, trait or enum
+    approved_at: str = field(default_factory=lambda: datetime.now().isoformat())
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+class ClassPlanStore:
+    """Approved class mapping plans in <target_dir>/.python2rust/class_plans.json.
+
+    An approval holds while the Python source is the one it was given on; editing the
+    file asks for the plan to be approved again.
+    """
+
+    def __init__(self, target_dir: Path):
+        self.path = get_state_dir(target_dir) / "class_plans.json"
+        self.plans: Dict[str, ApprovedClassPlan] = {}
+        if self.path.exists():
+            try:
+                data = json.loads(self.path.read_text())
+                self.plans = {
+                    source: ApprovedClassPlan(**plan)
+                    for source, plan in data.get("files", {}).items()
+                }
+            except (json.JSONDecodeError, TypeError, AttributeError) as e:
+                logger.warning(f"Ignoring unreadable class plans {self.path}: {e}")
+
+    def approved(self, source: Path, python_code: str) -> Optional[ApprovedClassPlan]:
+        """The approval of the file's plan, if given on this version of its source."""
+        plan = self.plans.get(str(source))
+        if plan is None or plan.source_hash != hash_source(python_code):
+            return None
+        return plan
+
+    def approve(
+        self,
+        source: Path,
+        python_code: str,
+        overrides: Optional[Dict[str, str]] = None
+    ) -> ApprovedClassPlan:
+        plan = ApprovedClassPlan(str(source), hash_source(python_code), dict(overrides or {}))
+        self.plans[str(source)] = plan
+        self.save()
+        return plan
+
+    def save(self) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_file = self.path.with_suffix(".json.tmp")
+        tmp_file.write_text(json.dumps({
+            "files": {source: plan.to_dict() for source, plan in self.plans.items()}
+        }, indent=2))
+        os.replace(tmp_file, self.path)
@@ -0,0 +1,172 @@
+import difflib
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from ..builders.build_stats import build_regressions
+from .checkpoint import FileCheckpoint, FileStatus, RunCheckpoint
+from .store import RunStore
+
+# Higher is better; a file moving down this scale between runs is a regression
+STATUS_RANK = {
+    FileStatus.VERIFIED: 3,
+    FileStatus.GENERATED: 2,
+    FileStatus.CANCELLED: 1,
+    FileStatus.IN_PROGRESS: 1,
+    FileStatus.FAILED: 0,
+    FileStatus.PENDING: 0,
+}
+
+
+@dataclass
+class FileDiff:
+    """Changes to the code generated for one source file between two runs."""
+    source: str
+    old_status: Optional[FileStatus]
+    new_status: Optional[FileStatus]
+    old_tokens: int = 0
+    new_tokens: int = 0
+    old_iterations: int = 0
+    new_iterations: int = 0
+    lines: List[str] = field(default_factory=list)
+    # Release build measures of each run, empty when not recorded
+    old_build: Dict[str, Any] = field(default_factory=dict)
+    new_build: Dict[str, Any] = field(default_factory=dict)
+
+    @property
+    def added(self) -> int:
+        return sum(1 for line in self.lines if line.startswith("+") and not line.startswith("+++"))
+
+    @property
+    def removed(self) -> int:
+        return sum(1 for line in self.lines if line.startswith("-") and not line.startswith("---"))
+
+    @property
+    def changed(self) -> bool:
+        return bool(self.lines) or self.old_status != self.new_status
+
+    @property
+    def build_regressions(self) -> List[str]:
+        return build_regressions(self.old_build, self.new_build)
+
+    @property
+    def regressed(self) -> bool:
+        if self.old_status is None or self.new_status is None:
+            return False
+        return (STATUS_RANK[self.new_status] < STATUS_RANK[self.old_status]
+                or bool(self.build_regressions))
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "source": self.source,
+            "old_status": self.old_status.value if self.old_status else None,
+            "new_status": self.new_status.value if self.new_status else None,
+            "added": self.added,
+            "removed": self.removed,
+            "old_tokens": self.old_tokens,
+            "new_tokens": self.new_tokens,
+            "old_iterations": self.old_iterations,
+            "new_iterations": self.new_iterations,
+            "old_build": self.old_build,
+            "new_build": self.new_build,
+            "build_regressions": self.build_regressions,
+            "regressed": self.regressed,
+            "diff": self.lines
+        }
+
+
+@dataclass
+class RunDiff:
+    """Per-file comparison of two runs of the same target directory."""
+    old_run: str
+    new_run: str
+    files: List[FileDiff] = field(default_factory=list)
+
+    @property
+    def changed(self) -> List[FileDiff]:
+        return [f for f in self.files if f.changed]
+
+    @property
+    def regressions(self) -> List[FileDiff]:
+        return [f for f in self.files if f.regressed]
+
+    def format(self, stat_only: bool = False) -> str:
+        lines = [f"Comparing run {self.old_run} -> {self.new_run}", ""]
+        for file in self.files:
+            old = file.old_status.value if file.old_status else "absent"
+            new = file.new_status.value if file.new_status else "absent"
+            marker = "  REGRESSED" if file.regressed else ""
+            lines.append(
+                f"{file.source}: {old} -> {new}, +{file.added} -{file.removed} lines, "
+                f"tokens {file.old_tokens} -> {file.new_tokens}, "
+                f"iterations {file.old_iterations} -> {file.new_iterations}{marker}"
+            )
+            lines.extend(f"  build regression: {regression}"
+                         for regression in file.build_regressions)
+            if not stat_only and file.lines:
+                lines.extend(file.lines)
+                lines.append("")
+
+        lines.append("")
+        lines.append(
+            f"{len(self.changed)}/{len(self.files)} files changed, "
+            f"+{sum(f.added for f in self.files)} -{sum(f.removed for f in self.files)} lines, "
+            f"{len(self.regressions)} regressed"
+        )
+        return "\n".join(lines)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "old_run": self.old_run,
+            "new_run": self.new_run,
+            "changed": len(self.changed),
+            "regressions": len(self.regressions),
+            "files": [f.to_dict() for f in self.files]
+        }
+
+
+def _read_outputs(store: RunStore, file: Optional[FileCheckpoint]) -> Tuple[str, str]:
+    if file is None or not file.has_outputs or not Path(file.outputs_dir).exists():
+        return "", ""
+    return store.load_outputs(file)
+
+
+def _unified(old: str, new: str, name: str, old_run: str, new_run: str) -> List[str]:
+    return [
+        line.rstrip("\n")
+        for line in difflib.unified_diff(
+            old.splitlines(keepends=True),
+            new.splitlines(keepends=True),
+            fromfile=f"{old_run}/{name}",
+            tofile=f"{new_run}/{name}"
+        )
+    ]
+
+
+def diff_runs(store: RunStore, old: RunCheckpoint, new: RunCheckpoint) -> RunDiff:
+    """Diff the Rust code and Cargo.toml generated for each file in two runs."""
+    result = RunDiff(old_run=old.run_id, new_run=new.run_id)
+    sources = list(old.files) + [source for source in new.files if source not in old.files]
+
+    for source in sources:
+        old_file = old.files.get(source)
+        new_file = new.files.get(source)
+        old_rust, old_toml = _read_outputs(store, old_file)
+        new_rust, new_toml = _read_outputs(store, new_file)
+        stem = Path(source).stem
+        result.files.append(FileDiff(
+            source=source,
+            old_status=old_file.status if old_file else None,
+            new_status=new_file.status if new_file else None,
+            old_tokens=old_file.tokens_used if old_file else 0,
+            new_tokens=new_file.tokens_used if new_file else 0,
+            old_iterations=old_file.iteration if old_file else 0,
+            new_iterations=new_file.iteration if new_file else 0,
+            old_build=old_file.build_stats if old_file else {},
+            new_build=new_file.build_stats if new_file else {},
+            lines=(
+                _unified(old_rust, new_rust, f"{stem}/src/main.rs", old.run_id, new.run_id)
+                + _unified(old_toml, new_toml, f"{stem}/Cargo.toml", old.run_id, new.run_id)
+            )
+        ))
+    return result
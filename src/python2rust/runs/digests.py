@@ -0,0 +1,39 @@
+import json
+from pathlib import Path
+from typing import Dict
+
+from .manifest import hash_source
+from .store import get_state_dir
+from ..analyzers.rust_signatures import summarize_rust
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+class DigestStore:
+    """Digests of the public API of translated modules, in <target_dir>/.python2rust/digests.json.
+
+    A module's digest is recomputed whenever its generated Rust changes, and
+    given to the modules importing it instead of the full code.
+    """
+
+    def __init__(self, target_dir: Path):
+        self.path = get_state_dir(target_dir) / "digests.json"
+        self._entries: Dict[str, Dict[str, str]] = {}
+        if self.path.exists():
+            try:
+                self._entries = json.loads(self.path.read_text())
+            except json.JSONDecodeError:
+                logger.warning(f"Ignoring unreadable digests file {self.path}")
+
+    def digest(self, module: str, rust_code: str) -> str:
+        """The digest of a module's generated Rust, updated when the code changed."""
+        rust_hash = hash_source(rust_code)
+        entry = self._entries.get(module)
+        if entry is not None and entry.get("rust_hash") == rust_hash:
+            return entry["digest"]
+        digest = summarize_rust(rust_code)
+        self._entries[module] = {"rust_hash": rust_hash, "digest": digest}
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self.path.write_text(json.dumps(self._entries, indent=2))
+        return digest
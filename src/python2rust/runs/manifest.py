@@ -0,0 +1,114 @@
+import hashlib
+import json
+import os
+from dataclasses import asdict, dataclass
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from .store import get_state_dir
+from ..analyzers import extract_signatures
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def _sha256(*parts: str) -> str:
+    digest = hashlib.sha256()
+    for part in parts:
+        digest.update(part.encode())
+        digest.update(b"\0")
+    return digest.hexdigest()
+
+
+def hash_inputs(python_code: str, fingerprint: str, dependency_apis: List[str]) -> str:
+    """Hash everything a file's translation depends on."""
+    return _sha256(python_code, fingerprint, *dependency_apis)
+
+
+def hash_source(python_code: str) -> str:
+    """Hash the Python source alone, to tell whether it was edited since its migration."""
+    return _sha256(python_code)
+
+
+def hash_api(rust_code: Optional[str]) -> str:
+    """Hash the public API of generated Rust code, ignoring implementation changes."""
+    return _sha256(*extract_signatures(rust_code or ""))
+
+
+@dataclass
+class ManifestEntry:
+    """Last verified translation of a source file."""
+    source: str
+    input_hash: str
+    api_hash: str
+    outputs_dir: str
+    updated_at: str = ""
+    source_hash: str = ""
+
+
+class MigrationManifest:
+    """Tracks verified translations in <target_dir>/.python2rust/manifest.json.
+
+    A file whose input hash matches its entry is not migrated again; its stored
+    output is reused instead.
+    """
+
+    def __init__(self, target_dir: Path):
+        self.path = get_state_dir(target_dir) / "manifest.json"
+        self.entries: Dict[str, ManifestEntry] = {}
+        if self.path.exists():
+            try:
+                data = json.loads(self.path.read_text())
+                self.entries = {
+                    source: ManifestEntry(**entry)
+                    for source, entry in data.get("files", {}).items()
+                }
+            except (json.JSONDecodeError, TypeError) as e:
+                logger.warning(f"Ignoring unreadable manifest {self.path}: {e}")
+
+    def lookup(self, source: Path, input_hash: str) -> Optional[Tuple[str, str]]:
+        """Previously verified (rust_code, toml_content) for unchanged inputs."""
+        entry = self.entries.get(str(source))
+        if entry is None or entry.input_hash != input_hash:
+            return None
+        outputs_dir = Path(entry.outputs_dir)
+        try:
+            return (
+                (outputs_dir / "src" / "main.rs").read_text(),
+                (outputs_dir / "Cargo.toml").read_text()
+            )
+        except OSError:
+            logger.info(f"Stored output for {source} is missing, migrating again")
+            return None
+
+    def record(
+        self,
+        source: Path,
+        input_hash: str,
+        rust_code: str,
+        outputs_dir: Path,
+        source_hash: str = ""
+    ) -> None:
+        self.entries[str(source)] = ManifestEntry(
+            source=str(source),
+            input_hash=input_hash,
+            api_hash=hash_api(rust_code),
+            outputs_dir=str(outputs_dir),
+            updated_at=datetime.now().isoformat(),
+            source_hash=source_hash
+        )
+        self.save()
+
+    def forget(self, source: Path) -> None:
+        if self.entries.pop(str(source), None) is not None:
+            self.save()
+
+    def save(self) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_file = self.path.with_suffix(".json.tmp")
+        tmp_file.write_text(json.dumps(
+            {"files": {source: asdict(entry) for source, entry in self.entries.items()}},
+            indent=2
+        ))
+        os.replace(tmp_file, self.path)
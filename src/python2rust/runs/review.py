@@ -0,0 +1,121 @@
+import hashlib
+import json
+import os
+from dataclasses import asdict, dataclass, field
+from datetime import datetime
+from enum import Enum
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from .manifest import hash_source
+from .store import get_state_dir
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+
+def hash_rust(rust_code: str) -> str:
+    return hashlib.sha256(rust_code.encode()).hexdigest()
+
+
+class ReviewDecision(str, Enum):
+    ACCEPTED = "accepted"          # keep this translation on re-runs
+    REJECTED = "rejected"          # regenerate, with the comment added to the prompt
+    KEEP_PYTHON = "keep_python"    # do not translate
+
+
+@dataclass
+class Review:
+    """A reviewer's decision on a translated file, or on one function of it."""
+    source: str
+    decision: ReviewDecision
+    item: Optional[str] = None     # Python qualname; None for the whole file
+    comment: str = ""
+    source_hash: str = ""          # Python source the decision was made on
+    rust_hash: str = ""            # Rust code the decision was made on
+    rust_snippet: str = ""         # Rust translation of the reviewed function
+    updated_at: str = field(default_factory=lambda: datetime.now().isoformat())
+
+    def to_dict(self) -> Dict[str, Any]:
+        data = asdict(self)
+        data["decision"] = self.decision.value
+        return data
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "Review":
+        data = dict(data)
+        data["decision"] = ReviewDecision(data["decision"])
+        return cls(**data)
+
+
+class ReviewStore:
+    """Review decisions in <target_dir>/.python2rust/reviews.json, respected by later runs."""
+
+    def __init__(self, target_dir: Path):
+        self.path = get_state_dir(target_dir) / "reviews.json"
+        self.reviews: Dict[str, Dict[str, Review]] = {}
+        if self.path.exists():
+            try:
+                data = json.loads(self.path.read_text())
+                for source, items in data.get("files", {}).items():
+                    self.reviews[source] = {
+                        key: Review.from_dict(review) for key, review in items.items()
+                    }
+            except (json.JSONDecodeError, KeyError, TypeError, ValueError) as e:
+                logger.warning(f"Ignoring unreadable reviews {self.path}: {e}")
+
+    def for_source(self, source: Path) -> List[Review]:
+        return list(self.reviews.get(str(source), {}).values())
+
+    def file_review(self, source: Path) -> Optional[Review]:
+        return self.reviews.get(str(source), {}).get("")
+
+    def item_reviews(self, source: Path) -> List[Review]:
+        return [review for review in self.for_source(source) if review.item]
+
+    def is_current(self, review: Review, python_code: str) -> bool:
+        """Whether the Python source is still the one that was reviewed."""
+        return not review.source_hash or review.source_hash == hash_source(python_code)
+
+    def record(
+        self,
+        source: Path,
+        decision: ReviewDecision,
+        python_code: str,
+        rust_code: str = "",
+        item: Optional[str] = None,
+        comment: str = "",
+        rust_snippet: str = ""
+    ) -> Review:
+        review = Review(
+            source=str(source),
+            decision=decision,
+            item=item,
+            comment=comment,
+            source_hash=hash_source(python_code),
+            rust_hash=hash_rust(rust_code) if rust_code else "",
+            rust_snippet=rust_snippet
+        )
+        self.reviews.setdefault(str(source), {})[item or ""] = review
+        self.save()
+        return review
+
+    def clear(self, source: Path, decisions: Optional[List[ReviewDecision]] = None) -> None:
+        """Drop the reviews of a file, or only those with the given decisions."""
+        items = self.reviews.get(str(source), {})
+        for key in [k for k, r in items.items() if decisions is None or r.decision in decisions]:
+            del items[key]
+        if not items:
+            self.reviews.pop(str(source), None)
+        self.save()
+
+    def save(self) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_file = self.path.with_suffix(".json.tmp")
+        tmp_file.write_text(json.dumps({
+            "files": {
+                source: {key: review.to_dict() for key, review in items.items()}
+                for source, items in self.reviews.items()
+            }
+        }, indent=2))
+        os.replace(tmp_file, self.path)
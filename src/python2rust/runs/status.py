@@ -0,0 +1,145 @@
+from dataclasses import dataclass, field
+from enum import Enum
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from .checkpoint import FileCheckpoint, FileStatus, RunCheckpoint
+from .manifest import MigrationManifest, hash_source
+from .store import RunStore
+from ..analyzers import ImportGraph
+
+
+class ModuleState(str, Enum):
+    """Overall state of a Python module across runs."""
+    NOT_STARTED = "not started"
+    IN_PROGRESS = "in progress"
+    GENERATED = "generated"            # code exists, verification did not pass
+    BUILD_FAILING = "build failing"
+    TESTS_FAILING = "tests failing"
+    FAILED = "failed"                  # no usable code was produced
+    VERIFIED = "verified"
+    STALE = "stale"                    # verified, but the source changed since
+
+
+@dataclass
+class ModuleStatus:
+    """State of one module and the run it was last seen in."""
+    source: Path
+    module: str
+    state: ModuleState
+    run_id: Optional[str] = None
+    updated_at: Optional[str] = None
+    detail: str = ""
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "source": str(self.source),
+            "module": self.module,
+            "state": self.state.value,
+            "run_id": self.run_id,
+            "updated_at": self.updated_at,
+            "detail": self.detail
+        }
+
+
+@dataclass
+class ProjectStatus:
+    """State of every module of a project, in migration order."""
+    modules: List[ModuleStatus] = field(default_factory=list)
+
+    def count(self, state: ModuleState) -> int:
+        return sum(1 for module in self.modules if module.state == state)
+
+    def format(self) -> str:
+        width = max((len(module.module) for module in self.modules), default=0)
+        lines = []
+        for module in self.modules:
+            line = f"{module.state.value:<14} {module.module:<{width}}"
+            if module.run_id:
+                line += f"  run {module.run_id}"
+            if module.detail:
+                line += f"  ({module.detail})"
+            lines.append(line.rstrip())
+        counts = [f"{self.count(state)} {state.value}" for state in ModuleState
+                  if self.count(state)]
+        lines.append("")
+        lines.append(f"{len(self.modules)} modules: " + ", ".join(counts))
+        return "\n".join(lines)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "modules": [module.to_dict() for module in self.modules],
+            "counts": {state.value: self.count(state) for state in ModuleState}
+        }
+
+
+def _latest_checkpoints(runs: List[RunCheckpoint]) -> Dict[str, Tuple[str, FileCheckpoint]]:
+    """Most recent run that touched each source file."""
+    latest = {}
+    for run in runs:
+        for source, file in run.files.items():
+            if file.status != FileStatus.PENDING or source not in latest:
+                latest[source] = (run.run_id, file)
+    return latest
+
+
+def _state_of(file: FileCheckpoint) -> ModuleState:
+    if file.status == FileStatus.PENDING:
+        return ModuleState.NOT_STARTED
+    if file.status == FileStatus.IN_PROGRESS:
+        return ModuleState.IN_PROGRESS
+    if file.status == FileStatus.FAILED:
+        return ModuleState.FAILED
+    if file.status == FileStatus.CANCELLED and not file.has_outputs:
+        return ModuleState.NOT_STARTED
+    if file.stage == "build":
+        return ModuleState.BUILD_FAILING
+    if file.stage == "test":
+        return ModuleState.TESTS_FAILING
+    return ModuleState.GENERATED
+
+
+def _source_changed(source: Path, source_hash: str) -> bool:
+    if not source_hash:
+        return False  # recorded before source hashes were kept
+    try:
+        return hash_source(source.read_text()) != source_hash
+    except OSError:
+        return True
+
+
+def collect_status(target_dir: Path, graph: ImportGraph) -> ProjectStatus:
+    """Combine run checkpoints and the manifest into a state per module."""
+    manifest = MigrationManifest(target_dir)
+    latest = _latest_checkpoints(RunStore(target_dir).list_runs())
+    status = ProjectStatus()
+    changed = set()
+
+    for source in graph.topological_order():
+        module = ModuleStatus(source=source, module=graph.names[source],
+                              state=ModuleState.NOT_STARTED)
+        run_id, file = latest.get(str(source), (None, None))
+        entry = manifest.entries.get(str(source))
+        if file is not None:
+            module.run_id, module.updated_at = run_id, file.updated_at
+
+        if file is not None and file.status != FileStatus.VERIFIED:
+            module.state = _state_of(file)
+            if file.error and module.state != ModuleState.IN_PROGRESS:
+                module.detail = file.error.splitlines()[0]
+        elif file is not None or entry is not None:
+            module.state = ModuleState.VERIFIED
+            changed_dependencies = [
+                graph.names[d] for d in graph.ordered_dependencies(source) if d in changed
+            ]
+            if entry is not None and _source_changed(source, entry.source_hash):
+                module.state = ModuleState.STALE
+                module.detail = "source changed"
+            elif changed_dependencies:
+                module.state = ModuleState.STALE
+                module.detail = f"dependency changed: {', '.join(changed_dependencies)}"
+
+        if module.state == ModuleState.STALE:
+            changed.add(source)
+        status.modules.append(module)
+    return status
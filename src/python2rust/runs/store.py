@@ -0,0 +1,105 @@
+import hashlib
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from .checkpoint import FileCheckpoint, RunCheckpoint
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+STATE_DIR_NAME = ".python2rust"
+
+
+def get_state_dir(target_dir: Path) -> Path:
+    """Directory holding python2rust bookkeeping for a target directory."""
+    return Path(target_dir) / STATE_DIR_NAME
+
+
+class RunStore:
+    """Reads and writes run checkpoints under <target_dir>/.python2rust/runs."""
+
+    def __init__(self, target_dir: Path):
+        self.target_dir = Path(target_dir)
+        self.runs_dir = get_state_dir(self.target_dir) / "runs"
+
+    def run_dir(self, run_id: str) -> Path:
+        return self.runs_dir / run_id
+
+    def _new_run_id(self) -> str:
+        base = datetime.now().strftime("%Y%m%d_%H%M%S")
+        run_id, suffix = base, 1
+        while self.run_dir(run_id).exists():
+            run_id = f"{base}_{suffix}"
+            suffix += 1
+        return run_id
+
+    def create(self, output_dirs: Dict[Path, Path],
+               prompt_version: Optional[str] = None) -> RunCheckpoint:
+        """Start a new run for the given source -> output directory mapping."""
+        checkpoint = RunCheckpoint(run_id=self._new_run_id(), target_dir=str(self.target_dir),
+                                   prompt_version=prompt_version)
+        for source, output_dir in output_dirs.items():
+            checkpoint.files[str(source)] = FileCheckpoint(
+                source=str(source), output_dir=str(output_dir))
+        self.run_dir(checkpoint.run_id).mkdir(parents=True, exist_ok=True)
+        self.save(checkpoint)
+        logger.info(f"Started run {checkpoint.run_id}")
+        return checkpoint
+
+    def save(self, checkpoint: RunCheckpoint) -> None:
+        """Atomically write the checkpoint so a crash never leaves it half-written."""
+        checkpoint.updated_at = datetime.now().isoformat()
+        run_dir = self.run_dir(checkpoint.run_id)
+        run_dir.mkdir(parents=True, exist_ok=True)
+        tmp_file = run_dir / "checkpoint.json.tmp"
+        tmp_file.write_text(json.dumps(checkpoint.to_dict(), indent=2))
+        os.replace(tmp_file, run_dir / "checkpoint.json")
+
+    def load(self, run_id: str) -> RunCheckpoint:
+        checkpoint_file = self.run_dir(run_id) / "checkpoint.json"
+        if not checkpoint_file.exists():
+            raise FileNotFoundError(f"No checkpoint for run {run_id} in {self.runs_dir}")
+        return RunCheckpoint.from_dict(json.loads(checkpoint_file.read_text()))
+
+    def list_runs(self) -> List[RunCheckpoint]:
+        """All runs, oldest first."""
+        if not self.runs_dir.exists():
+            return []
+        runs = []
+        for run_dir in sorted(self.runs_dir.iterdir()):
+            if (run_dir / "checkpoint.json").exists():
+                try:
+                    runs.append(self.load(run_dir.name))
+                except (json.JSONDecodeError, KeyError) as e:
+                    logger.warning(f"Skipping unreadable checkpoint {run_dir}: {e}")
+        return runs
+
+    def latest(self) -> Optional[RunCheckpoint]:
+        runs = self.list_runs()
+        return runs[-1] if runs else None
+
+    def save_outputs(
+        self,
+        checkpoint: RunCheckpoint,
+        source: str,
+        rust_code: str,
+        toml_content: Optional[str]
+    ) -> None:
+        """Store generated code for a file inside the run directory."""
+        key = hashlib.sha1(source.encode()).hexdigest()[:10]
+        outputs_dir = self.run_dir(checkpoint.run_id) / "outputs" / f"{Path(source).stem}_{key}"
+        (outputs_dir / "src").mkdir(parents=True, exist_ok=True)
+        (outputs_dir / "src" / "main.rs").write_text(rust_code)
+        (outputs_dir / "Cargo.toml").write_text(toml_content or "")
+        checkpoint.files[source].outputs_dir = str(outputs_dir)
+
+    def load_outputs(self, file: FileCheckpoint) -> Tuple[str, str]:
+        """Read generated code saved for a file."""
+        outputs_dir = Path(file.outputs_dir)
+        return (
+            (outputs_dir / "src" / "main.rs").read_text(),
+            (outputs_dir / "Cargo.toml").read_text()
+        )
@@ -0,0 +1,153 @@
+"""
+SARIF export of translation findings, for annotations in code review tools.
+"""
+import os
+import re
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+from .analyzers import SourceMap, collect_symbols, scan_rust_quality
+from .analyzers.rust_quality import QUALITY_PATTERNS
+
+SARIF_VERSION = "2.1.0"
+SARIF_SCHEMA = "https://json.schemastore.org/sarif-2.1.0.json"
+
+LEVELS = {"high": "error", "medium": "warning", "low": "note"}
+
+# rule id -> short description
+RULES = {
+    "divergence": "The Rust translation may behave differently from the Python source",
+    "translation-risk": "A Python construct that is hard to translate faithfully",
+    "unmapped-definition": "A Python definition has no matching item in the generated Rust",
+    **{construct.rstrip("!"): message for construct, _, message, _ in QUALITY_PATTERNS},
+}
+
+RISK_WARNING = re.compile(r"^risk: line (?P<line>\d+): (?P<message>.*)$")
+
+
+@dataclass
+class Finding:
+    """One annotation: a rule violated at a file location, with optional related locations."""
+    rule: str
+    message: str
+    level: str
+    path: Path
+    line: int
+    end_line: Optional[int] = None
+    related: List[Tuple[Path, int, str]] = field(default_factory=list)
+
+
+def collect_findings(source: Path, output_dir: Path) -> List[Finding]:
+    """Divergence warnings and unmapped definitions from the source map, and quality issues
+    of the generated code, for one migrated file."""
+    rust_file = output_dir / "src" / "main.rs"
+    if not rust_file.exists():
+        return []
+    findings = []
+    for risk in scan_rust_quality(rust_file.read_text()):
+        findings.append(Finding(
+            rule=risk.construct.rstrip("!"),
+            message=risk.message,
+            level=LEVELS[risk.severity],
+            path=rust_file,
+            line=risk.line
+        ))
+
+    map_file = rust_file.with_name(f"{rust_file.name}.map.json")
+    if not map_file.exists():
+        return findings
+    source_map = SourceMap.load(map_file)
+    for mapping in source_map.mappings:
+        rust = mapping.rust
+        for warning in mapping.warnings:
+            risk = RISK_WARNING.match(warning)
+            if risk:
+                findings.append(Finding(
+                    rule="translation-risk",
+                    message=f"{risk['message']} (translated as {rust.kind} {rust.name})",
+                    level="note",
+                    path=source,
+                    line=int(risk["line"]),
+                    related=[(rust_file, rust.start_line, f"Rust {rust.kind} {rust.name}")]
+                ))
+                continue
+            related = []
+            if mapping.python is not None:
+                python = mapping.python
+                related.append((source, python.start_line, f"Python {python.kind} {python.name}"))
+            findings.append(Finding(
+                rule="divergence",
+                message=f"{rust.name}: {warning}",
+                level="warning",
+                path=rust_file,
+                line=rust.start_line,
+                end_line=rust.end_line,
+                related=related
+            ))
+
+    python_code = source.read_text() if source.exists() else ""
+    symbols = {symbol.qualname: symbol for symbol in collect_symbols(python_code)}
+    for name in source_map.unmapped_python:
+        symbol = symbols.get(name)
+        findings.append(Finding(
+            rule="unmapped-definition",
+            message=f"{name} has no matching Rust item; check it was translated",
+            level="note",
+            path=source,
+            line=symbol.start_line if symbol else 1,
+            end_line=symbol.end_line if symbol else None
+        ))
+    return findings
+
+
+def _artifact(path: Path, root: Path) -> Dict[str, Any]:
+    resolved = Path(path).resolve()
+    try:
+        return {"uri": Path(os.path.relpath(resolved, root)).as_posix(), "uriBaseId": "%SRCROOT%"}
+    except ValueError:  # another drive on Windows
+        return {"uri": resolved.as_uri()}
+
+
+def _location(path: Path, line: int, root: Path, end_line: Optional[int] = None) -> Dict[str, Any]:
+    region = {"startLine": max(1, line)}
+    if end_line:
+        region["endLine"] = max(region["startLine"], end_line)
+    return {"physicalLocation": {"artifactLocation": _artifact(path, root), "region": region}}
+
+
+def to_sarif(findings: List[Finding], root: Path, version: str = "") -> Dict[str, Any]:
+    """A SARIF 2.1.0 log with one run; paths are relative to root (%SRCROOT%)."""
+    root = Path(root).resolve()
+    results = []
+    for finding in findings:
+        result = {
+            "ruleId": finding.rule,
+            "level": finding.level,
+            "message": {"text": finding.message},
+            "locations": [_location(finding.path, finding.line, root, finding.end_line)]
+        }
+        if finding.related:
+            result["relatedLocations"] = [
+                {"id": index, **_location(path, line, root), "message": {"text": text}}
+                for index, (path, line, text) in enumerate(finding.related)
+            ]
+        results.append(result)
+
+    return {
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {"driver": {
+                "name": "python2rust",
+                "version": version,
+                "informationUri": "https://github.com/obierlaire/python2rust",
+                "rules": [
+                    {"id": rule, "shortDescription": {"text": description}}
+                    for rule, description in RULES.items()
+                ]
+            }},
+            "originalUriBaseIds": {"%SRCROOT%": {"uri": root.as_uri() + "/"}},
+            "results": results
+        }]
+    }
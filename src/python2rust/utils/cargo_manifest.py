@@ -0,0 +1,65 @@
+"""
+Edits of generated Cargo.toml files that keep the model's formatting.
+"""
+import re
+from typing import Dict, List
+
+DEPENDENCY_PATTERN = re.compile(r"^\s*([A-Za-z0-9_-]+)\s*=")
+# Crates the generated tests may use -> their dev-dependency line
+TEST_CRATES = {"proptest": 'proptest = "1"'}
+
+
+def section_entries(manifest: str, section: str) -> Dict[str, str]:
+    """Entries of a section of a manifest, by name: their lines."""
+    found: Dict[str, str] = {}
+    current = None
+    for line in manifest.splitlines():
+        if line.strip().startswith("["):
+            current = line.strip()
+        elif current == section:
+            match = DEPENDENCY_PATTERN.match(line)
+            if match:
+                found[match.group(1)] = line.strip()
+    return found
+
+
+def add_to_section(manifest: str, section: str, entries: List[str]) -> str:
+    """The manifest with lines appended to a section, created at the end when missing."""
+    if not entries:
+        return manifest
+    lines = manifest.rstrip("\n").splitlines()
+    if section not in (line.strip() for line in lines):
+        return "\n".join(lines + ["", section, *entries]) + "\n"
+    start = next(i for i, line in enumerate(lines) if line.strip() == section)
+    end = next((i for i in range(start + 1, len(lines)) if lines[i].strip().startswith("[")),
+               len(lines))
+    while end > start + 1 and not lines[end - 1].strip():
+        end -= 1
+    return "\n".join(lines[:end] + entries + lines[end:]) + "\n"
+
+
+def add_test_dependencies(rust_code: str, manifest: str) -> str:
+    """The manifest with dev-dependencies on the test crates the code uses but does not declare."""
+    declared = {
+        **section_entries(manifest, "[dependencies]"),
+        **section_entries(manifest, "[dev-dependencies]")
+    }
+    missing = [
+        line for crate, line in TEST_CRATES.items()
+        if crate not in declared and re.search(rf"\b{crate}(::|!)", rust_code)
+    ]
+    return add_to_section(manifest, "[dev-dependencies]", missing)
+
+
+def replace_entries(manifest: str, section: str, entries: Dict[str, str]) -> str:
+    """The manifest with the lines of the named entries of a section replaced."""
+    lines = manifest.splitlines()
+    current = None
+    for index, line in enumerate(lines):
+        if line.strip().startswith("["):
+            current = line.strip()
+            continue
+        match = DEPENDENCY_PATTERN.match(line)
+        if current == section and match and match.group(1) in entries:
+            lines[index] = entries[match.group(1)]
+    return "\n".join(lines) + ("\n" if manifest.endswith("\n") else "")
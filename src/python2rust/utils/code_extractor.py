@@ -1,9 +1,29 @@
 import re
-from typing import Tuple
+from pathlib import PurePosixPath
+from typing import Any, Dict, List, Optional, Tuple
+
+from pydantic import BaseModel, Field
+
 from ..utils.logging import setup_logger
 
 logger = setup_logger()
 
+
+class GeneratedFile(BaseModel):
+    """One file of the generated crate."""
+    path: str = Field(description="Path in the crate, e.g. src/main.rs or Cargo.toml")
+    content: str = Field(description="Complete contents of the file")
+
+
+class GeneratedCode(BaseModel):
+    """Files of the generated Rust crate, returned instead of fenced code blocks."""
+    files: List[GeneratedFile] = Field(description="src/main.rs, Cargo.toml and "
+                                                   "any other crate files")
+    notes: str = Field(default="", description="Translation caveats worth reviewing")
+    confidence: Optional[float] = Field(
+        default=None, ge=0, le=1,
+        description="Confidence that the code is a faithful translation, 0 to 1")
+
 class CodeExtractor:
     """Utility for extracting code blocks from LLM responses."""
     
@@ -39,4 +59,54 @@ class CodeExtractor:
             
         except Exception as e:
             logger.exception(f"Failed to extract code blocks: {e}")
-            raise ValueError(f"Code extraction failed: {str(e)}")
\ No newline at end of file
+            raise ValueError(f"Code extraction failed: {str(e)}")
+    def extract_files(self, generated: Any) -> Tuple[str, Optional[str]]:
+        """Rust code and Cargo.toml of a structured GeneratedCode answer.
+
+        The crate root is src/main.rs, or else src/lib.rs or the first Rust file;
+        the other Rust files are inlined into it as modules.
+
+        Raises:
+            ValueError: If the answer has no Rust file
+        """
+        if generated is None:
+            raise ValueError("Model did not answer with the generated_code schema")
+        if not isinstance(generated, GeneratedCode):
+            generated = GeneratedCode.model_validate(generated)
+
+        toml_content = None
+        rust_files: Dict[str, str] = {}
+        for file in generated.files:
+            path = PurePosixPath(file.path.replace("\\", "/").lstrip("./"))
+            if path.name == "Cargo.toml":
+                toml_content = file.content.strip()
+            elif path.suffix == ".rs":
+                rust_files[str(path)] = file.content.strip()
+        if not rust_files:
+            raise ValueError("No Rust file in structured response")
+
+        root = next(
+            (path for name in ("main.rs", "lib.rs") for path in rust_files
+             if PurePosixPath(path).name == name),
+            next(iter(rust_files)))
+        rust_code = rust_files.pop(root)
+        for path, content in rust_files.items():
+            rust_code = self._inline_module(rust_code, PurePosixPath(path), content)
+
+        if generated.notes:
+            logger.info(f"Model notes: {generated.notes}")
+        if generated.confidence is not None:
+            logger.info(f"Model confidence: {generated.confidence:.2f}")
+        return rust_code, toml_content
+
+    @staticmethod
+    def _inline_module(rust_code: str, path: PurePosixPath, content: str) -> str:
+        name = path.parent.name if path.name == "mod.rs" else path.stem
+        declaration = re.compile(rf"^(\s*)((?:pub(?:\([^)]*\))?\s+)?)mod\s+{re.escape(name)}\s*;",
+                                 re.MULTILINE)
+        body = "\n".join(f"    {line}" if line else "" for line in content.splitlines())
+        match = declaration.search(rust_code)
+        if match is None:
+            return f"{rust_code}\n\nmod {name} {{\n{body}\n}}"
+        module = f"{match.group(1)}{match.group(2)}mod {name} {{\n{body}\n}}"
+        return rust_code[:match.start()] + module + rust_code[match.end():]
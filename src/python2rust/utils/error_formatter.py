@@ -1,3 +1,7 @@
+import re
+from typing import List
+
+
 def format_error_for_fix(error_text: str) -> str:
     """Extract essential error information from build/test output."""
     if not error_text:
@@ -71,3 +75,29 @@ def format_error_for_fix(error_text: str) -> str:
         error_lines.extend(error_block)
 
     return '\n'.join(error_lines)
+
+
+DIAGNOSTIC_START = re.compile(r"^(error|warning)(\[[^\]]*\])?: ")
+# Summary lines of a failed clippy run, not diagnostics of the code
+DIAGNOSTIC_SUMMARIES = ("could not compile", "aborting due to",
+                        "warnings emitted", "warning emitted", "build failed")
+
+
+def clippy_diagnostics(output: str, limit: int = 30) -> List[str]:
+    """The diagnostics of clippy output, each with its location, code excerpt and help, verbatim."""
+    diagnostics: List[str] = []
+    block: List[str] = []
+    for line in (output or "").split("\n"):
+        if DIAGNOSTIC_START.match(line):
+            if block:
+                diagnostics.append("\n".join(block).rstrip())
+            summary = any(marker in line for marker in DIAGNOSTIC_SUMMARIES)
+            block = [] if summary else [line]
+        elif block and line.strip():
+            block.append(line)
+        elif block:
+            diagnostics.append("\n".join(block).rstrip())
+            block = []
+    if block:
+        diagnostics.append("\n".join(block).rstrip())
+    return diagnostics[:limit]
@@ -0,0 +1,65 @@
+import asyncio
+from pathlib import Path
+from typing import Callable, Dict, List, Optional, Tuple
+
+from .logging import setup_logger
+
+logger = setup_logger()
+
+Snapshot = Dict[Path, Tuple[int, int]]
+
+
+class FileWatcher:
+    """Polls files for additions, edits and removals, without any extra dependency."""
+
+    def __init__(
+        self,
+        list_files: Callable[[], List[Path]],
+        interval: float = 1.0,
+        debounce: float = 0.5
+    ):
+        self.list_files = list_files
+        self.interval = interval
+        self.debounce = debounce
+
+    def snapshot(self) -> Snapshot:
+        """Modification time and size of each watched file."""
+        snapshot = {}
+        for path in self.list_files():
+            try:
+                stat = path.stat()
+            except OSError:
+                continue
+            snapshot[path] = (stat.st_mtime_ns, stat.st_size)
+        return snapshot
+
+    @staticmethod
+    def changes(previous: Snapshot, current: Snapshot) -> List[Path]:
+        """Files added, modified or removed between two snapshots."""
+        return sorted(
+            path for path in set(previous) | set(current)
+            if previous.get(path) != current.get(path)
+        )
+
+    async def wait_for_changes(
+        self,
+        previous: Snapshot,
+        stop: Optional[asyncio.Event] = None
+    ) -> Tuple[List[Path], Snapshot]:
+        """Block until files change, then wait for edits to settle.
+
+        Returns no changes when stop is set first.
+        """
+        stop = stop or asyncio.Event()
+        while not stop.is_set():
+            try:
+                await asyncio.wait_for(stop.wait(), timeout=self.interval)
+            except asyncio.TimeoutError:
+                pass
+            current = self.snapshot()
+            if self.changes(previous, current):
+                # Editors often write in several steps; let them finish
+                await asyncio.sleep(self.debounce)
+                current = self.snapshot()
+                return self.changes(previous, current), current
+        return [], previous
@@ -0,0 +1,58 @@
+"""
+Requests recorded in HAR files (HTTP Archive, as saved by browsers and proxies), replayed by
+the comparison of web app responses.
+"""
+import json
+from collections import Counter
+from pathlib import Path
+from typing import List
+from urllib.parse import urlencode, urlsplit
+
+from ..config.settings import HttpCase
+
+# Headers describing the recorded connection rather than the request, set again when replaying
+CONNECTION_HEADERS = {
+    "host", "content-length", "connection", "keep-alive", "transfer-encoding", "upgrade",
+    "accept-encoding", "proxy-connection", "te"
+}
+
+
+def load_har(path: Path) -> List[HttpCase]:
+    """The requests of a HAR file made to its main host, the one most requests went to, in order.
+
+    Raises ValueError when the file is not a HAR archive.
+    """
+    try:
+        entries = json.loads(Path(path).read_text())["log"]["entries"]
+    except (OSError, ValueError, KeyError, TypeError) as e:
+        raise ValueError(f"{path} is not a HAR file: {e}")
+    requests = [entry["request"] for entry in entries if isinstance(entry, dict)
+                and "request" in entry]
+    urls = [urlsplit(request.get("url", "")) for request in requests]
+    hosts = Counter(url.netloc for url in urls if url.netloc)
+    if not hosts:
+        return []
+    host = hosts.most_common(1)[0][0]
+
+    cases = []
+    for request, url in zip(requests, urls):
+        if url.netloc != host:
+            continue
+        headers = {
+            header["name"]: header["value"]
+            for header in request.get("headers", [])
+            if not header["name"].startswith(":")
+            and header["name"].lower() not in CONNECTION_HEADERS
+        }
+        post_data = request.get("postData") or {}
+        body = post_data.get("text")
+        if not body and post_data.get("params"):
+            body = urlencode([(param["name"], param.get("value", ""))
+                              for param in post_data["params"]])
+        cases.append(HttpCase(
+            method=request.get("method", "GET").upper(),
+            path=(url.path or "/") + (f"?{url.query}" if url.query else ""),
+            body=body or None,
+            headers=headers
+        ))
+    return cases
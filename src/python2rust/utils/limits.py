@@ -0,0 +1,32 @@
+"""
+Memory limits of the processes verification starts from generated code.
+"""
+import os
+from typing import Callable, Optional
+
+try:
+    import resource
+except ImportError:  # Windows
+    resource = None
+
+
+def preexec(memory_mb: Optional[int] = None,
+            new_session: bool = False) -> Optional[Callable[[], None]]:
+    """The preexec_fn of a subprocess: its address space capped at memory_mb, inherited by its
+    children, and optionally a session of its own so that its process group can be killed.
+
+    None on Windows, where neither is available.
+    """
+    if os.name == 'nt':
+        return None
+    if memory_mb is None and not new_session:
+        return None
+
+    def limit() -> None:
+        if new_session:
+            os.setsid()
+        if memory_mb is not None and resource is not None:
+            size = memory_mb * 1024 * 1024
+            resource.setrlimit(resource.RLIMIT_AS, (size, size))
+
+    return limit
@@ -33,4 +33,10 @@ def setup_logger(log_dir: Path = Path("logs")) -> logging.Logger:
     logger.addHandler(debug_handler)
     logger.addHandler(console_handler)
     
-    return logger
\ No newline at end of file
+    return logger
+
+def set_console_level(level: int) -> None:
+    """Change what reaches the terminal; the debug log file still gets everything."""
+    for handler in logging.getLogger("python2rust").handlers:
+        if not isinstance(handler, logging.FileHandler):
+            handler.setLevel(level)
@@ -0,0 +1,147 @@
+'''
+Live progress of a migration on the terminal.
+'''
+import shutil
+import sys
+import time
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, TextIO
+
+from ..events import EventType, MigrationEvent, MigrationObserver
+
+SPINNER = "|/-\\"
+
+
+def progress_bar(done: int, total: int, width: int = 24) -> str:
+    """A [####----] bar for done out of total."""
+    filled = width if total <= 0 else min(width, done * width // total)
+    return "[" + "#" * filled + "-" * (width - filled) + "]"
+
+
+@dataclass
+class FileProgress:
+    """Where a running file is in the pipeline."""
+    name: str
+    stage: str = "starting"
+    iteration: int = 0
+    streamed_tokens: int = 0
+    started: float = field(default_factory=time.monotonic)
+
+
+class ProgressDisplay(MigrationObserver):
+    """Shows the overall progress, the stage and iteration of each running file and
+    the tail of the model output as it is streamed.
+
+    On a terminal the display is redrawn in place; on any other stream it prints
+    one line per stage change and no streamed output.
+    """
+
+    def __init__(
+        self,
+        total: int,
+        stream: TextIO = sys.stderr,
+        live: Optional[bool] = None,
+        refresh_interval: float = 0.1
+    ):
+        self.total = total
+        self.stream = stream
+        self.live = stream.isatty() if live is None else live
+        self.refresh_interval = refresh_interval
+        self.running: Dict[str, FileProgress] = {}
+        self.done = 0
+        self.failed = 0
+        self.tail = ""          # end of the response being streamed
+        self.tail_file: Optional[str] = None
+        self._drawn = 0
+        self._last_draw = 0.0
+        self._frame = 0
+
+    def _file(self, event: MigrationEvent) -> Optional[FileProgress]:
+        if event.file is None:
+            return None
+        if event.file not in self.running:
+            self.running[event.file] = FileProgress(name=Path(event.file).name)
+        return self.running[event.file]
+
+    def on_event(self, event: MigrationEvent) -> None:
+        file = self._file(event)
+        if file is None:
+            return
+        if event.type == EventType.STAGE_STARTED:
+            file.stage, file.iteration = event.data.get("stage", file.stage), 0
+            self._print(f"{file.name}: {file.stage}")
+        elif event.type == EventType.ITERATION_RETRIED:
+            file.iteration += 1
+            self._print(f"{file.name}: {file.stage}, iteration {file.iteration + 1}")
+        elif event.type == EventType.BUILD_ATTEMPT:
+            file.stage = f"cargo {event.data.get('command', 'build')}"
+            file.iteration = event.data.get("attempt", 0)
+        elif event.type == EventType.PROMPT_SENT:
+            self.tail, self.tail_file = "", event.file
+        elif event.type == EventType.TOKEN_RECEIVED:
+            file.streamed_tokens += 1
+            self.tail = (self.tail + event.data.get("token", ""))[-500:]
+            self.tail_file = event.file
+            self._draw(force=False)
+            return
+        elif event.type == EventType.FILE_FINISHED:
+            del self.running[event.file]
+            self.done += 1
+            if not event.data.get("success"):
+                self.failed += 1
+            status = "done" if event.data.get("success") else f"failed: {event.data.get('error')}"
+            self._print(f"{file.name}: {status}", permanent=True)
+            return
+        self._draw(force=True)
+
+    def lines(self, width: int) -> List[str]:
+        """The live block: overall bar, one line per running file and the streamed tail."""
+        failed = f", {self.failed} failed" if self.failed else ""
+        spinner = SPINNER[self._frame % len(SPINNER)]
+        lines = [f"{spinner} {progress_bar(self.done, self.total)} "
+                 f"{self.done}/{self.total} files{failed}"]
+        now = time.monotonic()
+        for file in self.running.values():
+            iteration = f" #{file.iteration + 1}" if file.iteration else ""
+            tokens = f", {file.streamed_tokens} tokens" if file.streamed_tokens else ""
+            lines.append(f"  {file.name}  {file.stage}{iteration}  "
+                         f"{now - file.started:.0f}s{tokens}")
+        if self.tail and self.tail_file in self.running:
+            last_line = self.tail.rstrip().splitlines()[-1:] or [""]
+            lines.append(f"  > {last_line[0].strip()}")
+        return [line[:width - 1] for line in lines]
+
+    def _clear(self) -> None:
+        if self._drawn:
+            self.stream.write("\x1b[1A\x1b[2K" * self._drawn)
+            self._drawn = 0
+
+    def _draw(self, force: bool) -> None:
+        if not self.live:
+            return
+        now = time.monotonic()
+        if not force and now - self._last_draw < self.refresh_interval:
+            return
+        self._last_draw = now
+        self._frame += 1
+        self._clear()
+        lines = self.lines(shutil.get_terminal_size().columns)
+        self.stream.write("\n".join(lines) + "\n")
+        self.stream.flush()
+        self._drawn = len(lines)
+
+    def _print(self, message: str, permanent: bool = False) -> None:
+        """A line above the live block, or a plain progress line when not on a terminal."""
+        if self.live and not permanent:
+            return
+        self._clear()
+        self.stream.write(f"[{self.done}/{self.total}] {message}\n")
+        self.stream.flush()
+        self._draw(force=True)
+
+    def close(self) -> None:
+        """Remove the live block once the migration is over."""
+        if self.live:
+            self._clear()
+            self.stream.flush()
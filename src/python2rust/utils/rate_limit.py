@@ -0,0 +1,160 @@
+"""
+Backoff on rate-limited model requests and limits on how many are sent.
+"""
+import asyncio
+import random
+import time
+from typing import Any, Awaitable, Callable, Dict, Optional
+
+from langchain_core.runnables import Runnable
+
+from .logging import setup_logger
+
+logger = setup_logger()
+
+RATE_LIMIT_STATUSES = {429, 529}
+RATE_LIMIT_NAMES = ("ratelimit", "overloaded", "resourceexhausted", "throttling", "toomanyrequests")
+RATE_LIMIT_MESSAGES = ("rate limit", "rate_limit", "overloaded", "too many requests",
+                       "throttl", "error code: 429")
+
+
+def is_rate_limit_error(error: BaseException) -> bool:
+    """Whether a provider error asks to slow down rather than reporting a bad request."""
+    status = getattr(error, "status_code", None) or getattr(error, "code", None)
+    if status in RATE_LIMIT_STATUSES:
+        return True
+    name = type(error).__name__.lower()
+    if any(marker in name for marker in RATE_LIMIT_NAMES):
+        return True
+    message = str(error).lower()
+    return any(marker in message for marker in RATE_LIMIT_MESSAGES)
+
+
+def retry_after(error: BaseException) -> Optional[float]:
+    """Seconds a provider asked to wait in its Retry-After header, if it did."""
+    headers = getattr(getattr(error, "response", None), "headers", None) or {}
+    try:
+        value = headers.get("retry-after")
+        return float(value) if value is not None else None
+    except (AttributeError, TypeError, ValueError):
+        return None
+
+
+class RequestLimiter:
+    """Paces the model requests of a run.
+
+    At most max_concurrent requests are in flight across all the files it is
+    shared by, a provider listed in requests_per_minute is sent requests no
+    faster than its rate, and a request answered with a rate-limit or overloaded
+    error is retried after an exponential backoff with jitter. A request taking
+    longer than timeout seconds is abandoned as stalled.
+    """
+
+    def __init__(
+        self,
+        max_concurrent: int = 8,
+        requests_per_minute: Optional[Dict[str, int]] = None,
+        max_retries: int = 6,
+        base_delay: float = 1.0,
+        max_delay: float = 60.0,
+        timeout: Optional[float] = None
+    ):
+        self.max_concurrent = max_concurrent
+        self.requests_per_minute = dict(requests_per_minute or {})
+        self.max_retries = max_retries
+        self.base_delay = base_delay
+        self.max_delay = max_delay
+        self.timeout = timeout
+        self._slots: Optional[asyncio.Semaphore] = None
+        self._loop: Optional[asyncio.AbstractEventLoop] = None
+        self._next_request: Dict[str, float] = {}
+
+    @classmethod
+    def from_settings(cls, settings: Any) -> "RequestLimiter":
+        return cls(
+            max_concurrent=settings.max_concurrent_requests,
+            requests_per_minute=settings.requests_per_minute,
+            max_retries=settings.max_request_retries,
+            base_delay=settings.retry_base_delay,
+            max_delay=settings.retry_max_delay,
+            timeout=settings.request_timeout
+        )
+
+    def _get_slots(self) -> asyncio.Semaphore:
+        loop = asyncio.get_running_loop()
+        if self._slots is None or self._loop is not loop:
+            self._loop = loop
+            self._slots = asyncio.Semaphore(self.max_concurrent)
+        return self._slots
+
+    async def _wait_turn(self, provider: str) -> None:
+        rate = self.requests_per_minute.get(provider)
+        if not rate:
+            return
+        now = time.monotonic()
+        start = max(now, self._next_request.get(provider, now))
+        self._next_request[provider] = start + 60.0 / rate
+        if start > now:
+            await asyncio.sleep(start - now)
+
+    def backoff(self, attempt: int, error: Optional[BaseException] = None) -> float:
+        """Seconds to wait before retry `attempt` (0-based): Retry-After when
+        given, else jittered."""
+        requested = retry_after(error) if error is not None else None
+        if requested is not None:
+            return min(requested, self.max_delay)
+        delay = min(self.max_delay, self.base_delay * 2 ** attempt)
+        return delay / 2 + random.uniform(0, delay / 2)
+
+    async def call(self, provider: str, request: Callable[[], Awaitable[Any]]) -> Any:
+        """Send a request once its turn comes, retrying it while it is rate limited."""
+        attempt = 0
+        while True:
+            await self._wait_turn(provider)
+            async with self._get_slots():
+                try:
+                    if self.timeout is None:
+                        return await request()
+                    return await asyncio.wait_for(request(), self.timeout)
+                except asyncio.TimeoutError:
+                    raise TimeoutError(f"{provider} request stalled: no response "
+                                       f"after {self.timeout:.0f}s") from None
+                except Exception as e:
+                    if attempt >= self.max_retries or not is_rate_limit_error(e):
+                        raise
+                    error = e
+            delay = self.backoff(attempt, error)
+            attempt += 1
+            logger.warning(
+                f"{provider} is rate limiting requests ({error}); "
+                f"retry {attempt}/{self.max_retries} in {delay:.1f}s")
+            await asyncio.sleep(delay)
+
+
+class LimitedModel(Runnable):
+    """A model whose async requests go through a RequestLimiter.
+
+    Other attributes are those of the wrapped model.
+    """
+
+    def __init__(self, llm: Any, limiter: RequestLimiter, provider: str):
+        self.llm = llm
+        self.limiter = limiter
+        self.provider = provider
+
+    def invoke(self, input: Any, config: Optional[Dict[str, Any]] = None, **kwargs: Any) -> Any:
+        return self.llm.invoke(input, config, **kwargs)
+
+    async def ainvoke(self, input: Any, config: Optional[Dict[str, Any]] = None,
+                      **kwargs: Any) -> Any:
+        return await self.limiter.call(self.provider,
+                                       lambda: self.llm.ainvoke(input, config, **kwargs))
+
+    def with_structured_output(self, schema: Any, **kwargs: Any) -> "LimitedModel":
+        return LimitedModel(self.llm.with_structured_output(schema, **kwargs),
+                            self.limiter, self.provider)
+
+    def __getattr__(self, name: str) -> Any:
+        if name.startswith("__"):
+            raise AttributeError(name)
+        return getattr(self.__dict__["llm"], name)
@@ -0,0 +1,233 @@
+import hashlib
+import json
+import re
+import sqlite3
+from contextlib import contextmanager
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterator, List, Optional, Sequence, Set
+
+from langchain_core.caches import BaseCache
+from langchain_core.load import dumps, loads
+
+from ..config.settings import MIGRATION_STEPS, Settings
+from ..prompts.loader import prompt_overrides
+from .logging import setup_logger
+
+logger = setup_logger()
+
+
+def hash_text(text: str) -> str:
+    return hashlib.sha256(text.encode()).hexdigest()
+
+
+class ReplayMissError(Exception):
+    """A prompt has no recorded response while replaying."""
+
+
+CACHE_FILE_NAME = "responses.sqlite"
+MODEL_PATTERN = re.compile(r"""['"]?(?:model_name|model_id|model|"""
+                           r"""deployment_name)['"]?\s*[:=]\s*['"]([^'"]+)['"]""")
+
+
+def model_of(llm_string: str) -> str:
+    """Model name in a LangChain llm_string, or "unknown"."""
+    match = MODEL_PATTERN.search(llm_string)
+    return match.group(1) if match else "unknown"
+
+
+@dataclass
+class CacheStats:
+    """Size and use of a response cache."""
+    path: Path
+    entries: int = 0
+    hits: int = 0
+    size_bytes: int = 0
+    models: Dict[str, int] = field(default_factory=dict)
+    oldest: Optional[str] = None
+    newest: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "path": str(self.path),
+            "entries": self.entries,
+            "hits": self.hits,
+            "size_bytes": self.size_bytes,
+            "models": self.models,
+            "oldest": self.oldest,
+            "newest": self.newest
+        }
+
+    def format(self) -> str:
+        lines = [
+            f"Response cache: {self.path}",
+            f"Entries: {self.entries} ({self.size_bytes / 1024:.1f} KiB)",
+            f"Hits: {self.hits}"
+        ]
+        if self.oldest:
+            lines.append(f"Recorded: {self.oldest} to {self.newest}")
+        if self.models:
+            lines.append("Models:")
+            lines.extend(f"  {model}: {count}" for model, count in sorted(self.models.items()))
+        return "\n".join(lines)
+
+
+class ResponseCache(BaseCache):
+    """LangChain cache storing every model response in <cache_dir>/responses.sqlite.
+
+    Responses are keyed by a hash of the prompt and the model with its
+    parameters, so re-runs and resumes reuse them instead of paying for the same
+    request; without reuse responses are only recorded. Unless serve_repeats is
+    set, a stored response is served once per run: a prompt sent again in the
+    same run is a retry after a bad answer and goes to the model. In replay mode a missing response
+    is an error instead of a model call, so a replayed migration never reaches the network.
+    """
+
+    def __init__(
+        self,
+        cache_dir: Path,
+        replay: bool = False,
+        reuse: bool = True,
+        serve_repeats: bool = True
+    ):
+        self.cache_dir = Path(cache_dir)
+        self.replay = replay
+        self.reuse = reuse
+        self.serve_repeats = serve_repeats
+        self.calls: List[Dict[str, Any]] = []
+        self._served: Set[str] = set()
+        self.cache_dir.mkdir(parents=True, exist_ok=True)
+        self.path = self.cache_dir / CACHE_FILE_NAME
+        with self._connect() as connection:
+            connection.execute(
+                "CREATE TABLE IF NOT EXISTS responses ("
+                "key TEXT PRIMARY KEY, prompt_hash TEXT, llm_string TEXT, model TEXT, "
+                "generations TEXT, recorded_at TEXT, hits INTEGER DEFAULT 0)"
+            )
+        self._import_json_files()
+
+    @contextmanager
+    def _connect(self) -> Iterator[sqlite3.Connection]:
+        # A connection per operation: files migrated concurrently share the cache
+        connection = sqlite3.connect(self.path, timeout=30)
+        try:
+            with connection:
+                yield connection
+        finally:
+            connection.close()
+
+    def _import_json_files(self) -> None:
+        """Move responses recorded as one JSON file each by earlier versions into the database."""
+        for path in self.cache_dir.glob("*.json"):
+            try:
+                record = json.loads(path.read_text())
+                self._store(path.stem, record["prompt_hash"], record["llm_string"],
+                            record["generations"], record.get("recorded_at"))
+            except (OSError, KeyError, json.JSONDecodeError) as e:
+                logger.warning(f"Skipping unreadable recorded response {path}: {e}")
+                continue
+            path.unlink()
+
+    def _key(self, prompt: str, llm_string: str) -> str:
+        return hash_text(f"{llm_string}\0{prompt}")
+
+    def _store(
+        self,
+        key: str,
+        prompt_hash: str,
+        llm_string: str,
+        generations: str,
+        recorded_at: Optional[str] = None
+    ) -> None:
+        with self._connect() as connection:
+            connection.execute(
+                "INSERT OR REPLACE INTO responses (key, prompt_hash, llm_string, model, "
+                "generations, recorded_at, hits) VALUES (?, ?, ?, ?, ?, ?, COALESCE((SELECT hits "
+                "FROM responses WHERE key = ?), 0))",
+                (key, prompt_hash, llm_string, model_of(llm_string), generations,
+                 recorded_at or datetime.now().isoformat(), key)
+            )
+
+    def lookup(self, prompt: str, llm_string: str) -> Optional[Sequence[Any]]:
+        if not (self.reuse or self.replay):
+            return None
+        key = self._key(prompt, llm_string)
+        with self._connect() as connection:
+            row = connection.execute(
+                "SELECT generations FROM responses WHERE key = ?", (key,)).fetchone()
+            if row is not None and (self.replay or self.serve_repeats or key not in self._served):
+                connection.execute("UPDATE responses SET hits = hits + 1 WHERE key = ?", (key,))
+            elif self.replay:
+                raise ReplayMissError(f"No recorded response for prompt {hash_text(prompt)[:12]}")
+            else:
+                return None
+        self._served.add(key)
+        self.calls.append({"prompt_hash": hash_text(prompt), "response": key, "replayed": True})
+        generations = loads(row[0])
+        for generation in generations:
+            message = getattr(generation, "message", None)
+            if getattr(message, "usage_metadata", None):
+                # Served from the cache, so nothing was spent on it against the budget
+                message.usage_metadata = None
+        return generations
+
+    def update(self, prompt: str, llm_string: str, return_val: Sequence[Any]) -> None:
+        key = self._key(prompt, llm_string)
+        self._store(key, hash_text(prompt), llm_string, dumps(list(return_val)))
+        self._served.add(key)
+        self.calls.append({"prompt_hash": hash_text(prompt), "response": key, "replayed": False})
+
+    def stats(self) -> CacheStats:
+        """Entries, hits and models of the stored responses."""
+        stats = CacheStats(self.path, size_bytes=self.path.stat().st_size)
+        with self._connect() as connection:
+            stats.entries, hits, stats.oldest, stats.newest = connection.execute(
+                "SELECT COUNT(*), SUM(hits), MIN(recorded_at), "
+                "MAX(recorded_at) FROM responses").fetchone()
+            stats.hits = hits or 0
+            stats.models = dict(connection.execute(
+                "SELECT model, COUNT(*) FROM responses GROUP BY model").fetchall())
+        return stats
+
+    def clear(self, **kwargs: Any) -> None:
+        with self._connect() as connection:
+            connection.execute("DELETE FROM responses")
+        self._served.clear()
+
+
+def prompt_hashes() -> Dict[str, str]:
+    """Hash of every prompt template shipped with the package."""
+    from .. import prompts
+
+    return {
+        name: hash_text(getattr(prompts, name).template)
+        for name in sorted(prompts.__all__)
+    }
+
+
+def write_reproducibility_manifest(settings: Settings, cache: ResponseCache, path: Path) -> None:
+    """Record models, prompt hashes and the responses used by a deterministic run."""
+    steps = {step: settings.llm_steps.model_for(step) for step in MIGRATION_STEPS}
+    manifest = {
+        "created_at": datetime.now().isoformat(),
+        "replay": cache.replay,
+        "models": {
+            step: {
+                "choice": choice.value,
+                "model": settings.llm_configs[choice].model,
+                "temperature": settings.model_temperature(choice)
+            }
+            for step, choice in steps.items()
+        },
+        "translation_passes": settings.translation_passes,
+        "prompts": prompt_hashes(),
+        "prompt_overrides": {
+            name: hash_text(path.read_text())
+            for name, path in prompt_overrides(settings.prompts_dir).items()
+        },
+        "responses_dir": str(cache.cache_dir),
+        "calls": cache.calls
+    }
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(json.dumps(manifest, indent=2))
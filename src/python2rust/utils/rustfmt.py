@@ -0,0 +1,80 @@
+"""
+rustfmt normalization of generated code, so that revisions differ only where the code does.
+"""
+import asyncio
+import re
+import shutil
+from typing import Any, Dict, Optional, Tuple
+
+from .logging import setup_logger
+
+logger = setup_logger()
+
+EDITION_PATTERN = re.compile(r'^\s*edition\s*=\s*"(\d{4})"', re.MULTILINE)
+DEFAULT_EDITION = "2021"
+
+
+class Rustfmt:
+    """Formats Rust code with rustfmt, read from stdin.
+
+    Without rustfmt installed code is left as it is, with one warning.
+    """
+
+    def __init__(self, command: str = "rustfmt", timeout: float = 60):
+        self.command = command
+        self.timeout = timeout
+        self._available: Optional[bool] = None
+
+    @property
+    def available(self) -> bool:
+        if self._available is None:
+            self._available = shutil.which(self.command) is not None
+            if not self._available:
+                logger.warning(f"{self.command} not found: generated code is not normalized "
+                               f"(rustup component add rustfmt)")
+        return self._available
+
+    @staticmethod
+    def edition(toml_content: Optional[str]) -> str:
+        match = EDITION_PATTERN.search(toml_content or "")
+        return match.group(1) if match else DEFAULT_EDITION
+
+    async def format(self, rust_code: str,
+                     toml_content: Optional[str] = None) -> Tuple[str, Optional[str]]:
+        """The formatted code, or the code unchanged with rustfmt's error when it does not parse."""
+        if not self.available:
+            return rust_code, None
+        process = await asyncio.create_subprocess_exec(
+            self.command, "--edition", self.edition(toml_content),
+            stdin=asyncio.subprocess.PIPE,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE
+        )
+        try:
+            stdout, stderr = await asyncio.wait_for(
+                process.communicate(rust_code.encode()), timeout=self.timeout)
+        except (asyncio.TimeoutError, asyncio.CancelledError) as e:
+            process.kill()
+            await process.wait()
+            if isinstance(e, asyncio.CancelledError):
+                raise
+            logger.warning(f"rustfmt timed out after {self.timeout:.0f}s, code left unformatted")
+            return rust_code, None
+        if process.returncode != 0:
+            return rust_code, stderr.decode(errors="replace").strip() or ("rustfmt exited with "
+                                                                          f"{process.returncode}")
+        return stdout.decode(), None
+
+    async def normalize(self, rust_code: Optional[str],
+                        toml_content: Optional[str] = None) -> Optional[str]:
+        """The formatted code, or the code unchanged when it does not parse."""
+        if not rust_code:
+            return rust_code
+        formatted, _ = await self.format(rust_code, toml_content)
+        return formatted
+
+    async def normalize_result(self, result: Dict[str, Any]) -> Dict[str, Any]:
+        """Normalize the rust_code of a generation or fix result, in place."""
+        result["rust_code"] = await self.normalize(result.get("rust_code"),
+                                                   result.get("toml_content"))
+        return result
@@ -0,0 +1,39 @@
+"""
+Per-stage request parameters applied to a shared model.
+"""
+from typing import Any, Dict, Optional
+
+from langchain_core.runnables import Runnable
+
+
+class StageModel(Runnable):
+    """A model whose requests carry the parameters of one pipeline stage.
+
+    The parameters (temperature, max_tokens, top_p, stop) are passed with each
+    request, overriding those the model was created with. Other attributes are
+    those of the wrapped model.
+    """
+
+    def __init__(self, llm: Any, parameters: Dict[str, Any]):
+        self.llm = llm
+        self.parameters = dict(parameters)
+
+    def _kwargs(self, kwargs: Dict[str, Any]) -> Dict[str, Any]:
+        # Chains pass stop=None when they have no stop sequences of their own
+        return {**self.parameters,
+                **{key: value for key, value in kwargs.items() if value is not None}}
+
+    def invoke(self, input: Any, config: Optional[Dict[str, Any]] = None, **kwargs: Any) -> Any:
+        return self.llm.invoke(input, config, **self._kwargs(kwargs))
+
+    async def ainvoke(self, input: Any, config: Optional[Dict[str, Any]] = None,
+                      **kwargs: Any) -> Any:
+        return await self.llm.ainvoke(input, config, **self._kwargs(kwargs))
+
+    def with_structured_output(self, schema: Any, **kwargs: Any) -> "StageModel":
+        return StageModel(self.llm.with_structured_output(schema, **kwargs), self.parameters)
+
+    def __getattr__(self, name: str) -> Any:
+        if name.startswith("__"):
+            raise AttributeError(name)
+        return getattr(self.__dict__["llm"], name)
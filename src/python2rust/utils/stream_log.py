@@ -0,0 +1,114 @@
+"""
+Streamed model output written to the debug artifacts while it arrives.
+"""
+from contextlib import contextmanager
+from contextvars import ContextVar
+from pathlib import Path
+from typing import Any, Dict, Iterator, List, Optional
+
+from langchain.callbacks.base import BaseCallbackHandler
+
+from .logging import setup_logger
+
+logger = setup_logger()
+
+PARTIAL_FILE_NAME = "partial.md"
+RESPONSE_FILE_NAME = "response.md"
+# Longest partial output put back into a retry prompt
+MAX_SEED_CHARS = 20000
+
+_stream_dir: ContextVar[Optional[Path]] = ContextVar("stream_dir", default=None)
+
+
+@contextmanager
+def streaming_to(directory: Path) -> Iterator[None]:
+    """Stream the model calls made inside the block into directory/partial.md."""
+    token = _stream_dir.set(Path(directory))
+    try:
+        yield
+    finally:
+        _stream_dir.reset(token)
+
+
+def partial_output(directory: Path) -> Optional[str]:
+    """Output of a call in directory that never completed, if any."""
+    path = Path(directory) / PARTIAL_FILE_NAME
+    try:
+        text = path.read_text()
+    except OSError:
+        return None
+    return text if text.strip() else None
+
+
+def seed_prompt(partial: str) -> str:
+    """Project context asking the model to pick up an interrupted output."""
+    if len(partial) > MAX_SEED_CHARS:
+        partial = partial[-MAX_SEED_CHARS:]
+    return (
+        "An earlier attempt at this step was interrupted after writing the output below. "
+        "Continue from it, correcting anything wrong, and reply with the complete result:\n"
+        f"```\n{partial}\n```"
+    )
+
+
+class StreamLogHandler(BaseCallbackHandler):
+    """Appends streamed tokens to <directory>/partial.md as they arrive.
+
+    The directory is the one set with streaming_to, or default_dir/<call id>/.
+    A completed call replaces it with its response in response.md; the partial
+    output of an interrupted call stays, so that a stalled or killed generation
+    can be inspected and used to seed the retry.
+    """
+
+    # Tokens must be written in order, not from executor threads
+    run_inline = True
+
+    def __init__(self, default_dir: Path):
+        super().__init__()
+        self.default_dir = Path(default_dir)
+        self._files: Dict[Any, Path] = {}
+
+    def _file(self, run_id: Any) -> Path:
+        if run_id not in self._files:
+            directory = _stream_dir.get() or self.default_dir / str(run_id)
+            self._files[run_id] = directory / PARTIAL_FILE_NAME
+        return self._files[run_id]
+
+    def on_llm_start(self, serialized: Dict[str, Any], prompts: List[str], **kwargs: Any) -> None:
+        path = self._file(kwargs.get("run_id"))
+        try:
+            path.parent.mkdir(parents=True, exist_ok=True)
+            path.write_text("")
+        except OSError as e:
+            logger.warning(f"Cannot write streamed output to {path}: {e}")
+
+    def on_llm_new_token(self, token: str, **kwargs: Any) -> None:
+        path = self._file(kwargs.get("run_id"))
+        try:
+            with open(path, "a") as f:
+                f.write(token)
+        except OSError:
+            pass
+
+    def on_llm_end(self, response: Any, **kwargs: Any) -> None:
+        path = self._files.pop(kwargs.get("run_id"), None)
+        if path is None:
+            return
+        try:
+            if path.parent.parent == self.default_dir:
+                # Calls outside a pipeline attempt keep their full response in the traces
+                path.unlink(missing_ok=True)
+                path.parent.rmdir()
+                return
+            completion = path.read_text() if path.exists() else ""
+            if getattr(response, "generations", None):
+                completion = response.generations[0][0].text or completion
+            path.with_name(RESPONSE_FILE_NAME).write_text(completion)
+            path.unlink(missing_ok=True)
+        except OSError as e:
+            logger.warning(f"Cannot store the response of {path.parent}: {e}")
+
+    def on_llm_error(self, error: BaseException, **kwargs: Any) -> None:
+        path = self._files.pop(kwargs.get("run_id"), None)
+        if path is not None and path.exists():
+            logger.warning(f"Model call failed ({error}); its partial output is in {path}")
@@ -111,7 +111,19 @@ class UnifiedTracker(BaseCallbackHandler):
             if hasattr(response, 'generations') and response.generations:
                 generation = response.generations[0][0]
                 logger.info(f"Generation info: {generation}")
-                
+
+                # Standard usage of chat models (Gemini, OpenAI, recent Anthropic)
+                usage = getattr(getattr(generation, 'message', None), 'usage_metadata', None)
+                if usage:
+                    prompt_tokens = usage.get('input_tokens', 0)
+                    completion_tokens = usage.get('output_tokens', 0)
+                    return {
+                        "prompt_tokens": prompt_tokens,
+                        "completion_tokens": completion_tokens,
+                        "total_tokens": usage.get('total_tokens')
+                        or prompt_tokens + completion_tokens
+                    }
+
                 # Try additional_kwargs first (newer format)
                 if hasattr(generation, 'additional_kwargs'):
                     kwargs = generation.additional_kwargs
@@ -198,9 +210,11 @@ class UnifiedTracker(BaseCallbackHandler):
 
     def on_llm_start(self, serialized: Dict[str, Any], prompts: List[str], **kwargs: Any) -> None:
         """Record start of LLM call."""
+        params = kwargs.get("invocation_params") or {}
         self.current_trace = {
             "timestamp": datetime.utcnow().isoformat(),
             "model": serialized.get("name", "unknown"),
+            "model_id": params.get("model") or params.get("model_name"),
             "start_time": datetime.utcnow().isoformat(),
             "prompts": prompts
         }
@@ -2,5 +2,10 @@
 from .migration_workflow import MigrationWorkflow
 from .build_workflow import BuildWorkflow
 from .test_workflow import TestWorkflow
+from .pass_pipeline import PassFailed, TranslationPipeline
+from .consensus import ConsensusGenerator
+from .chunked_generation import ChunkedGenerator, merge_manifests, stitch_rust
 
-__all__ = ['MigrationWorkflow', 'BuildWorkflow', 'TestWorkflow']
\ No newline at end of file
+__all__ = ['MigrationWorkflow', 'BuildWorkflow', 'TestWorkflow', 'PassFailed',
+           'TranslationPipeline', 'ConsensusGenerator', 'ChunkedGenerator',
+           'merge_manifests', 'stitch_rust']
\ No newline at end of file
@@ -1,198 +1,149 @@
 # workflows/build_workflow.py
 from langchain.schema.runnable import RunnableSequence
-from typing import Dict, Any, Optional
+from typing import Dict, Any, List, Optional, Tuple
 from ..utils.logging import setup_logger
-from ..utils.error_formatter import format_error_for_fix
 from ..builders import RustBuilder
+from ..checkers import CargoCheck, Checker, CheckResult, Clippy
 from ..builders.build_result import BuildResult
 from ..utils.build_output import ensure_build_output
+from ..events import EventBus, EventType
 
 logger = setup_logger()
 
 
 class BuildWorkflow:
-    def __init__(self, chains: Dict[str, Any], rust_builder: RustBuilder, state: "MigrationState"):
+    def __init__(
+        self,
+        chains: Dict[str, Any],
+        rust_builder: RustBuilder,
+        state: "MigrationState",
+        events: Optional[EventBus] = None,
+        checkers: Optional[List[Checker]] = None
+    ):
+        """checkers run in order, by default cargo check then clippy."""
         self.chains = chains
         self.rust_builder = rust_builder
         self.state = state
+        self.events = events or EventBus()
+        self.checkers: List[Checker] = (list(checkers) if checkers is not None
+                                        else [CargoCheck(), Clippy()])
         self.max_fix_attempts = 6
+        # checker name -> warnings of its last passing run, which checked the latest code
+        self.warnings: Dict[str, List[str]] = {}
+        # checker name -> metrics of its last passing run
+        self.metrics: Dict[str, Dict[str, Any]] = {}
 
     def setup(self) -> RunnableSequence:
         return RunnableSequence(
-            self._run_cargo_check,
-            self._apply_build_fixes_if_needed,
-            self._run_clippy,
-            self._apply_clippy_fixes_if_needed,
+            self._run_checkers,
             self._ensure_build_output
         )
 
-    async def _run_cargo_check(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
-        """Run cargo check on the code."""
-        logger.info("Running cargo check")
-        success, error, check_info = await self.rust_builder.check(
-            inputs["rust_code"],
-            inputs["toml_content"]
-        )
-
-        if success:
-            logger.info("Cargo check passed")
-            return inputs
-
-        logger.error(f"Cargo check failed: {error}")
-        inputs["build_error"] = error
-        inputs["build_info"] = check_info
-        return inputs
-
-    async def _apply_build_fixes_if_needed(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
-        """Apply fixes for build errors if needed."""
-        if "build_error" not in inputs:
-            return inputs
-
-        current_rust_code = inputs["rust_code"]
-        current_toml_content = inputs["toml_content"]
-
-        for attempt in range(self.max_fix_attempts):
-            logger.info(f"Attempting build fix {attempt + 1}")
-
-            try:
-                # Clean up the error message
-                clean_error = format_error_for_fix(inputs["build_error"])
-
-                # Better error structure
-                verification_result = {
-                    "critical_differences": {
-                        "build": clean_error,  # Direct error message
-                        "error_details": {
-                            "type": "compilation",
-                            "message": clean_error,
-                            "attempt": attempt + 1,
-                            "max_attempts": self.max_fix_attempts
-                        }
-                    }
-                }
-
-                fix_result = await self.chains["fix"].fix(
-                    rust_code=current_rust_code,
-                    toml_content=current_toml_content,
-                    verification_result=verification_result,
-                    analysis=inputs.get("analysis")
-                )
-
-                success, error, check_info = await self.rust_builder.check(
-                    fix_result["rust_code"],
-                    fix_result["toml_content"]
-                )
-
-                if success:
-                    logger.info("Build fix successful")
-                    inputs["rust_code"] = fix_result["rust_code"]
-                    inputs["toml_content"] = fix_result["toml_content"]
-                    inputs.pop("build_error")
-                    return inputs
-
-                current_rust_code = fix_result["rust_code"]
-                current_toml_content = fix_result["toml_content"]
-                inputs["build_error"] = error
-
-            except Exception as e:
-                logger.error(f"Build fix attempt {attempt + 1} failed: {e}")
-                continue
-
-        return inputs
-
-    async def _run_clippy(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
-        """Run clippy on the code."""
-        if "build_error" in inputs:
-            logger.info("Skipping Clippy due to build errors")
-            return inputs
+    async def _check(self, checker: Checker, rust_code: str, toml_content: str,
+                     attempt: int) -> CheckResult:
+        logger.info(f"Running {checker.name}")
+        self.events.emit(EventType.BUILD_ATTEMPT, command=checker.name, attempt=attempt)
+        result = await checker.run(self.rust_builder, rust_code, toml_content)
+        if result.success:
+            logger.info(f"{checker.name} passed")
+            self.warnings[checker.name] = result.warnings
+            self.metrics[checker.name] = result.metrics
+        else:
+            logger.error(f"{checker.name} failed: {result.output}")
+            self.events.emit(EventType.CHECK_FAILED, check=checker.name, error=result.output)
+        return result
+
+    async def _run_checkers(self, inputs: Dict[str, Any]) -> BuildResult:
+        """Run every checker in order, fixing the code when one fails."""
+        self.events.emit(EventType.STAGE_STARTED, stage="build")
+        rust_code = inputs["rust_code"]
+        toml_content = inputs["toml_content"]
+        build_info = None
+        self.warnings = {}
+        self.metrics = {}
+
+        for index, checker in enumerate(self.checkers):
+            result = await self._check(checker, rust_code, toml_content, attempt=0)
+            if result.success and result.rust_code is not None:
+                rust_code = result.rust_code
+            if not result.success:
+                rust_code, toml_content, failed, result = await self._apply_fixes(
+                    self.checkers[:index + 1], checker, result, rust_code, toml_content,
+                    inputs.get("analysis"))
+                if not result.success:
+                    return BuildResult(
+                        success=False,
+                        rust_code=rust_code,
+                        toml_content=toml_content,
+                        error=failed.failure(result, self.max_fix_attempts),
+                        build_info=result.info
+                    )
+            build_info = result.info
 
-        logger.info("Running Clippy checks")
-        success, error, clippy_info = await self.rust_builder.clippy(
-            inputs["rust_code"],
-            inputs["toml_content"]
+        return BuildResult(
+            success=True,
+            rust_code=rust_code,
+            toml_content=toml_content,
+            build_info=build_info,
+            warnings=[warning for warnings in self.warnings.values() for warning in warnings],
+            metrics={name: value for metrics in self.metrics.values()
+                     for name, value in metrics.items()}
         )
 
-        if success:
-            logger.info("Clippy checks passed")
-            return inputs
-
-        logger.error(f"Clippy checks failed: {error}")
-        inputs["clippy_error"] = error
-        inputs["clippy_info"] = clippy_info
-        return inputs
-
-    async def _apply_clippy_fixes_if_needed(self, inputs: Dict[str, Any]) -> BuildResult:
-        """Apply fixes for clippy errors if needed."""
-        if "build_error" in inputs:
-            return BuildResult(
-                success=False,
-                rust_code=inputs["rust_code"],
-                toml_content=inputs["toml_content"],
-                error=inputs["build_error"],
-                build_info=inputs.get("build_info")
-            )
-
-        if "clippy_error" not in inputs:
-            return BuildResult(
-                success=True,
-                rust_code=inputs["rust_code"],
-                toml_content=inputs["toml_content"],
-                build_info=inputs.get("clippy_info")
-            )
-
-        current_rust_code = inputs["rust_code"]
-        current_toml_content = inputs["toml_content"]
-        clippy_error = inputs["clippy_error"]
-
+    async def _apply_fixes(
+        self,
+        checkers: List[Checker],
+        failed: Checker,
+        result: CheckResult,
+        rust_code: str,
+        toml_content: str,
+        analysis: Optional[Dict[str, Any]]
+    ) -> Tuple[str, str, Checker, CheckResult]:
+        """Fix the code until the checkers up to the failed one pass, or the attempts run out.
+
+        A fix can break what an earlier checker accepted, so they all run again and
+        the next fix addresses the first failure. The fixed code is recorded once the
+        checkers normalized it (rustfmt). Returns the code, and the checker that
+        failed last with its result.
+        """
         for attempt in range(self.max_fix_attempts):
-            logger.info(f"Attempting Clippy fix {attempt + 1}")
+            logger.info(f"Attempting {failed.name} fix {attempt + 1}")
+            self.events.emit(
+                EventType.ITERATION_RETRIED,
+                stage=failed.name,
+                attempt=attempt + 1,
+                max_attempts=self.max_fix_attempts
+            )
 
             try:
-                # Format Clippy errors as critical differences
-                fix_result = await self.chains["fix"].fix(
-                    rust_code=current_rust_code,
-                    toml_content=current_toml_content,
-                    verification_result={
-                        "critical_differences": {
-                            "build": {
-                                "clippy": clippy_error
-                            }
-                        }
-                    },
-                    analysis=inputs.get("analysis")
+                fix_result = await self.chains["build_fix"].fix(
+                    rust_code=rust_code,
+                    toml_content=toml_content,
+                    verification_result=failed.fix_request(result, attempt + 1,
+                                                           self.max_fix_attempts),
+                    analysis=analysis
                 )
-
-                # Check if fix worked
-                success, error, clippy_info = await self.rust_builder.clippy(
-                    fix_result["rust_code"],
-                    fix_result["toml_content"]
-                )
-
-                if success:
-                    logger.info("Clippy fix successful")
-                    return BuildResult(
-                        success=True,
-                        rust_code=fix_result["rust_code"],
-                        toml_content=fix_result["toml_content"],
-                        build_info=clippy_info
-                    )
-
-                # Update current code for next attempt
-                current_rust_code = fix_result["rust_code"]
-                current_toml_content = fix_result["toml_content"]
-                clippy_error = error
-
             except Exception as e:
-                logger.error(f"Clippy fix attempt {attempt + 1} failed: {e}")
+                logger.error(f"{failed.name} fix attempt {attempt + 1} failed: {e}")
                 continue
-
-        return BuildResult(
-            success=False,
-            rust_code=current_rust_code,
-            toml_content=current_toml_content,
-            error=f"Failed to fix Clippy errors after {self.max_fix_attempts} attempts: {clippy_error}",
-            build_info=inputs.get("clippy_info")
-        )
+            origin = f"{failed.fix_label()} fix {attempt + 1}"
+            rust_code = fix_result["rust_code"]
+            toml_content = fix_result["toml_content"]
+
+            for checker in checkers:
+                result = await self._check(checker, rust_code, toml_content, attempt=attempt + 1)
+                if result.success and result.rust_code is not None:
+                    rust_code = result.rust_code
+                if not result.success:
+                    failed = checker
+                    break
+            else:
+                self.state.record_code(origin, rust_code)
+                logger.info(f"{failed.name} fix successful")
+                return rust_code, toml_content, failed, result
+            self.state.record_code(origin, rust_code)
+
+        return rust_code, toml_content, failed, result
 
     async def _ensure_build_output(self, build_result: BuildResult) -> BuildResult:
         """Ensure the build output directory is properly prepared."""
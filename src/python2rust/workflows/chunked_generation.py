@@ -0,0 +1,165 @@
+import asyncio
+import json
+import re
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+from ..analyzers.python_chunks import PythonChunk, outline, split_module
+from ..analyzers.rust_items import scan_items
+from ..events import EventBus, EventType
+from ..utils.cargo_manifest import add_to_section, section_entries
+from ..utils.logging import setup_logger
+from .consensus import Generator
+
+logger = setup_logger()
+
+USE_PATTERN = re.compile(r"^(pub(\([^)]*\))?\s+)?use\s")
+
+
+def stitch_rust(parts: List[str], main_part: Optional[int] = None) -> str:
+    """Join the Rust translations of the chunks of a module into one module.
+
+    `use` declarations and inner attributes are deduplicated at the top, and an
+    item defined by several chunks is kept once: its first definition, except
+    for `main`, which comes from main_part when given.
+    """
+    attributes: List[str] = []
+    uses: List[str] = []
+    bodies: List[str] = []
+    seen = set()
+    for index, rust_code in enumerate(parts):
+        lines = rust_code.splitlines()
+        dropped = set()
+        for item in scan_items(rust_code):
+            if item.kind in ("impl", "method"):
+                continue
+            key = (item.kind, item.name)
+            if item.name == "main" and item.kind == "fn" and main_part is not None:
+                keep = index == main_part
+            else:
+                keep = key not in seen
+            seen.add(key)
+            if not keep:
+                dropped.update(range(item.start_line, item.end_line + 1))
+
+        body = []
+        statement: List[str] = []
+        for number, line in enumerate(lines, start=1):
+            if number in dropped:
+                continue
+            stripped = line.strip()
+            if statement or USE_PATTERN.match(line):
+                statement.append(stripped)
+                if stripped.endswith(";"):
+                    use = " ".join(statement)
+                    statement = []
+                    if use not in uses:
+                        uses.append(use)
+            elif stripped.startswith("#!["):
+                if stripped not in attributes:
+                    attributes.append(stripped)
+            else:
+                body.append(line)
+        bodies.append("\n".join(body).strip("\n"))
+
+    header = "\n".join(part for part in ["\n".join(attributes), "\n".join(uses)] if part)
+    return "\n\n".join(part for part in [header, *bodies] if part) + "\n"
+
+
+def merge_manifests(manifests: List[str]) -> str:
+    """The first Cargo.toml, with the dependencies the others add."""
+    manifests = [manifest for manifest in manifests if manifest and manifest.strip()]
+    if not manifests:
+        return ""
+
+    base = manifests[0]
+    known = section_entries(base, "[dependencies]")
+    added = []
+    for manifest in manifests[1:]:
+        for name, line in section_entries(manifest, "[dependencies]").items():
+            if name not in known:
+                known[name] = line
+                added.append(line)
+    return add_to_section(base, "[dependencies]", added)
+
+
+class ChunkedGenerator:
+    """Translates modules too large for one generation call chunk by chunk.
+
+    The module is split at class and function boundaries; each chunk is
+    generated with the signatures of the rest of the module as context, and the
+    translations are stitched back into one module. Each chunk is stored under
+    <artifacts_dir>/<chunk>/ and the split in <artifacts_dir>/chunks.json.
+    """
+
+    def __init__(
+        self,
+        generate: Generator,
+        max_tokens: int,
+        artifacts_dir: Path,
+        events: Optional[EventBus] = None,
+        chunk_generator: Optional[Callable[[Path], Generator]] = None
+    ):
+        self.generate = generate
+        self.max_tokens = max_tokens
+        self.artifacts_dir = Path(artifacts_dir)
+        self.events = events or EventBus()
+        # Builds the generator of one chunk from its artifacts directory, so that
+        # concurrent chunks do not overwrite each other's pass artifacts
+        self.chunk_generator = chunk_generator or (lambda directory: generate)
+
+    def _context(self, python_code: str, chunk: PythonChunk, project_context: str) -> str:
+        signatures = outline(python_code, chunk)
+        if not signatures:
+            return project_context
+        shared = (
+            "This is one part of a larger module; the rest of the module is translated "
+            "separately and joined with this part into a single Rust file. Use these "
+            "definitions of the rest of the module without redefining them, and only "
+            "write a main function if this part has the `if __name__ == \"__main__\"` block:\n"
+            f"```python\n{signatures}\n```"
+        )
+        return f"{project_context}\n\n{shared}" if project_context else shared
+
+    def _store(self, chunk: PythonChunk, result: Dict[str, Any]) -> None:
+        directory = self.artifacts_dir / chunk.name
+        directory.mkdir(parents=True, exist_ok=True)
+        (directory / "input.py").write_text(chunk.code)
+        if result.get("rust_code"):
+            (directory / "main.rs").write_text(result["rust_code"])
+
+    async def run(
+        self,
+        python_code: str,
+        analysis: Dict[str, Any],
+        project_context: str = ""
+    ) -> Dict[str, Any]:
+        """Generate the module at once when it fits, otherwise chunk by chunk."""
+        chunks = split_module(python_code, self.max_tokens)
+        if len(chunks) == 1:
+            return await self.generate(python_code, analysis, project_context)
+
+        logger.info(f"Translating the module in {len(chunks)} chunks of about "
+                    f"{self.max_tokens} tokens")
+        self.events.emit(EventType.STAGE_STARTED, stage="chunking", chunks=len(chunks))
+        results = await asyncio.gather(*(
+            self.chunk_generator(self.artifacts_dir / chunk.name)(
+                chunk.code, analysis, self._context(python_code, chunk, project_context))
+            for chunk in chunks
+        ))
+        for chunk, result in zip(chunks, results):
+            self._store(chunk, result)
+
+        main_part = next((index for index, chunk in enumerate(chunks) if chunk.has_main), None)
+        self.artifacts_dir.mkdir(parents=True, exist_ok=True)
+        (self.artifacts_dir / "chunks.json").write_text(json.dumps([
+            {"name": chunk.name, "start_line": chunk.start_line, "end_line": chunk.end_line,
+             "symbols": chunk.symbols}
+            for chunk in chunks
+        ], indent=2))
+        return {
+            **results[0],
+            "rust_code": stitch_rust([result["rust_code"] for result in results], main_part),
+            "toml_content": merge_manifests([result.get("toml_content", "") for result in results]),
+            "chunks": len(chunks)
+        }
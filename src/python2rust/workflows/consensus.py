@@ -0,0 +1,110 @@
+import asyncio
+import json
+from pathlib import Path
+from typing import Any, Awaitable, Callable, Dict, Optional
+
+from ..events import EventBus, EventType
+from ..utils.logging import setup_logger
+
+logger = setup_logger()
+
+Generator = Callable[[str, Dict[str, Any], str], Awaitable[Dict[str, Any]]]
+
+
+class ConsensusGenerator:
+    """Translates with several models and keeps the candidate the referee scores best.
+
+    The referee is the verification step: every candidate is verified and scored
+    by its remaining differences. Each candidate is stored under
+    <artifacts_dir>/<model>/ and the scores in <artifacts_dir>/consensus.json.
+    """
+
+    def __init__(
+        self,
+        generators: Dict[str, Generator],
+        artifacts_dir: Path,
+        events: Optional[EventBus] = None
+    ):
+        self.generators = generators
+        self.artifacts_dir = Path(artifacts_dir)
+        self.events = events or EventBus()
+
+    def _store(self, name: str, result: Any) -> None:
+        directory = self.artifacts_dir / name
+        directory.mkdir(parents=True, exist_ok=True)
+        if isinstance(result, Exception):
+            (directory / "error.txt").write_text(f"{type(result).__name__}: {result}")
+            return
+        if result.get("rust_code"):
+            (directory / "main.rs").write_text(result["rust_code"])
+        if result.get("toml_content"):
+            (directory / "Cargo.toml").write_text(result["toml_content"])
+        if result.get("verification") is not None:
+            (directory / "verification.json").write_text(
+                json.dumps(result["verification"], indent=2, default=str))
+
+    async def _referee(
+        self,
+        name: str,
+        result: Dict[str, Any],
+        verify: Callable[[Dict[str, Any]], Awaitable[Dict[str, Any]]],
+        score: Callable[[Dict[str, Any]], float]
+    ) -> float:
+        try:
+            result["verification"] = await verify(result)
+        except Exception as e:
+            logger.warning(f"Verification of the {name} candidate failed: {e}")
+            return float('-inf')
+        return score(result["verification"])
+
+    async def run(
+        self,
+        python_code: str,
+        analysis: Dict[str, Any],
+        project_context: str,
+        verify: Callable[[Dict[str, Any]], Awaitable[Dict[str, Any]]],
+        score: Callable[[Dict[str, Any]], float]
+    ) -> Dict[str, Any]:
+        """Generate a candidate per model, verify them all and return the best one.
+
+        The result carries its verification and the name of the model that wrote it;
+        ties go to the model listed first.
+        """
+        names = list(self.generators)
+        logger.info(f"Generating {len(names)} candidates with {', '.join(names)}")
+        results = await asyncio.gather(
+            *(self.generators[name](python_code, analysis, project_context) for name in names),
+            return_exceptions=True
+        )
+        candidates = {}
+        for name, result in zip(names, results):
+            if isinstance(result, Exception):
+                logger.warning(f"The {name} candidate failed: {result}")
+                self._store(name, result)
+            elif not result.get("rust_code"):
+                logger.warning(f"The {name} candidate has no Rust code")
+                self._store(name, result)
+            else:
+                candidates[name] = result
+        if not candidates:
+            raise ValueError(f"Every consensus candidate failed ({', '.join(names)})")
+
+        self.events.emit(EventType.STAGE_STARTED, stage="consensus")
+        scores = await asyncio.gather(
+            *(self._referee(name, result, verify, score) for name, result in candidates.items()))
+        scored = dict(zip(candidates, scores))
+        for name, result in candidates.items():
+            self._store(name, result)
+
+        best = max(scored, key=lambda name: scored[name])
+        logger.info(f"Selected the {best} candidate (scores: "
+                    + ", ".join(f"{name}={value}" for name, value in scored.items()) + ")")
+        self.artifacts_dir.mkdir(parents=True, exist_ok=True)
+        (self.artifacts_dir / "consensus.json").write_text(json.dumps({
+            "candidates": names,
+            "failed": [name for name in names if name not in candidates],
+            "scores": {name: str(value) if value in (float('inf'), float('-inf')) else value
+                       for name, value in scored.items()},
+            "selected": best
+        }, indent=2))
+        return {**candidates[best], "candidate": best}
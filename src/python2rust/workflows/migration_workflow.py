@@ -1,7 +1,14 @@
 from langchain.schema.runnable import RunnableSequence
-from typing import Dict, Any
+from typing import Dict, Any, Optional
 from ..utils.logging import setup_logger
 from ..utils.build_output import update_rust_files
+from ..utils.cargo_manifest import add_test_dependencies
+from ..utils.rustfmt import Rustfmt
+from ..plugins import PluginManager
+from ..events import EventBus, EventType
+from .pass_pipeline import TranslationPipeline
+from .consensus import ConsensusGenerator
+from .chunked_generation import ChunkedGenerator
 
 logger = setup_logger()
 
@@ -9,9 +16,27 @@ logger = setup_logger()
 class MigrationWorkflow:
     """Handles the core migration workflow from Python to Rust."""
 
-    def __init__(self, chains: Dict[str, Any], state: "MigrationState"):
+    def __init__(
+        self,
+        chains: Dict[str, Any],
+        state: "MigrationState",
+        plugins: Optional[PluginManager] = None,
+        events: Optional[EventBus] = None,
+        pipeline: Optional[TranslationPipeline] = None,
+        consensus: Optional[ConsensusGenerator] = None,
+        chunker: Optional[ChunkedGenerator] = None,
+        formatter: Optional[Rustfmt] = None
+    ):
         self.chains = chains
         self.state = state
+        self.plugins = plugins or PluginManager()
+        self.events = events or EventBus()
+        self.pipeline = pipeline
+        self.consensus = consensus
+        # Wraps the pipeline or the generation chain for modules too large for one call
+        self.chunker = chunker
+        # Normalizes generated and fixed code before it is recorded and verified
+        self.formatter = formatter
         self.max_fix_attempts = 4
 
     def setup(self) -> RunnableSequence:
@@ -24,10 +49,22 @@ class MigrationWorkflow:
             # TODO update_rust_files
         )
 
+    def setup_verification(self) -> RunnableSequence:
+        """Setup verification of previously generated code, without generation."""
+        return RunnableSequence(
+            self._run_analysis,
+            self._run_verification,
+            self._apply_fixes_if_needed
+        )
+
     async def _run_analysis(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
         """Run analysis on Python code."""
         logger.info("Running code analysis")
-        analysis_result = await self.chains["analysis"].analyze(inputs["python_code"])
+        self.events.emit(EventType.STAGE_STARTED, stage="analysis")
+        analysis_result = await self.chains["analysis"].analyze(
+            inputs["python_code"],
+            project_context=self.plugins.analysis_context(inputs["python_code"])
+        )
         self.state.current_analysis = analysis_result
         inputs["analysis"] = analysis_result
         return inputs
@@ -35,11 +72,44 @@ class MigrationWorkflow:
     async def _run_generation(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
         """Generate initial Rust code."""
         logger.info("Generating Rust code")
-        generation_result = await self.chains["generation"].generate(
-            python_code=inputs["python_code"],
-            analysis=inputs["analysis"]
-        )
+        self.events.emit(EventType.STAGE_STARTED, stage="generation")
+        project_context = self.plugins.generation_context(
+            inputs["python_code"], inputs["analysis"])
+        tests_context = self.plugins.tests_context(inputs["python_code"])
+        if (tests_context and (self.pipeline is None or self.consensus is not None
+                               or self.chunker is not None)):
+            # Only the translation passes write the tests apart
+            project_context = "\n\n".join(part for part in [project_context, tests_context] if part)
+        if self.consensus is not None:
+            generation_result = await self.consensus.run(
+                inputs["python_code"], inputs["analysis"], project_context,
+                verify=lambda candidate: self._verify(
+                    inputs, candidate["rust_code"], candidate["toml_content"]),
+                score=self._calculate_fix_score
+            )
+            # The referee already verified the selected candidate
+            inputs["verification"] = generation_result["verification"]
+        elif self.chunker is not None:
+            generation_result = await self.chunker.run(
+                inputs["python_code"], inputs["analysis"], project_context)
+        elif self.pipeline is not None:
+            generation_result = await self.pipeline.run(
+                inputs["python_code"], inputs["analysis"], project_context, tests_context)
+        else:
+            generation_result = await self.chains["generation"].generate(
+                python_code=inputs["python_code"],
+                analysis=inputs["analysis"],
+                project_context=project_context
+            )
+        if self.formatter is not None:
+            await self.formatter.normalize_result(generation_result)
+        if generation_result.get("toml_content"):
+            generation_result["toml_content"] = self.plugins.cargo_manifest(
+                generation_result["rust_code"],
+                add_test_dependencies(generation_result["rust_code"],
+                                      generation_result["toml_content"]))
         self.state.latest_generation = generation_result
+        self.state.record_code("generation", generation_result["rust_code"])
         inputs["rust_code"] = generation_result["rust_code"]
         inputs["toml_content"] = generation_result["toml_content"]
         return inputs
@@ -47,14 +117,42 @@ class MigrationWorkflow:
     async def _run_verification(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
         """Verify generated Rust code."""
         logger.info("Verifying generated code")
+        self.events.emit(EventType.STAGE_STARTED, stage="verification")
+        verification_result = inputs.get("verification")
+        if verification_result is None:
+            verification_result = await self._verify(
+                inputs, inputs["rust_code"], inputs["toml_content"])
+        self.state.last_verification_result = verification_result
+        inputs["verification"] = verification_result
+        return inputs
+
+    async def _verify(
+        self,
+        inputs: Dict[str, Any],
+        rust_code: str,
+        toml_content: str
+    ) -> Dict[str, Any]:
+        """Run the verification chain, then the plugin checkers."""
         verification_result = await self.chains["verification"].verify(
             python_code=inputs["python_code"],
-            rust_code=inputs["rust_code"],
+            rust_code=rust_code,
             analysis=inputs["analysis"]
         )
-        self.state.last_verification_result = verification_result
-        inputs["verification"] = verification_result
-        return inputs
+
+        plugin_issues = self.plugins.verify(inputs["python_code"], rust_code, toml_content)
+        if plugin_issues:
+            logger.info(f"Plugins reported {len(plugin_issues)} issues")
+            verification_result["critical_differences"]["plugins"] = plugin_issues
+            verification_result["matches"] = False
+
+        if not verification_result["matches"]:
+            self.events.emit(
+                EventType.CHECK_FAILED,
+                check="verification",
+                differences=verification_result.get("critical_differences", {})
+            )
+
+        return verification_result
 
     async def _apply_fixes_if_needed(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
         """Apply fixes if verification found issues, preserving improvements."""
@@ -71,6 +169,12 @@ class MigrationWorkflow:
 
         for attempt in range(self.max_fix_attempts):
             logger.info(f"Fix attempt {attempt + 1}/{self.max_fix_attempts}")
+            self.events.emit(
+                EventType.ITERATION_RETRIED,
+                stage="verification",
+                attempt=attempt + 1,
+                max_attempts=self.max_fix_attempts
+            )
 
             try:
                 # Apply fixes while preserving improvements
@@ -80,13 +184,16 @@ class MigrationWorkflow:
                     verification_result=verification_result,
                     analysis=inputs["analysis"]
                 )
+                if self.formatter is not None:
+                    await self.formatter.normalize_result(fix_result)
+                if fix_result.get("toml_content"):
+                    fix_result["toml_content"] = self.plugins.cargo_manifest(
+                        fix_result["rust_code"], fix_result["toml_content"])
+                self.state.record_code(f"verification fix {attempt + 1}", fix_result["rust_code"])
 
                 # Re-verify the fixed code
-                new_verification = await self.chains["verification"].verify(
-                    python_code=inputs["python_code"],
-                    rust_code=fix_result["rust_code"],
-                    analysis=inputs["analysis"]
-                )
+                new_verification = await self._verify(
+                    inputs, fix_result["rust_code"], fix_result["toml_content"])
 
                 # Calculate score based on remaining differences
                 score = self._calculate_fix_score(new_verification)
@@ -109,6 +216,8 @@ class MigrationWorkflow:
 
                 if new_verification["matches"]:
                     logger.info(f"Fixes successful on attempt {attempt + 1}")
+                    inputs["verification"] = new_verification
+                    self.state.last_verification_result = new_verification
                     return inputs
 
             except Exception as e:
@@ -121,6 +230,7 @@ class MigrationWorkflow:
             inputs["rust_code"] = best_result["rust_code"]
             inputs["toml_content"] = best_result["toml_content"]
             inputs["verification"] = best_result["verification"]
+            self.state.last_verification_result = best_result["verification"]
         else:
             logger.warning("No successful fixes achieved")
             logger.info("Proceeding with the best available result, if any")
@@ -0,0 +1,123 @@
+import json
+from pathlib import Path
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from ..events import EventBus, EventType
+from ..utils.logging import setup_logger
+from ..utils.stream_log import partial_output, seed_prompt, streaming_to
+
+logger = setup_logger()
+
+
+class PassFailed(Exception):
+    """A translation pass failed on every attempt."""
+
+
+class TranslationPipeline:
+    """Runs the staged translation passes, retrying each one on its own.
+
+    Every attempt is stored under <artifacts_dir>/<pass>/attempt_<n>/, with the
+    model output streamed into it, and the accepted output of each pass in
+    <artifacts_dir>/<pass>/final/. An attempt interrupted mid-output is retried
+    with that partial output in its prompt.
+    """
+
+    def __init__(
+        self,
+        chain: Any,
+        passes: List[str],
+        artifacts_dir: Path,
+        max_attempts: int = 2,
+        events: Optional[EventBus] = None
+    ):
+        self.chain = chain
+        self.passes = passes
+        self.artifacts_dir = Path(artifacts_dir)
+        self.max_attempts = max_attempts
+        self.events = events or EventBus()
+
+    def _store(self, directory: Path, output: Any) -> None:
+        directory.mkdir(parents=True, exist_ok=True)
+        if isinstance(output, str):
+            (directory / "output.md").write_text(output)
+        elif isinstance(output, dict):
+            if output.get("rust_code"):
+                (directory / "main.rs").write_text(output["rust_code"])
+            if output.get("toml_content"):
+                (directory / "Cargo.toml").write_text(output["toml_content"])
+        elif isinstance(output, Exception):
+            (directory / "error.txt").write_text(f"{type(output).__name__}: {output}")
+
+    async def _run_pass(self, name: str, step: Callable[[str], Awaitable[Any]]) -> Any:
+        """Run a pass until it succeeds; step gets the context seeding a retry, or ""."""
+        self.events.emit(EventType.STAGE_STARTED, stage=name)
+        pass_dir = self.artifacts_dir / name
+        seed = ""
+        for attempt in range(1, self.max_attempts + 1):
+            if attempt > 1:
+                self.events.emit(EventType.ITERATION_RETRIED, stage=name,
+                                 attempt=attempt, max_attempts=self.max_attempts)
+            attempt_dir = pass_dir / f"attempt_{attempt}"
+            try:
+                with streaming_to(attempt_dir):
+                    output = await step(seed)
+            except Exception as e:
+                logger.warning(f"{name} pass attempt {attempt}/{self.max_attempts} failed: {e}")
+                self._store(attempt_dir, e)
+                partial = partial_output(attempt_dir)
+                seed = seed_prompt(partial) if partial else ""
+                continue
+            self._store(attempt_dir, output)
+            self._store(pass_dir / "final", output)
+            logger.info(f"{name} pass completed on attempt {attempt}")
+            return output
+        raise PassFailed(f"{name} pass failed after {self.max_attempts} attempts")
+
+    async def run(
+        self,
+        python_code: str,
+        analysis: Dict[str, Any],
+        project_context: str = "",
+        tests_context: str = ""
+    ) -> Dict[str, Any]:
+        """Run the configured passes and return the final rust_code and toml_content.
+
+        tests_context, the Python tests to translate, goes to the tests pass, or to
+        the other passes' project context when there is none.
+        """
+        spec = ""
+        skeleton: Dict[str, str] = {}
+        if "tests" not in self.passes:
+            project_context = "\n\n".join(part for part in [project_context, tests_context] if part)
+
+        def context(seed: str) -> str:
+            return "\n\n".join(part for part in [project_context, seed] if part)
+
+        if "spec" in self.passes:
+            spec = await self._run_pass(
+                "spec", lambda seed: self.chain.spec(python_code, analysis, context(seed)))
+        if "skeleton" in self.passes:
+            skeleton = await self._run_pass(
+                "skeleton", lambda seed: self.chain.skeleton(python_code, spec, context(seed)))
+
+        result = await self._run_pass(
+            "implementation",
+            lambda seed: self.chain.implementation(python_code, spec, skeleton, context(seed)))
+
+        if "tests" in self.passes:
+            try:
+                # The tests prompt has no project context to seed
+                tests = await self._run_pass(
+                    "tests", lambda seed: self.chain.tests(
+                        python_code, spec, result["rust_code"], tests_context))
+                result = {**result, "rust_code": f"{result['rust_code']}\n\n{tests}\n"}
+            except PassFailed as e:
+                # Tests are a bonus; keep the implementation without them
+                logger.warning(str(e))
+
+        (self.artifacts_dir / "passes.json").write_text(json.dumps({
+            "passes": self.passes,
+            "spec": bool(spec),
+            "skeleton": bool(skeleton)
+        }, indent=2))
+        return {**result, "spec": spec, "skeleton": skeleton.get("rust_code")}
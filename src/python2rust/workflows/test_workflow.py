@@ -9,6 +9,8 @@ from ..utils.logging import setup_logger
 from ..builders import ServerTester
 from ..utils.error_formatter import format_error_for_fix
 from ..utils.build_output import update_rust_files
+from ..utils.rustfmt import Rustfmt
+from ..events import EventBus, EventType
 
 
 logger = setup_logger()
@@ -30,10 +32,19 @@ class TestResult:
 
 
 class TestWorkflow:
-    def __init__(self, chains: Dict[str, Any], server_tester: ServerTester, state: "MigrationState"):
+    def __init__(
+        self,
+        chains: Dict[str, Any],
+        server_tester: ServerTester,
+        state: "MigrationState",
+        events: Optional[EventBus] = None,
+        formatter: Optional[Rustfmt] = None
+    ):
         self.chains = chains
         self.server_tester = server_tester
         self.state = state
+        self.events = events or EventBus()
+        self.formatter = formatter
         self.max_fix_attempts = 3
 
     def setup(self) -> RunnableSequence:
@@ -90,6 +101,7 @@ After installation, restart your terminal and try again.
 
     async def _run_server_tests(self, inputs: Dict[str, Any]) -> Dict[str, Any]:
         """Run server tests."""
+        self.events.emit(EventType.STAGE_STARTED, stage="test")
         try:
             if not self.server_tester.test_script_path.exists():
                 error_msg = f"Test script not found: {self.server_tester.test_script_path}"
@@ -126,6 +138,8 @@ After installation, restart your terminal and try again.
             inputs["test_success"] = success
             inputs["test_error"] = error
             inputs["test_info"] = test_info
+            if not success:
+                self.events.emit(EventType.CHECK_FAILED, check="server", error=error)
 
             # Preserve the code for potential fixes
             inputs["rust_code"] = rust_code
@@ -160,6 +174,12 @@ After installation, restart your terminal and try again.
 
         for attempt in range(self.max_fix_attempts):
             logger.info(f"Attempting server fix {attempt + 1}")
+            self.events.emit(
+                EventType.ITERATION_RETRIED,
+                stage="server",
+                attempt=attempt + 1,
+                max_attempts=self.max_fix_attempts
+            )
 
             try:
                 error_text = format_error_for_fix(inputs.get("test_error", ""))
@@ -200,6 +220,9 @@ After installation, restart your terminal and try again.
                 )
 
                 # Update the code after fix
+                if self.formatter is not None:
+                    await self.formatter.normalize_result(fix_result)
+                self.state.record_code(f"server fix {attempt + 1}", fix_result["rust_code"])
                 current_rust_code = fix_result["rust_code"]
                 current_toml_content = fix_result["toml_content"]
 
@@ -217,6 +240,7 @@ After installation, restart your terminal and try again.
                     inputs["test_info"] = test_info
                     return inputs
 
+                self.events.emit(EventType.CHECK_FAILED, check="server", error=error)
                 inputs["test_error"] = error
                 inputs["test_info"] = test_info
 
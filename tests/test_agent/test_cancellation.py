@@ -0,0 +1,47 @@
+import asyncio
+import pytest
+from python2rust.agent.cancellation import CancellationToken, MigrationCancelled
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestCancellationToken:
+    async def test_guard_returns_result(self):
+        """Test that guarded awaitables complete normally when not cancelled."""
+        token = CancellationToken()
+
+        async def work():
+            return 42
+
+        assert await token.guard(work()) == 42
+
+    async def test_cancel_drops_inflight_work(self):
+        """Test that cancelling the token cancels the guarded task."""
+        token = CancellationToken()
+        state = {"cancelled": False}
+
+        async def slow():
+            try:
+                await asyncio.sleep(10)
+            except asyncio.CancelledError:
+                state["cancelled"] = True
+                raise
+
+        asyncio.get_running_loop().call_later(0.05, token.cancel)
+        with pytest.raises(MigrationCancelled):
+            await token.guard(slow())
+
+        assert state["cancelled"]
+
+    async def test_already_cancelled(self):
+        """Test that nothing runs after the token has fired."""
+        token = CancellationToken()
+        token.cancel()
+
+        async def work():
+            raise AssertionError("should not run")
+
+        coro = work()
+        with pytest.raises(MigrationCancelled):
+            await token.guard(coro)
+        coro.close()
@@ -0,0 +1,67 @@
+from python2rust.analyzers import RUNTIMES, async_uses, runtime_mappings
+from python2rust.config.crate_mappings import CrateMapping
+
+MODULE = '''import asyncio
+import time
+from asyncio import sleep as pause
+
+
+async def fetch(url):
+    await pause(0.5)
+    return url
+
+
+async def main():
+    await asyncio.gather(fetch("a"), fetch("b"))
+    time.sleep(1)
+
+asyncio.run(main())
+'''
+
+
+class TestAsyncRuntime:
+    def test_uses_take_the_idioms_of_the_runtime(self):
+        """Test that async constructs and the calls sleeping, gathering or running work are found
+        through their imports, described with the idiom of each runtime."""
+        uses = async_uses(MODULE)
+
+        assert [use.describe(RUNTIMES["async-std"]) for use in uses] == [
+            "line 6: async def fetch",
+            "line 7: await",
+            "line 7: pause() -> `async_std::task::sleep(Duration::from_secs_f64(seconds)).await`",
+            "line 11: async def main",
+            "line 12: await",
+            "line 12: asyncio.gather() -> the JoinHandles of "
+            "`async_std::task::spawn` awaited in order",
+            "line 13: time.sleep() -> `async_std::task::sleep(Duration::from_secs_f64(seconds))."
+            "await`",
+            "line 15: asyncio.run() -> `#[async_std::main] async fn main()`",
+        ]
+        assert uses[0].describe(RUNTIMES["sync"]) == "line 6: async def fetch -> synchronous code"
+        assert async_uses("def f():\n    return 1\n") == []
+
+    def test_blocking_calls_keep_std_idioms_outside_async_functions(self):
+        """Test that time.sleep and threading.Thread in plain functions keep std's blocking idioms,
+        whatever the runtime."""
+        code = ("import threading\nimport time\n\n\ndef worker():\n    time.sleep(1)\n\n\n"
+                "threading.Thread(target=worker).start()\n")
+
+        assert [use.describe(RUNTIMES["tokio"]) for use in async_uses(code)] == [
+            "line 6: time.sleep() -> `std::thread::sleep(Duration::from_secs_f64(seconds))`",
+            "line 9: threading.Thread() -> `std::thread::spawn(closure)`, joining its JoinHandle",
+        ]
+
+    def test_mappings_follow_the_runtime(self):
+        """Test that the libraries mapped to tokio take the runtime's crate, or none without one,
+        and that runtime-tokio features follow it."""
+        mappings = [CrateMapping("asyncio", "tokio", "async runtime",
+                                 version="1", features=["full"]),
+                    CrateMapping("sqlalchemy", "sqlx", "database", version="0.7",
+                                 features=["runtime-tokio"])]
+
+        assert runtime_mappings(mappings, "tokio") == mappings
+        assert [mapping.dependency() for mapping in runtime_mappings(mappings, "async-std")] == [
+            'async-std = { version = "1", features = ["attributes"] }',
+            'sqlx = { version = "0.7", features = ["runtime-async-std"] }',
+        ]
+        assert [mapping.python for mapping in runtime_mappings(mappings, "sync")] == ["sqlalchemy"]
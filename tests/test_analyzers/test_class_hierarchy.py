@@ -0,0 +1,122 @@
+from python2rust.analyzers import class_mappings
+
+PYTHON = '''from abc import ABC, abstractmethod
+from enum import Enum
+
+
+class Color(Enum):
+    RED = 1
+
+
+class Shape(ABC):
+    @abstractmethod
+    def area(self):
+        ...
+
+
+class Circle(Shape):
+    def area(self):
+        return 3.14
+
+
+class Token:
+    def __init__(self, text):
+        self.text = text
+
+
+class Number(Token):
+    pass
+
+
+class Word(Token):
+    pass
+
+
+class AppError(Exception):
+    pass
+
+
+class NotFound(AppError):
+    pass
+
+
+def kind(token):
+    return "number" if isinstance(token, Number) else "word"
+'''
+
+
+class TestClassHierarchy:
+    def test_classes_become_structs_traits_and_enums(self):
+        """Test that abstract bases become traits, isinstance-checked ones enums, and
+        the rest structs."""
+        planned = [(m.name, m.kind, m.parent, m.implements, m.depth)
+                   for m in class_mappings(PYTHON)]
+
+        assert planned == [
+            ("Color", "enum", None, [], 1),
+            ("Shape", "trait", None, [], 1),
+            ("Circle", "struct", None, ["Shape"], 2),
+            ("Token", "enum", None, [], 0),
+            ("Number", "variant", "Token", [], 1),
+            ("Word", "variant", "Token", [], 1),
+            ("AppError", "enum", None, [], 1),
+            ("NotFound", "variant", "AppError", [], 2),
+        ]
+        circle = class_mappings(PYTHON)[2]
+        assert circle.describe() == (
+            "Circle (line 15) -> struct Circle implementing Shape: a concrete class; inheritance "
+            "depth 2; overrides area")
+
+    def test_overrides_change_the_kind_of_subclasses(self):
+        """Test that an approved change of a base's kind carries over to its subclasses."""
+        planned = {m.name: (m.kind, m.implements)
+                   for m in class_mappings(PYTHON, {"Token": "trait"})}
+
+        assert planned["Token"] == ("trait", [])
+        assert planned["Number"] == ("struct", ["Token"])
+
+    def test_protocols_and_abstract_classes_become_traits(self):
+        """Test that interfaces keep required and default methods, and Protocols
+        match structurally."""
+        code = '''from abc import ABC, abstractmethod
+from typing import Protocol
+
+
+class Drawable(Protocol):
+    def draw(self) -> str:
+        ...
+
+
+class Shape(ABC):
+    def __init__(self, name):
+        self.name = name
+
+    @abstractmethod
+    def area(self) -> float:
+        raise NotImplementedError
+
+    def label(self):
+        return f"{self.name}: {self.area()}"
+
+
+class Square(Shape):
+    def area(self):
+        return 1.0
+
+    def draw(self):
+        return "[]"
+
+
+class Sprite:
+    def draw(self):
+        return "*"
+'''
+        drawable, shape, square, sprite = class_mappings(code)
+
+        assert (drawable.kind, drawable.required_methods,
+                drawable.default_methods) == ("trait", ["draw"], [])
+        assert (shape.kind, shape.required_methods, shape.default_methods) == ("trait",
+                                                                               ["area"], ["label"])
+        assert shape.fields == ["name"]
+        assert square.implements == ["Shape", "Drawable"]
+        assert sprite.implements == ["Drawable"]
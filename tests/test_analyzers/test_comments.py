@@ -0,0 +1,45 @@
+from python2rust.analyzers.comments import missing_comments, python_comments, rust_comments
+
+PYTHON = '''#!/usr/bin/env python3
+# Ledger totals
+import sys  # noqa: F401
+
+
+class Ledger:
+    def add(self, amount):
+        # Negative amounts are refunds
+        self.total += amount  # rounded later
+'''
+
+
+class TestComments:
+    def test_python_comments_with_their_statements(self):
+        """Test that comments are anchored to the statement they are on or above, tool
+        directives aside."""
+        comments = [(c.line, c.text, c.anchor, c.scope) for c in python_comments(PYTHON)]
+
+        assert comments == [
+            (2, "Ledger totals", "import sys", None),
+            (8, "Negative amounts are refunds", "self.total += amount", "Ledger.add"),
+            (9, "rounded later", "self.total += amount", "Ledger.add"),
+        ]
+
+    def test_rust_comments_are_looked_for_in_the_translating_function(self):
+        """Test that a comment counts as kept only in the function translating its scope."""
+        rust = (
+            "// Ledger totals\n"
+            "impl Ledger {\n"
+            "    fn add(&mut self, amount: i64) {\n"
+            "        let s = \"// rounded later\";\n"
+            "        self.total += amount; /* negative amounts: refunds */\n"
+            "    }\n"
+            "}\n"
+            "// rounded later\n"
+        )
+        functions = [{"name": "Ledger::add", "start_line": 3, "end_line": 6}]
+
+        assert rust_comments(rust) == [
+            (1, "Ledger totals"), (5, "negative amounts: refunds"), (8, "rounded later")
+        ]
+        missing = missing_comments(python_comments(PYTHON), PYTHON, rust, functions)
+        assert [comment.text for comment in missing] == ["rounded later"]
@@ -0,0 +1,61 @@
+from python2rust.analyzers import context_managers
+
+PYTHON = '''from contextlib import contextmanager
+
+
+class Transaction:
+    def __enter__(self):
+        return self
+
+    def __exit__(self, kind, error, trace):
+        if kind is KeyError:
+            return True
+        self.rollback()
+
+
+@contextmanager
+def opened_db(path):
+    db = connect(path)
+    try:
+        yield db
+    finally:
+        db.close()
+
+
+def save(path, rows):
+    with opened_db(path) as db, Transaction():
+        for row in rows:
+            if not row:
+                continue
+            db.insert(row)
+        if not rows:
+            return 0
+        raise ValueError("unsaved")
+'''
+
+
+class TestContextManagers:
+    def test_finds_the_context_managers_the_module_defines(self):
+        """Test that classes with __enter__ and __exit__ and @contextmanager functions are found
+        with the guard cleaning up after them and the test of their error path."""
+        transaction, opened, _ = context_managers(PYTHON)
+
+        assert (transaction.kind, transaction.guard, transaction.suppresses) == (
+            "class", "Transaction", True)
+        assert (opened.kind, opened.guard, opened.test_name) == (
+            "function", "OpenedDbGuard", "opened_db_cleans_up_on_error")
+        assert opened.describe() == (
+            "line 15: opened_db (@contextmanager function) -> Drop of OpenedDbGuard")
+
+    def test_with_blocks_list_their_early_exits(self):
+        """Test that with blocks are found with the returns and raises leaving them,
+        not loop continues."""
+        block = context_managers(PYTHON)[-1]
+
+        assert (block.kind, block.name, block.managers) == (
+            "with", "save", ["opened_db(path)", "Transaction()"])
+        assert block.exits_early == ["return (line 30)", "raise (line 31)"]
+        assert block.describe() == (
+            "line 24: with opened_db(path), Transaction() in save, left early by return (line 30), "
+            "raise (line 31)")
+        assert context_managers("def broken(:\n") == []
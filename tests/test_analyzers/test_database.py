@@ -0,0 +1,56 @@
+from pathlib import Path
+
+from python2rust.analyzers import LAYERS, database_mappings, database_use, write_migrations
+from python2rust.config.crate_mappings import CrateMapping
+
+MODULE = '''import sqlite3
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL);
+"""
+
+
+def names(connection):
+    connection.executescript(SCHEMA)
+    return connection.execute("SELECT name FROM users WHERE id = ?", (1,)).fetchall()
+'''
+
+
+class TestDatabase:
+    def test_driver_backend_tables_and_queries(self):
+        """Test that the driver, its backend, the created tables and the queries are found, and that
+        SQLAlchemy's backend is that of its database URL."""
+        use = database_use(MODULE)
+
+        assert use.describe() == "sqlite3 on sqlite, creating tables: users, with 1 queries"
+        assert [statement.describe() for statement in use.queries] == [
+            "line 10: SELECT name FROM users WHERE id = ?"]
+        assert database_use('import sqlalchemy\n'
+                            'engine = sqlalchemy.create_engine("mysql+pymysql://db")\n'
+                            ).backend == "mysql"
+        assert database_use("import json\n") is None
+
+    def test_migrations_in_the_layer_layout(self, temp_dir: Path):
+        """Test that each table gets a migration, diesel ones with a down.sql."""
+        tables = database_use(MODULE).tables
+
+        migrations = temp_dir / "migrations"
+
+        assert write_migrations(temp_dir, tables, "sqlx") == [migrations / "0001_create_users.sql"]
+        assert write_migrations(temp_dir, tables, "diesel") == [
+            migrations / "0001_create_users" / "up.sql",
+            migrations / "0001_create_users" / "down.sql"]
+        assert (migrations / "0001_create_users" / "down.sql").read_text() == "DROP TABLE users;\n"
+
+    def test_drivers_are_mapped_to_the_layer(self):
+        """Test that the SQL drivers take the layer's crate with the features of their backend and
+        of the runtime, and that other database crates are left alone."""
+        mappings = [CrateMapping("psycopg2", "tokio-postgres", "database", version="0.7"),
+                    CrateMapping("redis", "redis", "database", version="0.25")]
+
+        assert [mapping.dependency()
+                for mapping in database_mappings(mappings, "sea-orm", "async-std")] == [
+            'sea-orm = { version = "0.12", features = ["macros", "sqlx-postgres", '
+            '"runtime-async-std-rustls"] }', 'redis = "0.25"']
+        assert LAYERS["diesel"].dependencies("sqlite", "tokio") == [
+            'diesel = { version = "2", features = ["r2d2", "sqlite"] }', 'diesel_migrations = "2"']
@@ -0,0 +1,64 @@
+import pytest
+from pathlib import Path
+from python2rust.analyzers import ImportGraph, find_dead_code, referencing_files, remove_definitions
+
+
+class TestFindDeadCode:
+    @pytest.fixture
+    def package(self, temp_dir: Path) -> Path:
+        """Create an app whose main script uses part of a helpers module, next to a
+        tool nothing imports."""
+        root = temp_dir / "app"
+        root.mkdir()
+        (root / "helpers.py").write_text(
+            "def _format(value):\n    return str(value)\n\n"
+            "def render(value):\n    return _format(value)\n\n"
+            "def _debug(value):\n    print(value)\n\n"
+            "def legacy(value):\n    return value\n\n"
+            "def tested(value):\n    return value\n\n"
+            "@register\ndef plugin():\n    pass\n"
+        )
+        (root / "main.py").write_text(
+            "from helpers import render\n\nclass Unused:\n    pass\n\n"
+            "if __name__ == \"__main__\":\n    print(render(1))\n"
+        )
+        (root / "tool.py").write_text("def banner():\n    return 'tool'\n\nprint(banner())\n")
+        (root / "test_helpers.py").write_text("from helpers import tested\n")
+        return root
+
+    def graph(self, package: Path) -> ImportGraph:
+        files = [package / "helpers.py", package / "main.py", package / "tool.py"]
+        return ImportGraph(files, {f: package for f in files})
+
+    def test_finds_definitions_unused_from_the_entry_points(self, package: Path):
+        """Test that only definitions reached from the entry points, tests and
+        decorators are kept."""
+        graph = self.graph(package)
+        references = referencing_files([package], graph.files)
+
+        dead = find_dead_code(graph, [package / "main.py"], references)
+
+        assert references == [package / "test_helpers.py"]
+        assert {file.name: [d.name for d in definitions] for file, definitions in dead.items()} == {
+            "helpers.py": ["_debug", "legacy"],
+            "main.py": ["Unused"],
+            "tool.py": ["banner"],
+        }
+        assert [(d.start_line, d.end_line, d.kind)
+                for d in dead[package / "main.py"]] == [(3, 4, "class")]
+
+    def test_libraries_keep_their_public_definitions(self, package: Path):
+        """Test that without entry points only private definitions can be dead."""
+        dead = find_dead_code(self.graph(package))
+
+        assert {file.name: [d.name for d in definitions] for file, definitions in dead.items()} == {
+            "helpers.py": ["_debug"]
+        }
+
+    def test_remove_definitions(self, package: Path):
+        """Test that the lines of dead definitions are left out of the code."""
+        code = (package / "main.py").read_text()
+        dead = find_dead_code(self.graph(package), [package / "main.py"])
+
+        assert remove_definitions(code, dead[package / "main.py"]) == (
+            "from helpers import render\n\n\nif __name__ == \"__main__\":\n    print(render(1))\n")
@@ -0,0 +1,81 @@
+from python2rust.analyzers import decorator_uses
+from python2rust.analyzers.decorators import CUSTOM_STRATEGY
+
+PYTHON = '''import functools
+from dataclasses import dataclass
+from flask import Flask
+from .timing import timed
+import metrics
+
+app = Flask(__name__)
+
+
+def logged(function):
+    @functools.wraps(function)
+    def wrapper(*args):
+        return function(*args)
+    return wrapper
+
+
+@dataclass(frozen=True)
+class Point:
+    x: int
+
+    @property
+    def norm(self):
+        return abs(self.x)
+
+    @norm.setter
+    def norm(self, value):
+        self.x = value
+
+
+@app.route("/")
+@logged
+@timed
+@metrics.counted
+def index():
+    return "hi"
+'''
+
+
+class TestDecoratorUses:
+    def test_resolves_decorators_through_imports(self):
+        """Test that decorators are named as imported, with the object and method
+        they are taken from."""
+        uses = decorator_uses(PYTHON)
+
+        assert [(use.line, use.name, use.target) for use in uses] == [
+            (11, "functools.wraps", "logged.wrapper"),
+            (17, "dataclasses.dataclass", "Point"),
+            (21, "property", "Point.norm"),
+            (25, "norm.setter", "Point.norm"),
+            (30, "app.route", "index"),
+            (31, "logged", "index"),
+            (32, "timing.timed", "index"),
+            (33, "metrics.counted", "index"),
+        ]
+
+    def test_strategies_come_from_the_catalog_and_the_project(self):
+        """Test that known decorators get the catalog's strategy, patterns included, and the
+        project's own decorators the custom one, leaving the others unknown."""
+        uses = {use.name: use for use in decorator_uses(PYTHON)}
+
+        assert uses["functools.wraps"].origin == "catalog"
+        assert "router" in uses["app.route"].strategy
+        assert "set_<name>" in uses["norm.setter"].strategy
+        assert uses["logged"].strategy == uses["timing.timed"].strategy == CUSTOM_STRATEGY
+        assert not uses["metrics.counted"].known
+        assert uses["metrics.counted"].describe() == "line 33: @metrics.counted on index"
+
+    def test_configured_strategies_take_precedence(self):
+        """Test that the strategies of python2rust.toml map unknown decorators and
+        override the catalog."""
+        uses = {use.name: use for use in decorator_uses(PYTHON, {
+            "metrics.*": "increment a counter of the metrics crate",
+            "app.route": "an actix-web handler"})}
+
+        assert (uses["metrics.counted"].strategy, uses["metrics.counted"].origin) == (
+            "increment a counter of the metrics crate", "config")
+        assert uses["app.route"].strategy == "an actix-web handler"
+        assert decorator_uses("def broken(:\n") == []
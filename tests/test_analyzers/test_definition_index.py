@@ -0,0 +1,76 @@
+from pathlib import Path
+from python2rust.analyzers import DefinitionIndex, python_definitions, rust_definitions
+
+SHAPES = '''class Circle:
+    def __init__(self, radius):
+        self.radius = radius
+
+
+def circle_area(circle):
+    return 3.14 * circle.radius ** 2
+'''
+
+CONFIG = '''def load_config(path):
+    return open(path).read()
+'''
+
+
+class CountingEmbeddings:
+    """Embeds one dimension per known word and counts the embedded texts."""
+
+    def __init__(self):
+        self.embedded = 0
+
+    def embed_query(self, text):
+        return [float(word in text) for word in ("Circle", "radius", "config")]
+
+    def embed_documents(self, texts):
+        self.embedded += len(texts)
+        return [self.embed_query(text) for text in texts]
+
+
+class TestDefinitionIndex:
+    def test_definitions_of_both_languages(self):
+        """Test that top-level Python definitions and Rust items other than main are extracted."""
+        python = python_definitions("shapes", SHAPES)
+        rust = rust_definitions("shapes",
+                                "pub struct Circle {\n    radius: f64,\n}\n\nfn main() {}\n")
+
+        assert [(d.name, d.kind) for d in python] == [("Circle", "class"),
+                                                      ("circle_area", "function")]
+        assert [(d.name, d.kind, d.code) for d in rust] == [
+            ("Circle", "struct", "pub struct Circle {\n    radius: f64,\n}")
+        ]
+
+    def test_search_ranks_by_similarity_and_prefers_rust(self):
+        """Test that references find their definitions, in Rust once translated, outside
+        excluded modules."""
+        index = DefinitionIndex()
+        index.update("shapes", "python", python_definitions("shapes", SHAPES))
+        index.update("config", "python", python_definitions("config", CONFIG))
+        query = "def report(config_path):\n    shape = Circle(load_config(config_path))\n"
+
+        assert [d.name for d in index.search(query, limit=1)] == ["load_config"]
+        assert {d.name for d in index.search(query, limit=5)} == {"load_config",
+                                                                  "Circle", "circle_area"}
+
+        index.update("shapes", "rust",
+                     rust_definitions("shapes", "pub struct Circle {\n    radius: f64,\n}\n"))
+        found = {d.name: d.language
+                 for d in index.search(query, limit=5, exclude_modules=["config"])}
+        assert found == {"Circle": "rust", "circle_area": "python"}
+        assert index.search("def unrelated(): pass", limit=5) == []
+
+    def test_vectors_are_cached(self, temp_dir: Path):
+        """Test that definitions are embedded once, across index instances sharing a cache file."""
+        embeddings = CountingEmbeddings()
+        cache_file = temp_dir / "embeddings.json"
+        index = DefinitionIndex(embeddings, cache_file=cache_file)
+        index.update("shapes", "python", python_definitions("shapes", SHAPES))
+        index.update("shapes", "python", python_definitions("shapes", SHAPES))
+
+        reloaded = DefinitionIndex(embeddings, cache_file=cache_file)
+        reloaded.update("shapes", "python", python_definitions("shapes", SHAPES))
+
+        assert embeddings.embedded == 2
+        assert [d.name for d in reloaded.search("Circle(radius=1)", limit=1)] == ["Circle"]
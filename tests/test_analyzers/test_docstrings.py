@@ -0,0 +1,61 @@
+from python2rust.analyzers import doc_comment, documented_symbols, rustdoc
+
+
+class TestRustdoc:
+    def test_google_sections_become_rustdoc_sections(self):
+        """Test that Google style arguments, returns, raises and examples become
+        rustdoc sections."""
+        docstring = '''Sum the values.
+
+        Args:
+            self: The totals.
+            values (list[int]): The values
+                to sum.
+            start: Where to start.
+
+        Returns:
+            int: The total.
+
+        Raises:
+            ValueError: If values is empty.
+
+        Example:
+            >>> total([1, 2])
+            3
+        '''
+
+        assert rustdoc(docstring) == (
+            "Sum the values.\n\n"
+            "# Arguments\n\n* `values` - The values to sum.\n* `start` - Where to start.\n\n"
+            "# Returns\n\nThe total.\n\n"
+            "# Errors\n\n* `ValueError` - If values is empty.\n\n"
+            "# Examples\n\n```text\n>>> total([1, 2])\n3\n```"
+        )
+
+    def test_rest_and_numpy_fields_become_rustdoc_sections(self):
+        """Test that reST fields and NumPy sections give the same sections, without their types."""
+        rest = ":param values: the values\n    to sum\n:type values: list\n:returns: the total\n" \
+               ":rtype: int\n:raises ValueError: when empty\n"
+        numpy = "Parameters\n----------\nvalues : list of int\n    the values to sum\n\n" \
+                "Returns\n-------\nint\n    the total\n"
+
+        assert rustdoc(f"Sum.\n\n{rest}") == (
+            "Sum.\n\n# Arguments\n\n* `values` - the values to sum\n\n# Returns\n\nthe total\n\n"
+            "# Errors\n\n* `ValueError` - when empty"
+        )
+        assert rustdoc(f"Sum.\n\n{numpy}") == (
+            "Sum.\n\n# Arguments\n\n* `values` - the values to sum\n\n# Returns\n\nthe total")
+
+    def test_documented_symbols_of_a_module(self):
+        """Test that the module, classes and methods with docstrings are found
+        with their rustdoc."""
+        code = '"""Totals."""\nclass Ledger:\n    """A ledger.\n\n    Long."""\n' \
+               '    def add(self):\n        """Add."""\n\n' \
+               '    def undocumented(self):\n        pass\n'
+
+        documented = documented_symbols(code)
+
+        assert [(symbol.qualname, symbol.kind) for symbol in documented] == [
+            ("<module>", "module"), ("Ledger", "class"), ("Ledger.add", "method")
+        ]
+        assert doc_comment(documented[1].rustdoc) == "/// A ledger.\n///\n/// Long."
@@ -0,0 +1,47 @@
+from python2rust.analyzers.doctests import doctest_name, python_doctests
+
+PYTHON = '''"""Ledgers.
+
+>>> 1 + 1
+2
+"""
+
+
+class Ledger:
+    def add(self, amount):
+        """Add an amount.
+
+        >>> ledger = Ledger()
+        >>> ledger.add(3)
+        3
+        >>> ledger.add(-1)
+        Traceback (most recent call last):
+            ...
+        ValueError: negative amount
+        >>> print(ledger)
+        Ledger(3)
+        """
+        return amount
+'''
+
+
+class TestDoctests:
+    def test_examples_with_their_setup_and_kind(self):
+        """Test that examples with a result are found with their line, kind and the
+        setup before them."""
+        doctests = [(d.qualname, d.line, d.source, d.kind, d.setup)
+                    for d in python_doctests(PYTHON)]
+
+        assert doctests == [
+            ("<module>", 3, "1 + 1", "value", []),
+            ("Ledger.add", 13, "ledger.add(3)", "value", ["ledger = Ledger()"]),
+            ("Ledger.add", 15, "ledger.add(-1)", "error", ["ledger = Ledger()"]),
+            ("Ledger.add", 19, "print(ledger)", "output", ["ledger = Ledger()"]),
+        ]
+        assert python_doctests(PYTHON)[2].exception == "ValueError"
+
+    def test_doctest_names(self):
+        """Test that the Rust tests are named after the snake-cased definitions."""
+        assert doctest_name("Ledger.add", 2) == "doctest_ledger_add_2"
+        assert doctest_name("HttpClient.get_json", 1) == "doctest_http_client_get_json_1"
+        assert doctest_name("<module>", 1) == "doctest_module_1"
@@ -0,0 +1,102 @@
+from pathlib import Path
+
+from python2rust.analyzers import (
+    ExceptionHierarchy, exception_sites, write_error_crate, write_error_module
+)
+
+ERRORS = '''class AppError(Exception):
+    """Base of the application errors."""
+
+
+class NotFound(AppError):
+    def __init__(self, key: str, count: int):
+        super().__init__(f"missing {key} ({count} tries)")
+
+
+class StorageError(AppError):
+    pass
+
+
+class DiskFull(StorageError):
+    pass
+'''
+
+STORAGE = '''from .errors import AppError, DiskFull, NotFound
+
+
+def load(key):
+    if not key:
+        raise ValueError("empty key")
+    try:
+        return open(key).read()
+    except (OSError, DiskFull):
+        raise NotFound(key, 1)
+    except AppError:
+        raise DiskFull("no space left")
+'''
+
+
+class TestExceptionHierarchy:
+    def test_mirrors_the_project_exceptions(self):
+        """Test that exception bases become enums converting their subclasses, and the others
+        structs keeping the message their __init__ formats."""
+        hierarchy = ExceptionHierarchy({"errors": ERRORS, "storage": STORAGE})
+        module = hierarchy.rust_module()
+
+        assert list(hierarchy.classes) == ["AppError", "NotFound", "StorageError", "DiskFull"]
+        assert hierarchy.builtins == ["Value", "Io"]
+        assert ("pub enum AppError {\n    #[error(transparent)]\n"
+                "    NotFound(#[from] NotFound),") in module
+        assert ('#[error("missing {key} ({count} tries)")]\npub struct NotFound {\n'
+                '    pub key: String,\n'
+                "    pub count: i64,\n}") in module
+        assert '#[error("{0}")]\npub struct DiskFull(pub String);' in module
+        assert ("    Value(String),\n    #[error(transparent)]\n"
+                "    Io(#[from] std::io::Error),\n}") in module
+        assert "impl From<DiskFull> for AppError {\n    fn from(error: DiskFull) -> Self {\n" \
+               "        AppError::StorageError(error.into())" in module
+        assert "impl From<DiskFull> for Error {" in module
+        assert module.rstrip().endswith("pub type Result<T> = std::result::Result<T, Error>;")
+
+    def test_patterns_follow_the_hierarchy(self):
+        """Test that except clauses map onto patterns nesting the enums above the
+        caught exception."""
+        hierarchy = ExceptionHierarchy({"errors": ERRORS, "storage": STORAGE})
+
+        assert hierarchy.pattern("DiskFull") == (
+            "error::Error::AppError(error::AppError::StorageError(error::StorageError::"
+            "DiskFull(_)))")
+        assert hierarchy.pattern("OSError") == "error::Error::Io(_)"
+        assert hierarchy.pattern("Exception") == "_"
+        assert hierarchy.classes["DiskFull"].describe() == (
+            "errors.DiskFull (line 14) -> struct DiskFull, converted into "
+            "StorageError, raised at 1 site")
+        assert not ExceptionHierarchy({"empty": "x = 1\n"})
+
+    def test_finds_raise_and_except_sites(self):
+        """Test that raise statements keep their message and except clauses every
+        exception they catch."""
+        sites = exception_sites(STORAGE)
+
+        assert [(site.line, site.kind, site.exceptions, site.message) for site in sites] == [
+            (6, "raise", ["ValueError"], "'empty key'"),
+            (9, "except", ["OSError", "DiskFull"], None),
+            (10, "raise", ["NotFound"], "key"),
+            (11, "except", ["AppError"], None),
+            (12, "raise", ["DiskFull"], "'no space left'"),
+        ]
+
+    def test_writes_the_errors_crate_and_the_module_reexporting_it(self, temp_dir: Path):
+        """Test that the error module is the lib.rs of one errors crate, which the src/error.rs of
+        each crate re-exports."""
+        hierarchy = ExceptionHierarchy({"errors": ERRORS, "storage": STORAGE})
+
+        directory = write_error_crate(temp_dir, hierarchy)
+        module = write_error_module(temp_dir / "storage")
+
+        assert directory == temp_dir / "shared" / "errors"
+        assert (directory / "src" / "lib.rs").read_text() == hierarchy.rust_module()
+        assert 'name = "errors"' in (directory / "Cargo.toml").read_text()
+        assert 'thiserror = "1"' in (directory / "Cargo.toml").read_text()
+        assert module == temp_dir / "storage" / "src" / "error.rs"
+        assert module.read_text().endswith("pub use errors::*;\n")
@@ -0,0 +1,66 @@
+from python2rust.analyzers import format_conversions
+from python2rust.analyzers.formatting import convert_format_spec, convert_percent
+
+PYTHON = '''def report(total, name, count, ratio, width):
+    print(f"{total:>8.3f} {name!r} {count:,} {total} {name:{width}}")
+    print("{0:+05d} {1:#x}".format(count, count))
+    print("%05d %-10s %5s %.1e" % (count, name, name, ratio))
+    print(f"{total:>8.3f}")
+'''
+
+
+class TestFormatConversions:
+    def test_converts_format_specs_to_rust_placeholders(self):
+        """Test that format specs Rust can reproduce get their placeholder, in
+        order and once each."""
+        conversions = {conversion.python: conversion for conversion in format_conversions(PYTHON)}
+
+        assert [(python, conversion.line, conversion.style, conversion.rust)
+                for python, conversion in conversions.items() if conversion.rust] == [
+            ("{total:>8.3f}", 2, "f-string", "{:>8.3}"),
+            ("{0:+05d}", 3, "format", "{:+05}"),
+            ("{1:#x}", 3, "format", "{:#x}"),
+            ("%05d", 4, "percent", "{:05}"),
+            ("%-10s", 4, "percent", "{:<10}"),
+            ("%5s", 4, "percent", "{:>5}"),
+        ]
+        assert "{total}" not in conversions
+        assert conversions["{1:#x}"].describe() == (
+            "line 3: {1:#x} -> {:#x} (of the absolute value after a '-' when negative, Rust "
+            "printing the two's complement)")
+
+    def test_lists_what_rust_formatting_cannot_reproduce(self):
+        """Test that repr, grouping, exponents and dynamic specs are left to helpers."""
+        conversions = {conversion.python: conversion for conversion in format_conversions(PYTHON)}
+
+        assert {python: conversion.helpers for python, conversion in conversions.items()
+                if conversion.rust is None} == {
+            "{name!r}": ["repr"], "{count:,}": ["grouping"], "{name:{...}}": ["dynamic"],
+            "%.1e": ["exponent"],
+        }
+        assert convert_format_spec(" >+10.2%") == (None, ["percent"], None)
+        assert convert_format_spec("=+8d") == (None, ["sign_aware"], None)
+        assert convert_format_spec("0=8d") == ("{:08}", [], None)
+        assert convert_format_spec(".3") == (
+            None, ["general"], "a string is truncated to 3 characters, as {:.3} does")
+        assert convert_format_spec("3c") == convert_percent("%3c") == (
+            "{:>3}", [], "of char::from_u32(value)")
+        assert convert_percent("% d") == (None, ["space"], None)
+        assert convert_percent("%*d") == (None, ["dynamic"], None)
+
+    def test_cases_hold_python_output_of_representative_values(self):
+        """Test that each conversion holds Python's formatting of values of the type
+        its spec formats."""
+        conversions = {conversion.python: conversion for conversion in format_conversions(PYTHON)}
+
+        assert conversions["{total:>8.3f}"].cases == [
+            ("3.14159_f64", "   3.142"), ("-2.5_f64", "  -2.500"),
+            ("1234567.891_f64", "1234567.891")]
+        assert conversions["{count:,}"].cases[-1] == ("1234567_i64", "1,234,567")
+        assert conversions["{name!r}"].cases == [('"abc"', "'abc'"), ('"it\'s"', '"it\'s"')]
+        assert conversions["%5s"].cases == [('"abc"', "  abc"), ('"it\'s"', " it's")]
+        assert conversions["{name:{...}}"].cases == []
+        assert format_conversions('f"{ratio:.3}"\n')[0].cases == [
+            ("3.14159_f64", "3.14"), ("-2.5_f64", "-2.5"), ("1234567.891_f64", "1.23e+06"),
+            ('"abc"', "abc"), ('"it\'s"', "it'")]
+        assert format_conversions("def broken(:") == []
@@ -0,0 +1,39 @@
+from python2rust.analyzers.fuzz_targets import fuzz_targets
+
+PYTHON = """
+def clamp_sum(values, limit):
+    return min(sum(values), limit)
+
+def greet(name, *titles):
+    return name
+
+def first_word(text):
+    return text.split()[0]
+
+def _helper(x):
+    return x
+"""
+
+RUST = """
+pub fn clamp_sum(values: &[i64], limit: u8) -> Option<i64> {
+    Some(values.iter().sum::<i64>().min(limit as i64))
+}
+
+fn greet(name: &str) -> String { name.to_string() }
+
+fn first_word<'a>(text: &'a str) -> &'a str { text }
+
+fn _helper(x: i64) -> i64 { x }
+"""
+
+
+class TestFuzzTargets:
+    def test_functions_paired_with_their_translation(self):
+        """Test that only functions with supported parameter and return types become targets."""
+        [target] = fuzz_targets(PYTHON, RUST)
+
+        assert target.name == "clamp_sum"
+        assert [(p.kind, p.rust) for p in target.params] == [("list", "&[i64]"), ("int", "u8")]
+        assert target.params[0].item.kind == "int"
+        assert (target.params[1].low, target.params[1].high) == (0, 255)
+        assert target.returns == "Option<i64>"
@@ -0,0 +1,52 @@
+from python2rust.analyzers import python_generators
+
+PYTHON = '''def count(start=0):
+    n = start
+    while True:
+        yield n
+        n += 1
+
+
+def flatten(nested, extra):
+    for inner in nested:
+        yield from inner
+    yield from extra
+
+
+class Reader:
+    def lines(self):
+        while True:
+            line = yield
+            if line is None:
+                return
+
+
+def total(xs):
+    squares = (x * x for x in xs)
+    return sum(x for x in xs), squares
+'''
+
+
+class TestPythonGenerators:
+    def test_finds_generator_functions(self):
+        """Test that generator functions are found with what they yield from and how they end."""
+        functions = [g for g in python_generators(PYTHON) if g.kind == "function"]
+
+        assert [(g.qualname, g.infinite, g.delegates, g.sends) for g in functions] == [
+            ("count", True, [], False),
+            ("flatten", False, ["inner", "extra"], False),
+            ("Reader.lines", False, [], True),
+        ]
+        assert functions[1].describe() == "line 8: generator flatten (yields from inner, extra)"
+        assert functions[2].test_name == "generator_reader_lines_matches_python"
+
+    def test_finds_generator_expressions_and_their_consumers(self):
+        """Test that generator expressions are found, with the call consuming them
+        right away if any."""
+        expressions = [g for g in python_generators(PYTHON) if g.kind == "expression"]
+
+        assert [(g.qualname, g.line, g.consumer) for g in expressions] == [
+            ("total", 23, None), ("total", 24, "sum")]
+        assert expressions[0].describe() == (
+            "line 23: generator expression `(x * x for x in xs)` in total (kept lazy)")
+        assert python_generators("def broken(:\n") == []
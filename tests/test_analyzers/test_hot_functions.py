@@ -0,0 +1,40 @@
+from python2rust.analyzers.hot_functions import hot_functions
+
+PYTHON = """
+def calculate_primes_up_to(n):
+    primes = []
+    for num in range(2, n + 1):
+        if all(num % i for i in range(2, int(num ** 0.5) + 1)):
+            primes.append(num)
+    return primes
+
+def total(values):
+    return sum(v * w for v in values for w in values)
+
+def double(n):
+    return n * 2
+
+def greet(name):
+    return "hi " + name
+"""
+
+RUST = """
+fn calculate_primes_up_to(n: u32) -> Vec<u32> { vec![] }
+fn total(values: &[i64]) -> i64 { 0 }
+fn double(n: i64) -> i64 { n * 2 }
+fn greet(name: &str) -> String { format!("hi {}", name) }
+"""
+
+
+class TestHotFunctions:
+    def test_nested_loops_and_configured_functions(self):
+        """Test that nested loops and comprehensions make a function hot, with inputs scaled to
+        them, and that configured functions are benchmarked with their arguments."""
+        hot = {function.name: function
+               for function in hot_functions(PYTHON, RUST, {"greet": ["bob"]})}
+
+        assert set(hot) == {"calculate_primes_up_to", "total", "greet"}
+        assert hot["calculate_primes_up_to"].args == [100]
+        assert hot["calculate_primes_up_to"].reason == "2 nested loops"
+        assert hot["total"].args == [list(range(100))]
+        assert (hot["greet"].args, hot["greet"].reason) == (["bob"], "configured")
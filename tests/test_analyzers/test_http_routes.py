@@ -0,0 +1,64 @@
+from python2rust.analyzers.http_routes import find_web_app
+
+FLASK_APP = """
+from flask import Flask
+
+app = Flask(__name__)
+admin = Flask("admin")
+
+@app.route("/", methods=["GET", "post"])
+def index():
+    return "hello"
+
+@app.route("/users/<int:user_id>")
+def user(user_id):
+    return str(user_id)
+
+@admin.route("/admin")
+def dashboard():
+    return "admin"
+
+def helper():
+    return 1
+"""
+
+FASTAPI_APP = """
+from fastapi import FastAPI
+
+api = FastAPI()
+
+@api.get("/items/{item_id}")
+async def item(item_id: int):
+    return {"id": item_id}
+
+@api.post(path="/items")
+async def create():
+    return {}
+"""
+
+
+class TestHttpRoutes:
+    def test_flask_routes_and_methods(self):
+        """Test that the object with most routes is the app, with each route's methods."""
+        app = find_web_app(FLASK_APP)
+
+        assert app.name == "app"
+        assert [(route.path, route.methods, route.function) for route in app.routes] == [
+            ("/", ["GET", "POST"], "index"),
+            ("/users/<int:user_id>", ["GET"], "user")
+        ]
+        assert [route.parameterized for route in app.routes] == [False, True]
+
+    def test_fastapi_method_decorators(self):
+        """Test routes declared with one decorator per method, on async functions."""
+        app = find_web_app(FASTAPI_APP)
+
+        assert app.name == "api"
+        assert [(route.path, route.methods) for route in app.routes] == [
+            ("/items/{item_id}", ["GET"]), ("/items", ["POST"])]
+        assert app.routes[0].parameterized
+
+    def test_modules_without_routes(self):
+        """Test that plain modules and unparseable code define no app."""
+        assert find_web_app("def add(a, b):\n    return a + b\n") is None
+        assert find_web_app("def broken(:\n") is None
@@ -0,0 +1,156 @@
+import pytest
+from pathlib import Path
+from python2rust.analyzers import ImportGraph, extract_signatures, summarize_rust
+
+
+class TestImportGraph:
+    @pytest.fixture
+    def package(self, temp_dir: Path) -> Path:
+        """Create a package where main imports service, which imports models."""
+        root = temp_dir / "app"
+        (root / "core").mkdir(parents=True)
+        (root / "core" / "models.py").write_text("import json\n\nclass User: pass\n")
+        (root / "core" / "service.py").write_text("from .models import User\n")
+        (root / "main.py").write_text("from app.core import service\nimport os\n")
+        return root
+
+    def files(self, package: Path):
+        return [package / "main.py", package / "core" / "service.py",
+                package / "core" / "models.py"]
+
+    def test_resolves_local_imports(self, package: Path):
+        """Test that relative and absolute imports of migrated modules become edges."""
+        files = self.files(package)
+        graph = ImportGraph(files, {f: package for f in files})
+
+        assert graph.dependencies(package / "main.py") == [package / "core" / "service.py"]
+        assert graph.dependencies(package / "core" / "service.py") == (
+            [package / "core" / "models.py"])
+        assert graph.dependencies(package / "core" / "models.py") == []
+
+    def test_topological_order(self, package: Path):
+        """Test that dependencies come before dependents."""
+        files = self.files(package)
+        graph = ImportGraph(files, {f: package for f in files})
+
+        assert graph.topological_order() == list(reversed(files))
+
+    def test_cycles_do_not_block(self, temp_dir: Path):
+        """Test that an import cycle still yields every file once."""
+        (temp_dir / "a.py").write_text("import b\n")
+        (temp_dir / "b.py").write_text("import a\n")
+        files = [temp_dir / "a.py", temp_dir / "b.py"]
+        graph = ImportGraph(files)
+
+        assert graph.topological_order() == files
+        assert graph.ordered_dependencies(temp_dir / "a.py") == []
+        assert graph.ordered_dependencies(temp_dir / "b.py") == [temp_dir / "a.py"]
+
+    def test_cycles_come_after_their_dependencies(self, temp_dir: Path):
+        """Test that a cycle is migrated as a whole before its importers and reported
+        with its back edges."""
+        (temp_dir / "app.py").write_text("import orders\n")
+        (temp_dir / "orders.py").write_text("from customers import Customer\nimport money\n")
+        (temp_dir / "customers.py").write_text("def history():\n    from orders import Order\n")
+        (temp_dir / "money.py").write_text("ZERO = 0\n")
+        app, orders, customers, money = (
+            temp_dir / f"{name}.py" for name in ("app", "orders", "customers", "money"))
+        graph = ImportGraph([app, orders, customers, money])
+
+        assert graph.topological_order() == [money, orders, customers, app]
+        assert graph.cycles() == [[orders, customers]]
+        assert graph.cycle_of(orders) == [orders, customers]
+        assert graph.cycle_of(app) == []
+        assert graph.later_dependencies(orders) == [customers]
+        assert graph.later_dependencies(customers) == []
+        assert graph.cycle_warnings() == [
+            "Import cycle between orders, customers: the Rust of orders -> customers is "
+            "restructured, as crates cannot depend on each other in a cycle"]
+
+    def test_package_imports_resolve_to_init(self, temp_dir: Path):
+        """Test that names imported from a package depend on its __init__.py,
+        submodules on themselves."""
+        root = temp_dir / "shop"
+        root.mkdir()
+        (root / "__init__.py").write_text("from .models import Item\nVERSION = 1\n")
+        (root / "models.py").write_text("class Item: pass\n")
+        (root / "cli.py").write_text("from shop import VERSION\nfrom shop import models\n")
+        init, models, cli = root / "__init__.py", root / "models.py", root / "cli.py"
+        graph = ImportGraph([cli, init, models], {f: temp_dir for f in (cli, init, models)})
+
+        assert graph.dependencies(init) == [models]
+        assert graph.dependencies(cli) == [init, models]
+        assert graph.topological_order() == [models, init, cli]
+
+
+class TestRustSignatures:
+    def test_extracts_public_items(self):
+        """Test that public items and methods are listed without bodies."""
+        rust_code = """
+use std::fmt;
+
+pub struct User {
+    pub name: String,
+}
+
+impl User {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+
+    fn secret(&self) {}
+}
+
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+}
+
+pub fn load(
+    path: &str,
+) -> Vec<User> {
+    vec![]
+}
+
+fn main() {}
+"""
+        assert extract_signatures(rust_code) == [
+            "pub struct User",
+            "impl User",
+            "    pub fn new(name: &str) -> Self",
+            "pub fn load( path: &str, ) -> Vec<User>",
+        ]
+
+    def test_summarizes_public_api_with_docs(self):
+        """Test that the digest keeps doc comments, type declarations and signatures only."""
+        rust_code = """//! Geometry helpers
+/// A point in the plane
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub x: f64,
+}
+
+impl Point {
+    /// Creates a point
+    pub fn new(x: f64) -> Self {
+        Self { x }
+    }
+    fn secret(&self) {}
+}
+
+pub const ORIGIN: f64 = 0.0;
+
+fn main() {}
+"""
+        assert summarize_rust(rust_code).splitlines() == [
+            "//! Geometry helpers",
+            "/// A point in the plane",
+            "#[derive(Debug, Clone)]",
+            "pub struct Point {",
+            "    pub x: f64,",
+            "}",
+            "impl Point {",
+            "    /// Creates a point",
+            "    pub fn new(x: f64) -> Self;",
+            "}",
+            "pub const ORIGIN: f64;",
+        ]
@@ -0,0 +1,67 @@
+import json
+
+from python2rust.analyzers.inferred_types import (
+    SIGNATURE, inferred_types, probe_source, revealed_mypy, revealed_pyright, types_summary
+)
+
+CODE = '''class Basket:
+    def total(self, prices, discount=0.0):
+        """Sum of the prices."""
+        subtotal = sum(prices)
+        with open("log") as log: log.write("total")
+        for index, price in enumerate(prices):
+            subtotal += price
+        return subtotal * (1 - discount)
+
+
+def first(items): return items[0]
+'''
+
+
+class TestProbeSource:
+    def test_probes_parameters_assignments_and_signatures(self):
+        """Test that reveal_type calls are inserted at the right indentation with
+        their lines kept."""
+        probed, probes = probe_source(CODE)
+        lines = probed.splitlines()
+
+        assert probes == {
+            3: ("Basket.total", "prices"), 4: ("Basket.total", "discount"),
+            7: ("Basket.total", "subtotal"), 10: ("Basket.total", "index"),
+            11: ("Basket.total", "price"), 17: ("Basket.total", SIGNATURE),
+            18: ("first", SIGNATURE),
+        }
+        assert lines[2] == "        reveal_type(prices)"
+        assert lines[6] == "        reveal_type(subtotal)"
+        assert lines[9] == "            reveal_type(index)"
+        assert lines[16] == "reveal_type(Basket.total)"
+        compile(probed, "probed.py", "exec")
+
+    def test_revealed_types_of_both_checkers(self):
+        """Test that mypy notes and pyright diagnostics map back to the probes,
+        unknown types left out."""
+        _, probes = probe_source(CODE)
+        mypy = ('probed.py:3: note: Revealed type is "builtins.list[builtins.float]"\n'
+                'probed.py:4: note: Revealed type is "builtins.float"\n'
+                'probed.py:7: note: Revealed type is "Any"\n'
+                'probed.py:5: error: Name "x" is not defined  [name-defined]\n'
+                'probed.py:17: note: Revealed type is "def (self: probed.Basket, prices: Any, '
+                'discount: builtins.float =) -> Any"\n')
+        types = inferred_types(probes, revealed_mypy(mypy))
+
+        assert list(types) == ["Basket.total"]
+        assert types["Basket.total"].variables == {"prices": "list[float]", "discount": "float"}
+        assert types["Basket.total"].signature.startswith("def (self: probed.Basket")
+        assert types_summary(types).splitlines()[1:] == ["- prices: `list[float]`",
+                                                         "- discount: `float`"]
+
+        pyright = json.dumps({"generalDiagnostics": [
+            {"severity": "information", "message": 'Type of "prices" is "list[float]"',
+             "range": {"start": {"line": 2, "character": 8}}},
+            {"severity": "information", "message": 'Type of "first" is "(items) -> int"',
+             "range": {"start": {"line": 17, "character": 0}}},
+            {"severity": "error", "message": 'Type of "x" is "int"',
+             "range": {"start": {"line": 5}}}]})
+        assert revealed_pyright(pyright) == {3: "list[float]", 18: "(items) -> int"}
+        assert inferred_types(probes,
+                              revealed_pyright(pyright))["first"].signature == "(items) -> int"
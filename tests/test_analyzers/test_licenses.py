@@ -0,0 +1,68 @@
+import json
+from pathlib import Path
+
+from python2rust.analyzers import (
+    CrateLicenses, license_allowed, license_violations, resolve_requirements
+)
+from python2rust.analyzers.licenses import version_matches
+from python2rust.analyzers.requirements import Requirement
+from python2rust.config.crate_mappings import CrateMapping
+
+CRATES = {
+    "reqwest": [{"num": "0.13.0", "license": "GPL-3.0-only"},
+                {"num": "0.12.9", "license": "GPL-3.0-only"},
+                {"num": "0.12.10", "license": "GPL-3.0-only", "yanked": True}],
+    "ureq": [{"num": "2.10.1", "license": "MIT OR Apache-2.0"}],
+    "isahc": [{"num": "1.7.2", "license": "LGPL-2.1-only"}],
+}
+
+
+def fetch(crate: str):
+    if crate not in CRATES:
+        raise OSError("404 Not Found")
+    return {"versions": CRATES[crate]}
+
+
+class TestLicenses:
+    def test_evaluates_spdx_expressions(self):
+        """Test that an OR needs one allowed side, an AND both, WITH exceptions being
+        allowed as written."""
+        allowed = ["MIT", "Apache-2.0", "Apache-2.0 WITH LLVM-exception"]
+
+        assert license_allowed("MIT OR GPL-3.0", allowed)
+        assert license_allowed("MIT/Apache-2.0", allowed)
+        assert license_allowed("(MIT OR GPL-3.0) AND Apache-2.0 WITH LLVM-exception", allowed)
+        assert not license_allowed("MIT AND GPL-3.0", allowed)
+        assert not license_allowed("GPL-2.0 WITH Classpath-exception-2.0", allowed)
+        assert not license_allowed("", allowed)
+
+    def test_matches_caret_requirements(self):
+        """Test that requirements keep their left-most non-zero number, as Cargo's caret ones."""
+        assert version_matches("0.12", "0.12.9")
+        assert not version_matches("0.12", "0.13.0")
+        assert version_matches("1.2", "1.9.0")
+        assert not version_matches("1.2", "1.1.5")
+        assert version_matches("*", "0.0.1")
+
+    def test_violations_suggest_allowed_crates_of_the_category(self, temp_dir: Path):
+        """Test that a disallowed crate is reported with the allowed crates of its category, using
+        the newest unyanked version its requirement selects, and that licenses are cached."""
+        mappings = [CrateMapping("requests", "reqwest", "http client", version="0.12"),
+                    CrateMapping("urllib3", "ureq", "http client"),
+                    CrateMapping("pycurl", "isahc", "http client"),
+                    CrateMapping("leftpad", "leftpad-rs", "text")]
+        resolved, _ = resolve_requirements([Requirement("requests"), Requirement("leftpad")],
+                                           mappings)
+        dependencies = [dependency for dependency in resolved if dependency.requirement is not None]
+        cache = temp_dir / "licenses.json"
+
+        violations, unknown = license_violations(
+            dependencies, ["MIT", "Apache-2.0"], CrateLicenses(cache, fetch=fetch))
+
+        assert [violation.describe() for violation in violations] == [
+            "requests -> reqwest 0.12.9 (GPL-3.0-only); allowed instead: ureq (MIT OR Apache-2.0)"]
+        assert unknown == ["leftpad-rs"]
+        cached = json.loads(cache.read_text())
+        assert cached["reqwest@0.12"] == {"version": "0.12.9", "license": "GPL-3.0-only"}
+        assert CrateLicenses(cache, fetch=lambda crate: {}).license("reqwest",
+                                                                    "0.12").version == "0.12.9"
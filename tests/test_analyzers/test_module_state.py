@@ -0,0 +1,63 @@
+from python2rust.analyzers import module_state, state_strategy
+
+PYTHON = '''import logging
+from typing import TypeVar
+
+T = TypeVar("T")
+logger = logging.getLogger(__name__)
+DEFAULTS = {"retries": 3}
+_cache = {}
+_client = None
+session = requests.Session()
+
+
+class Config:
+    _instance = None
+
+    def __new__(cls):
+        if cls._instance is None:
+            cls._instance = super().__new__(cls)
+        return cls._instance
+
+
+def lookup(key):
+    if key not in _cache:
+        _cache[key] = fetch(key)
+    return _cache[key]
+
+
+def client():
+    global _client
+    if _client is None:
+        _client = Client()
+    return _client
+
+
+def retries():
+    items = []
+    items.append(DEFAULTS["retries"])
+    return items
+'''
+
+
+class TestModuleState:
+    def test_changed_globals_and_singletons(self):
+        """Test that globals functions change and shared instances are found,
+        read-only tables aside."""
+        states = [(s.line, s.name, s.kind, s.changed_by) for s in module_state(PYTHON)]
+
+        assert states == [
+            (7, "_cache", "mutable", ["lookup"]),
+            (8, "_client", "mutable", ["client"]),
+            (9, "session", "singleton", []),
+            (12, "Config", "singleton", []),
+        ]
+
+    def test_unchanged_singletons_are_once_lock_statics(self):
+        """Test that only state the code changes follows the project strategy,
+        unless it is injected."""
+        cache, _, session, _ = module_state(PYTHON)
+
+        assert state_strategy(cache, "app_state") == "app_state"
+        assert state_strategy(session, "app_state") == "once_lock"
+        assert state_strategy(session, "injection") == "injection"
@@ -0,0 +1,78 @@
+from python2rust.analyzers import camel_case, escape_keyword, renamings, rust_identifier, snake_case
+
+MODULE = '''MaxSize = 3
+_cache = {}
+
+
+def getValue(type, limit=1):
+    matchAll = type
+    return matchAll
+
+
+class shape_base:
+    def __init__(self, sideCount):
+        self.sides = sideCount
+
+    def computeArea(self, match):
+        return match
+
+
+def _helper(): ...
+'''
+
+
+class TestNaming:
+    def test_converts_to_rust_conventions(self):
+        """Test that functions become snake_case, classes CamelCase, constants SCREAMING_SNAKE_CASE
+        and that private definitions lose their leading underscore."""
+        assert snake_case("getHTTPResponse") == "get_http_response"
+        assert snake_case("_parseXml") == "_parse_xml"
+        assert camel_case("shape_base") == camel_case("ShapeBase") == "ShapeBase"
+        assert rust_identifier("maxSize", "constant") == "MAX_SIZE"
+        assert rust_identifier("_scale", "function") == "scale"
+        assert rust_identifier("_unused", "variable") == "_unused"
+
+    def test_escapes_keywords(self):
+        """Test that keywords become raw identifiers or take a trailing underscore, the ones a raw
+        identifier cannot escape always taking the underscore."""
+        assert escape_keyword("type") == "r#type"
+        assert escape_keyword("type", "suffix") == "type_"
+        assert escape_keyword("self") == "self_"
+        assert escape_keyword("value") == "value"
+        assert rust_identifier("async", "parameter") == "r#async"
+
+    def test_renamings_list_the_changed_identifiers(self):
+        """Test that the table lists the identifiers whose Rust names differ, with their scope and
+        reason, leaving out special methods, self and unchanged names."""
+        table = [renaming.describe() for renaming in renamings(MODULE)]
+
+        assert table == [
+            "line 1: constant MaxSize -> MAX_SIZE (case)",
+            "line 2: constant _cache -> CACHE (private, case)",
+            "line 5: function getValue -> get_value (case)",
+            "line 5: parameter type of getValue -> r#type (keyword)",
+            "line 6: variable matchAll of getValue -> match_all (case)",
+            "line 10: class shape_base -> ShapeBase (case)",
+            "line 11: parameter sideCount of shape_base.__init__ -> side_count (case)",
+            "line 14: method computeArea of shape_base -> compute_area (case)",
+            "line 14: parameter match of shape_base.computeArea -> r#match (keyword)",
+            "line 18: function _helper -> helper (private)",
+        ]
+        escaped = [renaming.rust for renaming in renamings(MODULE, "suffix")
+                   if renaming.reason == "keyword"]
+        assert escaped == ["type_", "match_"]
+
+    def test_colliding_names_are_kept_or_numbered(self):
+        """Test that identifiers of a scope and kind converting to the same name leave it to the
+        one unchanged or defined first, the others keeping their Python names, or numbered when
+        these are keywords."""
+        code = ("def _parse(): ...\ndef parse(): ...\n\n\nclass Box:\n    def getValue(self): ...\n"
+                "    def get_value(self): ...\n\n\ndef pick(type, type_): ...\n")
+
+        assert [renaming.describe() for renaming in renamings(code)] == [
+            "line 1: function _parse -> _parse (collision)",
+            "line 6: method getValue of Box -> getValue (collision)",
+            "line 10: parameter type of pick -> r#type (keyword)",
+        ]
+        assert [renaming.describe() for renaming in renamings(code, "suffix")][-1] == (
+            "line 10: parameter type of pick -> type_2 (collision)")
@@ -0,0 +1,42 @@
+from pathlib import Path
+
+from python2rust.analyzers import native_imports
+
+
+class TestNativeImports:
+    def test_flags_known_native_packages_with_their_crates(self):
+        """Test that packages backed by native libraries are found once, with the
+        crate replacing them."""
+        code = (
+            "import json\n"
+            "import numpy as np\n"
+            "from numpy.linalg import inv\n"
+            "def parse(text):\n"
+            "    from lxml import etree\n"
+            "    return etree.fromstring(text)\n"
+        )
+
+        found = native_imports(code, installed=False)
+
+        assert [(native.line, native.module, native.crate) for native in found] == [
+            (2, "numpy", "ndarray"), (5, "lxml", "libxml")
+        ]
+        assert found[0].describe() == (
+            "line 2 imports numpy, backed by C array kernels over BLAS and LAPACK: "
+            "use the ndarray crate or bind it through FFI")
+
+    def test_flags_compiled_modules_of_the_project(self, temp_dir: Path):
+        """Test that imports resolving to extension modules of the sources are found, and
+        pure ones are not."""
+        (temp_dir / "fastmath.cpython-311-x86_64-linux-gnu.so").write_bytes(b"\x7fELF")
+        (temp_dir / "kernels").mkdir()
+        (temp_dir / "kernels" / "blur.so").write_bytes(b"\x7fELF")
+        (temp_dir / "helpers.py").write_text("")
+        code = "import fastmath\nimport helpers\nfrom kernels import blur\n"
+
+        found = native_imports(code, [temp_dir], installed=False)
+
+        assert [(native.line, native.module, native.library, native.crate) for native in found] == [
+            (1, "fastmath", "the compiled module fastmath.cpython-311-x86_64-linux-gnu.so", None),
+            (3, "kernels.blur", "the compiled module blur.so", None)
+        ]
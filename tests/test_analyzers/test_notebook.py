@@ -0,0 +1,58 @@
+import json
+
+from python2rust.analyzers import SourceMap, build_source_map, notebook_cells, notebook_source
+
+
+def _notebook(*cells):
+    return json.dumps({
+        "cells": [{"cell_type": kind, "source": source} for kind, source in cells],
+        "metadata": {},
+        "nbformat": 4,
+        "nbformat_minor": 5
+    })
+
+
+NOTEBOOK = _notebook(
+    ("markdown", "# Totals"),
+    ("code", ["import math\n", "%matplotlib inline\n", "\n", "def total(values):\n",
+              "    return sum(values)\n"]),
+    ("code", "%%bash\nls"),
+    ("code", "data = [1, 2]\ntotal(data)\n!pip install numpy")
+)
+
+
+class TestNotebookSource:
+    def test_extracts_code_cells_in_order_without_magics(self):
+        """Test that code cells follow their markers in order, with magics commented out."""
+        code = notebook_source(NOTEBOOK)
+
+        assert code == (
+            "# %% cell 2\nimport math\n# %matplotlib inline\n\ndef total(values):\n"
+            "    return sum(values)\n# %% cell 3\n# %%bash\n# ls\n"
+            "# %% cell 4\ndata = [1, 2]\ntotal(data)\n# !pip install numpy\n"
+        )
+        assert [(cell.index, cell.start_line, cell.end_line) for cell in notebook_cells(code)] == [
+            (2, 1, 6), (3, 7, 9), (4, 10, 13)
+        ]
+
+    def test_library_keeps_only_imports_definitions_and_assignments(self):
+        """Test that a notebook read as a library drops the code exploring its definitions."""
+        code = notebook_source(NOTEBOOK, mode="library")
+
+        assert code == (
+            "# %% cell 2\nimport math\ndef total(values):\n    return sum(values)\n"
+            "# %% cell 3\n"
+            "# %% cell 4\ndata = [1, 2]\n"
+        )
+
+    def test_source_map_traces_items_to_cells(self):
+        """Test that the source map of a notebook gives the cell of each Python definition."""
+        code = notebook_source(NOTEBOOK)
+        rust = "fn total(values: &[i64]) -> i64 {\n    values.iter().sum()\n}\n"
+
+        source_map = SourceMap.from_dict(
+            build_source_map(code, rust, "totals.py", "src/main.rs", notebook="totals.ipynb")
+            .to_dict())
+
+        assert source_map.notebook == "totals.ipynb"
+        assert source_map.cell_at(source_map.lookup(1).start_line).index == 2
@@ -0,0 +1,43 @@
+from python2rust.analyzers.numeric import numeric_risks
+
+PYTHON = """
+def area(width, height):
+    return width * height
+
+def half(n):
+    return n // 2
+
+def label(n):
+    return "n=" + str(n)
+
+def to_int(x):
+    return int(x)
+"""
+
+RUST = """
+pub fn area(width: i32, height: i32) -> i32 { width * height }
+pub fn half(n: f64) -> f64 { (n / 2.0).floor() }
+pub fn label(n: f64) -> String { format!("n={}", n) }
+pub fn to_int(x: f64) -> i64 { x as i64 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn casts() { assert_eq!(1.5 as i32, 1); }
+}
+"""
+
+
+class TestNumericRisks:
+    def test_arithmetic_sites_at_risk(self):
+        """Test that overflow is flagged on fixed-width integers only, with divisions, float
+        conversions and Rust integer casts outside tests."""
+        risks = [(risk.language, risk.line, risk.function, risk.kind)
+                 for risk in numeric_risks(PYTHON, RUST)]
+
+        assert risks == [
+            ("python", 3, "area", "overflow"),
+            ("python", 6, "half", "division"),
+            ("python", 12, "to_int", "truncation"),
+            ("rust", 5, "to_int", "truncation"),
+        ]
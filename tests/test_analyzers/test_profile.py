@@ -0,0 +1,43 @@
+from pathlib import Path
+
+from python2rust.analyzers.profile import hot_paths
+
+
+class TestHotPaths:
+    def test_attributes_builtins_and_comprehensions(self, temp_dir: Path):
+        """Test that the time of builtins and comprehensions goes to the function they run for."""
+        source = temp_dir / "mathy.py"
+        source.write_text(
+            "def total(values):\n"
+            "    return sum(v * v for v in values)\n\n"
+            "class Grid:\n"
+            "    @staticmethod\n"
+            "    def cells(n):\n"
+            "        return [i for i in range(n)]\n\n"
+            "def cheap():\n"
+            "    return 1\n"
+        )
+        name = str(source)
+        stats = {
+            (name, 1, "total"): (1, 1, 0.1, 0.9, {}),
+            (name, 2, "<genexpr>"): (10, 10, 0.5, 0.5, {}),
+            ("~", 0, "<built-in method builtins.sum>"): (
+                1, 1, 0.3, 0.8, {(name, 1, "total"): (1, 1, 0.3, 0.8)}),
+            (name, 5, "cells"): (2, 2, 0.05, 0.1, {}),
+            (name, 7, "<listcomp>"): (2, 2, 0.05, 0.05, {}),
+            (name, 9, "cheap"): (1, 1, 0.01, 0.01, {}),
+            (name, 1, "<module>"): (1, 1, 0.0, 1.0, {}),
+            ("other.py", 3, "run"): (1, 1, 0.09, 1.0, {}),
+        }
+
+        paths = hot_paths(stats, [source], threshold=0.05)
+
+        assert [(p.qualname, p.line, p.calls) for p in paths[source]] == [
+            ("total", 1, 1), ("Grid.cells", 5, 2)]
+        total, cells = paths[source]
+        assert round(total.own_time, 3) == 0.9 and round(total.share, 2) == 0.82
+        assert round(cells.cumulative_time, 3) == 0.1
+
+    def test_no_time_no_hot_path(self, temp_dir: Path):
+        """Test that an empty profile has no hot path."""
+        assert hot_paths({}, [temp_dir / "a.py"]) == {}
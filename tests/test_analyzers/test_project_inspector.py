@@ -0,0 +1,51 @@
+import pytest
+from pathlib import Path
+from python2rust.analyzers import inspect_project
+
+
+class TestInspectProject:
+    @pytest.fixture
+    def project(self, temp_dir: Path) -> Path:
+        """Create a src-layout Flask project with a console script."""
+        (temp_dir / "src" / "shop").mkdir(parents=True)
+        (temp_dir / "tests").mkdir()
+        (temp_dir / "pyproject.toml").write_text(
+            '[project]\nname = "shop"\ndependencies = ["Flask>=2.0", "requests"]\n'
+            '[project.scripts]\nshop = "shop.cli:main"\n'
+        )
+        (temp_dir / "requirements.txt").write_text("pillow==10.0  # images\n-e .\n")
+        (temp_dir / "src" / "shop" / "__init__.py").write_text("")
+        (temp_dir / "src" / "shop" / "cli.py").write_text("import argparse\n\ndef main(): pass\n")
+        (temp_dir / "src" / "shop" / "worker.py").write_text(
+            "import json\n\nif __name__ == '__main__':\n    pass\n")
+        (temp_dir / "src" / "shop" / "models.py").write_text("from datetime import date\n")
+        return temp_dir
+
+    def test_detects_layout(self, project: Path):
+        """Test that source dir, entry points and tests are found."""
+        inspection = inspect_project(project)
+
+        assert inspection.name == "shop"
+        assert inspection.source_dir == project / "src" / "shop"
+        assert inspection.tests_dir == project / "tests"
+        assert inspection.entry_points == [
+            project / "src" / "shop" / "cli.py",
+            project / "src" / "shop" / "worker.py",
+        ]
+
+    def test_suggests_crates(self, project: Path):
+        """Test that dependencies and imports get crate suggestions."""
+        inspection = inspect_project(project)
+
+        assert inspection.dependencies == ["Flask", "pillow", "requests"]
+        assert inspection.crates["flask"] == "axum"
+        assert inspection.crates["PIL"] == "image"
+        assert inspection.crates["datetime"] == "chrono"
+        assert inspection.frameworks == ["flask", "argparse"]
+
+    def test_config_paths_are_relative(self, project: Path):
+        """Test that the written config does not depend on the project location."""
+        config = inspect_project(project).to_config()
+
+        assert config.project.source_dir == Path("src/shop")
+        assert config.project.entry_points[0] == Path("src/shop/cli.py")
@@ -0,0 +1,63 @@
+from python2rust.analyzers import python2_constructs
+
+
+class TestPython2Constructs:
+    def test_flags_python2_syntax(self):
+        """Test that Python 2 statements, literals and operators are reported with their line."""
+        code = (
+            "import sys\n"
+            "print \"total\", count\n"
+            "print >>sys.stderr, \"done\"\n"
+            "exec \"x = 1\"\n"
+            "shown = `count`\n"
+            "if count <> 0:\n"
+            "    mode = 0755 + 10L\n"
+            "name = ur\"raw\"\n"
+            "try:\n"
+            "    pass\n"
+            "except ValueError, e:\n"
+            "    raise TypeError, \"bad\"\n"
+        )
+
+        found = [(construct.line, construct.construct) for construct in python2_constructs(code)]
+
+        assert found == [
+            (2, "print statement"),
+            (3, "print statement"),
+            (4, "exec statement"),
+            (5, "backtick repr"),
+            (6, "<> operator"),
+            (7, "octal literal"),
+            (7, "long literal"),
+            (8, "ur string prefix"),
+            (11, "except X, e"),
+            (12, "raise E, message"),
+        ]
+
+    def test_flags_removed_builtins_unless_defined(self):
+        """Test that Python 2 builtins are reported unless the module binds them itself."""
+        code = (
+            "for i in xrange(3):\n"
+            "    if table.has_key(i):\n"
+            "        text = unicode(i)\n"
+        )
+        shimmed = "try:\n    unicode\nexcept NameError:\n    unicode = str\ntext = unicode(3)\n"
+
+        found = [(construct.line, construct.construct) for construct in python2_constructs(code)]
+
+        assert found == [(1, "xrange()"), (2, "dict.has_key()"), (3, "unicode")]
+        assert python2_constructs(shimmed) == []
+
+    def test_python3_code_has_none(self):
+        """Test that Python 3 code, including print calls and exception chaining, is not flagged."""
+        code = (
+            "from functools import reduce\n"
+            "def total(values, *, start=0o7):\n"
+            "    print(\"total\", file=sys.stderr)\n"
+            "    try:\n"
+            "        return reduce(lambda a, b: a + b, values, start)\n"
+            "    except (ValueError, TypeError) as e:\n"
+            "        raise RuntimeError(\"bad\", e) from e\n"
+        )
+
+        assert python2_constructs(code) == []
@@ -0,0 +1,72 @@
+import pytest
+from python2rust.analyzers import outline, python_chunks, split_module
+
+MODULE = '''import math
+from typing import List
+
+PI2 = math.pi * 2
+
+
+class Circle:
+    """A circle."""
+    radius: float
+
+    def area(self) -> float:
+        """Area of the circle."""
+        return math.pi * self.radius ** 2
+
+    # Length of the border
+    def perimeter(self) -> float:
+        return PI2 * self.radius
+
+
+def total(circles: List[Circle]) -> float:
+    return sum(c.area() for c in circles)
+
+
+if __name__ == "__main__":
+    print(total([Circle()]))
+'''
+
+
+@pytest.fixture
+def line_tokens(monkeypatch):
+    """Count a token per line so that chunk sizes do not depend on the tokenizer."""
+    monkeypatch.setattr(python_chunks, "count_tokens", lambda text: text.count("\n") + 1)
+
+
+class TestSplitModule:
+    def test_small_module_is_one_chunk(self, line_tokens):
+        """Test that a module within the budget is returned unchanged."""
+        chunks = split_module(MODULE, max_tokens=10_000)
+
+        assert len(chunks) == 1
+        assert chunks[0].code == MODULE
+
+    def test_splits_at_definitions_and_between_methods(self, line_tokens):
+        """Test that chunks follow definitions, large classes split between methods and
+        imports are repeated."""
+        chunks = split_module(MODULE, max_tokens=7)
+
+        assert [chunk.symbols for chunk in chunks] == [
+            [], ["Circle", "Circle.area"], ["Circle.perimeter"], ["total"], ["__main__"]
+        ]
+        assert all(chunk.code.startswith("import math\nfrom typing import List\n")
+                   for chunk in chunks)
+        continued = chunks[2].code
+        assert "class Circle:\n    # continued" in continued
+        assert "# Length of the border" in continued
+        assert [chunk.has_main for chunk in chunks] == [False, False, False, False, True]
+
+    def test_outline_lists_the_rest_of_the_module(self, line_tokens):
+        """Test that the outline of a chunk has the signatures defined elsewhere, without bodies."""
+        chunks = split_module(MODULE, max_tokens=7)
+
+        rest = outline(MODULE, chunks[2])
+
+        assert "PI2 = math.pi * 2" in rest
+        assert ("def area(self) -> float:\n"
+                "        \"\"\"Area of the circle.\"\"\"\n        ...") in rest
+        assert "def total(circles: List[Circle]) -> float:\n    ..." in rest
+        assert "perimeter" not in rest
+        assert "return" not in rest
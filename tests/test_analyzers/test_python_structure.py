@@ -0,0 +1,82 @@
+from python2rust.analyzers import analyze_structure, structure_summary
+from python2rust.plugins import PythonStructure
+
+CODE = '''import json
+from dataclasses import dataclass, field
+
+LIMIT = 10
+
+
+@dataclass
+class Point:
+    x: float
+    y: float = 0.0
+
+    def __add__(self, other: "Point") -> "Point":
+        return Point(self.x + other.x, self.y + other.y)
+
+    @property
+    def norm(self) -> float:
+        return (self.x ** 2 + self.y ** 2) ** 0.5
+
+
+def evens(values, seen=[]):
+    def keep(value):
+        return value % 2 == 0
+    for value in values:
+        if keep(value):
+            yield value
+
+
+async def load(path: str) -> dict:
+    try:
+        with open(path) as handle:
+            return json.loads(await read(handle))
+    except OSError:
+        raise ValueError(path)
+
+
+if __name__ == "__main__":
+    print([point.norm for point in [Point(1, 2)]][::-1])
+'''
+
+
+class TestAnalyzeStructure:
+    def test_signatures_and_features(self):
+        """Test that signatures are read as written and features found with their lines."""
+        structure = analyze_structure(CODE)
+
+        assert structure.imports == ["import json", "from dataclasses import dataclass, field"]
+        assert structure.classes == {"Point": []}
+        assert structure.constants == ["LIMIT"]
+        assert structure.main_guard
+        assert [(function.qualname, function.signature) for function in structure.functions] == [
+            ("Point.__add__", "def __add__(self, other: 'Point') -> 'Point'"),
+            ("Point.norm", "def norm(self) -> float"),
+            ("evens", "def evens(values, seen=[])"),
+            ("evens.keep", "def keep(value)"),
+            ("load", "async def load(path: str) -> dict"),
+        ]
+        assert structure.functions[1].decorators == ["property"]
+        assert structure.features == {
+            "dataclass": [8], "operator overloading": [12], "property": [16],
+            "mutable default argument": [20], "closure": [21], "generator": [20], "async": [28, 31],
+            "exception handling": [29], "context manager": [30], "raise": [33],
+            "comprehension": [37], "slice step": [37],
+        }
+
+    def test_summary_and_plugin(self):
+        """Test that the summary lists the API and features, and that
+        unparseable code gives none."""
+        summary = structure_summary(analyze_structure(CODE))
+
+        assert "- evens.keep (line 21): `def keep(value)`" in summary
+        assert "- Point.norm (line 16): `def norm(self) -> float`, decorated @property" in summary
+        assert ("- functions that yield; an `impl Iterator` or a struct "
+                "implementing Iterator (lines 20)") in summary
+        assert "Module constants: LIMIT" in summary
+
+        plugin = PythonStructure()
+        assert plugin.generation_context(CODE, {}).endswith(summary)
+        assert plugin.analysis_context("def broken(:\n") is None
+        assert plugin.analysis_context("") is None
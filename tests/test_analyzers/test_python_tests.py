@@ -0,0 +1,27 @@
+from pathlib import Path
+from python2rust.analyzers.python_tests import find_python_tests
+
+
+class TestFindPythonTests:
+    def test_tests_of_a_module(self, temp_dir: Path):
+        """Test that the tests importing or named after a module are found with
+        their conftest files."""
+        root = temp_dir / "app"
+        (root / "core").mkdir(parents=True)
+        (root / "core" / "shapes.py").write_text("def area(r): return 3.14 * r * r\n")
+        tests = temp_dir / "tests"
+        (tests / "unit").mkdir(parents=True)
+        (tests / "conftest.py").write_text("import pytest\n")
+        (tests / "unit" / "test_geometry.py").write_text("from app.core.shapes import area\n")
+        (tests / "unit" / "test_shapes.py").write_text("def test_nothing(): pass\n")
+        (tests / "test_other.py").write_text("from app.core import config\n")
+        (root / "core" / "shapes_test.py").write_text("from core import shapes\n")
+
+        found = find_python_tests(root, "core.shapes")
+
+        assert found == [
+            tests / "conftest.py",
+            root / "core" / "shapes_test.py",
+            tests / "unit" / "test_geometry.py",
+            tests / "unit" / "test_shapes.py"
+        ]
@@ -0,0 +1,60 @@
+from pathlib import Path
+
+from python2rust.analyzers import imported_dependencies, project_requirements, resolve_requirements
+from python2rust.config.crate_mappings import CrateMapping, load_crate_mappings
+
+PYPROJECT = """[project]
+name = "shop"
+dependencies = ["requests[socks]>=2.31,<3", "PyYAML ; python_version >= '3.9'"]
+
+[tool.poetry.dependencies]
+python = "^3.9"
+flask = { version = "^3.0", extras = ["async"] }
+"""
+
+
+class TestRequirements:
+    def test_reads_pyproject_and_requirements_files(self, temp_dir: Path):
+        """Test that requirements come from pyproject.toml and requirements files, with their
+        constraints, once each."""
+        (temp_dir / "pyproject.toml").write_text(PYPROJECT)
+        (temp_dir / "requirements.txt").write_text("-r base.txt\nrequests==2.32.0  # pinned\n"
+                                                   "leftpad~=1.0\n")
+
+        requirements = project_requirements(temp_dir)
+
+        assert [(r.name, r.constraint, r.source) for r in requirements] == [
+            ("flask", "^3.0", "pyproject.toml"),
+            ("leftpad", "~=1.0", "requirements.txt"),
+            ("PyYAML", "", "pyproject.toml"),
+            ("requests", ">=2.31,<3", "pyproject.toml"),
+        ]
+
+    def test_resolves_through_the_mappings_later_ones_replacing(self, temp_dir: Path):
+        """Test that requirements resolve by name and alias, overrides replace built-in mappings and
+        unmapped requirements are returned apart."""
+        (temp_dir / "requirements.txt").write_text("requests>=2.31\npyyaml\nleftpad\n")
+        mappings = [*load_crate_mappings(),
+                    CrateMapping("requests", "ureq", "http client", version="2")]
+
+        dependencies, unmapped = resolve_requirements(project_requirements(temp_dir), mappings)
+
+        by_library = {dependency.python: dependency for dependency in dependencies}
+        assert by_library["requests"].describe() == 'requests>=2.31 -> ureq = "2"'
+        assert by_library["yaml"].describe() == 'pyyaml -> serde_yaml = "0.9"'
+        assert by_library["httpx"].line == (
+            'reqwest = { version = "0.12", features = ["blocking", "json"] }')
+        assert by_library["httpx"].requirement is None
+        assert "reqwest" in by_library["requests"].alternatives
+        assert [requirement.name for requirement in unmapped] == ["leftpad"]
+
+    def test_dependencies_of_the_imported_libraries(self):
+        """Test that a module gets the dependencies of the libraries it imports, by import name or
+        dotted name."""
+        dependencies, _ = resolve_requirements([], load_crate_mappings())
+        code = ("import tomllib\nfrom concurrent.futures import wait\nimport os.path\n"
+                "from . import yaml\n")
+
+        imported = imported_dependencies(code, dependencies)
+
+        assert [dependency.crate for dependency in imported] == ["toml", "rayon"]
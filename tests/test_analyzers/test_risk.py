@@ -0,0 +1,71 @@
+from python2rust.analyzers import extra_fix_attempts, risk_warnings, scan_risks
+from python2rust.plugins import TranslationRisks
+
+
+class TestScanRisks:
+    def test_flags_dynamic_constructs(self):
+        """Test that dynamic features are reported with their line."""
+        code = (
+            "import pickle\n"
+            "class Proxy(Base, Mixin):\n"
+            "    def __getattr__(self, name):\n"
+            "        return getattr(self.target, name)\n"
+            "def run(expr, *args):\n"
+            "    return eval(expr)\n"
+        )
+
+        findings = [(f.line, f.construct, f.severity) for f in scan_risks(code)]
+
+        assert findings == [
+            (1, "import pickle", "medium"),
+            (2, "multiple inheritance", "medium"),
+            (3, "__getattr__", "high"),
+            (4, "getattr", "medium"),
+            (5, "variadic arguments", "low"),
+            (6, "eval", "high"),
+        ]
+
+    def test_plain_code_has_no_risks(self):
+        """Test that ordinary code is not flagged."""
+        assert scan_risks("def add(a, b):\n    return a + b\n") == []
+
+    def test_flags_monkeypatching_and_reflection(self):
+        """Test that patched imports and classes, runtime classes and reflection are flagged."""
+        code = (
+            "import json\n"
+            "from app import settings\n"
+            "class Config: pass\n"
+            "json.dumps = lambda value: '{}'\n"
+            "setattr(settings, 'DEBUG', True)\n"
+            "Config.level = 3\n"
+            "Model = type('Model', (object,), {})\n"
+            "def fields(obj):\n"
+            "    obj.__class__ = Config\n"
+            "    return obj.__dict__.keys()\n"
+        )
+
+        findings = [(f.line, f.construct, f.severity) for f in scan_risks(code)]
+
+        assert findings == [
+            (4, "monkeypatching", "high"),
+            (5, "monkeypatching", "high"),
+            (6, "monkeypatching", "high"),
+            (7, "type()", "high"),
+            (9, "__class__ assignment", "high"),
+            (10, "__dict__", "medium"),
+        ]
+
+    def test_budget_and_warnings(self):
+        """Test that risks add capped fix attempts, and that warnings leave low risks out."""
+        findings = scan_risks("import pickle\ndef run(*args):\n    return eval(args[0])\n")
+
+        assert extra_fix_attempts(findings) == 3
+        assert extra_fix_attempts(scan_risks("eval(a)\nexec(b)\neval(c)\n")) == 4
+        assert risk_warnings(findings) == [
+            "line 1 [medium] import pickle: pickle serializes arbitrary Python objects",
+            "line 3 [high] eval: eval() executes code built at runtime, which "
+            "has no Rust equivalent",
+        ]
+        context = TranslationRisks(findings).generation_context("", {})
+        assert "- line 2 [low] variadic arguments" in context
+        assert TranslationRisks([]).analysis_context("") is None
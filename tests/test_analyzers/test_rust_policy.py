@@ -0,0 +1,121 @@
+import pytest
+
+from python2rust.analyzers.rust_policy import (
+    analyze_rust, build_analyzer, error_style_violations, policy_violations, scanned_facts
+)
+from python2rust.config.settings import CodePolicies
+
+CODE = (
+    "fn parse(text: &str) -> u32 {\n"
+    "    text.parse().unwrap()\n"
+    "}\n"
+    "/// Reads a byte\n"
+    "fn raw(p: *const u8) -> u8 {\n"
+    "    println!(\"{}\", Some(1).expect(\"one\"));\n"
+    "    unsafe { *p }\n"
+    "}\n"
+    "struct Loader;\n"
+    "impl Loader {\n"
+    "    fn load(&self) { todo!() }\n"
+    "}\n"
+    "#[cfg(test)]\n"
+    "mod tests {\n"
+    "    #[test]\n"
+    "    fn parses() { assert_eq!(\"1\".parse::<u32>().unwrap(), 1); }\n"
+    "}\n"
+)
+POLICIES = CodePolicies(no_unwrap=True, no_expect=True, no_unsafe=True, no_panic=True,
+                        max_function_lines=3)
+
+
+class TestRustPolicy:
+    def test_violations_outside_tests(self):
+        """Test that each enabled policy is enforced on its constructs, test code being exempt."""
+        facts = {
+            "functions": [
+                {"name": "parse", "start_line": 1, "end_line": 3, "test": False},
+                {"name": "raw", "start_line": 5, "end_line": 8, "test": False},
+                {"name": "parses", "start_line": 16, "end_line": 16, "test": True},
+            ],
+            "constructs": [
+                {"kind": "call", "name": "unwrap", "line": 2, "test": False},
+                {"kind": "macro", "name": "dbg", "line": 6, "test": False},
+                {"kind": "unsafe", "name": "block", "line": 7, "test": False},
+                {"kind": "call", "name": "unwrap", "line": 16, "test": True},
+            ]
+        }
+
+        violations = policy_violations(facts, CodePolicies(no_unwrap=True, no_unsafe=True,
+                                                           max_function_lines=3,
+                                                           forbidden_macros=["dbg"]))
+
+        assert [str(violation) for violation in violations] == [
+            "line 2: `unwrap()` outside tests",
+            "line 5: function `raw` is 4 lines long, more than 3",
+            "line 6: `dbg!` is forbidden",
+            "line 7: `unsafe` block outside tests",
+        ]
+        assert policy_violations(facts, CodePolicies()) == []
+
+    def test_scans_stand_in_for_the_analyzer(self):
+        """Test that the line-based scans find the same constructs outside test modules."""
+        violations = policy_violations(scanned_facts(CODE), POLICIES)
+
+        assert [(violation.line, violation.policy) for violation in violations] == [
+            (2, "no_unwrap"), (5, "max_function_lines"), (6, "no_expect"),
+            (7, "no_unsafe"), (11, "no_panic")]
+
+    def test_error_style_leaves_out_the_other_error_crates(self):
+        """Test that each error style fails the error crates it leaves out, in the code and in the
+        dependencies, and String errors, boxed errors being fine in the std style only."""
+        rust = ("use anyhow::Result;\n"
+                "fn parse() -> std::result::Result<u8, String> { Ok(1) }\n"
+                "fn load() -> Result<u8, Box<dyn std::error::Error>> { Ok(1) }\n"
+                "#[derive(thiserror::Error, Debug)]\n"
+                "enum LoadError {}\n"
+                "#[cfg(test)]\n"
+                "mod tests {\n"
+                "    fn loads() -> anyhow::Result<()> { Ok(()) }\n"
+                "}\n")
+        toml = '[dependencies]\nthiserror = "1"\n\n[dev-dependencies]\nanyhow = "1"\n'
+
+        assert [str(violation) for violation in error_style_violations(rust, toml, "anyhow")] == [
+            "Cargo.toml depends on thiserror, which the anyhow error style leaves out",
+            "line 2: String error in the anyhow error style",
+            "line 3: Box<dyn Error> error in the anyhow error style",
+            "line 4: `thiserror` in the anyhow error style",
+        ]
+        std = error_style_violations(rust, toml, "std")
+        assert [(violation.line, violation.message) for violation in std] == [
+            (0, "Cargo.toml depends on thiserror, which the std error style leaves out"),
+            (1, "`anyhow` in the std error style"),
+            (2, "String error in the std error style"),
+            (4, "`thiserror` in the std error style"),
+        ]
+        with pytest.raises(ValueError):
+            CodePolicies(error_style="eyre")
+
+    @pytest.mark.asyncio
+    async def test_syn_analyzer(self, temp_dir):
+        """Test that the syn-based analyzer reports functions and constructs with their
+        lines, tests marked."""
+        try:
+            binary = await build_analyzer(temp_dir)
+        except RuntimeError as e:
+            pytest.skip(f"the analyzer cannot be built here: {e}")
+
+        facts = await analyze_rust(CODE, binary)
+
+        assert [(f["name"], f["start_line"], f["end_line"], f["test"])
+                for f in facts["functions"]] == [
+            ("parse", 1, 3, False), ("raw", 5, 8, False), ("Loader::load", 11, 11, False),
+            ("parses", 16, 16, True)]
+        assert [(c["name"], c["line"], c["test"]) for c in facts["constructs"]] == [
+            ("unwrap", 2, False), ("println", 6, False), ("expect", 6, False), ("block", 7, False),
+            ("todo", 11, False), ("assert_eq", 16, True), ("unwrap", 16, True)]
+        assert [(violation.line, violation.policy)
+                for violation in policy_violations(facts, POLICIES)] == [
+            (2, "no_unwrap"), (5, "max_function_lines"), (6, "no_expect"),
+            (7, "no_unsafe"), (11, "no_panic")]
+        with pytest.raises(ValueError):
+            await analyze_rust("fn broken(", binary)
@@ -0,0 +1,41 @@
+from python2rust.analyzers import scan_rust_quality
+
+
+class TestScanRustQuality:
+    def test_flags_panics_and_unsafe(self):
+        """Test that panicking calls, unsafe code and unfinished items are
+        reported with their line."""
+        code = (
+            "fn parse(text: &str) -> u32 {\n"
+            "    text.parse().unwrap()\n"
+            "}\n"
+            "fn load() {\n"
+            "    todo!()\n"
+            "}\n"
+            "fn raw(p: *const u8) -> u8 {\n"
+            "    unsafe { *p }\n"
+            "}\n"
+        )
+
+        findings = [(f.line, f.construct, f.severity) for f in scan_rust_quality(code)]
+
+        assert findings == [(2, "unwrap", "medium"), (5, "todo!", "high"), (8, "unsafe", "high")]
+
+    def test_ignores_comments_strings_and_tests(self):
+        """Test that comments, string literals and #[cfg(test)] modules are not flagged."""
+        code = (
+            "// .unwrap() is avoided here\n"
+            "fn message() -> &'static str {\n"
+            "    \"do not panic!\"\n"
+            "}\n"
+            "#[cfg(test)]\n"
+            "mod tests {\n"
+            "    #[test]\n"
+            "    fn works() {\n"
+            "        \"1\".parse::<u32>().unwrap();\n"
+            "    }\n"
+            "}\n"
+            "fn after() { None::<u8>.expect(\"value\"); }\n"
+        )
+
+        assert [(f.line, f.construct) for f in scan_rust_quality(code)] == [(12, "expect")]
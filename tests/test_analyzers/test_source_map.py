@@ -0,0 +1,107 @@
+import json
+from pathlib import Path
+from python2rust.analyzers import SourceMap, build_source_map, renamings, write_source_map
+
+PYTHON_CODE = '''class Counter:
+    def __init__(self):
+        self.count = 0
+
+    def increment(self):
+        self.count += 1
+
+
+def report(counter):
+    print(counter.count)
+
+
+if __name__ == "__main__":
+    report(Counter())
+'''
+
+RUST_CODE = '''pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    pub fn increment(&mut self) {
+        self.count += 1;
+    }
+}
+
+fn report(counter: &Counter) {
+    println!("{}", counter.count);
+}
+
+fn main() {
+    report(&Counter::new());
+}
+'''
+
+
+class TestSourceMap:
+    def test_maps_items_to_python_definitions(self):
+        """Test that structs, methods and functions are linked to their Python origin."""
+        source_map = build_source_map(PYTHON_CODE, RUST_CODE, "counter.py", "src/main.rs")
+
+        links = {m.rust.name: m.python.name for m in source_map.mappings if m.python}
+        assert links == {
+            "Counter": "Counter",
+            "Counter::new": "Counter.__init__",
+            "Counter::increment": "Counter.increment",
+            "report": "report",
+            "main": "__main__",
+        }
+        assert source_map.unmapped_python == []
+
+    def test_lookup_prefers_innermost_item(self):
+        """Test that a line inside a method resolves to the method, not the class."""
+        source_map = build_source_map(PYTHON_CODE, RUST_CODE, "counter.py", "src/main.rs")
+
+        span = source_map.lookup(11)
+
+        assert (span.name, span.start_line, span.end_line) == ("Counter.increment", 5, 6)
+
+    def test_write_and_load(self, temp_dir: Path):
+        """Test that the map is written next to main.rs and can be loaded back."""
+        map_file = write_source_map(temp_dir, Path("counter.py"), PYTHON_CODE, RUST_CODE)
+
+        assert map_file == temp_dir / "src" / "main.rs.map.json"
+        assert json.loads(map_file.read_text())["version"] == 1
+        assert SourceMap.load(map_file).lookup(17).name == "report"
+
+    def test_items_record_origin_and_warnings(self):
+        """Test that each item names the prompt that last rewrote it and matching differences."""
+        generated = RUST_CODE.replace("self.count += 1;", "self.count += 2;")
+        history = [
+            {"origin": "generation", "rust_code": generated},
+            {"origin": "verification fix 1", "rust_code": RUST_CODE},
+        ]
+        differences = {"core": ["increment must add exactly one"]}
+
+        source_map = build_source_map(PYTHON_CODE, RUST_CODE, "counter.py", "src/main.rs",
+                                      history=history, differences=differences)
+
+        increment = source_map.mapping_at(11)
+        assert increment.origin == "verification fix 1"
+        assert increment.warnings == ["core: increment must add exactly one"]
+        assert source_map.mapping_at(16).origin == "generation"
+        assert source_map.mapping_at(16).warnings == []
+
+    def test_renamed_definitions_are_mapped_and_recorded(self, temp_dir: Path):
+        """Test that Rust items named after the converted Python identifiers are linked to them, and
+        that the renaming table is written with the map."""
+        python_code = "def getTotal(itemCount):\n    return itemCount\n"
+        rust_code = "pub fn get_total(item_count: u32) -> u32 {\n    item_count\n}\n"
+        renames = renamings(python_code)
+
+        map_file = write_source_map(temp_dir, Path("totals.py"), python_code,
+                                    rust_code, renames=renames)
+
+        source_map = SourceMap.load(map_file)
+        assert source_map.mapping_at(2).python.name == "getTotal"
+        assert [(renaming.python, renaming.rust) for renaming in source_map.renames] == [
+            ("getTotal", "get_total"), ("itemCount", "item_count")]
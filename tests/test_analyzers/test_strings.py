@@ -0,0 +1,39 @@
+from python2rust.analyzers.strings import string_risks
+
+PYTHON = """
+def initials(name, n):
+    return name[:n]
+
+def last(text: str):
+    return text[-1]
+
+def width(values):
+    label = str(values[0])
+    return len(values) + len(label)
+"""
+
+RUST = """
+pub fn initials(name: &str, n: usize) -> String { name[..n].to_string() }
+pub fn last(text: &str) -> char { text.chars().last().unwrap() }
+pub fn width(values: &[i64]) -> usize { values.len() + values[0].to_string().len() }
+
+#[cfg(test)]
+mod tests {
+    fn sample(text: &str) -> usize { text.len() }
+}
+"""
+
+
+class TestStringRisks:
+    def test_string_operations_counted_in_bytes(self):
+        """Test that lengths, indexes and slices of strings are flagged, not those of lists, with
+        the byte operations of the Rust code outside tests."""
+        risks = [(risk.language, risk.line, risk.function, risk.kind)
+                 for risk in string_risks(PYTHON, RUST)]
+
+        assert risks == [
+            ("python", 3, "initials", "slicing"),
+            ("python", 6, "last", "indexing"),
+            ("python", 10, "width", "length"),
+            ("rust", 2, "initials", "slicing"),
+        ]
@@ -0,0 +1,55 @@
+from pathlib import Path
+
+from python2rust.analyzers import engine_mappings, engine_source, python_templates, write_templates
+from python2rust.config.crate_mappings import CrateMapping
+
+MODULE = '''from flask import Flask, render_template, render_template_string
+
+PAGE = "<h1>{{ title }}</h1>{% if items %}<ul></ul>{% endif %}"
+
+
+def index():
+    return render_template_string(PAGE, title="Items", items=[])
+
+
+def item(item_id):
+    return render_template("item.html", item_id=item_id)
+'''
+
+
+class TestTemplates:
+    def test_inline_and_file_templates_are_found(self, temp_dir: Path):
+        """Test that inline templates and files of the templates directory are found with their
+        variables, and the templates they extend."""
+        (temp_dir / "item.html").write_text(
+            '{% extends "base.html" %}{% block body %}{{ item_id }}{% endblock %}')
+        (temp_dir / "base.html").write_text("<html>{% block body %}{% endblock %}</html>")
+
+        page, item = python_templates(MODULE, [temp_dir])
+
+        assert page.describe() == "line 7: page.html (inline in PAGE), rendered with title, items"
+        assert page.struct == "PageTemplate"
+        assert item.describe() == "line 11: item.html (a file), rendered with item_id"
+        assert item.files == {"base.html": "<html>{% block body %}{% endblock %}</html>"}
+
+    def test_templates_written_in_the_engine_syntax(self, temp_dir: Path):
+        """Test that askama templates test their variables as Options, and that
+        maud writes no files."""
+        templates = python_templates(MODULE)
+
+        assert engine_source(templates[0].source, templates[0].variables, "askama") == (
+            "<h1>{{ title }}</h1>{% if let Some(items) = items %}<ul></ul>{% endif %}")
+        assert engine_source("{% if a %}{% elif b %}{% endif %}", [], "askama") == (
+            "{% if a %}{% else if b %}{% endif %}")
+        assert write_templates(temp_dir, templates, "maud") == []
+        assert write_templates(temp_dir, templates,
+                               "tera") == [temp_dir / "templates" / "page.html"]
+        assert (temp_dir / "templates" / "page.html").read_text() == templates[0].source
+
+    def test_template_libraries_are_mapped_to_the_engine(self):
+        """Test that the libraries of the templating category take the engine's crate."""
+        mappings = [CrateMapping("jinja2", "minijinja", "templating", version="2"),
+                    CrateMapping("flask", "axum", "web framework", version="0.7")]
+
+        assert [mapping.dependency() for mapping in engine_mappings(mappings, "maud")] == [
+            'maud = "0.26"', 'axum = "0.7"']
@@ -0,0 +1,55 @@
+from python2rust.analyzers.visibility import ReExport, module_visibility
+
+SHAPES = '''
+from .base import Shape, _registry
+from . import util
+from os import path
+
+MAX_SIDES = 12
+_cache = {}
+__version__ = "1.0"
+
+
+def area(shape): ...
+
+
+def _scale(shape): ...
+
+
+class Circle: ...
+
+
+class _Helper: ...
+'''
+
+
+class TestVisibility:
+    def test_underscore_names_are_private(self):
+        """Test that without __all__ the names without a leading underscore are public, and that a
+        package's __init__ exports the names it imports from its modules."""
+        visibility = module_visibility(SHAPES, "geometry", is_package=True)
+
+        assert visibility.public == ["MAX_SIDES", "area", "Circle"]
+        assert visibility.private == ["_cache", "_scale", "_Helper"]
+        assert visibility.reexports == [ReExport("geometry.base", "Shape"),
+                                        ReExport("geometry", "util")]
+        assert [visibility.rust_name(name)
+                for name in visibility.private] == ["CACHE", "scale", "Helper"]
+        assert visibility.describe() == (
+            "public, without a leading underscore: MAX_SIDES (constant), area (function), Circle "
+            "(class); private: _cache -> CACHE, _scale -> scale, _Helper -> Helper")
+        assert module_visibility(SHAPES, "geometry.shapes").reexports == []
+
+    def test_all_lists_the_public_names(self):
+        """Test that __all__, extended or not, decides the public names and the re-exports."""
+        code = ("from ..io.reader import read as load, Reader\n__all__ = ['load', 'parse']\n"
+                "__all__ += ['Config']\n\n\ndef parse(): ...\n\n\ndef dump(): ...\n\n\n"
+                "class Config: ...\n")
+
+        visibility = module_visibility(code, "app.core.config")
+
+        assert visibility.exported
+        assert visibility.public == ["parse", "Config"]
+        assert visibility.private == ["dump"]
+        assert visibility.reexports == [ReExport("app.io.reader", "read", "load")]
+        assert module_visibility("import os\n") is None
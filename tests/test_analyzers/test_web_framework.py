@@ -0,0 +1,29 @@
+from python2rust.analyzers import FRAMEWORKS, framework_mappings
+from python2rust.analyzers.http_routes import HttpRoute
+from python2rust.config.crate_mappings import CrateMapping
+
+
+class TestWebFramework:
+    def test_paths_and_routes_in_the_framework_syntax(self):
+        """Test that Flask and FastAPI path parameters are written in each framework's syntax, path
+        converters as the parameters matching the rest of the path."""
+        route = HttpRoute("/items/<int:item_id>/<path:rest>", ["GET", "POST"], "item")
+
+        assert FRAMEWORKS["axum"].registration(route) == (
+            '`.route("/items/:item_id/*rest", get(item))` and '
+            '`.route("/items/:item_id/*rest", post(item))`')
+        assert FRAMEWORKS["actix-web"].path("/users/{user_id}/{file:path}") == (
+            "/users/{user_id}/{file:.*}")
+        assert FRAMEWORKS["rocket"].registration(HttpRoute("/users/{user_id}",
+                                                           ["DELETE"], "remove")) == (
+            '`#[delete("/users/<user_id>")]` on a handler')
+
+    def test_web_libraries_are_mapped_to_the_framework(self):
+        """Test that the libraries of the web framework category take the framework's crate."""
+        mappings = [CrateMapping("flask", "axum", "web framework", version="0.7"),
+                    CrateMapping("requests", "reqwest", "http client", version="0.12")]
+
+        assert [mapping.dependency() for mapping in framework_mappings(mappings, "rocket")] == [
+            'rocket = { version = "0.5", features = ["json"] }', 'reqwest = "0.12"']
+        assert FRAMEWORKS["axum"].dependencies() == [
+            'axum = "0.7"', 'tokio = { version = "1", features = ["full"] }']
@@ -0,0 +1,69 @@
+from pathlib import Path
+
+import pytest
+
+from python2rust.coverage_parity import CoverageReport, ModuleCoverage, lcov_lines, measure_coverage
+
+RUST_CODE = '''fn add(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+fn sub(a: i64, b: i64) -> i64 {
+    a - b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(1, 2), 3);
+    }
+}
+'''
+
+
+class TestCoverageParity:
+    def test_counts_lcov_lines_outside_test_modules(self, temp_dir: Path):
+        """Test that lcov lines of the crate sources count, except those of #[cfg(test)] modules."""
+        crate = temp_dir / "generated"
+        (crate / "src").mkdir(parents=True)
+        main = crate / "src" / "main.rs"
+        main.write_text(RUST_CODE)
+        lcov = "\n".join([
+            f"SF:{main}", "DA:1,1", "DA:2,1", "DA:3,1", "DA:5,0", "DA:6,0", "DA:7,0",
+            "DA:14,1", "DA:15,1", "DA:16,1", "end_of_record",
+            "SF:/home/user/.cargo/registry/src/lib.rs", "DA:1,0", "end_of_record"
+        ])
+
+        assert lcov_lines(lcov, crate) == (6, 3)
+
+    def test_lists_largest_losses_first(self):
+        """Test that modules are sorted by coverage delta, unmeasured ones last."""
+        report = CoverageReport([
+            ModuleCoverage(Path("a.py"), Path("a"), 10, 9, 10, 9),
+            ModuleCoverage(Path("b.py"), Path("b"), 10, 10, 10, 4),
+            ModuleCoverage(Path("c.py"), Path("c"), 10, 5, rust_error="not generated")
+        ])
+
+        assert [module.source.name for module in report.sorted()] == ["b.py", "a.py", "c.py"]
+        assert report.sorted()[0].delta == pytest.approx(-60)
+        assert report.to_dict()["modules"][2]["rust"]["error"] == "not generated"
+        assert "-60.0" in report.format()
+        assert "c.py: Rust coverage not measured: not generated" in report.format()
+
+    @pytest.mark.asyncio
+    async def test_reports_what_cannot_be_measured(self, temp_dir: Path):
+        """Test that a module without tests or crate gets an error on each side
+        instead of numbers."""
+        source = temp_dir / "app" / "main.py"
+        source.parent.mkdir(parents=True)
+        source.write_text("def add(a, b):\n    return a + b\n")
+
+        report = await measure_coverage({source: temp_dir / "generated"}, {source: source.parent})
+
+        module = report.modules[0]
+        assert "no pytest file" in module.python_error
+        assert module.rust_error == "not generated"
+        assert module.delta is None
@@ -0,0 +1,75 @@
+import pytest
+from python2rust.config.settings import SandboxConfig, Settings
+from python2rust.doctor import (
+    ERROR, OK, DoctorReport, check_api_keys, check_disk_space,
+    WARNING, check_miri, check_rust_components, check_rust_toolchain, check_sandbox
+)
+
+
+class TestDoctor:
+    def test_rust_toolchain_and_components(self):
+        """Test that missing tools, old versions and missing components are errors with a hint."""
+        versions = {
+            ("rustc", "--version"): "rustc 1.50.0 (cb75ad5db 2021-02-10)",
+            ("cargo", "--version"): "cargo 1.79.0 (ffa9cf99a 2024-06-03)",
+            ("cargo", "clippy", "--version"): "clippy 0.1.79 (129f3b99 2024-06-10)",
+        }
+
+        def run(command):
+            return versions.get(tuple(command))
+
+        rustc, cargo = check_rust_toolchain(run)
+        assert rustc.status == ERROR and "needs 1.56" in rustc.detail
+        assert cargo.status == OK
+
+        clippy, rustfmt = check_rust_components(run)
+        assert clippy.status == OK
+        assert rustfmt.status == ERROR
+        assert rustfmt.hint == "rustup component add rustfmt"
+
+        report = DoctorReport([rustc, cargo, clippy, rustfmt])
+        assert not report.ok
+        assert "-> rustup component add rustfmt" in report.format()
+
+    def test_sandbox_needs_docker_and_its_image(self):
+        """Test that an enabled sandbox checks docker and the image, a disabled one nothing."""
+        def run(command):
+            return "Docker version 27.1.1" if command[1] == "--version" else None
+
+        assert check_sandbox(SandboxConfig(), run) == []
+        docker, image = check_sandbox(SandboxConfig(enabled=True, image="rust:1.80"), run)
+        assert docker.status == OK
+        assert image.status == ERROR and image.hint == "docker pull rust:1.80"
+
+    def test_miri_on_its_toolchain(self, temp_dir):
+        """Test that Miri is looked up on the configured toolchain only when enabled,
+        missing being a warning."""
+        def run(command):
+            return ("miri 0.1.0 (4f5e1c2 2024-09-01)"
+                    if command[1] == "+nightly-2024-09-01" else None)
+
+        assert check_miri(Settings(output_dir=temp_dir), run) == []
+        (missing,) = check_miri(Settings(output_dir=temp_dir, miri=True), run)
+        assert (missing.status == WARNING
+                and missing.hint == "rustup toolchain install nightly --component miri")
+        (found,) = check_miri(Settings(output_dir=temp_dir, miri=True,
+                                       miri_toolchain="nightly-2024-09-01"), run)
+        assert found.status == OK
+
+    @pytest.mark.asyncio
+    async def test_api_keys_offline_and_disk_space(self, temp_dir):
+        """Test that only the Claude key is required and that disk space is checked on the nearest
+        existing directory."""
+        checks = await check_api_keys(
+            {"claude": None, "hf": "hf_token", "mistral": None},
+            Settings(output_dir=temp_dir),
+            online=False
+        )
+        status = {check.name: check.status for check in checks}
+        assert status.pop("claude api key") == ERROR
+        assert set(status.values()) == {OK}
+
+        disk = check_disk_space(temp_dir / "generated" / "app", min_free_gb=0)
+        assert disk.status == OK
+        assert str(temp_dir.resolve()) in disk.detail
+        assert check_disk_space(temp_dir, min_free_gb=1e9).status == ERROR
@@ -0,0 +1,680 @@
+import json
+import sqlite3
+import sys
+import pytest
+from pathlib import Path
+from typing import Any, Callable, Dict, List
+from python2rust.migrator import Migrator, discover_python_files
+from python2rust.agent.state import MigrationState
+from python2rust.analyzers import SourceMap, database_use
+from python2rust.config.project_config import ProjectConfig
+from python2rust.config.settings import LLMChoice
+from python2rust.initializers.chain_initializer import ChainInitializer
+from python2rust.utils.stage_model import StageModel
+from python2rust.events import EventType
+from python2rust.report import FileReport
+from python2rust.runs import RunStatus, RunStore
+
+pytestmark = pytest.mark.asyncio
+
+
+@pytest.fixture
+def fake_agent(monkeypatch) -> Callable[..., List[Dict[str, Any]]]:
+    """Replace the migration agent with one translating every file into the given Rust code, and
+    return the inputs of its migrations as they happen: settings, plugins and Python code."""
+    def install(rust_code: str = "fn main() {}") -> List[Dict[str, Any]]:
+        migrations: List[Dict[str, Any]] = []
+
+        class FakeAgent:
+            def __init__(self, settings=None, plugins=(), **kwargs):
+                self.settings = settings
+                self.plugins = list(plugins)
+                self.state = MigrationState()
+                self.last_result = {"metrics": {}}
+
+            async def __aenter__(self):
+                return self
+
+            async def __aexit__(self, *exc):
+                return False
+
+            async def migrate(self, python_code, existing_code=None):
+                migrations.append({"settings": self.settings, "plugins": self.plugins,
+                                   "code": python_code})
+                return True, rust_code, "[package]"
+
+        monkeypatch.setattr("python2rust.migrator.MigrationAgent", FakeAgent)
+        return migrations
+
+    return install
+
+
+class TestMigrator:
+    @pytest.fixture
+    def python_project(self, temp_dir: Path) -> Path:
+        """Create a small Python package layout."""
+        (temp_dir / "app" / "core").mkdir(parents=True)
+        (temp_dir / "app" / "tests").mkdir()
+        (temp_dir / "app" / "main.py").write_text("print('hi')\n")
+        (temp_dir / "app" / "core" / "__init__.py").write_text("")
+        (temp_dir / "app" / "core" / "primes.py").write_text("def f(): pass\n")
+        (temp_dir / "app" / "tests" / "test_main.py").write_text("def test(): pass\n")
+        (temp_dir / "app" / "conftest.py").write_text("")
+        return temp_dir / "app"
+
+    def test_discover_skips_tests_and_packages(self, python_project: Path):
+        """Test that only program modules are discovered."""
+        files = discover_python_files(python_project)
+
+        assert [f.relative_to(python_project) for f in files] == [
+            Path("core/primes.py"),
+            Path("main.py"),
+        ]
+
+    def test_output_dirs_per_file(self, python_project: Path, temp_dir: Path):
+        """Test that each file of a directory migration gets its own crate."""
+        migrator = Migrator().source_dir(python_project).target_dir(temp_dir / "out")
+
+        output_dirs = [migrator._output_dir_for(f) for f in migrator._source_files]
+
+        assert output_dirs == [temp_dir / "out" / "core_primes", temp_dir / "out" / "main"]
+
+    def test_single_file_uses_target_dir(self, python_project: Path, temp_dir: Path):
+        """Test that a single source file is written directly into the target dir."""
+        migrator = Migrator().source_file(python_project / "main.py").target_dir(temp_dir)
+
+        assert migrator._output_dir_for(python_project / "main.py") == temp_dir
+
+    def test_model_selection(self):
+        """Test selecting models per step."""
+        migrator = Migrator().model("fixes", LLMChoice.CODESTRAL)
+
+        assert migrator._settings.llm_steps.fixes == LLMChoice.CODESTRAL
+        with pytest.raises(ValueError):
+            migrator.model("unknown", LLMChoice.CLAUDE)
+
+    def test_stage_parameters(self):
+        """Test that stage parameters from python2rust.toml reach the requests
+        of their step only."""
+        config = ProjectConfig.model_validate({"stages": {
+            "tests": {"temperature": 0.7, "stop": ["// END"]},
+            "fixes": {"temperature": 0.0, "system_prompt": "Fix {it} with minimal changes."}
+        }})
+        migrator = Migrator().project_config(config)
+        initializer = ChainInitializer(migrator._settings)
+        llms = {LLMChoice.CLAUDE: object()}
+
+        tests_llm = initializer._llm(llms, "tests")
+        assert isinstance(tests_llm, StageModel)
+        assert tests_llm.parameters == {"temperature": 0.7, "stop": ["// END"]}
+        assert initializer._llm(llms, "generation") is llms[LLMChoice.CLAUDE]
+        assert initializer._system_prompt("fixes") == "Fix {{it}} with minimal changes."
+        migrator.deterministic()
+        assert migrator._settings.stage_model_kwargs("tests") == {"stop": ["// END"]}
+        with pytest.raises(ValueError):
+            migrator.stage("review", temperature=0.5)
+
+    def test_verification_limits(self):
+        """Test that step limits from python2rust.toml replace the defaults of their step only."""
+        config = ProjectConfig.model_validate({"limits": {"run": {"timeout": 5, "memory_mb": 256}}})
+        migrator = Migrator().project_config(config)
+
+        assert migrator._settings.limits.run.model_dump() == {"timeout": 5, "memory_mb": 256}
+        assert migrator._settings.limits.build.timeout == 300
+        with pytest.raises(ValueError):
+            migrator.limits("deploy", timeout=10)
+        with pytest.raises(ValueError):
+            migrator.limits("test", timeout=0)
+
+    def test_keyword_style(self):
+        """Test that the keyword style from python2rust.toml is applied and validated."""
+        config = ProjectConfig.model_validate({"migration": {"keyword_style": "suffix"}})
+        migrator = Migrator().project_config(config)
+
+        assert migrator._renamings("def run(type): pass\n")[0].rust == "type_"
+        assert migrator.naming(False)._renamings("def run(type): pass\n") == []
+        with pytest.raises(ValueError):
+            migrator.naming(keywords="prefix")
+
+    def test_async_runtime(self, temp_dir: Path):
+        """Test that the async runtime from python2rust.toml gives asyncio its crate,
+        and is validated."""
+        (temp_dir / "main.py").write_text("import asyncio\n")
+        config = ProjectConfig.model_validate({"migration": {"async_runtime": "async-std"}})
+        migrator = Migrator().source_file(temp_dir / "main.py").project_config(config)
+
+        crates, _ = migrator._resolve_requirements()
+        assert next(crate.line for crate in crates if crate.python == "asyncio") == (
+            'async-std = { version = "1", features = ["attributes"] }')
+        crates, _ = migrator.async_runtime("sync")._resolve_requirements()
+        assert "asyncio" not in [crate.python for crate in crates]
+        with pytest.raises(ValueError):
+            migrator.async_runtime("smol")
+
+    def test_web_framework(self, temp_dir: Path):
+        """Test that the web framework from python2rust.toml gives the web libraries its crate,
+        and is validated."""
+        (temp_dir / "main.py").write_text("import flask\n")
+        config = ProjectConfig.model_validate({"migration": {"web_framework": "actix-web"}})
+        migrator = Migrator().source_file(temp_dir / "main.py").project_config(config)
+
+        crates, _ = migrator._resolve_requirements()
+        assert next(crate.line for crate in crates if crate.python == "flask") == 'actix-web = "4"'
+        with pytest.raises(ValueError):
+            migrator.web_framework("warp")
+
+    def test_template_engine(self, temp_dir: Path):
+        """Test that the template engine from python2rust.toml gives jinja2 its crate, and is
+        validated."""
+        (temp_dir / "main.py").write_text("import jinja2\n")
+        config = ProjectConfig.model_validate({"migration": {"template_engine": "tera"}})
+        migrator = Migrator().source_file(temp_dir / "main.py").project_config(config)
+
+        crates, _ = migrator._resolve_requirements()
+        assert next(crate.line for crate in crates if crate.python == "jinja2") == 'tera = "1"'
+        with pytest.raises(ValueError):
+            migrator.template_engine("handlebars")
+
+    def test_database_layer(self, temp_dir: Path):
+        """Test that the database layer from python2rust.toml gives the SQL drivers its crate,
+        and is validated."""
+        (temp_dir / "main.py").write_text("import sqlite3\n")
+        config = ProjectConfig.model_validate({"migration": {"database_layer": "diesel"}})
+        migrator = Migrator().source_file(temp_dir / "main.py").project_config(config)
+
+        crates, _ = migrator._resolve_requirements()
+        assert next(crate.line for crate in crates if crate.python == "sqlite3") == (
+            'diesel = { version = "2", features = ["r2d2", "sqlite"] }')
+        with pytest.raises(ValueError):
+            migrator.database_layer("rusqlite")
+
+    async def test_query_database_holds_the_tables(self, temp_dir: Path):
+        """Test that sqlx's queries on SQLite get a scratch database with the tables of the module,
+        and that other backends and layers get none."""
+        use = database_use('import sqlite3\nSCHEMA = "CREATE TABLE users (id '
+                           'INTEGER PRIMARY KEY)"\n')
+        migrator = Migrator()
+
+        url = await migrator._query_database(temp_dir, use)
+        assert url == f"sqlite:///{(temp_dir / 'debug' / 'queries.db').resolve()}"
+        tables = sqlite3.connect(temp_dir / "debug" / "queries.db").execute(
+            "SELECT name FROM sqlite_master WHERE type = 'table'").fetchall()
+        assert tables == [("users",)]
+        assert await migrator.database_layer("diesel")._query_database(temp_dir, use) is None
+        use.backend = "postgres"
+        assert await migrator.database_layer("sqlx")._query_database(temp_dir, use) is None
+
+    def test_checkers_validation(self):
+        """Test that checker names are validated."""
+        with pytest.raises(ValueError):
+            Migrator().checkers("lint")
+        with pytest.raises(ValueError):
+            Migrator().checkers("test")
+
+    async def test_migrate_requires_sources(self):
+        """Test that migrating without sources fails early."""
+        with pytest.raises(ValueError):
+            await Migrator().tokens(claude="token").migrate()
+
+    async def test_dependencies_migrated_first(self, temp_dir: Path):
+        """Test that imported modules are migrated first and their signatures reach dependents."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "main.py").write_text("from util import helper\n")
+        (temp_dir / "app" / "util.py").write_text("def helper(): pass\n")
+        migrator = (
+            Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+            .tokens(claude="token").workers(2).checkpoints(False)
+        )
+        calls = []
+
+        async def fake_migrate_file(source, plugins):
+            calls.append((source.name, [p.name for p in plugins]))
+            return FileReport(source=source, output_dir=temp_dir, success=True,
+                              rust_code="pub fn helper() {}")
+
+        migrator._migrate_file = fake_migrate_file
+        report = await migrator.migrate()
+
+        assert report.success
+        assert calls == [
+            ("util.py", ["structure", "docs", "comments", "doctests"]),
+            ("main.py", ["structure", "docs", "comments", "dependencies", "doctests"])
+        ]
+
+    async def test_related_definitions_reach_prompts(self, temp_dir: Path):
+        """Test that definitions of modules a file does not import are retrieved
+        into its prompts."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "report.py").write_text(
+            "def report(settings_path):\n    return load_settings(settings_path)\n")
+        (temp_dir / "app" / "settings.py").write_text("def load_settings(settings_path):\n"
+                                                      "    return {}\n")
+        migrator = (
+            Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+            .tokens(claude="token").checkpoints(False)
+        )
+        plugins_of = {}
+
+        async def fake_migrate_file(source, plugins):
+            plugins_of[source.name] = plugins
+            return FileReport(source=source, output_dir=temp_dir, success=True)
+
+        migrator._migrate_file = fake_migrate_file
+        await migrator.migrate()
+
+        [retrieval] = [p for p in plugins_of["report.py"] if p.name == "retrieval"]
+        assert [(d.module, d.name)
+                for d in retrieval.definitions] == [("settings", "load_settings")]
+        assert "def load_settings(settings_path):" in retrieval.generation_context("", {})
+
+        plugins_of.clear()
+        await migrator.context_definitions(0).migrate()
+        assert [p.name for p in plugins_of["report.py"]] == ["structure", "docs",
+                                                             "comments", "doctests"]
+
+    async def test_recorded_calls_reach_tests_prompt(self, temp_dir: Path):
+        """Test that the calls recorded while the trace command runs are given to
+        write the Rust tests."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "shapes.py").write_text(
+            "def area(r):\n"
+            "    if r < 0:\n"
+            "        raise ValueError('negative radius')\n"
+            "    return r * r\n\n"
+            "if __name__ == '__main__':\n"
+            "    area(2)\n"
+            "    area(2)\n"
+            "    area(-1)\n")
+        migrator = (
+            Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+            .tokens(claude="token").checkpoints(False)
+            .trace_calls(f"{sys.executable} {temp_dir / 'app' / 'shapes.py'}")
+        )
+        plugins_of = {}
+
+        async def fake_migrate_file(source, plugins):
+            plugins_of[source.name] = plugins
+            return FileReport(source=source, output_dir=temp_dir, success=True)
+
+        migrator._migrate_file = fake_migrate_file
+        await migrator.migrate()
+
+        [traces] = [p for p in plugins_of["shapes.py"] if p.name == "traces"]
+        context = traces.tests_context("")
+        assert ("- `area(2)` returned `4`\n"
+                "- `area(-1)` raised `ValueError('negative radius')`") in context
+
+    async def test_unchanged_files_are_reused(self, temp_dir: Path):
+        """Test that a second run only migrates files whose inputs changed."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "main.py").write_text("import util\n")
+        (temp_dir / "app" / "util.py").write_text("def helper(): pass\n")
+        migrated = []
+
+        def make_migrator(api: str) -> Migrator:
+            migrator = (
+                Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+                .tokens(claude="token")
+            )
+
+            async def fake_migrate_file(source, plugins):
+                migrated.append(source.name)
+                output_dir = migrator._output_dir_for(source)
+                (output_dir / "src").mkdir(parents=True, exist_ok=True)
+                (output_dir / "src" / "main.rs").write_text(api)
+                (output_dir / "Cargo.toml").write_text("")
+                return FileReport(source=source, output_dir=output_dir, success=True, rust_code=api)
+
+            migrator._migrate_file = fake_migrate_file
+            return migrator
+
+        await make_migrator("pub fn helper() {}").migrate()
+        report = await make_migrator("pub fn helper() {}").migrate()
+        assert migrated == ["util.py", "main.py"]
+        assert all(f.metrics.get("reused") for f in report.files)
+
+        (temp_dir / "app" / "util.py").write_text("def helper(x): pass\n")
+        await make_migrator("pub fn helper(x: i32) {}").migrate()
+        assert migrated[2:] == ["util.py", "main.py"]
+
+    async def test_budget_stops_run_resumably(self, temp_dir: Path):
+        """Test that exceeding the run budget cancels the remaining files and keeps the spend."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "main.py").write_text("import util\n")
+        (temp_dir / "app" / "util.py").write_text("def helper(): pass\n")
+        migrator = (
+            Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+            .tokens(claude="token").budget(run_tokens=1000)
+        )
+        migrated = []
+
+        async def fake_migrate_file(source, plugins):
+            migrated.append(source.name)
+            migrator._events.for_file(source).emit(
+                EventType.RESPONSE_RECEIVED, input_tokens=900, output_tokens=200, model="gpt-4o")
+            return FileReport(source=source, output_dir=temp_dir, success=True)
+
+        migrator._migrate_file = fake_migrate_file
+        report = await migrator.migrate()
+
+        assert migrated == ["util.py"]
+        assert report.cancelled
+        assert report.files[1].error.startswith("Budget exhausted: the run token "
+                                                "limit of 1000 tokens")
+        assert report.budget["tokens"] == 1100 and report.budget["cost"] == 0.00425
+        checkpoint = RunStore(temp_dir / "out").load(report.run_id)
+        assert checkpoint.status == RunStatus.CANCELLED
+        assert checkpoint.budget["files"][str(temp_dir / "app" / "util.py")]["tokens"] == 1100
+
+    def test_plan_orders_and_prices_files(self, temp_dir: Path):
+        """Test that the plan follows the import graph and prices every stage."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "main.py").write_text("import util\nprint(eval('1'))\n")
+        (temp_dir / "app" / "util.py").write_text("def helper(): pass\n")
+
+        plan = Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out").plan()
+
+        assert [f.module for f in plan.files] == ["util", "main"]
+        assert plan.files[1].dependencies == ["util"]
+        assert [r.construct for r in plan.files[1].risks] == ["eval"]
+        assert "implementation" in plan.files[0].stages
+        assert plan.total_cost > 0
+        assert "cargo build" in " ".join(plan.verification_steps)
+
+    def test_plan_records_the_module_state_strategy(self, temp_dir: Path):
+        """Test that the plan lists the module-level state of each file with the
+        strategy holding it."""
+        (temp_dir / "main.py").write_text("hits = 0\n\ndef hit():\n    global hits\n"
+                                          "    hits += 1\n")
+
+        plan = Migrator().source_file(temp_dir / "main.py").module_state("once_lock").plan()
+
+        assert [state.name for state in plan.files[0].module_state] == ["hits"]
+        assert plan.to_dict()["state_strategy"] == "once_lock"
+        assert "main.py: line 1: hits (mutable global, changed by hit) -> once_lock" in (
+            plan.format())
+        unmanaged = Migrator().source_file(temp_dir / "main.py").module_state("none").plan()
+        assert unmanaged.files[0].module_state == []
+
+    def test_plan_lists_decorators_without_a_strategy(self, temp_dir: Path):
+        """Test that plans list the decorators neither the catalog nor python2rust.toml maps."""
+        (temp_dir / "main.py").write_text(
+            "import functools\nimport metrics\n\n\n@metrics.counted\n@functools.cache\n"
+            "def f():\n    pass\n")
+
+        plan = Migrator().source_file(temp_dir / "main.py").plan()
+
+        assert [use.name
+                for use in plan.files[0].decorators] == ["metrics.counted", "functools.cache"]
+        assert "main.py: line 5: @metrics.counted on f" in plan.format()
+        mapped = (Migrator().source_file(temp_dir / "main.py")
+                  .decorators({"metrics.*": "a counter"}).plan())
+        assert "Decorators without a translation strategy" not in mapped.format()
+
+    def test_plan_lists_the_error_module(self, temp_dir: Path):
+        """Test that plans list the exception classes of the project with the Rust error of each."""
+        (temp_dir / "main.py").write_text(
+            "class AppError(Exception):\n    pass\n\n\ndef run():\n    raise AppError('failed')\n")
+
+        plan = Migrator().source_file(temp_dir / "main.py").plan()
+
+        assert [exception.name for exception in plan.errors] == ["AppError"]
+        assert ("  main.AppError (line 1) -> struct AppError, a variant of Error, raised at 1 site"
+                in plan.format())
+        assert Migrator().source_file(temp_dir / "main.py").error_module(False).plan().errors == []
+        assert (Migrator().source_file(temp_dir / "main.py").policies(error_style="anyhow")
+                .plan().errors == [])
+
+    async def test_crates_share_the_errors_crate(self, temp_dir: Path, fake_agent):
+        """Test that runs write the errors crate once, and into each crate the src/error.rs declared
+        with `mod error;` re-exporting it, the plugin depending on it by path."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "errors.py").write_text("class AppError(Exception):\n    pass\n")
+        (temp_dir / "app" / "fetch.py").write_text(
+            "from errors import AppError\n\n\ndef run():\n    raise AppError('failed')\n")
+        migrations = fake_agent()
+        migrator = (Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+                    .checkpoints(False))
+        await migrator.tokens(claude="token").migrate()
+
+        lib = temp_dir / "out" / "shared" / "errors" / "src" / "lib.rs"
+        assert "pub struct AppError" in lib.read_text()
+        assert ((temp_dir / "out" / "fetch" / "src" / "error.rs").read_text()
+                .endswith("pub use errors::*;\n"))
+        assert [plugin.crate_path for migration in migrations for plugin in migration["plugins"]
+                if plugin.name == "errors"] == ["../shared/errors", "../shared/errors"]
+
+    def test_plan_lists_the_requirement_crates(self, temp_dir: Path):
+        """Test that plans list the crate of each requirement, overridden or mapped,
+        and those unmapped."""
+        (temp_dir / "requirements.txt").write_text("requests>=2.31\nclick\nobscure-lib==1.0\n")
+        (temp_dir / "main.py").write_text("import requests\n")
+
+        migrator = Migrator().source_file(temp_dir / "main.py").crates({"click": "argh"})
+        plan = migrator.plan()
+
+        assert [dependency.crate for dependency in plan.requirements] == ["reqwest", "argh"]
+        assert plan.unmapped_requirements == ["obscure-lib==1.0"]
+        assert "  requests>=2.31 -> reqwest = " in plan.format()
+        disabled = Migrator().source_file(temp_dir / "main.py").requirement_crates(False)
+        assert disabled.plan().requirements == []
+
+    async def test_disallowed_crate_licenses_fail_the_plan_and_the_run(self, temp_dir: Path):
+        """Test that a crate under a license outside the allowed ones fails plans, with the allowed
+        crates of its category, and runs before any generation."""
+        (temp_dir / "requirements.txt").write_text("requests\n")
+        (temp_dir / "main.py").write_text("import requests\n")
+        state = temp_dir / "out" / ".python2rust"
+        state.mkdir(parents=True)
+        (state / "crate_licenses.json").write_text(json.dumps({
+            "reqwest@0.12": {"version": "0.12.9", "license": "GPL-3.0-only"},
+            "ureq@*": {"version": "2.10.1", "license": "MIT OR Apache-2.0"},
+        }))
+        migrator = Migrator().source_file(temp_dir / "main.py").target_dir(temp_dir / "out")
+        migrator.tokens(claude="token").crate_licenses(allow=["MIT", "Apache-2.0"])
+
+        plan = migrator.plan()
+
+        assert [violation.crate for violation in plan.license_violations] == ["reqwest"]
+        assert ("  requests -> reqwest 0.12.9 (GPL-3.0-only); allowed instead: "
+                "ureq (MIT OR Apache-2.0)"
+                in plan.format())
+        with pytest.raises(ValueError, match="reqwest 0.12.9"):
+            await migrator.migrate()
+        assert not (temp_dir / "out" / "main").exists()
+
+    async def test_unapproved_class_plans_are_not_migrated(self, temp_dir: Path):
+        """Test that with approvals required a file with classes waits for its
+        plan to be approved."""
+        source = temp_dir / "shapes.py"
+        source.write_text("class Shape:\n    pass\n\n\nclass Circle(Shape):\n    pass\n")
+        migrator = Migrator().source_file(source).target_dir(temp_dir / "out").checkpoints(False)
+        migrator.tokens(claude="token").class_plan(approve=True)
+
+        assert "  " + str(source) + " (not approved):" in migrator.plan().format()
+        report = await migrator.migrate()
+
+        [file] = report.files
+        assert not file.success
+        assert "not approved; review it with `python2rust classes`" in file.error
+
+    def test_plan_prices_consensus_candidates(self, temp_dir: Path):
+        """Test that consensus plans the generation of every candidate and the
+        extra verifications."""
+        (temp_dir / "main.py").write_text("print('hi')\n")
+
+        plan = Migrator().source_file(temp_dir / "main.py").consensus("claude", "openai").plan()
+
+        stages = plan.files[0].stages
+        assert stages["implementation (openai)"]["model"] == "gpt-4o"
+        assert "implementation" not in stages
+        assert stages["consensus"]["input"] < stages["verification"]["input"]
+
+    async def test_check_verifies_existing_code_without_fixes(self, temp_dir: Path, monkeypatch):
+        """Test that check reruns verification on generated code and reports missing crates."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "main.py").write_text("print('hi')\n")
+        (temp_dir / "app" / "util.py").write_text("def helper(): pass\n")
+        (temp_dir / "out" / "main" / "src").mkdir(parents=True)
+        (temp_dir / "out" / "main" / "src" / "main.rs").write_text("fn main() {}")
+        (temp_dir / "out" / "main" / "Cargo.toml").write_text("[package]")
+        runs = []
+
+        class FakeAgent:
+            def __init__(self, settings, workflows, **kwargs):
+                self.settings = settings
+                self.workflows = workflows
+                self.last_result = {"metrics": {"verification_matches": False}}
+                self.last_error = None
+
+            async def __aenter__(self):
+                return self
+
+            async def __aexit__(self, *exc):
+                return False
+
+            async def migrate(self, python_code, existing_code=None):
+                runs.append((self.workflows, self.settings.apply_fixes, existing_code))
+                return False, None, None
+
+        monkeypatch.setattr("python2rust.migrator.MigrationAgent", FakeAgent)
+        migrator = Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+        report = await migrator.tokens(claude="token").checkers("build").check()
+
+        assert runs == [(["verify", "build"], False, ("fn main() {}", "[package]"))]
+        assert [f.error for f in report.files] == [
+            "Behavior differs from the Python source",
+            "[Errno 2] No such file or directory: "
+            f"'{temp_dir / 'out' / 'util' / 'src' / 'main.rs'}'"
+        ]
+        assert not report.success
+
+    async def test_risky_files_get_warnings_and_fix_attempts(self, temp_dir: Path, fake_agent):
+        """Test that dynamic constructs reach the prompts and report and raise the fix attempts."""
+        source = temp_dir / "calc.py"
+        source.write_text("import math\nmath.pi = 3\n\ndef run(expression):\n"
+                          "    return eval(expression)\n")
+        migrations = fake_agent()
+        migrator = Migrator().source_file(source).target_dir(temp_dir / "out").checkpoints(False)
+        report = await migrator.tokens(claude="token").migrate()
+
+        [migration] = migrations
+        assert migration["settings"].extra_fix_attempts == 4
+        assert [plugin.name for plugin in migration["plugins"]] == [
+            "structure", "docs", "comments", "doctests", "risks", "visibility"]
+        [file] = report.files
+        assert file.metrics["risks"] == {"low": 0, "medium": 0, "high": 2}
+        assert file.warnings == [
+            "line 2 [high] monkeypatching: assigning math.pi changes it for all its users",
+            "line 5 [high] eval: eval() executes code built at runtime, which "
+            "has no Rust equivalent",
+        ]
+
+        migrations.clear()
+        await migrator.risk_report(False).migrate()
+        [migration] = migrations
+        assert migration["settings"].extra_fix_attempts == 0
+        assert [plugin.name for plugin in migration["plugins"]] == [
+            "structure", "docs", "comments", "doctests", "visibility"]
+
+    async def test_python2_files_fail_or_are_converted(self, temp_dir: Path, fake_agent):
+        """Test that Python 2 files fail with their constructs, or are translated once converted."""
+        source = temp_dir / "hello.py"
+        source.write_text("print \"hello\"\n")
+        script = temp_dir / "fake_2to3.py"
+        script.write_text("import sys\npath = sys.argv[1]\n"
+                          "open(path, 'w').write('print(\"hello\")\\n')\n")
+        migrations = fake_agent()
+        migrator = Migrator().source_file(source).target_dir(temp_dir / "out").checkpoints(False)
+        report = await migrator.tokens(claude="token").migrate()
+
+        assert migrations == []
+        [file] = report.files
+        assert not file.success
+        assert file.error == (
+            "Python 2 source, convert it first or set python2 to convert: line 1 print statement")
+
+        report = (await migrator.python2("convert", f'"{sys.executable}" "{script}" {{file}}')
+                  .migrate())
+
+        assert [migration["code"] for migration in migrations] == ["print(\"hello\")\n"]
+        [file] = report.files
+        assert file.success
+        assert file.metrics["python2_converted"] == 1
+        assert (file.output_dir / "debug" / "python3" / "hello.py").read_text() == (
+            migrations[0]["code"])
+        assert file.warnings[0].startswith("Translated from a conversion of its Python 2 source")
+
+    async def test_dead_code_is_left_untranslated(self, temp_dir: Path, fake_agent):
+        """Test that unused definitions are removed from the translated code and
+        listed in the report."""
+        source = temp_dir / "main.py"
+        source.write_text("def _unused():\n    pass\n\ndef run():\n    pass\n\nrun()\n")
+        migrations = fake_agent()
+        migrator = Migrator().source_file(source).target_dir(temp_dir / "out").checkpoints(False)
+        report = await migrator.tokens(claude="token").skip_dead_code().migrate()
+
+        assert [migration["code"]
+                for migration in migrations] == ["\ndef run():\n    pass\n\nrun()\n"]
+        [file] = report.files
+        assert file.dead_code == ["_unused"]
+        assert migrator.plan().files[0].dead_code == ["_unused"]
+
+    async def test_notebooks_are_migrated_from_their_code_cells(self, temp_dir: Path, fake_agent):
+        """Test that a notebook is migrated as its extracted code, with its cells
+        in the source map."""
+        (temp_dir / "app").mkdir()
+        (temp_dir / "app" / "helpers.py").write_text("def double(x):\n    return x * 2\n")
+        (temp_dir / "app" / "report.ipynb").write_text(json.dumps({"cells": [
+            {"cell_type": "code", "source": "from helpers import double\n"},
+            {"cell_type": "code", "source": "def show():\n    print(double(2))\n\nshow()\n"}
+        ]}))
+        migrations = fake_agent("fn show() {}\nfn main() {}")
+        migrator = (Migrator().source_dir(temp_dir / "app").target_dir(temp_dir / "out")
+                    .checkpoints(False))
+        report = await migrator.tokens(claude="token").notebooks("library").migrate()
+
+        assert [file.output_dir.name for file in report.files] == ["helpers", "report"]
+        assert [migration["code"] for migration in migrations
+                if migration["code"].startswith("# %%")] == [
+            "# %% cell 1\nfrom helpers import double\n# %% cell 2\ndef show():\n"
+            "    print(double(2))\n"]
+        notebook = report.files[1]
+        assert notebook.metrics["notebook"] == str(temp_dir / "app" / "report.ipynb")
+        source_map = SourceMap.load(notebook.output_dir / "src" / "main.rs.map.json")
+        assert source_map.notebook == str(temp_dir / "app" / "report.ipynb")
+        assert source_map.cell_at(source_map.lookup(1).start_line).index == 2
+
+    async def test_profiling_limits_the_migration_to_hot_paths(self, temp_dir: Path, fake_agent):
+        """Test that only the hot functions, what they use and the files they
+        import are migrated."""
+        (temp_dir / "mathy.py").write_text(
+            "def _start():\n    return 2\n\n"
+            "def primes(n):\n"
+            "    return [i for i in range(_start(), n) if all(i % d for d in range(2, i))]\n\n"
+            "def cheap():\n    return 1\n"
+        )
+        (temp_dir / "report.py").write_text("def show(values):\n    print(values)\n")
+        (temp_dir / "workload.py").write_text("from mathy import primes, cheap\n"
+                                              "primes(2000)\ncheap()\n")
+        migrations = fake_agent()
+        migrator = (
+            Migrator()
+            .source_file(temp_dir / "mathy.py").source_file(temp_dir / "report.py")
+            .target_dir(temp_dir / "out").checkpoints(False)
+            .profile(f'cd "{temp_dir}" && "{sys.executable}" workload.py',
+                     threshold=0.2, limit=True)
+        )
+        plan = migrator.plan()
+        report = await migrator.tokens(claude="token").migrate()
+
+        assert [f.module for f in plan.files] == ["mathy"] and plan.skipped == ["report"]
+        assert [path.qualname for path in plan.files[0].hot_paths] == ["primes"]
+        assert plan.files[0].cold_code == ["cheap"]
+        [migration] = migrations
+        code = migration["code"]
+        assert "def primes" in code and "def _start" in code and "def cheap" not in code
+        assert "hot_paths" in [plugin.name for plugin in migration["plugins"]]
+        [file] = report.files
+        assert file.metrics["cold_code"] == ["cheap"]
+        assert report.warnings == ["Not migrated, without hot paths when profiled: report"]
@@ -0,0 +1,36 @@
+import json
+from pathlib import Path
+from python2rust.main import build_parser
+from python2rust.commands.common import OUTPUT_SCHEMA_VERSION
+
+
+class TestOutputFormat:
+    def test_every_command_accepts_format(self):
+        """Test that --format json and the --json shorthand are available on every subcommand."""
+        parser = build_parser()
+
+        assert parser.parse_args(["status", "--format", "json"]).format == "json"
+        assert parser.parse_args(["diff", "--json"]).format == "json"
+        assert parser.parse_args(["plan"]).format == "text"
+
+    def test_json_document_envelope(self, temp_dir: Path, capsys):
+        """Test that commands print a single versioned document, also on errors."""
+        parser = build_parser()
+        args = parser.parse_args(["resume", "--list", "--output-dir", str(temp_dir),
+                                  "--format", "json"])
+
+        assert args.handler(args)
+        document = json.loads(capsys.readouterr().out)
+        assert document == {
+            "schema_version": OUTPUT_SCHEMA_VERSION,
+            "command": "resume",
+            "success": True,
+            "error": None,
+            "result": {"runs": []}
+        }
+
+        args = parser.parse_args(["blame", "main.rs", "--format", "json"])
+        assert not args.handler(args)
+        document = json.loads(capsys.readouterr().out)
+        assert document["success"] is False
+        assert "expected <rust-file>:<line>" in document["error"]
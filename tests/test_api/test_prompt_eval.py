@@ -0,0 +1,47 @@
+import json
+import pytest
+from pathlib import Path
+from python2rust.migrator import Migrator
+from python2rust.prompt_eval import compare_prompt_sets
+from python2rust.prompts.loader import prompt_set_version
+from python2rust.report import FileReport
+from python2rust.runs import RunStore
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestPromptComparison:
+    async def test_compare_reports_each_prompt_set(self, temp_dir: Path):
+        """Test that both prompt sets migrate the corpus, tag their run and are scored."""
+        (temp_dir / "corpus").mkdir()
+        (temp_dir / "corpus" / "primes.py").write_text("def f(): pass\n")
+        (temp_dir / "corpus" / "server.py").write_text("print('hi')\n")
+        (temp_dir / "tuned").mkdir()
+        (temp_dir / "tuned" / "VERSION").write_text("tuned-v2\n")
+        (temp_dir / "tuned" / "generation.txt").write_text("Translate this:\n{python_code}\n")
+
+        def make_migrator() -> Migrator:
+            migrator = Migrator().tokens(claude="token")
+
+            async def fake_migrate_file(source, plugins):
+                tuned = migrator._settings.prompts_dir is not None
+                success = tuned or source.name == "primes.py"
+                return FileReport(source=source, output_dir=migrator._output_dir_for(source),
+                                  success=success, metrics={"iterations": 1 if tuned else 3})
+
+            migrator._migrate_file = fake_migrate_file
+            return migrator
+
+        comparison = await compare_prompt_sets(
+            "default", str(temp_dir / "tuned"), temp_dir / "corpus",
+            temp_dir / "out", make_migrator)
+
+        assert comparison.baseline.success_rate == 0.5
+        assert comparison.candidate.success_rate == 1.0
+        assert comparison.baseline.mean_iterations == 3.0
+        assert comparison.candidate.version.startswith("tuned-v2-")
+        assert comparison.baseline.version == prompt_set_version()
+        run = RunStore(temp_dir / "out" / "candidate").latest()
+        assert run.prompt_version == comparison.candidate.version
+        assert "success rate" in comparison.format()
+        assert json.dumps(comparison.to_dict())
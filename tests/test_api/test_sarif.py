@@ -0,0 +1,51 @@
+import json
+from pathlib import Path
+from python2rust.analyzers import write_source_map
+from python2rust.sarif import collect_findings, to_sarif
+
+PYTHON_CODE = '''def parse(text):
+    return int(text)
+
+
+def load(name):
+    return eval(name)
+'''
+
+RUST_CODE = '''fn parse(text: &str) -> i64 {
+    text.parse().unwrap()
+}
+'''
+
+
+class TestSarif:
+    def test_exports_findings_with_locations(self, temp_dir: Path):
+        """Test that divergences, quality issues and unmapped definitions become SARIF results."""
+        source = temp_dir / "app" / "main.py"
+        source.parent.mkdir(parents=True)
+        source.write_text(PYTHON_CODE)
+        output_dir = temp_dir / "generated"
+        (output_dir / "src").mkdir(parents=True)
+        (output_dir / "src" / "main.rs").write_text(RUST_CODE)
+        write_source_map(output_dir, source, PYTHON_CODE, RUST_CODE, differences={
+            "error_handling": ["parse panics on invalid input where Python raises ValueError"]
+        })
+
+        findings = collect_findings(source, output_dir)
+        sarif = to_sarif(findings, temp_dir, "0.1.0")
+
+        results = {result["ruleId"]: result for result in sarif["runs"][0]["results"]}
+        assert set(results) == {"unwrap", "divergence", "unmapped-definition"}
+        assert results["unwrap"]["locations"][0]["physicalLocation"] == {
+            "artifactLocation": {"uri": "generated/src/main.rs", "uriBaseId": "%SRCROOT%"},
+            "region": {"startLine": 2}
+        }
+        divergence = results["divergence"]
+        assert "ValueError" in divergence["message"]["text"]
+        assert divergence["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"] == (
+            "app/main.py")
+        unmapped = results["unmapped-definition"]["locations"][0]["physicalLocation"]
+        assert (unmapped["artifactLocation"]["uri"],
+                unmapped["region"]["startLine"]) == ("app/main.py", 5)
+        assert sarif["version"] == "2.1.0"
+        assert {rule["id"] for rule in sarif["runs"][0]["tool"]["driver"]["rules"]} >= set(results)
+        json.dumps(sarif)
@@ -0,0 +1,791 @@
+import json
+import sys
+import pytest
+from pathlib import Path
+from python2rust.agent.state import MigrationState
+from python2rust.builders import RustBuilder
+from python2rust.builders.memory import MemoryProfiler
+from python2rust.checkers import (
+    CargoTest, Checker, CheckResult, Clippy, CommentCheck, DependencyAuditCheck, Miri,
+    PolicyCheck, RustfmtCheck, ShellChecker
+)
+from python2rust.checkers.audit import dependency_violations
+from python2rust.analyzers.fuzz_targets import parse_fuzz_type
+from python2rust.checkers.cargo import failing_tests
+from python2rust.checkers.cli import CliEquivalence, CliRun, is_cli_script, run_differences
+from python2rust.checkers.fuzz import rust_literal, same_result
+from python2rust.analyzers.fuzz_targets import fuzz_targets
+from python2rust.analyzers.sql_schema import orm_framework, rust_schema_statements
+from python2rust.builders.database import SqliteDatabase
+from python2rust.checkers.schema import SchemaEquivalence, schema_differences
+from python2rust.checkers.numeric import NumericBoundaryCheck, boundary_cases
+from python2rust.analyzers.openapi import openapi_differences
+from python2rust.checkers.openapi import OpenApiEquivalence
+from python2rust.checkers.strings import UnicodeCheck
+from python2rust.checkers.html_diff import html_outline
+from python2rust.checkers.http import (
+    HttpEquivalence, HttpResponse, response_differences, write_report
+)
+from python2rust.config.settings import CliCase, CodePolicies, HttpCase, StepLimits
+from python2rust.utils.rustfmt import Rustfmt
+from python2rust.workflows import BuildWorkflow
+
+pytestmark = pytest.mark.asyncio
+
+
+class FakeChecker(Checker):
+    """Checker passing once the code contains a marker."""
+
+    def __init__(self, name, marker):
+        self.name = name
+        self.marker = marker
+        self.runs = 0
+
+    async def run(self, builder, rust_code, toml_content):
+        self.runs += 1
+        if self.marker in rust_code:
+            return CheckResult(success=True, metrics={f"{self.name}_runs": self.runs})
+        return CheckResult(success=False, output=f"missing {self.marker}")
+
+
+class FakeFixChain:
+    """Fix chain appending the marker a failure asks for."""
+
+    def __init__(self):
+        self.requests = []
+
+    async def fix(self, rust_code, toml_content, verification_result, analysis):
+        self.requests.append(verification_result)
+        (issue,) = next(iter(verification_result["critical_differences"].values()))
+        return {"rust_code": f"{rust_code}\n// {issue.split()[-1]}", "toml_content": toml_content}
+
+
+class TestShellChecker:
+    async def test_passes_on_expected_exit_codes(self, temp_dir: Path):
+        """Test that the command runs in the crate and its exit code decides the check."""
+        builder = RustBuilder(output_dir=temp_dir)
+
+        passed = (await ShellChecker("lint", "test -f Cargo.toml && echo ok")
+                  .run(builder, "fn main() {}", "[package]"))
+        allowed = (await ShellChecker("lint", "exit 3", exit_codes=[0, 3])
+                   .run(builder, "fn main() {}", "[package]"))
+
+        assert passed.success and passed.output == "ok"
+        assert allowed.success
+        assert (temp_dir / "src" / "main.rs").read_text() == "fn main() {}"
+
+    async def test_failure_output_and_timeout(self, temp_dir: Path):
+        """Test that a failure carries the command's stdout and stderr, and a hung command fails."""
+        builder = RustBuilder(output_dir=temp_dir)
+
+        failed = (await ShellChecker("lint", "echo 'bad name: foo'; echo oops >&2; exit 1")
+                  .run(builder, "", ""))
+        hung = await ShellChecker("lint", "sleep 5", timeout=0.2).run(builder, "", "")
+
+        assert not failed.success
+        assert failed.output.endswith("exited with code 1:\nbad name: foo\noops")
+        assert not hung.success and "timed out" in hung.output
+
+
+class TestClippy:
+    async def test_denied_and_allowed_lints(self):
+        """Test that the configured lint levels are passed to cargo clippy."""
+        class Builder:
+            async def clippy(self, rust_code, toml_content, lint_args=None):
+                self.lint_args = lint_args
+                return False, "error: needless clone", {}
+
+        builder = Builder()
+        clippy = Clippy(deny=["warnings", "clippy::pedantic"],
+                        allow=["clippy::module_name_repetitions"])
+
+        result = await clippy.run(builder, "fn main() {}", "")
+
+        assert builder.lint_args == ["-D", "warnings", "-D", "clippy::pedantic",
+                                     "-A", "clippy::module_name_repetitions"]
+        assert clippy.fix_request(result, 1, 6) == {
+            "critical_differences": {"build": {"clippy": "error: needless clone"}}}
+
+
+CARGO_TEST_OUTPUT = """
+running 3 tests
+test tests::parses ... ok
+test tests::rounds ... FAILED
+test tests::empty ... FAILED
+
+failures:
+
+---- tests::rounds stdout ----
+thread 'tests::rounds' panicked at src/main.rs:40:9:
+assertion `left == right` failed
+  left: 2
+ right: 3
+
+---- tests::empty stdout ----
+thread 'tests::empty' panicked at src/main.rs:47:5:
+called `Option::unwrap()` on a `None` value
+
+failures:
+    tests::rounds
+    tests::empty
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out
+"""
+
+
+class TestCargoTest:
+    async def test_failures_keep_their_panics(self):
+        """Test that each failing test is parsed with its panic, verbatim."""
+        failures = failing_tests(CARGO_TEST_OUTPUT)
+
+        assert [name for name, _ in failures] == ["tests::rounds", "tests::empty"]
+        assert failures[0][1].startswith("thread 'tests::rounds' panicked at src/main.rs:40:9:")
+        assert failures[0][1].endswith(" right: 3")
+        assert failures[1][1] == ("thread 'tests::empty' panicked at src/main.rs:47:5:\n"
+                                  "called `Option::unwrap()` on a `None` value")
+
+    async def test_failing_tests_reach_the_fix_request(self):
+        """Test that the fix request names the failing tests and includes their output."""
+        class Builder:
+            async def test(self, rust_code, toml_content):
+                return False, "error: test failed", {"output": CARGO_TEST_OUTPUT}
+
+        checker = CargoTest()
+        result = await checker.run(Builder(), "fn main() {}", "")
+
+        assert not result.success
+        assert result.info["failed_tests"] == ["tests::rounds", "tests::empty"]
+        request = checker.fix_request(result, 1, 6)["critical_differences"]["tests"][0]
+        assert request.startswith("Failing tests: tests::rounds, tests::empty.")
+        assert "called `Option::unwrap()` on a `None` value" in request
+
+    async def test_tests_that_do_not_build(self):
+        """Test that compile errors of the tests are reported when no test ran."""
+        class Builder:
+            async def test(self, rust_code, toml_content):
+                return False, "error[E0425]: cannot find function `parse`", {"output": ""}
+
+        checker = CargoTest()
+        result = await checker.run(Builder(), "fn main() {}", "")
+
+        assert checker.fix_request(result, 1, 6) == {"critical_differences": {"tests": [
+            "The tests do not build:\nerror[E0425]: cannot find function `parse`"]}}
+
+    async def test_timed_out_tests(self):
+        """Test that tests running out of time are reported as such, not as a build failure."""
+        class Builder:
+            async def test(self, rust_code, toml_content):
+                error = "Command timed out after 5 seconds: `cargo test` did not finish"
+                return False, error, {"error": error, "timed_out": True}
+
+        checker = CargoTest()
+        result = await checker.run(Builder(), "fn main() {}", "")
+
+        (request,) = checker.fix_request(result, 1, 6)["critical_differences"]["tests"]
+        assert request.startswith("The tests did not finish in time")
+        assert request.endswith("`cargo test` did not finish")
+
+
+MIRI_OUTPUT = """   Compiling calc v0.1.0 (/tmp/calc)
+    Finished test [unoptimized + debuginfo] target(s) in 0.52s
+     Running unittests src/main.rs (target/miri/x86_64-unknown-linux-gnu/debug/deps/calc-1a2b3c)
+error: Undefined Behavior: out-of-bounds pointer use: alloc1234 has size 12, so pointer to 4 bytes \
+starting at offset 12 is out-of-bounds
+  --> src/main.rs:8:18
+   |
+8  |         unsafe { *values.as_ptr().add(index) }
+   |                  ^^^^^^^^^^^^^^^^^^^^^^^^^^^ out-of-bounds pointer use
+   |
+   = help: this indicates a bug in the program: it performed an invalid operation, and caused \
+Undefined Behavior
+
+note: some details are omitted, run with `MIRIFLAGS=-Zmiri-backtrace=full` for a verbose backtrace
+
+error: aborting due to 1 previous error
+
+error: test failed, to rerun pass `--bin calc`
+"""
+
+
+class MiriBuilder:
+    """Builder whose Miri run fails with the given error."""
+
+    def __init__(self, error):
+        self.error = error
+
+    async def miri(self, rust_code, toml_content, flags, toolchain):
+        return False, self.error, {"output": ""}
+
+
+class TestMiri:
+    async def test_undefined_behavior_reaches_the_fix_request(self):
+        """Test that each undefined behavior report is kept with its span, without
+        cargo's trailing errors."""
+        checker = Miri()
+        result = await checker.run(MiriBuilder(MIRI_OUTPUT), "fn main() {}", "")
+
+        assert not result.success
+        assert result.output.startswith("error: Undefined Behavior: out-of-bounds pointer use")
+        assert "src/main.rs:8:18" in result.output and "aborting" not in result.output
+        (request,) = checker.fix_request(result, 1, 6)["critical_differences"]["miri"]
+        assert request.startswith("Miri found undefined behavior")
+
+    async def test_what_miri_cannot_check_is_skipped(self):
+        """Test that unsupported operations and a missing toolchain skip the check."""
+        unsupported = ("error: unsupported operation: can't call foreign function "
+                       "`socket` on OS `linux`\n  --> src/main.rs:3:5\n")
+        result = await Miri().run(MiriBuilder(unsupported), "fn main() {}", "")
+        assert result.success
+        assert result.info["skipped"] == (
+            "error: unsupported operation: can't call foreign function `socket` on OS `linux`")
+
+        missing = "error: toolchain 'nightly-x86_64-unknown-linux-gnu' is not installed"
+        assert ((await Miri().run(MiriBuilder(missing), "fn main() {}", ""))
+                .info["skipped"].startswith("Miri is not installed"))
+
+
+CARGO_DENY_OUTPUT = "\n".join(json.dumps(entry) for entry in [
+    {"type": "diagnostic", "fields": {
+        "severity": "error", "code": "vulnerability",
+        "message": "Potential segfault in the time crate",
+        "advisory": {"id": "RUSTSEC-2020-0071", "package": "time",
+                     "title": "Potential segfault in the time crate"},
+        "graphs": [{"Krate": {"name": "time", "version": "0.1.45"},
+                    "parents": [{"Krate": {"name": "calc"}}]}]}},
+    {"type": "diagnostic", "fields": {
+        "severity": "error", "code": "rejected",
+        "message": "failed to satisfy license requirements",
+        "graphs": [{"Krate": {"name": "calc", "version": "0.1.0"}}]}},
+    {"type": "diagnostic", "fields": {
+        "severity": "error", "code": "rejected",
+        "message": "failed to satisfy license requirements",
+        "graphs": [{"Krate": {"name": "ring", "version": "0.16.20"}}]}},
+    {"type": "diagnostic", "fields": {
+        "severity": "warning", "code": "license-not-encountered",
+        "message": "license was not encountered"}},
+    {"type": "summary", "fields": {"advisories": {"errors": 1}, "licenses": {"errors": 2}}},
+])
+
+
+class TestDependencyAudit:
+    async def test_violations_of_the_dependencies(self):
+        """Test that errors are violations per crate, warnings and the crate itself are not."""
+        violations = dependency_violations(CARGO_DENY_OUTPUT, package="calc")
+
+        assert [str(violation) for violation in violations] == [
+            "time 0.1.45: vulnerability RUSTSEC-2020-0071: Potential segfault in the time crate",
+            "ring 0.16.20: rejected: failed to satisfy license requirements",
+        ]
+
+    async def test_violations_are_warnings_unless_failing(self, temp_dir: Path):
+        """Test that violations reach the build result as warnings, or the fix prompt with fail."""
+        class Audit(DependencyAuditCheck):
+            async def _execute(self, project_dir, config, timeout):
+                assert 'allow = ["MIT"]' in config.read_text()
+                return 1, "", CARGO_DENY_OUTPUT
+
+        toml_content = "[package]\nname = 'calc'\nversion = '0.1.0'\n"
+        workflow = BuildWorkflow({}, RustBuilder(output_dir=temp_dir), MigrationState())
+        workflow.checkers = [Audit(licenses=["MIT"])]
+        result = await workflow._run_checkers({"rust_code": "fn main() {}",
+                                               "toml_content": toml_content})
+
+        assert result.success
+        assert result.warnings[0].startswith("time 0.1.45: vulnerability RUSTSEC-2020-0071")
+
+        checker = Audit(fail=True, licenses=["MIT"])
+        failed = await checker.run(RustBuilder(output_dir=temp_dir), "fn main() {}", toml_content)
+        assert not failed.success
+        (request,) = checker.fix_request(failed, 1, 6)["critical_differences"]["dependencies"]
+        assert request.endswith("- ring 0.16.20: rejected: failed to satisfy license requirements")
+
+
+class TestPolicyCheck:
+    async def test_violations_reach_a_targeted_fix_request(self, temp_dir: Path):
+        """Test that each violation is given with its line of code, and how to fix its policy."""
+        class Policies(PolicyCheck):
+            async def _facts(self, builder, rust_code):
+                return {"functions": [], "constructs": [{"kind": "call", "name": "unwrap",
+                                                         "line": 2, "test": False}]}
+
+        checker = Policies(CodePolicies(no_unwrap=True))
+        result = await checker.run(RustBuilder(output_dir=temp_dir),
+                                   "fn main() {\n    let n = arg.unwrap();\n}\n", "")
+
+        assert not result.success
+        assert result.output == "- line 2: `unwrap()` outside tests: `let n = arg.unwrap();`"
+        (request,) = checker.fix_request(result, 1, 6)["critical_differences"]["policy"]
+        assert request.endswith("- no_unwrap: handle the None or Err case, or "
+                                "propagate it with `?`")
+
+    async def test_error_style_is_enforced(self, temp_dir: Path):
+        """Test that errors of another style than the project's fail with how the
+        style handles them."""
+        class Policies(PolicyCheck):
+            async def _facts(self, builder, rust_code):
+                return {"functions": [], "constructs": []}
+
+        checker = Policies(CodePolicies(error_style="anyhow"))
+        rust = "fn load() -> Result<u8, String> {\n    Ok(1)\n}\n"
+        result = await checker.run(RustBuilder(output_dir=temp_dir), rust,
+                                   '[dependencies]\nanyhow = "1"\n')
+
+        assert not result.success
+        assert result.output == ("- line 1: String error in the anyhow error style: "
+                                 "`fn load() -> Result<u8, String> {`")
+        (request,) = checker.fix_request(result, 1, 6)["critical_differences"]["policy"]
+        assert "- error_style: functions that can fail return `anyhow::Result<T>`" in request
+        fixed = "fn load() -> anyhow::Result<u8> {\n    Ok(1)\n}\n"
+        assert (await checker.run(RustBuilder(output_dir=temp_dir), fixed, "")).success
+
+
+class TestCommentCheck:
+    async def test_missing_comments_reach_the_fix_request(self, temp_dir: Path):
+        """Test that the comments a Rust function does not repeat are listed with
+        their statements."""
+        class Comments(CommentCheck):
+            async def _functions(self, builder, rust_code):
+                return [{"name": "total", "start_line": 1, "end_line": 4, "test": False}]
+
+        python = ("def total(rows):\n    # Skip the header row\n    rows = rows[1:]\n"
+                  "    return sum(rows)  # all of them\n")
+        checker = Comments(python)
+        rust = ("fn total(rows: &[i64]) -> i64 {\n    // skips the header row\n"
+                "    rows[1..].iter().sum()\n}\n")
+        result = await checker.run(RustBuilder(output_dir=temp_dir), rust, "")
+
+        assert not result.success
+        assert result.output == "- line 4 in total: `# all of them` at `return sum(rows)`"
+        assert result.metrics == {"comments": 2, "comments_kept": 1}
+        (request,) = checker.fix_request(result, 1, 6)["critical_differences"]["comments"]
+        assert request.endswith(result.output)
+
+
+class TestDifferentialFuzzer:
+    async def test_inputs_as_rust_literals(self):
+        """Test that generated inputs are written as expressions of the parameter types."""
+        assert rust_literal([1, -2], parse_fuzz_type("&[i64]")) == "&[1, -2]"
+        assert rust_literal(["a\"b"],
+                            parse_fuzz_type("Vec<String>")) == 'vec![String::from("a\\"b")]'
+        assert rust_literal("é\n", parse_fuzz_type("&str")) == '"é\\n"'
+        assert rust_literal(3.0, parse_fuzz_type("&f64")) == "&3.0"
+        assert rust_literal(True, parse_fuzz_type("bool")) == "true"
+
+    async def test_results_compared_across_languages(self):
+        """Test that errors match errors, numbers are compared with a tolerance
+        and tuples as lists."""
+        assert same_result({"error": "ValueError: empty"}, {"error": "panic"})
+        assert not same_result(0, {"error": "panic"})
+        assert same_result(0.1 + 0.2, 0.3)
+        assert same_result((1, "a"), [1, "a"])
+        assert same_result({1: True}, {"1": True})
+        assert not same_result(True, 1)
+
+
+class TestNumericBoundaryCheck:
+    async def test_boundary_inputs(self):
+        """Test that integer bounds, both signs and overflowing lists are combined, and sampled."""
+        [target] = fuzz_targets("def scale(values, factor):\n    return sum(values) * factor\n",
+                                "pub fn scale(values: &[i8], factor: i8) -> i8 { 0 }")
+        cases = boundary_cases(target)
+
+        assert ([[127, 127], 1] in cases and [[-128, -128], 2] in cases and [[], -128] in cases
+                and [[], 7] in cases)
+        assert len(boundary_cases(target, limit=5)) == 5
+        assert boundary_cases(target, limit=5) == boundary_cases(target, limit=5)
+
+    async def test_untestable_sites_are_warnings(self, temp_dir: Path):
+        """Test that sites of functions the harness cannot call are reported without failing."""
+        source = temp_dir / "app.py"
+        source.write_text("class Counter:\n    def add(self, n):\n        self.total += n\n")
+        rust_code = ("pub struct Counter { total: i32 }\n"
+                     "impl Counter { pub fn add(&mut self, n: i32) {} }")
+
+        result = (await NumericBoundaryCheck(source)
+                  .run(RustBuilder(output_dir=temp_dir / "out"), rust_code, ""))
+
+        assert result.success
+        assert result.warnings == [
+            "numeric overflow risk at python line 3 (Counter.add): `+` cannot overflow on Python "
+            "ints, while fixed-width Rust integers panic in debug builds and wrap in release builds"
+        ]
+
+
+class TestUnicodeCheck:
+    async def test_multibyte_inputs(self):
+        """Test that string functions get multi-byte text with offsets into it."""
+        [target] = fuzz_targets("def head(text, n):\n    return text[:n]\n",
+                                "pub fn head(text: &str, n: usize) -> String { "
+                                "text[..n].to_string() }")
+
+        cases = UnicodeCheck(Path("app.py")).case_inputs(target)
+
+        assert ["日本語", 1] in cases and ["e\u0301", 1] in cases
+        assert all(n >= 0 for _, n in cases)
+
+
+class TestHttpEquivalence:
+    async def test_requests_from_routes_and_cases(self, temp_dir: Path):
+        """Test that routes without path parameters are requested with each
+        method, then the cases."""
+        case = HttpCase(method="POST", path="/", body="number=7")
+        checker = HttpEquivalence(temp_dir / "main.py", cases=[case])
+
+        app, requests = checker.requests(
+            "@app.route('/', methods=['GET', 'POST'])\ndef index(): ...\n"
+            "@app.route('/item/<name>')\ndef item(name): ...\n")
+
+        assert app == "app"
+        assert [(r.method, r.path, r.body) for r in requests] == [
+            ("GET", "/", None), ("POST", "/", None), ("POST", "/", "number=7")]
+        assert checker.requests("x = 1\n") == (None, [])
+
+    async def test_response_differences(self):
+        """Test that statuses, compared headers and bodies are diffed, JSON bodies as documents."""
+        python = HttpResponse(200, {"content-type": "application/json", "server": "Werkzeug"},
+                              '{"a": 1, "b": 2}')
+        rust = HttpResponse(200,
+                            {"content-type": "application/json", "server": "axum"}, '{"b":2,"a":1}')
+        assert response_differences(python, rust) == []
+
+        rust = HttpResponse(500, {"content-type": "text/plain"}, "boom")
+        differences = response_differences(python, rust)
+        assert differences[0] == "status 200 in Python, 500 in Rust"
+        assert "header content-type" in differences[1]
+        assert differences[2].startswith("body differs:")
+
+        assert response_differences(HttpResponse(0), HttpResponse(0, error="refused")) == [
+            "the Rust request failed: refused"]
+
+    async def test_ignored_body_parts(self):
+        """Test that ignored regexes are replaced in both bodies before comparing them."""
+        python = HttpResponse(200, {}, "<p>took 1.25s</p>")
+        rust = HttpResponse(200, {}, "<p>took 0.01s</p>")
+
+        assert response_differences(python, rust, ignore=[r"\d+\.\d+s"]) == []
+        assert "-<p>took 1.25s</p>" in response_differences(python, rust)[0]
+
+
+    async def test_html_compared_by_structure(self):
+        """Test that HTML differing in whitespace, attribute order and ignored parts is equal."""
+        python = HttpResponse(200, {"content-type": "text/html; charset=utf-8"},
+                              '<div class="b a" id="r">\n  <p>Primes:  9592</p>\n'
+                              '  <p>Time   23.4s</p><span class="timing">12:00</span><!-- cached '
+                              '--></div>')
+        rust = HttpResponse(200, {"content-type": "text/html; charset=utf-8"},
+                            '<div id="r" class="a b"><p>Primes: 9592</p><p>Time 0.2s</p>'
+                            '<span class="timing">12:01</span></div>')
+
+        assert response_differences(python, rust, ignore=[r"[0-9.]+s"],
+                                    ignore_elements=[".timing"]) == []
+
+        rust.body = rust.body.replace("9592", "9593")
+        differences = response_differences(python, rust, ignore=[r"[0-9.]+s"],
+                                           ignore_elements=[".timing"])
+        assert differences[0].startswith("HTML differs:")
+        assert "-    Primes: 9592" in differences[0] and "+    Primes: 9593" in differences[0]
+
+    async def test_html_outline(self):
+        """Test that the outline keeps preformatted text and closes implicitly closed elements."""
+        outline = html_outline("<ul><li>one<li>two</ul><pre>a  b</pre><br/>")
+
+        assert outline == ["<ul>", "  <li>", "    one", "  <li>", "    two",
+                           "<pre>", "  a  b", "<br>"]
+
+    async def test_per_request_report(self, temp_dir: Path):
+        """Test that the report counts passed and failed requests and keeps their differences."""
+        report = [
+            {"request": "GET /", "passed": True, "differences": []},
+            {"request": "POST /cart", "passed": False,
+             "differences": ["status 200 in Python, 500 in Rust"]}
+        ]
+
+        report_file = write_report(temp_dir, report)
+
+        saved = json.loads(report_file.read_text())
+        assert report_file == temp_dir / "debug" / "http_report.json"
+        assert (saved["passed"], saved["failed"]) == (1, 1)
+        assert saved["requests"][1]["differences"] == ["status 200 in Python, 500 in Rust"]
+
+class TestCliEquivalence:
+    async def test_command_line_scripts(self):
+        """Test that modules with a main guard are programs, unless they serve a web app."""
+        assert is_cli_script("import sys\n\nif __name__ == '__main__':\n    print(sys.argv)\n")
+        assert not is_cli_script("def add(a, b):\n    return a + b\n")
+        assert not is_cli_script(
+            "@app.route('/')\ndef index(): ...\n\nif __name__ == '__main__':\n    app.run()\n")
+
+    async def test_run_differences(self):
+        """Test that exit codes and both streams are compared, stderr only when enabled."""
+        python = CliRun(2, "usage: <program> FILE\n", "error: missing FILE\n")
+        rust = CliRun(1, "usage: <program> FILE\n", "thread 'main' panicked\n")
+
+        differences = run_differences(python, rust)
+        assert differences[0] == "exit code 2 in Python, 1 in Rust"
+        assert (differences[1].startswith("stderr differs:")
+                and "-error: missing FILE" in differences[1])
+        assert run_differences(python, rust, stderr=False) == ["exit code 2 in Python, 1 in Rust"]
+        assert run_differences(CliRun(0, "took 1.2s\n"), CliRun(0, "took 0.1s\n"),
+                               ignore=[r"[0-9.]+s"]) == []
+
+    async def test_runs_out_of_time(self, temp_dir: Path):
+        """Test that a Rust program running past the run limit is killed and reported
+        as not finishing."""
+        script = temp_dir / "sleepy.sh"
+        script.write_text("#!/bin/sh\nsleep 30\n")
+        script.chmod(0o755)
+        checker = CliEquivalence(temp_dir / "tool.py")
+
+        run = await checker._run([str(script)], CliCase(), [], StepLimits(timeout=0.5))
+
+        assert run.exit_code is None
+        (difference,) = run_differences(CliRun(0, "done\n"), run)
+        assert difference.startswith("the Rust program did not finish: timed out after 0.5 seconds")
+
+    async def test_memory_of_the_runs(self, temp_dir: Path):
+        """Test that with a memory profiler the peaks of both runs are sampled and
+        reported in the metrics."""
+        source = temp_dir / "tool.py"
+        # Both long enough to be sampled
+        source.write_text("import sys, time\n\nif __name__ == '__main__':\n"
+                          "    time.sleep(0.2)\n    print(' '.join(sys.argv[1:]))\n")
+        binary = temp_dir / "target" / "debug" / "tool"
+        binary.parent.mkdir(parents=True)
+        binary.write_text("#!/bin/sh\nsleep 0.2\necho \"$@\"\n")
+        binary.chmod(0o755)
+        (temp_dir / "Cargo.toml").write_text('[package]\nname = "tool"\n')
+
+        class Builder(RustBuilder):
+            async def build(self, rust_code, toml_content, release=False):
+                return True, None, {}
+
+        checker = CliEquivalence(
+            source, [CliCase(args=["a", "b"])], memory=MemoryProfiler(allocations=False))
+        result = await checker.run(Builder(output_dir=temp_dir), "fn main() {}", "")
+
+        assert result.success
+        (memory,) = result.metrics["memory"]
+        assert memory["run"] == "<program> a b"
+        assert memory["python"]["peak_rss_mb"] > 0 and memory["rust"]["peak_rss_mb"] > 0
+        assert memory["rust"]["allocations"] is None
+
+class TestSchemaEquivalence:
+    async def test_schema_statements_of_rust_code(self):
+        """Test that the CREATE statements of string literals are found, outside test modules."""
+        rust_code = (
+            'const USERS: &str = r#"CREATE TABLE users (id INTEGER PRIMARY KEY, '
+            'name TEXT NOT NULL)"#;\nfn init(db: &Connection) {\n    let quote = \'"\';\n'
+            '    db.execute("create index ix_name ON users (name);", []).unwrap();\n'
+            '    db.execute("SELECT * FROM users", []).unwrap();\n}\n'
+            '#[cfg(test)]\nmod tests {\n'
+            '    const T: &str = "CREATE TABLE scratch (id INTEGER)";\n}\n'
+        )
+
+        assert rust_schema_statements(rust_code) == [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            "create index ix_name ON users (name);",
+        ]
+        assert orm_framework("from sqlalchemy.orm import DeclarativeBase\n") == "sqlalchemy"
+        assert orm_framework("from django.db import models\n") == "django"
+        assert orm_framework("import sqlite3\n") is None
+
+    async def test_sqlite_schema_differences(self, temp_dir: Path):
+        """Test that columns, constraints and indexes are compared whatever their names."""
+        python, rust = SqliteDatabase(temp_dir / "python.db"), SqliteDatabase(temp_dir / "rust.db")
+        await python.execute(
+            "CREATE TABLE users (id INTEGER NOT NULL, email VARCHAR(255) NOT NULL, status "
+            "VARCHAR(20) DEFAULT 'active', PRIMARY KEY (id), UNIQUE (email));\n"
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id) "
+            "ON DELETE CASCADE);\nCREATE INDEX ix_orders_user_id ON orders (user_id);")
+        await rust.execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, "
+            "status VARCHAR(20));\nCREATE UNIQUE INDEX users_email ON users (email);\n"
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES "
+            "users(id), note TEXT);")
+
+        assert schema_differences(await python.schema(), await rust.schema()) == [
+            "users.email: type VARCHAR(255) in Python, TEXT in Rust",
+            "users.status: default 'active' in Python, none in Rust",
+            "orders.note: column is not in the Python schema",
+            "orders: constraint FOREIGN KEY (user_id) REFERENCES users(id) ON "
+            "DELETE CASCADE is missing",
+            "orders: constraint FOREIGN KEY (user_id) REFERENCES users(id) is not "
+            "in the Python schema",
+            "orders: index INDEX (user_id) is missing",
+        ]
+
+    async def test_python_command_against_rust_code(self, temp_dir: Path):
+        """Test that the schema of python_command is compared with the statements
+        of the Rust code."""
+        source = temp_dir / "models.py"
+        source.write_text("import os, sqlite3\n")
+        create = ("import os, sqlite3; sqlite3.connect(os.environ['DATABASE_URL'][len('sqlite:///"
+                  "'):]).executescript('CREATE TABLE items (id INTEGER PRIMARY KEY, "
+                  "name TEXT NOT NULL)')")
+        checker = SchemaEquivalence(source, python_command=f'"{sys.executable}" -c "{create}"')
+        builder = RustBuilder(output_dir=temp_dir / "crate")
+
+        result = await checker.run(builder,
+                                   'const SCHEMA: &str = "CREATE TABLE items (id '
+                                   'INTEGER PRIMARY KEY)";', "")
+        assert not result.success
+        assert "- items.name: column is missing" in result.output
+
+        matching = ('const SCHEMA: &str = "CREATE TABLE items (id INTEGER PRIMARY KEY, '
+                    'name TEXT NOT NULL)";')
+        result = await checker.run(builder, matching, "")
+        assert result.success and result.info == {"engine": "sqlite", "tables": 1, "differences": 0}
+
+        result = await checker.run(builder, "fn main() {}", "")
+        assert not result.success and result.output.startswith("The Rust code creates no tables")
+
+
+class TestOpenApiEquivalence:
+    async def test_fastapi_and_utoipa_documents(self):
+        """Test that references, nullability styles and descriptions are normalized
+        before comparing."""
+        python = {"openapi": "3.1.0", "paths": {"/items/{item_id}": {"get": {
+            "parameters": [
+                {"name": "item_id", "in": "path", "required": True,
+                 "schema": {"type": "integer", "title": "Item Id"}},
+                {"name": "q", "in": "query", "required": False,
+                 "schema": {"anyOf": [{"type": "string"}, {"type": "null"}], "title": "Q"}}],
+            "responses": {
+                "200": {"content": {"application/json": {"schema": {"$ref": "#/components/"
+                                                                            "schemas/Item"}}}},
+                "422": {"description": "Validation Error"}}}}},
+            "components": {"schemas": {"Item": {
+                "title": "Item", "type": "object", "required": ["name", "price"], "properties": {
+                    "name": {"type": "string", "title": "Name"},
+                    "price": {"type": "number", "title": "Price"},
+                    "tags": {"anyOf": [{"type": "array", "items": {"type": "string"}},
+                                       {"type": "null"}]}}}}}}
+        rust = {"openapi": "3.1.0", "paths": {"/items/{id}": {"get": {
+            "parameters": [{"name": "id", "in": "path", "required": True,
+                            "schema": {"type": "integer", "format": "int64"}}],
+            "responses": {"200": {"description": "", "content": {"application/json": {"schema": {
+                "type": "object", "required": ["name", "price"], "properties": {
+                    "name": {"type": "string"},
+                    "price": {"type": "integer", "format": "int64"},
+                    "tags": {"type": ["array", "null"], "items": {"type": "string"}}}}}}}}}},
+            "/health": {"get": {"responses": {"200": {"description": "ok"}}}}}}
+
+        assert openapi_differences(python, rust, ["422"]) == [
+            "GET /items/{item_id}: parameter item_id (path) is missing",
+            "GET /items/{item_id}: parameter q (query) is missing",
+            "GET /items/{item_id}: parameter id (path) is not in the Python app",
+            "GET /items/{item_id}: response 200.price: type number in Python, integer in Rust",
+            "GET /health: not in the Python app",
+        ]
+
+    async def test_flask_routes_compare_path_parameters(self, temp_dir: Path):
+        """Test that a Flask app is documented from its URL map and compared by
+        path parameters only."""
+        source = temp_dir / "app.py"
+        source.write_text(
+            "from flask import Flask\napp = Flask(__name__)\n\n"
+            "@app.route('/users/<int:user_id>', methods=['GET', 'DELETE'])\n"
+            "def user(user_id):\n    return {}\n")
+        checker = OpenApiEquivalence(source)
+
+        python, reason = await checker._python_document("app", 30)
+        assert reason == ""
+        assert set(python["paths"]) == {"/users/{user_id}"}
+
+        rust = {"paths": {"/users/{user_id}": {
+            "get": {"parameters": [
+                {"name": "user_id", "in": "path", "required": True, "schema": {"type": "string"}},
+                {"name": "verbose", "in": "query", "schema": {"type": "boolean"}}],
+                "responses": {"200": {"description": ""}}}}}}
+        assert openapi_differences(python, rust) == [
+            "DELETE /users/{user_id}: missing in Rust",
+            "GET /users/{user_id}: parameter user_id (path): type integer in "
+            "Python, string in Rust",
+        ]
+
+
+class TestBuildWorkflowCheckers:
+    async def test_failures_are_fixed_and_earlier_checkers_rerun(self, temp_dir: Path):
+        """Test that a failing checker's output reaches the fix prompt and earlier
+        checkers run again."""
+        chain = FakeFixChain()
+        workflow = BuildWorkflow({"build_fix": chain},
+                                 RustBuilder(output_dir=temp_dir), MigrationState())
+        first, second = FakeChecker("first", "alpha"), FakeChecker("lint", "beta")
+        workflow.checkers = [first, second]
+
+        result = await workflow._run_checkers({"rust_code": "// alpha",
+                                               "toml_content": "[package]"})
+
+        assert result.success
+        assert result.rust_code == "// alpha\n// beta"
+        assert chain.requests == [{"critical_differences": {"lint": ["missing beta"]}}]
+        assert first.runs == 2
+        assert result.metrics == {"first_runs": 2, "lint_runs": 2}
+
+    async def test_gives_up_after_max_attempts(self, temp_dir: Path):
+        """Test that a checker still failing after the fix attempts fails the
+        build with its output."""
+        class NoFix(FakeFixChain):
+            async def fix(self, rust_code, toml_content, verification_result, analysis):
+                self.requests.append(verification_result)
+                return {"rust_code": rust_code + " ", "toml_content": toml_content}
+
+        workflow = BuildWorkflow({"build_fix": NoFix()},
+                                 RustBuilder(output_dir=temp_dir), MigrationState())
+        workflow.checkers = [FakeChecker("lint", "beta")]
+        workflow.max_fix_attempts = 2
+
+        result = await workflow._run_checkers({"rust_code": "fn main() {}", "toml_content": ""})
+
+        assert not result.success
+        assert result.error == "Failed to fix lint errors after 2 attempts: missing beta"
+
+
+class TestRustfmt:
+    @pytest.fixture
+    def fake_rustfmt(self, temp_dir: Path) -> str:
+        """A rustfmt stand-in squeezing spaces, failing on code containing BROKEN."""
+        script = temp_dir / "rustfmt"
+        script.write_text(
+            '#!/bin/sh\n'
+            'input=$(cat)\n'
+            'case "$input" in *BROKEN*) echo "error: expected item" >&2; exit 1;; esac\n'
+            'printf "%s\\n" "$input" | tr -s " "\n')
+        script.chmod(0o755)
+        return str(script)
+
+    async def test_normalizes_and_rejects_unparseable_code(self, temp_dir: Path, fake_rustfmt: str):
+        """Test that parsing code is replaced by its formatted version and other code fails."""
+        checker = RustfmtCheck(Rustfmt(command=fake_rustfmt))
+        builder = RustBuilder(output_dir=temp_dir)
+
+        formatted = await checker.run(builder, "fn  main()   {}", '[package]\nedition = "2018"')
+        broken = await checker.run(builder, "fn main( BROKEN", "")
+
+        assert formatted.success and formatted.rust_code == "fn main() {}\n"
+        assert not broken.success
+        assert checker.fix_request(broken, 1, 6) == {
+            "critical_differences": {"syntax": ["The code does not parse:\nerror: expected item"]}}
+        assert Rustfmt.edition('[package]\nedition = "2018"') == "2018"
+
+    async def test_fixed_code_is_recorded_formatted(self, temp_dir: Path, fake_rustfmt: str):
+        """Test that the code history holds fixes as rustfmt formatted them."""
+        state = MigrationState()
+        workflow = BuildWorkflow({"build_fix": FakeFixChain()},
+                                 RustBuilder(output_dir=temp_dir), state)
+        workflow.checkers = [RustfmtCheck(Rustfmt(command=fake_rustfmt)),
+                             FakeChecker("lint", "beta")]
+
+        result = await workflow._run_checkers({"rust_code": "fn  main() {}", "toml_content": ""})
+
+        assert result.success
+        assert result.rust_code == "fn main() {}\n\n// beta\n"
+        assert state.code_history == [{"origin": "lint fix 1",
+                                       "rust_code": "fn main() {}\n\n// beta\n"}]
+
+    async def test_missing_rustfmt_leaves_code(self):
+        """Test that code is left unchanged when rustfmt is not installed."""
+        formatter = Rustfmt(command="rustfmt-not-installed")
+
+        assert await formatter.format("fn  main() {}") == ("fn  main() {}", None)
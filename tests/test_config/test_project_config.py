@@ -0,0 +1,63 @@
+from pathlib import Path
+from python2rust.config import ProjectConfig, find_project_config
+from python2rust.config.project_config import dump_toml
+
+
+class TestProjectConfig:
+    def test_save_and_load(self, temp_dir: Path):
+        """Test that a config survives a round trip through TOML."""
+        config = ProjectConfig(crates={"PIL": "image", "http.server": "hyper"},
+                               frameworks=["flask"])
+        config.project.name = "shop"
+        config.project.entry_points = [Path("shop/app.py")]
+
+        config.save(temp_dir / "python2rust.toml")
+        loaded = ProjectConfig.load(temp_dir / "python2rust.toml")
+
+        assert loaded == config
+
+    def test_dump_quotes_keys_and_strings(self):
+        """Test that dotted keys and quotes are escaped."""
+        text = dump_toml({"crates": {"http.server": "hyper"}, "name": 'say "hi"'})
+
+        assert text == 'name = "say \\"hi\\""\n\n[crates]\n"http.server" = "hyper"\n'
+
+    def test_dump_nested_tables(self):
+        """Test that nested dicts become dotted tables."""
+        text = dump_toml({"model_settings": {"azure": {"deployment": "gpt4o-prod"}}})
+
+        assert text == '[model_settings]\n[model_settings.azure]\ndeployment = "gpt4o-prod"\n'
+
+    def test_find_in_parent(self, temp_dir: Path):
+        """Test that the config is found from a subdirectory."""
+        (temp_dir / "python2rust.toml").write_text("")
+        (temp_dir / "pkg").mkdir()
+
+        assert find_project_config(temp_dir / "pkg") == (temp_dir / "python2rust.toml").resolve()
+
+    def test_http_cases_round_trip(self, temp_dir: Path):
+        """Test that the web app comparison and its requests survive a round trip through TOML."""
+        config = ProjectConfig.model_validate({"http": {
+            "enabled": True,
+            "ignore": ["[0-9.]+ seconds"],
+            "cases": {"form": {"method": "POST", "body": "submit=Run",
+                               "headers": {"Accept": "text/html"}}}
+        }})
+
+        config.save(temp_dir / "python2rust.toml")
+        loaded = ProjectConfig.load(temp_dir / "python2rust.toml")
+
+        assert loaded.http == config.http
+        assert loaded.http.cases["form"].path == "/"
+
+    def test_crates_as_tables_round_trip(self, temp_dir: Path):
+        """Test that [crates] entries given with their version and features survive a round trip."""
+        config = ProjectConfig.model_validate({"crates": {
+            "PIL": "image",
+            "requests": {"crate": "reqwest", "version": "0.12", "features": ["json"]}}})
+
+        config.save(temp_dir / "python2rust.toml")
+        loaded = ProjectConfig.load(temp_dir / "python2rust.toml")
+
+        assert loaded.crates == config.crates
+        assert loaded.crates["requests"].features == ["json"]
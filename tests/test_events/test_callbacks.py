@@ -0,0 +1,26 @@
+from types import SimpleNamespace
+from python2rust.events.callbacks import response_usage
+
+
+def response(message=None, llm_output=None):
+    return SimpleNamespace(generations=[[SimpleNamespace(text="fn main() {}", message=message)]],
+                           llm_output=llm_output)
+
+
+class TestResponseUsage:
+    def test_usage_metadata_of_chat_messages(self):
+        """Test that token counts and the model come from the message of a chat response."""
+        message = SimpleNamespace(usage_metadata={"input_tokens": 120, "output_tokens": 30},
+                                  response_metadata={"model_name": "gpt-4o"})
+
+        assert response_usage(response(message)) == {
+            "input_tokens": 120, "output_tokens": 30, "model": "gpt-4o"}
+
+    def test_provider_token_usage(self):
+        """Test that OpenAI-style token_usage is read when messages carry no usage."""
+        usage = response(llm_output={"token_usage": {"prompt_tokens": 7, "completion_tokens": 3},
+                                     "model_name": "gpt-4o-mini"})
+
+        assert response_usage(usage) == {"input_tokens": 7, "output_tokens": 3,
+                                         "model": "gpt-4o-mini"}
+        assert response_usage(response()) == {}
@@ -0,0 +1,57 @@
+import pytest
+from typing import List
+from python2rust.events import EventBus, EventType, MigrationEvent, MigrationObserver
+
+
+class RecordingObserver(MigrationObserver):
+    def __init__(self):
+        self.events: List[MigrationEvent] = []
+        self.builds: List[MigrationEvent] = []
+
+    def on_event(self, event: MigrationEvent) -> None:
+        self.events.append(event)
+        super().on_event(event)
+
+    def on_build_attempt(self, event: MigrationEvent) -> None:
+        self.builds.append(event)
+
+
+class FailingObserver(MigrationObserver):
+    def on_event(self, event: MigrationEvent) -> None:
+        raise RuntimeError("observer crashed")
+
+
+class TestEventBus:
+    @pytest.fixture
+    def observer(self) -> RecordingObserver:
+        return RecordingObserver()
+
+    def test_emit_dispatches_typed_handlers(self, observer: RecordingObserver):
+        """Test that on_<type> handlers receive matching events."""
+        bus = EventBus([observer])
+
+        bus.emit(EventType.BUILD_ATTEMPT, command="check", attempt=1)
+        bus.emit(EventType.CHECK_FAILED, check="clippy")
+
+        assert [e.type for e in observer.events] == [EventType.BUILD_ATTEMPT,
+                                                     EventType.CHECK_FAILED]
+        assert len(observer.builds) == 1
+        assert observer.builds[0].data == {"command": "check", "attempt": 1}
+
+    def test_for_file_tags_events(self, observer: RecordingObserver):
+        """Test that file-bound buses share observers and tag the file."""
+        bus = EventBus()
+        bus.subscribe(observer)
+
+        bus.for_file("src/main.py").emit(EventType.FILE_STARTED)
+
+        assert observer.events[0].file == "src/main.py"
+        assert observer.events[0].to_dict()["type"] == "file_started"
+
+    def test_failing_observer_does_not_stop_others(self, observer: RecordingObserver):
+        """Test that a crashing observer doesn't break the pipeline."""
+        bus = EventBus([FailingObserver(), observer])
+
+        bus.emit(EventType.PROMPT_SENT, model="claude")
+
+        assert len(observer.events) == 1
@@ -0,0 +1,35 @@
+from python2rust.analyzers import async_uses
+from python2rust.plugins import AsyncRuntime
+
+CODE = "import asyncio\n\n\nasync def tick():\n    await asyncio.sleep(1)\n"
+
+
+class TestAsyncRuntime:
+    def test_prompts_give_the_idioms_of_the_runtime(self):
+        """Test that the prompts and the tests prompt name the runtime's idioms and attributes."""
+        plugin = AsyncRuntime("tokio", async_uses(CODE))
+
+        context = plugin.generation_context(CODE, {})
+        assert context.startswith("Target the tokio runtime, and no other")
+        assert context.endswith("- line 5: asyncio.sleep() -> "
+                                "`tokio::time::sleep(Duration::from_secs_f64(seconds)).await`")
+        assert plugin.tests_context(CODE) == (
+            "Test the async functions with `#[tokio::test] async fn` tests, not #[test] functions.")
+        assert AsyncRuntime("sync", async_uses(CODE)).generation_context(CODE, {}).startswith(
+            "The output is synchronous")
+
+    def test_verification_fails_other_runtimes(self):
+        """Test that another runtime's crate, or async code in the synchronous output, fails, and
+        that the runtime's crate is declared when used."""
+        toml = '[package]\nname = "tick"\n\n[dependencies]\n'
+        rust = "#[async_std::main]\nasync fn main() {\n    async_std::task::sleep(d).await;\n}\n"
+
+        assert AsyncRuntime("async-std", async_uses(CODE)).verify(CODE, rust, toml) == []
+        assert AsyncRuntime("tokio", async_uses(CODE)).verify(CODE, rust, toml) == [
+            "the code uses async-std instead of tokio; use tokio's idioms and crate only"]
+        assert AsyncRuntime("sync", async_uses(CODE)).verify(CODE, rust, toml) == [
+            "the output must be synchronous, but the code uses async-std",
+            "the output must be synchronous, but lines 2, 3 are async; use plain "
+            "functions and threads"]
+        assert AsyncRuntime("async-std", async_uses(CODE)).cargo_manifest(rust, toml).endswith(
+            '[dependencies]\nasync-std = { version = "1", features = ["attributes"] }\n')
@@ -0,0 +1,45 @@
+from python2rust.analyzers import ClassMapping
+from python2rust.plugins import ClassPlan
+
+
+class TestClassPlan:
+    def test_classes_must_become_their_planned_items(self):
+        """Test that the prompt gets the plan, and code translating a class otherwise fails."""
+        plugin = ClassPlan([
+            ClassMapping("Shape", 1, "trait", "abstract base of Circle"),
+            ClassMapping("Token", 5, "enum",
+                         "its subclasses Number are told apart with isinstance"),
+            ClassMapping("Number", 9, "variant", "a case of Token", parent="Token"),
+        ], approved=True)
+        rust = "struct Shape {\n    sides: u32,\n}\n\nenum Token {\n    Word(String),\n}\n"
+
+        assert "as the user approved" in plugin.generation_context("", {})
+        assert plugin.verify("", rust, "") == [
+            "class Shape is planned as the trait Shape, which the Rust code lacks",
+            "class Number is planned as the variant Token::Number, which the Rust code lacks",
+        ]
+        planned = ("trait Shape {\n    fn area(&self) -> f64;\n}\n\nenum Token {\n"
+                   "    Number(f64),\n}\n")
+        assert plugin.verify("", planned, "") == []
+
+    def test_traits_need_their_methods_and_implementations(self):
+        """Test that a trait without its default bodies, or a struct copying its methods, fails."""
+        plugin = ClassPlan([
+            ClassMapping("Shape", 1, "trait", "an abstract interface", required_methods=["area"],
+                         default_methods=["label"]),
+            ClassMapping("Square", 9, "struct", "a concrete class", implements=["Shape"]),
+        ])
+        flattened = (
+            "trait Shape {\n    fn area(&self) -> f64;\n    fn label(&self) -> String;\n}\n\n"
+            "struct Square;\n\nimpl Square {\n    fn area(&self) -> f64 {\n        1.0\n    }\n}\n"
+        )
+
+        assert plugin.verify("", flattened, "") == [
+            "the trait Shape declares label without the default body of the Python method",
+            "Square does not implement the trait Shape; write `impl Shape for Square` instead "
+            "of copying its methods",
+        ]
+        default = "fn label(&self) -> String {\n        self.area().to_string()\n    }"
+        implemented = flattened.replace("fn label(&self) -> String;", default).replace(
+            "impl Square {", "impl Shape for Square {")
+        assert plugin.verify("", implemented, "") == []
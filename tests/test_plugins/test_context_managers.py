@@ -0,0 +1,34 @@
+from python2rust.analyzers import ContextManager
+from python2rust.plugins import RaiiGuards
+
+
+class TestRaiiGuards:
+    def test_prompts_name_the_guards_and_their_error_path_tests(self):
+        """Test that the prompts list the with blocks and ask for a test of each
+        defined context manager."""
+        plugin = RaiiGuards([
+            ContextManager(3, "function", "opened_db"),
+            ContextManager(9, "with", "save", managers=["opened_db(path)"],
+                           exits_early=["return (line 11)"]),
+        ])
+
+        assert "- line 9: with opened_db(path) in save, left early by return (line 11)" in (
+            plugin.generation_context("", {}))
+        assert "- `opened_db_cleans_up_on_error` for opened_db, guarded by OpenedDbGuard" in (
+            plugin.tests_context(""))
+        assert RaiiGuards([ContextManager(9, "with", "save")]).tests_context("") is None
+
+    def test_verification_requires_drop_and_the_test(self):
+        """Test that a context manager without a Drop implementation or error path test
+        fails verification."""
+        plugin = RaiiGuards([ContextManager(3, "class", "Transaction")])
+        guarded = ("impl Drop for Transaction { fn drop(&mut self) { self.rollback(); } }\n"
+                   "#[test]\nfn transaction_cleans_up_on_error() {}\n")
+
+        assert plugin.verify("", guarded, "") == []
+        assert plugin.verify("", "impl Transaction { fn close(&mut self) {} }", "") == [
+            "the context manager Transaction has no `impl Drop for Transaction`, so its cleanup "
+            "does not run on early returns and errors",
+            "the context manager Transaction has no test of its error path; add #[test] fn "
+            "transaction_cleans_up_on_error",
+        ]
@@ -0,0 +1,61 @@
+from python2rust.analyzers import database_use
+from python2rust.plugins import DatabaseAccess
+
+MODULE = '''import sqlite3
+
+SCHEMA = "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)"
+
+
+def names(connection):
+    connection.execute(SCHEMA)
+    return connection.execute("SELECT name FROM users").fetchall()
+'''
+
+
+class TestDatabaseAccess:
+    def test_prompts_give_the_pool_queries_and_migrations(self):
+        """Test that the prompts give the layer's pool for the backend, the queries and the
+        migration files of the tables."""
+        context = DatabaseAccess("sqlx", database_use(MODULE), "tokio", checked=True
+                                 ).generation_context(MODULE, {})
+
+        assert context.startswith("Access the sqlite database of the module with sqlx, and no "
+                                  "other database crate. Open a single connection pool, a "
+                                  "`sqlx::SqlitePool` connected")
+        assert "- line 8: SELECT name FROM users" in context
+        assert "The tables users are created by the migrations written in the crate " \
+               "(migrations/0001_create_users.sql), run at startup with `sqlx::migrate!" in context
+        assert context.endswith('Declare `sqlx = { version = "0.7", features = ["macros",'
+                                ' "migrate", "sqlite", "runtime-tokio"] }` in [dependencies].')
+
+    def test_verification_fails_driver_crates_tables_in_code_and_unchecked_queries(self):
+        """Test that a driver crate, tables created in code, migrations not run and unchecked
+        queries fail, and that the layer is declared when used."""
+        plugin = DatabaseAccess("sqlx", database_use(MODULE), "tokio", checked=True)
+        toml = '[package]\nname = "app"\n\n[dependencies]\n'
+        rust = ('let pool = sqlx::SqlitePool::connect(&url).await?;\n'
+                'sqlx::migrate!("./migrations").run(&pool).await?;\n'
+                'let rows = sqlx::query!("SELECT name FROM users").fetch_all(&pool).await?;\n')
+
+        assert plugin.verify(MODULE, rust, toml) == []
+        assert plugin.verify(MODULE, 'conn.execute("CREATE TABLE users (id INTEGER)", [])?;\n',
+                             toml + 'rusqlite = "0.31"\n') == [
+            "the database is accessed with rusqlite instead of sqlx",
+            "tables are created in code instead of by the migrations: users",
+            "the migrations of the crate are not run at startup",
+            "queries are not checked at compile time with sqlx's query! macros"]
+        assert plugin.cargo_manifest(rust, toml).endswith(
+            '[dependencies]\nsqlx = { version = "0.7", features = ["macros", "migrate", "sqlite", '
+            '"runtime-tokio"] }\n')
+
+    def test_queries_are_checked_at_run_time_without_a_database(self):
+        """Test that sqlx queries use the runtime functions when the build has no database to check
+        the query! macros against."""
+        plugin = DatabaseAccess("sqlx", database_use(MODULE), "tokio")
+        rust = ('let pool = sqlx::SqlitePool::connect(&url).await?;\n'
+                'sqlx::migrate!("./migrations").run(&pool).await?;\n'
+                'let rows = sqlx::query("SELECT name FROM users").fetch_all(&pool).await?;\n')
+
+        assert "Write the queries with the `sqlx::query` and `sqlx::query_as` functions" in (
+            plugin.generation_context(MODULE, {}))
+        assert plugin.verify(MODULE, rust, "") == []
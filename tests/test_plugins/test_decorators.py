@@ -0,0 +1,20 @@
+from python2rust.analyzers import DecoratorUse
+from python2rust.plugins import DecoratorStrategies
+
+
+class TestDecoratorStrategies:
+    def test_prompt_gives_each_decorator_its_strategy(self):
+        """Test that the prompt lists the strategy of each decorator once, and the
+        decorators without one."""
+        plugin = DecoratorStrategies([
+            DecoratorUse(3, "staticmethod", "Point.zero", "an associated function", "catalog"),
+            DecoratorUse(7, "staticmethod", "Point.unit", "an associated function", "catalog"),
+            DecoratorUse(9, "metrics.counted", "index"),
+        ])
+
+        context = plugin.generation_context("", {})
+
+        assert "- @staticmethod (lines 3, 7): an associated function" in context
+        assert "No strategy is configured for these decorators" in context
+        assert context.endswith("- @metrics.counted (lines 9)")
+        assert DecoratorStrategies([]).generation_context("", {}) is None
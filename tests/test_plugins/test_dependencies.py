@@ -0,0 +1,54 @@
+from pathlib import Path
+from python2rust.plugins import DependencySignatures, ImportCycle
+from python2rust.runs import DigestStore
+
+SHAPES = """/// Area of a circle
+pub fn circle_area(r: f64) -> f64 {
+    3.14 * r * r
+}
+"""
+
+
+class TestDependencySignatures:
+    def test_large_modules_are_summarized(self, monkeypatch):
+        """Test that modules are included in full within the token budget and as
+        digests beyond it."""
+        monkeypatch.setattr("python2rust.plugins.dependencies.count_tokens",
+                            lambda text: len(text.split()))
+        generated = {"util": "pub fn helper() {}", "shapes": SHAPES}
+
+        context = DependencySignatures(generated, full_tokens=5).generation_context("", {})
+
+        assert "Module `util`:\n```rust\npub fn helper() {}\n```" in context
+        assert ("Module `shapes` (public API):\n"
+                "```rust\n/// Area of a circle\npub fn circle_area(r: f64) -> f64;\n```") in context
+        assert "3.14" not in context
+
+    def test_digests_follow_code_changes(self, temp_dir: Path):
+        """Test that stored digests are reused for the same code and recomputed when it changes."""
+        store = DigestStore(temp_dir)
+        assert store.digest("shapes",
+                            SHAPES) == "/// Area of a circle\npub fn circle_area(r: f64) -> f64;"
+
+        reloaded = DigestStore(temp_dir)
+        assert reloaded.digest("shapes", SHAPES).endswith("circle_area(r: f64) -> f64;")
+        assert reloaded.digest("shapes", "pub fn circle_area(r: f32) -> f32 { r }") == (
+            "pub fn circle_area(r: f32) -> f32;")
+        assert "f32" in DigestStore(temp_dir).digest("shapes",
+                                                     "pub fn circle_area(r: f32) -> f32 { r }")
+
+
+class TestImportCycle:
+    def test_later_modules_of_the_cycle(self):
+        """Test that the modules migrated later are named with their Python API, and
+        none without them."""
+        later = {"customers": (
+            "class Customer:\n    def name(self): pass\n\ndef history(customer_id: int): pass\n")}
+
+        context = ImportCycle(["orders", "customers"], later).generation_context("", {})
+
+        assert "import cycle `orders` -> `customers`" in context
+        assert "must not depend on `customers`" in context
+        assert ("Module `customers`:\n- class `Customer`\n"
+                "- `def history(customer_id: int)`") in context
+        assert ImportCycle(["orders", "customers"], {}).generation_context("", {}) is None
@@ -0,0 +1,38 @@
+from python2rust.plugins import RustDocs
+
+PYTHON = '''class Ledger:
+    """A ledger."""
+    def total(self):
+        """Sum the entries.
+
+        Returns:
+            int: The total.
+        """
+        return 0
+
+
+def helper():
+    return 1
+'''
+
+
+class TestRustDocs:
+    def test_documented_definitions_need_doc_comments(self):
+        """Test that the prompt gets the converted docstrings, and items without them
+        fail verification."""
+        plugin = RustDocs()
+        rust = (
+            "/// A ledger.\n#[derive(Debug)]\npub struct Ledger;\n\n"
+            "impl Ledger {\n    pub fn total(&self) -> i64 {\n        0\n    }\n}\n\n"
+            "fn helper() -> i64 {\n    1\n}\n"
+        )
+
+        assert ("`Ledger.total` (method):\n/// Sum the entries.\n///\n/// # Returns\n///\n"
+                "/// The total.") in (
+            plugin.generation_context(PYTHON, {}))
+        assert plugin.verify(PYTHON, rust, "") == [
+            "the Rust translation of Ledger.total has no doc comment; carry its "
+            "docstring over as ///"
+        ]
+        documented = rust.replace("    pub fn total", "    /// Sum the entries.\n    pub fn total")
+        assert plugin.verify(PYTHON, documented, "") == []
@@ -0,0 +1,36 @@
+from python2rust.plugins import PythonDoctests
+
+PYTHON = '''def double(n):
+    """Double a number.
+
+    >>> double(2)
+    4
+    >>> double("a")
+    Traceback (most recent call last):
+    TypeError: not a number
+    """
+    return n * 2
+'''
+
+
+class TestPythonDoctests:
+    def test_doctests_need_their_rust_tests(self):
+        """Test that the tests prompt names a test per doctest, and code missing one
+        fails verification."""
+        plugin = PythonDoctests()
+        rust = (
+            "fn double(n: i64) -> i64 {\n    n * 2\n}\n\n"
+            "#[cfg(test)]\nmod tests {\n    use super::*;\n\n"
+            "    #[test]\n    fn doctest_double_1() {\n"
+            "        assert_eq!(double(2), 4);\n    }\n}\n"
+        )
+
+        context = plugin.tests_context(PYTHON)
+        assert ("`doctest_double_1` (double, line 4): assert_eq! the "
+                "call against the value") in context
+        assert "`doctest_double_2` (double, line 6): check that the call returns Err" in context
+        assert "(TypeError)\n```python\n>>> double(\"a\")\nTraceback" in context
+        assert plugin.verify(PYTHON, rust, "") == [
+            "the doctest of double at line 6 has no Rust test; add #[test] fn doctest_double_2"
+        ]
+        assert plugin.tests_context("def f():\n    return 1\n") is None
@@ -0,0 +1,23 @@
+from python2rust.plugins import ErrorStyle
+
+
+class TestErrorStyle:
+    def test_prompts_ask_for_the_error_style(self):
+        """Test that the prompts describe the project's error style and the crate it needs."""
+        context = ErrorStyle("thiserror").generation_context("", {})
+
+        assert context.startswith("The project handles errors in the thiserror style")
+        assert context.endswith('Add `thiserror = "1"` to [dependencies].')
+        assert "Add" not in ErrorStyle("std").generation_context("", {})
+
+    def test_the_style_crate_is_declared_when_used(self):
+        """Test that the crate of the style is added to Cargo.toml when the code
+        uses it undeclared."""
+        plugin = ErrorStyle("anyhow")
+        toml = '[package]\nname = "app"\n\n[dependencies]\nserde = "1"\n'
+
+        assert plugin.cargo_manifest("fn run() -> anyhow::Result<()> {\n    bail!(\"no\")\n}\n",
+                                     toml) == (
+            '[package]\nname = "app"\n\n[dependencies]\nserde = "1"\nanyhow = "1"\n')
+        assert plugin.cargo_manifest("fn run() {}\n", toml) == toml
+        assert ErrorStyle("std").cargo_manifest("fn run() {}\n", toml) == toml
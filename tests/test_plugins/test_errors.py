@@ -0,0 +1,52 @@
+from python2rust.analyzers import ExceptionHierarchy
+from python2rust.plugins import ErrorModule
+
+ERRORS = "class AppError(Exception):\n    pass\n\n\nclass Timeout(AppError):\n    pass\n"
+FETCH = "def fetch(url):\n    if not url:\n        raise Timeout('no url')\n"
+
+
+class TestErrorModule:
+    def test_prompt_declares_the_module_and_maps_the_sites(self):
+        """Test that the prompt has the crate declare the shared error module and gives the Rust
+        error of each raise."""
+        plugin = ErrorModule(ExceptionHierarchy({"errors": ERRORS, "fetch": FETCH}),
+                             "../shared/errors")
+
+        context = plugin.generation_context(FETCH, {})
+
+        assert "Declare it with `mod error;`" in context
+        assert "```rust\n//! Errors of the project" in context
+        assert "- line 3: raise Timeout('no url') -> error::Timeout" in context
+        assert plugin.generation_context("x = 1\n", {}) is None
+
+    def test_manifest_depends_on_the_errors_crate(self):
+        """Test that crates declaring the error module depend on the errors crate by path."""
+        plugin = ErrorModule(ExceptionHierarchy({"errors": ERRORS, "fetch": FETCH}),
+                             "../shared/errors")
+        toml = "[package]\nname = \"fetch\"\n\n[dependencies]\n"
+
+        manifest = plugin.cargo_manifest("mod error;\nfn main() {}\n", toml)
+
+        assert 'errors = { path = "../shared/errors" }' in manifest
+        assert plugin.cargo_manifest(manifest, manifest) == manifest
+        assert plugin.cargo_manifest("fn main() {}\n", toml) == toml
+
+    def test_verification_requires_the_module_and_no_ad_hoc_errors(self):
+        """Test that code without the error module, defining it again, or returning String errors,
+        fails verification."""
+        hierarchy = ExceptionHierarchy({"errors": ERRORS, "fetch": FETCH})
+        plugin = ErrorModule(hierarchy, "../shared/errors")
+        rust = "mod error;\n\nfn fetch(url: &str) -> error::Result<()> { Ok(()) }\n"
+
+        assert plugin.verify(FETCH, rust, "") == []
+        assert plugin.verify(FETCH, "fn fetch(url: &str) -> Result<(), String> { Ok(()) }", "") == [
+            "the project's error module is not declared with `mod error;`",
+            "functions return ad-hoc String errors; return error::Result with the "
+            "project's error types",
+        ]
+        assert plugin.verify(FETCH, f"mod error {{\n{hierarchy.rust_module()}}}\n", "") == [
+            "the project's error module is not declared with `mod error;`",
+            "the error module is defined in the code instead of declared with `mod error;`",
+        ]
+        assert plugin.verify(FETCH, rust + "pub struct Timeout(pub String);\n", "") == [
+            "Timeout is defined again instead of used from the error module"]
@@ -0,0 +1,35 @@
+from python2rust.analyzers import FormatConversion
+from python2rust.plugins import FormatConversions
+
+
+class TestFormatConversions:
+    def test_prompts_list_conversions_and_python_output(self):
+        """Test that the prompts give each spec's conversion and the values the test compares."""
+        plugin = FormatConversions([
+            FormatConversion(2, "{total:>8.3f}", "f-string", "{:>8.3}",
+                             cases=[("3.14159_f64", "   3.142")]),
+            FormatConversion(3, "{count:,}", "f-string", helpers=["grouping"]),
+        ])
+
+        context = plugin.generation_context("", {})
+
+        assert "- line 2: {total:>8.3f} -> {:>8.3}\n" in context
+        assert context.endswith("- line 3: {count:,} -> a helper reproducing thousands separators")
+        assert plugin.tests_context("").endswith("- {total:>8.3f} of 3.14159_f64: '   3.142'")
+        assert FormatConversions([]).generation_context("", {}) is None
+
+    def test_verification_requires_the_test_and_rust_specs(self):
+        """Test that code without the test, or keeping Python specs in a format string,
+        fails verification."""
+        plugin = FormatConversions([
+            FormatConversion(2, "{total:>8.3f}", "f-string", "{:>8.3}",
+                             cases=[("3.14159_f64", "   3.142")])])
+        rust = 'fn report(total: f64) -> String { format!("{:>8.3}", total) }\n'
+
+        assert plugin.verify("", rust + "#[test]\nfn formatting_matches_python() {}\n", "") == []
+        assert plugin.verify("", 'let s = format!("{:>8.3f} {:,} {{x:.2f}}", a, b);', "") == [
+            "the formatting of the module has no test comparing it with Python's output; add "
+            "#[test] fn formatting_matches_python",
+            "format strings keep Python format specs ({:,}, {:>8.3f}); use the Rust placeholders "
+            "or the helpers converted from them",
+        ]
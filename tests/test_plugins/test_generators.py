@@ -0,0 +1,35 @@
+from python2rust.analyzers import PythonGenerator
+from python2rust.plugins import GeneratorIterators
+
+
+class TestGeneratorIterators:
+    def test_tests_compare_each_generator_function(self):
+        """Test that the tests prompt names a test per generator function, comparing a prefix
+        of infinite ones."""
+        plugin = GeneratorIterators([
+            PythonGenerator("count", 1, "function", infinite=True),
+            PythonGenerator("total", 9, "expression", source="(x for x in xs)", consumer="sum"),
+        ])
+
+        context = plugin.tests_context("")
+
+        assert "- `generator_count_matches_python` for count (infinite: compare a prefix" in context
+        assert "total" not in context
+        assert "`(x for x in xs)` in total (consumed by sum)" in plugin.generation_context("", {})
+
+    def test_verification_requires_lazy_iterators_and_their_test(self):
+        """Test that a generator function returning a Vec, or without its test,
+        fails verification."""
+        plugin = GeneratorIterators([PythonGenerator("Reader.chunks", 3, "function")])
+        lazy = ("impl Reader { pub fn chunks(&self) -> impl Iterator<Item = Vec<u8>> "
+                "+ '_ { todo!() } }\n#[test]\nfn generator_reader_chunks_matches_python() {}\n")
+
+        assert plugin.verify("", lazy, "") == []
+        assert plugin.verify("", "pub fn chunks(&self, size: usize) -> Vec<Vec<u8>> { vec![] }",
+                             "") == [
+            "the generator Reader.chunks returns a Vec, producing its elements eagerly; return an "
+            "Iterator producing them on demand",
+            "the generator Reader.chunks has no test comparing its elements with Python's; add "
+            "#[test] fn generator_reader_chunks_matches_python",
+        ]
+        assert GeneratorIterators([]).generation_context("", {}) is None
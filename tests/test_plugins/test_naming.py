@@ -0,0 +1,39 @@
+from python2rust.analyzers.naming import renamings
+from python2rust.plugins import IdentifierNames
+
+CODE = "MaxSize = 3\n\n\ndef getValue(type, itemCount):\n    total = itemCount\n    return total\n"
+
+
+class TestIdentifierNames:
+    def test_prompts_list_the_renamings(self):
+        """Test that the prompts give the Rust name of every renamed identifier."""
+        context = IdentifierNames(renamings(CODE)).generation_context(CODE, {})
+
+        assert context.endswith("- line 1: constant MaxSize -> MAX_SIZE (case)\n"
+                                "- line 4: function getValue -> get_value (case)\n"
+                                "- line 4: parameter type of getValue -> r#type (keyword)\n"
+                                "- line 4: parameter itemCount of getValue -> item_count (case)")
+        assert IdentifierNames([]).generation_context(CODE, {}) is None
+
+    def test_verification_fails_declarations_keeping_python_names(self):
+        """Test that declarations under the Python names fail verification, uses of them as values
+        or fields not counting."""
+        plugin = IdentifierNames(renamings(CODE))
+
+        assert plugin.verify(CODE,
+                             "const MAX_SIZE: u32 = 3;\n"
+                             "pub fn get_value(r#type: u8, item_count: u32) "
+                                   "-> u32 {\n    let total = item_count;\n    total\n}\n",
+                             "") == []
+        rust = ("static MaxSize: u32 = 3;\nfn getValue(r#type: u8, mut itemCount: u32) -> u32 {\n"
+                "    itemCount\n}\n")
+        assert plugin.verify(CODE, rust, "") == [
+            "Rust declarations keep Python names instead of their Rust ones: MaxSize -> MAX_SIZE, "
+            "getValue -> get_value, itemCount -> item_count"]
+
+    def test_verification_accepts_python_names_kept_on_collisions(self):
+        """Test that an identifier keeping its Python name, as its Rust one is taken, passes."""
+        code = "def _parse(): ...\ndef parse(): ...\n"
+        plugin = IdentifierNames(renamings(code))
+
+        assert plugin.verify(code, "fn _parse() {}\nfn parse() {}\n", "") == []
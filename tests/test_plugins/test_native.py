@@ -0,0 +1,25 @@
+from python2rust.analyzers import NativeImport
+from python2rust.plugins import NativeDependencies
+
+
+class TestNativeDependencies:
+    def test_verification_requires_the_crate_or_ffi(self):
+        """Test that Rust code reimplementing a native dependency fails, and using its
+        crate or FFI passes."""
+        plugin = NativeDependencies([
+            NativeImport(1, "numpy", "C array kernels", "ndarray"),
+            NativeImport(2, "fastmath", "the compiled module fastmath.so")
+        ])
+        rust = "fn dot(a: &[f64], b: &[f64]) -> f64 { a.iter().zip(b).map(|(x, y)| x * y).sum() }"
+
+        assert plugin.verify("", rust, "[dependencies]\n") == [
+            "numpy is backed by C array kernels, but the Rust code neither depends on the ndarray "
+            "crate nor binds the library through FFI",
+            "fastmath is backed by the compiled module fastmath.so, but the Rust code does "
+            "not bind it through FFI"
+        ]
+        assert plugin.verify("", rust, "[dependencies]\nfastmath-sys = \"0.1\"\n") == []
+        assert plugin.verify("", 'extern "C" { fn dot(); }',
+                             "[dependencies]\nndarray = \"0.15\"\n") == []
+        assert len(plugin.verify("", rust, "[dependencies]\nndarray = \"0.15\"\n")) == 1
+        assert "unimplemented!()" in plugin.generation_context("", {})
@@ -0,0 +1,73 @@
+import pytest
+from pathlib import Path
+from python2rust.plugins import Plugin, PluginManager, load_plugins
+
+
+class MappingPlugin(Plugin):
+    name = "mappings"
+
+    def crate_mappings(self):
+        return {"flask": "actix-web", "requests": "reqwest"}
+
+
+class NoUnsafePlugin(Plugin):
+    name = "no-unsafe"
+
+    def generation_context(self, python_code, analysis):
+        return "Never use unsafe blocks."
+
+    def verify(self, python_code, rust_code, toml_content):
+        return ["unsafe block found"] if "unsafe" in rust_code else []
+
+
+class BrokenPlugin(Plugin):
+    name = "broken"
+
+    def verify(self, python_code, rust_code, toml_content):
+        raise RuntimeError("boom")
+
+
+class TestPluginManager:
+    @pytest.fixture
+    def manager(self) -> PluginManager:
+        return PluginManager([MappingPlugin(), NoUnsafePlugin(), BrokenPlugin()])
+
+    def test_generation_context(self, manager: PluginManager):
+        """Test that mappings and plugin context are merged into the prompt context."""
+        context = manager.generation_context("import flask", {})
+
+        assert "- flask -> actix-web" in context
+        assert "Never use unsafe blocks." in context
+
+    def test_verify_collects_issues(self, manager: PluginManager):
+        """Test that issues are prefixed by plugin name and failing plugins are skipped."""
+        issues = manager.verify("", "unsafe { x() }", "")
+
+        assert issues == ["[no-unsafe] unsafe block found"]
+
+    def test_no_plugins(self):
+        """Test that an empty manager adds no context."""
+        manager = PluginManager()
+
+        assert manager.analysis_context("print(1)") == ""
+        assert manager.verify("", "", "") == []
+
+    def test_load_plugins_from_dir(self, temp_dir: Path):
+        """Test loading plugin classes from a directory."""
+        (temp_dir / "company.py").write_text(
+            "from python2rust.plugins import Plugin\n"
+            "class Company(Plugin):\n"
+            "    name = 'company'\n"
+            "    def crate_mappings(self):\n"
+            "        return {'numpy': 'ndarray'}\n"
+        )
+        (temp_dir / "_private.py").write_text("raise RuntimeError('not loaded')\n")
+
+        plugins = load_plugins(temp_dir)
+
+        assert [p.name for p in plugins] == ["company"]
+        assert plugins[0].crate_mappings() == {"numpy": "ndarray"}
+
+    def test_load_plugins_missing_dir(self, temp_dir: Path):
+        """Test that a missing plugins directory yields no plugins."""
+        assert load_plugins(temp_dir / "missing") == []
@@ -0,0 +1,34 @@
+from python2rust.analyzers import CrateDependency
+from python2rust.plugins import RequirementCrates
+
+REQWEST = 'reqwest = { version = "0.12", features = ["json"] }'
+MANIFEST = '[package]\nname = "client"\n\n[dependencies]\nreqwest = "0.11"\nanyhow = "1"\n'
+
+
+class TestRequirementCrates:
+    def test_manifest_declares_the_pinned_lines(self):
+        """Test that declared crates get their pinned line and crates used without a declaration are
+        added, whatever the model wrote."""
+        plugin = RequirementCrates([
+            CrateDependency("requests", "reqwest", REQWEST),
+            CrateDependency("dotenv", "dotenvy", 'dotenvy = "0.15"'),
+            CrateDependency("psycopg2", "tokio-postgres", 'tokio-postgres = "0.7"'),
+        ])
+        rust_code = "fn main() { dotenvy::dotenv().ok(); let _ = tokio_postgres::NoTls; }"
+
+        assert plugin.cargo_manifest(rust_code, MANIFEST) == (
+            f'[package]\nname = "client"\n\n[dependencies]\n{REQWEST}\nanyhow = "1"\n'
+            'dotenvy = "0.15"\ntokio-postgres = "0.7"\n')
+        assert plugin.cargo_manifest("fn main() {}", '[dependencies]\n') == '[dependencies]\n'
+        assert "- requests -> " + REQWEST in plugin.generation_context("", {})
+
+    def test_verification_fails_another_crate_of_the_category(self):
+        """Test that declaring another crate of the library's category instead of its own fails
+        verification, unless another library of the module maps to it."""
+        requests = CrateDependency("requests", "reqwest", REQWEST, alternatives=["ureq"])
+        manifest = '[dependencies]\nureq = "2"\n'
+
+        assert RequirementCrates([requests]).verify("", "", manifest) == [
+            "requests is replaced by reqwest, but Cargo.toml declares ureq instead; use " + REQWEST]
+        urllib = CrateDependency("urllib.request", "ureq", 'ureq = "2"', alternatives=["reqwest"])
+        assert RequirementCrates([requests, urllib]).verify("", "", manifest) == []
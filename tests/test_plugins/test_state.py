@@ -0,0 +1,28 @@
+from python2rust.analyzers import ModuleState
+from python2rust.plugins import GlobalState
+
+
+class TestGlobalState:
+    def test_state_must_follow_the_strategy(self):
+        """Test that the prompt gives each state its strategy, and code holding
+        it otherwise fails."""
+        plugin = GlobalState([
+            ModuleState(3, "_cache", "mutable", ["lookup"]),
+            ModuleState(4, "session", "singleton")
+        ], "app_state")
+        rust = "static mut CACHE: Option<HashMap<String, String>> = None;\n"
+        kept = (
+            "static SESSION: OnceLock<Session> = OnceLock::new();\n"
+            "struct AppState {\n    cache: HashMap<String, String>,\n}\n"
+        )
+
+        context = plugin.generation_context("", {})
+        assert ("- line 3: _cache (mutable global, changed by lookup): a field "
+                "of a single `AppState`") in context
+        assert "- line 4: session (singleton): a `static` initialized once" in context
+        assert plugin.verify("", rust, "") == [
+            "module-level state is held in a `static mut`; use the project's app_state strategy",
+            "the mutable module-level state is not gathered in an `AppState` struct",
+            "the module's singletons are not statics initialized once with OnceLock or LazyLock",
+        ]
+        assert plugin.verify("", kept, "") == []
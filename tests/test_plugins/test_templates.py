@@ -0,0 +1,68 @@
+from pathlib import Path
+
+from python2rust.analyzers import python_templates
+from python2rust.plugins import HtmlTemplates
+
+MODULE = '''from flask import render_template_string
+
+PAGE = "<h1>{{ title }}</h1>"
+
+
+def index():
+    return render_template_string(PAGE, title="Items")
+'''
+
+
+class TestHtmlTemplates:
+    def test_prompts_render_the_templates_with_the_engine(self):
+        """Test that the prompts give the template files and how the engine renders them, and the
+        Jinja source of templates maud writes as markup."""
+        templates = python_templates(MODULE)
+        context = HtmlTemplates("askama", templates).generation_context(MODULE, {})
+        maud = HtmlTemplates("maud", templates).generation_context(MODULE, {})
+
+        assert context.startswith("Render the HTML of the module with askama, and no "
+                                  "other template engine.")
+        assert ("- line 7: page.html (inline in PAGE), rendered with title: templates/page.html, "
+                'rendered with `#[derive(askama::Template)] #[template(path = "page.html")] '
+                "struct PageTemplate`") in context
+        assert context.endswith('Declare `askama = "0.12"` in [dependencies].')
+        assert "maud::html!" in maud and "```jinja\n<h1>{{ title }}</h1>\n```" in maud
+
+    def test_verification_fails_string_templating_and_missing_templates(self):
+        """Test that string substitution, kept constants and templates left out fail, and that the
+        engine is declared when used."""
+        plugin = HtmlTemplates("askama", python_templates(MODULE))
+        toml = '[package]\nname = "app"\n\n[dependencies]\n'
+        rust = ('#[derive(askama::Template)]\n#[template(path = "page.html")]\n'
+                "struct PageTemplate { title: String }\n")
+
+        assert plugin.verify(MODULE, rust, toml) == []
+        assert plugin.verify(MODULE,
+                             'const PAGE: &str = "<h1>{title}</h1>";\nPAGE.replace("{title}", t)\n',
+                             toml) == [
+            "HTML is built by replacing placeholders in strings instead of rendering "
+            "templates with askama",
+            "inline templates are kept as string constants: PAGE",
+            "templates are not rendered with askama: page.html"]
+        assert plugin.cargo_manifest(rust, toml).endswith('[dependencies]\naskama = "0.12"\n')
+
+    def test_templates_they_extend_are_registered_and_converted(self, temp_dir: Path):
+        """Test that tera registers the templates a template extends, verified, and that maud is
+        given their Jinja source."""
+        (temp_dir / "item.html").write_text('{% extends "base.html" %}{% block '
+                                            'body %}{% endblock %}')
+        (temp_dir / "base.html").write_text("<html>{% block body %}{% endblock %}</html>")
+        code = ('from flask import render_template\n\n\ndef item():\n'
+                '    return render_template("item.html")\n')
+        templates = python_templates(code, [temp_dir])
+        tera = HtmlTemplates("tera", templates)
+
+        assert ('`tera.add_raw_templates(vec![("item.html", include_str!("../templates/item.'
+                'html")), ("base.html", include_str!("../templates/base.html"))])`'
+                ) in tera.generation_context(code, {})
+        assert tera.verify(code, 'tera.render("item.html", &context)', "") == [
+            "templates are not rendered with tera: base.html"]
+        assert ("  extending or including base.html:\n"
+                "```jinja\n<html>{% block body %}{% endblock %}</html>\n```"
+                ) in HtmlTemplates("maud", templates).generation_context(code, {})
@@ -0,0 +1,40 @@
+from python2rust.plugins import PythonTests
+
+
+class TestPythonTests:
+    def test_files_within_the_token_budget(self, monkeypatch):
+        """Test that the pytest files are given in order until the token budget is spent."""
+        monkeypatch.setattr("python2rust.plugins.tests.count_tokens",
+                            lambda text: len(text.split()))
+        tests = {
+            "tests/conftest.py": "@pytest.fixture\ndef radius(): return 2",
+            "tests/test_shapes.py": "def test_area(radius):\n    assert area(radius) == 12.56",
+            "tests/test_large.py": " ".join(["x"] * 50)
+        }
+
+        context = PythonTests(tests, max_tokens=20).tests_context("")
+
+        assert "#[cfg(test)] mod tests" in context
+        assert ("`tests/conftest.py`:\n"
+                "```python\n@pytest.fixture\ndef radius(): return 2\n```") in context
+        assert "assert area(radius) == 12.56" in context
+        assert "test_large.py" not in context
+
+    def test_hypothesis_properties_become_proptest(self):
+        """Test that hypothesis tests come with their translation into proptest strategies."""
+        tests = {"tests/test_shapes.py": (
+            "from hypothesis import given, strategies as st\n\n"
+            "@given(st.floats(min_value=0, max_value=1e6))\n"
+            "def test_area_is_positive(r):\n"
+            "    assert area(r) >= 0\n")}
+
+        context = PythonTests(tests, max_tokens=8000).tests_context("")
+
+        assert "`proptest!` tests" in context
+        assert "`prop::collection::vec(s, m..=n)`" in context
+        assert "proptest" not in PythonTests({"tests/test_a.py": "def test_a(): pass"},
+                                             8000).tests_context("")
+
+    def test_no_context_without_tests(self):
+        """Test that a module without tests adds nothing to the prompt."""
+        assert PythonTests({}, max_tokens=8000).tests_context("") is None
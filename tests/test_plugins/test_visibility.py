@@ -0,0 +1,30 @@
+from python2rust.analyzers.visibility import module_visibility
+from python2rust.plugins import ItemVisibility
+
+CODE = "LIMIT = 3\n\n\ndef area(): ...\n\n\ndef _scale(): ...\n\n\nclass Circle: ...\n"
+
+
+class TestItemVisibility:
+    def test_prompts_give_the_visibility_of_each_item(self):
+        """Test that the prompts give the public items and the Rust names of the private ones."""
+        context = ItemVisibility(module_visibility(CODE)).generation_context(CODE, {})
+
+        assert context.endswith("- public, without a leading underscore: LIMIT (constant), area "
+                                "(function), Circle (class); private: _scale -> scale")
+
+    def test_verification_compares_declared_visibility(self):
+        """Test that public items declared without pub and private ones declared pub fail
+        verification, pub(crate) keeping an item private."""
+        plugin = ItemVisibility(module_visibility(CODE))
+
+        assert plugin.verify(CODE,
+                             "pub const LIMIT: u32 = 3;\npub fn area() {}\n"
+                             "pub(crate) fn scale() {}\n"
+                                   "pub struct Circle;\nimpl Circle {\n    fn area(&self) {}\n}\n",
+                             "") == []
+        rust = "const LIMIT: u32 = 3;\npub fn scale() {}\npub async fn area() {}\n"
+        assert plugin.verify(CODE, rust, "") == [
+            "LIMIT is public in Python, but LIMIT is not declared pub",
+            "_scale is private in Python, but scale is declared pub; leave out "
+            "pub or use pub(crate)",
+        ]
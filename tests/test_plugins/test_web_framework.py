@@ -0,0 +1,40 @@
+from python2rust.analyzers.http_routes import find_web_app
+from python2rust.plugins import WebFramework
+
+APP = '''from flask import Flask
+
+app = Flask(__name__)
+
+
+@app.route("/items/<int:item_id>")
+def get_item(item_id):
+    return {"id": item_id}
+'''
+
+
+class TestWebFramework:
+    def test_prompts_register_the_routes_with_the_framework(self):
+        """Test that the prompts give each route in the framework's syntax, and its idioms."""
+        context = WebFramework("actix-web", find_web_app(APP)).generation_context(APP, {})
+
+        assert context.startswith("Build the service of `app` with actix-web, and no "
+                                  "other web framework.")
+        assert ('- GET /items/<int:item_id> (get_item): '
+                '`.route("/items/{item_id}", web::get().to(get_item))`'
+                in context)
+        assert context.endswith('Declare `actix-web = "4"` in [dependencies].')
+
+    def test_verification_fails_other_frameworks_and_missing_routes(self):
+        """Test that another framework or an unregistered route fails, and that the framework's
+        crates are declared when used."""
+        plugin = WebFramework("axum", find_web_app(APP))
+        toml = '[package]\nname = "app"\n\n[dependencies]\n'
+        rust = ('let app = axum::Router::new().route("/items/:item_id", get(get_item));\n'
+                "#[tokio::main]\nasync fn main() {}\n")
+
+        assert plugin.verify(APP, rust, toml + 'axum = "0.7"\n') == []
+        assert plugin.verify(APP, 'use actix_web::web;\nweb::get().to(get_item)\n', toml) == [
+            "the service uses actix-web instead of axum",
+            "routes of the app are not registered with their axum paths: GET /items/<int:item_id>"]
+        assert plugin.cargo_manifest(rust, toml).endswith(
+            '[dependencies]\naxum = "0.7"\ntokio = { version = "1", features = ["full"] }\n')
@@ -0,0 +1,40 @@
+import pytest
+from pathlib import Path
+from python2rust.migrator import Migrator
+from python2rust.prompts import GENERATION_PROMPT
+from python2rust.prompts.loader import load_prompts, prompt_overrides, template_variables
+
+
+class TestPromptLoader:
+    def test_overrides_replace_packaged_templates(self, temp_dir: Path):
+        """Test that a prompts directory overrides only the templates it contains."""
+        (temp_dir / "fix.txt").write_text(
+            "Our style guide applies.\nErrors:\n{verification_result}\n```rust\n{rust_code}\n```")
+
+        prompts = load_prompts(temp_dir)
+
+        assert prompts["fix"].template.startswith("Our style guide applies.")
+        assert prompts["generation"].template == GENERATION_PROMPT.template
+        assert list(prompt_overrides(temp_dir)) == ["fix"]
+        assert prompt_overrides(None) == {}
+
+    def test_invalid_overrides_are_rejected(self, temp_dir: Path):
+        """Test that unknown variables, unknown templates and stray braces fail with
+        the template path."""
+        (temp_dir / "generation.txt").write_text("Translate {python_code} for {team}")
+        with pytest.raises(ValueError, match="unknown variables team"):
+            Migrator().prompts_dir(temp_dir)
+
+        (temp_dir / "generation.txt").write_text('Return {"rust": "..."}')
+        with pytest.raises(ValueError, match="generation.txt"):
+            load_prompts(temp_dir)
+
+        (temp_dir / "generation.txt").unlink()
+        (temp_dir / "genration.txt").write_text("{python_code}")
+        with pytest.raises(ValueError, match="Unknown prompt templates"):
+            load_prompts(temp_dir)
+
+    def test_template_variables(self):
+        """Test that literal braces are not variables."""
+        assert template_variables('{{"matches": bool}} {rust_code} {python_code} {rust_code}') == [
+            "rust_code", "python_code"]
@@ -0,0 +1,49 @@
+import pytest
+from python2rust.config.project_config import ProjectConfig
+from python2rust.config.settings import LLMChoice, LLMConfig
+from python2rust.migrator import Migrator
+from python2rust.providers import azure
+from python2rust.providers.azure import AzureOpenAIProvider, azure_endpoint, deployment_name
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestAzureOpenAIProvider:
+    async def test_probe_routes_to_the_deployment(self, monkeypatch):
+        """Test that the deployment name defaults to the model and is used in the request path."""
+        requests = []
+
+        async def probe(method, url, headers, payload=None):
+            requests.append((method, url, headers))
+            return {"status": 200}
+
+        monkeypatch.setattr(azure, "probe_endpoint", probe)
+        config = LLMConfig(
+            model="gpt-4o", provider="azure", api_version="2024-10-21",
+            endpoint_url="https://contoso.openai.azure.com/"
+        )
+        assert deployment_name(config) == "gpt-4o"
+        config.deployment = "gpt4o-prod"
+
+        assert (await AzureOpenAIProvider().test(config, "key"))["status"] == 200
+        method, url, headers = requests[0]
+        assert url == ("https://contoso.openai.azure.com/openai/deployments/gpt4o-prod"
+                       "/chat/completions?api-version=2024-10-21")
+        assert headers["api-key"] == "key"
+
+    def test_deployment_from_project_config(self, monkeypatch):
+        """Test that [model_settings.azure] overrides the Azure config and an
+        endpoint is required."""
+        monkeypatch.delenv("AZURE_OPENAI_ENDPOINT", raising=False)
+        config = ProjectConfig(
+            models={"generation": "azure"},
+            model_settings={"azure": {"deployment": "gpt4o-prod",
+                                      "endpoint_url": "https://contoso.openai.azure.com"}}
+        )
+        settings = Migrator().project_config(config)._settings
+
+        assert settings.llm_steps.generation == LLMChoice.AZURE
+        assert settings.llm_configs[LLMChoice.AZURE].deployment == "gpt4o-prod"
+        assert settings.llm_configs[LLMChoice.AZURE].api_version == "2024-10-21"
+        with pytest.raises(ValueError, match="AZURE_OPENAI_ENDPOINT"):
+            azure_endpoint(LLMConfig(model="gpt-4o", provider="azure"))
@@ -0,0 +1,40 @@
+from python2rust.config.settings import LLMConfig
+from python2rust.providers import bedrock
+from python2rust.providers.bedrock import DEFAULT_CREDENTIALS, BedrockProvider, bedrock_region
+
+
+class FakeSession:
+    """boto3.Session with credentials for the profiles in `available` only."""
+    available = {None}
+
+    def __init__(self, profile_name=None, region_name=None):
+        self.profile_name = profile_name
+
+    def get_credentials(self):
+        return object() if self.profile_name in self.available else None
+
+
+class TestBedrockProvider:
+    def test_region(self, monkeypatch):
+        """Test that the configured region wins over the AWS environment and the default."""
+        monkeypatch.delenv("AWS_REGION", raising=False)
+        monkeypatch.delenv("AWS_DEFAULT_REGION", raising=False)
+        config = LLMConfig(model="anthropic.claude-3-5-sonnet-20241022-v2:0", provider="bedrock")
+        assert bedrock_region(config) == "us-east-1"
+        monkeypatch.setenv("AWS_DEFAULT_REGION", "eu-west-3")
+        assert bedrock_region(config) == "eu-west-3"
+        config.region = "eu-central-1"
+        assert bedrock_region(config) == "eu-central-1"
+
+    def test_credentials_from_profile_or_default_chain(self, monkeypatch):
+        """Test that the token names an AWS profile and the default chain is used without one."""
+        monkeypatch.setattr(bedrock.boto3, "Session", FakeSession)
+        monkeypatch.delenv("AWS_PROFILE", raising=False)
+        provider = BedrockProvider()
+
+        assert provider.credentials({}) == DEFAULT_CREDENTIALS
+        assert provider.credentials({"bedrock": "missing"}) is None
+        monkeypatch.setattr(FakeSession, "available", {None, "prod"})
+        assert provider.credentials({"bedrock": "prod"}) == "prod"
+        monkeypatch.setattr(FakeSession, "available", set())
+        assert provider.credentials({}) is None
@@ -0,0 +1,29 @@
+import pytest
+from types import SimpleNamespace
+from python2rust.providers.google import (
+    SafeChatGoogleGenerativeAI, SafetyBlockedError, blocked_reason
+)
+
+
+def result(finish_reason, safety_ratings=None):
+    info = {"finish_reason": finish_reason, "safety_ratings": safety_ratings or []}
+    return SimpleNamespace(generations=[SimpleNamespace(text="", generation_info=info)])
+
+
+class TestGeminiSafetyBlocks:
+    def test_blocked_reason(self):
+        """Test that safety stops are reported with the categories that blocked them."""
+        assert blocked_reason(result("STOP")) is None
+        assert blocked_reason(result("FinishReason.SAFETY", [
+            {"category": "HarmCategory.HARM_CATEGORY_DANGEROUS_CONTENT",
+             "probability": "HIGH", "blocked": True},
+            {"category": "HarmCategory.HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE"},
+        ])) == "SAFETY (HARM_CATEGORY_DANGEROUS_CONTENT)"
+        assert blocked_reason(result("RECITATION")) == "RECITATION"
+
+    def test_blocked_response_raises(self):
+        """Test that a blocked response raises instead of returning empty code."""
+        with pytest.raises(SafetyBlockedError, match="Gemini blocked the response: SAFETY"):
+            SafeChatGoogleGenerativeAI._check(None, result("SAFETY"))
+        finished = result("STOP")
+        assert SafeChatGoogleGenerativeAI._check(None, finished) is finished
@@ -0,0 +1,37 @@
+import pytest
+from python2rust.config.settings import LLMChoice, Settings
+from python2rust.migrator import Migrator
+from python2rust.providers import ollama
+from python2rust.providers.ollama import OllamaProvider
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestOllamaProvider:
+    async def test_probe_requires_a_pulled_model(self, monkeypatch):
+        """Test that the server check fails when the configured model was not pulled."""
+        async def probe(method, url, headers, payload=None):
+            return {"status": 200, "body": {"models": [{"name": "llama3.1:latest"}]}}
+
+        monkeypatch.setattr(ollama, "probe_endpoint", probe)
+        config = Settings().llm_configs[LLMChoice.OLLAMA]
+        provider = OllamaProvider()
+
+        assert provider.credentials({}) == "local"
+        assert (await provider.test(config, "local"))["status"] == 404
+        config.model = "llama3.1"
+        assert (await provider.test(config, "local"))["status"] == 200
+
+    def test_local_model_warnings(self, temp_dir):
+        """Test that local steps and prompts larger than the context window are reported."""
+        source = temp_dir / "app.py"
+        source.write_text("def handler(request):\n    return request\n" * 200)
+        migrator = Migrator().source_file(source).model("generation", LLMChoice.OLLAMA)
+        migrator._settings.llm_configs[LLMChoice.OLLAMA].context_length = 2048
+
+        warnings = migrator._local_model_warnings([source])
+
+        assert warnings[0].startswith("generation, tests run on the local model qwen2.5-coder:14b")
+        assert any(w.startswith("app.py: the implementation prompt") and "2048-token context" in w
+                   for w in warnings[1:])
+        assert not Migrator().source_file(source)._local_model_warnings([source])
@@ -0,0 +1,32 @@
+import pytest
+from python2rust.config.settings import LLMChoice, Settings
+from python2rust.providers import openai_compatible
+from python2rust.providers.openai_compatible import NO_KEY, OpenAICompatibleProvider
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestOpenAICompatibleProvider:
+    async def test_probe_sends_headers_and_requires_a_served_model(self, monkeypatch):
+        """Test that the check lists models at the base URL with the configured headers."""
+        requests = []
+
+        async def probe(method, url, headers, payload=None):
+            requests.append((url, headers))
+            return {"status": 200, "body": {"data": [{"id": "Qwen/Qwen2.5-Coder-32B-Instruct"}]}}
+
+        monkeypatch.setattr(openai_compatible, "probe_endpoint", probe)
+        config = Settings().llm_configs[LLMChoice.OPENAI_COMPATIBLE]
+        provider = OpenAICompatibleProvider()
+
+        assert "endpoint_url" in (await provider.test(config, NO_KEY))["error"]
+        config.endpoint_url = "http://gpu-box:8000/v1/"
+        config.headers = {"X-Team": "migrations"}
+        assert (await provider.test(config, NO_KEY))["status"] == 404
+        config.model = "Qwen/Qwen2.5-Coder-32B-Instruct"
+        assert (await provider.test(config, "secret"))["status"] == 200
+
+        assert requests[0] == ("http://gpu-box:8000/v1/models", {"X-Team": "migrations"})
+        assert requests[1][1] == {"X-Team": "migrations", "Authorization": "Bearer secret"}
+        assert provider.credentials({}) == NO_KEY
+        assert provider.credentials({"openai_compatible": "secret"}) == "secret"
@@ -0,0 +1,74 @@
+import pytest
+from python2rust.config.settings import LLMChoice, Settings
+from python2rust.initializers import LLMInitializer
+from python2rust.providers import PROVIDERS, fallback_models, missing_tokens, resolve_model
+
+pytestmark = pytest.mark.asyncio
+
+
+class FakeProvider:
+    """Stands in for a provider without calling any API."""
+
+    def __init__(self, name, token, working=True):
+        self.name = name
+        self.token = token
+        self.label = name
+        self.probe_before_use = True
+        self.working = working
+
+    def credentials(self, tokens):
+        return tokens.get(self.token)
+
+    def create(self, config, token, temperature, streaming=False, callbacks=None, cache=None):
+        return f"{self.name}:{config.model}"
+
+    async def test(self, config, token):
+        return {"status": 200 if self.working else 401}
+
+
+class TestProviderRegistry:
+    def test_steps_fall_back_to_openai_without_claude(self, monkeypatch):
+        """Test that an OpenAI key alone serves the default Claude steps."""
+        monkeypatch.setitem(PROVIDERS, "bedrock", FakeProvider("bedrock", "bedrock"))
+        settings = Settings()
+        assert fallback_models(settings, LLMChoice.CODELLAMA) == [
+            LLMChoice.CLAUDE, LLMChoice.OPENAI, LLMChoice.AZURE, LLMChoice.GEMINI, LLMChoice.BEDROCK
+        ]
+        assert resolve_model(settings, LLMChoice.CLAUDE, {"openai": "key"}) == LLMChoice.OPENAI
+        assert missing_tokens(settings, {"openai": "key"}) == {}
+        assert missing_tokens(settings, {"hf": "key"}) == {
+            "analysis": "claude", "generation": "claude",
+            "verification": "claude", "fixes": "claude", "tests": "claude", "build_fixes": "claude"
+        }
+
+    async def test_initializer_uses_the_provider_of_each_config(self, monkeypatch):
+        """Test that models are built by their provider and failing ones are
+        replaced by a fallback."""
+        monkeypatch.setitem(PROVIDERS, "openai", FakeProvider("openai", "openai"))
+        monkeypatch.setitem(PROVIDERS, "mistral", FakeProvider("mistral", "mistral", working=False))
+        monkeypatch.setitem(PROVIDERS, "bedrock", FakeProvider("bedrock", "bedrock"))
+        settings = Settings()
+        settings.llm_steps.analysis = LLMChoice.OPENAI
+        settings.llm_steps.fixes = LLMChoice.CODESTRAL
+
+        llms = await LLMInitializer(settings).initialize({"openai": "key", "mistral": "key"})
+
+        assert llms[LLMChoice.OPENAI] == "openai:gpt-4o"
+        assert llms[LLMChoice.CLAUDE] == "openai:gpt-4o"
+        assert llms[LLMChoice.CODESTRAL] == "openai:gpt-4o"
+
+    async def test_tests_and_build_fixes_get_their_own_models(self, monkeypatch):
+        """Test that the tests and build_fixes steps default to generation and fixes unless set."""
+        monkeypatch.setitem(PROVIDERS, "anthropic", FakeProvider("anthropic", "claude"))
+        monkeypatch.setitem(PROVIDERS, "openai", FakeProvider("openai", "openai"))
+        settings = Settings()
+        settings.llm_steps.fixes = LLMChoice.OPENAI
+        assert settings.llm_steps.model_for("tests") == LLMChoice.CLAUDE
+        assert settings.llm_steps.model_for("build_fixes") == LLMChoice.OPENAI
+
+        settings.llm_steps.fixes = LLMChoice.CLAUDE
+        settings.llm_steps.build_fixes = LLMChoice.OPENAI
+        llms = await LLMInitializer(settings).initialize({"claude": "key", "openai": "key"})
+
+        assert llms == {LLMChoice.CLAUDE: "anthropic:claude-3-5-sonnet-20241022",
+                        LLMChoice.OPENAI: "openai:gpt-4o"}
@@ -0,0 +1,63 @@
+import json
+import pytest
+from pathlib import Path
+from python2rust.config.pricing import ModelPricing
+from python2rust.runs import FileStatus, RunBrowser, RunStore
+
+
+class TestRunBrowser:
+    @pytest.fixture
+    def store(self, temp_dir: Path) -> RunStore:
+        return RunStore(temp_dir / "generated")
+
+    def test_collects_code_logs_and_usage(self, store: RunStore, temp_dir: Path):
+        """Test that a file's code, cargo logs and priced token usage are gathered."""
+        source = temp_dir / "app" / "a.py"
+        source.parent.mkdir(parents=True)
+        source.write_text("def a():\n    pass\n")
+        output_dir = temp_dir / "generated" / "a"
+        checkpoint = store.create({source: output_dir})
+        checkpoint.files[str(source)].status = FileStatus.GENERATED
+        store.save_outputs(checkpoint, str(source), "fn a() {}\n", "[package]\n")
+        store.save(checkpoint)
+
+        logs_dir = output_dir / "debug" / "logs"
+        logs_dir.mkdir(parents=True)
+        (logs_dir / "002_clippy.log").write_text("warning")
+        (logs_dir / "001_check.log").write_text("error[E0425]")
+        traces_dir = output_dir / "debug" / "traces"
+        traces_dir.mkdir(parents=True)
+        for name, model in [("trace_1.json", "sonnet"), ("trace_2.json", "sonnet")]:
+            (traces_dir / name).write_text(json.dumps({
+                "model": "ChatAnthropic",
+                "model_id": model,
+                "token_usage": {"prompt_tokens": 1_000_000, "completion_tokens": 100_000}
+            }))
+
+        browser = RunBrowser(store, checkpoint, pricing={
+            "sonnet": ModelPricing(model="sonnet", input_per_mtok=3.0, output_per_mtok=15.0)
+        })
+
+        record, = browser.files
+        assert record.python_code().startswith("def a()")
+        assert record.rust_code() == "fn a() {}\n"
+        assert [log.name for log in record.logs] == ["001_check.log", "002_clippy.log"]
+        assert record.usage.calls == 2
+        assert record.usage.total_tokens == 2_200_000
+        assert record.usage.cost == pytest.approx(9.0)
+        assert "2 calls" in browser.total_usage().format()
+
+    def test_unknown_model_has_no_cost(self, store: RunStore, temp_dir: Path):
+        """Test that usage from a model without a price reports an unknown cost."""
+        output_dir = temp_dir / "generated" / "a"
+        checkpoint = store.create({temp_dir / "a.py": output_dir})
+        traces_dir = output_dir / "debug" / "traces"
+        traces_dir.mkdir(parents=True)
+        (traces_dir / "trace_1.json").write_text(json.dumps({
+            "model": "other", "token_usage": {"prompt_tokens": 10, "completion_tokens": 5}
+        }))
+
+        browser = RunBrowser(store, checkpoint)
+
+        assert browser.files[0].usage.cost is None
+        assert "unknown cost" in browser.total_usage().format()
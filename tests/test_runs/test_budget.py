@@ -0,0 +1,43 @@
+from python2rust.config.pricing import ModelPricing
+from python2rust.config.settings import BudgetLimits
+from python2rust.runs import BudgetTracker
+
+PRICING = {"model-a": ModelPricing("model-a", input_per_mtok=1.0, output_per_mtok=3.0)}
+
+
+class TestBudgetTracker:
+    def test_warns_once_per_threshold(self):
+        """Test that each warn_at fraction of a limit is reported the first time it is crossed."""
+        tracker = BudgetTracker(BudgetLimits(run_tokens=1000, warn_at=[0.5, 0.8]), PRICING)
+
+        assert tracker.record("a.py", "model-a", 300, 0) == []
+        assert tracker.record("a.py", "model-a", 300, 0) == [
+            "Run has used 50% of its token budget (600 tokens of 1000 tokens)"]
+        assert tracker.record("a.py", "model-a", 10, 0) == []
+        assert len(tracker.record("a.py", "model-a", 300, 0)) == 1
+        assert tracker.exhausted() is None
+
+    def test_exhausted_by_run_or_file_limit(self):
+        """Test that priced usage exhausts the run limit and token usage a file's own limit."""
+        tracker = BudgetTracker(BudgetLimits(run_cost=0.01, file_tokens=5000), PRICING)
+
+        tracker.record("a.py", "model-a", 6000, 0)
+        assert tracker.exhausted() is None
+        assert tracker.exhausted("a.py").startswith("Budget exhausted: the file token limit")
+        assert tracker.exhausted("b.py") is None
+
+        tracker.record("b.py", "model-a", 0, 2000)
+        assert tracker.run.cost == 0.012
+        assert tracker.exhausted("b.py") == (
+            "Budget exhausted: the run cost limit of $0.01 was reached ($0.01)")
+
+    def test_resumes_from_recorded_spend(self):
+        """Test that a tracker continues from the spend stored in a checkpoint."""
+        tracker = BudgetTracker(BudgetLimits(run_tokens=100), PRICING)
+        tracker.record("a.py", "unknown-model", 60, 0)
+
+        resumed = BudgetTracker(BudgetLimits(run_tokens=100), PRICING, tracker.to_dict())
+        resumed.record("b.py", "unknown-model", 40, 0)
+
+        assert resumed.files["a.py"].tokens == 60
+        assert resumed.exhausted() is not None
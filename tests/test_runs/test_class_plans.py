@@ -0,0 +1,22 @@
+from pathlib import Path
+from python2rust.commands.classes import review_plan
+from python2rust.runs import ClassPlanStore
+
+
+class TestClassPlans:
+    def test_approvals_hold_for_the_source_they_were_given_on(self, temp_dir: Path):
+        """Test that an approval with its changed kinds is saved, and dropped once
+        the source changes."""
+        source = temp_dir / "shapes.py"
+        source.write_text("class Shape:\n    pass\n\n\nclass Circle(Shape):\n    pass\n")
+        store = ClassPlanStore(temp_dir / "out")
+        answers = iter(["c", "Square trait", "c", "Shape enum", "a"])
+        said = []
+
+        review_plan(store, source, ask=lambda prompt: next(answers), say=said.append)
+
+        approval = ClassPlanStore(temp_dir / "out").approved(source, source.read_text())
+        assert approval.overrides == {"Shape": "enum"}
+        assert "   expected a class of the file and one of struct, trait, enum" in said
+        assert (ClassPlanStore(temp_dir / "out")
+                .approved(source, "class Shape:\n    x = 1\n") is None)
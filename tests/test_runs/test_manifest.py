@@ -0,0 +1,39 @@
+import pytest
+from pathlib import Path
+from python2rust.runs import MigrationManifest, hash_api, hash_inputs
+
+
+class TestMigrationManifest:
+    @pytest.fixture
+    def outputs_dir(self, temp_dir: Path) -> Path:
+        (temp_dir / "out" / "src").mkdir(parents=True)
+        (temp_dir / "out" / "src" / "main.rs").write_text("pub fn f() {}")
+        (temp_dir / "out" / "Cargo.toml").write_text("[package]")
+        return temp_dir / "out"
+
+    def test_lookup_matches_input_hash(self, temp_dir: Path, outputs_dir: Path):
+        """Test that stored output is only reused for identical inputs."""
+        manifest = MigrationManifest(temp_dir)
+        manifest.record(Path("a.py"), "hash-1", "pub fn f() {}", outputs_dir)
+
+        reloaded = MigrationManifest(temp_dir)
+
+        assert reloaded.lookup(Path("a.py"), "hash-1") == ("pub fn f() {}", "[package]")
+        assert reloaded.lookup(Path("a.py"), "hash-2") is None
+        assert reloaded.lookup(Path("b.py"), "hash-1") is None
+
+    def test_missing_outputs_are_not_reused(self, temp_dir: Path):
+        """Test that deleted outputs force a new migration."""
+        manifest = MigrationManifest(temp_dir)
+        manifest.record(Path("a.py"), "hash-1", "", temp_dir / "gone")
+
+        assert manifest.lookup(Path("a.py"), "hash-1") is None
+
+    def test_api_hash_ignores_bodies(self):
+        """Test that only public signatures affect dependents."""
+        assert hash_api("pub fn f() -> u32 { 1 }") == hash_api("pub fn f() -> u32 { 2 }")
+        assert hash_api("pub fn f() -> u32 { 1 }") != hash_api("pub fn f() -> u64 { 1 }")
+
+    def test_input_hash_includes_dependencies(self):
+        """Test that a dependency API change changes the input hash."""
+        assert hash_inputs("code", "settings", ["a"]) != hash_inputs("code", "settings", ["b"])
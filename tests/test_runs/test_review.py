@@ -0,0 +1,75 @@
+import pytest
+from pathlib import Path
+from python2rust.commands.review import review_file
+from python2rust.migrator import Migrator
+from python2rust.report import FileReport
+from python2rust.runs import ReviewDecision, ReviewStore
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestReview:
+    @pytest.fixture
+    def project(self, temp_dir: Path) -> Path:
+        """Three modules, each with generated code in its own crate."""
+        app = temp_dir / "app"
+        app.mkdir()
+        for name in ("kept", "accepted", "rejected"):
+            (app / f"{name}.py").write_text(f"def {name}(): pass\n")
+            crate = temp_dir / "out" / name
+            (crate / "src").mkdir(parents=True)
+            (crate / "src" / "main.rs").write_text(f"fn {name}() {{}}\n")
+            (crate / "Cargo.toml").write_text("[package]\n")
+        return app
+
+    def test_decisions_persist(self, project: Path, temp_dir: Path):
+        """Test that decisions are saved and loaded back per file and function."""
+        store = ReviewStore(temp_dir / "out")
+        source = project / "rejected.py"
+        store.record(source, ReviewDecision.REJECTED, "code", item="rejected", comment="use u64")
+
+        loaded = ReviewStore(temp_dir / "out")
+
+        assert loaded.file_review(source) is None
+        [review] = loaded.item_reviews(source)
+        assert (review.decision, review.comment) == (ReviewDecision.REJECTED, "use u64")
+
+    def test_review_file_records_answers(self, project: Path, temp_dir: Path):
+        """Test the interactive prompt, including the comment asked on reject."""
+        store = ReviewStore(temp_dir / "out")
+        answers = iter(["x", "r", "handle overflow"])
+
+        review_file(store, project / "rejected.py", temp_dir / "out" / "rejected",
+                    ask=lambda prompt: next(answers), say=lambda text: None)
+
+        review = store.file_review(project / "rejected.py")
+        assert (review.decision, review.comment) == (ReviewDecision.REJECTED, "handle overflow")
+
+    async def test_migrate_respects_reviews(self, project: Path, temp_dir: Path):
+        """Test that kept and accepted files are left alone and rejected ones regenerated."""
+        store = ReviewStore(temp_dir / "out")
+        for name, decision in [("kept", ReviewDecision.KEEP_PYTHON),
+                               ("accepted", ReviewDecision.ACCEPTED),
+                               ("rejected", ReviewDecision.REJECTED)]:
+            source = project / f"{name}.py"
+            rust_code = (temp_dir / "out" / name / "src" / "main.rs").read_text()
+            store.record(source, decision, source.read_text(), rust_code, comment="too slow")
+        migrator = (
+            Migrator().source_dir(project).target_dir(temp_dir / "out")
+            .tokens(claude="token").checkpoints(False)
+        )
+        calls = []
+
+        async def fake_migrate_file(source, plugins):
+            calls.append((source.name, [p.name for p in plugins]))
+            return FileReport(source=source, output_dir=temp_dir, success=True,
+                              rust_code="fn rejected() { fast() }")
+
+        migrator._migrate_file = fake_migrate_file
+        report = await migrator.migrate()
+
+        assert calls == [("rejected.py", ["structure", "docs", "comments", "doctests", "review"])]
+        metrics = {f.source.name: f.metrics for f in report.files}
+        assert metrics["kept.py"] == {"kept_python": True}
+        assert metrics["accepted.py"] == {"accepted": True}
+        assert ReviewStore(temp_dir / "out").for_source(project / "rejected.py") == []
@@ -0,0 +1,59 @@
+import pytest
+from pathlib import Path
+from python2rust.runs import FileStatus, RunStore, diff_runs
+
+
+class TestRunDiff:
+    @pytest.fixture
+    def store(self, temp_dir: Path) -> RunStore:
+        return RunStore(temp_dir / "generated")
+
+    @pytest.fixture
+    def output_dirs(self, temp_dir: Path):
+        return {
+            temp_dir / "app" / "a.py": temp_dir / "generated" / "a",
+            temp_dir / "app" / "b.py": temp_dir / "generated" / "b",
+        }
+
+    def _run(self, store: RunStore, output_dirs, outputs):
+        checkpoint = store.create(output_dirs)
+        for source, (status, rust_code) in zip(checkpoint.sources, outputs):
+            checkpoint.files[source].status = status
+            store.save_outputs(checkpoint, source, rust_code, "[package]\n")
+        store.save(checkpoint)
+        return checkpoint
+
+    def test_diff_reports_changes_and_regressions(self, store: RunStore, output_dirs):
+        """Test per-file diffs, line counts and status regressions between two runs."""
+        old = self._run(store, output_dirs, [
+            (FileStatus.VERIFIED, "fn a() {}\n"),
+            (FileStatus.GENERATED, "fn b() {}\n"),
+        ])
+        new = self._run(store, output_dirs, [
+            (FileStatus.GENERATED, "fn a() {\n    todo!()\n}\n"),
+            (FileStatus.VERIFIED, "fn b() {}\n"),
+        ])
+
+        result = diff_runs(store, old, new)
+
+        first, second = result.files
+        assert (first.added, first.removed) == (3, 1)
+        assert first.regressed
+        assert second.lines == [] and second.changed and not second.regressed
+        assert [f.source for f in result.regressions] == [first.source]
+        assert "2/2 files changed, +3 -1 lines, 1 regressed" in result.format(stat_only=True)
+
+    def test_build_regressions(self, store: RunStore, output_dirs):
+        """Test that a verified file whose binary and dependencies balloon is a regression."""
+        old = self._run(store, output_dirs, [(FileStatus.VERIFIED, "fn a() {}\n")] * 2)
+        new = self._run(store, output_dirs, [(FileStatus.VERIFIED, "fn a() {}\n")] * 2)
+        source = old.sources[0]
+        old.files[source].build_stats = {"binary_bytes": 400_000, "dependencies": 2}
+        new.files[source].build_stats = {"binary_bytes": 1_200_000, "dependencies": 40}
+
+        result = diff_runs(store, old, new)
+
+        first, second = result.files
+        assert first.regressed and not second.regressed
+        assert first.build_regressions[0] == "binary size 400000 bytes -> 1200000 bytes (3.00x)"
+        assert "build regression: dependencies 2 -> 40 (20.00x)" in result.format(stat_only=True)
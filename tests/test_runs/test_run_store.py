@@ -0,0 +1,99 @@
+import pytest
+from pathlib import Path
+from python2rust.runs import FileStatus, RunStatus, RunStore
+from python2rust.migrator import Migrator
+from python2rust.commands.resume import format_run
+
+
+class TestRunStore:
+    @pytest.fixture
+    def store(self, temp_dir: Path) -> RunStore:
+        return RunStore(temp_dir / "generated")
+
+    @pytest.fixture
+    def output_dirs(self, temp_dir: Path):
+        return {
+            temp_dir / "app" / "a.py": temp_dir / "generated" / "a",
+            temp_dir / "app" / "b.py": temp_dir / "generated" / "b",
+        }
+
+    def test_create_and_load(self, store: RunStore, output_dirs):
+        """Test that a new run is persisted with every file pending."""
+        checkpoint = store.create(output_dirs)
+
+        loaded = store.load(checkpoint.run_id)
+
+        assert loaded.status == RunStatus.RUNNING
+        assert [f.status for f in loaded.files.values()] == [FileStatus.PENDING] * 2
+        assert (store.run_dir(checkpoint.run_id) / "checkpoint.json").exists()
+
+    def test_run_ids_are_unique(self, store: RunStore, output_dirs):
+        """Test that runs started in the same second get distinct ids."""
+        first = store.create(output_dirs)
+        second = store.create(output_dirs)
+
+        assert first.run_id != second.run_id
+        assert store.latest().run_id == second.run_id
+
+    def test_outputs_roundtrip(self, store: RunStore, output_dirs):
+        """Test saving and loading generated code for a file."""
+        checkpoint = store.create(output_dirs)
+        source = checkpoint.sources[0]
+
+        store.save_outputs(checkpoint, source, "fn main() {}", "[package]")
+        store.save(checkpoint)
+
+        file = store.load(checkpoint.run_id).files[source]
+        assert store.load_outputs(file) == ("fn main() {}", "[package]")
+
+    def test_pending_files_skip_verified(self, store: RunStore, output_dirs):
+        """Test that verified files are not resumed."""
+        checkpoint = store.create(output_dirs)
+        checkpoint.files[checkpoint.sources[0]].status = FileStatus.VERIFIED
+        store.save(checkpoint)
+
+        pending = store.load(checkpoint.run_id).pending_files()
+
+        assert [f.source for f in pending] == [checkpoint.sources[1]]
+
+    def test_load_missing_run(self, store: RunStore):
+        """Test that loading an unknown run fails clearly."""
+        with pytest.raises(FileNotFoundError):
+            store.load("missing")
+
+
+class TestResume:
+    pytestmark = pytest.mark.asyncio
+
+    async def test_resume_skips_verified_files(self, temp_dir: Path):
+        """Test that resuming a finished run reuses the stored outputs."""
+        store = RunStore(temp_dir)
+        source = temp_dir / "main.py"
+        checkpoint = store.create({source: temp_dir})
+        store.save_outputs(checkpoint, str(source), "fn main() {}", "[package]")
+        checkpoint.files[str(source)].status = FileStatus.VERIFIED
+        store.save(checkpoint)
+
+        report = (await Migrator().target_dir(temp_dir).tokens(claude="token")
+                  .resume(checkpoint.run_id))
+
+        assert report.success
+        assert report.run_id == checkpoint.run_id
+        assert report.files[0].rust_code == "fn main() {}"
+        assert store.load(checkpoint.run_id).status == RunStatus.COMPLETED
+
+    def test_list_shows_file_status(self, temp_dir: Path):
+        """Test that a listed run shows the status and progress of each file."""
+        store = RunStore(temp_dir)
+        checkpoint = store.create({temp_dir / "a.py": temp_dir / "a",
+                                   temp_dir / "b.py": temp_dir / "b"})
+        first, second = checkpoint.files.values()
+        first.status = FileStatus.VERIFIED
+        second.status, second.stage, second.iteration = FileStatus.GENERATED, "check", 2
+        second.error = "cargo check failed"
+
+        lines = format_run(checkpoint)
+
+        assert "1/2 verified" in lines[0]
+        assert lines[1].split() == ["verified", first.source]
+        assert lines[2].endswith("b.py at check, iteration 2: cargo check failed")
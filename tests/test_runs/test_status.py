@@ -0,0 +1,61 @@
+import pytest
+from pathlib import Path
+from python2rust.analyzers import ImportGraph
+from python2rust.runs import (
+    FileStatus,
+    MigrationManifest,
+    ModuleState,
+    RunStore,
+    collect_status,
+    hash_source
+)
+
+
+class TestStatus:
+    @pytest.fixture
+    def project(self, temp_dir: Path):
+        """A package where main imports util, and an unrelated module."""
+        app = temp_dir / "app"
+        app.mkdir()
+        (app / "util.py").write_text("def helper(): pass\n")
+        (app / "main.py").write_text("import util\n")
+        (app / "other.py").write_text("print('other')\n")
+        return app
+
+    def _graph(self, app: Path) -> ImportGraph:
+        files = sorted(app.glob("*.py"))
+        return ImportGraph(files, {f: app for f in files})
+
+    def test_states_from_runs_and_manifest(self, project: Path, temp_dir: Path):
+        """Test verified, build failing and not started modules."""
+        target = temp_dir / "out"
+        store = RunStore(target)
+        checkpoint = store.create({project / "util.py": target / "util",
+                                   project / "main.py": target / "main"})
+        util, main = checkpoint.files.values()
+        util.status = FileStatus.VERIFIED
+        main.status, main.stage, main.error = FileStatus.GENERATED, "build", "cargo check failed"
+        store.save(checkpoint)
+
+        status = collect_status(target, self._graph(project))
+
+        states = {m.module: m.state for m in status.modules}
+        assert states == {"util": ModuleState.VERIFIED, "main": ModuleState.BUILD_FAILING,
+                          "other": ModuleState.NOT_STARTED}
+        assert [m.detail for m in status.modules if m.module == "main"] == ["cargo check failed"]
+
+    def test_changed_source_marks_dependents_stale(self, project: Path, temp_dir: Path):
+        """Test that editing a verified module makes it and its importers stale."""
+        target = temp_dir / "out"
+        manifest = MigrationManifest(target)
+        for name in ("util.py", "main.py"):
+            source = project / name
+            manifest.record(source, "hash", "fn f() {}", target, hash_source(source.read_text()))
+        (project / "util.py").write_text("def helper(x): pass\n")
+
+        status = collect_status(target, self._graph(project))
+
+        states = {m.module: (m.state, m.detail) for m in status.modules}
+        assert states["util"] == (ModuleState.STALE, "source changed")
+        assert states["main"] == (ModuleState.STALE, "dependency changed: util")
+        assert "3 modules: 1 not started, 2 stale" in status.format()
@@ -0,0 +1,37 @@
+from pathlib import Path
+from python2rust.builders.benchmark import BenchResult, format_comparison, percentile, rust_binary
+
+
+class TestBenchmark:
+    def test_percentile(self):
+        """Test nearest-rank percentiles."""
+        values = [0.5, 0.1, 0.4, 0.2, 0.3]
+
+        assert percentile(values, 0.95) == 0.5
+        assert percentile(values, 0.5) == 0.3
+        assert percentile([], 0.95) == 0.0
+
+    def test_throughput_counts_successful_requests(self):
+        """Test that failed requests do not count towards throughput."""
+        result = BenchResult(name="rust", requests=10, failures=2, wall_time=2.0)
+
+        assert result.throughput == 4.0
+        assert BenchResult(name="python").throughput == 0.0
+
+    def test_format_comparison(self):
+        """Test that the table reports the Rust speedup for each metric."""
+        python = BenchResult(name="python", requests=10, wall_time=10.0,
+                             latencies=[1.0] * 10, peak_memory_mb=60.0)
+        rust = BenchResult(name="rust", requests=10, wall_time=0.5,
+                           latencies=[0.05] * 10, peak_memory_mb=6.0)
+
+        table = format_comparison(python, rust)
+
+        assert "20.00x" in table.splitlines()[2]
+        assert "10.00x" in table.splitlines()[6]
+
+    def test_rust_binary_uses_package_name(self, temp_dir: Path):
+        """Test locating the release binary of a generated crate."""
+        (temp_dir / "Cargo.toml").write_text('[package]\nname = "prime_server"\n')
+
+        assert rust_binary(temp_dir) == temp_dir / "target" / "release" / "prime_server"
@@ -0,0 +1,45 @@
+import shutil
+from pathlib import Path
+
+import pytest
+
+from python2rust.builders import RustBuilder
+from python2rust.builders.build_stats import build_regressions, count_dependencies, measure_build
+
+
+class TestBuildStats:
+    def test_dependencies_of_the_lock_file(self, temp_dir: Path):
+        """Test that the packages of Cargo.lock are counted without the crate itself."""
+        (temp_dir / "Cargo.lock").write_text(
+            "version = 3\n\n[[package]]\nname = \"app\"\n\n[[package]]\nname = \"serde\"\n\n"
+            "[[package]]\nname = \"serde_derive\"\n")
+
+        assert count_dependencies(temp_dir) == 2
+        assert count_dependencies(temp_dir / "missing") is None
+
+    def test_regressions_need_ratio_and_increase(self):
+        """Test that a measure regresses only when it grows past both its ratio and its
+        minimum increase."""
+        old = {"release_seconds": 2.0, "rebuild_seconds": 1.0,
+               "binary_bytes": 400_000, "dependencies": 4}
+        new = {"release_seconds": 9.0, "rebuild_seconds": 1.2,
+               "binary_bytes": 900_000, "dependencies": 30}
+
+        assert build_regressions(old, new) == [
+            "binary size 400000 bytes -> 900000 bytes (2.25x)",
+            "dependencies 4 -> 30 (7.50x)",
+        ]
+        assert build_regressions({}, new) == []
+
+    @pytest.mark.asyncio
+    @pytest.mark.skipif(shutil.which("cargo") is None, reason="cargo is not installed")
+    async def test_measured_release_build(self, temp_dir: Path):
+        """Test that a release build is timed from scratch and rebuilt, with its binary measured."""
+        builder = RustBuilder(output_dir=temp_dir / "app")
+        toml_content = '[package]\nname = "app"\nversion = "0.1.0"\nedition = "2021"\n'
+
+        stats = await measure_build(builder, 'fn main() { println!("hi"); }\n', toml_content)
+
+        assert stats.release_seconds is not None and stats.rebuild_seconds is not None
+        assert stats.binary_bytes > 0
+        assert stats.dependencies == 0
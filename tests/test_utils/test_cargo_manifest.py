@@ -0,0 +1,49 @@
+from python2rust.utils.cargo_manifest import add_test_dependencies, replace_entries
+
+MANIFEST = """[package]
+name = "shapes"
+version = "0.1.0"
+
+[dependencies]
+serde = "1"
+"""
+
+PROPERTY_TESTS = """
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn area_is_positive(r in 0.0f64..1e6) {
+            prop_assert!(super::area(r) >= 0.0);
+        }
+    }
+}
+"""
+
+
+class TestAddTestDependencies:
+    def test_proptest_is_declared_for_property_tests(self):
+        """Test that tests using proptest get it as a dev-dependency, once."""
+        manifest = add_test_dependencies(PROPERTY_TESTS, MANIFEST)
+
+        assert manifest == MANIFEST + '\n[dev-dependencies]\nproptest = "1"\n'
+        assert add_test_dependencies(PROPERTY_TESTS, manifest) == manifest
+
+    def test_manifest_unchanged_without_test_crates(self):
+        """Test that code not using a test crate keeps its manifest as written."""
+        assert add_test_dependencies("fn main() {}", MANIFEST) == MANIFEST
+
+
+class TestReplaceEntries:
+    def test_named_entries_of_the_section_are_replaced(self):
+        """Test that only the named entries of the section change, other sections keeping theirs."""
+        manifest = MANIFEST + '\n[dev-dependencies]\nserde = "0.9"\n'
+
+        serde = 'serde = { version = "1", features = ["derive"] }'
+
+        replaced = replace_entries(manifest, "[dependencies]", {"serde": serde})
+
+        assert replaced == manifest.replace('serde = "1"', serde)
+        assert replace_entries(MANIFEST, "[dependencies]", {"rand": 'rand = "0.8"'}) == MANIFEST
@@ -0,0 +1,43 @@
+import pytest
+from python2rust.utils.code_extractor import CodeExtractor, GeneratedCode
+
+
+class TestStructuredFiles:
+    def test_main_and_cargo_toml(self):
+        """Test that the crate root and manifest are taken from the files of the answer."""
+        generated = GeneratedCode.model_validate({
+            "files": [
+                {"path": "Cargo.toml", "content": "[package]\nname = \"app\"\n"},
+                {"path": "src/main.rs", "content": "fn main() {}\n"}
+            ],
+            "notes": "Uses u64 for Python ints",
+            "confidence": 0.9
+        })
+
+        rust_code, toml_content = CodeExtractor().extract_files(generated)
+
+        assert rust_code == "fn main() {}"
+        assert toml_content == "[package]\nname = \"app\""
+
+    def test_inlines_other_modules(self):
+        """Test that extra Rust files replace their mod declarations in the crate root."""
+        rust_code, toml_content = CodeExtractor().extract_files({
+            "files": [
+                {"path": "src/shapes.rs", "content": "pub struct Circle;"},
+                {"path": "src/main.rs", "content": "pub mod shapes;\n\nfn main() {}"},
+                {"path": "src/util/mod.rs", "content": "pub fn helper() {}"}
+            ]
+        })
+
+        assert rust_code.startswith("pub mod shapes {\n    pub struct Circle;\n}")
+        assert rust_code.endswith("mod util {\n    pub fn helper() {}\n}")
+        assert toml_content is None
+
+    def test_rejects_answers_without_rust(self):
+        """Test that an answer without a Rust file, or without a tool call, fails."""
+        extractor = CodeExtractor()
+
+        with pytest.raises(ValueError, match="No Rust file"):
+            extractor.extract_files({"files": [{"path": "Cargo.toml", "content": ""}]})
+        with pytest.raises(ValueError):
+            extractor.extract_files(None)
@@ -0,0 +1,36 @@
+from python2rust.utils.error_formatter import clippy_diagnostics
+
+CLIPPY_OUTPUT = """    Checking app v0.1.0 (/tmp/app)
+error: using `clone` on type `i32` which implements the `Copy` trait
+ --> src/main.rs:3:13
+  |
+3 |     let y = x.clone();
+  |             ^^^^^^^^^ help: try removing the `clone` call: `x`
+  |
+  = note: `-D clippy::clone-on-copy` implied by `-D warnings`
+
+error: the loop variable `i` is only used to index `items`
+ --> src/main.rs:5:14
+  |
+5 |     for i in 0..items.len() {
+  |              ^^^^^^^^^^^^^^
+  |
+help: consider using an iterator
+  |
+5 |     for <item> in &items {
+  |         ~~~~~~    ~~~~~~
+
+error: could not compile `app` (bin "app") due to 2 previous errors
+"""
+
+
+class TestClippyDiagnostics:
+    def test_splits_diagnostics_without_summaries(self):
+        """Test that each lint is kept whole, with its suggestion, and the summary is dropped."""
+        diagnostics = clippy_diagnostics(CLIPPY_OUTPUT)
+
+        assert len(diagnostics) == 2
+        assert diagnostics[0].startswith("error: using `clone` on type `i32`")
+        assert "help: try removing the `clone` call: `x`" in diagnostics[0]
+        assert diagnostics[1].endswith("~~~~~~    ~~~~~~")
+        assert clippy_diagnostics(CLIPPY_OUTPUT, limit=1) == diagnostics[:1]
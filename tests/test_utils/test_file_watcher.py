@@ -0,0 +1,47 @@
+import asyncio
+import pytest
+from pathlib import Path
+from python2rust.utils.file_watcher import FileWatcher
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestFileWatcher:
+    def test_changes_between_snapshots(self, temp_dir: Path):
+        """Test that edits, additions and removals are all reported."""
+        edited, removed, added = temp_dir / "a.py", temp_dir / "b.py", temp_dir / "c.py"
+        edited.write_text("x = 1\n")
+        removed.write_text("y = 1\n")
+        watcher = FileWatcher(lambda: sorted(temp_dir.glob("*.py")))
+        before = watcher.snapshot()
+
+        edited.write_text("x = 22\n")
+        removed.unlink()
+        added.write_text("z = 1\n")
+
+        assert FileWatcher.changes(before, watcher.snapshot()) == [edited, removed, added]
+        assert FileWatcher.changes(before, before) == []
+
+    async def test_wait_returns_changed_files(self, temp_dir: Path):
+        """Test that waiting ends once a watched file is modified."""
+        source = temp_dir / "main.py"
+        source.write_text("print(1)\n")
+        watcher = FileWatcher(lambda: [source], interval=0.01, debounce=0.01)
+        snapshot = watcher.snapshot()
+
+        async def edit():
+            await asyncio.sleep(0.05)
+            source.write_text("print(2)\n")
+
+        changed, current = (await asyncio.gather(watcher.wait_for_changes(snapshot), edit()))[0]
+
+        assert changed == [source]
+        assert current != snapshot
+
+    async def test_stop_ends_wait(self, temp_dir: Path):
+        """Test that setting the stop event returns without changes."""
+        watcher = FileWatcher(lambda: [], interval=0.01)
+        stop = asyncio.Event()
+        stop.set()
+
+        assert await watcher.wait_for_changes({}, stop) == ([], {})
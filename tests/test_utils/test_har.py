@@ -0,0 +1,43 @@
+import json
+import pytest
+from pathlib import Path
+from python2rust.utils.har import load_har
+
+
+def har_entry(method, url, headers=(), post_data=None):
+    request = {"method": method, "url": url,
+               "headers": [{"name": n, "value": v} for n, v in headers]}
+    if post_data is not None:
+        request["postData"] = post_data
+    return {"request": request, "response": {"status": 200}}
+
+
+class TestHar:
+    def test_requests_to_the_main_host(self, temp_dir: Path):
+        """Test that requests to the most requested host are kept in order, as paths."""
+        (temp_dir / "traffic.har").write_text(json.dumps({"log": {"entries": [
+            har_entry("GET", "https://shop.example.com/?page=2",
+                      [("Accept", "text/html"), ("Host", "shop.example.com"),
+                       (":authority", "shop")]),
+            har_entry("GET", "https://cdn.example.com/app.js"),
+            har_entry("post", "https://shop.example.com/cart",
+                      post_data={"mimeType": "application/json", "text": '{"item": 3}'}),
+            har_entry("POST", "https://shop.example.com/login",
+                      post_data={"params": [{"name": "user", "value": "ann lee"}]})
+        ]}}))
+
+        cases = load_har(temp_dir / "traffic.har")
+
+        assert [(case.method, case.path, case.body) for case in cases] == [
+            ("GET", "/?page=2", None),
+            ("POST", "/cart", '{"item": 3}'),
+            ("POST", "/login", "user=ann+lee")
+        ]
+        assert cases[0].headers == {"Accept": "text/html"}
+
+    def test_rejects_other_files(self, temp_dir: Path):
+        """Test that files without HAR entries are reported."""
+        (temp_dir / "notes.json").write_text('{"entries": []}')
+
+        with pytest.raises(ValueError, match="not a HAR file"):
+            load_har(temp_dir / "notes.json")
@@ -0,0 +1,42 @@
+from pathlib import Path
+
+from python2rust.analyzers.hot_functions import hot_functions
+from python2rust.builders.hot_benchmarks import write_hot_benchmarks
+
+PYTHON = ("def count(text, times):\n"
+          "    return sum(len(text) for _ in range(times) for _ in range(times))\n")
+RUST = ("fn count(text: &str, times: u32) -> usize { text.len() * (times * times) as usize }\n"
+        "fn main() {}\n")
+MANIFEST = '[package]\nname = "app"\nversion = "0.1.0"\nedition = "2021"\n\n[dependencies]\n'
+
+
+class TestHotBenchmarks:
+    def test_benchmarks_and_python_timing(self, temp_dir: Path):
+        """Test that the benchmark includes the crate code, builds strings outside the measure and
+        is declared in the manifest, next to a Python script timing the same calls."""
+        source = temp_dir / "app" / "app.py"
+        source.parent.mkdir()
+        source.write_text(PYTHON)
+        output_dir = temp_dir / "generated"
+
+        manifest = write_hot_benchmarks(output_dir, source, RUST, MANIFEST,
+                                        hot_functions(PYTHON, RUST))
+
+        bench = (output_dir / "benches" / "python2rust_hot.rs").read_text()
+        assert 'include!("../src/main.rs");' in bench
+        assert f'let arg0 = String::from("{"a" * 100}");' in bench
+        assert "count(std::hint::black_box(&arg0), std::hint::black_box(100))" in bench
+        assert manifest == (output_dir / "Cargo.toml").read_text()
+        assert ('criterion = "0.5"' in manifest
+                and '[[bench]]\nname = "python2rust_hot"\nharness = false' in manifest)
+        timing = (output_dir / "benches" / "python_timing.py").read_text()
+        assert "SOURCE = Path(__file__).resolve().parent / '../../app/app.py'" in timing
+        assert "CALLS = [['count', [" in timing
+
+    def test_code_that_cannot_be_included(self, temp_dir: Path):
+        """Test that code with inner attributes gets no benchmark."""
+        rust_code = "#![allow(dead_code)]\n" + RUST
+
+        assert write_hot_benchmarks(temp_dir, temp_dir / "app.py", rust_code, MANIFEST,
+                                    hot_functions(PYTHON, rust_code)) is None
+        assert not (temp_dir / "benches").exists()
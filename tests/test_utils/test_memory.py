@@ -0,0 +1,45 @@
+import asyncio
+import sys
+from pathlib import Path
+
+import pytest
+
+from python2rust.builders.memory import (
+    MemoryProfiler, MemoryUsage, memory_comparison, read_allocations
+)
+
+
+class TestMemoryProfiler:
+    @pytest.mark.asyncio
+    async def test_sampled_peak(self):
+        """Test that the peak resident memory of a running process is kept until
+        the sampling stops."""
+        program = "import time; data = bytearray(64 * 1024 * 1024); time.sleep(0.5)"
+        process = await asyncio.create_subprocess_exec(sys.executable, "-c", program)
+        usage = MemoryUsage()
+
+        sampler = MemoryProfiler().sample(process.pid, usage)
+        await process.wait()
+        sampler.cancel()
+
+        assert usage.peak_rss_mb > 60
+
+    def test_valgrind_heap_usage(self, temp_dir: Path):
+        """Test that allocations are read from the heap summary of a valgrind log."""
+        log = temp_dir / "valgrind.log"
+        log.write_text("==12== HEAP SUMMARY:\n==12==     in use at exit: 0 bytes in 0 blocks\n"
+                       "==12==   total heap usage: 1,234 allocs, 1,234 frees, "
+                       "56,789 bytes allocated\n")
+
+        assert read_allocations(log) == 1234
+        assert MemoryProfiler().counted(["app"], log)[-2:] == [f"--log-file={log}", "app"]
+        assert MemoryProfiler.counted_env(python=True) == {"PYTHONMALLOC": "malloc"}
+
+    def test_comparison(self):
+        """Test that a workload compares both usages as Python over Rust ratios,
+        unmeasured ones None."""
+        comparison = memory_comparison("<program> a.txt", MemoryUsage(24.0, 9000), MemoryUsage(2.0))
+
+        assert comparison["python"] == {"peak_rss_mb": 24.0, "allocations": 9000}
+        assert comparison["peak_rss_ratio"] == 12.0
+        assert comparison["allocations_ratio"] is None
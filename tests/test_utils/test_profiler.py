@@ -0,0 +1,42 @@
+import sys
+from pathlib import Path
+
+from python2rust.builders import Profiler
+
+WORKLOAD = "from mathy import primes, cheap\nprimes(3000)\ncheap()\n"
+MATHY = (
+    "def primes(n):\n"
+    "    return [i for i in range(2, n) if all(i % d for d in range(2, i))]\n\n"
+    "def cheap():\n"
+    "    return 1\n"
+)
+
+
+class TestProfiler:
+    def test_hot_paths_of_a_workload(self, temp_dir: Path):
+        """Test that the functions taking the workload's CPU time are found in its
+        Python processes."""
+        source = temp_dir / "mathy.py"
+        source.write_text(MATHY)
+        (temp_dir / "workload.py").write_text(WORKLOAD)
+
+        paths = (Profiler(f'"{sys.executable}" workload.py', cwd=temp_dir, threshold=0.2)
+                 .profile([source]))
+
+        assert [path.qualname for path in paths[source]] == ["primes"]
+        assert paths[source][0].calls == 1
+
+    def test_failing_command_keeps_the_profile(self, temp_dir: Path):
+        """Test that a workload exiting with an error still gives its hot paths."""
+        source = temp_dir / "mathy.py"
+        source.write_text(MATHY)
+        (temp_dir / "workload.py").write_text(WORKLOAD + "raise SystemExit(3)\n")
+
+        paths = (Profiler(f'"{sys.executable}" workload.py', cwd=temp_dir, threshold=0.2)
+                 .profile([source]))
+
+        assert list(paths) == [source]
+
+    def test_command_without_python_has_none(self, temp_dir: Path):
+        """Test that a workload running no Python process finds nothing."""
+        assert Profiler("true", cwd=temp_dir).profile([temp_dir / "mathy.py"]) == {}
@@ -0,0 +1,47 @@
+import io
+from python2rust.events import EventBus, EventType
+from python2rust.utils.progress import ProgressDisplay, progress_bar
+
+
+class TestProgressDisplay:
+    def test_progress_bar(self):
+        """Test that the bar fills in proportion to the files done."""
+        assert progress_bar(0, 4, width=8) == "[--------]"
+        assert progress_bar(1, 4, width=8) == "[##------]"
+        assert progress_bar(0, 0, width=4) == "[####]"
+
+    def test_live_block_shows_stage_iteration_and_stream(self):
+        """Test that running files, retries and streamed tokens appear in the live block."""
+        stream = io.StringIO()
+        display = ProgressDisplay(total=2, stream=stream, live=True, refresh_interval=0)
+        events = EventBus([display]).for_file("app/main.py")
+
+        events.emit(EventType.FILE_STARTED)
+        events.emit(EventType.STAGE_STARTED, stage="generation")
+        events.emit(EventType.ITERATION_RETRIED, stage="generation")
+        events.emit(EventType.PROMPT_SENT)
+        for token in ["fn main() {\n", "    let x", " = 1;"]:
+            events.emit(EventType.TOKEN_RECEIVED, token=token)
+
+        header, file_line, tail = display.lines(width=80)
+        assert "0/2 files" in header
+        assert file_line.startswith("  main.py  generation #2")
+        assert "3 tokens" in file_line
+        assert tail == "  > let x = 1;"
+
+        events.emit(EventType.FILE_FINISHED, success=False, error="Build failed")
+        assert display.lines(width=80)[0].endswith("1/2 files, 1 failed")
+        assert "main.py: failed: Build failed" in stream.getvalue()
+
+    def test_plain_output_when_not_a_terminal(self):
+        """Test that a non-terminal stream gets one line per stage and no redraws."""
+        stream = io.StringIO()
+        display = ProgressDisplay(total=1, stream=stream, live=False)
+        events = EventBus([display]).for_file("util.py")
+
+        events.emit(EventType.STAGE_STARTED, stage="analysis")
+        events.emit(EventType.TOKEN_RECEIVED, token="x")
+        events.emit(EventType.FILE_FINISHED, success=True)
+        display.close()
+
+        assert stream.getvalue() == "[0/1] util.py: analysis\n[1/1] util.py: done\n"
@@ -0,0 +1,50 @@
+import sys
+from pathlib import Path
+
+import pytest
+
+from python2rust.builders.python2 import convert_python2
+
+# Rewrites print statements in place, like a 2to3 limited to that fixer
+FAKE_2TO3 = '''import re, sys
+path = sys.argv[1]
+code = open(path).read()
+open(path, "w").write(re.sub(r"print (.*)", r"print(\\1)", code))
+'''
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestConvertPython2:
+    async def test_converts_a_copy(self, temp_dir: Path):
+        """Test that the command converts a copy of the code, keeping the source as it was."""
+        script = temp_dir / "fake_2to3.py"
+        script.write_text(FAKE_2TO3)
+        source = temp_dir / "hello.py"
+        source.write_text("print \"hello\"\n")
+
+        converted, error = await convert_python2(
+            source, source.read_text(), f'"{sys.executable}" "{script}" {{file}}')
+
+        assert (converted, error) == ("print(\"hello\")\n", "")
+        assert source.read_text() == "print \"hello\"\n"
+
+    async def test_incomplete_conversion_fails(self, temp_dir: Path):
+        """Test that Python 2 constructs left by the conversion are reported."""
+        script = temp_dir / "fake_2to3.py"
+        script.write_text(FAKE_2TO3)
+        source = temp_dir / "loop.py"
+
+        converted, error = await convert_python2(
+            source, "for i in xrange(3):\n    print i\n", f'"{sys.executable}" "{script}" {{file}}')
+
+        assert converted is None
+        assert error == "the converted code still has Python 2 constructs: line 1 xrange()"
+
+    async def test_missing_command_fails(self, temp_dir: Path):
+        """Test that a converter that cannot run is reported."""
+        converted, error = await convert_python2(
+            temp_dir / "a.py", "print 1\n", "python2rust-missing-2to3 {file}")
+
+        assert converted is None
+        assert error.startswith("python2rust-missing-2to3 could not run")
@@ -0,0 +1,85 @@
+import asyncio
+import time
+import pytest
+from python2rust.utils.rate_limit import RequestLimiter, is_rate_limit_error
+
+pytestmark = pytest.mark.asyncio
+
+
+class RateLimitError(Exception):
+    status_code = 429
+
+
+class TestRequestLimiter:
+    async def test_retries_rate_limited_requests(self):
+        """Test that 429 and overloaded errors are retried and other errors raised at once."""
+        limiter = RequestLimiter(max_retries=3, base_delay=0.001)
+        attempts = []
+
+        async def flaky():
+            attempts.append(1)
+            if len(attempts) == 1:
+                raise RateLimitError("slow down")
+            if len(attempts) == 2:
+                raise RuntimeError("Error code: 529 - overloaded_error")
+            return "fn main() {}"
+
+        assert await limiter.call("anthropic", flaky) == "fn main() {}"
+        assert len(attempts) == 3
+
+        async def invalid():
+            attempts.append(1)
+            raise ValueError("prompt is too long")
+
+        with pytest.raises(ValueError):
+            await limiter.call("anthropic", invalid)
+        assert len(attempts) == 4
+
+    async def test_gives_up_after_max_retries(self):
+        """Test that a request still rate limited after its retries fails with
+        the provider error."""
+        limiter = RequestLimiter(max_retries=2, base_delay=0.001)
+        attempts = []
+
+        async def limited():
+            attempts.append(1)
+            raise RateLimitError("rate limit exceeded")
+
+        with pytest.raises(RateLimitError):
+            await limiter.call("openai", limited)
+        assert len(attempts) == 3
+
+    async def test_stalled_request_times_out(self):
+        """Test that a request without a response within the timeout fails as stalled."""
+        limiter = RequestLimiter(timeout=0.01)
+
+        with pytest.raises(TimeoutError, match="stalled"):
+            await limiter.call("ollama", lambda: asyncio.sleep(1))
+
+    async def test_limits_concurrency_and_rate(self):
+        """Test that requests share the concurrency limit and each provider's rate."""
+        limiter = RequestLimiter(max_concurrent=2, requests_per_minute={"openai": 3000})
+        running, peak = 0, 0
+
+        async def request():
+            nonlocal running, peak
+            running += 1
+            peak = max(peak, running)
+            await asyncio.sleep(0.01)
+            running -= 1
+
+        await asyncio.gather(*(limiter.call("anthropic", request) for _ in range(6)))
+        assert peak == 2
+
+        started = time.monotonic()
+        await asyncio.gather(*(limiter.call("openai", request) for _ in range(3)))
+        assert time.monotonic() - started >= 0.04
+
+    def test_backoff_grows_with_jitter(self):
+        """Test that backoff doubles per attempt, stays within its cap and jitters."""
+        limiter = RequestLimiter(base_delay=1.0, max_delay=10.0)
+
+        assert 0.5 <= limiter.backoff(0) <= 1.0
+        assert 4.0 <= limiter.backoff(3) <= 8.0
+        assert 5.0 <= limiter.backoff(10) <= 10.0
+        assert is_rate_limit_error(RateLimitError()) and not is_rate_limit_error(KeyError("x"))
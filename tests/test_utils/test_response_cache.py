@@ -0,0 +1,74 @@
+import json
+import pytest
+from pathlib import Path
+from langchain_core.outputs import Generation
+from python2rust.config.settings import LLMChoice, Settings
+from python2rust.utils.response_cache import (
+    ReplayMissError,
+    ResponseCache,
+    write_reproducibility_manifest
+)
+
+
+class TestResponseCache:
+    def test_record_then_replay(self, temp_dir: Path):
+        """Test that recorded responses are returned when replaying."""
+        recorder = ResponseCache(temp_dir)
+        assert recorder.lookup("prompt", "claude") is None
+        recorder.update("prompt", "claude", [Generation(text="fn main() {}")])
+
+        replayed = ResponseCache(temp_dir, replay=True).lookup("prompt", "claude")
+
+        assert [g.text for g in replayed] == ["fn main() {}"]
+
+    def test_replay_miss_fails(self, temp_dir: Path):
+        """Test that replaying an unrecorded prompt never falls through to the model."""
+        with pytest.raises(ReplayMissError):
+            ResponseCache(temp_dir, replay=True).lookup("other prompt", "claude")
+
+    def test_manifest(self, temp_dir: Path):
+        """Test that the manifest lists pinned models, prompt hashes and calls."""
+        settings = Settings(deterministic=True)
+        cache = ResponseCache(temp_dir / "responses")
+        cache.update("prompt", "claude", [Generation(text="ok")])
+
+        write_reproducibility_manifest(settings, cache, temp_dir / "reproducibility.json")
+
+        manifest = json.loads((temp_dir / "reproducibility.json").read_text())
+        assert manifest["models"]["generation"]["temperature"] == 0.0
+        assert manifest["models"]["generation"]["model"] == (
+            settings.llm_configs[LLMChoice.CLAUDE].model)
+        assert "GENERATION_PROMPT" in manifest["prompts"]
+        assert len(manifest["calls"]) == 1
+
+    def test_responses_are_reused_across_runs(self, temp_dir: Path):
+        """Test that a later run gets stored responses, and that a retry within a run
+        reaches the model."""
+        llm_string = "{'model_name': 'claude-3-5-sonnet', 'temperature': 0.1}"
+        ResponseCache(temp_dir).update("prompt", llm_string, [Generation(text="fn main() {}")])
+
+        rerun = ResponseCache(temp_dir, serve_repeats=False)
+        assert [g.text for g in rerun.lookup("prompt", llm_string)] == ["fn main() {}"]
+        assert rerun.lookup("prompt", llm_string) is None
+        assert rerun.lookup("prompt", "{'model_name': 'gpt-4o'}") is None
+        assert ResponseCache(temp_dir, reuse=False).lookup("prompt", llm_string) is None
+
+        stats = ResponseCache(temp_dir).stats()
+        assert (stats.entries, stats.hits, stats.models) == (1, 1, {"claude-3-5-sonnet": 1})
+
+    def test_clear_and_recorded_files_import(self, temp_dir: Path):
+        """Test that responses recorded as JSON files are imported and that clear
+        empties the cache."""
+        cache = ResponseCache(temp_dir)
+        cache.update("prompt", "claude", [Generation(text="ok")])
+        legacy = temp_dir / "recorded.json"
+        legacy.write_text(json.dumps({
+            "prompt_hash": "abc", "llm_string": "claude", "generations": '[{"text": "old"}]'
+        }))
+
+        reopened = ResponseCache(temp_dir)
+
+        assert not legacy.exists()
+        assert reopened.stats().entries == 2
+        reopened.clear()
+        assert reopened.stats().entries == 0
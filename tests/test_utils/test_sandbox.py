@@ -0,0 +1,54 @@
+import sys
+import pytest
+from pathlib import Path
+from python2rust.builders import RustBuilder, Sandbox
+from python2rust.config.settings import SandboxConfig
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestSandbox:
+    async def test_command_limits_and_mounts(self, temp_dir: Path):
+        """Test that commands run without network, with limits, their paths mounted as is."""
+        sandbox = Sandbox(SandboxConfig(enabled=True, image="rust:1.80", memory="1g",
+                                        mounts=["/data:/data:ro"]))
+
+        command = sandbox.command(["cargo", "test"], temp_dir,
+                                  env={"RUST_LOG": "debug"}, timeout=120)
+
+        assert command[:3] == ["docker", "run", "--rm"]
+        assert command[command.index("--network") + 1] == "none"
+        assert command[command.index("--memory") + 1] == "1g"
+        assert f"{temp_dir.resolve()}:{temp_dir.resolve()}" in command
+        assert "/data:/data:ro" in command and "RUST_LOG=debug" in command
+        assert command[command.index("rust:1.80") + 1:] == ["timeout", "-s", "KILL",
+                                                            "120", "cargo", "test"]
+
+    async def test_servers_publish_their_port(self, temp_dir: Path):
+        """Test that servers get the server network and a port published on localhost only."""
+        command = Sandbox(SandboxConfig(enabled=True)).command(["./server"], temp_dir, ports=[8123])
+
+        assert command[command.index("--network") + 1] == "bridge"
+        assert command[command.index("-p") + 1] == "127.0.0.1:8123:8123"
+
+    async def test_cargo_commands_go_through_docker(self, temp_dir: Path):
+        """Test that the builder runs cargo in the sandbox, fetching each manifest
+        once on the host."""
+        calls = temp_dir / "calls.txt"
+        docker = temp_dir / "docker"
+        docker.write_text(f"#!{sys.executable}\nimport sys\n"
+                          f"open({str(calls)!r}, 'a').write(' '.join(sys.argv[1:]) + '\\n')\n")
+        docker.chmod(0o755)
+        project = temp_dir / "crate"
+        sandbox = Sandbox(SandboxConfig(enabled=True, docker=str(docker)))
+        builder = RustBuilder(project, sandbox=sandbox)
+        toml = '[package]\nname = "sandboxed"\nversion = "0.1.0"\nedition = "2021"\n'
+
+        await builder.check("fn main() {}\n", toml)
+        await builder.test("fn main() {}\n", toml)
+
+        runs = calls.read_text().splitlines()
+        assert [run.split()[-2:] for run in runs] == [["cargo", "check"], ["cargo", "test"]]
+        assert not sandbox.needs_fetch(project)
+        (project / "Cargo.toml").write_text(toml + '\n[dependencies]\nserde = "1"\n')
+        assert sandbox.needs_fetch(project)
@@ -0,0 +1,46 @@
+import sys
+from pathlib import Path
+
+import pytest
+
+from python2rust.builders import TypeInference
+
+# Reveals list[str] for items and int for everything else, like mypy's notes
+FAKE_MYPY = '''import os, sys
+path = sys.argv[-1]
+assert os.environ["MYPYPATH"].split(os.pathsep)[0] == sys.argv[1]
+for number, line in enumerate(open(path).read().splitlines(), start=1):
+    if "reveal_type(" in line:
+        revealed = "builtins.list[builtins.str]" if "(items)" in line else "builtins.int"
+        print(f'{path}:{number}: note: Revealed type is "{revealed}"')
+sys.exit(1)
+'''
+
+pytestmark = pytest.mark.asyncio
+
+
+class TestTypeInference:
+    async def test_types_of_a_checked_file(self, temp_dir: Path):
+        """Test that the checker runs on a probed copy with the source directory on its path."""
+        script = temp_dir / "fake_mypy.py"
+        script.write_text(FAKE_MYPY)
+        source = temp_dir / "app" / "words.py"
+        source.parent.mkdir()
+        source.write_text("def longest(items):\n    best = max(items, key=len)\n    return best\n")
+        command = f'"{sys.executable}" "{script}" "{source.parent.resolve()}"'
+
+        types = await TypeInference("mypy", command).infer([source, temp_dir / "missing.py"])
+
+        assert list(types) == [source]
+        assert types[source]["longest"].variables == {"items": "list[str]", "best": "int"}
+        assert types[source]["longest"].signature == "int"
+        assert source.read_text().count("reveal_type") == 0
+
+    async def test_unavailable_checker_gives_no_types(self, temp_dir: Path):
+        """Test that a checker that cannot run is skipped."""
+        source = temp_dir / "words.py"
+        source.write_text("def count(items):\n    return len(items)\n")
+
+        assert await TypeInference("pyright", str(temp_dir / "no-pyright")).infer([source]) == {}
+        with pytest.raises(ValueError):
+            TypeInference("pytype")
@@ -0,0 +1,169 @@
+from pathlib import Path
+from typing import Dict
+
+from python2rust.analyzers import ExceptionHierarchy, write_error_crate
+from python2rust.analyzers.import_graph import ImportGraph
+from python2rust.builders.workspace import package_crates, write_workspace
+
+MANIFEST = ('[package]\nname = "{name}"\nversion = "0.1.0"\nedition = "2021"\n\n[dependencies]\n'
+            '{dependencies}')
+
+
+def _project(root: Path, files: Dict[str, str]) -> ImportGraph:
+    for name, code in files.items():
+        path = root / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(code)
+    sources = sorted(root.rglob("*.py"))
+    return ImportGraph(sources, {source: root for source in sources})
+
+
+class TestWorkspace:
+    def test_crate_per_package_with_path_dependencies(self, temp_dir: Path):
+        """Test that each package gets a crate, after the packages it imports, and modules outside
+        packages none."""
+        graph = _project(temp_dir / "src", {
+            "billing/__init__.py": "", "billing/invoice.py": "from ledger.money import Money\n",
+            "ledger/__init__.py": "", "ledger/money.py": "class Money: ...\n",
+            "ledger/io/type.py": "", "tool.py": "import billing.invoice\n",
+        })
+
+        crates, warnings = package_crates(graph)
+
+        assert [crate.describe() for crate in crates] == [
+            "ledger -> workspace/crates/ledger (3 modules)",
+            "billing -> workspace/crates/billing (2 modules, depends on ledger)",
+        ]
+        assert [crates[0].module_path(module) for module in crates[0].modules] == [
+            (), ("io", "r#type"), ("money",)]
+        assert warnings == []
+        single = _project(temp_dir / "single", {"app/__init__.py": "", "app/cli.py": ""})
+        assert package_crates(single) == ([], [])
+        reserved, _ = package_crates(_project(
+            temp_dir / "reserved", {"core/__init__.py": "", "core/self.py": "", "app/type.py": ""}))
+        assert [(crate.name, crate.module_path(crate.modules[-1])) for crate in reserved] == [
+            ("app", ("r#type",)), ("core_crate", ("self_module",))]
+
+    def test_packages_importing_each_other(self, temp_dir: Path):
+        """Test that the import closing a cycle between packages is left out and reported."""
+        graph = _project(temp_dir, {"a/__init__.py": "", "a/x.py": "import b.y\n",
+                                    "b/__init__.py": "", "b/y.py": "import a.x\n"})
+
+        crates, warnings = package_crates(graph)
+
+        assert sum(len(crate.dependencies) for crate in crates) == 1
+        assert len(warnings) == 1 and "import each other" in warnings[0]
+
+    def test_writes_the_workspace(self, temp_dir: Path):
+        """Test that the workspace lists the crates, shares their common dependencies and metadata,
+        and includes the translation of each module."""
+        graph = _project(temp_dir / "src", {
+            "ledger/__init__.py": "", "ledger/money.py": "",
+            "billing/__init__.py": "", "billing/invoice.py": "from ledger.money import Money\n",
+        })
+        crates, _ = package_crates(graph)
+        output_dirs = {}
+        for source in graph.files:
+            name = graph.names[source].replace(".", "_")
+            output_dir = temp_dir / "generated" / name
+            (output_dir / "src").mkdir(parents=True)
+            (output_dir / "src" / "main.rs").write_text("fn main() {}\n")
+            shared = "money" in name or "invoice" in name
+            serde = 'serde = { version = "1", features = ["derive"] }\n' if shared else ""
+            rand = 'rand = "0.8"\n' if name == "billing_invoice" else ""
+            (output_dir / "Cargo.toml").write_text(MANIFEST.format(name=name,
+                                                                   dependencies=serde + rand))
+            output_dirs[source] = output_dir
+
+        directory = write_workspace(temp_dir / "generated", crates, output_dirs)
+
+        assert directory == temp_dir / "generated" / "workspace"
+        assert (directory / "Cargo.toml").read_text() == (
+            "# Workspace of the Python packages of the project, generated by python2rust\n"
+            '[workspace]\nresolver = "2"\nmembers = ["crates/ledger", "crates/billing"]\n\n'
+            '[workspace.package]\nversion = "0.1.0"\nedition = "2021"\n\n'
+            '[workspace.dependencies]\nserde = { version = "1", features = ["derive"] }\n')
+        assert (directory / "crates" / "billing" / "Cargo.toml").read_text() == (
+            '[package]\nname = "billing"\nversion.workspace = true\nedition.workspace = true\n\n'
+            '[dependencies]\nledger = { path = "../ledger" }\nserde.workspace = true\n'
+            'rand = "0.8"\n')
+        lib = (directory / "crates" / "ledger" / "src" / "lib.rs").read_text()
+        assert ('#[allow(dead_code)]\n#[path = "../../../../ledger_money/src/main.rs"]\n'
+                'pub mod money;') in lib
+        assert ('#[path = "../../../../ledger___init__/src/main.rs"]\nmod init;\n'
+                '#[allow(unused_imports)]\npub use init::*;') in lib
+
+    def test_mirrors_the_package_layout_with_reexports(self, temp_dir: Path):
+        """Test that subpackages become module files declaring their modules, and that names
+        __init__ modules export are re-exported where the translation declares them pub."""
+        graph = _project(temp_dir / "src", {
+            "ledger/__init__.py": "from .money import Money, _rate\n"
+                                  "from .io.reader import read as load\n",
+            "ledger/money.py": "class Money: ...\n\n\ndef _rate(): ...\n",
+            "ledger/io/__init__.py": "__all__ = ['read']\nfrom .reader import read, Reader\n",
+            "ledger/io/reader.py": "def read(): ...\n\n\nclass Reader: ...\n",
+            "billing/__init__.py": "", "billing/invoice.py": "",
+        })
+        crates, _ = package_crates(graph)
+        translations = {"ledger.money": "pub struct Money;\nfn rate() {}\n",
+                        "ledger.io.reader": "pub fn read() {}\nstruct Reader;\n"}
+        output_dirs = {}
+        for source in graph.files:
+            output_dir = temp_dir / "generated" / graph.names[source].replace(".", "_")
+            (output_dir / "src").mkdir(parents=True)
+            (output_dir / "src" / "main.rs").write_text(
+                translations.get(graph.names[source], "") + "fn main() {}\n")
+            output_dirs[source] = output_dir
+
+        directory = write_workspace(temp_dir / "generated", crates, output_dirs)
+
+        src = directory / "crates" / "ledger" / "src"
+        lib = (src / "lib.rs").read_text()
+        assert "pub mod io;" in lib
+        assert '#[path = "../../../../ledger_money/src/main.rs"]\npub mod money;' in lib
+        assert lib.endswith("pub use crate::money::Money;\n\n"
+                            "pub use crate::io::reader::read as load;\n")
+        io = (src / "io.rs").read_text()
+        assert ('#[path = "../../../../ledger_io___init__/src/main.rs"]\nmod init;\n'
+                '#[allow(unused_imports)]\npub use init::*;') in io
+        assert '#[path = "../../../../ledger_io_reader/src/main.rs"]\npub mod reader;' in io
+        assert io.endswith("pub mod reader;\n\npub use crate::io::reader::read;\n")
+        without = write_workspace(temp_dir / "plain", crates, output_dirs, reexports=False)
+        assert "pub use crate" not in (without / "crates" / "ledger" / "src" / "lib.rs").read_text()
+
+    def test_shares_the_errors_crate(self, temp_dir: Path):
+        """Test that the errors crate is a member of the workspace, which the crates of the modules
+        using it depend on, and that a package named errors takes another crate name."""
+        graph = _project(temp_dir / "src", {
+            "ledger/__init__.py": "", "ledger/money.py": "",
+            "billing/__init__.py": "", "billing/invoice.py": "from ledger.money import Money\n",
+        })
+        crates, _ = package_crates(graph)
+        errors = write_error_crate(temp_dir / "generated", ExceptionHierarchy(
+            {"ledger.money": "class LedgerError(Exception):\n    pass\n"}))
+        output_dirs = {}
+        for source in graph.files:
+            name = graph.names[source].replace(".", "_")
+            output_dir = temp_dir / "generated" / name
+            (output_dir / "src").mkdir(parents=True)
+            (output_dir / "src" / "main.rs").write_text("mod error;\nfn main() {}\n")
+            uses = "money" in name or "invoice" in name
+            dependency = 'errors = { path = "../shared/errors" }\n' if uses else ""
+            (output_dir / "Cargo.toml").write_text(MANIFEST.format(name=name,
+                                                                   dependencies=dependency))
+            output_dirs[source] = output_dir
+
+        directory = write_workspace(temp_dir / "generated", crates, output_dirs, errors=errors)
+
+        assert 'members = ["crates/errors", "crates/ledger", "crates/billing"]' in (
+            directory / "Cargo.toml").read_text()
+        assert (directory / "crates" / "errors" / "Cargo.toml").read_text() == (
+            '[package]\nname = "errors"\nversion.workspace = true\nedition.workspace = true\n\n'
+            '[dependencies]\nthiserror = "1"\n')
+        assert (directory / "crates" / "errors" / "src" / "lib.rs").read_text() == (
+            errors / "src" / "lib.rs").read_text()
+        assert (directory / "crates" / "billing" / "Cargo.toml").read_text().endswith(
+            '[dependencies]\nerrors = { path = "../errors" }\nledger = { path = "../ledger" }\n')
+        renamed, _ = package_crates(_project(
+            temp_dir / "renamed", {"errors/__init__.py": "", "app/cli.py": ""}))
+        assert sorted(crate.name for crate in renamed) == ["app", "errors_crate"]
@@ -0,0 +1,104 @@
+import json
+import pytest
+from pathlib import Path
+from python2rust.analyzers import python_chunks
+from python2rust.workflows import ChunkedGenerator, merge_manifests, stitch_rust
+
+pytestmark = pytest.mark.asyncio
+
+MODULE = '''import math
+
+
+def area(radius):
+    return math.pi * radius ** 2
+
+
+def perimeter(radius):
+    return 2 * math.pi * radius
+
+
+if __name__ == "__main__":
+    print(area(1.0), perimeter(1.0))
+'''
+
+
+@pytest.fixture
+def line_tokens(monkeypatch):
+    """Count a token per line so that chunk sizes do not depend on the tokenizer."""
+    monkeypatch.setattr(python_chunks, "count_tokens", lambda text: text.count("\n") + 1)
+
+
+class TestStitching:
+    def test_stitch_deduplicates_uses_and_items(self):
+        """Test that imports are hoisted once, shared items kept once and main
+        taken from its chunk."""
+        parts = [
+            "use std::f64::consts::PI;\n\nstruct Circle;\n\nfn main() {}\n",
+            "use std::f64::consts::PI;\nuse std::{\n    fmt,\n};\n\nstruct Circle;\n\n"
+            "impl Circle {\n    fn area(&self) {}\n}\n",
+            "use std::fmt;\n\nfn main() {\n    println!(\"{}\", PI);\n}\n",
+        ]
+
+        rust_code = stitch_rust(parts, main_part=2)
+
+        assert rust_code.startswith("use std::f64::consts::PI;\nuse std::{ fmt, };\n"
+                                    "use std::fmt;\n\n")
+        assert rust_code.count("struct Circle;") == 1
+        assert rust_code.count("fn main()") == 1
+        assert "println!" in rust_code
+        assert "impl Circle" in rust_code
+
+    def test_manifests_merge_dependencies(self):
+        """Test that dependencies of later chunks are added to the first manifest."""
+        first = ('[package]\nname = "app"\n\n[dependencies]\nserde = "1"\n\n[profile.release]\n'
+                 'lto = true\n')
+        second = '[package]\nname = "app"\n\n[dependencies]\nserde = "1.0"\nregex = "1"\n'
+
+        merged = merge_manifests([first, second, ""])
+
+        assert merged == (
+            '[package]\nname = "app"\n\n[dependencies]\nserde = "1"\nregex = "1"\n\n'
+            '[profile.release]\nlto = true\n'
+        )
+        assert merge_manifests([first]) == first
+
+
+class TestChunkedGenerator:
+    async def test_large_module_is_generated_in_chunks(self, temp_dir: Path, line_tokens):
+        """Test that each chunk is generated with the signatures of the others and stitched back."""
+        calls = []
+
+        async def generate(python_code, analysis, project_context):
+            calls.append((python_code, project_context))
+            name = ("main" if "__main__" in python_code
+                    else python_code.split("def ")[1].split("(")[0])
+            return {
+                "rust_code": f"use std::f64::consts::PI;\n\nfn {name}() {{}}\n",
+                "toml_content": f'[package]\nname = "shapes"\n\n[dependencies]\n{name} = "1"\n'
+            }
+
+        generator = ChunkedGenerator(generate, max_tokens=6, artifacts_dir=temp_dir)
+        result = await generator.run(MODULE, {}, "Project context")
+
+        assert result["chunks"] == 3
+        assert result["rust_code"] == (
+            "use std::f64::consts::PI;\n\nfn area() {}\n\nfn perimeter() {}\n\nfn main() {}\n"
+        )
+        assert result["toml_content"].endswith('area = "1"\nperimeter = "1"\nmain = "1"\n')
+        perimeter_code, perimeter_context = calls[1]
+        assert perimeter_code.startswith("import math\n")
+        assert perimeter_context.startswith("Project context\n\n")
+        assert "def area(radius):\n    ..." in perimeter_context
+        chunks = json.loads((temp_dir / "chunks.json").read_text())
+        assert [chunk["symbols"] for chunk in chunks] == [["area"], ["perimeter"], ["__main__"]]
+        assert (temp_dir / chunks[0]["name"] / "main.rs").exists()
+
+    async def test_module_within_budget_is_generated_at_once(self, temp_dir: Path):
+        """Test that a module that fits goes to the generator unchanged."""
+        async def generate(python_code, analysis, project_context):
+            return {"rust_code": "fn main() {}", "toml_content": "", "python": python_code}
+
+        result = await ChunkedGenerator(generate, 10_000, temp_dir).run(MODULE, {}, "")
+
+        assert result["python"] == MODULE
+        assert not (temp_dir / "chunks.json").exists()
@@ -0,0 +1,51 @@
+import json
+import pytest
+from pathlib import Path
+from python2rust.workflows import ConsensusGenerator
+
+pytestmark = pytest.mark.asyncio
+
+
+def candidate(rust_code):
+    async def generate(python_code, analysis, project_context):
+        if rust_code is None:
+            raise ValueError("No Rust code block found in response")
+        return {"rust_code": rust_code, "toml_content": "[package]"}
+    return generate
+
+
+async def verify(result):
+    differences = result["rust_code"].count("todo!")
+    return {"matches": not differences, "critical_differences": {"core": ["todo"] * differences}}
+
+
+def score(verification):
+    return (float("inf") if verification["matches"]
+            else -len(verification["critical_differences"]["core"]))
+
+
+class TestConsensusGenerator:
+    async def test_best_verified_candidate_is_selected(self, temp_dir: Path):
+        """Test that the candidate with the fewest differences wins and every candidate is kept."""
+        consensus = ConsensusGenerator({
+            "claude": candidate("fn main() { todo!(); todo!() }"),
+            "openai": candidate("fn main() { todo!() }"),
+            "gemini": candidate(None),
+        }, temp_dir)
+
+        result = await consensus.run("print('hello')", {}, "", verify, score)
+
+        assert result["candidate"] == "openai"
+        assert result["verification"]["critical_differences"]["core"] == ["todo"]
+        assert (temp_dir / "claude" / "main.rs").exists()
+        assert (temp_dir / "gemini" / "error.txt").exists()
+        summary = json.loads((temp_dir / "consensus.json").read_text())
+        assert summary["selected"] == "openai"
+        assert summary["failed"] == ["gemini"]
+
+    async def test_all_candidates_failing_raises(self, temp_dir: Path):
+        """Test that generation fails when no candidate produced code."""
+        consensus = ConsensusGenerator({"claude": candidate(None)}, temp_dir)
+
+        with pytest.raises(ValueError):
+            await consensus.run("print('hello')", {}, "", verify, score)
@@ -0,0 +1,126 @@
+import pytest
+from pathlib import Path
+from python2rust.config.settings import Settings
+from python2rust.utils.stream_log import StreamLogHandler
+from python2rust.workflows import PassFailed, TranslationPipeline
+
+pytestmark = pytest.mark.asyncio
+
+
+class FakePassChain:
+    """Pass chain whose skeleton fails once before succeeding."""
+
+    def __init__(self):
+        self.calls = []
+        self.skeleton_failures = 1
+
+    async def spec(self, python_code, analysis, project_context):
+        self.calls.append("spec")
+        return "Prints hello"
+
+    async def skeleton(self, python_code, spec, project_context):
+        self.calls.append("skeleton")
+        if self.skeleton_failures:
+            self.skeleton_failures -= 1
+            raise ValueError("No Rust code block found in response")
+        return {"rust_code": "fn main() { todo!() }", "toml_content": "[package]"}
+
+    async def implementation(self, python_code, spec, skeleton, project_context):
+        self.calls.append("implementation")
+        return {"rust_code": 'fn main() { println!("hello"); }',
+                "toml_content": skeleton.get("toml_content")}
+
+    async def tests(self, python_code, spec, rust_code, python_tests=""):
+        self.calls.append("tests")
+        self.python_tests = python_tests
+        raise ValueError("No #[test] functions in generated tests")
+
+
+class TestTranslationPipeline:
+    async def test_passes_retry_independently(self, temp_dir: Path):
+        """Test that a failing pass is retried without re-running earlier passes."""
+        chain = FakePassChain()
+        pipeline = TranslationPipeline(
+            chain, ["spec", "skeleton", "implementation"], temp_dir, max_attempts=2)
+
+        result = await pipeline.run("print('hello')", {})
+
+        assert chain.calls == ["spec", "skeleton", "skeleton", "implementation"]
+        assert result["toml_content"] == "[package]"
+        assert (temp_dir / "skeleton" / "attempt_1" / "error.txt").exists()
+        assert (temp_dir / "skeleton" / "final" / "main.rs").exists()
+        assert (temp_dir / "spec" / "final" / "output.md").read_text() == "Prints hello"
+
+    async def test_failed_tests_pass_keeps_implementation(self, temp_dir: Path):
+        """Test that the implementation is kept when test generation fails."""
+        chain = FakePassChain()
+        chain.skeleton_failures = 0
+        pipeline = TranslationPipeline(chain, ["implementation", "tests"], temp_dir, max_attempts=1)
+
+        result = await pipeline.run("print('hello')", {})
+
+        assert result["rust_code"] == 'fn main() { println!("hello"); }'
+
+    async def test_python_tests_go_to_the_tests_pass(self, temp_dir: Path):
+        """Test that the project's tests reach the tests pass, or the implementation without one."""
+        contexts = []
+
+        class Chain(FakePassChain):
+            async def implementation(self, python_code, spec, skeleton, project_context):
+                contexts.append(project_context)
+                return await super().implementation(python_code, spec, skeleton, project_context)
+
+        chain = Chain()
+        await TranslationPipeline(chain, ["implementation", "tests"], temp_dir, max_attempts=1).run(
+            "print('hello')", {}, "Use clap", tests_context="def test_hello(): ...")
+        await TranslationPipeline(chain, ["implementation"], temp_dir, max_attempts=1).run(
+            "print('hello')", {}, "Use clap", tests_context="def test_hello(): ...")
+
+        assert chain.python_tests == "def test_hello(): ..."
+        assert contexts == ["Use clap", "Use clap\n\ndef test_hello(): ..."]
+
+    async def test_required_pass_failure_raises(self, temp_dir: Path):
+        """Test that exhausting the attempts of a required pass fails the pipeline."""
+        chain = FakePassChain()
+        chain.skeleton_failures = 5
+        pipeline = TranslationPipeline(chain, ["skeleton", "implementation"],
+                                       temp_dir, max_attempts=2)
+
+        with pytest.raises(PassFailed):
+            await pipeline.run("print('hello')", {})
+
+    async def test_interrupted_output_seeds_retry(self, temp_dir: Path):
+        """Test that streamed output is kept in the attempt and put into the retry prompt."""
+        handler = StreamLogHandler(temp_dir / "streams")
+        contexts = []
+
+        class StallingChain(FakePassChain):
+            async def skeleton(self, python_code, spec, project_context):
+                contexts.append(project_context)
+                handler.on_llm_start({}, ["prompt"], run_id=len(contexts))
+                for token in ["fn main() ", "{ todo!() }"]:
+                    handler.on_llm_new_token(token, run_id=len(contexts))
+                if len(contexts) == 1:
+                    raise TimeoutError("anthropic request stalled")
+                handler.on_llm_end(None, run_id=len(contexts))
+                return {"rust_code": "fn main() { todo!() }", "toml_content": "[package]"}
+
+        pipeline = TranslationPipeline(StallingChain(), ["skeleton", "implementation"], temp_dir)
+
+        await pipeline.run("print('hello')", {}, project_context="Use clap")
+
+        assert (temp_dir / "skeleton" / "attempt_1" / "partial.md").read_text() == (
+            "fn main() { todo!() }")
+        assert (temp_dir / "skeleton" / "attempt_2" / "response.md").read_text() == (
+            "fn main() { todo!() }")
+        assert not (temp_dir / "skeleton" / "attempt_2" / "partial.md").exists()
+        assert contexts[0] == "Use clap"
+        assert contexts[1].startswith("Use clap\n\nAn earlier attempt at this step was interrupted")
+        assert "fn main() { todo!() }" in contexts[1]
+
+    def test_settings_validate_passes(self):
+        """Test that passes are validated and put in pipeline order."""
+        assert Settings(translation_passes=["tests", "implementation"]).translation_passes == [
+            "implementation", "tests"]
+        with pytest.raises(ValueError):
+            Settings(translation_passes=["spec"])